@@ -3,20 +3,24 @@ use std::{ops::Deref, sync::Arc};
 use anyhow::Result;
 use napi::{bindgen_prelude::External, JsFunction};
 use next_api::{
+    issue_origin::{endpoint_issue_origin, IssueOrigin},
     paths::ServerPath,
     route::{Endpoint, WrittenEndpoint},
 };
 use tracing::Instrument;
-use turbo_tasks::{get_effects, Completion, Effects, ReadRef, Vc, VcValueType};
+use turbo_tasks::{get_effects, Completion, Effects, ReadRef, TryJoinIterExt, Vc, VcValueType};
 use turbopack_core::{
     diagnostics::PlainDiagnostic,
     error::PrettyPrintError,
     issue::{IssueSeverity, PlainIssue},
 };
 
-use super::utils::{
-    get_diagnostics, get_issues, subscribe, NapiDiagnostic, NapiIssue, RootTask, TurbopackResult,
-    VcArc,
+use super::{
+    shadow_rebuild,
+    utils::{
+        get_diagnostics, get_issues, subscribe, NapiDiagnostic, NapiIssue, RootTask,
+        TurbopackResult, VcArc,
+    },
 };
 
 #[napi(object)]
@@ -120,10 +124,48 @@ async fn strongly_consistent_catch_collectables<R: VcValueType + Send>(
     Ok((result, issues, diagnostics, effects))
 }
 
+/// Looks up where in the module graph of the endpoint the errors among
+/// `issues` originated. Returns one entry per issue.
+async fn issue_origins(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    issues: &[ReadRef<PlainIssue>],
+) -> Result<Arc<Vec<Option<ReadRef<IssueOrigin>>>>> {
+    let origins = issues
+        .iter()
+        .map(|issue| async move {
+            if issue.severity > IssueSeverity::Error {
+                return Ok(None);
+            }
+            Ok(Some(
+                endpoint_issue_origin(
+                    endpoint,
+                    issue.file_path.clone(),
+                    issue.module_ident.clone(),
+                )
+                .await?,
+            ))
+        })
+        .try_join()
+        .await?;
+    Ok(Arc::new(origins))
+}
+
+fn napi_issues(
+    issues: &[ReadRef<PlainIssue>],
+    origins: &[Option<ReadRef<IssueOrigin>>],
+) -> Vec<NapiIssue> {
+    issues
+        .iter()
+        .zip(origins)
+        .map(|(issue, origin)| NapiIssue::from(&**issue).with_origin(origin.as_deref()))
+        .collect()
+}
+
 #[turbo_tasks::value(serialization = "none")]
 struct WrittenEndpointWithIssues {
     written: Option<ReadRef<WrittenEndpoint>>,
     issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    issue_origins: Arc<Vec<Option<ReadRef<IssueOrigin>>>>,
     diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
     effects: Arc<Effects>,
 }
@@ -135,9 +177,11 @@ async fn get_written_endpoint_with_issues(
     let write_to_disk = endpoint.write_to_disk();
     let (written, issues, diagnostics, effects) =
         strongly_consistent_catch_collectables(write_to_disk).await?;
+    let issue_origins = issue_origins(endpoint, &issues).await?;
     Ok(WrittenEndpointWithIssues {
         written,
         issues,
+        issue_origins,
         diagnostics,
         effects,
     }
@@ -157,18 +201,26 @@ pub async fn endpoint_write_to_disk(
             let WrittenEndpointWithIssues {
                 written,
                 issues,
+                issue_origins,
                 diagnostics,
                 effects,
             } = &*operation.strongly_consistent().await?;
             effects.apply().await?;
 
-            Ok((written.clone(), issues.clone(), diagnostics.clone()))
+            Ok((
+                written.clone(),
+                napi_issues(issues, issue_origins),
+                diagnostics.clone(),
+            ))
         })
         .await
         .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    if written.is_some() {
+        shadow_rebuild::endpoint_written(&turbo_tasks, endpoint);
+    }
     Ok(TurbopackResult {
         result: NapiWrittenEndpoint::from(written.map(|v| v.clone_value())),
-        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        issues,
         diagnostics: diags.iter().map(|d| NapiDiagnostic::from(d)).collect(),
     })
 }
@@ -197,13 +249,14 @@ pub fn endpoint_server_changed_subscribe(
             let EndpointIssuesAndDiags {
                 changed: _,
                 issues,
+                issue_origins,
                 diagnostics,
                 effects: _,
             } = &*ctx.value;
 
             Ok(vec![TurbopackResult {
                 result: (),
-                issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+                issues: napi_issues(issues, issue_origins),
                 diagnostics: diagnostics
                     .iter()
                     .map(|d| NapiDiagnostic::from(d))
@@ -217,6 +270,7 @@ pub fn endpoint_server_changed_subscribe(
 struct EndpointIssuesAndDiags {
     changed: Option<ReadRef<Completion>>,
     issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    issue_origins: Arc<Vec<Option<ReadRef<IssueOrigin>>>>,
     diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
     effects: Arc<Effects>,
 }
@@ -244,9 +298,11 @@ async fn subscribe_issues_and_diags(
     if should_include_issues {
         let (changed_value, issues, diagnostics, effects) =
             strongly_consistent_catch_collectables(changed).await?;
+        let issue_origins = issue_origins(endpoint, &issues).await?;
         Ok(EndpointIssuesAndDiags {
             changed: changed_value,
             issues,
+            issue_origins,
             diagnostics,
             effects,
         }
@@ -256,6 +312,7 @@ async fn subscribe_issues_and_diags(
         Ok(EndpointIssuesAndDiags {
             changed: Some(changed_value),
             issues: Arc::new(vec![]),
+            issue_origins: Arc::new(vec![]),
             diagnostics: Arc::new(vec![]),
             effects: Arc::new(Effects::default()),
         }
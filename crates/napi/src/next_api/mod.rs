@@ -1,3 +1,4 @@
 pub mod endpoint;
 pub mod project;
+pub mod shadow_rebuild;
 pub mod utils;
@@ -1,4 +1,14 @@
-use std::{path::PathBuf, sync::Arc, thread, time::Duration};
+use std::{
+    collections::HashMap,
+    mem::take,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use napi::{
@@ -7,16 +17,23 @@ use napi::{
     JsFunction, Status,
 };
 use next_api::{
-    entrypoints::Entrypoints,
+    budgets::route_budgets,
+    bundle_diff::bundle_diff,
+    emitted_assets::EmittedAsset,
+    entrypoints::{EntrypointChanges, Entrypoints, WrittenEntrypoints},
+    paths::source_map_chunk_path,
     project::{
         DefineEnv, DraftModeOptions, Instrumentation, Middleware, PartialProjectOptions, Project,
         ProjectContainer, ProjectOptions, WatchOptions,
     },
     route::{Endpoint, Route},
 };
-use next_core::tracing_presets::{
-    TRACING_NEXT_OVERVIEW_TARGETS, TRACING_NEXT_TARGETS, TRACING_NEXT_TURBOPACK_TARGETS,
-    TRACING_NEXT_TURBO_TASKS_TARGETS,
+use next_core::{
+    next_config::ExperimentalTurboConfig,
+    tracing_presets::{
+        TRACING_NEXT_OVERVIEW_TARGETS, TRACING_NEXT_TARGETS, TRACING_NEXT_TURBOPACK_TARGETS,
+        TRACING_NEXT_TURBO_TASKS_TARGETS,
+    },
 };
 use once_cell::sync::Lazy;
 use rand::Rng;
@@ -24,11 +41,20 @@ use tokio::{io::AsyncWriteExt, time::Instant};
 use tracing::Instrument;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
 use turbo_rcstr::RcStr;
-use turbo_tasks::{get_effects, Completion, Effects, ReadRef, TransientInstance, UpdateInfo, Vc};
+use turbo_tasks::{
+    apply_effects,
+    feature_flags::{set_feature_flag, FeatureFlag},
+    get_effects, Completion, Effects, FxIndexSet, ReadRef, TaskId, TransientInstance,
+    TryJoinIterExt, UpdateInfo, Vc,
+};
 use turbo_tasks_fs::{
-    util::uri_from_file, DiskFileSystem, FileContent, FileSystem, FileSystemPath,
+    hash_service::HashServiceHandle, util::uri_from_file, DiskFileSystem, FileContent, FileSystem,
+    FileSystemPath,
 };
+use turbo_tasks_malloc::{AllocatorStats, TurboMalloc};
 use turbopack_core::{
+    build_telemetry::{build_telemetry, enable_build_telemetry},
+    compile_time_report::enable_compile_time_report,
     diagnostics::PlainDiagnostic,
     error::PrettyPrintError,
     issue::PlainIssue,
@@ -42,13 +68,13 @@ use turbopack_trace_utils::{
     raw_trace::RawTraceLayer,
     trace_writer::TraceWriter,
 };
-use url::Url;
 
 use super::{
     endpoint::ExternalEndpoint,
+    shadow_rebuild::ShadowRebuild,
     utils::{
         create_turbo_tasks, get_diagnostics, get_issues, subscribe, NapiDiagnostic, NapiIssue,
-        NextTurboTasks, RootTask, TurbopackResult, VcArc,
+        NextTurboTasks, Parallelism, RootTask, TurbopackResult, VcArc, PERSISTENT_CACHING,
     },
 };
 use crate::register;
@@ -198,6 +224,18 @@ pub struct NapiTurboEngineOptions {
     pub persistent_caching: Option<bool>,
     /// An upper bound of memory that turbopack will attempt to stay under.
     pub memory_limit: Option<f64>,
+    /// The memory usage in bytes above which the persistent caching backend
+    /// drops the data of tasks that weren't accessed recently. It's restored
+    /// from the cache when needed again.
+    pub memory_target: Option<f64>,
+    /// Overrides for feature flags of experimental code paths, by flag name.
+    pub feature_flags: Option<HashMap<String, String>>,
+    /// The maximum number of chunk groups whose modules are collected at the
+    /// same time.
+    pub chunking_parallelism: Option<u32>,
+    /// The maximum number of output assets generated and written at the same
+    /// time.
+    pub emit_parallelism: Option<u32>,
 }
 
 impl From<NapiWatchOptions> for WatchOptions {
@@ -282,9 +320,51 @@ impl From<NapiDefineEnv> for DefineEnv {
 pub struct ProjectInstance {
     turbo_tasks: NextTurboTasks,
     container: Vc<ProjectContainer>,
-    exit_receiver: tokio::sync::Mutex<Option<ExitReceiver>>,
+    /// The exit handlers of the turbo-tasks instance, shared by all of its
+    /// projects, see [project_on_exit].
+    exit_receiver: Arc<tokio::sync::Mutex<Option<ExitReceiver>>>,
+    /// The number of projects that are not shut down yet in the turbo-tasks
+    /// instance. The instance is stopped when the last one is shut down.
+    live_projects: Arc<AtomicUsize>,
+    /// Set by [project_shutdown], so shutting a project down twice doesn't
+    /// count it twice in `live_projects`.
+    shut_down: AtomicBool,
+    /// The file systems of the projects that are shut down already. Projects
+    /// can share a file system, so watchers are only stopped together with the
+    /// turbo-tasks instance.
+    retired_filesystems: Arc<Mutex<Vec<ReadRef<DiskFileSystem>>>>,
+    navigation_hints: Arc<NavigationHints>,
+    /// Serves file hashes to other tools, see [start_hash_service]. Stopped on
+    /// shutdown.
+    hash_service: Mutex<Option<HashServiceHandle>>,
+    /// Set when incremental builds are verified, see [ShadowRebuild]. Stopped
+    /// on shutdown.
+    shadow_rebuild: Mutex<Option<Arc<ShadowRebuild>>>,
+    /// Pins the client runtime in development, see [pin_client_runtime].
+    /// Unpinned on shutdown.
+    pinned_client_runtime: Mutex<Option<TaskId>>,
 }
 
+/// Routes the user is likely to navigate to next, e.g. the targets of links on
+/// the current page.
+#[derive(Default)]
+struct NavigationHints {
+    pathnames: Mutex<FxIndexSet<RcStr>>,
+    added: tokio::sync::Notify,
+    idle_loop_started: AtomicBool,
+}
+
+static INTERN_STRINGS: FeatureFlag<bool> =
+    FeatureFlag::new("intern-strings", "NEXT_TURBOPACK_INTERN_STRINGS", false);
+/// The percentage of modules sampled by the build telemetry, see [build_telemetry_report].
+static BUILD_TELEMETRY: FeatureFlag<usize> =
+    FeatureFlag::new("build-telemetry", "NEXT_TURBOPACK_BUILD_TELEMETRY", 0);
+static COMPILE_TIME_REPORT: FeatureFlag<bool> = FeatureFlag::new(
+    "compile-time-report",
+    "NEXT_TURBOPACK_COMPILE_TIME_REPORT",
+    false,
+);
+
 #[napi(ts_return_type = "Promise<{ __napiType: \"Project\" }>")]
 pub async fn project_new(
     options: NapiProjectOptions,
@@ -346,11 +426,32 @@ pub async fn project_new(
         .memory_limit
         .map(|m| m as usize)
         .unwrap_or(usize::MAX);
-    let persistent_caching = turbo_engine_options.persistent_caching.unwrap_or_default();
+    for (name, value) in turbo_engine_options.feature_flags.into_iter().flatten() {
+        if let Err(err) = set_feature_flag(&name, &value) {
+            tracing::warn!("{err}");
+        }
+    }
+    let persistent_caching = turbo_engine_options
+        .persistent_caching
+        .unwrap_or_else(|| PERSISTENT_CACHING.get());
+    if INTERN_STRINGS.get() {
+        turbo_rcstr::set_interning(true);
+    }
     let turbo_tasks = create_turbo_tasks(
         PathBuf::from(&options.dist_dir),
         persistent_caching,
         memory_limit,
+        turbo_engine_options
+            .memory_target
+            .map(|target| target as usize),
+        Parallelism {
+            chunking: turbo_engine_options
+                .chunking_parallelism
+                .map(|limit| limit as usize),
+            emit: turbo_engine_options
+                .emit_parallelism
+                .map(|limit| limit as usize),
+        },
     )?;
     if !persistent_caching {
         use std::io::Write;
@@ -374,32 +475,171 @@ pub async fn project_new(
             });
         }
     }
+    if COMPILE_TIME_REPORT.get() {
+        let times = enable_compile_time_report();
+        exit.on_exit(async move {
+            print!("{}", times.report());
+        });
+    }
+    let build_telemetry_percentage = BUILD_TELEMETRY.get();
+    if build_telemetry_percentage > 0 {
+        enable_build_telemetry(build_telemetry_percentage as f64 / 100.0);
+    }
+    let dev = options.dev;
+    let container = create_project_container(&turbo_tasks, "next.js".into(), options).await?;
+    let shadow_rebuild = ShadowRebuild::start(&turbo_tasks, container)
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    let pinned_client_runtime = pin_client_runtime(&turbo_tasks, container, dev).await?;
+
+    let hash_service = start_hash_service(&turbo_tasks, container)
+        .await
+        .inspect_err(|err| tracing::warn!(%err, "failed to start the hash service"))
+        .ok()
+        .flatten();
+
+    turbo_tasks.spawn_once_task(async move {
+        benchmark_file_io(container.project().node_root())
+            .await
+            .inspect_err(|err| tracing::warn!(%err, "failed to benchmark file IO"))
+    });
+    Ok(External::new_with_size_hint(
+        ProjectInstance {
+            turbo_tasks,
+            container,
+            exit_receiver: Arc::new(tokio::sync::Mutex::new(Some(exit_receiver))),
+            live_projects: Arc::new(AtomicUsize::new(1)),
+            shut_down: AtomicBool::new(false),
+            retired_filesystems: Default::default(),
+            navigation_hints: Default::default(),
+            hash_service: Mutex::new(hash_service),
+            shadow_rebuild: Mutex::new(shadow_rebuild),
+            pinned_client_runtime: Mutex::new(pinned_client_runtime),
+        },
+        100,
+    ))
+}
+
+async fn create_project_container(
+    turbo_tasks: &NextTurboTasks,
+    name: RcStr,
+    options: NapiProjectOptions,
+) -> napi::Result<Vc<ProjectContainer>> {
     let options: ProjectOptions = options.into();
-    let container = turbo_tasks
+    turbo_tasks
         .run_once(async move {
-            let project = ProjectContainer::new("next.js".into(), options.dev);
+            let project = ProjectContainer::new(name, options.dev);
             let project = project.resolve().await?;
             project.initialize(options).await?;
             Ok(project)
         })
         .await
-        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))
+}
 
-    turbo_tasks.spawn_once_task(async move {
-        benchmark_file_io(container.project().node_root())
+/// Creates another project in the turbo-tasks instance of `project`, e.g. for
+/// another app in the same monorepo. The projects share the task cache, the
+/// persistent cache and the file watcher, but have their own options, so
+/// modules are compiled separately for each project. Fails when `project` is
+/// shut down already.
+#[napi(ts_return_type = "Promise<{ __napiType: \"Project\" }>")]
+pub async fn project_new_sibling(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    options: NapiProjectOptions,
+) -> napi::Result<External<ProjectInstance>> {
+    // Counts the sibling as live before creating it, so the instance isn't
+    // stopped while the sibling is created.
+    if project.shut_down.load(Ordering::SeqCst)
+        || project
+            .live_projects
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |live| {
+                (live > 0).then_some(live + 1)
+            })
+            .is_err()
+    {
+        return Err(napi::Error::from_reason(
+            "Unable to create a sibling of a project that is shut down",
+        ));
+    }
+    let turbo_tasks = project.turbo_tasks.clone();
+    // Containers are cached by their name, so each project needs its own.
+    let name = format!("next.js ({})", options.project_path).into();
+    let dev = options.dev;
+    let created = async {
+        let container = create_project_container(&turbo_tasks, name, options).await?;
+        let shadow_rebuild = ShadowRebuild::start(&turbo_tasks, container)
             .await
-            .inspect_err(|err| tracing::warn!(%err, "failed to benchmark file IO"))
-    });
+            .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+        let pinned_client_runtime = pin_client_runtime(&turbo_tasks, container, dev).await?;
+        Ok::<_, napi::Error>((container, shadow_rebuild, pinned_client_runtime))
+    }
+    .await;
+    let (container, shadow_rebuild, pinned_client_runtime) = match created {
+        Ok(created) => created,
+        Err(err) => {
+            if project.live_projects.fetch_sub(1, Ordering::SeqCst) == 1 {
+                stop_instance(&project, None).await?;
+            }
+            return Err(err);
+        }
+    };
     Ok(External::new_with_size_hint(
         ProjectInstance {
             turbo_tasks,
             container,
-            exit_receiver: tokio::sync::Mutex::new(Some(exit_receiver)),
+            exit_receiver: project.exit_receiver.clone(),
+            live_projects: project.live_projects.clone(),
+            shut_down: AtomicBool::new(false),
+            retired_filesystems: project.retired_filesystems.clone(),
+            navigation_hints: Default::default(),
+            hash_service: Default::default(),
+            shadow_rebuild: Mutex::new(shadow_rebuild),
+            pinned_client_runtime: Mutex::new(pinned_client_runtime),
         },
         100,
     ))
 }
 
+/// Pins the client runtime modules in development. Every page loads them, so
+/// they are recomputed right after a change instead of when the next page is
+/// requested, see [TurboTasks::pin_vc][turbo_tasks::TurboTasks::pin_vc].
+async fn pin_client_runtime(
+    turbo_tasks: &NextTurboTasks,
+    container: Vc<ProjectContainer>,
+    dev: bool,
+) -> napi::Result<Option<TaskId>> {
+    if !dev {
+        return Ok(None);
+    }
+    let client_main_modules = turbo_tasks
+        .run_once(async move { Ok(container.project().client_main_modules()) })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(Some(turbo_tasks.pin_vc(client_main_modules)))
+}
+
+/// Serves content hashes of the project files on the unix socket at
+/// `NEXT_TURBOPACK_HASH_SERVICE_SOCKET`, when it's set, so tools like a
+/// type-check wrapper can share the watcher and hash cache of the project.
+async fn start_hash_service(
+    turbo_tasks: &NextTurboTasks,
+    container: Vc<ProjectContainer>,
+) -> Result<Option<HashServiceHandle>> {
+    let Some(socket_path) = std::env::var_os("NEXT_TURBOPACK_HASH_SERVICE_SOCKET") else {
+        return Ok(None);
+    };
+    let project_fs = turbo_tasks
+        .run_once(async move {
+            Ok(container
+                .project()
+                .project_fs()
+                .strongly_consistent()
+                .await?)
+        })
+        .await?;
+    Ok(Some(project_fs.start_hash_service(socket_path.into())?))
+}
+
 /// A very simple and low-overhead, but potentially noisy benchmark to detect
 /// very slow disk IO. Warns the user (via `println!`) if the benchmark takes
 /// more than `SLOW_FILESYSTEM_THRESHOLD`.
@@ -475,11 +715,146 @@ pub async fn project_update(
     Ok(())
 }
 
+/// Shuts the project down. The last project of a turbo-tasks instance also
+/// stops the instance: it stops accepting work, drains in-flight tasks (or
+/// abandons them after `drain_timeout_ms`), flushes the persistent cache and
+/// then stops the file watchers. Shutting a project down again does nothing.
 #[napi]
 pub async fn project_shutdown(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    drain_timeout_ms: Option<u32>,
+) -> napi::Result<()> {
+    if project.shut_down.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let container = project.container;
+    let project_fs = project
+        .turbo_tasks
+        .run_once(async move {
+            Ok(container
+                .project()
+                .project_fs()
+                .strongly_consistent()
+                .await?)
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    project.retired_filesystems.lock().unwrap().push(project_fs);
+    drop(project.hash_service.lock().unwrap().take());
+    // Endpoints written during the shutdown aren't verified anymore.
+    drop(project.shadow_rebuild.lock().unwrap().take());
+    if let Some(task) = project.pinned_client_runtime.lock().unwrap().take() {
+        project.turbo_tasks.dispose_root_task(task);
+    }
+
+    if project.live_projects.fetch_sub(1, Ordering::SeqCst) != 1 {
+        return Ok(());
+    }
+    stop_instance(&project, drain_timeout_ms).await
+}
+
+/// Stops the turbo-tasks instance of `project` after its last project was shut
+/// down, see [project_shutdown].
+async fn stop_instance(
+    project: &ProjectInstance,
+    drain_timeout_ms: Option<u32>,
+) -> napi::Result<()> {
+    let turbo_tasks = &project.turbo_tasks;
+    match drain_timeout_ms {
+        Some(drain_timeout_ms) => {
+            let drain_timeout = Duration::from_millis(drain_timeout_ms.into());
+            if !turbo_tasks.stop_and_wait_with_timeout(drain_timeout).await {
+                tracing::warn!(
+                    ?drain_timeout,
+                    "abandoned tasks that were still running on shutdown"
+                );
+            }
+        }
+        None => turbo_tasks.stop_and_wait().await,
+    }
+    // Lets the idle loop notice the stop.
+    project.navigation_hints.added.notify_one();
+
+    // Watchers are only stopped after turbo-tasks has stopped, so changes
+    // made while the running tasks are drained still invalidate them before
+    // their results are persisted. Later changes are ignored.
+    let filesystems = take(&mut *project.retired_filesystems.lock().unwrap());
+    tokio::task::spawn_blocking(move || {
+        for fs in filesystems {
+            fs.stop_watching();
+        }
+    })
+    .await
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(())
+}
+
+/// How long the project needs to be idle before hinted routes are compiled.
+const PRECOMPILE_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Hints routes the user is likely to navigate to next. They are compiled in
+/// the background once the project has been idle for a few seconds, so the
+/// navigation doesn't need to wait for the compilation.
+#[napi]
+pub async fn project_navigation_hints(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    pathnames: Vec<String>,
+) {
+    let hints = &project.navigation_hints;
+    hints
+        .pathnames
+        .lock()
+        .unwrap()
+        .extend(pathnames.into_iter().map(RcStr::from));
+    hints.added.notify_one();
+    if !hints.idle_loop_started.swap(true, Ordering::SeqCst) {
+        tokio::spawn(precompile_when_idle(
+            project.turbo_tasks.clone(),
+            project.container,
+            hints.clone(),
+        ));
+    }
+}
+
+/// Compiles hinted routes whenever the project becomes idle, until turbo-tasks
+/// is stopped. Garbage collection and persisting the cache are already done by
+/// the backends when they become idle.
+async fn precompile_when_idle(
+    turbo_tasks: NextTurboTasks,
+    container: Vc<ProjectContainer>,
+    hints: Arc<NavigationHints>,
 ) {
-    project.turbo_tasks.stop_and_wait().await;
+    loop {
+        hints.added.notified().await;
+        if !turbo_tasks.wait_idle(PRECOMPILE_IDLE_TIMEOUT).await {
+            return;
+        }
+        let pathnames: Vec<RcStr> = take(&mut *hints.pathnames.lock().unwrap())
+            .into_iter()
+            .collect();
+        if pathnames.is_empty() {
+            continue;
+        }
+        let result = turbo_tasks
+            .run_once(async move {
+                // One task per route, so hinting the same route again reuses it.
+                pathnames
+                    .into_iter()
+                    .map(|pathname| async move {
+                        container
+                            .precompile_route(pathname)
+                            .strongly_consistent()
+                            .await
+                    })
+                    .try_join()
+                    .await?;
+                Ok(())
+            })
+            .await;
+        if let Err(err) = result {
+            tracing::warn!(%err, "failed to precompile hinted routes");
+        }
+    }
 }
 
 #[napi(object)]
@@ -906,6 +1281,111 @@ pub fn project_hmr_identifiers_subscribe(
     )
 }
 
+#[napi(object)]
+struct NapiEntrypointChanges {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl From<EntrypointChanges> for NapiEntrypointChanges {
+    fn from(changes: EntrypointChanges) -> Self {
+        let into_strings = |names: Vec<RcStr>| names.into_iter().map(String::from).collect();
+        NapiEntrypointChanges {
+            added: into_strings(changes.added),
+            changed: into_strings(changes.changed),
+            removed: into_strings(changes.removed),
+        }
+    }
+}
+
+#[turbo_tasks::value(serialization = "none")]
+struct WrittenEntrypointsWithIssues {
+    written: ReadRef<WrittenEntrypoints>,
+    issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
+    effects: Arc<Effects>,
+}
+
+#[turbo_tasks::function]
+async fn write_all_entrypoints_to_disk_with_issues(
+    container: Vc<ProjectContainer>,
+) -> Result<Vc<WrittenEntrypointsWithIssues>> {
+    let written_operation = container.write_all_entrypoints_to_disk();
+    let written = written_operation.strongly_consistent().await?;
+    let issues = get_issues(written_operation).await?;
+    let diagnostics = get_diagnostics(written_operation).await?;
+    let effects = Arc::new(get_effects(written_operation).await?);
+    Ok(WrittenEntrypointsWithIssues {
+        written,
+        issues,
+        diagnostics,
+        effects,
+    }
+    .cell())
+}
+
+/// Builds all entrypoints and writes them to disk, and does so again whenever
+/// the output changes. Combined with a production project with `watch`
+/// enabled, this gives `next build --watch` semantics. Each build reports the
+/// endpoints whose output was added, changed or removed since the previous
+/// one. Files which are no longer written by any endpoint are removed.
+#[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
+pub fn project_build_watch_subscribe(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    func: JsFunction,
+) -> napi::Result<External<RootTask>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let previous: Arc<Mutex<Option<ReadRef<WrittenEntrypoints>>>> = Default::default();
+    subscribe(
+        turbo_tasks.clone(),
+        func,
+        move || {
+            let previous = previous.clone();
+            async move {
+                let operation = write_all_entrypoints_to_disk_with_issues(container);
+                let WrittenEntrypointsWithIssues {
+                    written,
+                    issues,
+                    diagnostics,
+                    effects,
+                } = &*operation.strongly_consistent().await?;
+                effects.apply().await?;
+
+                let changes = {
+                    let mut previous = previous.lock().unwrap();
+                    let changes = EntrypointChanges::between(previous.as_deref(), written);
+                    *previous = Some(written.clone());
+                    changes
+                };
+                if !changes.stale_paths.is_empty() {
+                    let removal = container.remove_output_files(changes.stale_paths.clone());
+                    removal.strongly_consistent().await?;
+                    apply_effects(removal).await?;
+                }
+                Ok((changes, issues.clone(), diagnostics.clone()))
+            }
+            .instrument(tracing::info_span!("build watch subscription"))
+        },
+        move |ctx| {
+            let (changes, issues, diagnostics) = ctx.value;
+
+            Ok(vec![TurbopackResult {
+                result: NapiEntrypointChanges::from(changes),
+                issues: issues
+                    .iter()
+                    .map(|issue| NapiIssue::from(&**issue))
+                    .collect(),
+                diagnostics: diagnostics
+                    .iter()
+                    .map(|d| NapiDiagnostic::from(d))
+                    .collect(),
+            }])
+        },
+    )
+}
+
 enum UpdateMessage {
     Start,
     End(UpdateInfo),
@@ -1024,34 +1504,16 @@ pub async fn get_source_map(
     container: Vc<ProjectContainer>,
     file_path: String,
 ) -> Result<Option<Vc<SourceMap>>> {
-    let (file, module) = match Url::parse(&file_path) {
-        Ok(url) => match url.scheme() {
-            "file" => {
-                let path = urlencoding::decode(url.path())?.to_string();
-                let module = url.query_pairs().find(|(k, _)| k == "id");
-                (
-                    path,
-                    match module {
-                        Some(module) => Some(urlencoding::decode(&module.1)?.into_owned().into()),
-                        None => None,
-                    },
-                )
-            }
-            _ => bail!("Unknown url scheme"),
-        },
-        Err(_) => (file_path.to_string(), None),
-    };
-
-    let Some(chunk_base) = file.strip_prefix(
-        &(format!(
-            "{}/{}/",
-            container.project().await?.project_path,
-            container.project().dist_dir().await?
-        )),
-    ) else {
+    let dist_path = format!(
+        "{}/{}",
+        container.project().await?.project_path,
+        container.project().dist_dir().await?
+    );
+    let Some((chunk_base, module)) = source_map_chunk_path(&file_path, &dist_path)? else {
         // File doesn't exist within the dist dir
         return Ok(None);
     };
+    let chunk_base = chunk_base.as_str();
 
     let server_path = container.project().node_root().join(chunk_base.into());
 
@@ -1072,6 +1534,18 @@ pub async fn get_source_map(
         map = container.get_source_map(client_path, module).await?;
     }
 
+    if map.is_none() {
+        // Client chunks of production builds might have an internal symbol
+        // map instead of a source map next to them.
+        let symbol_map = container
+            .project()
+            .symbol_maps_root()
+            .join(format!("{chunk_base}.map").into());
+        map = SourceMap::new_from_file(symbol_map)
+            .await?
+            .map(|map| map.cell());
+    }
+
     let map = map.context("chunk/module is missing a sourcemap")?;
 
     Ok(Some(map))
@@ -1202,14 +1676,342 @@ pub async fn project_get_source_map(
     Ok(source_map)
 }
 
+#[napi(object)]
+pub struct NapiEmittedAsset {
+    /// Relative to the dist dir.
+    pub path: String,
+    pub size: f64,
+    pub content_hash: String,
+    /// The names of the endpoints whose output contains the asset.
+    pub endpoints: Vec<String>,
+    /// The paths of the source modules in the asset, when it is a chunk,
+    /// relative to the project directory when they are inside of it.
+    pub modules: Vec<String>,
+}
+
+impl From<&EmittedAsset> for NapiEmittedAsset {
+    fn from(asset: &EmittedAsset) -> Self {
+        NapiEmittedAsset {
+            path: asset.path.to_string(),
+            size: asset.size as f64,
+            content_hash: format!("{:x}", asset.content_hash),
+            endpoints: asset
+                .endpoints
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            modules: asset.modules.iter().map(|path| path.to_string()).collect(),
+        }
+    }
+}
+
+/// Lists every asset written by `writeAllEntrypointsToDisk`, with the
+/// endpoints it belongs to and the modules it contains.
+#[napi]
+pub async fn project_emitted_assets(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<Vec<NapiEmittedAsset>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    let assets = turbo_tasks
+        .run_once(async move {
+            let assets = container
+                .project()
+                .emitted_assets()
+                .strongly_consistent()
+                .await?;
+            Ok(assets.iter().map(NapiEmittedAsset::from).collect())
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(assets)
+}
+
+/// Writes `fingerprint-manifest.json` to the dist dir, which maps the logical
+/// path of every written asset to its fingerprinted path, content hash, size
+/// and referenced assets, for differential CDN uploads.
+#[napi]
+pub async fn project_write_fingerprint_manifest(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<()> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    turbo_tasks
+        .run_once(async move {
+            let write = container.project().write_fingerprint_manifest();
+            write.strongly_consistent().await?;
+            apply_effects(write).await?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(())
+}
+
+/// The environments `project_trace_resolve` can trace resolves in, e.g.
+/// `app-rsc` or `pages-edge-api`.
+#[napi]
+pub async fn project_resolve_trace_environments(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<Vec<String>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    let environments = turbo_tasks
+        .run_once(async move {
+            Ok(container
+                .project()
+                .resolve_trace_environments()
+                .strongly_consistent()
+                .await?
+                .iter()
+                .map(|environment| environment.to_string())
+                .collect())
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(environments)
+}
+
+#[napi(object)]
+pub struct NapiResolveTraceOptions {
+    /// One of `project_resolve_trace_environments`.
+    pub environment: String,
+    pub specifier: String,
+    /// The importing file, relative to the project directory.
+    pub importer: String,
+    /// `import` (the default), `require` or `url`.
+    pub kind: Option<String>,
+}
+
+/// Resolves a specifier like the given environment would and returns every
+/// step the resolver took as JSON: the aliases tried, the extensions probed,
+/// the exports conditions evaluated and the plugins consulted.
+#[napi]
+pub async fn project_trace_resolve(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    options: NapiResolveTraceOptions,
+) -> napi::Result<String> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    let json = turbo_tasks
+        .run_once(async move {
+            let trace = container
+                .project()
+                .trace_resolve(
+                    options.environment.into(),
+                    options.specifier.into(),
+                    options.importer.into(),
+                    options.kind.map(RcStr::from),
+                )
+                .strongly_consistent()
+                .await?;
+            Ok(serde_json::to_string(&*trace)?)
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(json)
+}
+
+#[napi(object)]
+pub struct NapiBundleDiff {
+    /// The comparison of every route with the previous build, as JSON.
+    pub json: String,
+    /// The routes which changed, as a table.
+    pub table: String,
+    /// The build compared against, if there was a previous successful build.
+    pub previous_build_id: Option<String>,
+}
+
+/// Compares the output size and the npm packages of every route with the last
+/// successful build. With `persist`, this build is stored to be compared
+/// against by the next one, which should only be done when it succeeded.
+#[napi]
+pub async fn project_bundle_diff(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    persist: bool,
+) -> napi::Result<NapiBundleDiff> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    let diff = turbo_tasks
+        .run_once(async move {
+            let diff = bundle_diff(container.project(), persist).await?;
+            Ok(NapiBundleDiff {
+                json: serde_json::to_string_pretty(&diff)?,
+                table: diff.to_string(),
+                previous_build_id: diff.previous_build_id.as_ref().map(|id| id.to_string()),
+            })
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(diff)
+}
+
+/// Reports the route budgets exceeded by this build, compared to the last
+/// successful build. With `persist`, this build is stored to be compared
+/// against by the next one, which should only be done when it succeeded.
+#[napi]
+pub async fn project_check_route_budgets(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    persist: bool,
+) -> napi::Result<TurbopackResult<()>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+
+    let (issues, diagnostics) = turbo_tasks
+        .run_once(async move {
+            let operation = route_budgets(container.project(), persist).await?;
+            Ok((
+                get_issues(operation).await?,
+                get_diagnostics(operation).await?,
+            ))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+
+    Ok(TurbopackResult {
+        result: (),
+        issues: issues
+            .iter()
+            .map(|issue| NapiIssue::from(&**issue))
+            .collect(),
+        diagnostics: diagnostics
+            .iter()
+            .map(|d| NapiDiagnostic::from(d))
+            .collect(),
+    })
+}
+
+#[napi(object)]
+pub struct NapiDryRunReport {
+    /// The number of tasks in the persistent cache.
+    pub tasks: u32,
+    /// The number of cached tasks which wouldn't need to be executed again.
+    pub cache_hits: u32,
+    /// `cacheHits / tasks`, `0` when nothing is cached.
+    pub hit_rate: f64,
+    /// The inputs, e.g. file reads, which changed since they were cached.
+    pub changed_inputs: Vec<String>,
+    /// The report, as text.
+    pub summary: String,
+}
+
+/// Reports how much of the build can be taken from the persistent cache,
+/// without building. Only the inputs of the cached tasks, e.g. file reads, are
+/// executed again to find out whether they changed. Needs to be called before
+/// anything else is computed with the project.
+#[napi]
+pub async fn project_build_dry_run(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<NapiDryRunReport> {
+    let report = project.turbo_tasks.dry_run().await;
+    Ok(NapiDryRunReport {
+        tasks: report.tasks as u32,
+        cache_hits: report.cache_hits as u32,
+        hit_rate: report.hit_rate(),
+        summary: report.to_string(),
+        changed_inputs: report.changed_inputs,
+    })
+}
+
+/// Returns the JSON schema of `experimental.turbo` in `next.config.js`, for
+/// validating the config and for editor autocompletion.
+#[napi]
+pub fn turbopack_config_schema() -> napi::Result<String> {
+    ExperimentalTurboConfig::json_schema()
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))
+}
+
+#[napi(object)]
+pub struct NapiAllocatorStats {
+    /// Bytes currently allocated.
+    pub allocated: f64,
+    /// Bytes the allocator has committed from the OS. Not reported by the system
+    /// allocator.
+    pub committed: Option<f64>,
+    /// Bytes committed by the allocator that aren't allocated.
+    pub retained: Option<f64>,
+    /// The share of committed memory that isn't allocated, between 0 and 1.
+    pub fragmentation: Option<f64>,
+    /// The resident set size of the process in bytes.
+    pub resident: Option<f64>,
+    /// The peak resident set size of the process in bytes.
+    pub peak_resident: Option<f64>,
+}
+
+impl From<AllocatorStats> for NapiAllocatorStats {
+    fn from(stats: AllocatorStats) -> Self {
+        NapiAllocatorStats {
+            allocated: stats.allocated as f64,
+            committed: stats.committed.map(|bytes| bytes as f64),
+            retained: stats.retained().map(|bytes| bytes as f64),
+            fragmentation: stats.fragmentation(),
+            resident: stats.resident.map(|bytes| bytes as f64),
+            peak_resident: stats.peak_resident.map(|bytes| bytes as f64),
+        }
+    }
+}
+
+/// Returns statistics of the memory allocator, which is shared by all projects
+/// of the process.
+#[napi]
+pub fn allocator_stats() -> NapiAllocatorStats {
+    TurboMalloc::stats().into()
+}
+
+/// Returns memory the allocator retains for future allocations to the OS, e.g.
+/// after a large rebuild. Returns the statistics after purging.
+#[napi]
+pub fn allocator_purge() -> NapiAllocatorStats {
+    TurboMalloc::purge();
+    TurboMalloc::stats().into()
+}
+
+/// Starts collecting the compile times of a sample of all modules, including the
+/// time of every transform. `sample_rate` is the share of sampled modules,
+/// between 0 and 1. Collecting is global to the process and can't be disabled
+/// again, and only the first call sets the sample rate. Can also be enabled with
+/// `NEXT_TURBOPACK_BUILD_TELEMETRY=<percentage>`.
+#[napi]
+pub fn enable_build_telemetry_sampling(sample_rate: f64) {
+    enable_build_telemetry(sample_rate);
+}
+
+/// Returns the compile times of the sampled modules as JSON, with modules
+/// anonymized, or `null` when sampling isn't enabled. With `clear`, collecting
+/// starts over afterwards.
+#[napi]
+pub fn build_telemetry_report(clear: bool) -> napi::Result<Option<String>> {
+    let Some(telemetry) = build_telemetry() else {
+        return Ok(None);
+    };
+    let report = serde_json::to_string(&telemetry.report())
+        .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    if clear {
+        telemetry.clear();
+    }
+    Ok(Some(report))
+}
+
 /// Runs exit handlers for the project registered using the [`ExitHandler`] API.
+/// Projects sharing a turbo-tasks instance exit together: the first call runs
+/// the handlers and the other calls wait for them to finish.
 #[napi]
 pub async fn project_on_exit(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
 ) {
-    let exit_receiver = project.exit_receiver.lock().await.take();
-    exit_receiver
-        .expect("`project.onExitSync` must only be called once")
-        .run_exit_handler()
-        .await;
+    // The lock is held while the handlers run, so the other calls wait for them.
+    let mut exit_receiver = project.exit_receiver.lock().await;
+    if let Some(exit_receiver) = exit_receiver.take() {
+        exit_receiver.run_exit_handler().await;
+    }
 }
@@ -0,0 +1,223 @@
+//! Verifies incremental builds: endpoints written after a change are computed
+//! again from scratch in a separate turbo-tasks instance, and the outputs of
+//! both are compared. Outputs that differ point to a missing or wrong
+//! invalidation, which users see as stale output until a restart.
+
+use std::{
+    fmt::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex, Weak},
+};
+
+use anyhow::Result;
+use next_api::{
+    project::{ProjectContainer, WatchOptions},
+    route::Endpoint,
+    shadow_rebuild::{
+        compare_output_hashes, endpoint_by_name, endpoint_name, endpoint_output_hashes,
+        output_content_task,
+    },
+};
+use rand::Rng;
+use tokio::sync::mpsc;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{feature_flags::FeatureFlag, why_invalidated, Vc};
+use turbopack_core::error::PrettyPrintError;
+
+use super::utils::{create_turbo_tasks, NextTurboTasks, Parallelism};
+
+/// The percentage of written endpoints that are verified. Only one endpoint is
+/// verified at a time, endpoints written in the meantime are skipped.
+static SHADOW_REBUILD: FeatureFlag<usize> =
+    FeatureFlag::new("shadow-rebuild", "NEXT_TURBOPACK_SHADOW_REBUILD", 0);
+
+/// The number of differing outputs the invalidation chain is reported for.
+const MAX_REPORTED_OUTPUTS: usize = 5;
+
+/// The active verifications, to find the one of the project an endpoint
+/// belongs to.
+static SHADOW_REBUILDS: Mutex<Vec<Weak<ShadowRebuild>>> = Mutex::new(Vec::new());
+
+/// Verifies the endpoints of one project. Stops when dropped.
+pub struct ShadowRebuild {
+    turbo_tasks: NextTurboTasks,
+    container: Vc<ProjectContainer>,
+    queue: mpsc::Sender<(RcStr, Vc<Box<dyn Endpoint>>)>,
+}
+
+impl ShadowRebuild {
+    /// Starts verifying the endpoints of `container` when enabled with
+    /// `NEXT_TURBOPACK_SHADOW_REBUILD=<percentage>`.
+    pub async fn start(
+        turbo_tasks: &NextTurboTasks,
+        container: Vc<ProjectContainer>,
+    ) -> Result<Option<Arc<Self>>> {
+        if SHADOW_REBUILD.get() == 0 {
+            return Ok(None);
+        }
+        let dev = turbo_tasks
+            .run_once(async move { Ok(container.options().await?.dev) })
+            .await?;
+        if !dev {
+            // Production builds aren't incremental.
+            return Ok(None);
+        }
+        let (queue, mut receiver) = mpsc::channel(1);
+        let this = Arc::new(ShadowRebuild {
+            turbo_tasks: turbo_tasks.clone(),
+            container,
+            queue,
+        });
+        let turbo_tasks = turbo_tasks.clone();
+        tokio::spawn(async move {
+            while let Some((name, endpoint)) = receiver.recv().await {
+                if let Err(err) = verify(&turbo_tasks, container, name.clone(), endpoint).await {
+                    tracing::warn!(
+                        "shadow rebuild of {name} failed: {}",
+                        PrettyPrintError(&err)
+                    );
+                }
+            }
+        });
+        let mut shadow_rebuilds = SHADOW_REBUILDS.lock().unwrap();
+        shadow_rebuilds.retain(|shadow_rebuild| shadow_rebuild.strong_count() > 0);
+        shadow_rebuilds.push(Arc::downgrade(&this));
+        Ok(Some(this))
+    }
+}
+
+/// Called when `endpoint` was written, to verify it with the configured
+/// probability.
+pub fn endpoint_written(turbo_tasks: &NextTurboTasks, endpoint: Vc<Box<dyn Endpoint>>) {
+    let percentage = SHADOW_REBUILD.get();
+    if percentage == 0 || rand::thread_rng().gen_range(0..100) >= percentage {
+        return;
+    }
+    let candidates: Vec<_> = SHADOW_REBUILDS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .filter(|shadow_rebuild| shadow_rebuild.turbo_tasks.ptr_eq(turbo_tasks))
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+    let turbo_tasks = turbo_tasks.clone();
+    tokio::spawn(async move {
+        // Projects can share a turbo-tasks instance, the endpoint belongs to the
+        // one it has a name in.
+        for shadow_rebuild in candidates {
+            let container = shadow_rebuild.container;
+            let name = turbo_tasks
+                .run_once(async move { endpoint_name(container.project(), endpoint).await })
+                .await;
+            if let Ok(Some(name)) = name {
+                // Skipped when a verification is in progress.
+                let _ = shadow_rebuild.queue.try_send((name, endpoint));
+                return;
+            }
+        }
+    });
+}
+
+async fn verify(
+    turbo_tasks: &NextTurboTasks,
+    container: Vc<ProjectContainer>,
+    name: RcStr,
+    endpoint: Vc<Box<dyn Endpoint>>,
+) -> Result<()> {
+    let (mut options, incremental) = turbo_tasks
+        .run_once(async move {
+            let incremental = endpoint_output_hashes(endpoint)
+                .strongly_consistent()
+                .await?
+                .clone_value();
+            Ok((container.options().await?, incremental))
+        })
+        .await?;
+
+    // The fresh instance only reads the files, it doesn't need to watch them.
+    options.watch = WatchOptions {
+        enable: false,
+        poll_interval: None,
+    };
+    let fresh = create_turbo_tasks(
+        PathBuf::new(),
+        false,
+        usize::MAX,
+        None,
+        Parallelism::default(),
+    )?;
+    let from_scratch = fresh
+        .run_once({
+            let name = name.clone();
+            async move {
+                let container = ProjectContainer::new("shadow rebuild".into(), options.dev)
+                    .resolve()
+                    .await?;
+                container.initialize(options).await?;
+                let Some(endpoint) = endpoint_by_name(container.project(), &name).await? else {
+                    return Ok(None);
+                };
+                Ok(Some(
+                    endpoint_output_hashes(endpoint)
+                        .strongly_consistent()
+                        .await?
+                        .clone_value(),
+                ))
+            }
+        })
+        .await;
+    fresh.stop_and_wait().await;
+    let Some(from_scratch) = from_scratch? else {
+        // The endpoint was removed in the meantime.
+        return Ok(());
+    };
+
+    let divergences = compare_output_hashes(&incremental, &from_scratch);
+    if divergences.is_empty() {
+        return Ok(());
+    }
+    let report = turbo_tasks
+        .run_once(async move {
+            let mut report = format!(
+                "Shadow rebuild: {} output(s) of {name} differ from a build from scratch. The \
+                 incremental build missed a change, please report this.\n",
+                divergences.len()
+            );
+            let format_hash = |hash: Option<u64>| {
+                hash.map_or("missing".to_string(), |hash| format!("{hash:016x}"))
+            };
+            for (index, divergence) in divergences.iter().enumerate() {
+                writeln!(
+                    report,
+                    "  {}: incremental {}, from scratch {}",
+                    divergence.path,
+                    format_hash(divergence.incremental),
+                    format_hash(divergence.from_scratch)
+                )?;
+                if index >= MAX_REPORTED_OUTPUTS || divergence.incremental.is_none() {
+                    continue;
+                }
+                let Some(task) = output_content_task(endpoint, &divergence.path).await? else {
+                    continue;
+                };
+                let steps = why_invalidated(task);
+                if steps.is_empty() {
+                    writeln!(
+                        report,
+                        "    not invalidated since it was computed (set \
+                         NEXT_TURBOPACK_TRACK_INVALIDATION_CAUSES=1 to record invalidations)"
+                    )?;
+                }
+                for step in steps {
+                    writeln!(report, "    {step}")?;
+                }
+            }
+            Ok(report)
+        })
+        .await?;
+    eprint!("{report}");
+    Ok(())
+}
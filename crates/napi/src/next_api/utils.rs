@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap, env, future::Future, ops::Deref, path::PathBuf, sync::Arc, time::Duration,
+    collections::HashMap, future::Future, ops::Deref, path::PathBuf, sync::Arc, time::Duration,
 };
 
 use anyhow::{anyhow, Context, Result};
@@ -8,16 +8,23 @@ use napi::{
     threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode},
     JsFunction, JsObject, JsUnknown, NapiRaw, NapiValue, Status,
 };
+use next_api::issue_origin::IssueOrigin;
+use next_core::EMIT_PARALLELISM;
 use serde::Serialize;
 use turbo_tasks::{
-    trace::TraceRawVcs, ReadRef, TaskId, TryJoinIterExt, TurboTasks, UpdateInfo, Vc,
+    backend::Backend, feature_flags::FeatureFlag, trace::TraceRawVcs, DryRunReport, ReadRef,
+    TaskId, TryJoinIterExt, TurboTasks, TurboTasksBuilder, UpdateInfo, Vc,
 };
 use turbo_tasks_backend::{default_backing_storage, DefaultBackingStorage};
 use turbo_tasks_fs::FileContent;
 use turbopack_core::{
+    chunk::CHUNKING_PARALLELISM,
     diagnostics::{Diagnostic, DiagnosticContextExt, PlainDiagnostic},
     error::PrettyPrintError,
-    issue::{IssueDescriptionExt, PlainIssue, PlainIssueSource, PlainSource, StyledString},
+    issue::{
+        IssueDescriptionExt, PlainIssue, PlainIssueSource, PlainSource, PlainSuggestedFix,
+        PlainTextEdit, StyledString,
+    },
     source_pos::SourcePos,
 };
 
@@ -32,6 +39,17 @@ pub enum NextTurboTasks {
 }
 
 impl NextTurboTasks {
+    /// Whether both refer to the same turbo-tasks instance.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NextTurboTasks::Memory(a), NextTurboTasks::Memory(b)) => Arc::ptr_eq(a, b),
+            (NextTurboTasks::PersistentCaching(a), NextTurboTasks::PersistentCaching(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+
     pub fn dispose_root_task(&self, task: TaskId) {
         match self {
             NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.dispose_root_task(task),
@@ -39,6 +57,16 @@ impl NextTurboTasks {
         }
     }
 
+    pub fn pin_vc<T>(&self, vc: Vc<T>) -> TaskId
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.pin_vc(vc),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.pin_vc(vc),
+        }
+    }
+
     pub fn spawn_root_task<T, F, Fut>(&self, functor: F) -> TaskId
     where
         T: Send,
@@ -106,6 +134,13 @@ impl NextTurboTasks {
         }
     }
 
+    pub async fn dry_run(&self) -> DryRunReport {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.dry_run().await,
+            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.dry_run().await,
+        }
+    }
+
     pub fn memory_backend(&self) -> Option<&turbo_tasks_memory::MemoryBackend> {
         match self {
             NextTurboTasks::Memory(turbo_tasks) => Some(turbo_tasks.backend()),
@@ -119,26 +154,102 @@ impl NextTurboTasks {
             NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.stop_and_wait().await,
         }
     }
+
+    pub async fn wait_idle(&self, idle_timeout: Duration) -> bool {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.wait_idle(idle_timeout).await,
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.wait_idle(idle_timeout).await
+            }
+        }
+    }
+
+    pub async fn stop_and_wait_with_timeout(&self, drain_timeout: Duration) -> bool {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => {
+                turbo_tasks.stop_and_wait_with_timeout(drain_timeout).await
+            }
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.stop_and_wait_with_timeout(drain_timeout).await
+            }
+        }
+    }
+}
+
+/// Uses the new backend with persistent caching when `persistentCaching` isn't passed in the
+/// turbo engine options.
+pub static PERSISTENT_CACHING: FeatureFlag<bool> = FeatureFlag::new(
+    "persistent-caching",
+    "NEXT_TURBOPACK_PERSISTENT_CACHING",
+    false,
+);
+static PRINT_TASK_INVALIDATION: FeatureFlag<bool> = FeatureFlag::new(
+    "print-task-invalidation",
+    "NEXT_TURBOPACK_PRINT_TASK_INVALIDATION",
+    false,
+);
+/// Records why tasks are invalidated, see `turbo_tasks::why_invalidated`.
+static TRACK_INVALIDATION_CAUSES: FeatureFlag<bool> = FeatureFlag::new(
+    "track-invalidation-causes",
+    "NEXT_TURBOPACK_TRACK_INVALIDATION_CAUSES",
+    false,
+);
+
+/// How many futures of a phase of the compilation may run at the same time.
+/// `None` doesn't limit the phase.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Parallelism {
+    /// See [CHUNKING_PARALLELISM].
+    pub chunking: Option<usize>,
+    /// See [EMIT_PARALLELISM].
+    pub emit: Option<usize>,
+}
+
+impl Parallelism {
+    fn apply<B: Backend + 'static>(
+        &self,
+        mut builder: TurboTasksBuilder<B>,
+    ) -> TurboTasksBuilder<B> {
+        if let Some(limit) = self.chunking {
+            builder = builder.parallelism_limit(CHUNKING_PARALLELISM.name(), limit);
+        }
+        if let Some(limit) = self.emit {
+            builder = builder.parallelism_limit(EMIT_PARALLELISM.name(), limit);
+        }
+        builder
+    }
 }
 
 pub fn create_turbo_tasks(
     output_path: PathBuf,
     persistent_caching: bool,
     memory_limit: usize,
+    memory_target: Option<usize>,
+    parallelism: Parallelism,
 ) -> Result<NextTurboTasks> {
     Ok(if persistent_caching {
-        NextTurboTasks::PersistentCaching(TurboTasks::new(
-            turbo_tasks_backend::TurboTasksBackend::new(
-                turbo_tasks_backend::BackendOptions::default(),
-                default_backing_storage(&output_path.join("cache/turbopack"))?,
-            ),
-        ))
+        NextTurboTasks::PersistentCaching(
+            parallelism
+                .apply(TurboTasks::builder(
+                    turbo_tasks_backend::TurboTasksBackend::new(
+                        turbo_tasks_backend::BackendOptions {
+                            memory_target,
+                            ..Default::default()
+                        },
+                        default_backing_storage(&output_path.join("cache/turbopack"))?,
+                    ),
+                ))
+                .build(),
+        )
     } else {
         let mut backend = turbo_tasks_memory::MemoryBackend::new(memory_limit);
-        if env::var_os("NEXT_TURBOPACK_PRINT_TASK_INVALIDATION").is_some() {
+        if PRINT_TASK_INVALIDATION.get() {
             backend.print_task_invalidation(true);
         }
-        NextTurboTasks::Memory(TurboTasks::new(backend))
+        if TRACK_INVALIDATION_CAUSES.get() {
+            backend.track_invalidation_causes(true);
+        }
+        NextTurboTasks::Memory(parallelism.apply(TurboTasks::builder(backend)).build())
     })
 }
 
@@ -227,6 +338,7 @@ pub async fn get_diagnostics<T: Send>(source: Vc<T>) -> Result<Arc<Vec<ReadRef<P
 #[napi(object)]
 pub struct NapiIssue {
     pub severity: String,
+    pub code: Option<String>,
     pub stage: String,
     pub file_path: String,
     pub title: serde_json::Value,
@@ -235,6 +347,10 @@ pub struct NapiIssue {
     pub source: Option<NapiIssueSource>,
     pub documentation_link: String,
     pub sub_issues: Vec<NapiIssue>,
+    pub suggested_fix: Option<NapiSuggestedFix>,
+    pub transform: Option<String>,
+    pub import_chain: Vec<String>,
+    pub environment: Option<String>,
 }
 
 impl From<&PlainIssue> for NapiIssue {
@@ -252,6 +368,7 @@ impl From<&PlainIssue> for NapiIssue {
                 .map(|styled| serde_json::to_value(StyledStringSerialize::from(styled)).unwrap()),
             documentation_link: issue.documentation_link.to_string(),
             severity: issue.severity.as_str().to_string(),
+            code: issue.code.map(|code| code.to_string()),
             source: issue.source.as_deref().map(|source| source.into()),
             title: serde_json::to_value(StyledStringSerialize::from(&issue.title)).unwrap(),
             sub_issues: issue
@@ -259,10 +376,26 @@ impl From<&PlainIssue> for NapiIssue {
                 .iter()
                 .map(|issue| (&**issue).into())
                 .collect(),
+            suggested_fix: issue.suggested_fix.as_ref().map(|fix| (&**fix).into()),
+            transform: issue.transform.as_ref().map(|t| t.to_string()),
+            import_chain: vec![],
+            environment: None,
         }
     }
 }
 
+impl NapiIssue {
+    /// Adds the import chain and environment of the issue, when they are
+    /// known.
+    pub fn with_origin(mut self, origin: Option<&IssueOrigin>) -> Self {
+        if let Some(origin) = origin {
+            self.import_chain = origin.import_chain.iter().map(|m| m.to_string()).collect();
+            self.environment = origin.environment.as_ref().map(|e| e.to_string());
+        }
+        self
+    }
+}
+
 #[derive(Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum StyledStringSerialize<'a> {
@@ -303,6 +436,8 @@ impl<'a> From<&'a StyledString> for StyledStringSerialize<'a> {
 pub struct NapiIssueSource {
     pub source: NapiSource,
     pub range: Option<NapiIssueSourceRange>,
+    pub label: Option<String>,
+    pub related: Vec<NapiIssueSource>,
 }
 
 impl From<&PlainIssueSource> for NapiIssueSource {
@@ -310,11 +445,49 @@ impl From<&PlainIssueSource> for NapiIssueSource {
         PlainIssueSource {
             asset: source,
             range,
+            label,
+            related,
         }: &PlainIssueSource,
     ) -> Self {
         Self {
             source: (&**source).into(),
             range: range.as_ref().map(|range| range.into()),
+            label: label.as_ref().map(|label| label.to_string()),
+            related: related.iter().map(|related| (&**related).into()).collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct NapiSuggestedFix {
+    pub description: String,
+    pub edits: Vec<NapiTextEdit>,
+}
+
+impl From<&PlainSuggestedFix> for NapiSuggestedFix {
+    fn from(fix: &PlainSuggestedFix) -> Self {
+        Self {
+            description: fix.description.to_string(),
+            edits: fix.edits.iter().map(|edit| edit.into()).collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct NapiTextEdit {
+    pub file_path: String,
+    pub start: NapiSourcePos,
+    pub end: NapiSourcePos,
+    pub replacement: String,
+}
+
+impl From<&PlainTextEdit> for NapiTextEdit {
+    fn from(edit: &PlainTextEdit) -> Self {
+        Self {
+            file_path: edit.file_path.to_string(),
+            start: edit.start.into(),
+            end: edit.end.into(),
+            replacement: edit.replacement.to_string(),
         }
     }
 }
@@ -1,14 +1,20 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Context;
 use napi::bindgen_prelude::*;
+use next_api::library::{emit_library_build, LibraryBuildOptions};
 use next_build::{
     build_options::{BuildContext, DefineEnv},
     BuildOptions as NextBuildOptions,
 };
 use next_core::next_config::{Rewrite, Rewrites, RouteHas};
+use once_cell::sync::Lazy;
+use turbo_rcstr::RcStr;
+use turbo_tasks::TurboTasks;
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::ecmascript::library::LibraryFormat;
 
-use crate::next_api::project::NapiDefineEnv;
+use crate::{next_api::project::NapiDefineEnv, register, util::MapErr};
 
 #[napi(object, object_to_js = false)]
 #[derive(Debug)]
@@ -208,3 +214,63 @@ impl From<NapiRouteHas> for RouteHas {
         }
     }
 }
+
+#[napi(object, object_to_js = false)]
+#[derive(Debug)]
+pub struct NapiLibraryBuildOptions {
+    /// The absolute path of the package.
+    pub dir: String,
+
+    /// The absolute path of the workspace root. Defaults to `dir`.
+    pub root: Option<String>,
+
+    /// The entries, relative to `dir`.
+    pub entries: Vec<String>,
+
+    /// The module format of the entries, one of `cjs`, `esm` or `umd`.
+    pub format: String,
+
+    /// The name of the library in the error of the `umd` format without a
+    /// module loader. Defaults to the file name of the entry.
+    pub name: Option<String>,
+
+    pub minify: Option<bool>,
+}
+
+/// Shared by all library builds, so building a package again only recomputes
+/// what changed.
+static LIBRARY_TURBO_TASKS: Lazy<Arc<TurboTasks<MemoryBackend>>> =
+    Lazy::new(|| TurboTasks::new(MemoryBackend::default()));
+
+/// Bundles a package. The output is written to `dist` in the package
+/// directory.
+#[napi]
+pub async fn turbopack_library_build(options: NapiLibraryBuildOptions) -> napi::Result<()> {
+    register();
+
+    let format = match options.format.as_str() {
+        "cjs" => LibraryFormat::CommonJs,
+        "esm" => LibraryFormat::Esm,
+        "umd" => LibraryFormat::Umd,
+        format => {
+            return Err(napi::Error::new(
+                Status::InvalidArg,
+                format!("invalid library format: {format}"),
+            ))
+        }
+    };
+    let root = options.root.unwrap_or_else(|| options.dir.clone());
+    let options = LibraryBuildOptions {
+        project_dir: options.dir.into(),
+        root_dir: root.into(),
+        entries: options.entries.into_iter().map(RcStr::from).collect(),
+        format,
+        name: options.name.map(RcStr::from),
+        minify: options.minify.unwrap_or(true),
+    };
+
+    LIBRARY_TURBO_TASKS
+        .run_once(emit_library_build(options))
+        .await
+        .convert_err()
+}
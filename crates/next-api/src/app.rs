@@ -317,7 +317,7 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    async fn rsc_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
+    pub(crate) async fn rsc_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
         let transitions = [
             (
                 ECMASCRIPT_CLIENT_TRANSITION_NAME.into(),
@@ -360,7 +360,7 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    async fn edge_rsc_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
+    pub(crate) async fn edge_rsc_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
         let transitions = [
             (
                 ECMASCRIPT_CLIENT_TRANSITION_NAME.into(),
@@ -405,7 +405,7 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    async fn route_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
+    pub(crate) async fn route_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
         let transitions = [
             (
                 ECMASCRIPT_CLIENT_TRANSITION_NAME.into(),
@@ -445,7 +445,9 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    async fn edge_route_module_context(self: Vc<Self>) -> Result<Vc<ModuleAssetContext>> {
+    pub(crate) async fn edge_route_module_context(
+        self: Vc<Self>,
+    ) -> Result<Vc<ModuleAssetContext>> {
         let transitions = [
             (
                 ECMASCRIPT_CLIENT_TRANSITION_NAME.into(),
@@ -486,7 +488,7 @@ impl AppProject {
     }
 
     #[turbo_tasks::function]
-    fn client_module_context(self: Vc<Self>) -> Vc<ModuleAssetContext> {
+    pub(crate) fn client_module_context(self: Vc<Self>) -> Vc<ModuleAssetContext> {
         ModuleAssetContext::new(
             Default::default(),
             self.project().client_compile_time_info(),
@@ -858,11 +860,6 @@ impl AppEndpoint {
         Ok(app_entry)
     }
 
-    #[turbo_tasks::function]
-    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
-        self.output().output_assets()
-    }
-
     #[turbo_tasks::function]
     async fn output(self: Vc<Self>) -> Result<Vc<AppEndpointOutput>> {
         let this = self.await?;
@@ -1714,6 +1711,11 @@ impl Endpoint for AppEndpoint {
         let rsc_entry = self.app_endpoint_entry().await?.rsc_entry;
         Ok(Vc::cell(vec![rsc_entry]))
     }
+
+    #[turbo_tasks::function]
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().output_assets()
+    }
 }
 
 #[turbo_tasks::value]
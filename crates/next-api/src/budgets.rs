@@ -0,0 +1,282 @@
+use std::{fs, io::Write, path::Path};
+
+use anyhow::{Context, Result};
+use flate2::{write::GzEncoder, Compression};
+use next_core::next_config::BudgetSeverity;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{glob::Glob, to_sys_path, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::Asset,
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
+    reference::all_assets_from_entries,
+};
+
+use crate::{
+    project::Project,
+    route::{Endpoint, Route},
+};
+
+/// Where the sizes of the routes of the last successful build are kept,
+/// relative to the node root. This is next to the persistent cache, so both
+/// are dropped together.
+const PREVIOUS_SIZES_FILE: &str = "cache/turbopack/route-sizes.json";
+
+/// The size of the client JavaScript and CSS of a route.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RouteSize {
+    pub raw: u64,
+    pub gzip: u64,
+}
+
+/// The sizes of the routes with a budget, by route.
+#[turbo_tasks::value(transparent)]
+pub struct RouteSizes(FxIndexMap<RcStr, RouteSize>);
+
+/// Checks the budgets of the routes of `project` against the sizes of the last
+/// successful build, which are reported with the exceeded budgets. With
+/// `persist`, the sizes of this build are stored to be compared against by the
+/// next one, which should only be done when the build succeeded.
+///
+/// Returns the operation which emits the issues.
+pub async fn route_budgets(project: Vc<Project>, persist: bool) -> Result<Vc<RouteSizes>> {
+    let file = to_sys_path(project.node_root())
+        .await?
+        .map(|node_root| node_root.join(PREVIOUS_SIZES_FILE));
+    let previous = file
+        .as_deref()
+        .and_then(read_previous_sizes)
+        .unwrap_or_default();
+    let sizes = check_route_budgets(project, Vc::cell(previous));
+    let current = sizes.strongly_consistent().await?;
+    if let (true, Some(file)) = (persist, &file) {
+        write_sizes(file, &current)?;
+    }
+    Ok(sizes)
+}
+
+/// Reports the budgets which the client JavaScript and CSS of the routes of
+/// `project` exceed, and how much the routes changed since `previous`. Budgets
+/// only apply to production builds.
+#[turbo_tasks::function]
+async fn check_route_budgets(
+    project: Vc<Project>,
+    previous: Vc<RouteSizes>,
+) -> Result<Vc<RouteSizes>> {
+    let budgets = project.next_config().route_budgets().await?;
+    if budgets.is_empty() || !project.next_mode().await?.is_production() {
+        return Ok(Vc::cell(Default::default()));
+    }
+    let budgets = budgets
+        .iter()
+        .map(|budget| {
+            let glob = Glob::parse(&budget.route)
+                .with_context(|| format!("invalid route pattern {} in budgets", budget.route))?;
+            Ok((budget, glob))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let entrypoints = project.entrypoints().await?;
+    let client_relative_path = project.client_relative_path();
+    let routes = entrypoints
+        .routes
+        .iter()
+        .filter(|(route, _)| budgets.iter().any(|(_, glob)| glob.execute(route)))
+        .map(|(route, endpoints)| async move {
+            let endpoints = match endpoints {
+                Route::Page { html_endpoint, .. } => vec![*html_endpoint],
+                Route::AppPage(pages) => pages.iter().map(|page| page.html_endpoint).collect(),
+                // API routes don't have client assets.
+                Route::PageApi { .. } | Route::AppRoute { .. } | Route::Conflict => vec![],
+            };
+            let mut size = RouteSize::default();
+            for endpoint in endpoints {
+                let endpoint_size = client_size(endpoint, client_relative_path).await?;
+                size.raw += endpoint_size.raw;
+                size.gzip += endpoint_size.gzip;
+            }
+            anyhow::Ok((route.clone(), size))
+        })
+        .try_join()
+        .await?;
+
+    let previous = previous.await?;
+    let project_path = project.project_path().to_resolved().await?;
+    for (route, size) in &routes {
+        let previous = previous.get(route);
+        for (budget, glob) in &budgets {
+            if !glob.execute(route) {
+                continue;
+            }
+            let limits = [
+                (
+                    BudgetKind::Raw,
+                    budget.raw,
+                    size.raw,
+                    previous.map(|p| p.raw),
+                ),
+                (
+                    BudgetKind::Gzip,
+                    budget.gzip,
+                    size.gzip,
+                    previous.map(|p| p.gzip),
+                ),
+            ];
+            for (kind, limit, size, previous) in limits {
+                let Some(limit) = limit else {
+                    continue;
+                };
+                if size > limit {
+                    RouteBudgetIssue {
+                        path: project_path,
+                        route: route.clone(),
+                        kind,
+                        limit,
+                        size,
+                        previous,
+                        severity: budget.severity,
+                    }
+                    .cell()
+                    .emit();
+                }
+            }
+        }
+    }
+
+    Ok(Vc::cell(routes.into_iter().collect()))
+}
+
+/// The size of the client JavaScript and CSS of an endpoint.
+#[turbo_tasks::function]
+async fn client_size(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    client_relative_path: Vc<FileSystemPath>,
+) -> Result<Vc<RouteSize>> {
+    let client_relative_path = client_relative_path.await?;
+    let mut size = RouteSize::default();
+    for asset in all_assets_from_entries(endpoint.output_assets())
+        .await?
+        .iter()
+    {
+        let path = asset.ident().path().await?;
+        if !path.is_inside_ref(&client_relative_path)
+            || !matches!(path.extension_ref(), Some("js" | "css"))
+        {
+            continue;
+        }
+        let content = asset.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            continue;
+        };
+        let bytes = file.content().to_bytes()?;
+        size.raw += bytes.len() as u64;
+        size.gzip += gzip_size(&bytes)?;
+    }
+    Ok(size.cell())
+}
+
+fn gzip_size(bytes: &[u8]) -> Result<u64> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    Ok(encoder.finish()?.len() as u64)
+}
+
+fn read_previous_sizes(file: &Path) -> Option<FxIndexMap<RcStr, RouteSize>> {
+    // A missing or broken file only means that there is nothing to compare with.
+    serde_json::from_slice(&fs::read(file).ok()?).ok()
+}
+
+fn write_sizes(file: &Path, sizes: &FxIndexMap<RcStr, RouteSize>) -> Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(file, serde_json::to_vec(sizes)?)
+        .with_context(|| format!("writing {}", file.display()))?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+enum BudgetKind {
+    Raw,
+    Gzip,
+}
+
+#[turbo_tasks::value(shared)]
+struct RouteBudgetIssue {
+    path: ResolvedVc<FileSystemPath>,
+    route: RcStr,
+    kind: BudgetKind,
+    limit: u64,
+    size: u64,
+    previous: Option<u64>,
+    severity: BudgetSeverity,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RouteBudgetIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::ROUTE_BUDGET_EXCEEDED))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        match self.severity {
+            BudgetSeverity::Warning => IssueSeverity::Warning.cell(),
+            BudgetSeverity::Error => IssueSeverity::Error.cell(),
+        }
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Other("emit".to_string()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text("Route ".into()),
+            StyledString::Code(self.route.clone()),
+            StyledString::Text(
+                match self.kind {
+                    BudgetKind::Raw => " exceeds its size budget",
+                    BudgetKind::Gzip => " exceeds its gzip size budget",
+                }
+                .into(),
+            ),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut description = format!(
+            "The client JavaScript and CSS of the route is {} bytes, which is {} bytes over the \
+             budget of {} bytes.",
+            self.size,
+            self.size - self.limit,
+            self.limit
+        );
+        match self.previous {
+            Some(previous) if previous == self.size => {
+                description.push_str(" It didn't change since the previous build.")
+            }
+            Some(previous) => description.push_str(&format!(
+                " It changed by {:+} bytes since the previous build.",
+                self.size as i64 - previous as i64
+            )),
+            None => {}
+        }
+        Vc::cell(Some(StyledString::Text(description.into()).cell()))
+    }
+}
@@ -0,0 +1,477 @@
+use std::{collections::BTreeSet, fmt, fs, path::Path};
+
+use anyhow::{Context, Result};
+use next_core::all_assets_from_entries;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    graph::{GraphTraversal, NonDeterministic},
+    FxIndexMap, TryJoinIterExt, Vc,
+};
+use turbo_tasks_fs::{to_sys_path, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    changed::get_referenced_modules,
+    compile_time_report::{package_name, PROJECT_PACKAGE_NAME},
+    module::Module,
+};
+
+use crate::{project::Project, route::Endpoint};
+
+/// Where the route bundles of the last successful build are kept, relative to
+/// the node root. This is next to the persistent cache, so both are dropped
+/// together.
+const PREVIOUS_BUILD_FILE: &str = "cache/turbopack/route-bundles.json";
+
+/// The version of the file format. Files with a different version are
+/// ignored.
+const VERSION: u32 = 1;
+
+/// The size of the output of an endpoint and the npm packages it contains.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Default)]
+pub struct RouteBundle {
+    /// The size of the JavaScript and CSS sent to the browser.
+    pub client_size: u64,
+    /// The size of all other output.
+    pub server_size: u64,
+    /// The npm packages of the modules of the endpoint, sorted by name.
+    pub packages: Vec<RcStr>,
+}
+
+/// The route bundles of all endpoints, by the names of
+/// [crate::entrypoints::Entrypoints::endpoints].
+#[turbo_tasks::value(transparent)]
+pub struct RouteBundles(FxIndexMap<RcStr, RouteBundle>);
+
+pub(crate) async fn route_bundles(
+    endpoints: Vec<(RcStr, Vc<Box<dyn Endpoint>>)>,
+    client_relative_path: Vc<FileSystemPath>,
+) -> Result<Vc<RouteBundles>> {
+    let bundles = endpoints
+        .into_iter()
+        .map(|(name, endpoint)| async move {
+            Ok((
+                name,
+                route_bundle(endpoint, client_relative_path)
+                    .await?
+                    .clone_value(),
+            ))
+        })
+        .try_join()
+        .await?;
+    Ok(Vc::cell(bundles.into_iter().collect()))
+}
+
+#[turbo_tasks::function]
+async fn route_bundle(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    client_relative_path: Vc<FileSystemPath>,
+) -> Result<Vc<RouteBundle>> {
+    let client_relative_path = &*client_relative_path.await?;
+    let mut bundle = RouteBundle::default();
+    for asset in all_assets_from_entries(endpoint.output_assets())
+        .await?
+        .iter()
+    {
+        let path = asset.ident().path().await?;
+        // Source maps aren't loaded by users, so they don't count.
+        if path.extension_ref() == Some("map") {
+            continue;
+        }
+        let AssetContent::File(file) = *asset.content().await? else {
+            continue;
+        };
+        let size = file
+            .await?
+            .as_content()
+            .map_or(0, |file| file.content().len()) as u64;
+        if path.is_inside_ref(client_relative_path)
+            && matches!(path.extension_ref(), Some("js" | "css"))
+        {
+            bundle.client_size += size;
+        } else {
+            bundle.server_size += size;
+        }
+    }
+
+    let modules = NonDeterministic::new()
+        .skip_duplicates()
+        .visit(
+            endpoint.root_modules().await?.iter().copied(),
+            get_referenced_modules,
+        )
+        .await
+        .completed()?
+        .into_inner();
+    let paths = modules
+        .into_iter()
+        .map(|module| async move { anyhow::Ok(module.ident().path().await?) })
+        .try_join()
+        .await?;
+    let packages: BTreeSet<&str> = paths
+        .iter()
+        .map(|path| package_name(&path.path))
+        .filter(|&name| name != PROJECT_PACKAGE_NAME)
+        .collect();
+    bundle.packages = packages.into_iter().map(RcStr::from).collect();
+
+    Ok(bundle.cell())
+}
+
+#[derive(Serialize, Deserialize)]
+struct PreviousBuild {
+    version: u32,
+    build_id: RcStr,
+    routes: FxIndexMap<RcStr, RouteBundle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RouteBundleStatus {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+/// How the output of an endpoint changed since the previous build.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteBundleDiff {
+    pub route: RcStr,
+    pub status: RouteBundleStatus,
+    pub client_size: u64,
+    pub previous_client_size: u64,
+    pub server_size: u64,
+    pub previous_server_size: u64,
+    pub added_packages: Vec<RcStr>,
+    pub removed_packages: Vec<RcStr>,
+}
+
+impl RouteBundleDiff {
+    pub fn client_size_delta(&self) -> i64 {
+        self.client_size as i64 - self.previous_client_size as i64
+    }
+
+    pub fn server_size_delta(&self) -> i64 {
+        self.server_size as i64 - self.previous_server_size as i64
+    }
+}
+
+/// Compares the output of every endpoint with the last successful build.
+///
+/// [fmt::Display] renders the changed routes as a table, [Serialize] the full
+/// comparison.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleDiff {
+    /// The build compared against, or `None` when there was no previous
+    /// build. Then all routes count as added.
+    pub previous_build_id: Option<RcStr>,
+    pub routes: Vec<RouteBundleDiff>,
+}
+
+impl BundleDiff {
+    pub fn between(
+        previous: Option<&FxIndexMap<RcStr, RouteBundle>>,
+        current: &FxIndexMap<RcStr, RouteBundle>,
+    ) -> Vec<RouteBundleDiff> {
+        let empty = RouteBundle::default();
+        let diff =
+            |route: &RcStr, previous: Option<&RouteBundle>, current: Option<&RouteBundle>| {
+                let status = match (previous, current) {
+                    (None, _) => RouteBundleStatus::Added,
+                    (_, None) => RouteBundleStatus::Removed,
+                    (Some(previous), Some(current)) if previous == current => {
+                        RouteBundleStatus::Unchanged
+                    }
+                    _ => RouteBundleStatus::Changed,
+                };
+                let previous = previous.unwrap_or(&empty);
+                let current = current.unwrap_or(&empty);
+                // Both package lists are sorted.
+                let packages = |a: &RouteBundle, b: &RouteBundle| -> Vec<RcStr> {
+                    a.packages
+                        .iter()
+                        .filter(|package| b.packages.binary_search(package).is_err())
+                        .cloned()
+                        .collect()
+                };
+                RouteBundleDiff {
+                    route: route.clone(),
+                    status,
+                    client_size: current.client_size,
+                    previous_client_size: previous.client_size,
+                    server_size: current.server_size,
+                    previous_server_size: previous.server_size,
+                    added_packages: packages(current, previous),
+                    removed_packages: packages(previous, current),
+                }
+            };
+
+        let mut routes: Vec<_> = current
+            .iter()
+            .map(|(route, bundle)| {
+                diff(
+                    route,
+                    previous.and_then(|previous| previous.get(route)),
+                    Some(bundle),
+                )
+            })
+            .collect();
+        if let Some(previous) = previous {
+            routes.extend(
+                previous
+                    .iter()
+                    .filter(|(route, _)| !current.contains_key(*route))
+                    .map(|(route, bundle)| diff(route, Some(bundle), None)),
+            );
+        }
+        routes
+    }
+}
+
+/// Compares the route bundles of `project` with the ones of the last
+/// successful build. With `persist`, the current build becomes the one the
+/// next build is compared against, which should only be done when the build
+/// succeeded.
+pub async fn bundle_diff(project: Vc<Project>, persist: bool) -> Result<BundleDiff> {
+    let current = project.route_bundles().strongly_consistent().await?;
+    let Some(node_root) = to_sys_path(project.node_root()).await? else {
+        return Ok(BundleDiff {
+            previous_build_id: None,
+            routes: BundleDiff::between(None, &current),
+        });
+    };
+    let file = node_root.join(PREVIOUS_BUILD_FILE);
+    let previous = read_previous_build(&file);
+    let diff = BundleDiff {
+        routes: BundleDiff::between(previous.as_ref().map(|p| &p.routes), &current),
+        previous_build_id: previous.map(|p| p.build_id),
+    };
+    if persist {
+        write_previous_build(
+            &file,
+            &PreviousBuild {
+                version: VERSION,
+                build_id: project.build_id().await?.clone_value(),
+                routes: current.clone_value(),
+            },
+        )?;
+    }
+    Ok(diff)
+}
+
+fn read_previous_build(file: &Path) -> Option<PreviousBuild> {
+    // A missing or broken file only means that there is nothing to compare with.
+    serde_json::from_slice::<PreviousBuild>(&fs::read(file).ok()?)
+        .ok()
+        .filter(|previous| previous.version == VERSION)
+}
+
+fn write_previous_build(file: &Path, previous: &PreviousBuild) -> Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(file, serde_json::to_vec(previous)?)
+        .with_context(|| format!("writing {}", file.display()))?;
+    Ok(())
+}
+
+impl fmt::Display for BundleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changed: Vec<_> = self
+            .routes
+            .iter()
+            .filter(|route| route.status != RouteBundleStatus::Unchanged)
+            .collect();
+        match &self.previous_build_id {
+            Some(build_id) => writeln!(f, "Route bundles compared to build {build_id}:")?,
+            None => writeln!(f, "Route bundles (no previous build to compare to):")?,
+        }
+        if changed.is_empty() {
+            return writeln!(f, "  No route changed.");
+        }
+
+        let rows: Vec<[String; 5]> = changed
+            .iter()
+            .map(|route| {
+                let mut packages: Vec<String> = route
+                    .added_packages
+                    .iter()
+                    .map(|package| format!("+{package}"))
+                    .collect();
+                packages.extend(
+                    route
+                        .removed_packages
+                        .iter()
+                        .map(|package| format!("-{package}")),
+                );
+                [
+                    route.route.to_string(),
+                    match route.status {
+                        RouteBundleStatus::Added => "added",
+                        RouteBundleStatus::Removed => "removed",
+                        RouteBundleStatus::Changed => "changed",
+                        RouteBundleStatus::Unchanged => "unchanged",
+                    }
+                    .to_string(),
+                    format_size_delta(route.client_size, route.client_size_delta()),
+                    format_size_delta(route.server_size, route.server_size_delta()),
+                    packages.join(" "),
+                ]
+            })
+            .collect();
+        let header = ["Route", "Status", "Client", "Server", "Packages"];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in std::iter::once(header.map(String::from)).chain(rows) {
+            write!(f, " ")?;
+            for (width, cell) in widths.iter().zip(&row) {
+                write!(f, " {cell:width$}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn format_size_delta(size: u64, delta: i64) -> String {
+    if delta == 0 {
+        format!("{size} B")
+    } else {
+        format!("{size} B ({delta:+} B)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(client_size: u64, server_size: u64, packages: &[&str]) -> RouteBundle {
+        RouteBundle {
+            client_size,
+            server_size,
+            packages: packages.iter().map(|&package| package.into()).collect(),
+        }
+    }
+
+    fn bundles(
+        bundles: impl IntoIterator<Item = (&'static str, RouteBundle)>,
+    ) -> FxIndexMap<RcStr, RouteBundle> {
+        bundles
+            .into_iter()
+            .map(|(route, bundle)| (route.into(), bundle))
+            .collect()
+    }
+
+    #[test]
+    fn without_a_previous_build_all_routes_are_added() {
+        let current = bundles([("/", bundle(10, 20, &["react"]))]);
+
+        let routes = BundleDiff::between(None, &current);
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].status, RouteBundleStatus::Added);
+        assert_eq!(routes[0].client_size_delta(), 10);
+        assert_eq!(routes[0].server_size_delta(), 20);
+        assert_eq!(routes[0].added_packages, vec![RcStr::from("react")]);
+        assert!(routes[0].removed_packages.is_empty());
+    }
+
+    #[test]
+    fn compares_sizes_and_packages_of_routes() {
+        let previous = bundles([
+            ("/", bundle(10, 20, &["lodash", "react"])),
+            ("/about", bundle(5, 5, &["react"])),
+            ("/old", bundle(1, 2, &[])),
+        ]);
+        let current = bundles([
+            ("/", bundle(15, 20, &["date-fns", "react"])),
+            ("/about", bundle(5, 5, &["react"])),
+            ("/new", bundle(3, 4, &[])),
+        ]);
+
+        let routes = BundleDiff::between(Some(&previous), &current);
+        let statuses: Vec<_> = routes
+            .iter()
+            .map(|route| (route.route.as_str(), route.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("/", RouteBundleStatus::Changed),
+                ("/about", RouteBundleStatus::Unchanged),
+                ("/new", RouteBundleStatus::Added),
+                ("/old", RouteBundleStatus::Removed),
+            ]
+        );
+
+        let index = &routes[0];
+        assert_eq!(index.client_size_delta(), 5);
+        assert_eq!(index.server_size_delta(), 0);
+        assert_eq!(index.added_packages, vec![RcStr::from("date-fns")]);
+        assert_eq!(index.removed_packages, vec![RcStr::from("lodash")]);
+
+        let old = &routes[3];
+        assert_eq!(old.client_size, 0);
+        assert_eq!(old.client_size_delta(), -1);
+        assert_eq!(old.server_size_delta(), -2);
+    }
+
+    #[test]
+    fn formats_the_changed_routes_as_a_table() {
+        let previous = bundles([
+            ("/", bundle(10, 20, &["lodash", "react"])),
+            ("/about", bundle(5, 5, &["react"])),
+        ]);
+        let current = bundles([
+            ("/", bundle(1500, 20, &["date-fns", "react"])),
+            ("/about", bundle(5, 5, &["react"])),
+            ("/blog/[slug]", bundle(3, 4, &[])),
+        ]);
+        let diff = BundleDiff {
+            previous_build_id: Some("abc".into()),
+            routes: BundleDiff::between(Some(&previous), &current),
+        };
+
+        // Unchanged routes are left out, and the columns are padded.
+        let table = diff.to_string();
+        let lines: Vec<_> = table.lines().map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Route bundles compared to build abc:",
+                "  Route        Status  Client           Server     Packages",
+                "  /            changed 1500 B (+1490 B) 20 B       +date-fns -lodash",
+                "  /blog/[slug] added   3 B (+3 B)       4 B (+4 B)",
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_a_diff_without_changes() {
+        let current = bundles([("/", bundle(10, 20, &[]))]);
+        let diff = BundleDiff {
+            previous_build_id: Some("abc".into()),
+            routes: BundleDiff::between(Some(&current), &current),
+        };
+        assert_eq!(
+            diff.to_string(),
+            "Route bundles compared to build abc:\n  No route changed.\n"
+        );
+
+        let diff = BundleDiff {
+            previous_build_id: None,
+            routes: Vec::new(),
+        };
+        assert_eq!(
+            diff.to_string(),
+            "Route bundles (no previous build to compare to):\n  No route changed.\n"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::{collections::HashMap, hash::Hash};
+
+use anyhow::Result;
+use next_core::all_assets_from_entries;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    graph::{GraphTraversal, NonDeterministic},
+    FxIndexMap, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, ValueToString, Vc,
+};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    changed::get_referenced_modules,
+    chunk::{module_id_strategies::ModuleIdStrategy, OutputChunk},
+    module::Module,
+    output::OutputAsset,
+};
+
+use crate::route::Endpoint;
+
+/// An output asset written by a build, with the endpoints it was written for.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct EmittedAsset {
+    /// Relative to the node root, where the asset is written.
+    pub path: RcStr,
+    pub size: u64,
+    pub content_hash: u64,
+    /// The names of the endpoints whose output contains the asset, see
+    /// [crate::entrypoints::Entrypoints::endpoints].
+    pub endpoints: Vec<RcStr>,
+    /// The paths of the source modules in the asset, when it is a chunk,
+    /// relative to the project directory when they are inside of it.
+    pub modules: Vec<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct EmittedAssets(Vec<EmittedAsset>);
+
+/// Describes all assets emitted for the given endpoints, in the way
+/// [next_core::emit_assets] writes them.
+pub(crate) async fn emitted_assets(
+    endpoints: Vec<(RcStr, Vc<Box<dyn Endpoint>>)>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    project_path: Vc<FileSystemPath>,
+    module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+) -> Result<Vc<EmittedAssets>> {
+    let node_root = &*node_root.await?;
+    let client_relative_path = &*client_relative_path.await?;
+
+    let endpoint_assets = endpoints
+        .iter()
+        .map(|(name, endpoint)| async move {
+            Ok((
+                name.clone(),
+                all_assets_from_entries(endpoint.output_assets())
+                    .await?
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>(),
+            ))
+        })
+        .try_join()
+        .await?;
+    let paths_by_id = module_paths_by_id(&endpoints, project_path, module_id_strategy).await?;
+
+    let assets = endpoints_by_asset(endpoint_assets)
+        .into_iter()
+        .map(|(asset, endpoints)| {
+            let paths_by_id = &paths_by_id;
+            async move {
+                let path = asset.ident().path().await?;
+                let Some(relative) = node_root
+                    .get_path_to(&path)
+                    .or_else(|| client_relative_path.get_path_to(&path))
+                else {
+                    return Ok(None);
+                };
+                let (size, content_hash) = match *asset.content().await? {
+                    AssetContent::File(file) => {
+                        let size = file
+                            .await?
+                            .as_content()
+                            .map_or(0, |file| file.content().len());
+                        (size as u64, *file.hash().await?)
+                    }
+                    AssetContent::Redirect { .. } => (0, 0),
+                };
+                let modules = module_paths(&module_ids(asset).await?, paths_by_id);
+                Ok(Some(EmittedAsset {
+                    path: relative.into(),
+                    size,
+                    content_hash,
+                    endpoints,
+                    modules,
+                }))
+            }
+        })
+        .try_flat_join()
+        .await?;
+    Ok(Vc::cell(assets))
+}
+
+/// The names of the endpoints each asset belongs to, in the order the assets
+/// are first found.
+fn endpoints_by_asset<T: Copy + Eq + Hash>(
+    endpoint_assets: Vec<(RcStr, Vec<T>)>,
+) -> FxIndexMap<T, Vec<RcStr>> {
+    let mut endpoints: FxIndexMap<T, Vec<RcStr>> = FxIndexMap::default();
+    for (name, assets) in endpoint_assets {
+        for asset in assets {
+            let names = endpoints.entry(asset).or_default();
+            if !names.contains(&name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    endpoints
+}
+
+/// Maps the ids chunks know their modules by to the paths of the modules of
+/// all endpoints.
+async fn module_paths_by_id(
+    endpoints: &[(RcStr, Vc<Box<dyn Endpoint>>)],
+    project_path: Vc<FileSystemPath>,
+    module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
+) -> Result<HashMap<RcStr, RcStr>> {
+    let mut roots = Vec::new();
+    for (_, endpoint) in endpoints {
+        roots.extend(endpoint.root_modules().await?.iter().copied());
+    }
+    let modules = NonDeterministic::new()
+        .skip_duplicates()
+        .visit(roots, get_referenced_modules)
+        .await
+        .completed()?
+        .into_inner();
+
+    let project_path = &*project_path.await?;
+    let paths = modules
+        .into_iter()
+        .map(|module| async move {
+            let ident = module.ident();
+            let id = module_id_strategy.get_module_id(ident).to_string().await?;
+            let path = match project_path.get_path_to(&*ident.path().await?) {
+                Some(relative) => relative.into(),
+                None => ident.path().to_string().await?.clone_value(),
+            };
+            anyhow::Ok((id.clone_value(), path))
+        })
+        .try_join()
+        .await?;
+    Ok(paths.into_iter().collect())
+}
+
+/// The sorted paths of the modules with the given ids. Ids of chunk items
+/// which aren't modules of the endpoints, like async loaders, are left out.
+fn module_paths(ids: &[RcStr], paths_by_id: &HashMap<RcStr, RcStr>) -> Vec<RcStr> {
+    let mut paths: Vec<_> = ids
+        .iter()
+        .filter_map(|id| paths_by_id.get(id).cloned())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+async fn module_ids(asset: ResolvedVc<Box<dyn OutputAsset>>) -> Result<Vec<RcStr>> {
+    let Some(chunk) = ResolvedVc::try_sidecast::<Box<dyn OutputChunk>>(asset).await? else {
+        return Ok(vec![]);
+    };
+    let Some(included_ids) = chunk.runtime_info().await?.included_ids else {
+        return Ok(vec![]);
+    };
+    included_ids
+        .await?
+        .iter()
+        .map(|id| async move { Ok((*id.to_string().await?).clone()) })
+        .try_join()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<RcStr> {
+        names.iter().map(|&name| name.into()).collect()
+    }
+
+    #[test]
+    fn lists_the_endpoints_of_each_asset_once() {
+        let endpoints = endpoints_by_asset(vec![
+            ("/".into(), vec![1, 2, 2]),
+            ("/about".into(), vec![3, 1]),
+        ]);
+        assert_eq!(
+            endpoints.into_iter().collect::<Vec<_>>(),
+            vec![
+                (1, names(&["/", "/about"])),
+                (2, names(&["/"])),
+                (3, names(&["/about"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_module_ids_to_sorted_paths() {
+        let paths_by_id: HashMap<RcStr, RcStr> = [
+            ("1", "app/page.tsx"),
+            ("2", "node_modules/react/index.js"),
+            ("3", "app/layout.tsx"),
+        ]
+        .into_iter()
+        .map(|(id, path)| (id.into(), path.into()))
+        .collect();
+        assert_eq!(
+            module_paths(&names(&["2", "loader", "1", "3", "1"]), &paths_by_id),
+            names(&[
+                "app/layout.tsx",
+                "app/page.tsx",
+                "node_modules/react/index.js"
+            ])
+        );
+        assert!(module_paths(&[], &paths_by_id).is_empty());
+    }
+}
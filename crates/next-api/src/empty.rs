@@ -1,6 +1,6 @@
 use anyhow::{bail, Result};
 use turbo_tasks::{Completion, Vc};
-use turbopack_core::module::Modules;
+use turbopack_core::{module::Modules, output::OutputAssets};
 
 use crate::route::{Endpoint, WrittenEndpoint};
 
@@ -36,4 +36,9 @@ impl Endpoint for EmptyEndpoint {
     fn root_modules(self: Vc<Self>) -> Vc<Modules> {
         Vc::cell(vec![])
     }
+
+    #[turbo_tasks::function]
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        OutputAssets::empty()
+    }
 }
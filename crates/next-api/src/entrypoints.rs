@@ -1,9 +1,13 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
-use turbo_tasks::{FxIndexMap, ResolvedVc};
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, Vc};
 
 use crate::{
+    paths::ServerPath,
     project::{Instrumentation, Middleware},
-    route::{Endpoint, Route},
+    route::{Endpoint, Route, WrittenEndpoint},
 };
 
 #[turbo_tasks::value(shared)]
@@ -15,3 +19,221 @@ pub struct Entrypoints {
     pub pages_app_endpoint: ResolvedVc<Box<dyn Endpoint>>,
     pub pages_error_endpoint: ResolvedVc<Box<dyn Endpoint>>,
 }
+
+impl Entrypoints {
+    /// All endpoints that are written to disk in a production build, by a name
+    /// that describes the endpoint.
+    pub fn endpoints(&self) -> Vec<(RcStr, Vc<Box<dyn Endpoint>>)> {
+        let mut endpoints = vec![];
+        for (pathname, route) in &self.routes {
+            match route {
+                Route::Page {
+                    html_endpoint,
+                    data_endpoint,
+                } => {
+                    endpoints.push((pathname.clone(), *html_endpoint));
+                    endpoints.push((format!("{pathname} (data)").into(), *data_endpoint));
+                }
+                Route::PageApi { endpoint } | Route::AppRoute { endpoint, .. } => {
+                    endpoints.push((pathname.clone(), *endpoint));
+                }
+                Route::AppPage(pages) => {
+                    for page in pages {
+                        endpoints.push((page.original_name.clone().into(), page.html_endpoint));
+                        endpoints.push((
+                            format!("{} (rsc)", page.original_name).into(),
+                            page.rsc_endpoint,
+                        ));
+                    }
+                }
+                Route::Conflict => {}
+            }
+        }
+        if let Some(middleware) = &self.middleware {
+            endpoints.push(("middleware".into(), middleware.endpoint));
+        }
+        if let Some(instrumentation) = &self.instrumentation {
+            endpoints.push(("instrumentation (node.js)".into(), instrumentation.node_js));
+            endpoints.push(("instrumentation (edge)".into(), instrumentation.edge));
+        }
+        endpoints.push(("/_document".into(), *self.pages_document_endpoint));
+        endpoints.push(("/_app".into(), *self.pages_app_endpoint));
+        endpoints.push(("/_error".into(), *self.pages_error_endpoint));
+        endpoints
+    }
+}
+
+/// The output of an endpoint written by
+/// [Project::write_all_entrypoints_to_disk][crate::project::Project::write_all_entrypoints_to_disk].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+pub struct WrittenEntrypoint {
+    pub endpoint: WrittenEndpoint,
+    /// All files written for the endpoint with their content hashes, relative
+    /// to the node root. Client assets are emitted into the node root too.
+    pub output_paths: Vec<ServerPath>,
+}
+
+/// The output of all endpoints, by the names of [Entrypoints::endpoints].
+#[turbo_tasks::value(transparent)]
+pub struct WrittenEntrypoints(FxIndexMap<RcStr, WrittenEntrypoint>);
+
+/// The endpoints whose output differs between two builds.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EntrypointChanges {
+    pub added: Vec<RcStr>,
+    pub changed: Vec<RcStr>,
+    pub removed: Vec<RcStr>,
+    /// Files written by the previous build which no endpoint writes anymore,
+    /// relative to the node root. They are left over from removed endpoints
+    /// or replaced by files with other names, e.g. content hashed chunks.
+    pub stale_paths: Vec<RcStr>,
+}
+
+impl EntrypointChanges {
+    /// Compares the output of a build with the one of the `previous` build.
+    /// Without a previous build, all endpoints count as added.
+    pub fn between(
+        previous: Option<&FxIndexMap<RcStr, WrittenEntrypoint>>,
+        current: &FxIndexMap<RcStr, WrittenEntrypoint>,
+    ) -> Self {
+        let mut changes = EntrypointChanges::default();
+        for (name, written) in current {
+            match previous.and_then(|previous| previous.get(name)) {
+                None => changes.added.push(name.clone()),
+                Some(previous) if previous != written => changes.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        if let Some(previous) = previous {
+            changes.removed = previous
+                .keys()
+                .filter(|name| !current.contains_key(*name))
+                .cloned()
+                .collect();
+
+            let current_paths: HashSet<&str> = current
+                .values()
+                .flat_map(|written| &written.output_paths)
+                .map(|path| path.path.as_str())
+                .collect();
+            let mut stale_paths = HashSet::new();
+            for path in previous.values().flat_map(|written| &written.output_paths) {
+                if !current_paths.contains(path.path.as_str()) && stale_paths.insert(&path.path) {
+                    changes.stale_paths.push(path.path.as_str().into());
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written(entry: &str, output_paths: &[(&str, u64)]) -> WrittenEntrypoint {
+        WrittenEntrypoint {
+            endpoint: WrittenEndpoint::NodeJs {
+                server_entry_path: entry.to_string(),
+                server_paths: vec![],
+                client_paths: vec![],
+            },
+            output_paths: output_paths
+                .iter()
+                .map(|&(path, content_hash)| ServerPath {
+                    path: path.to_string(),
+                    content_hash,
+                })
+                .collect(),
+        }
+    }
+
+    fn entrypoints(
+        entrypoints: impl IntoIterator<Item = (&'static str, WrittenEntrypoint)>,
+    ) -> FxIndexMap<RcStr, WrittenEntrypoint> {
+        entrypoints
+            .into_iter()
+            .map(|(name, written)| (name.into(), written))
+            .collect()
+    }
+
+    #[test]
+    fn without_a_previous_build_all_endpoints_are_added() {
+        let current = entrypoints([
+            (
+                "/",
+                written("server/app/page.js", &[("server/app/page.js", 1)]),
+            ),
+            ("/about", written("server/app/about/page.js", &[])),
+        ]);
+
+        let changes = EntrypointChanges::between(None, &current);
+        assert_eq!(changes.added, vec![RcStr::from("/"), RcStr::from("/about")]);
+        assert!(changes.changed.is_empty());
+        assert!(changes.removed.is_empty());
+        assert!(changes.stale_paths.is_empty());
+    }
+
+    #[test]
+    fn compares_the_output_of_endpoints() {
+        let previous = entrypoints([
+            (
+                "/",
+                written(
+                    "server/app/page.js",
+                    &[("server/app/page.js", 1), ("static/chunks/a.js", 2)],
+                ),
+            ),
+            (
+                "/about",
+                written(
+                    "server/app/about/page.js",
+                    &[("server/app/about/page.js", 3), ("static/chunks/a.js", 2)],
+                ),
+            ),
+            (
+                "/blog",
+                written(
+                    "server/app/blog/page.js",
+                    &[("server/app/blog/page.js", 4), ("static/chunks/b.js", 5)],
+                ),
+            ),
+        ]);
+        let current = entrypoints([
+            (
+                "/",
+                written(
+                    "server/app/page.js",
+                    &[("server/app/page.js", 6), ("static/chunks/c.js", 7)],
+                ),
+            ),
+            (
+                "/about",
+                written(
+                    "server/app/about/page.js",
+                    &[("server/app/about/page.js", 3), ("static/chunks/a.js", 2)],
+                ),
+            ),
+            ("/contact", written("server/app/contact/page.js", &[])),
+        ]);
+
+        let changes = EntrypointChanges::between(Some(&previous), &current);
+        assert_eq!(changes.added, vec![RcStr::from("/contact")]);
+        assert_eq!(changes.changed, vec![RcStr::from("/")]);
+        assert_eq!(changes.removed, vec![RcStr::from("/blog")]);
+        // `static/chunks/a.js` is still written for `/about`.
+        assert_eq!(
+            changes.stale_paths,
+            vec![
+                RcStr::from("server/app/blog/page.js"),
+                RcStr::from("static/chunks/b.js")
+            ]
+        );
+        assert!(!changes.is_empty());
+        assert!(EntrypointChanges::between(Some(&current), &current).is_empty());
+    }
+}
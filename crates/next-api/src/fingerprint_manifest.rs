@@ -0,0 +1,171 @@
+use anyhow::Result;
+use next_core::all_assets_from_entries;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, FxIndexSet, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbo_tasks_hash::HashAlgorithm;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    output::OutputAsset,
+};
+
+use crate::route::Endpoint;
+
+/// Where the manifest is written, relative to the node root.
+pub const FINGERPRINT_MANIFEST_FILE: &str = "fingerprint-manifest.json";
+
+/// The version of the manifest format, bumped on breaking changes.
+const VERSION: u32 = 2;
+
+/// An output asset as it needs to be known to upload it to a CDN.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintedAsset {
+    /// Relative to the dist dir, where the asset is written.
+    pub path: RcStr,
+    /// The hash of the content with [FingerprintManifest::hash_algorithm], hex
+    /// encoded.
+    pub content_hash: RcStr,
+    pub size: u64,
+    /// The logical paths of the assets the asset references, e.g. the fonts of
+    /// a CSS chunk. Referenced assets need to be uploaded before the asset.
+    pub references: Vec<RcStr>,
+}
+
+/// Maps the logical path of every output asset to where and with which content
+/// it is written, see [logical_path].
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintManifest {
+    pub version: u32,
+    pub hash_algorithm: HashAlgorithm,
+    pub assets: FxIndexMap<RcStr, FingerprintedAsset>,
+}
+
+impl FingerprintManifest {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Builds the manifest of all assets emitted for the given endpoints, in the
+/// way [next_core::emit_assets] writes them.
+pub(crate) async fn fingerprint_manifest(
+    endpoints: Vec<(RcStr, Vc<Box<dyn Endpoint>>)>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    hash_algorithm: HashAlgorithm,
+) -> Result<Vc<FingerprintManifest>> {
+    let node_root = &*node_root.await?;
+    let client_relative_path = &*client_relative_path.await?;
+
+    let endpoint_assets = endpoints
+        .into_iter()
+        .map(|(_, endpoint)| async move { all_assets_from_entries(endpoint.output_assets()).await })
+        .try_join()
+        .await?;
+    let all_assets: FxIndexSet<ResolvedVc<Box<dyn OutputAsset>>> = endpoint_assets
+        .iter()
+        .flat_map(|assets| assets.iter().copied())
+        .collect();
+
+    let relative_path = |asset: ResolvedVc<Box<dyn OutputAsset>>| async move {
+        let path = asset.ident().path().await?;
+        Ok(node_root
+            .get_path_to(&path)
+            .or_else(|| client_relative_path.get_path_to(&path))
+            .map(RcStr::from))
+    };
+
+    let assets = all_assets
+        .iter()
+        .map(|&asset| async move {
+            let Some(path) = relative_path(asset).await? else {
+                return Ok(None);
+            };
+            let AssetContent::File(file) = *asset.content().await? else {
+                return Ok(None);
+            };
+            let FileContent::Content(file) = &*file.await? else {
+                return Ok(None);
+            };
+            let references = asset
+                .references()
+                .await?
+                .iter()
+                .map(|&reference| async move {
+                    Ok(relative_path(reference)
+                        .await?
+                        .map(|path| logical_path(&path)))
+                })
+                .try_flat_join()
+                .await?;
+            Ok(Some((
+                logical_path(&path),
+                FingerprintedAsset {
+                    content_hash: hash_algorithm.hash(file.content()).to_hex().into(),
+                    size: file.content().len() as u64,
+                    path,
+                    references,
+                },
+            )))
+        })
+        .try_flat_join()
+        .await?;
+
+    Ok(FingerprintManifest {
+        version: VERSION,
+        hash_algorithm,
+        assets: assets.into_iter().collect(),
+    }
+    .cell())
+}
+
+/// Writes the manifest to [FINGERPRINT_MANIFEST_FILE].
+#[turbo_tasks::function]
+pub(crate) async fn write_fingerprint_manifest(
+    manifest: Vc<FingerprintManifest>,
+    node_root: Vc<FileSystemPath>,
+) -> Result<()> {
+    let json = manifest.await?.to_json()?;
+    node_root
+        .join(FINGERPRINT_MANIFEST_FILE.into())
+        .write(FileContent::Content(File::from(json)).cell())
+        .await?;
+    Ok(())
+}
+
+/// The path of an asset without the content hash of static assets, which are
+/// written to `name.<hash>.ext`. Chunks are named after the modules they
+/// contain, so their path is already logical.
+fn logical_path(path: &str) -> RcStr {
+    let (dir, file_name) = match path.rsplit_once('/') {
+        Some((dir, file_name)) => (Some(dir), file_name),
+        None => (None, path),
+    };
+    let mut segments: Vec<&str> = file_name.split('.').collect();
+    let hash_index = match segments.len() {
+        // `name.<hash>`
+        2 => 1,
+        // `name.<hash>.ext`
+        len if len > 2 => len - 2,
+        _ => return path.into(),
+    };
+    let is_hash = |segment: &str| {
+        segment.len() == 8
+            && segment
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    };
+    if !is_hash(segments[hash_index]) {
+        return path.into();
+    }
+    segments.remove(hash_index);
+    let file_name = segments.join(".");
+    match dir {
+        Some(dir) => format!("{dir}/{file_name}").into(),
+        None => file_name.into(),
+    }
+}
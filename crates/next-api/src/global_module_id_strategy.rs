@@ -1,5 +1,6 @@
 use anyhow::Result;
 use turbo_tasks::Vc;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::chunk::module_id_strategies::{GlobalModuleIdStrategy, ModuleIdStrategy};
 use turbopack_ecmascript::global_module_id_strategy::{
     children_modules_idents, merge_preprocessed_module_ids, PreprocessedChildrenIdents,
@@ -20,25 +21,39 @@ pub struct GlobalModuleIdStrategyBuilder;
 impl GlobalModuleIdStrategyBuilder {
     #[turbo_tasks::function]
     pub async fn build(project: Vc<Project>) -> Result<Vc<Box<dyn ModuleIdStrategy>>> {
+        let hash_algorithm = project.next_config().hash_algorithm();
         let mut preprocessed_module_ids = Vec::new();
 
-        preprocessed_module_ids.push(children_modules_idents(project.client_main_modules()));
+        preprocessed_module_ids.push(children_modules_idents(
+            project.client_main_modules(),
+            hash_algorithm,
+        ));
 
         let entrypoints = project.entrypoints().await?;
 
-        preprocessed_module_ids.push(preprocess_module_ids(*entrypoints.pages_error_endpoint));
-        preprocessed_module_ids.push(preprocess_module_ids(*entrypoints.pages_app_endpoint));
-        preprocessed_module_ids.push(preprocess_module_ids(*entrypoints.pages_document_endpoint));
+        preprocessed_module_ids.push(preprocess_module_ids(
+            *entrypoints.pages_error_endpoint,
+            hash_algorithm,
+        ));
+        preprocessed_module_ids.push(preprocess_module_ids(
+            *entrypoints.pages_app_endpoint,
+            hash_algorithm,
+        ));
+        preprocessed_module_ids.push(preprocess_module_ids(
+            *entrypoints.pages_document_endpoint,
+            hash_algorithm,
+        ));
 
         if let Some(middleware) = &entrypoints.middleware {
-            preprocessed_module_ids.push(preprocess_module_ids(middleware.endpoint));
+            preprocessed_module_ids
+                .push(preprocess_module_ids(middleware.endpoint, hash_algorithm));
         }
 
         if let Some(instrumentation) = &entrypoints.instrumentation {
             let node_js = instrumentation.node_js;
             let edge = instrumentation.edge;
-            preprocessed_module_ids.push(preprocess_module_ids(node_js));
-            preprocessed_module_ids.push(preprocess_module_ids(edge));
+            preprocessed_module_ids.push(preprocess_module_ids(node_js, hash_algorithm));
+            preprocessed_module_ids.push(preprocess_module_ids(edge, hash_algorithm));
         }
 
         for (_, route) in entrypoints.routes.iter() {
@@ -47,25 +62,31 @@ impl GlobalModuleIdStrategyBuilder {
                     html_endpoint,
                     data_endpoint,
                 } => {
-                    preprocessed_module_ids.push(preprocess_module_ids(*html_endpoint));
-                    preprocessed_module_ids.push(preprocess_module_ids(*data_endpoint));
+                    preprocessed_module_ids
+                        .push(preprocess_module_ids(*html_endpoint, hash_algorithm));
+                    preprocessed_module_ids
+                        .push(preprocess_module_ids(*data_endpoint, hash_algorithm));
                 }
                 Route::PageApi { endpoint } => {
-                    preprocessed_module_ids.push(preprocess_module_ids(*endpoint));
+                    preprocessed_module_ids.push(preprocess_module_ids(*endpoint, hash_algorithm));
                 }
                 Route::AppPage(page_routes) => {
                     for page_route in page_routes {
-                        preprocessed_module_ids
-                            .push(preprocess_module_ids(page_route.html_endpoint));
-                        preprocessed_module_ids
-                            .push(preprocess_module_ids(page_route.rsc_endpoint));
+                        preprocessed_module_ids.push(preprocess_module_ids(
+                            page_route.html_endpoint,
+                            hash_algorithm,
+                        ));
+                        preprocessed_module_ids.push(preprocess_module_ids(
+                            page_route.rsc_endpoint,
+                            hash_algorithm,
+                        ));
                     }
                 }
                 Route::AppRoute {
                     original_name: _,
                     endpoint,
                 } => {
-                    preprocessed_module_ids.push(preprocess_module_ids(*endpoint));
+                    preprocessed_module_ids.push(preprocess_module_ids(*endpoint, hash_algorithm));
                 }
                 Route::Conflict => {
                     tracing::info!("WARN: conflict");
@@ -73,10 +94,12 @@ impl GlobalModuleIdStrategyBuilder {
             }
         }
 
-        let module_id_map = merge_preprocessed_module_ids(preprocessed_module_ids).await?;
+        let hash_algorithm = *hash_algorithm.await?;
+        let module_id_map =
+            merge_preprocessed_module_ids(preprocessed_module_ids, hash_algorithm).await?;
 
         Ok(Vc::upcast(
-            GlobalModuleIdStrategy::new(module_id_map).await?,
+            GlobalModuleIdStrategy::new(module_id_map, hash_algorithm).await?,
         ))
     }
 }
@@ -84,7 +107,10 @@ impl GlobalModuleIdStrategyBuilder {
 // NOTE(LichuAcu) We can't move this function to `turbopack-core` because we need access to
 // `Endpoint`, which is not available there.
 #[turbo_tasks::function]
-fn preprocess_module_ids(endpoint: Vc<Box<dyn Endpoint>>) -> Vc<PreprocessedChildrenIdents> {
+fn preprocess_module_ids(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    hash_algorithm: Vc<HashAlgorithm>,
+) -> Vc<PreprocessedChildrenIdents> {
     let root_modules = endpoint.root_modules();
-    children_modules_idents(root_modules)
+    children_modules_idents(root_modules, hash_algorithm)
 }
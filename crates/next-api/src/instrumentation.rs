@@ -176,63 +176,6 @@ impl InstrumentationEndpoint {
             .await?;
         Ok(*chunk)
     }
-
-    #[turbo_tasks::function]
-    async fn output_assets(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
-        let this = self.await?;
-
-        if this.is_edge {
-            let edge_files = self.edge_files();
-            let mut output_assets = edge_files.await?.clone_value();
-
-            let node_root = this.project.node_root();
-            let node_root_value = node_root.await?;
-
-            let file_paths_from_root =
-                get_js_paths_from_root(&node_root_value, &output_assets).await?;
-
-            let all_output_assets = all_assets_from_entries(edge_files).await?;
-
-            let wasm_paths_from_root =
-                get_wasm_paths_from_root(&node_root_value, &all_output_assets).await?;
-
-            let instrumentation_definition = InstrumentationDefinition {
-                files: file_paths_from_root,
-                wasm: wasm_paths_to_bindings(wasm_paths_from_root),
-                name: "instrumentation".into(),
-                ..Default::default()
-            };
-            let middleware_manifest_v2 = MiddlewaresManifestV2 {
-                instrumentation: Some(instrumentation_definition),
-                ..Default::default()
-            };
-            let middleware_manifest_v2 = VirtualOutputAsset::new(
-                node_root.join("server/instrumentation/middleware-manifest.json".into()),
-                AssetContent::file(
-                    FileContent::Content(File::from(serde_json::to_string_pretty(
-                        &middleware_manifest_v2,
-                    )?))
-                    .cell(),
-                ),
-            )
-            .to_resolved()
-            .await?;
-            output_assets.push(ResolvedVc::upcast(middleware_manifest_v2));
-
-            Ok(Vc::cell(output_assets))
-        } else {
-            let chunk = self.node_chunk().to_resolved().await?;
-            let mut output_assets = vec![chunk];
-            if this.project.next_mode().await?.is_production() {
-                output_assets.push(ResolvedVc::upcast(
-                    NftJsonAsset::new(*this.project, *chunk, vec![])
-                        .to_resolved()
-                        .await?,
-                ));
-            }
-            Ok(Vc::cell(output_assets))
-        }
-    }
 }
 
 #[turbo_tasks::value]
@@ -293,4 +236,61 @@ impl Endpoint for InstrumentationEndpoint {
             core_modules.edge_entry_module,
         ]))
     }
+
+    #[turbo_tasks::function]
+    async fn output_assets(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
+        let this = self.await?;
+
+        if this.is_edge {
+            let edge_files = self.edge_files();
+            let mut output_assets = edge_files.await?.clone_value();
+
+            let node_root = this.project.node_root();
+            let node_root_value = node_root.await?;
+
+            let file_paths_from_root =
+                get_js_paths_from_root(&node_root_value, &output_assets).await?;
+
+            let all_output_assets = all_assets_from_entries(edge_files).await?;
+
+            let wasm_paths_from_root =
+                get_wasm_paths_from_root(&node_root_value, &all_output_assets).await?;
+
+            let instrumentation_definition = InstrumentationDefinition {
+                files: file_paths_from_root,
+                wasm: wasm_paths_to_bindings(wasm_paths_from_root),
+                name: "instrumentation".into(),
+                ..Default::default()
+            };
+            let middleware_manifest_v2 = MiddlewaresManifestV2 {
+                instrumentation: Some(instrumentation_definition),
+                ..Default::default()
+            };
+            let middleware_manifest_v2 = VirtualOutputAsset::new(
+                node_root.join("server/instrumentation/middleware-manifest.json".into()),
+                AssetContent::file(
+                    FileContent::Content(File::from(serde_json::to_string_pretty(
+                        &middleware_manifest_v2,
+                    )?))
+                    .cell(),
+                ),
+            )
+            .to_resolved()
+            .await?;
+            output_assets.push(ResolvedVc::upcast(middleware_manifest_v2));
+
+            Ok(Vc::cell(output_assets))
+        } else {
+            let chunk = self.node_chunk().to_resolved().await?;
+            let mut output_assets = vec![chunk];
+            if this.project.next_mode().await?.is_production() {
+                output_assets.push(ResolvedVc::upcast(
+                    NftJsonAsset::new(*this.project, *chunk, vec![])
+                        .to_resolved()
+                        .await?,
+                ));
+            }
+            Ok(Vc::cell(output_assets))
+        }
+    }
 }
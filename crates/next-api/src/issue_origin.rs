@@ -0,0 +1,54 @@
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::Vc;
+use turbopack_core::issue::import_chain::find_import_chain;
+
+use crate::route::Endpoint;
+
+/// Where in the module graph of an endpoint an issue originated. Shown in the
+/// error overlay to help diagnosing errors that only happen in some
+/// environments, e.g. when a module works on the client but fails on the
+/// server.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default)]
+pub struct IssueOrigin {
+    /// Idents of the modules from an entry of the endpoint to the module the
+    /// issue was reported for.
+    pub import_chain: Vec<RcStr>,
+    /// The environment the module was compiled for, one of `server`, `client`
+    /// or `edge`.
+    pub environment: Option<RcStr>,
+}
+
+/// Maps a module layer to the environment it is compiled for.
+fn layer_environment(layer: &str) -> &'static str {
+    if layer == "middleware" || layer.contains("edge") {
+        "edge"
+    } else if layer == "client" || layer == "app-client" {
+        "client"
+    } else {
+        "server"
+    }
+}
+
+/// See [find_import_chain] for `file_path` and `module_ident`.
+#[turbo_tasks::function]
+pub async fn endpoint_issue_origin(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    file_path: RcStr,
+    module_ident: Option<RcStr>,
+) -> Result<Vc<IssueOrigin>> {
+    let chain = find_import_chain(endpoint.root_modules(), file_path, module_ident).await?;
+    let Some(chain) = &*chain else {
+        return Ok(IssueOrigin::default().cell());
+    };
+    let chain = chain.await?;
+    Ok(IssueOrigin {
+        import_chain: chain.modules.clone(),
+        environment: chain
+            .layer
+            .as_deref()
+            .map(|layer| layer_environment(layer).into()),
+    }
+    .cell())
+}
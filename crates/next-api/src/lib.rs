@@ -4,20 +4,28 @@
 #![feature(impl_trait_in_assoc_type)]
 
 mod app;
+pub mod budgets;
+pub mod bundle_diff;
 mod dynamic_imports;
+pub mod emitted_assets;
 mod empty;
 pub mod entrypoints;
+pub mod fingerprint_manifest;
 mod font;
 pub mod global_module_id_strategy;
 mod instrumentation;
+pub mod issue_origin;
+pub mod library;
 mod loadable_manifest;
 mod middleware;
 mod nft_json;
 mod pages;
 pub mod paths;
 pub mod project;
+mod resolve_trace;
 pub mod route;
 mod server_actions;
+pub mod shadow_rebuild;
 mod versioned_content_map;
 mod webpack_stats;
 
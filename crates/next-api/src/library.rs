@@ -0,0 +1,242 @@
+//! Bundling packages instead of Next.js apps. Each entry is built into a
+//! Node.js entry chunk group and a facade in the requested [LibraryFormat],
+//! see [turbopack::ecmascript::library].
+
+use std::{
+    env::current_dir,
+    path::{PathBuf, MAIN_SEPARATOR},
+};
+
+use anyhow::{bail, Context, Result};
+use next_core::mode::NextMode;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    apply_effects, trace::TraceRawVcs, FxIndexSet, ResolvedVc, TaskInput, TransientInstance,
+    TryJoinIterExt, Value, Vc,
+};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
+use turbopack::{
+    ecmascript::{
+        library::{library_entry_assets, peer_dependency_externals, LibraryFormat},
+        TreeShakingMode,
+    },
+    module_options::{EcmascriptOptionsContext, ModuleOptionsContext, TypescriptTransformOptions},
+    resolve_options_context::ResolveOptionsContext,
+    ModuleAssetContext,
+};
+use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
+use turbopack_core::{
+    asset::Asset,
+    chunk::{ChunkingContext, EvaluatableAsset, MinifyType},
+    compile_time_defines,
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+    issue::{handle_issues, IssueReporter, IssueSeverity},
+    output::{OutputAsset, OutputAssets},
+    reference::all_assets_from_entries,
+    reference_type::{EntryReferenceSubType, ReferenceType},
+    resolve::{
+        origin::{PlainResolveOrigin, ResolveOriginExt},
+        parse::Request,
+    },
+};
+use turbopack_nodejs::NodeJsChunkingContext;
+
+/// What to bundle, see [library_build].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs, TaskInput)]
+pub struct LibraryBuildOptions {
+    /// The absolute path of the package.
+    pub project_dir: RcStr,
+    /// The absolute path of the workspace root, which contains the
+    /// `node_modules` of the package.
+    pub root_dir: RcStr,
+    /// The entries, relative to the package.
+    pub entries: Vec<RcStr>,
+    pub format: LibraryFormat,
+    /// The name of the library in the error of the [LibraryFormat::Umd] facade
+    /// without a module loader. Defaults to the file name of the entry.
+    pub name: Option<RcStr>,
+    pub minify: bool,
+}
+
+/// The path of the package in the file system of the workspace root.
+#[turbo_tasks::function]
+async fn project_path(options: LibraryBuildOptions) -> Result<Vc<FileSystemPath>> {
+    let relative = options
+        .project_dir
+        .strip_prefix(&*options.root_dir)
+        .with_context(|| {
+            format!(
+                "the package {} is outside of the workspace root {}",
+                options.project_dir, options.root_dir
+            )
+        })?;
+    let relative = relative.strip_prefix(MAIN_SEPARATOR).unwrap_or(relative);
+    let root = DiskFileSystem::new("project".into(), options.root_dir.clone(), vec![]).root();
+    Ok(root.join(relative.replace(MAIN_SEPARATOR, "/").into()))
+}
+
+#[turbo_tasks::function]
+async fn library_asset_context(
+    project_path: Vc<FileSystemPath>,
+    environment: ResolvedVc<Environment>,
+    format: LibraryFormat,
+) -> Result<Vc<Box<dyn AssetContext>>> {
+    let resolve_options_context = ResolveOptionsContext {
+        enable_node_modules: Some(project_path.root().to_resolved().await?),
+        enable_node_externals: true,
+        enable_typescript: true,
+        custom_conditions: vec!["production".into(), "node".into()],
+        module: true,
+        import_map: Some(
+            peer_dependency_externals(project_path.join("package.json".into()), format)
+                .to_resolved()
+                .await?,
+        ),
+        ..Default::default()
+    };
+    let module_options_context = ModuleOptionsContext {
+        ecmascript: EcmascriptOptionsContext {
+            enable_typescript_transform: Some(
+                TypescriptTransformOptions::default().resolved_cell(),
+            ),
+            ..Default::default()
+        },
+        preset_env_versions: Some(environment),
+        tree_shaking_mode: Some(TreeShakingMode::ReexportsOnly),
+        ..Default::default()
+    };
+    let compile_time_info = CompileTimeInfo::builder(environment)
+        .defines(
+            compile_time_defines!(
+                process.turbopack = true,
+                process.env.TURBOPACK = true,
+                process.env.NODE_ENV = "production"
+            )
+            .resolved_cell(),
+        )
+        .cell()
+        .await?;
+    Ok(Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        compile_time_info,
+        module_options_context.cell(),
+        resolve_options_context.cell(),
+        Vc::cell("library".into()),
+    )))
+}
+
+/// Bundles the entries of a package into its `dist` directory. Peer
+/// dependencies are kept external, as they are provided by the consumer of
+/// the package. Returns all assets to emit.
+#[turbo_tasks::function]
+pub async fn library_build(options: LibraryBuildOptions) -> Result<Vc<OutputAssets>> {
+    let project_path = project_path(options.clone()).to_resolved().await?;
+    let output_root = project_path.join("dist".into()).to_resolved().await?;
+    let environment = Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+        NodeJsEnvironment::default().resolved_cell(),
+    )))
+    .to_resolved()
+    .await?;
+    let chunking_context: Vc<Box<dyn ChunkingContext>> = Vc::upcast(
+        NodeJsChunkingContext::builder(
+            project_path,
+            output_root,
+            output_root,
+            output_root,
+            output_root,
+            environment,
+            NextMode::Build.runtime_type(),
+        )
+        .minify_type(if options.minify {
+            MinifyType::Minify
+        } else {
+            MinifyType::NoMinify
+        })
+        .build()?,
+    );
+    let asset_context = library_asset_context(*project_path, *environment, options.format);
+    let origin = PlainResolveOrigin::new(asset_context, project_path.join("_".into()));
+    let format = options.format;
+    let name = &options.name;
+
+    let entry_assets = options
+        .entries
+        .iter()
+        .map(|entry| async move {
+            let ty = Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined));
+            let request = Request::relative(
+                Value::new(entry.clone().into()),
+                Default::default(),
+                Default::default(),
+                false,
+            );
+            let module = origin
+                .resolve_asset(request, origin.resolve_options(ty.clone()), ty)
+                .first_module()
+                .await?
+                .with_context(|| format!("unable to resolve the library entry {entry}"))?;
+            let Some(module) =
+                ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(module).await?
+            else {
+                bail!("the library entry {entry} is not an ecmascript module");
+            };
+            Ok(library_entry_assets(
+                chunking_context,
+                *module,
+                *output_root,
+                format,
+                name.clone(),
+            ))
+        })
+        .try_join()
+        .await?;
+
+    // Entries share the chunks of their common dependencies.
+    let mut assets = FxIndexSet::default();
+    for entry_assets in entry_assets {
+        assets.extend(all_assets_from_entries(entry_assets).await?.iter().copied());
+    }
+    Ok(Vc::cell(assets.into_iter().collect()))
+}
+
+#[turbo_tasks::function]
+async fn write_library_build(options: LibraryBuildOptions) -> Result<()> {
+    library_build(options)
+        .await?
+        .iter()
+        .map(|asset| asset.content().write(asset.ident().path()))
+        .try_join()
+        .await?;
+    Ok(())
+}
+
+/// Runs [library_build] and writes the assets to disk. Issues are printed, and
+/// nothing is written when there are errors. Needs to run in a turbo tasks
+/// context.
+pub async fn emit_library_build(options: LibraryBuildOptions) -> Result<()> {
+    let project_dir = PathBuf::from(&*options.project_dir);
+    let result = write_library_build(options);
+    result.strongly_consistent().await?;
+
+    let issue_reporter: Vc<Box<dyn IssueReporter>> =
+        Vc::upcast(ConsoleUi::new(TransientInstance::new(LogOptions {
+            project_dir,
+            current_dir: current_dir()?,
+            show_all: false,
+            log_detail: false,
+            log_level: IssueSeverity::Warning,
+        })));
+    handle_issues(
+        result,
+        issue_reporter,
+        IssueSeverity::Error.into(),
+        None,
+        None,
+    )
+    .await?;
+
+    apply_effects(result).await
+}
@@ -120,6 +120,75 @@ impl MiddlewareEndpoint {
         Ok(edge_files)
     }
 
+    #[turbo_tasks::function]
+    fn userland_module(&self) -> Vc<Box<dyn Module>> {
+        self.asset_context
+            .process(
+                *self.source,
+                Value::new(ReferenceType::Entry(EntryReferenceSubType::Middleware)),
+            )
+            .module()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Endpoint for MiddlewareEndpoint {
+    #[turbo_tasks::function]
+    async fn write_to_disk(self: Vc<Self>) -> Result<Vc<WrittenEndpoint>> {
+        let span = tracing::info_span!("middleware endpoint");
+        async move {
+            let this = self.await?;
+            let output_assets = self.output_assets();
+            let _ = output_assets.resolve().await?;
+            let _ = this
+                .project
+                .emit_all_output_assets(Vc::cell(output_assets))
+                .resolve()
+                .await?;
+
+            let (server_paths, client_paths) = if this.project.next_mode().await?.is_development() {
+                let node_root = this.project.node_root();
+                let server_paths = all_server_paths(output_assets, node_root)
+                    .await?
+                    .clone_value();
+
+                // Middleware could in theory have a client path (e.g. `new URL`).
+                let client_relative_root = this.project.client_relative_path();
+                let client_paths = all_paths_in_root(output_assets, client_relative_root)
+                    .into_future()
+                    .instrument(tracing::info_span!("client_paths"))
+                    .await?
+                    .clone_value();
+                (server_paths, client_paths)
+            } else {
+                (vec![], vec![])
+            };
+
+            Ok(WrittenEndpoint::Edge {
+                server_paths,
+                client_paths,
+            }
+            .cell())
+        }
+        .instrument(span)
+        .await
+    }
+
+    #[turbo_tasks::function]
+    async fn server_changed(self: Vc<Self>) -> Result<Vc<Completion>> {
+        Ok(self.await?.project.server_changed(self.output_assets()))
+    }
+
+    #[turbo_tasks::function]
+    fn client_changed(self: Vc<Self>) -> Vc<Completion> {
+        Completion::immutable()
+    }
+
+    #[turbo_tasks::function]
+    async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
+        Ok(Vc::cell(vec![self.userland_module().to_resolved().await?]))
+    }
+
     #[turbo_tasks::function]
     async fn output_assets(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
         let this = self.await?;
@@ -254,73 +323,4 @@ impl MiddlewareEndpoint {
 
         Ok(Vc::cell(output_assets))
     }
-
-    #[turbo_tasks::function]
-    fn userland_module(&self) -> Vc<Box<dyn Module>> {
-        self.asset_context
-            .process(
-                *self.source,
-                Value::new(ReferenceType::Entry(EntryReferenceSubType::Middleware)),
-            )
-            .module()
-    }
-}
-
-#[turbo_tasks::value_impl]
-impl Endpoint for MiddlewareEndpoint {
-    #[turbo_tasks::function]
-    async fn write_to_disk(self: Vc<Self>) -> Result<Vc<WrittenEndpoint>> {
-        let span = tracing::info_span!("middleware endpoint");
-        async move {
-            let this = self.await?;
-            let output_assets = self.output_assets();
-            let _ = output_assets.resolve().await?;
-            let _ = this
-                .project
-                .emit_all_output_assets(Vc::cell(output_assets))
-                .resolve()
-                .await?;
-
-            let (server_paths, client_paths) = if this.project.next_mode().await?.is_development() {
-                let node_root = this.project.node_root();
-                let server_paths = all_server_paths(output_assets, node_root)
-                    .await?
-                    .clone_value();
-
-                // Middleware could in theory have a client path (e.g. `new URL`).
-                let client_relative_root = this.project.client_relative_path();
-                let client_paths = all_paths_in_root(output_assets, client_relative_root)
-                    .into_future()
-                    .instrument(tracing::info_span!("client_paths"))
-                    .await?
-                    .clone_value();
-                (server_paths, client_paths)
-            } else {
-                (vec![], vec![])
-            };
-
-            Ok(WrittenEndpoint::Edge {
-                server_paths,
-                client_paths,
-            }
-            .cell())
-        }
-        .instrument(span)
-        .await
-    }
-
-    #[turbo_tasks::function]
-    async fn server_changed(self: Vc<Self>) -> Result<Vc<Completion>> {
-        Ok(self.await?.project.server_changed(self.output_assets()))
-    }
-
-    #[turbo_tasks::function]
-    fn client_changed(self: Vc<Self>) -> Vc<Completion> {
-        Completion::immutable()
-    }
-
-    #[turbo_tasks::function]
-    async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
-        Ok(Vc::cell(vec![self.userland_module().to_resolved().await?]))
-    }
 }
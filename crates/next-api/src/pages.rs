@@ -1040,11 +1040,6 @@ impl PageEndpoint {
         ))
     }
 
-    #[turbo_tasks::function]
-    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
-        self.output().output_assets()
-    }
-
     #[turbo_tasks::function]
     async fn output(self: Vc<Self>) -> Result<Vc<PageEndpointOutput>> {
         let this = self.await?;
@@ -1363,6 +1358,11 @@ impl Endpoint for PageEndpoint {
 
         Ok(Vc::cell(modules))
     }
+
+    #[turbo_tasks::function]
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().output_assets()
+    }
 }
 
 #[turbo_tasks::value]
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use next_core::{all_assets_from_entries, next_manifests::AssetBinding};
 use serde::{Deserialize, Serialize};
 use tracing::Instrument;
@@ -9,6 +9,7 @@ use turbopack_core::{
     asset::{Asset, AssetContent},
     output::{OutputAsset, OutputAssets},
 };
+use url::Url;
 
 /// A reference to a server file with content hash for change detection
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs)]
@@ -184,3 +185,91 @@ pub(crate) fn paths_to_bindings(paths: Vec<RcStr>) -> Vec<AssetBinding> {
         })
         .collect()
 }
+
+/// Finds the chunk a stack frame file belongs to, which is either a path, a
+/// `file://` URL or the `http(s)://` URL of a client chunk served from
+/// `/_next/`. Returns the path of the chunk relative to the dist dir at
+/// `dist_path` and the id of the module in the chunk from the `id` query
+/// parameter, or `None` when the file isn't inside of the dist dir.
+pub fn source_map_chunk_path(
+    file_path: &str,
+    dist_path: &str,
+) -> Result<Option<(String, Option<RcStr>)>> {
+    let (file, module) = match Url::parse(file_path) {
+        Ok(url) => match url.scheme() {
+            "file" => {
+                let path = urlencoding::decode(url.path())?.to_string();
+                let module = url.query_pairs().find(|(k, _)| k == "id");
+                (
+                    path,
+                    match module {
+                        Some(module) => Some(urlencoding::decode(&module.1)?.into_owned().into()),
+                        None => None,
+                    },
+                )
+            }
+            // Client chunks, which are served from `/_next/` in the dist dir.
+            "http" | "https" => {
+                let path = urlencoding::decode(url.path())?;
+                let Some(chunk_base) = path.strip_prefix("/_next/") else {
+                    return Ok(None);
+                };
+                (format!("{dist_path}/{chunk_base}"), None)
+            }
+            _ => bail!("Unknown url scheme"),
+        },
+        Err(_) => (file_path.to_string(), None),
+    };
+
+    Ok(file
+        .strip_prefix(&format!("{dist_path}/"))
+        .map(|chunk_base| (chunk_base.to_string(), module)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIST: &str = "/app/.next";
+
+    fn chunk(file_path: &str) -> Option<(String, Option<RcStr>)> {
+        source_map_chunk_path(file_path, DIST).unwrap()
+    }
+
+    #[test]
+    fn finds_chunks_of_paths_and_file_urls() {
+        assert_eq!(
+            chunk("/app/.next/server/chunks/ssr/a.js"),
+            Some(("server/chunks/ssr/a.js".into(), None))
+        );
+        assert_eq!(
+            chunk("file:///app/.next/server/chunks/ssr/a%20b.js?id=%5Bproject%5D%2Fpage.tsx"),
+            Some((
+                "server/chunks/ssr/a b.js".into(),
+                Some("[project]/page.tsx".into())
+            ))
+        );
+        assert_eq!(chunk("/app/src/page.tsx"), None);
+        assert_eq!(chunk("file:///app/.next-other/a.js"), None);
+    }
+
+    #[test]
+    fn finds_client_chunks_of_http_urls() {
+        assert_eq!(
+            chunk("http://localhost:3000/_next/static/chunks/a.js"),
+            Some(("static/chunks/a.js".into(), None))
+        );
+        assert_eq!(
+            chunk("https://example.com/_next/static/chunks/%5Broot%5D%20a.js?v=1#hash"),
+            Some(("static/chunks/[root] a.js".into(), None))
+        );
+        // Only chunks are served from `/_next/`.
+        assert_eq!(chunk("http://localhost:3000/static/chunks/a.js"), None);
+        assert_eq!(chunk("http://localhost:3000/"), None);
+    }
+
+    #[test]
+    fn fails_for_unknown_schemes() {
+        assert!(source_map_chunk_path("ftp://localhost/_next/a.js", DIST).is_err());
+    }
+}
@@ -1,17 +1,23 @@
-use std::{path::MAIN_SEPARATOR, time::Duration};
+use std::{
+    path::{Path, MAIN_SEPARATOR},
+    time::Duration,
+};
 
 use anyhow::{bail, Context, Result};
 use indexmap::map::Entry;
 use next_core::{
     all_assets_from_entries,
     app_structure::find_app_dir,
-    emit_assets, get_edge_chunking_context, get_edge_chunking_context_with_client_assets,
-    get_edge_compile_time_info, get_edge_resolve_options_context,
+    emit_assets, emit_symbol_maps, get_edge_chunking_context,
+    get_edge_chunking_context_with_client_assets, get_edge_compile_time_info,
+    get_edge_resolve_options_context,
     instrumentation::instrumentation_files,
     middleware::middleware_files,
     mode::NextMode,
     next_client::{get_client_chunking_context, get_client_compile_time_info},
-    next_config::{JsConfig, ModuleIdStrategy as ModuleIdStrategyConfig, NextConfig},
+    next_config::{
+        HermeticConfig, JsConfig, ModuleIdStrategy as ModuleIdStrategyConfig, NextConfig,
+    },
     next_server::{
         get_server_chunking_context, get_server_chunking_context_with_client_assets,
         get_server_compile_time_info, get_server_module_options_context,
@@ -29,10 +35,10 @@ use turbo_tasks::{
     graph::{AdjacencyMap, GraphTraversal},
     trace::TraceRawVcs,
     Completion, Completions, FxIndexMap, IntoTraitRef, ReadRef, ResolvedVc, State, TaskInput,
-    TransientInstance, TryFlatJoinIterExt, Value, Vc,
+    TransientInstance, TryFlatJoinIterExt, TryJoinIterExt, Value, Vc,
 };
-use turbo_tasks_env::{EnvMap, ProcessEnv};
-use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_env::{EnvMap, HermeticProcessEnv, ProcessEnv};
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath, VirtualFileSystem};
 use turbopack::{
     evaluate_context::node_build_environment, transition::TransitionOptions, ModuleAssetContext,
 };
@@ -46,10 +52,13 @@ use turbopack_core::{
     context::AssetContext,
     diagnostics::DiagnosticExt,
     file_source::FileSource,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        hermetic::HermeticViolationIssueReporter, Issue, IssueCode, IssueExt, IssueSeverity,
+        IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+    },
     module::Modules,
     output::{OutputAsset, OutputAssets},
-    resolve::{find_context_file, FindContextFileResult},
+    resolve::{find_context_file, trace::ResolveTrace, FindContextFileResult},
     source_map::OptionSourceMap,
     version::{
         NotFoundVersion, OptionVersionedContent, Update, Version, VersionState, VersionedContent,
@@ -62,16 +71,46 @@ use turbopack_nodejs::NodeJsChunkingContext;
 use crate::{
     app::{AppProject, OptionAppProject, ECMASCRIPT_CLIENT_TRANSITION_NAME},
     build,
+    bundle_diff::{route_bundles, RouteBundles},
+    emitted_assets::{emitted_assets, EmittedAssets},
     empty::EmptyEndpoint,
-    entrypoints::Entrypoints,
+    entrypoints::{Entrypoints, WrittenEntrypoint, WrittenEntrypoints},
+    fingerprint_manifest::{fingerprint_manifest, write_fingerprint_manifest, FingerprintManifest},
     global_module_id_strategy::GlobalModuleIdStrategyBuilder,
     instrumentation::InstrumentationEndpoint,
     middleware::MiddlewareEndpoint,
     pages::PagesProject,
+    paths::all_server_paths,
+    resolve_trace::{project_trace_resolve, resolve_trace_environments},
     route::{Endpoint, Route},
     versioned_content_map::{OutputAssetsOperation, VersionedContentMap},
 };
 
+/// Env variables which hermetic builds can read without declaring them, as
+/// Next.js and Node.js read them.
+const HERMETIC_INTERNAL_ENV_VARS: &[&str] = &[
+    "NODE_*",
+    "UV_*",
+    "SSL_*",
+    "FORCE_COLOR",
+    "NO_COLOR",
+    "OPENSSL_CONF",
+    "TZ",
+    "PATH",
+    "NEXT_FONT_GOOGLE_MOCKED_RESPONSES",
+    "TURBOPACK_TEST_ONLY_MOCK_SERVER",
+];
+
+/// The absolute paths a hermetic build can read: the project directory and the
+/// declared roots, which are relative to it.
+fn hermetic_roots(project_path: &str, hermetic: &HermeticConfig) -> Vec<RcStr> {
+    let project_path = Path::new(project_path);
+    std::iter::once(project_path.to_path_buf())
+        .chain(hermetic.roots.iter().map(|root| project_path.join(&**root)))
+        .map(|root| root.to_string_lossy().into())
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, TaskInput, PartialEq, Eq, Hash, TraceRawVcs)]
 #[serde(rename_all = "camelCase")]
 pub struct DraftModeOptions {
@@ -240,6 +279,15 @@ impl ProjectContainer {
         Ok(())
     }
 
+    /// The options the project was last initialized or updated with.
+    pub async fn options(self: Vc<Self>) -> Result<ProjectOptions> {
+        self.await?
+            .options_state
+            .get_untracked()
+            .clone()
+            .context("ProjectContainer need to be initialized with initialize()")
+    }
+
     #[tracing::instrument(level = "info", name = "update project", skip_all)]
     pub async fn update(self: Vc<Self>, options: PartialProjectOptions) -> Result<()> {
         let PartialProjectOptions {
@@ -402,6 +450,24 @@ impl ProjectContainer {
         self.project().entrypoints()
     }
 
+    /// See [Project::write_all_entrypoints_to_disk].
+    #[turbo_tasks::function]
+    pub fn write_all_entrypoints_to_disk(self: Vc<Self>) -> Vc<WrittenEntrypoints> {
+        self.project().write_all_entrypoints_to_disk()
+    }
+
+    /// See [Project::remove_output_files].
+    #[turbo_tasks::function]
+    pub fn remove_output_files(self: Vc<Self>, paths: Vec<RcStr>) -> Vc<()> {
+        self.project().remove_output_files(paths)
+    }
+
+    /// See [Project::precompile_route].
+    #[turbo_tasks::function]
+    pub fn precompile_route(self: Vc<Self>, pathname: RcStr) -> Vc<Completion> {
+        self.project().precompile_route(pathname)
+    }
+
     /// See [Project::hmr_identifiers].
     #[turbo_tasks::function]
     pub fn hmr_identifiers(self: Vc<Self>) -> Vc<Vec<RcStr>> {
@@ -501,6 +567,11 @@ struct ConflictIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ConflictIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::OUTPUT_CONFLICT))
+    }
+
     #[turbo_tasks::function]
     fn stage(&self) -> Vc<IssueStage> {
         IssueStage::AppStructure.cell()
@@ -544,13 +615,24 @@ impl Project {
         PagesProject::new(self)
     }
 
+    /// In hermetic builds, only the project directory and the declared roots
+    /// can be read, see [NextConfig::hermetic].
     #[turbo_tasks::function]
-    pub fn project_fs(&self) -> Vc<DiskFileSystem> {
-        DiskFileSystem::new(
+    pub async fn project_fs(&self) -> Result<Vc<DiskFileSystem>> {
+        let Some(hermetic) = &*self.next_config.hermetic().await? else {
+            return Ok(DiskFileSystem::new(
+                PROJECT_FILESYSTEM_NAME.into(),
+                self.root_path.clone(),
+                vec![],
+            ));
+        };
+        Ok(DiskFileSystem::new_hermetic(
             PROJECT_FILESYSTEM_NAME.into(),
             self.root_path.clone(),
             vec![],
-        )
+            hermetic_roots(&self.project_path, hermetic),
+            Vc::upcast(HermeticViolationIssueReporter::new(hermetic.roots.clone())),
+        ))
     }
 
     #[turbo_tasks::function]
@@ -569,12 +651,24 @@ impl Project {
         Vc::cell(self.dist_dir.clone())
     }
 
+    #[turbo_tasks::function]
+    pub fn build_id(&self) -> Vc<RcStr> {
+        Vc::cell(self.build_id.clone())
+    }
+
     #[turbo_tasks::function]
     pub async fn node_root(self: Vc<Self>) -> Result<Vc<FileSystemPath>> {
         let this = self.await?;
         Ok(self.output_fs().root().join(this.dist_dir.clone()))
     }
 
+    /// The directory the internal symbol maps of client chunks are emitted to,
+    /// see [NextConfig::internal_symbol_maps].
+    #[turbo_tasks::function]
+    pub fn symbol_maps_root(self: Vc<Self>) -> Vc<FileSystemPath> {
+        self.node_root().join("server/symbol-maps".into())
+    }
+
     #[turbo_tasks::function]
     pub fn client_root(self: Vc<Self>) -> Vc<FileSystemPath> {
         self.client_fs().root()
@@ -609,9 +703,23 @@ impl Project {
         Ok(root.join(project_relative.into()))
     }
 
+    /// In hermetic builds, only the declared env variables and the ones
+    /// Next.js and Node.js need can be read, see [NextConfig::hermetic].
     #[turbo_tasks::function]
-    pub(super) fn env(&self) -> Vc<Box<dyn ProcessEnv>> {
-        *self.env
+    pub(super) async fn env(&self) -> Result<Vc<Box<dyn ProcessEnv>>> {
+        let Some(hermetic) = &*self.next_config.hermetic().await? else {
+            return Ok(*self.env);
+        };
+        let allowed = HERMETIC_INTERNAL_ENV_VARS
+            .iter()
+            .map(|&name| RcStr::from(name))
+            .chain(hermetic.env.iter().cloned())
+            .collect();
+        Ok(Vc::upcast(HermeticProcessEnv::new(
+            *self.env,
+            allowed,
+            hermetic_roots(&self.project_path, hermetic),
+        )))
     }
 
     #[turbo_tasks::function]
@@ -651,7 +759,7 @@ impl Project {
                 node_build_environment().to_resolved().await?,
                 next_mode.runtime_type(),
             )
-            .build(),
+            .build()?,
         );
 
         Ok(ExecutionContext::new(
@@ -662,8 +770,13 @@ impl Project {
     }
 
     #[turbo_tasks::function]
-    pub(super) fn client_compile_time_info(&self) -> Vc<CompileTimeInfo> {
-        get_client_compile_time_info(self.browserslist_query.clone(), self.define_env.client())
+    pub(super) async fn client_compile_time_info(self: Vc<Self>) -> Result<Vc<CompileTimeInfo>> {
+        let this = self.await?;
+        Ok(get_client_compile_time_info(
+            this.browserslist_query.clone(),
+            this.define_env.client(),
+            self.next_config().chunk_load_retry(),
+        ))
     }
 
     #[turbo_tasks::function]
@@ -708,6 +821,8 @@ impl Project {
             self.next_mode(),
             self.module_id_strategy(),
             self.next_config().turbo_minify(self.next_mode()),
+            self.next_config().hash_algorithm(),
+            self.next_config().internal_symbol_maps(self.next_mode()),
         )
     }
 
@@ -726,6 +841,7 @@ impl Project {
                 self.server_compile_time_info().environment(),
                 self.module_id_strategy(),
                 self.next_config().turbo_minify(self.next_mode()),
+                self.next_config().hash_algorithm(),
             )
         } else {
             get_server_chunking_context(
@@ -735,6 +851,7 @@ impl Project {
                 self.server_compile_time_info().environment(),
                 self.module_id_strategy(),
                 self.next_config().turbo_minify(self.next_mode()),
+                self.next_config().hash_algorithm(),
             )
         }
     }
@@ -754,6 +871,7 @@ impl Project {
                 self.edge_compile_time_info().environment(),
                 self.module_id_strategy(),
                 self.next_config().turbo_minify(self.next_mode()),
+                self.next_config().hash_algorithm(),
             )
         } else {
             get_edge_chunking_context(
@@ -763,6 +881,7 @@ impl Project {
                 self.edge_compile_time_info().environment(),
                 self.module_id_strategy(),
                 self.next_config().turbo_minify(self.next_mode()),
+                self.next_config().hash_algorithm(),
             )
         }
     }
@@ -855,6 +974,156 @@ impl Project {
         Ok(Default::default())
     }
 
+    /// Writes the output of all endpoints to disk. In a production build with
+    /// watching enabled, this is recomputed after changes to the project and
+    /// only changed output assets are written again.
+    #[turbo_tasks::function]
+    pub async fn write_all_entrypoints_to_disk(self: Vc<Self>) -> Result<Vc<WrittenEntrypoints>> {
+        let entrypoints = self.entrypoints().await?;
+        let node_root = self.node_root();
+        let client_relative_path = self.client_relative_path();
+        let written = entrypoints
+            .endpoints()
+            .into_iter()
+            .map(|(name, endpoint)| async move {
+                let written = endpoint.write_to_disk().await?.clone_value();
+                // Client assets are emitted into the node root without the
+                // client relative path, so their paths are relative to it too.
+                let output_assets = endpoint.output_assets();
+                let mut output_paths = all_server_paths(output_assets, node_root)
+                    .await?
+                    .clone_value();
+                output_paths.extend(
+                    all_server_paths(output_assets, client_relative_path)
+                        .await?
+                        .iter()
+                        .cloned(),
+                );
+                Ok((
+                    name,
+                    WrittenEntrypoint {
+                        endpoint: written,
+                        output_paths,
+                    },
+                ))
+            })
+            .try_join()
+            .await?;
+        Ok(Vc::cell(written.into_iter().collect()))
+    }
+
+    /// Removes files from the node root, e.g. the
+    /// [stale paths][crate::entrypoints::EntrypointChanges::stale_paths] of a
+    /// watched build. The removals are effects of this function.
+    #[turbo_tasks::function]
+    pub async fn remove_output_files(self: Vc<Self>, paths: Vec<RcStr>) -> Result<()> {
+        let node_root = self.node_root();
+        paths
+            .into_iter()
+            .map(|path| node_root.join(path).write(FileContent::NotFound.cell()))
+            .try_join()
+            .await?;
+        Ok(())
+    }
+
+    /// Describes every asset written by [Project::write_all_entrypoints_to_disk],
+    /// so deployment adapters don't need to walk the output directory.
+    #[turbo_tasks::function]
+    pub async fn emitted_assets(self: Vc<Self>) -> Result<Vc<EmittedAssets>> {
+        let entrypoints = self.entrypoints().await?;
+        emitted_assets(
+            entrypoints.endpoints(),
+            self.node_root(),
+            self.client_relative_path(),
+            self.project_path(),
+            self.module_id_strategy(),
+        )
+        .await
+    }
+
+    /// Maps the logical path of every asset written by
+    /// [Project::write_all_entrypoints_to_disk] to its fingerprinted path,
+    /// content hash and size, for differential CDN uploads.
+    #[turbo_tasks::function]
+    pub async fn fingerprint_manifest(self: Vc<Self>) -> Result<Vc<FingerprintManifest>> {
+        let entrypoints = self.entrypoints().await?;
+        fingerprint_manifest(
+            entrypoints.endpoints(),
+            self.node_root(),
+            self.client_relative_path(),
+            *self.next_config().hash_algorithm().await?,
+        )
+        .await
+    }
+
+    /// Writes [Project::fingerprint_manifest] to
+    /// [crate::fingerprint_manifest::FINGERPRINT_MANIFEST_FILE] in the node
+    /// root.
+    #[turbo_tasks::function]
+    pub fn write_fingerprint_manifest(self: Vc<Self>) -> Vc<()> {
+        write_fingerprint_manifest(self.fingerprint_manifest(), self.node_root())
+    }
+
+    /// The environments [Project::trace_resolve] can trace resolves in.
+    #[turbo_tasks::function]
+    pub async fn resolve_trace_environments(self: Vc<Self>) -> Result<Vc<Vec<RcStr>>> {
+        Ok(Vc::cell(
+            resolve_trace_environments(self)
+                .await?
+                .into_keys()
+                .collect(),
+        ))
+    }
+
+    /// Resolves `specifier` from `importer`, relative to the project
+    /// directory, in one of [Project::resolve_trace_environments] and
+    /// returns every step the resolver took, for debugging "module not
+    /// found" errors.
+    #[turbo_tasks::function]
+    pub async fn trace_resolve(
+        self: Vc<Self>,
+        environment: RcStr,
+        specifier: RcStr,
+        importer: RcStr,
+        kind: Option<RcStr>,
+    ) -> Result<Vc<ResolveTrace>> {
+        project_trace_resolve(self, &environment, specifier, importer, kind.as_deref()).await
+    }
+
+    /// The output sizes and npm packages of every endpoint, see
+    /// [crate::bundle_diff::bundle_diff].
+    #[turbo_tasks::function]
+    pub async fn route_bundles(self: Vc<Self>) -> Result<Vc<RouteBundles>> {
+        let entrypoints = self.entrypoints().await?;
+        route_bundles(entrypoints.endpoints(), self.client_relative_path()).await
+    }
+
+    /// Compiles the route with the given pathname without writing it to disk,
+    /// so a later request doesn't need to wait for the compilation. Unknown
+    /// pathnames are ignored.
+    #[turbo_tasks::function]
+    pub async fn precompile_route(self: Vc<Self>, pathname: RcStr) -> Result<Vc<Completion>> {
+        let entrypoints = self.entrypoints().await?;
+        let endpoints = match entrypoints.routes.get(&pathname) {
+            Some(Route::Page { html_endpoint, .. }) => vec![*html_endpoint],
+            Some(Route::PageApi { endpoint } | Route::AppRoute { endpoint, .. }) => {
+                vec![*endpoint]
+            }
+            Some(Route::AppPage(pages)) => pages.iter().map(|page| page.html_endpoint).collect(),
+            Some(Route::Conflict) | None => vec![],
+        };
+        endpoints
+            .into_iter()
+            .map(|endpoint| async move {
+                endpoint.server_changed().await?;
+                endpoint.client_changed().await?;
+                Ok(())
+            })
+            .try_join()
+            .await?;
+        Ok(Completion::new())
+    }
+
     /// Scans the app/pages directories for entry points files (matching the
     /// provided page_extensions).
     #[turbo_tasks::function]
@@ -1199,15 +1468,34 @@ impl Project {
 
                 Ok(())
             } else {
+                let all_output_assets = *all_output_assets.await?;
                 let _ = emit_assets(
-                    *all_output_assets.await?,
+                    all_output_assets,
                     node_root,
                     client_relative_path,
                     node_root,
+                    *self
+                        .next_config()
+                        .deterministic_build(self.next_mode())
+                        .await?,
                 )
                 .resolve()
                 .await?;
 
+                if *self
+                    .next_config()
+                    .internal_symbol_maps(self.next_mode())
+                    .await?
+                {
+                    let _ = emit_symbol_maps(
+                        all_output_assets,
+                        client_relative_path,
+                        self.symbol_maps_root(),
+                    )
+                    .resolve()
+                    .await?;
+                }
+
                 Ok(())
             }
         }
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, Value, Vc};
+use turbopack_core::{
+    context::AssetContext,
+    resolve::{
+        parse::Request,
+        trace::{trace_reference_type, trace_resolve, ResolveTrace},
+    },
+};
+
+use crate::project::Project;
+
+/// The module contexts of the project by the name of their environment, e.g. `app-rsc` or
+/// `pages-edge-api`, as they can be traced with [Project::trace_resolve].
+///
+/// The SSR contexts of the app directory are transitions of the RSC contexts, and resolve like
+/// `pages-ssr` and `pages-edge-ssr`.
+pub(crate) async fn resolve_trace_environments(
+    project: Vc<Project>,
+) -> Result<FxIndexMap<RcStr, Vc<Box<dyn AssetContext>>>> {
+    let mut environments = FxIndexMap::default();
+    if let Some(app_project) = *project.app_project().await? {
+        environments.extend([
+            (
+                "app-client".into(),
+                Vc::upcast(app_project.client_module_context()),
+            ),
+            (
+                "app-rsc".into(),
+                Vc::upcast(app_project.rsc_module_context()),
+            ),
+            (
+                "app-edge-rsc".into(),
+                Vc::upcast(app_project.edge_rsc_module_context()),
+            ),
+            (
+                "app-route".into(),
+                Vc::upcast(app_project.route_module_context()),
+            ),
+            (
+                "app-edge-route".into(),
+                Vc::upcast(app_project.edge_route_module_context()),
+            ),
+        ]);
+    }
+    let pages_project = project.pages_project();
+    environments.extend([
+        ("pages-client".into(), pages_project.client_module_context()),
+        (
+            "pages-ssr".into(),
+            Vc::upcast(pages_project.ssr_module_context()),
+        ),
+        (
+            "pages-edge-ssr".into(),
+            Vc::upcast(pages_project.edge_ssr_module_context()),
+        ),
+        (
+            "pages-api".into(),
+            Vc::upcast(pages_project.api_module_context()),
+        ),
+        (
+            "pages-edge-api".into(),
+            Vc::upcast(pages_project.edge_api_module_context()),
+        ),
+    ]);
+    Ok(environments)
+}
+
+/// Looks up the context of `environment`, naming the known environments when it's unknown.
+fn environment_context<'a, T>(
+    environments: &'a FxIndexMap<RcStr, T>,
+    environment: &str,
+) -> Result<&'a T> {
+    environments.get(environment).with_context(|| {
+        format!(
+            "Unknown environment {environment:?}, expected one of {}",
+            environments
+                .keys()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })
+}
+
+/// Traces how `specifier` is resolved from `importer`, a path relative to the project
+/// directory, in the given environment. `kind` is `import` (the default), `require` or `url`.
+pub(crate) async fn project_trace_resolve(
+    project: Vc<Project>,
+    environment: &str,
+    specifier: RcStr,
+    importer: RcStr,
+    kind: Option<&str>,
+) -> Result<Vc<ResolveTrace>> {
+    let environments = resolve_trace_environments(project).await?;
+    let asset_context = *environment_context(&environments, environment)?;
+    let reference_type =
+        trace_reference_type(kind).with_context(|| format!("Unknown reference kind {kind:?}"))?;
+    Ok(trace_resolve(
+        asset_context,
+        project.project_path().join(importer),
+        Request::parse_string(specifier),
+        Value::new(reference_type),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_context_of_an_environment() {
+        let environments: FxIndexMap<RcStr, u32> =
+            FxIndexMap::from_iter([("app-client".into(), 1), ("pages-ssr".into(), 2)]);
+        assert_eq!(*environment_context(&environments, "pages-ssr").unwrap(), 2);
+        assert_eq!(
+            environment_context(&environments, "app-ssr")
+                .unwrap_err()
+                .to_string(),
+            "Unknown environment \"app-ssr\", expected one of app-client, pages-ssr"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
 use turbo_tasks::{debug::ValueDebugFormat, trace::TraceRawVcs, Completion, FxIndexMap, Vc};
-use turbopack_core::module::Modules;
+use turbopack_core::{module::Modules, output::OutputAssets};
 
 use crate::paths::ServerPath;
 
@@ -77,6 +77,9 @@ pub trait Endpoint {
     fn server_changed(self: Vc<Self>) -> Vc<Completion>;
     fn client_changed(self: Vc<Self>) -> Vc<Completion>;
     fn root_modules(self: Vc<Self>) -> Vc<Modules>;
+    /// The output assets of the endpoint, which [Endpoint::write_to_disk]
+    /// writes together with all assets they reference.
+    fn output_assets(self: Vc<Self>) -> Vc<OutputAssets>;
 }
 
 #[turbo_tasks::value(shared)]
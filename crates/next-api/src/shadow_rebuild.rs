@@ -0,0 +1,162 @@
+use anyhow::Result;
+use next_core::all_assets_from_entries;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, TaskId, TryJoinIterExt, Vc};
+use turbopack_core::asset::Asset;
+
+use crate::{project::Project, route::Endpoint};
+
+/// The content hashes of the output assets of an endpoint, by their path.
+#[turbo_tasks::value(transparent)]
+pub struct OutputHashes(FxIndexMap<RcStr, u64>);
+
+#[turbo_tasks::function]
+pub async fn endpoint_output_hashes(endpoint: Vc<Box<dyn Endpoint>>) -> Result<Vc<OutputHashes>> {
+    let hashes = all_assets_from_entries(endpoint.output_assets())
+        .await?
+        .iter()
+        .map(|asset| async move {
+            let path = asset.ident().path().await?.path.clone();
+            let hash = *asset.content().file_content().hash().await?;
+            anyhow::Ok((path, hash))
+        })
+        .try_join()
+        .await?;
+    Ok(Vc::cell(hashes.into_iter().collect()))
+}
+
+/// The name of `endpoint` in [crate::entrypoints::Entrypoints::endpoints] of
+/// `project`.
+pub async fn endpoint_name(
+    project: Vc<Project>,
+    endpoint: Vc<Box<dyn Endpoint>>,
+) -> Result<Option<RcStr>> {
+    Ok(project
+        .entrypoints()
+        .await?
+        .endpoints()
+        .into_iter()
+        .find(|(_, candidate)| *candidate == endpoint)
+        .map(|(name, _)| name))
+}
+
+/// The endpoint called `name` in [crate::entrypoints::Entrypoints::endpoints]
+/// of `project`.
+pub async fn endpoint_by_name(
+    project: Vc<Project>,
+    name: &str,
+) -> Result<Option<Vc<Box<dyn Endpoint>>>> {
+    Ok(project
+        .entrypoints()
+        .await?
+        .endpoints()
+        .into_iter()
+        .find(|(candidate, _)| candidate == name)
+        .map(|(_, endpoint)| endpoint))
+}
+
+/// An output asset whose content differs between an incremental build and a
+/// build from scratch.
+#[derive(Debug)]
+pub struct OutputDivergence {
+    pub path: RcStr,
+    /// `None` when the asset is missing in the incremental build.
+    pub incremental: Option<u64>,
+    /// `None` when the asset is missing in the build from scratch.
+    pub from_scratch: Option<u64>,
+}
+
+pub fn compare_output_hashes(
+    incremental: &FxIndexMap<RcStr, u64>,
+    from_scratch: &FxIndexMap<RcStr, u64>,
+) -> Vec<OutputDivergence> {
+    let mut divergences: Vec<_> = incremental
+        .iter()
+        .filter(|(path, hash)| from_scratch.get(*path) != Some(hash))
+        .map(|(path, hash)| OutputDivergence {
+            path: path.clone(),
+            incremental: Some(*hash),
+            from_scratch: from_scratch.get(path).copied(),
+        })
+        .collect();
+    divergences.extend(
+        from_scratch
+            .iter()
+            .filter(|(path, _)| !incremental.contains_key(*path))
+            .map(|(path, hash)| OutputDivergence {
+                path: path.clone(),
+                incremental: None,
+                from_scratch: Some(*hash),
+            }),
+    );
+    divergences
+}
+
+/// The task computing the content of the output asset at `path` of
+/// `endpoint`, to find out why it was (or wasn't) recomputed with
+/// [turbo_tasks::why_invalidated].
+pub async fn output_content_task(
+    endpoint: Vc<Box<dyn Endpoint>>,
+    path: &str,
+) -> Result<Option<TaskId>> {
+    for asset in all_assets_from_entries(endpoint.output_assets())
+        .await?
+        .iter()
+    {
+        if asset.ident().path().await?.path == path {
+            return Ok(Some(Vc::into_raw(asset.content()).get_task_id()));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(hashes: &[(&str, u64)]) -> FxIndexMap<RcStr, u64> {
+        hashes
+            .iter()
+            .map(|&(path, hash)| (path.into(), hash))
+            .collect()
+    }
+
+    #[test]
+    fn equal_outputs_do_not_diverge() {
+        let outputs = hashes(&[("server/app/page.js", 1), ("static/chunks/a.js", 2)]);
+        assert!(compare_output_hashes(&outputs, &outputs).is_empty());
+        assert!(compare_output_hashes(&hashes(&[]), &hashes(&[])).is_empty());
+    }
+
+    #[test]
+    fn reports_changed_missing_and_added_outputs() {
+        let incremental = hashes(&[
+            ("server/app/page.js", 1),
+            ("static/chunks/a.js", 2),
+            ("static/chunks/stale.js", 3),
+        ]);
+        let from_scratch = hashes(&[
+            ("server/app/page.js", 1),
+            ("static/chunks/a.js", 4),
+            ("static/chunks/b.js", 5),
+        ]);
+        let divergences: Vec<_> = compare_output_hashes(&incremental, &from_scratch)
+            .into_iter()
+            .map(|divergence| {
+                (
+                    divergence.path.to_string(),
+                    divergence.incremental,
+                    divergence.from_scratch,
+                )
+            })
+            .collect();
+        assert_eq!(
+            divergences,
+            vec![
+                ("static/chunks/a.js".to_string(), Some(2), Some(4)),
+                ("static/chunks/stale.js".to_string(), Some(3), None),
+                ("static/chunks/b.js".to_string(), None, Some(5)),
+            ]
+        );
+    }
+}
@@ -139,10 +139,17 @@ impl VersionedContentMap {
             changed
         });
 
-        // Make sure all written client assets are up-to-date
-        let _ = emit_assets(assets, node_root, client_relative_path, client_output_path)
-            .resolve()
-            .await?;
+        // Make sure all written client assets are up-to-date. The map is only used by the dev
+        // server, which doesn't need reproducible output.
+        let _ = emit_assets(
+            assets,
+            node_root,
+            client_relative_path,
+            client_output_path,
+            false,
+        )
+        .resolve()
+        .await?;
         let map_entry = Vc::cell(Some(MapEntry {
             assets_operation: assets,
             path_to_asset: entries.into_iter().collect(),
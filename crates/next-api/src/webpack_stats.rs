@@ -4,9 +4,11 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, FxIndexSet, ResolvedVc, Vc};
 use turbopack_browser::ecmascript::EcmascriptDevChunk;
 use turbopack_core::{
+    asset::Asset,
     chunk::{Chunk, ChunkItem},
     output::OutputAsset,
 };
+use turbopack_image::process::media::{get_media_meta_data, MediaContainer};
 
 pub async fn generate_webpack_stats<'a, I>(
     entry_name: RcStr,
@@ -45,9 +47,32 @@ where
             }
         }
 
+        let asset_path = asset.ident().path().await?;
+        let media = if asset_path
+            .extension_ref()
+            .and_then(MediaContainer::from_extension)
+            .is_some()
+        {
+            let info = get_media_meta_data(asset.ident(), asset.content().file_content()).await?;
+            Some(WebpackStatsMediaInfo {
+                duration: info.duration_ms.map(|ms| ms as f64 / 1000.0),
+                width: info.width,
+                height: info.height,
+                mime_type: info.mime_type.as_ref().map(|mime| mime.to_string().into()),
+                codecs: info
+                    .codecs
+                    .iter()
+                    .map(|codec| codec.as_str().into())
+                    .collect(),
+            })
+        } else {
+            None
+        };
+
         assets.push(WebpackStatsAsset {
             ty: "asset".into(),
             name: path.clone().into(),
+            info: WebpackStatsAssetInfo { media },
             chunks: vec![path.into()],
             size: asset_len,
             ..Default::default()
@@ -95,7 +120,22 @@ fn normalize_client_path(path: &str) -> String {
 
 #[derive(Serialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct WebpackStatsAssetInfo {}
+pub struct WebpackStatsAssetInfo {
+    /// Meta information of audio and video assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<WebpackStatsMediaInfo>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WebpackStatsMediaInfo {
+    /// The duration in seconds.
+    pub duration: Option<f64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub mime_type: Option<RcStr>,
+    pub codecs: Vec<RcStr>,
+}
 
 #[derive(Serialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
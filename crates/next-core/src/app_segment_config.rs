@@ -14,8 +14,8 @@ use turbopack_core::{
     file_source::FileSource,
     ident::AssetIdent,
     issue::{
-        Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource,
-        OptionStyledString, StyledString,
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueCode,
+        OptionIssueSource, OptionStyledString, StyledString,
     },
     source::Source,
 };
@@ -175,6 +175,11 @@ pub struct NextSegmentConfigParsingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for NextSegmentConfigParsingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_SEGMENT_CONFIG))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Warning.into()
@@ -10,9 +10,12 @@ use turbo_tasks::{
     debug::ValueDebugFormat, fxindexmap, trace::TraceRawVcs, FxIndexMap, ResolvedVc, TaskInput,
     TryJoinIterExt, ValueDefault, ValueToString, Vc,
 };
-use turbo_tasks_fs::{DirectoryContent, DirectoryEntry, FileSystemEntryType, FileSystemPath};
+use turbo_tasks_fs::{
+    DirectoryContent, DirectoryEntry, FileSystemEntryType, FileSystemPath, WalkDirOptions,
+};
 use turbopack_core::issue::{
-    Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString,
+    Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+    StyledString,
 };
 
 use crate::{
@@ -710,16 +713,19 @@ fn add_app_metadata_route(
 }
 
 #[turbo_tasks::function]
-pub fn get_entrypoints(
+pub async fn get_entrypoints(
     app_dir: Vc<FileSystemPath>,
     page_extensions: Vc<Vec<RcStr>>,
-) -> Vc<Entrypoints> {
-    directory_tree_to_entrypoints(
+) -> Result<Vc<Entrypoints>> {
+    // Walking the app directory reads all of its subdirectories in parallel, so
+    // the directory tree below is built from cached reads.
+    app_dir.walk_dir(WalkDirOptions::default().cell()).await?;
+    Ok(directory_tree_to_entrypoints(
         app_dir,
         get_directory_tree(app_dir, page_extensions),
         get_global_metadata(app_dir, page_extensions),
         Default::default(),
-    )
+    ))
 }
 
 #[turbo_tasks::function]
@@ -747,6 +753,11 @@ struct DuplicateParallelRouteIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for DuplicateParallelRouteIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::DUPLICATE_PARALLEL_ROUTE))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         self.app_dir.join(self.page.to_string().into())
@@ -1426,6 +1437,11 @@ struct DirectoryTreeIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for DirectoryTreeIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::APP_DIRECTORY_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         *self.severity
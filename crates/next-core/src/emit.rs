@@ -1,32 +1,53 @@
 use anyhow::Result;
 use tracing::Instrument;
+use turbo_rcstr::RcStr;
 use turbo_tasks::{
     graph::{AdjacencyMap, GraphTraversal},
-    ResolvedVc, TryFlatJoinIterExt, ValueToString, Vc,
+    publish_event, ParallelismLimit, ResolvedVc, TryFlatJoinIterExt, ValueToString, Vc,
 };
 use turbo_tasks_fs::{rebase, FileSystemPath};
 use turbopack_core::{
-    asset::Asset,
+    asset::{Asset, AssetContent},
     output::{OutputAsset, OutputAssets},
+    source_map::{GenerateSourceMap, SourceMapAsset},
 };
 
+/// Limits the number of assets which are generated and written to disk at the
+/// same time.
+pub static EMIT_PARALLELISM: ParallelismLimit = ParallelismLimit::new("emit");
+
+/// Published when [emit_assets] emits an asset, e.g. for the dev server to
+/// react to changed output. The event is published whenever the task emitting
+/// the asset executes, i.e. on the first emit and when the content or path of
+/// the asset was invalidated. The write itself is an effect which is applied
+/// later and skips files whose content on disk is already equal, so the event
+/// doesn't mean that the file on disk changed or was written yet. Subscribe
+/// with `TurboTasks::subscribe_events`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputEmitted {
+    /// The path of the asset, relative to the root of its file system.
+    pub path: RcStr,
+}
+
 /// Emits all assets transitively reachable from the given chunks, that are
 /// inside the node root or the client root.
 ///
 /// Assets inside the given client root are rebased to the given client output
-/// path.
+/// path. When `deterministic` is set, the keys of JSON assets are sorted.
 #[turbo_tasks::function]
 pub async fn emit_all_assets(
     assets: Vc<OutputAssets>,
     node_root: Vc<FileSystemPath>,
     client_relative_path: Vc<FileSystemPath>,
     client_output_path: Vc<FileSystemPath>,
+    deterministic: bool,
 ) -> Result<()> {
     let _ = emit_assets(
         all_assets_from_entries(assets),
         node_root,
         client_relative_path,
         client_output_path,
+        deterministic,
     )
     .resolve()
     .await?;
@@ -37,13 +58,14 @@ pub async fn emit_all_assets(
 /// inside the node root or the client root.
 ///
 /// Assets inside the given client root are rebased to the given client output
-/// path.
+/// path. When `deterministic` is set, the keys of JSON assets are sorted.
 #[turbo_tasks::function]
 pub async fn emit_assets(
     assets: Vc<OutputAssets>,
     node_root: Vc<FileSystemPath>,
     client_relative_path: Vc<FileSystemPath>,
     client_output_path: Vc<FileSystemPath>,
+    deterministic: bool,
 ) -> Result<()> {
     let _: Vec<Vc<()>> = assets
         .await?
@@ -56,12 +78,17 @@ pub async fn emit_assets(
             async move {
                 let path = path.await?;
                 Ok(if path.is_inside_ref(&*node_root.await?) {
-                    Some(emit(asset))
+                    Some(emit(asset, deterministic))
                 } else if path.is_inside_ref(&*client_relative_path.await?) {
                     // Client assets are emitted to the client output path, which is prefixed
                     // with _next. We need to rebase them to remove that
                     // prefix.
-                    Some(emit_rebase(asset, client_relative_path, client_output_path))
+                    Some(emit_rebase(
+                        asset,
+                        client_relative_path,
+                        client_output_path,
+                        deterministic,
+                    ))
                 } else {
                     None
                 })
@@ -74,14 +101,32 @@ pub async fn emit_assets(
     Ok(())
 }
 
+/// The content to write for the asset. Manifests are collected in whatever
+/// order routes are built, so deterministic builds sort their keys.
+async fn content(asset: Vc<Box<dyn OutputAsset>>, deterministic: bool) -> Result<Vc<AssetContent>> {
+    let content = asset.content();
+    if deterministic && asset.ident().path().await?.extension_ref() == Some("json") {
+        return Ok(content.with_sorted_json_keys());
+    }
+    Ok(content)
+}
+
 #[turbo_tasks::function]
-async fn emit(asset: Vc<Box<dyn OutputAsset>>) -> Result<()> {
-    let _ = asset
-        .content()
-        .write(asset.ident().path())
-        .resolve()
-        .await?;
-    Ok(())
+async fn emit(asset: Vc<Box<dyn OutputAsset>>, deterministic: bool) -> Result<()> {
+    EMIT_PARALLELISM
+        .run(async {
+            let path = asset.ident().path();
+            let _ = content(asset, deterministic)
+                .await?
+                .write(path)
+                .resolve()
+                .await?;
+            publish_event(OutputEmitted {
+                path: path.await?.path.clone(),
+            });
+            Ok(())
+        })
+        .await
 }
 
 #[turbo_tasks::function]
@@ -89,13 +134,78 @@ async fn emit_rebase(
     asset: Vc<Box<dyn OutputAsset>>,
     from: Vc<FileSystemPath>,
     to: Vc<FileSystemPath>,
+    deterministic: bool,
 ) -> Result<()> {
-    let path = rebase(asset.ident().path(), from, to);
-    let content = asset.content();
-    let _ = content
-        .resolve()
+    EMIT_PARALLELISM
+        .run(async {
+            let path = rebase(asset.ident().path(), from, to);
+            let content = content(asset, deterministic).await?;
+            let _ = content
+                .resolve()
+                .await?
+                .write(path.resolve().await?)
+                .resolve()
+                .await?;
+            publish_event(OutputEmitted {
+                path: path.await?.path.clone(),
+            });
+            Ok(())
+        })
+        .await
+}
+
+/// Emits the source maps of the given assets which are inside the client root
+/// below `symbol_maps_root` instead of next to the assets, e.g. the map of
+/// `static/chunks/a.js` to `<symbol_maps_root>/static/chunks/a.js.map`.
+///
+/// The symbol maps are used to resolve stack traces of minified client code
+/// on the server and are never served to clients.
+#[turbo_tasks::function]
+pub async fn emit_symbol_maps(
+    assets: Vc<OutputAssets>,
+    client_relative_path: Vc<FileSystemPath>,
+    symbol_maps_root: Vc<FileSystemPath>,
+) -> Result<()> {
+    let client_relative_path_value = client_relative_path.await?;
+    let _: Vec<Vc<()>> = assets
         .await?
-        .write(path.resolve().await?)
+        .iter()
+        .map(|&asset| {
+            let client_relative_path_value = &client_relative_path_value;
+            async move {
+                if !asset
+                    .ident()
+                    .path()
+                    .await?
+                    .is_inside_ref(client_relative_path_value)
+                    || ResolvedVc::try_sidecast::<Box<dyn GenerateSourceMap>>(asset)
+                        .await?
+                        .is_none()
+                {
+                    return Ok(None);
+                }
+                Ok(Some(emit_symbol_map(
+                    *asset,
+                    client_relative_path,
+                    symbol_maps_root,
+                )))
+            }
+        })
+        .try_flat_join()
+        .await?;
+    Ok(())
+}
+
+#[turbo_tasks::function]
+async fn emit_symbol_map(
+    asset: Vc<Box<dyn OutputAsset>>,
+    from: Vc<FileSystemPath>,
+    to: Vc<FileSystemPath>,
+) -> Result<()> {
+    let path = rebase(asset.ident().path(), from, to).append(".map".into());
+    let _ = SourceMapAsset::new(asset)
+        .content()
+        .write(path)
         .resolve()
         .await?;
     Ok(())
@@ -5,6 +5,7 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ResolvedVc, Value, Vc};
 use turbo_tasks_env::EnvMap;
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack::{
     module_options::{
         module_options_context::ModuleOptionsContext, CssOptionsContext, EcmascriptOptionsContext,
@@ -20,7 +21,7 @@ use turbopack_core::{
         FreeVarReference, FreeVarReferences,
     },
     condition::ContextCondition,
-    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
+    environment::{BrowserEnvironment, ChunkLoadRetry, Environment, ExecutionEnvironment},
     free_var_references,
     resolve::{parse::Request, pattern::Pattern},
 };
@@ -43,8 +44,8 @@ use crate::{
     },
     next_shared::{
         resolve::{
-            get_invalid_server_only_resolve_plugin, ModuleFeatureReportResolvePlugin,
-            NextSharedRuntimeResolvePlugin,
+            get_invalid_server_only_resolve_plugin, get_rpc_resolve_plugins,
+            ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
         },
         transforms::{
             emotion::get_emotion_transform_rule,
@@ -113,6 +114,7 @@ async fn next_client_free_vars(define_env: Vc<EnvMap>) -> Result<Vc<FreeVarRefer
 pub async fn get_client_compile_time_info(
     browserslist_query: RcStr,
     define_env: Vc<EnvMap>,
+    chunk_load_retry: Vc<ChunkLoadRetry>,
 ) -> Result<Vc<CompileTimeInfo>> {
     CompileTimeInfo::builder(
         Environment::new(Value::new(ExecutionEnvironment::Browser(
@@ -124,6 +126,7 @@ pub async fn get_client_compile_time_info(
             }
             .resolved_cell(),
         )))
+        .with_chunk_load_retry(Value::new(chunk_load_retry.await?.clone_value()))
         .to_resolved()
         .await?,
     )
@@ -158,39 +161,44 @@ pub async fn get_client_resolve_options_context(
         get_next_client_import_map(*project_path, ty, next_config, execution_context)
             .to_resolved()
             .await?;
-    let next_client_fallback_import_map = get_next_client_fallback_import_map(ty)
-        .to_resolved()
-        .await?;
+    let next_client_fallback_import_map =
+        get_next_client_fallback_import_map(*project_path, ty, next_config)
+            .to_resolved()
+            .await?;
     let next_client_resolved_map =
         get_next_client_resolved_map(*project_path, project_path, *mode.await?)
             .to_resolved()
             .await?;
     let custom_conditions = vec![mode.await?.condition().into()];
+    let mut before_resolve_plugins = vec![
+        ResolvedVc::upcast(
+            get_invalid_server_only_resolve_plugin(project_path)
+                .to_resolved()
+                .await?,
+        ),
+        ResolvedVc::upcast(
+            ModuleFeatureReportResolvePlugin::new(*project_path)
+                .to_resolved()
+                .await?,
+        ),
+        ResolvedVc::upcast(
+            NextFontLocalResolvePlugin::new(*project_path)
+                .to_resolved()
+                .await?,
+        ),
+    ];
+    before_resolve_plugins.extend(get_rpc_resolve_plugins(next_config, project_path).await?);
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
+        enable_pnp: true,
         custom_conditions,
         import_map: Some(next_client_import_map),
         fallback_import_map: Some(next_client_fallback_import_map),
         resolved_map: Some(next_client_resolved_map),
         browser: true,
         module: true,
-        before_resolve_plugins: vec![
-            ResolvedVc::upcast(
-                get_invalid_server_only_resolve_plugin(project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-            ResolvedVc::upcast(
-                ModuleFeatureReportResolvePlugin::new(*project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-            ResolvedVc::upcast(
-                NextFontLocalResolvePlugin::new(*project_path)
-                    .to_resolved()
-                    .await?,
-            ),
-        ],
+        enable_module_files: *next_config.resolve_module_files().await?,
+        before_resolve_plugins,
         after_resolve_plugins: vec![ResolvedVc::upcast(
             NextSharedRuntimeResolvePlugin::new(*project_path)
                 .to_resolved()
@@ -329,6 +337,7 @@ pub async fn get_client_module_options_context(
         tree_shaking_mode: tree_shaking_mode_for_user_code,
         enable_postcss_transform,
         side_effect_free_packages: next_config.optimize_package_imports().await?.clone_value(),
+        rpc_plugins: next_config.rpc_plugins(*project_path).await?.clone_value(),
         ..Default::default()
     };
 
@@ -405,6 +414,8 @@ pub async fn get_client_chunking_context(
     mode: Vc<NextMode>,
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     turbo_minify: Vc<bool>,
+    content_hash_algorithm: Vc<HashAlgorithm>,
+    internal_symbol_maps: Vc<bool>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let next_mode = mode.await?;
     let mut builder = BrowserChunkingContext::builder(
@@ -430,9 +441,19 @@ pub async fn get_client_chunking_context(
 
     if next_mode.is_development() {
         builder = builder.hot_module_replacement().use_file_source_map_uris();
+    } else {
+        builder = builder.content_hash_algorithm(*content_hash_algorithm.await?);
+    }
+
+    if *internal_symbol_maps.await? {
+        // The source maps are emitted as internal symbol maps instead, see
+        // `emit_symbol_maps`.
+        builder = builder
+            .reference_chunk_source_maps(false)
+            .reference_css_chunk_source_maps(false);
     }
 
-    Ok(Vc::upcast(builder.build()))
+    Ok(Vc::upcast(builder.build()?))
 }
 
 #[turbo_tasks::function]
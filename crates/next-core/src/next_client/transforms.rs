@@ -8,15 +8,18 @@ use crate::{
     next_client::context::ClientContextType,
     next_config::NextConfig,
     next_shared::transforms::{
-        debug_fn_name::get_debug_fn_name_rule, get_next_dynamic_transform_rule,
-        get_next_font_transform_rule, get_next_image_rule, get_next_lint_transform_rule,
-        get_next_modularize_imports_rule, get_next_pages_transforms_rule,
-        get_server_actions_transform_rule, next_amp_attributes::get_next_amp_attr_rule,
+        debug_fn_name::get_debug_fn_name_rule,
+        get_next_dynamic_transform_rule, get_next_font_transform_rule, get_next_image_rule,
+        get_next_lint_transform_rule, get_next_media_rule, get_next_modularize_imports_rule,
+        get_next_pages_transforms_rule, get_server_actions_transform_rule,
+        next_amp_attributes::get_next_amp_attr_rule,
         next_cjs_optimizer::get_next_cjs_optimizer_rule,
         next_disallow_re_export_all_in_page::get_next_disallow_export_all_in_page_rule,
+        next_env_pragmas::{get_next_env_pragmas_rule, PragmaEnv},
         next_page_config::get_next_page_config_rule,
         next_page_static_info::get_next_page_static_info_assert_rule,
-        next_pure::get_next_pure_rule, server_actions::ActionsTransform,
+        next_pure::get_next_pure_rule,
+        server_actions::ActionsTransform,
     },
 };
 
@@ -33,6 +36,7 @@ pub async fn get_next_client_transforms_rules(
     let modularize_imports_config = &next_config.modularize_imports().await?;
     let enable_mdx_rs = next_config.mdx_rs().await?.is_some();
     rules.push(get_next_lint_transform_rule(enable_mdx_rs));
+    rules.push(get_next_env_pragmas_rule(PragmaEnv::Client, enable_mdx_rs));
 
     if !modularize_imports_config.is_empty() {
         rules.push(get_next_modularize_imports_rule(
@@ -91,6 +95,7 @@ pub async fn get_next_client_transforms_rules(
         );
 
         rules.push(get_next_image_rule().await?);
+        rules.push(get_next_media_rule().await?);
         rules.push(get_next_page_static_info_assert_rule(
             enable_mdx_rs,
             None,
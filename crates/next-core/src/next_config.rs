@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use anyhow::{bail, Context, Result};
 use rustc_hash::FxHashSet;
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 use turbo_rcstr::RcStr;
@@ -10,11 +11,16 @@ use turbo_tasks::{
 };
 use turbo_tasks_env::EnvMap;
 use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack::module_options::{
     module_options_context::MdxTransformOptions, LoaderRuleItem, OptionWebpackRules,
 };
 use turbopack_core::{
-    issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    environment::{ChunkLoadRetry, StaleDeploymentAction},
+    issue::{
+        Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     resolve::ResolveAliasMap,
 };
 use turbopack_ecmascript::{OptionTreeShaking, TreeShakingMode};
@@ -23,6 +29,7 @@ use turbopack_ecmascript_plugins::transform::{
     styled_components::StyledComponentsTransformConfig,
 };
 use turbopack_node::transforms::webpack::{WebpackLoaderItem, WebpackLoaderItems};
+use turbopack_plugin_rpc::{RpcPlugin, RpcPlugins};
 
 use crate::{
     mode::NextMode, next_import_map::mdx_import_source_file,
@@ -402,20 +409,278 @@ pub enum RemotePatternProtocal {
     Https,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TraceRawVcs)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExperimentalTurboConfig {
     /// This option has been replaced by `rules`.
+    #[schemars(with = "Option<FxIndexMap<RcStr, Vec<LoaderItem>>>")]
     pub loaders: Option<JsonValue>,
     pub rules: Option<FxIndexMap<RcStr, RuleConfigItemOrShortcut>>,
+    #[schemars(with = "Option<FxIndexMap<RcStr, ResolveAliasConfig>>")]
     pub resolve_alias: Option<FxIndexMap<RcStr, JsonValue>>,
     pub resolve_extensions: Option<Vec<RcStr>>,
+    /// Modules to use when a request can't be resolved otherwise. `false`
+    /// resolves to an empty module.
+    #[schemars(with = "Option<FxIndexMap<RcStr, ResolveFallbackConfig>>")]
+    pub resolve_fallback: Option<FxIndexMap<RcStr, JsonValue>>,
+    /// Also resolve bare module requests to files directly inside of
+    /// `node_modules`, e.g. `foo` to `node_modules/foo.js`, for modules
+    /// without a package.json.
+    pub resolve_module_files: Option<bool>,
     pub tree_shaking: Option<bool>,
+    /// The persistent cache to use, or `false` to disable it. Read by the JS
+    /// side when creating the turbo-tasks backend.
+    pub persistent_caching: Option<PersistentCachingConfig>,
+    /// A target memory limit for turbo-tasks, in bytes. Read by the JS side
+    /// when creating the turbo-tasks backend.
+    pub memory_limit: Option<f64>,
+    /// The memory usage in bytes above which data of the persistent cache
+    /// that wasn't used recently is dropped from memory.
+    pub memory_target: Option<f64>,
     pub module_id_strategy: Option<ModuleIdStrategy>,
     pub minify: Option<bool>,
+    /// Size budgets for the client JavaScript and CSS of routes.
+    pub budgets: Option<Vec<RouteBudget>>,
+    /// Limits how much work of production builds runs at the same time. Read
+    /// by the JS side when creating the project.
+    pub build_parallelism: Option<BuildParallelismConfig>,
+    /// Emits the source maps of client chunks of production builds to
+    /// `server/symbol-maps` instead of next to the chunks, so minified stack
+    /// traces can be resolved locally without serving the maps to clients.
+    pub internal_symbol_maps: Option<bool>,
+    /// Fails the build when it reads a file or env variable which isn't
+    /// declared as its input, or when evaluated JavaScript accesses the
+    /// network.
+    pub hermetic: Option<HermeticConfig>,
+    /// How the browser retries chunks which fail to load.
+    pub chunk_load_retry: Option<ChunkLoadRetryConfig>,
+    /// The hash function for content hashes of static assets and for the
+    /// module ids of the deterministic module id strategy which aren't in
+    /// its module id map.
+    pub hash_algorithm: Option<HashAlgorithmConfig>,
+    /// Plugin processes resolving requests and transforming sources, see
+    /// [turbopack_plugin_rpc].
+    pub plugins: Option<Vec<RpcPluginConfig>>,
+    /// Sorts the keys of all emitted JSON files of production builds, so the
+    /// manifests don't depend on the order in which routes were built.
+    pub deterministic: Option<bool>,
+    /// Writes a manifest of all emitted assets for differential CDN uploads,
+    /// see `next_api::fingerprint_manifest`.
+    pub fingerprint_manifest: Option<bool>,
+}
+
+impl ExperimentalTurboConfig {
+    /// The JSON schema of `experimental.turbo` in `next.config.js`. It's
+    /// derived from the structs the config is parsed into, so it stays in
+    /// sync with the options Turbopack understands.
+    pub fn json_schema() -> Result<String> {
+        Ok(serde_json::to_string_pretty(&schemars::schema_for!(
+            ExperimentalTurboConfig
+        ))?)
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+/// The schema of a `resolveAlias` entry, which is parsed when the import map
+/// is created.
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum ResolveAliasConfig {
+    Request(RcStr),
+    Alternatives(Vec<RcStr>),
+    /// The requests by condition, e.g. `browser` or `default`.
+    Conditional(FxIndexMap<RcStr, ResolveAliasTargetConfig>),
+}
+
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum ResolveAliasTargetConfig {
+    Request(RcStr),
+    Alternatives(Vec<RcStr>),
+}
+
+/// The schema of a `resolveFallback` entry.
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum ResolveFallbackConfig {
+    Request(RcStr),
+    Empty(#[schemars(schema_with = "false_schema")] bool),
+}
+
+/// Maps the requests of `experimental.turbo.resolveFallback` to their fallback
+/// request, or to `None` for an empty module.
+fn resolve_fallbacks(
+    resolve_fallback: &FxIndexMap<RcStr, JsonValue>,
+) -> Result<FxIndexMap<RcStr, Option<RcStr>>> {
+    resolve_fallback
+        .iter()
+        .map(|(request, value)| {
+            let fallback = match value {
+                JsonValue::String(fallback) => Some(fallback.as_str().into()),
+                JsonValue::Bool(false) => None,
+                _ => bail!(
+                    "experimental.turbo.resolveFallback[\"{request}\"] must be a string or false"
+                ),
+            };
+            Ok((request.clone(), fallback))
+        })
+        .collect()
+}
+
+/// The schema of the literal `false`.
+fn false_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    schemars::schema::SchemaObject {
+        const_value: Some(JsonValue::Bool(false)),
+        ..Default::default()
+    }
+    .into()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(untagged)]
+pub enum PersistentCachingConfig {
+    /// The version of the cache, changing it drops the cache.
+    Version(f64),
+    Disabled(#[schemars(schema_with = "false_schema")] bool),
+}
+
+/// How many endpoints are built, how many chunk groups are computed and how
+/// many files are emitted at the same time.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildParallelismConfig {
+    #[schemars(range(min = 1))]
+    pub endpoints: Option<u32>,
+    #[schemars(range(min = 1))]
+    pub chunking: Option<u32>,
+    #[schemars(range(min = 1))]
+    pub emit: Option<u32>,
+}
+
+/// The keys of the object passed to `z.object` (or `z.strictObject`) after
+/// `<key>: z` in the zod schema `source`.
+#[cfg(test)]
+fn zod_object_keys(source: &str, key: &str) -> Vec<String> {
+    let start = source
+        .find(&format!("{key}: z"))
+        .unwrap_or_else(|| panic!("`{key}` isn't in the zod schema"));
+    let object = start + source[start..].find("bject({").unwrap() + "bject({".len();
+    let mut keys = Vec::new();
+    let mut depth = 0;
+    for line in source[object..].lines() {
+        if depth == 0 {
+            if let Some((name, _)) = line.trim().split_once(':') {
+                if name.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    keys.push(name.to_string());
+                }
+            }
+        }
+        for c in line.chars() {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth < 0 {
+            break;
+        }
+    }
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_turbo_config_schema_matches_the_zod_schema() {
+    let zod = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../packages/next/src/server/config-schema.ts"
+    ))
+    .unwrap();
+    let schema = serde_json::to_value(schemars::schema_for!(ExperimentalTurboConfig)).unwrap();
+    let properties = |schema: &JsonValue| {
+        let mut keys = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    };
+    let definition = |name: &str| &schema["definitions"][name];
+
+    assert_eq!(properties(&schema), zod_object_keys(&zod, "turbo"));
+    assert_eq!(
+        properties(definition("RouteBudget")),
+        zod_object_keys(&zod, "budgets")
+    );
+    assert_eq!(
+        properties(definition("BuildParallelismConfig")),
+        zod_object_keys(&zod, "buildParallelism")
+    );
+    assert_eq!(
+        properties(definition("HermeticConfig")),
+        zod_object_keys(&zod, "hermetic")
+    );
+    assert_eq!(
+        properties(definition("ChunkLoadRetryConfig")),
+        zod_object_keys(&zod, "chunkLoadRetry")
+    );
+    assert_eq!(
+        properties(definition("RpcPluginConfig")),
+        zod_object_keys(&zod, "plugins")
+    );
+}
+
+#[test]
+fn test_turbo_config_schema_describes_the_values() {
+    let schema = serde_json::to_value(schemars::schema_for!(ExperimentalTurboConfig)).unwrap();
+    let enum_values = |name: &str| schema["definitions"][name]["enum"].clone();
+    assert_eq!(
+        enum_values("ModuleIdStrategy"),
+        serde_json::json!(["named", "deterministic"])
+    );
+    assert_eq!(
+        enum_values("HashAlgorithmConfig"),
+        serde_json::json!(["xxh3-64", "xxh3-128", "blake3"])
+    );
+    assert_eq!(
+        schema["definitions"]["ResolveFallbackConfig"]["anyOf"][1],
+        serde_json::json!({ "const": false })
+    );
+    assert_eq!(
+        schema["definitions"]["PersistentCachingConfig"]["anyOf"][1],
+        serde_json::json!({ "const": false })
+    );
+}
+
+#[test]
+fn test_resolve_fallbacks_map_false_to_an_empty_module() {
+    let config: FxIndexMap<RcStr, JsonValue> = serde_json::from_value(serde_json::json!({
+        "fs": false,
+        "crypto": "crypto-browserify",
+    }))
+    .unwrap();
+    assert_eq!(
+        resolve_fallbacks(&config).unwrap(),
+        FxIndexMap::from_iter([
+            ("fs".into(), None),
+            ("crypto".into(), Some("crypto-browserify".into())),
+        ])
+    );
+
+    let config: FxIndexMap<RcStr, JsonValue> =
+        serde_json::from_value(serde_json::json!({ "fs": true })).unwrap();
+    assert!(resolve_fallbacks(&config)
+        .unwrap_err()
+        .to_string()
+        .contains("resolveFallback[\"fs\"]"));
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RuleConfigItemOptions {
     pub loaders: Vec<LoaderItem>,
@@ -423,14 +688,14 @@ pub struct RuleConfigItemOptions {
     pub rename_as: Option<RcStr>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum RuleConfigItemOrShortcut {
     Loaders(Vec<LoaderItem>),
     Advanced(RuleConfigItem),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum RuleConfigItem {
     Options(RuleConfigItemOptions),
@@ -438,7 +703,7 @@ pub enum RuleConfigItem {
     Boolean(bool),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
 #[serde(untagged)]
 pub enum LoaderItem {
     LoaderName(RcStr),
@@ -446,7 +711,7 @@ pub enum LoaderItem {
 }
 
 #[turbo_tasks::value]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ModuleIdStrategy {
     Named,
@@ -456,6 +721,89 @@ pub enum ModuleIdStrategy {
 #[turbo_tasks::value(transparent)]
 pub struct OptionModuleIdStrategy(pub Option<ModuleIdStrategy>);
 
+/// A limit for the size of the client JavaScript and CSS of the routes
+/// matching `route`, which is checked when the routes are emitted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteBudget {
+    /// A glob for the routes the budget applies to, e.g. `/blog/*`.
+    pub route: RcStr,
+    /// The maximum size in bytes.
+    pub raw: Option<u64>,
+    /// The maximum gzip compressed size in bytes.
+    pub gzip: Option<u64>,
+    #[serde(default)]
+    pub severity: BudgetSeverity,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetSeverity {
+    #[default]
+    Warning,
+    Error,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct RouteBudgets(pub Vec<RouteBudget>);
+
+/// The declared inputs of a hermetic build.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HermeticConfig {
+    /// Directories outside of the project directory which can be read,
+    /// relative to the project directory, e.g. `../../packages/ui`.
+    #[serde(default)]
+    pub roots: Vec<RcStr>,
+    /// Env variables which can be read. A trailing `*` matches every
+    /// variable with the prefix, e.g. `NEXT_PUBLIC_*`.
+    #[serde(default)]
+    pub env: Vec<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionHermeticConfig(Option<HermeticConfig>);
+
+/// A plugin process, started in the project directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPluginConfig {
+    pub command: RcStr,
+    #[serde(default)]
+    pub args: Vec<RcStr>,
+}
+
+/// Overrides of the defaults of [ChunkLoadRetry].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkLoadRetryConfig {
+    pub retries: Option<u32>,
+    pub initial_delay_ms: Option<u32>,
+    pub max_delay_ms: Option<u32>,
+    pub on_stale_deployment: Option<StaleDeploymentConfig>,
+}
+
+/// See [StaleDeploymentAction].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StaleDeploymentConfig {
+    Event,
+    Reload,
+}
+
+/// See [HashAlgorithm].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs, JsonSchema)]
+pub enum HashAlgorithmConfig {
+    #[serde(rename = "xxh3-64")]
+    Xxh3Hash64,
+    #[serde(rename = "xxh3-128")]
+    Xxh3Hash128,
+    #[serde(rename = "blake3")]
+    Blake3,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TraceRawVcs)]
 #[serde(untagged)]
 pub enum MdxRsOptions {
@@ -856,6 +1204,10 @@ impl RemoveConsoleConfig {
 #[turbo_tasks::value(transparent)]
 pub struct ResolveExtensions(Option<Vec<RcStr>>);
 
+/// Maps requests to their fallback request, or to `None` for an empty module.
+#[turbo_tasks::value(transparent)]
+pub struct ResolveFallbacks(FxIndexMap<RcStr, Option<RcStr>>);
+
 #[turbo_tasks::value(transparent)]
 pub struct SwcPlugins(Vec<(RcStr, serde_json::Value)>);
 
@@ -1068,6 +1420,30 @@ impl NextConfig {
         Vc::cell(Some(resolve_extensions.clone()))
     }
 
+    #[turbo_tasks::function]
+    pub fn resolve_fallback_options(&self) -> Result<Vc<ResolveFallbacks>> {
+        let Some(resolve_fallback) = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.resolve_fallback.as_ref())
+        else {
+            return Ok(Vc::cell(Default::default()));
+        };
+        Ok(Vc::cell(resolve_fallbacks(resolve_fallback)?))
+    }
+
+    #[turbo_tasks::function]
+    pub fn resolve_module_files(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.resolve_module_files)
+                .unwrap_or_default(),
+        )
+    }
+
     #[turbo_tasks::function]
     pub async fn import_externals(&self) -> Result<Vc<bool>> {
         Ok(Vc::cell(match self.experimental.esm_externals {
@@ -1303,6 +1679,116 @@ impl NextConfig {
         Vc::cell(Some(module_id_strategy.clone()))
     }
 
+    /// Whether the source maps of client chunks are emitted as internal symbol
+    /// maps. Only production builds emit them.
+    #[turbo_tasks::function]
+    pub async fn internal_symbol_maps(&self, mode: Vc<NextMode>) -> Result<Vc<bool>> {
+        let enabled = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.internal_symbol_maps)
+            .unwrap_or(false);
+        Ok(Vc::cell(enabled && matches!(*mode.await?, NextMode::Build)))
+    }
+
+    /// Whether the emitted JSON files are made reproducible. Only production
+    /// builds are.
+    #[turbo_tasks::function]
+    pub async fn deterministic_build(&self, mode: Vc<NextMode>) -> Result<Vc<bool>> {
+        let enabled = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.deterministic)
+            .unwrap_or(false);
+        Ok(Vc::cell(enabled && matches!(*mode.await?, NextMode::Build)))
+    }
+
+    #[turbo_tasks::function]
+    pub fn chunk_load_retry(&self) -> Vc<ChunkLoadRetry> {
+        let config = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.chunk_load_retry.clone())
+            .unwrap_or_default();
+        let default = ChunkLoadRetry::default();
+        ChunkLoadRetry {
+            retries: config.retries.unwrap_or(default.retries),
+            initial_delay_ms: config.initial_delay_ms.unwrap_or(default.initial_delay_ms),
+            max_delay_ms: config.max_delay_ms.unwrap_or(default.max_delay_ms),
+            on_stale_deployment: match config.on_stale_deployment {
+                Some(StaleDeploymentConfig::Event) => StaleDeploymentAction::Event,
+                Some(StaleDeploymentConfig::Reload) => StaleDeploymentAction::Reload,
+                None => default.on_stale_deployment,
+            },
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    pub fn hash_algorithm(&self) -> Vc<HashAlgorithm> {
+        Vc::cell(
+            match self
+                .experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.hash_algorithm)
+            {
+                Some(HashAlgorithmConfig::Xxh3Hash64) => HashAlgorithm::Xxh3Hash64,
+                Some(HashAlgorithmConfig::Xxh3Hash128) => HashAlgorithm::Xxh3Hash128,
+                Some(HashAlgorithmConfig::Blake3) => HashAlgorithm::Blake3,
+                None => HashAlgorithm::default(),
+            },
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn hermetic(&self) -> Vc<OptionHermeticConfig> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.hermetic.clone()),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub async fn rpc_plugins(
+        &self,
+        project_path: ResolvedVc<FileSystemPath>,
+    ) -> Result<Vc<RpcPlugins>> {
+        let Some(plugins) = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.plugins.as_ref())
+        else {
+            return Ok(Vc::cell(vec![]));
+        };
+        let mut rpc_plugins = Vec::with_capacity(plugins.len());
+        for plugin in plugins {
+            rpc_plugins.push(
+                RpcPlugin::new(project_path, plugin.command.clone(), plugin.args.clone())
+                    .to_resolved()
+                    .await?,
+            );
+        }
+        Ok(Vc::cell(rpc_plugins))
+    }
+
+    #[turbo_tasks::function]
+    pub fn route_budgets(&self) -> Vc<RouteBudgets> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.budgets.clone())
+                .unwrap_or_default(),
+        )
+    }
+
     #[turbo_tasks::function]
     pub async fn turbo_minify(&self, mode: Vc<NextMode>) -> Result<Vc<bool>> {
         let minify = self.experimental.turbo.as_ref().and_then(|t| t.minify);
@@ -1349,6 +1835,11 @@ struct OutdatedConfigIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for OutdatedConfigIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::OUTDATED_CONFIG))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Error.into()
@@ -3,6 +3,7 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ResolvedVc, Value, Vc};
 use turbo_tasks_env::EnvMap;
 use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack::resolve_options_context::ResolveOptionsContext;
 use turbopack_browser::BrowserChunkingContext;
 use turbopack_core::{
@@ -20,11 +21,11 @@ use crate::{
     mode::NextMode,
     next_config::NextConfig,
     next_font::local::NextFontLocalResolvePlugin,
-    next_import_map::get_next_edge_import_map,
+    next_import_map::{get_next_edge_import_map, get_next_server_fallback_import_map},
     next_server::context::ServerContextType,
     next_shared::resolve::{
         get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
-        ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
+        get_rpc_resolve_plugins, ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
     },
     util::{foreign_code_context_condition, NextRuntime},
 };
@@ -109,6 +110,10 @@ pub async fn get_edge_resolve_options_context(
         get_next_edge_import_map(*project_path, ty, next_config, execution_context)
             .to_resolved()
             .await?;
+    let next_edge_fallback_import_map =
+        get_next_server_fallback_import_map(*project_path, next_config)
+            .to_resolved()
+            .await?;
 
     let ty: ServerContextType = ty.into_value();
 
@@ -150,6 +155,8 @@ pub async fn get_edge_resolve_options_context(
         ));
     }
 
+    before_resolve_plugins.extend(get_rpc_resolve_plugins(next_config, project_path).await?);
+
     let after_resolve_plugins = vec![ResolvedVc::upcast(
         NextSharedRuntimeResolvePlugin::new(*project_path)
             .to_resolved()
@@ -172,10 +179,13 @@ pub async fn get_edge_resolve_options_context(
 
     let resolve_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
+        enable_pnp: true,
         enable_edge_node_externals: true,
         custom_conditions,
         import_map: Some(next_edge_import_map),
+        fallback_import_map: Some(next_edge_fallback_import_map),
         module: true,
+        enable_module_files: *next_config.resolve_module_files().await?,
         browser: true,
         after_resolve_plugins,
         before_resolve_plugins,
@@ -207,6 +217,7 @@ pub async fn get_edge_chunking_context_with_client_assets(
     environment: ResolvedVc<Environment>,
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     turbo_minify: Vc<bool>,
+    content_hash_algorithm: Vc<HashAlgorithm>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let output_root = node_root.join("server/edge".into()).to_resolved().await?;
     let next_mode = mode.await?;
@@ -230,7 +241,8 @@ pub async fn get_edge_chunking_context_with_client_assets(
             MinifyType::NoMinify
         })
         .module_id_strategy(module_id_strategy)
-        .build(),
+        .content_hash_algorithm(*content_hash_algorithm.await?)
+        .build()?,
     ))
 }
 
@@ -242,6 +254,7 @@ pub async fn get_edge_chunking_context(
     environment: ResolvedVc<Environment>,
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     turbo_minify: Vc<bool>,
+    content_hash_algorithm: Vc<HashAlgorithm>,
 ) -> Result<Vc<Box<dyn ChunkingContext>>> {
     let output_root = node_root.join("server/edge".into()).to_resolved().await?;
     let next_mode = mode.await?;
@@ -266,6 +279,7 @@ pub async fn get_edge_chunking_context(
             MinifyType::NoMinify
         })
         .module_id_strategy(module_id_strategy)
-        .build(),
+        .content_hash_algorithm(*content_hash_algorithm.await?)
+        .build()?,
     ))
 }
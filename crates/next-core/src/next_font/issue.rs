@@ -1,6 +1,8 @@
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 #[turbo_tasks::value(shared)]
 pub(crate) struct NextFontIssue {
@@ -12,6 +14,11 @@ pub(crate) struct NextFontIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for NextFontIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::NEXT_FONT_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn stage(&self) -> Vc<IssueStage> {
         IssueStage::CodeGen.into()
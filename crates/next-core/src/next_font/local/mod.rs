@@ -8,7 +8,7 @@ use turbo_tasks_fs::{
 };
 use turbopack_core::{
     asset::AssetContent,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, StyledString},
+    issue::{Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, StyledString},
     reference_type::ReferenceType,
     resolve::{
         parse::Request,
@@ -325,6 +325,11 @@ struct FontResolvingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for FontResolvingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::NEXT_FONT_NOT_FOUND))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Error.cell()
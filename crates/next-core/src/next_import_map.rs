@@ -21,7 +21,7 @@ use crate::{
     embed_js::{next_js_fs, VIRTUAL_PACKAGE_NAME},
     mode::NextMode,
     next_client::context::ClientContextType,
-    next_config::NextConfig,
+    next_config::{NextConfig, ResolveFallbacks},
     next_edge::unsupported::NextEdgeUnsupportedModuleReplacer,
     next_font::google::{
         NextFontGoogleCssModuleReplacer, NextFontGoogleFontFileReplacer, NextFontGoogleReplacer,
@@ -277,10 +277,19 @@ pub async fn get_next_build_import_map() -> Result<Vc<ImportMap>> {
 /// polyfills to Node.js externals.
 #[turbo_tasks::function]
 pub async fn get_next_client_fallback_import_map(
+    project_path: ResolvedVc<FileSystemPath>,
     ty: Value<ClientContextType>,
+    next_config: Vc<NextConfig>,
 ) -> Result<Vc<ImportMap>> {
     let mut import_map = ImportMap::empty();
 
+    insert_fallback_option(
+        &mut import_map,
+        project_path,
+        next_config.resolve_fallback_options(),
+    )
+    .await?;
+
     match ty.into_value() {
         ClientContextType::Pages {
             pages_dir: context_dir,
@@ -302,6 +311,24 @@ pub async fn get_next_client_fallback_import_map(
     Ok(import_map.cell())
 }
 
+/// Computes the Next-specific server-side fallback import map.
+#[turbo_tasks::function]
+pub async fn get_next_server_fallback_import_map(
+    project_path: ResolvedVc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<ImportMap>> {
+    let mut import_map = ImportMap::empty();
+
+    insert_fallback_option(
+        &mut import_map,
+        project_path,
+        next_config.resolve_fallback_options(),
+    )
+    .await?;
+
+    Ok(import_map.cell())
+}
+
 /// Computes the Next-specific server-side import map.
 #[turbo_tasks::function]
 pub async fn get_next_server_import_map(
@@ -1040,6 +1067,23 @@ pub async fn insert_alias_option<const N: usize>(
     Ok(())
 }
 
+/// Inserts the `experimental.turbo.resolveFallback` entries into a fallback
+/// import map.
+async fn insert_fallback_option(
+    import_map: &mut ImportMap,
+    project_path: ResolvedVc<FileSystemPath>,
+    fallbacks: Vc<ResolveFallbacks>,
+) -> Result<()> {
+    for (request, fallback) in &*fallbacks.await? {
+        let mapping = match fallback {
+            Some(fallback) => request_to_import_mapping(project_path, fallback),
+            None => ImportMapping::Empty.resolved_cell(),
+        };
+        import_map.insert_alias(AliasPattern::parse(request.as_str()), mapping);
+    }
+    Ok(())
+}
+
 fn export_value_to_import_mapping(
     value: &SubpathValue,
     conditions: &BTreeMap<RcStr, ConditionValue>,
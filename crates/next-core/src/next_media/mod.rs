@@ -0,0 +1,4 @@
+pub(crate) mod module;
+pub(crate) mod source_asset;
+
+pub use module::StructuredMediaModuleType;
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use turbo_tasks::{fxindexmap, ResolvedVc, Value, Vc};
+use turbopack::{module_options::CustomModuleType, ModuleAssetContext};
+use turbopack_core::{
+    context::AssetContext, module::Module, reference_type::ReferenceType, resolve::ModulePart,
+    source::Source,
+};
+use turbopack_static::StaticModuleAsset;
+
+use super::source_asset::StructuredMediaFileSource;
+
+/// Module type that analyzes audio and video files and offers some meta
+/// information like duration, dimensions and codecs as export from the module.
+#[turbo_tasks::value]
+pub struct StructuredMediaModuleType {}
+
+#[turbo_tasks::value_impl]
+impl StructuredMediaModuleType {
+    #[turbo_tasks::function]
+    pub(crate) async fn create_module(
+        source: ResolvedVc<Box<dyn Source>>,
+        module_asset_context: ResolvedVc<ModuleAssetContext>,
+    ) -> Result<Vc<Box<dyn Module>>> {
+        let static_asset = StaticModuleAsset::new(*source, Vc::upcast(*module_asset_context))
+            .to_resolved()
+            .await?;
+        Ok(module_asset_context
+            .process(
+                Vc::upcast(StructuredMediaFileSource { media: source }.cell()),
+                Value::new(ReferenceType::Internal(ResolvedVc::cell(fxindexmap!(
+                    "MEDIA".into() => ResolvedVc::upcast(static_asset)
+                )))),
+            )
+            .module())
+    }
+
+    #[turbo_tasks::function]
+    pub fn new() -> Vc<Self> {
+        StructuredMediaModuleType::cell(StructuredMediaModuleType {})
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl CustomModuleType for StructuredMediaModuleType {
+    #[turbo_tasks::function]
+    fn create_module(
+        &self,
+        source: Vc<Box<dyn Source>>,
+        module_asset_context: Vc<ModuleAssetContext>,
+        _part: Option<Vc<ModulePart>>,
+    ) -> Vc<Box<dyn Module>> {
+        StructuredMediaModuleType::create_module(source, module_asset_context)
+    }
+}
@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{rope::RopeBuilder, FileContent};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    source::Source,
+};
+use turbopack_ecmascript::utils::StringifyJs;
+use turbopack_image::process::media::get_media_meta_data;
+
+fn modifier() -> Vc<RcStr> {
+    Vc::cell("structured media object".into())
+}
+
+/// An source asset that transforms an audio or video file into javascript code
+/// which exports an object with meta information like duration, dimensions and
+/// codecs.
+#[turbo_tasks::value(shared)]
+pub struct StructuredMediaFileSource {
+    pub media: ResolvedVc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl Source for StructuredMediaFileSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.media
+            .ident()
+            .with_modifier(modifier())
+            .rename_as("*.mjs".into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for StructuredMediaFileSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let content = self.media.content().await?;
+        let AssetContent::File(content) = *content else {
+            bail!("Input source is not a file and can't be transformed into media information");
+        };
+        let info = get_media_meta_data(self.media.ident(), *content).await?;
+        let mut result = RopeBuilder::from("");
+        writeln!(result, "import src from \"MEDIA\";",)?;
+        writeln!(
+            result,
+            "export default {{ src, duration: {duration}, width: {width}, height: {height}, \
+             mimeType: {mime_type}, codecs: {codecs} }}",
+            // Like `HTMLMediaElement.duration`, the duration is in seconds.
+            duration = StringifyJs(&info.duration_ms.map(|ms| ms as f64 / 1000.0)),
+            width = StringifyJs(&info.width),
+            height = StringifyJs(&info.height),
+            mime_type = StringifyJs(&info.mime_type.as_ref().map(|mime| mime.to_string())),
+            codecs = StringifyJs(&info.codecs),
+        )?;
+        Ok(AssetContent::File(FileContent::Content(result.build().into()).resolved_cell()).cell())
+    }
+}
@@ -5,6 +5,7 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ResolvedVc, Value, Vc};
 use turbo_tasks_env::{EnvMap, ProcessEnv};
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack::{
     module_options::{
         CssOptionsContext, EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext,
@@ -47,12 +48,12 @@ use crate::{
     next_client::RuntimeEntries,
     next_config::NextConfig,
     next_font::local::NextFontLocalResolvePlugin,
-    next_import_map::get_next_server_import_map,
+    next_import_map::{get_next_server_fallback_import_map, get_next_server_import_map},
     next_server::resolve::ExternalPredicate,
     next_shared::{
         resolve::{
             get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
-            ModuleFeatureReportResolvePlugin, NextExternalResolvePlugin,
+            get_rpc_resolve_plugins, ModuleFeatureReportResolvePlugin, NextExternalResolvePlugin,
             NextNodeSharedRuntimeResolvePlugin,
         },
         transforms::{
@@ -135,6 +136,10 @@ pub async fn get_server_resolve_options_context(
         get_next_server_import_map(*project_path, ty, next_config, execution_context)
             .to_resolved()
             .await?;
+    let next_server_fallback_import_map =
+        get_next_server_fallback_import_map(*project_path, next_config)
+            .to_resolved()
+            .await?;
     let foreign_code_context_condition =
         foreign_code_context_condition(next_config, project_path).await?;
     let root_dir = project_path.root().to_resolved().await?;
@@ -311,13 +316,18 @@ pub async fn get_server_resolve_options_context(
         }
     }
 
+    before_resolve_plugins.extend(get_rpc_resolve_plugins(next_config, project_path).await?);
+
     let resolve_options_context = ResolveOptionsContext {
         enable_node_modules: Some(root_dir),
+        enable_pnp: true,
         enable_node_externals: true,
         enable_node_native_modules: true,
         module: true,
+        enable_module_files: *next_config.resolve_module_files().await?,
         custom_conditions,
         import_map: Some(next_server_import_map),
+        fallback_import_map: Some(next_server_fallback_import_map),
         before_resolve_plugins,
         after_resolve_plugins,
         ..Default::default()
@@ -550,6 +560,7 @@ pub async fn get_server_module_options_context(
         } else {
             None
         },
+        rpc_plugins: next_config.rpc_plugins(*project_path).await?.clone_value(),
         ..Default::default()
     };
 
@@ -981,6 +992,7 @@ pub async fn get_server_chunking_context_with_client_assets(
     environment: ResolvedVc<Environment>,
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     turbo_minify: Vc<bool>,
+    content_hash_algorithm: Vc<HashAlgorithm>,
 ) -> Result<Vc<NodeJsChunkingContext>> {
     let next_mode = mode.await?;
     // TODO(alexkirsz) This should return a trait that can be implemented by the
@@ -1008,12 +1020,13 @@ pub async fn get_server_chunking_context_with_client_assets(
         MinifyType::NoMinify
     })
     .module_id_strategy(module_id_strategy)
+    .content_hash_algorithm(*content_hash_algorithm.await?)
     .file_tracing(next_mode.is_production());
 
     if next_mode.is_development() {
         builder = builder.use_file_source_map_uris();
     }
-    Ok(builder.build())
+    builder.build()
 }
 
 #[turbo_tasks::function]
@@ -1024,6 +1037,7 @@ pub async fn get_server_chunking_context(
     environment: ResolvedVc<Environment>,
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     turbo_minify: Vc<bool>,
+    content_hash_algorithm: Vc<HashAlgorithm>,
 ) -> Result<Vc<NodeJsChunkingContext>> {
     let next_mode = mode.await?;
     // TODO(alexkirsz) This should return a trait that can be implemented by the
@@ -1044,11 +1058,12 @@ pub async fn get_server_chunking_context(
         MinifyType::NoMinify
     })
     .module_id_strategy(module_id_strategy)
+    .content_hash_algorithm(*content_hash_algorithm.await?)
     .file_tracing(next_mode.is_production());
 
     if next_mode.is_development() {
         builder = builder.use_file_source_map_uris()
     }
 
-    Ok(builder.build())
+    builder.build()
 }
@@ -4,7 +4,10 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{trace::TraceRawVcs, ResolvedVc, Value, Vc};
 use turbo_tasks_fs::{self, glob::Glob, FileJsonContent, FileSystemPath};
 use turbopack_core::{
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
     resolve::{
         find_context_file,
@@ -463,6 +466,11 @@ struct ExternalizeIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ExternalizeIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::EXTERNALIZE_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Warning.cell()
@@ -8,7 +8,10 @@ use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use turbopack_core::{
     diagnostics::DiagnosticExt,
     file_source::FileSource,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     reference_type::ReferenceType,
     resolve::{
         parse::Request,
@@ -20,7 +23,9 @@ use turbopack_core::{
     },
 };
 
-use crate::{next_server::ServerContextType, next_telemetry::ModuleFeatureTelemetry};
+use crate::{
+    next_config::NextConfig, next_server::ServerContextType, next_telemetry::ModuleFeatureTelemetry,
+};
 
 lazy_static! {
     // Set of the features we want to track, following existing references in webpack/plugins/telemetry-plugin.
@@ -50,6 +55,11 @@ pub struct InvalidImportModuleIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for InvalidImportModuleIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_IMPORT))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Error.into()
@@ -435,3 +445,17 @@ impl AfterResolvePlugin for NextSharedRuntimeResolvePlugin {
         )))
     }
 }
+
+/// The resolve plugins of the plugin processes configured in `next.config.js`.
+pub async fn get_rpc_resolve_plugins(
+    next_config: Vc<NextConfig>,
+    project_path: ResolvedVc<FileSystemPath>,
+) -> Result<Vec<ResolvedVc<Box<dyn BeforeResolvePlugin>>>> {
+    let mut plugins = Vec::new();
+    for plugin in next_config.rpc_plugins(*project_path).await?.iter() {
+        plugins.push(ResolvedVc::upcast(
+            plugin.resolve_plugin().to_resolved().await?,
+        ));
+    }
+    Ok(plugins)
+}
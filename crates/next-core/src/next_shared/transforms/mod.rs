@@ -6,6 +6,7 @@ pub(crate) mod next_cjs_optimizer;
 pub(crate) mod next_disallow_re_export_all_in_page;
 pub(crate) mod next_dynamic;
 pub(crate) mod next_edge_node_api_assert;
+pub(crate) mod next_env_pragmas;
 pub(crate) mod next_font;
 pub(crate) mod next_lint;
 pub(crate) mod next_middleware_dynamic_assert;
@@ -37,7 +38,10 @@ use turbopack::module_options::{ModuleRule, ModuleRuleEffect, ModuleType, RuleCo
 use turbopack_core::reference_type::{ReferenceType, UrlReferenceSubType};
 use turbopack_ecmascript::{CustomTransformer, EcmascriptInputTransform};
 
-use crate::next_image::{module::BlurPlaceholderMode, StructuredImageModuleType};
+use crate::{
+    next_image::{module::BlurPlaceholderMode, StructuredImageModuleType},
+    next_media::StructuredMediaModuleType,
+};
 
 pub async fn get_next_image_rule() -> Result<ModuleRule> {
     Ok(ModuleRule::new(
@@ -73,6 +77,30 @@ pub async fn get_next_image_rule() -> Result<ModuleRule> {
     ))
 }
 
+pub async fn get_next_media_rule() -> Result<ModuleRule> {
+    Ok(ModuleRule::new(
+        RuleCondition::All(vec![
+            // like images, url references need the raw module
+            RuleCondition::not(RuleCondition::ReferenceType(ReferenceType::Url(
+                UrlReferenceSubType::Undefined,
+            ))),
+            RuleCondition::any(vec![
+                RuleCondition::ResourcePathEndsWith(".mp4".to_string()),
+                RuleCondition::ResourcePathEndsWith(".m4v".to_string()),
+                RuleCondition::ResourcePathEndsWith(".m4a".to_string()),
+                RuleCondition::ResourcePathEndsWith(".mov".to_string()),
+                RuleCondition::ResourcePathEndsWith(".webm".to_string()),
+                RuleCondition::ResourcePathEndsWith(".mkv".to_string()),
+                RuleCondition::ResourcePathEndsWith(".mka".to_string()),
+                RuleCondition::ResourcePathEndsWith(".mp3".to_string()),
+            ]),
+        ]),
+        vec![ModuleRuleEffect::ModuleType(ModuleType::Custom(
+            ResolvedVc::upcast(StructuredMediaModuleType::new().to_resolved().await?),
+        ))],
+    ))
+}
+
 fn match_js_extension(enable_mdx_rs: bool) -> Vec<RuleCondition> {
     let mut conditions = vec![
         RuleCondition::ResourcePathEndsWith(".js".to_string()),
@@ -0,0 +1,428 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use swc_core::{
+    base::SwcComments,
+    common::{BytePos, Span, Spanned},
+    ecma::{
+        ast::*,
+        visit::{VisitMut, VisitMutWith},
+    },
+};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack::module_options::ModuleRule;
+use turbopack_core::{
+    file_source::FileSource,
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueCode,
+        OptionIssueSource, OptionStyledString, StyledString,
+    },
+    source::Source,
+};
+use turbopack_ecmascript::{CustomTransformer, TransformContext};
+
+use super::get_ecma_transform_rule;
+
+const PRAGMA: &str = "@turbopack-env";
+
+/// The environment a module is compiled for, which decides which
+/// `// @turbopack-env` regions of it are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PragmaEnv {
+    Client,
+    Node,
+    Edge,
+}
+
+impl PragmaEnv {
+    /// Whether a region for the environment `name` is kept. `server` matches both server
+    /// runtimes. Unknown names never match, and are reported by the transform.
+    fn matches(self, name: &str) -> bool {
+        matches!(
+            (self, name),
+            (PragmaEnv::Client, "client")
+                | (PragmaEnv::Node | PragmaEnv::Edge, "server")
+                | (PragmaEnv::Node, "node")
+                | (PragmaEnv::Edge, "edge")
+        )
+    }
+}
+
+/// Returns a rule which strips the code regions written for other environments:
+///
+/// ```js
+/// // @turbopack-env server
+/// import { readFile } from 'fs'
+/// // @turbopack-env client
+/// const readFile = undefined
+/// // @turbopack-env end
+/// ```
+///
+/// A region starts at a `// @turbopack-env <env>` comment, with `<env>` being `client`, `server`,
+/// `node` or `edge`, and ends at the next pragma or at the end of the file. Statements which
+/// are completely inside a region of another environment are removed before any other
+/// transform runs. The remaining code keeps its spans, so source maps stay correct. A pragma
+/// naming an unknown environment is reported, and its region is stripped everywhere.
+pub fn get_next_env_pragmas_rule(env: PragmaEnv, enable_mdx_rs: bool) -> ModuleRule {
+    get_ecma_transform_rule(Box::new(NextEnvPragmas { env }), enable_mdx_rs, true)
+}
+
+#[derive(Debug)]
+struct NextEnvPragmas {
+    env: PragmaEnv,
+}
+
+#[async_trait]
+impl CustomTransformer for NextEnvPragmas {
+    #[tracing::instrument(level = tracing::Level::TRACE, name = "next_env_pragmas", skip_all)]
+    async fn transform(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
+        let unknown = strip_env_regions(self.env, program, ctx.comments);
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        let source = Vc::upcast::<Box<dyn Source>>(FileSource::new(*ctx.file_path));
+        let issue_source = |span: Span| {
+            IssueSource::from_swc_offsets(source, span.lo.to_usize(), span.hi.to_usize())
+        };
+        for pragma in unknown {
+            let mut pragma_source =
+                issue_source(pragma.span).with_label("unknown environment".into());
+            if let Some(region_end) = pragma.region_end {
+                pragma_source = pragma_source.with_related(
+                    issue_source(region_end)
+                        .with_label("the code up to here is removed in every environment".into())
+                        .to_resolved()
+                        .await?,
+                );
+            }
+            InvalidEnvPragmaIssue {
+                file_path: ctx.file_path,
+                name: pragma.name.into(),
+                source: pragma_source.to_resolved().await?,
+            }
+            .cell()
+            .emit();
+        }
+        Ok(())
+    }
+}
+
+/// Returns the environment name of a `@turbopack-env <env>` comment.
+fn env_pragma(comment: &str) -> Option<&str> {
+    let rest = comment.trim().strip_prefix(PRAGMA)?;
+    // Don't match e.g. `@turbopack-environment`.
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// A pragma naming an unknown environment.
+#[derive(Debug, PartialEq, Eq)]
+struct UnknownPragma {
+    name: String,
+    /// The span of the pragma comment.
+    span: Span,
+    /// The span of the next pragma, which ends the region that is removed in every
+    /// environment. `None` when the region lasts until the end of the file.
+    region_end: Option<Span>,
+}
+
+/// Removes the statements in regions of other environments than `env`, and
+/// returns the pragmas naming unknown environments.
+fn strip_env_regions(
+    env: PragmaEnv,
+    program: &mut Program,
+    comments: &SwcComments,
+) -> Vec<UnknownPragma> {
+    let mut pragmas = vec![];
+    for comments in [&comments.leading, &comments.trailing] {
+        for entry in comments.iter() {
+            for comment in entry.value() {
+                if let Some(name) = env_pragma(&comment.text) {
+                    pragmas.push((comment.span, name.to_string()));
+                }
+            }
+        }
+    }
+    if pragmas.is_empty() {
+        return vec![];
+    }
+    pragmas.sort_by_key(|(span, _)| span.lo);
+
+    let mut stripped = vec![];
+    let mut unknown: Vec<UnknownPragma> = vec![];
+    let mut open: Option<(BytePos, bool)> = None;
+    for (span, name) in pragmas {
+        if let Some((start, false)) = open.take() {
+            stripped.push((start, span.lo));
+        }
+        if let Some(last) = unknown.last_mut().filter(|last| last.region_end.is_none()) {
+            last.region_end = Some(span);
+        }
+        if name == "end" {
+            continue;
+        }
+        if !matches!(&*name, "client" | "server" | "node" | "edge") {
+            unknown.push(UnknownPragma {
+                name: name.clone(),
+                span,
+                region_end: None,
+            });
+        }
+        open = Some((span.lo, env.matches(&name)));
+    }
+    if let Some((start, false)) = open {
+        stripped.push((start, BytePos(u32::MAX)));
+    }
+
+    if !stripped.is_empty() {
+        program.visit_mut_with(&mut StripRegions { stripped });
+    }
+    unknown
+}
+
+struct StripRegions {
+    /// Sorted, non-overlapping ranges of source positions.
+    stripped: Vec<(BytePos, BytePos)>,
+}
+
+impl StripRegions {
+    fn is_stripped(&self, node: &impl Spanned) -> bool {
+        let span = node.span();
+        self.stripped
+            .iter()
+            .any(|&(start, end)| start <= span.lo && span.hi <= end)
+    }
+}
+
+impl VisitMut for StripRegions {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.retain(|item| !self.is_stripped(item));
+        items.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.retain(|stmt| !self.is_stripped(stmt));
+        stmts.visit_mut_children_with(self);
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct InvalidEnvPragmaIssue {
+    file_path: ResolvedVc<FileSystemPath>,
+    name: RcStr,
+    source: ResolvedVc<IssueSource>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for InvalidEnvPragmaIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_ENV_PRAGMA))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Transform.into()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Line(vec![
+            StyledString::Text("Unknown environment in ".into()),
+            StyledString::Code(format!("// {PRAGMA} {}", self.name).into()),
+        ])
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.file_path
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(Some(self.source.resolve_source_map(*self.file_path)))
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                "The environment must be `client`, `server`, `node` or `edge`, or `end` to end \
+                 the region. The code up to the next pragma is removed in every environment."
+                    .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_core::ecma::parser::{Parser, StringInput, Syntax};
+
+    use super::*;
+
+    /// The names of the top level variables kept for `env`, and the pragmas
+    /// naming unknown environments.
+    fn strip(env: PragmaEnv, source: &str) -> (Vec<String>, Vec<UnknownPragma>) {
+        let comments = SwcComments::default();
+        let input = StringInput::new(source, BytePos(1), BytePos(1 + source.len() as u32));
+        let module = Parser::new(Syntax::Es(Default::default()), input, Some(&comments))
+            .parse_module()
+            .unwrap();
+        let mut program = Program::Module(module);
+        let unknown = strip_env_regions(env, &mut program, &comments);
+        let Program::Module(module) = program else {
+            unreachable!()
+        };
+        let kept = module
+            .body
+            .iter()
+            .map(|item| {
+                let decl = item.as_stmt().unwrap().as_decl().unwrap().as_var().unwrap();
+                decl.decls[0].name.as_ident().unwrap().sym.to_string()
+            })
+            .collect();
+        (kept, unknown)
+    }
+
+    /// The span of the first occurrence of `text` in `source`, as parsed by
+    /// [strip].
+    fn span_of(source: &str, text: &str) -> Span {
+        let start = 1 + source.find(text).unwrap() as u32;
+        Span::new(BytePos(start), BytePos(start + text.len() as u32))
+    }
+
+    const SOURCE: &str = "
+        const shared = 1;
+        // @turbopack-env server
+        const server = 1;
+        // @turbopack-env node
+        const node = 1;
+        // @turbopack-env edge
+        const edge = 1;
+        // @turbopack-env client
+        const client = 1;
+        // @turbopack-env end
+        const after = 1;
+    ";
+
+    #[test]
+    fn keeps_the_regions_of_the_environment() {
+        assert_eq!(
+            strip(PragmaEnv::Client, SOURCE),
+            (
+                vec!["shared".into(), "client".into(), "after".into()],
+                vec![]
+            )
+        );
+        assert_eq!(
+            strip(PragmaEnv::Node, SOURCE),
+            (
+                vec![
+                    "shared".into(),
+                    "server".into(),
+                    "node".into(),
+                    "after".into()
+                ],
+                vec![]
+            )
+        );
+        assert_eq!(
+            strip(PragmaEnv::Edge, SOURCE),
+            (
+                vec![
+                    "shared".into(),
+                    "server".into(),
+                    "edge".into(),
+                    "after".into()
+                ],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn region_without_end_lasts_until_the_end_of_the_file() {
+        let source = "
+            const shared = 1;
+            // @turbopack-env server
+            const server = 1;
+            const alsoServer = 1;
+        ";
+        assert_eq!(
+            strip(PragmaEnv::Client, source),
+            (vec!["shared".into()], vec![])
+        );
+        assert_eq!(
+            strip(PragmaEnv::Node, source),
+            (
+                vec!["shared".into(), "server".into(), "alsoServer".into()],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn reports_unknown_environments() {
+        let source = "
+            // @turbopack-env sever
+            const server = 1;
+            // @turbopack-env end
+            const after = 1;
+        ";
+        assert_eq!(
+            strip(PragmaEnv::Node, source),
+            (
+                vec!["after".into()],
+                vec![UnknownPragma {
+                    name: "sever".into(),
+                    span: span_of(source, "// @turbopack-env sever"),
+                    region_end: Some(span_of(source, "// @turbopack-env end")),
+                }]
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_environment_without_end_lasts_until_the_end_of_the_file() {
+        let source = "
+            // @turbopack-env server
+            const server = 1;
+            // @turbopack-env clent
+            const client = 1;
+        ";
+        assert_eq!(
+            strip(PragmaEnv::Node, source),
+            (
+                vec!["server".into()],
+                vec![UnknownPragma {
+                    name: "clent".into(),
+                    span: span_of(source, "// @turbopack-env clent"),
+                    region_end: None,
+                }]
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_other_comments_with_the_prefix() {
+        let source = "
+            // @turbopack-environment server
+            const kept = 1;
+        ";
+        assert_eq!(
+            strip(PragmaEnv::Client, source),
+            (vec!["kept".into()], vec![])
+        );
+    }
+}
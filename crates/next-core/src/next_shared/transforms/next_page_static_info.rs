@@ -9,7 +9,8 @@ use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack::module_options::{ModuleRule, ModuleRuleEffect};
 use turbopack_core::issue::{
-    Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString,
+    Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+    StyledString,
 };
 use turbopack_ecmascript::{CustomTransformer, EcmascriptInputTransform, TransformContext};
 
@@ -137,6 +138,11 @@ pub struct PageStaticInfoIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for PageStaticInfoIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::PAGE_STATIC_INFO_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         self.severity.into()
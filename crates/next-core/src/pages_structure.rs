@@ -4,6 +4,7 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, TryJoinIterExt, ValueToString, Vc};
 use turbo_tasks_fs::{
     DirectoryContent, DirectoryEntry, FileSystemEntryType, FileSystemPath, FileSystemPathOption,
+    WalkDirOptions,
 };
 
 use crate::next_import_map::get_next_package;
@@ -137,6 +138,13 @@ pub async fn find_pages_structure(
             None
         }
     };
+    if let Some(pages_root) = pages_root {
+        // Walking the pages directory reads all of its subdirectories in
+        // parallel, so the structure below is built from cached reads.
+        pages_root
+            .walk_dir(WalkDirOptions::default().cell())
+            .await?;
+    }
 
     Ok(get_pages_structure_for_root_directory(
         project_root,
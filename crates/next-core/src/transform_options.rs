@@ -41,18 +41,26 @@ pub async fn get_typescript_transform_options(
 ) -> Result<Vc<TypescriptTransformOptions>> {
     let tsconfig = get_typescript_options(project_path).await?;
 
-    let use_define_for_class_fields = if let Some(tsconfig) = tsconfig {
-        read_from_tsconfigs(&tsconfig, |json, _| {
-            json["compilerOptions"]["useDefineForClassFields"].as_bool()
-        })
-        .await?
-        .unwrap_or(false)
+    let (use_define_for_class_fields, verbatim_module_syntax) = if let Some(tsconfig) = tsconfig {
+        (
+            read_from_tsconfigs(&tsconfig, |json, _| {
+                json["compilerOptions"]["useDefineForClassFields"].as_bool()
+            })
+            .await?
+            .unwrap_or(false),
+            read_from_tsconfigs(&tsconfig, |json, _| {
+                json["compilerOptions"]["verbatimModuleSyntax"].as_bool()
+            })
+            .await?
+            .unwrap_or(false),
+        )
     } else {
-        false
+        (false, false)
     };
 
     let ts_transform_options = TypescriptTransformOptions {
         use_define_for_class_fields,
+        verbatim_module_syntax,
     };
 
     Ok(ts_transform_options.cell())
@@ -17,7 +17,10 @@ use turbopack_core::{
     asset::AssetContent,
     condition::ContextCondition,
     ident::AssetIdent,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     module::Module,
     source::Source,
     virtual_source::VirtualSource,
@@ -202,6 +205,11 @@ pub struct NextSourceConfigParsingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for NextSourceConfigParsingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_SOURCE_CONFIG))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Warning.into()
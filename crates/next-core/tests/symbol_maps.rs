@@ -0,0 +1,102 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::fs;
+
+use next_core::emit_symbol_maps;
+use turbo_tasks::{apply_effects, ResolvedVc, TurboTasks, Vc};
+use turbo_tasks_fs::{DiskFileSystem, File, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    output::{OutputAsset, OutputAssets},
+    source_map::{GenerateSourceMap, OptionSourceMap, SourceMap},
+};
+
+fn register() {
+    next_core::register();
+    include!(concat!(env!("OUT_DIR"), "/register_test_symbol_maps.rs"));
+}
+
+/// A chunk with an empty source map.
+#[turbo_tasks::value]
+struct TestChunk {
+    path: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for TestChunk {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for TestChunk {
+    #[turbo_tasks::function]
+    fn content(&self) -> Vc<AssetContent> {
+        AssetContent::file(File::from("console.log(1)").into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GenerateSourceMap for TestChunk {
+    #[turbo_tasks::function]
+    fn generate_source_map(&self) -> Vc<OptionSourceMap> {
+        Vc::cell(Some(SourceMap::empty()))
+    }
+}
+
+#[tokio::test]
+async fn symbol_maps_of_client_chunks_are_emitted_below_the_root() {
+    register();
+    let dir = std::env::temp_dir().join("next-core-symbol-maps");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let dir_path = dir.clone();
+    tt.run_once(async move {
+        // Like in a Next.js project, client chunks are in a virtual file system.
+        let client_root = VirtualFileSystem::new_with_name("client-fs".into()).root();
+        let node_root =
+            DiskFileSystem::new("output".into(), dir_path.to_string_lossy().into(), vec![])
+                .root()
+                .join(".next".into());
+        let chunk = |path: Vc<FileSystemPath>| async move {
+            anyhow::Ok(ResolvedVc::upcast::<Box<dyn OutputAsset>>(
+                TestChunk {
+                    path: path.to_resolved().await?,
+                }
+                .resolved_cell(),
+            ))
+        };
+        let assets = Vc::<OutputAssets>::cell(vec![
+            chunk(client_root.join("_next/static/chunks/a.js".into())).await?,
+            chunk(node_root.join("server/chunks/b.js".into())).await?,
+        ]);
+        let symbol_maps_root = node_root.join("server/symbol-maps".into());
+
+        let emit = emit_symbol_maps(assets, client_root.join("_next".into()), symbol_maps_root);
+        emit.strongly_consistent().await?;
+        apply_effects(emit).await?;
+
+        // The map is found by the path of the chunk relative to the client root, which is how
+        // stack frames of client chunks are mapped when the chunk has no source map.
+        let map = SourceMap::new_from_file(symbol_maps_root.join("static/chunks/a.js.map".into()))
+            .await?;
+        assert!(map.is_some());
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    // Only client chunks get a symbol map.
+    let symbol_maps = dir.join(".next/server/symbol-maps");
+    assert!(symbol_maps.join("static/chunks/a.js.map").is_file());
+    assert_eq!(fs::read_dir(&symbol_maps).unwrap().count(), 1);
+    assert!(!dir.join(".next/server/chunks").exists());
+}
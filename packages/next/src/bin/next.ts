@@ -141,6 +141,10 @@ program
     '--experimental-upload-trace, <traceUrl>',
     'Reports a subset of the debugging trace to a remote HTTP URL. Includes sensitive data.'
   )
+  .option(
+    '--experimental-turbo-dry-run',
+    'Reports how much of a Turbopack build would come from the persistent cache, without building.'
+  )
   .action((directory: string, options: NextBuildOptions) =>
     // ensure process exits after build completes so open handles/connections
     // don't cause process to hang
@@ -106,6 +106,7 @@ import {
   EVENT_BUILD_FEATURE_USAGE,
   eventPackageUsedInGetServerSideProps,
   eventBuildCompleted,
+  eventTurbopackBuildTelemetry,
 } from '../telemetry/events'
 import type { EventBuildFeatureUsage } from '../telemetry/events'
 import { Telemetry } from '../telemetry/storage'
@@ -1466,9 +1467,28 @@ export default async function build(
           {
             persistentCaching: isPersistentCachingEnabled(config),
             memoryLimit: config.experimental.turbo?.memoryLimit,
+            memoryTarget: config.experimental.turbo?.memoryTarget,
+            chunkingParallelism:
+              config.experimental.turbo?.buildParallelism?.chunking,
+            emitParallelism: config.experimental.turbo?.buildParallelism?.emit,
           }
         )
 
+        // Reports what the build would take from the persistent cache instead
+        // of building, e.g. for CI to decide whether a build is needed. This
+        // needs to happen before anything is computed with the project.
+        if (process.env.NEXT_TURBOPACK_DRY_RUN) {
+          const report = await project.buildDryRun()
+          await project.shutdown()
+          Log.info(report.summary.trimEnd())
+          await fs.mkdir(distDir, { recursive: true })
+          await fs.writeFile(
+            path.join(distDir, 'turbopack-dry-run.json'),
+            JSON.stringify(report, null, 2)
+          )
+          process.exit(0)
+        }
+
         await fs.mkdir(path.join(distDir, 'server'), { recursive: true })
         await fs.mkdir(path.join(distDir, 'static', buildId), {
           recursive: true,
@@ -1552,8 +1572,9 @@ export default async function build(
         // Concurrency will start at INITIAL_CONCURRENCY and
         // slowly ramp up to CONCURRENCY by increasing the
         // concurrency by 1 every time a task is completed.
-        const INITIAL_CONCURRENCY = 5
-        const CONCURRENCY = 10
+        const CONCURRENCY =
+          config.experimental.turbo?.buildParallelism?.endpoints ?? 10
+        const INITIAL_CONCURRENCY = Math.min(5, CONCURRENCY)
 
         const sema = new Sema(INITIAL_CONCURRENCY)
         let remainingRampup = CONCURRENCY - INITIAL_CONCURRENCY
@@ -1657,6 +1678,38 @@ export default async function build(
           }
         }
 
+        // Like the bundle diff, only a successful build becomes the one the
+        // route sizes of the next build are compared against.
+        const routeBudgets = await project.checkRouteBudgets(errors.length === 0)
+        for (const issue of routeBudgets.issues) {
+          const target = issue.severity === 'warning' ? warnings : errors
+          target.push({ page: 'route budgets', message: formatIssue(issue) })
+        }
+
+        if (config.experimental.turbo?.fingerprintManifest) {
+          await project.writeFingerprintManifest()
+        }
+
+        // Only a successful build becomes the one the next build is compared
+        // against. This needs to happen before the project is shut down.
+        const bundleDiff = await project.bundleDiff(errors.length === 0)
+        // Without a previous build every route counts as added, which isn't
+        // worth reporting.
+        const hasBundleDiffBaseline = bundleDiff.previousBuildId != null
+        if (hasBundleDiffBaseline) {
+          await fs.writeFile(
+            path.join(distDir, 'bundle-diff.json'),
+            bundleDiff.json
+          )
+        }
+
+        // Modules are only sampled when enabled with
+        // `NEXT_TURBOPACK_BUILD_TELEMETRY=<percentage>`.
+        const buildTelemetry = project.buildTelemetryReport(true)
+        if (buildTelemetry) {
+          telemetry.record(eventTurbopackBuildTelemetry(buildTelemetry))
+        }
+
         const shutdownPromise = project.shutdown()
 
         if (warnings.length > 0) {
@@ -1679,6 +1732,10 @@ export default async function build(
           )
         }
 
+        if (hasBundleDiffBaseline) {
+          Log.info(bundleDiff.table.trimEnd())
+        }
+
         const time = process.hrtime(startTime)
         return {
           duration: time[0] + time[1] / 1e9,
@@ -177,18 +177,63 @@ export interface NapiTurboEngineOptions {
   persistentCaching?: boolean
   /** An upper bound of memory that turbopack will attempt to stay under. */
   memoryLimit?: number
+  /**
+   * The memory usage in bytes above which the persistent caching backend
+   * drops the data of tasks that weren't accessed recently. It's restored
+   * from the cache when needed again.
+   */
+  memoryTarget?: number
+  /** Overrides for feature flags of experimental code paths, by flag name. */
+  featureFlags?: Record<string, string>
+  /**
+   * The maximum number of chunk groups whose modules are collected at the
+   * same time.
+   */
+  chunkingParallelism?: number
+  /**
+   * The maximum number of output assets generated and written at the same
+   * time.
+   */
+  emitParallelism?: number
 }
 export function projectNew(
   options: NapiProjectOptions,
   turboEngineOptions: NapiTurboEngineOptions
 ): Promise<{ __napiType: 'Project' }>
+/**
+ * Creates another project in the turbo-tasks instance of `project`, e.g. for
+ * another app in the same monorepo. The projects share the task cache, the
+ * persistent cache and the file watcher, but have their own options, so
+ * modules are compiled separately for each project. Fails when `project` is
+ * shut down already.
+ */
+export function projectNewSibling(
+  project: { __napiType: 'Project' },
+  options: NapiProjectOptions
+): Promise<{ __napiType: 'Project' }>
 export function projectUpdate(
   project: { __napiType: 'Project' },
   options: NapiPartialProjectOptions
 ): Promise<void>
-export function projectShutdown(project: {
-  __napiType: 'Project'
-}): Promise<void>
+/**
+ * Shuts the project down. The last project of a turbo-tasks instance also
+ * stops the instance: it stops accepting work, drains in-flight tasks (or
+ * abandons them after `drain_timeout_ms`), flushes the persistent cache and
+ * then stops the file watchers.
+ */
+export function projectShutdown(
+  project: { __napiType: 'Project' },
+  drainTimeoutMs?: number | undefined | null
+): Promise<void>
+/**
+ * Hints routes the user is likely to navigate to next. They are compiled in
+ * the background once the project has been idle for a few seconds, so the
+ * navigation doesn't need to wait for the compilation.
+ */
+export function projectNavigationHints(
+  project: { __napiType: 'Project' },
+  pathnames: Array<string>
+): Promise<void>
 export interface AppPageNapiRoute {
   /** The relative path from project_path to the route file */
   originalName?: string
@@ -239,6 +284,22 @@ export function projectHmrIdentifiersSubscribe(
   project: { __napiType: 'Project' },
   func: (...args: any[]) => any
 ): { __napiType: 'RootTask' }
+export interface NapiEntrypointChanges {
+  added: Array<string>
+  changed: Array<string>
+  removed: Array<string>
+}
+/**
+ * Builds all entrypoints and writes them to disk, and does so again whenever
+ * the output changes. Combined with a production project with `watch`
+ * enabled, this gives `next build --watch` semantics. Each build reports the
+ * endpoints whose output was added, changed or removed since the previous
+ * one.
+ */
+export function projectBuildWatchSubscribe(
+  project: { __napiType: 'Project' },
+  func: (...args: any[]) => any
+): { __napiType: 'RootTask' }
 export interface NapiUpdateMessage {
   updateType: string
   value?: NapiUpdateInfo
@@ -285,11 +346,162 @@ export function projectGetSourceMap(
   project: { __napiType: 'Project' },
   filePath: string
 ): Promise<string | null>
-/** Runs exit handlers for the project registered using the [`ExitHandler`] API. */
+export interface NapiEmittedAsset {
+  /** Relative to the dist dir. */
+  path: string
+  size: number
+  contentHash: string
+  /** The names of the endpoints whose output contains the asset. */
+  endpoints: Array<string>
+  /**
+   * The paths of the source modules in the asset, when it is a chunk,
+   * relative to the project directory when they are inside of it.
+   */
+  modules: Array<string>
+}
+/**
+ * Lists every asset written by `writeAllEntrypointsToDisk`, with the
+ * endpoints it belongs to and the modules it contains.
+ */
+export function projectEmittedAssets(project: {
+  __napiType: 'Project'
+}): Promise<Array<NapiEmittedAsset>>
+/**
+ * Writes `fingerprint-manifest.json` to the dist dir, which maps the logical
+ * path of every written asset to its fingerprinted path, content hash, size
+ * and referenced assets, for differential CDN uploads.
+ */
+export function projectWriteFingerprintManifest(project: {
+  __napiType: 'Project'
+}): Promise<void>
+/**
+ * The environments `project_trace_resolve` can trace resolves in, e.g.
+ * `app-rsc` or `pages-edge-api`.
+ */
+export function projectResolveTraceEnvironments(project: {
+  __napiType: 'Project'
+}): Promise<Array<string>>
+export interface NapiResolveTraceOptions {
+  /** One of `project_resolve_trace_environments`. */
+  environment: string
+  specifier: string
+  /** The importing file, relative to the project directory. */
+  importer: string
+  /** `import` (the default), `require` or `url`. */
+  kind?: string
+}
+/**
+ * Resolves a specifier like the given environment would and returns every
+ * step the resolver took as JSON: the aliases tried, the extensions probed,
+ * the exports conditions evaluated and the plugins consulted.
+ */
+export function projectTraceResolve(
+  project: { __napiType: 'Project' },
+  options: NapiResolveTraceOptions
+): Promise<string>
+export interface NapiBundleDiff {
+  /** The comparison of every route with the previous build, as JSON. */
+  json: string
+  /** The routes which changed, as a table. */
+  table: string
+  /** The build compared against, if there was a previous successful build. */
+  previousBuildId?: string
+}
+/**
+ * Compares the output size and the npm packages of every route with the last
+ * successful build. With `persist`, this build is stored to be compared
+ * against by the next one, which should only be done when it succeeded.
+ */
+export function projectBundleDiff(
+  project: { __napiType: 'Project' },
+  persist: boolean
+): Promise<NapiBundleDiff>
+/**
+ * Reports the route budgets exceeded by this build, compared to the last
+ * successful build. With `persist`, this build is stored to be compared
+ * against by the next one, which should only be done when it succeeded.
+ */
+export function projectCheckRouteBudgets(
+  project: { __napiType: 'Project' },
+  persist: boolean
+): Promise<TurbopackResult>
+export interface NapiDryRunReport {
+  /** The number of tasks in the persistent cache. */
+  tasks: number
+  /** The number of cached tasks which wouldn't need to be executed again. */
+  cacheHits: number
+  /** `cacheHits / tasks`, `0` when nothing is cached. */
+  hitRate: number
+  /** The inputs, e.g. file reads, which changed since they were cached. */
+  changedInputs: Array<string>
+  /** The report, as text. */
+  summary: string
+}
+/**
+ * Reports how much of the build can be taken from the persistent cache,
+ * without building. Only the inputs of the cached tasks, e.g. file reads, are
+ * executed again to find out whether they changed. Needs to be called before
+ * anything else is computed with the project.
+ */
+export function projectBuildDryRun(project: {
+  __napiType: 'Project'
+}): Promise<NapiDryRunReport>
+/**
+ * Returns the JSON schema of `experimental.turbo` in `next.config.js`, for
+ * validating the config and for editor autocompletion.
+ */
+export function turbopackConfigSchema(): string
+export interface NapiAllocatorStats {
+  /** Bytes currently allocated. */
+  allocated: number
+  /**
+   * Bytes the allocator has committed from the OS. Not reported by the system
+   * allocator.
+   */
+  committed?: number
+  /** Bytes committed by the allocator that aren't allocated. */
+  retained?: number
+  /** The share of committed memory that isn't allocated, between 0 and 1. */
+  fragmentation?: number
+  /** The resident set size of the process in bytes. */
+  resident?: number
+  /** The peak resident set size of the process in bytes. */
+  peakResident?: number
+}
+/**
+ * Returns statistics of the memory allocator, which is shared by all projects
+ * of the process.
+ */
+export function allocatorStats(): NapiAllocatorStats
+/**
+ * Returns memory the allocator retains for future allocations to the OS, e.g.
+ * after a large rebuild. Returns the statistics after purging.
+ */
+export function allocatorPurge(): NapiAllocatorStats
+/**
+ * Starts collecting the compile times of a sample of all modules, including the
+ * time of every transform. `sample_rate` is the share of sampled modules,
+ * between 0 and 1. Collecting is global to the process and can't be disabled
+ * again, and only the first call sets the sample rate. Can also be enabled with
+ * `NEXT_TURBOPACK_BUILD_TELEMETRY=<percentage>`.
+ */
+export function enableBuildTelemetrySampling(sampleRate: number): void
+/**
+ * Returns the compile times of the sampled modules as JSON, with modules
+ * anonymized, or `null` when sampling isn't enabled. With `clear`, collecting
+ * starts over afterwards.
+ */
+export function buildTelemetryReport(clear: boolean): string | null
+/**
+ * Runs exit handlers for the project registered using the [`ExitHandler`] API.
+ * Projects sharing a turbo-tasks instance exit together: the first call runs
+ * the handlers and the other calls wait for them to finish.
+ */
 export function projectOnExit(project: { __napiType: 'Project' }): Promise<void>
 export function rootTaskDispose(rootTask: { __napiType: 'RootTask' }): void
 export interface NapiIssue {
   severity: string
+  code?: string
   stage: string
   filePath: string
   title: any
@@ -298,10 +510,26 @@ export interface NapiIssue {
   source?: NapiIssueSource
   documentationLink: string
   subIssues: Array<NapiIssue>
+  suggestedFix?: NapiSuggestedFix
+  transform?: string
+  importChain: Array<string>
+  environment?: string
 }
 export interface NapiIssueSource {
   source: NapiSource
   range?: NapiIssueSourceRange
+  label?: string
+  related: Array<NapiIssueSource>
+}
+export interface NapiSuggestedFix {
+  description: string
+  edits: Array<NapiTextEdit>
+}
+export interface NapiTextEdit {
+  filePath: string
+  start: NapiSourcePos
+  end: NapiSourcePos
+  replacement: string
 }
 export interface NapiIssueSourceRange {
   start: NapiSourcePos
@@ -370,6 +598,29 @@ export interface NapiRewrite {
   has?: Array<NapiRouteHas>
   missing?: Array<NapiRouteHas>
 }
+export interface NapiLibraryBuildOptions {
+  /** The absolute path of the package. */
+  dir: string
+  /** The absolute path of the workspace root. Defaults to `dir`. */
+  root?: string
+  /** The entries, relative to `dir`. */
+  entries: Array<string>
+  /** The module format of the entries, one of `cjs`, `esm` or `umd`. */
+  format: string
+  /**
+   * The name of the library in the error of the `umd` format without a
+   * module loader. Defaults to the file name of the entry.
+   */
+  name?: string
+  minify?: boolean
+}
+/**
+ * Bundles a package. The output is written to `dist` in the package
+ * directory.
+ */
+export function turbopackLibraryBuild(
+  options: NapiLibraryBuildOptions
+): Promise<void>
 export function getTargetTriple(): string
 export function initHeapProfiler(): ExternalObject<RefCell>
 export function teardownHeapProfiler(
@@ -28,11 +28,19 @@ import type {
 } from './generated-native'
 import type {
   Binding,
+  AllocatorStats,
+  BuildTelemetryReport,
+  BundleDiff,
   DefineEnv,
+  DryRunReport,
+  EmittedAsset,
   Endpoint,
+  EntrypointChanges,
   HmrIdentifiers,
   Project,
   ProjectOptions,
+  ResolveTrace,
+  ResolveTraceOptions,
   Route,
   TurboEngineOptions,
   TurbopackResult,
@@ -769,8 +777,78 @@ function bindingToApi(
       )
     }
 
-    shutdown(): Promise<void> {
-      return binding.projectShutdown(this._nativeProject)
+    buildWatchSubscribe() {
+      return subscribe<TurbopackResult<EntrypointChanges>>(
+        false,
+        async (callback) =>
+          binding.projectBuildWatchSubscribe(this._nativeProject, callback)
+      )
+    }
+
+    writeFingerprintManifest(): Promise<void> {
+      return binding.projectWriteFingerprintManifest(this._nativeProject)
+    }
+
+    emittedAssets(): Promise<EmittedAsset[]> {
+      return binding.projectEmittedAssets(this._nativeProject)
+    }
+
+    bundleDiff(persist: boolean): Promise<BundleDiff> {
+      return binding.projectBundleDiff(this._nativeProject, persist)
+    }
+
+    checkRouteBudgets(persist: boolean): Promise<TurbopackResult> {
+      return binding.projectCheckRouteBudgets(this._nativeProject, persist)
+    }
+
+    buildDryRun(): Promise<DryRunReport> {
+      return binding.projectBuildDryRun(this._nativeProject)
+    }
+
+    resolveTraceEnvironments(): Promise<string[]> {
+      return binding.projectResolveTraceEnvironments(this._nativeProject)
+    }
+
+    async traceResolve(options: ResolveTraceOptions): Promise<ResolveTrace> {
+      return JSON.parse(
+        await binding.projectTraceResolve(this._nativeProject, options)
+      )
+    }
+
+    navigationHints(pathnames: string[]): Promise<void> {
+      return binding.projectNavigationHints(this._nativeProject, pathnames)
+    }
+
+    async createSiblingProject(options: ProjectOptions): Promise<Project> {
+      return new ProjectImpl(
+        await withErrorCause(async () =>
+          binding.projectNewSibling(
+            this._nativeProject,
+            await rustifyProjectOptions(options)
+          )
+        )
+      )
+    }
+
+    shutdown(drainTimeoutMs?: number): Promise<void> {
+      return binding.projectShutdown(this._nativeProject, drainTimeoutMs)
+    }
+
+    allocatorStats(): AllocatorStats {
+      return binding.allocatorStats()
+    }
+
+    purgeMemory(): AllocatorStats {
+      return binding.allocatorPurge()
+    }
+
+    enableBuildTelemetry(sampleRate: number): void {
+      binding.enableBuildTelemetrySampling(sampleRate)
+    }
+
+    buildTelemetryReport(clear = false): BuildTelemetryReport | undefined {
+      const report = binding.buildTelemetryReport(clear)
+      return report === null ? undefined : JSON.parse(report)
     }
 
     onExit(): Promise<void> {
@@ -1065,6 +1143,11 @@ async function loadWasm(importPath = '') {
               '`turbo.startTurbopackTraceServer` is not supported by the wasm bindings.'
             )
           },
+          getConfigSchema: function (): object {
+            throw new Error(
+              '`turbo.getConfigSchema` is not supported by the wasm bindings.'
+            )
+          },
         },
         mdx: {
           compile(src: string, options: any) {
@@ -1226,6 +1309,9 @@ function loadNative(importPath?: string) {
           )
           ;(customBindings ?? bindings).startTurbopackTraceServer(traceFilePath)
         },
+        getConfigSchema() {
+          return JSON.parse((customBindings ?? bindings).turbopackConfigSchema())
+        },
       },
       mdx: {
         compile(src: string, options: any) {
@@ -10,6 +10,11 @@ export interface Binding {
       turboEngineOptions?: TurboEngineOptions
     ): Promise<Project>
     startTurbopackTraceServer(traceFilePath: string): void
+    /**
+     * The JSON schema of `experimental.turbo` in `next.config.js`, generated
+     * from the Rust config structs.
+     */
+    getConfigSchema(): object
 
     nextBuild?: any
   }
@@ -59,35 +64,57 @@ export type StyledString =
       value: StyledString[]
     }
 
+export interface IssueSource {
+  source: {
+    ident: string
+    content?: string
+  }
+  range?: {
+    start: {
+      // 0-indexed
+      line: number
+      // 0-indexed
+      column: number
+    }
+    end: {
+      // 0-indexed
+      line: number
+      // 0-indexed
+      column: number
+    }
+  }
+  // describes the span, e.g. "first import here"
+  label?: string
+  // secondary locations related to the issue
+  related: IssueSource[]
+}
+
+export interface SuggestedFix {
+  description: string
+  edits: {
+    filePath: string
+    // 0-indexed line and column
+    start: { line: number; column: number }
+    end: { line: number; column: number }
+    replacement: string
+  }[]
+}
+
 export interface Issue {
   severity: string
+  code?: string
   stage: string
   filePath: string
   title: StyledString
   description?: StyledString
   detail?: StyledString
-  source?: {
-    source: {
-      ident: string
-      content?: string
-    }
-    range?: {
-      start: {
-        // 0-indexed
-        line: number
-        // 0-indexed
-        column: number
-      }
-      end: {
-        // 0-indexed
-        line: number
-        // 0-indexed
-        column: number
-      }
-    }
-  }
+  source?: IssueSource
   documentationLink: string
   subIssues: Issue[]
+  suggestedFix?: SuggestedFix
+  transform?: string
+  importChain?: string[]
+  environment?: 'server' | 'client' | 'edge'
 }
 
 export interface Diagnostics {
@@ -111,6 +138,24 @@ export interface TurboEngineOptions {
    * An upper bound of memory that turbopack will attempt to stay under.
    */
   memoryLimit?: number
+
+  /**
+   * The memory usage above which cold task data of the persistent cache is
+   * dropped from memory.
+   */
+  memoryTarget?: number
+
+  /**
+   * The maximum number of chunk groups whose modules are collected at the
+   * same time.
+   */
+  chunkingParallelism?: number
+
+  /**
+   * The maximum number of output assets generated and written at the same
+   * time.
+   */
+  emitParallelism?: number
 }
 
 export interface Middleware {
@@ -213,11 +258,214 @@ export interface Project {
     aggregationMs: number
   ): AsyncIterableIterator<TurbopackResult<UpdateMessage>>
 
-  shutdown(): Promise<void>
+  buildWatchSubscribe(): AsyncIterableIterator<
+    TurbopackResult<EntrypointChanges>
+  >
+
+  /**
+   * Writes `fingerprint-manifest.json` to the dist dir, mapping the logical
+   * path of every written asset to its fingerprinted path, content hash, size
+   * and referenced assets.
+   */
+  writeFingerprintManifest(): Promise<void>
+
+  /**
+   * Lists every asset written by `writeAllEntrypointsToDisk`, with the
+   * endpoints it belongs to and the source modules it contains.
+   */
+  emittedAssets(): Promise<EmittedAsset[]>
+
+  /**
+   * Compares the output size and the npm packages of every route with the
+   * last successful build. With `persist`, this build becomes the one the next
+   * build is compared against.
+   */
+  bundleDiff(persist: boolean): Promise<BundleDiff>
+
+  /**
+   * Reports the configured route budgets which the client JavaScript and CSS
+   * of the routes exceed, and how much they changed since the last successful
+   * build. With `persist`, this build becomes the one the next build is
+   * compared against.
+   */
+  checkRouteBudgets(persist: boolean): Promise<TurbopackResult>
+
+  /**
+   * Reports how much of the build can be taken from the persistent cache,
+   * without building. Needs to be called before anything else is computed
+   * with the project.
+   */
+  buildDryRun(): Promise<DryRunReport>
+
+  /**
+   * The environments `traceResolve` can trace resolves in, e.g. `app-rsc` or
+   * `pages-edge-api`.
+   */
+  resolveTraceEnvironments(): Promise<string[]>
+
+  /**
+   * Resolves a specifier like the given environment would and returns every
+   * step the resolver took, for debugging "module not found" errors.
+   */
+  traceResolve(options: ResolveTraceOptions): Promise<ResolveTrace>
+
+  /**
+   * Hints routes the user is likely to navigate to next, which are compiled
+   * in the background when the project is idle.
+   */
+  navigationHints(pathnames: string[]): Promise<void>
+
+  /**
+   * Creates a project for another app that shares the compiler, caches and
+   * file watcher with this one. Fails when this project is shut down.
+   */
+  createSiblingProject(options: ProjectOptions): Promise<Project>
+
+  /**
+   * Waits for in-flight work (at most `drainTimeoutMs` when given), persists
+   * the cache and stops watching the file system.
+   */
+  shutdown(drainTimeoutMs?: number): Promise<void>
+
+  /**
+   * Returns statistics of the memory allocator. The allocator is shared by all
+   * projects of the process.
+   */
+  allocatorStats(): AllocatorStats
+
+  /**
+   * Returns memory the allocator retains for future allocations to the OS,
+   * e.g. after a large rebuild, and returns the statistics after purging.
+   */
+  purgeMemory(): AllocatorStats
+
+  /**
+   * Starts collecting the compile times of a share of `sampleRate` (between 0
+   * and 1) of all modules. Collecting is shared by all projects of the process.
+   */
+  enableBuildTelemetry(sampleRate: number): void
+
+  /**
+   * Returns the anonymized compile times of the sampled modules, or undefined
+   * when collecting isn't enabled. With `clear`, collecting starts over.
+   */
+  buildTelemetryReport(clear?: boolean): BuildTelemetryReport | undefined
 
   onExit(): Promise<void>
 }
 
+export interface EntrypointChanges {
+  added: string[]
+  changed: string[]
+  removed: string[]
+}
+
+export interface EmittedAsset {
+  /** Relative to the dist dir. */
+  path: string
+  size: number
+  contentHash: string
+  /** The names of the endpoints whose output contains the asset. */
+  endpoints: string[]
+  /**
+   * The paths of the source modules in the asset, when it is a chunk,
+   * relative to the project directory when they are inside of it.
+   */
+  modules: string[]
+}
+
+export interface BundleDiff {
+  /** The comparison of every route with the previous build, as JSON. */
+  json: string
+  /** The routes which changed, as a table. */
+  table: string
+  /** The build compared against, if there was a previous successful build. */
+  previousBuildId?: string
+}
+
+export interface DryRunReport {
+  /** The number of tasks in the persistent cache. */
+  tasks: number
+  /** The number of cached tasks which wouldn't need to be executed again. */
+  cacheHits: number
+  /** `cacheHits / tasks`, `0` when nothing is cached. */
+  hitRate: number
+  /** The inputs, e.g. file reads, which changed since they were cached. */
+  changedInputs: string[]
+  /** The report, as text. */
+  summary: string
+}
+
+export interface ResolveTraceOptions {
+  environment: string
+  specifier: string
+  /** The importing file, relative to the project directory. */
+  importer: string
+  /** `import` (the default), `require` or `url`. */
+  kind?: 'import' | 'require' | 'url'
+}
+
+export interface ResolveTraceStep {
+  kind:
+    | 'request'
+    | 'plugin'
+    | 'alias'
+    | 'probe'
+    | 'module'
+    | 'exports-conditions'
+    | 'main-field'
+  /** The directory the step was taken in. */
+  lookupPath: string
+  request: string
+  message: string
+}
+
+export interface ResolveTrace {
+  request: string
+  importer: string
+  /** A description of what the request resolved to. */
+  result: string
+  /**
+   * Sorted by the request and the directory they were taken in, not by the
+   * time they were taken.
+   */
+  steps: ResolveTraceStep[]
+}
+
+export interface BuildTelemetryReport {
+  sampleRate: number
+  /** The sampled modules, slowest first. */
+  modules: Array<{
+    /** A hash of the path of the module. */
+    id: string
+    /** The npm package of the module, or `(project)`. */
+    package: string
+    extension: string | null
+    parseMs: number
+    transformMs: number
+    codeGenerationMs: number
+    /** The time of every transform, which is part of `transformMs`. */
+    transformsMs: Record<string, number>
+  }>
+  /** The time of every transform across all sampled modules, slowest first. */
+  transforms: Array<{ name: string; modules: number; totalMs: number }>
+}
+
+export interface AllocatorStats {
+  /** Bytes currently allocated. */
+  allocated: number
+  /** Bytes committed from the OS. Not reported by the system allocator. */
+  committed?: number
+  /** Bytes committed by the allocator that aren't allocated. */
+  retained?: number
+  /** The share of committed memory that isn't allocated, between 0 and 1. */
+  fragmentation?: number
+  /** The resident set size of the process in bytes. */
+  resident?: number
+  /** The peak resident set size of the process in bytes. */
+  peakResident?: number
+}
+
 export type Route =
   | {
       type: 'conflict'
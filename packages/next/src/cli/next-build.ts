@@ -21,6 +21,7 @@ export type NextBuildOptions = {
   experimentalTurbo?: boolean
   experimentalBuildMode: 'default' | 'compile' | 'generate'
   experimentalUploadTrace?: string
+  experimentalTurboDryRun?: boolean
 }
 
 const nextBuild = (options: NextBuildOptions, directory?: string) => {
@@ -37,6 +38,7 @@ const nextBuild = (options: NextBuildOptions, directory?: string) => {
     experimentalTurbo,
     experimentalBuildMode,
     experimentalUploadTrace,
+    experimentalTurboDryRun,
   } = options
 
   let traceUploadUrl: string | undefined
@@ -75,6 +77,10 @@ const nextBuild = (options: NextBuildOptions, directory?: string) => {
     process.env.TURBOPACK = '1'
   }
 
+  if (experimentalTurboDryRun) {
+    process.env.NEXT_TURBOPACK_DRY_RUN = '1'
+  }
+
   return build(
     dir,
     profile,
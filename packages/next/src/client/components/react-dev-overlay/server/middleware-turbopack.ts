@@ -234,3 +234,55 @@ export function getSourceMapMiddleware(project: Project) {
     noContent(res)
   }
 }
+
+/**
+ * Traces how a specifier is resolved, for debugging "module not found"
+ * errors, e.g.
+ * `/__nextjs_resolve-trace?environment=app-rsc&specifier=foo&importer=app/page.tsx`.
+ * Without a specifier, the environments are listed.
+ */
+export function getResolveTraceMiddleware(project: Project) {
+  return async function (
+    req: IncomingMessage,
+    res: ServerResponse,
+    next: () => void
+  ): Promise<void> {
+    const { pathname, searchParams } = new URL(req.url!, 'http://n')
+
+    if (pathname !== '/__nextjs_resolve-trace') {
+      return next()
+    }
+
+    const specifier = searchParams.get('specifier')
+    const environment = searchParams.get('environment')
+    const kind = searchParams.get('kind') ?? undefined
+
+    try {
+      if (!specifier) {
+        return json(res, await project.resolveTraceEnvironments())
+      }
+
+      if (
+        !environment ||
+        (kind !== undefined &&
+          kind !== 'import' &&
+          kind !== 'require' &&
+          kind !== 'url')
+      ) {
+        return badRequest(res)
+      }
+
+      return json(
+        res,
+        await project.traceResolve({
+          environment,
+          specifier,
+          importer: searchParams.get('importer') ?? 'index.js',
+          kind,
+        })
+      )
+    } catch (error) {
+      return internalServerError(res, error)
+    }
+  }
+}
@@ -407,13 +407,61 @@ export const configSchema: zod.ZodType<NextConfig> = z.lazy(() =>
               )
               .optional(),
             resolveExtensions: z.array(z.string()).optional(),
+            resolveFallback: z
+              .record(z.string(), z.union([z.string(), z.literal(false)]))
+              .optional(),
+            resolveModuleFiles: z.boolean().optional(),
             treeShaking: z.boolean().optional(),
             persistentCaching: z
               .union([z.number(), z.literal(false)])
               .optional(),
             memoryLimit: z.number().optional(),
+            memoryTarget: z.number().optional(),
             moduleIdStrategy: z.enum(['named', 'deterministic']).optional(),
             minify: z.boolean().optional(),
+            budgets: z
+              .array(
+                z.strictObject({
+                  route: z.string(),
+                  raw: z.number().int().nonnegative().optional(),
+                  gzip: z.number().int().nonnegative().optional(),
+                  severity: z.enum(['warning', 'error']).optional(),
+                })
+              )
+              .optional(),
+            buildParallelism: z
+              .strictObject({
+                endpoints: z.number().int().positive().optional(),
+                chunking: z.number().int().positive().optional(),
+                emit: z.number().int().positive().optional(),
+              })
+              .optional(),
+            internalSymbolMaps: z.boolean().optional(),
+            hermetic: z
+              .strictObject({
+                roots: z.array(z.string()).optional(),
+                env: z.array(z.string()).optional(),
+              })
+              .optional(),
+            chunkLoadRetry: z
+              .strictObject({
+                retries: z.number().int().nonnegative().optional(),
+                initialDelayMs: z.number().int().nonnegative().optional(),
+                maxDelayMs: z.number().int().nonnegative().optional(),
+                onStaleDeployment: z.enum(['event', 'reload']).optional(),
+              })
+              .optional(),
+            hashAlgorithm: z.enum(['xxh3-64', 'xxh3-128', 'blake3']).optional(),
+            plugins: z
+              .array(
+                z.strictObject({
+                  command: z.string(),
+                  args: z.array(z.string()).optional(),
+                })
+              )
+              .optional(),
+            deterministic: z.boolean().optional(),
+            fingerprintManifest: z.boolean().optional(),
           })
           .optional(),
         optimizePackageImports: z.array(z.string()).optional(),
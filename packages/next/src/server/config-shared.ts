@@ -131,6 +131,18 @@ export interface ExperimentalTurboOptions {
    */
   resolveExtensions?: string[]
 
+  /**
+   * (`next --turbopack` only) Modules to use when a request can't be resolved, e.g. to provide
+   * browser polyfills for Node.js built-ins. `false` resolves to an empty module.
+   */
+  resolveFallback?: Record<string, string | false>
+
+  /**
+   * (`next --turbopack` only) Also resolve bare imports to files directly inside of `node_modules`,
+   * e.g. `foo` to `node_modules/foo.js`, for modules without a `package.json`.
+   */
+  resolveModuleFiles?: boolean
+
   /**
    * (`next --turbopack` only) A list of webpack loaders to apply when running with Turbopack.
    *
@@ -150,6 +162,12 @@ export interface ExperimentalTurboOptions {
    */
   memoryLimit?: number
 
+  /**
+   * The memory usage in bytes above which data of the persistent cache that
+   * wasn't used recently is dropped from memory. Requires persistent caching.
+   */
+  memoryTarget?: number
+
   /**
    * Enable persistent caching for the turbopack dev server and build.
    */
@@ -177,6 +195,154 @@ export interface ExperimentalTurboOptions {
    * Enable minification. Defaults to true in build mode and false in dev mode.
    */
   minify?: boolean
+
+  /**
+   * Size budgets for the client JavaScript and CSS of routes. Routes which
+   * exceed a budget are reported when they are emitted.
+   */
+  budgets?: TurboRouteBudget[]
+
+  /**
+   * (`next build --turbopack` only) Limits how much work runs at the same
+   * time, to lower the peak memory usage at the cost of a slower build.
+   */
+  buildParallelism?: TurboBuildParallelism
+
+  /**
+   * (`next build --turbopack` only) Emits the source maps of client chunks to
+   * `server/symbol-maps` in the dist dir instead of next to the chunks, so
+   * minified client stack traces can be resolved on the server without
+   * serving the source maps to browsers.
+   */
+  internalSymbolMaps?: boolean
+
+  /**
+   * Fails the build when it reads a file or env variable which isn't declared
+   * as its input, or when code evaluated during the build (e.g. loaders)
+   * accesses the network. The project directory can always be read.
+   */
+  hermetic?: TurboHermeticConfig
+
+  /**
+   * How the browser retries chunks which fail to load, and what it does when
+   * a chunk is gone because the app was deployed again while the page was
+   * open. Listen to the `turbopack:chunk-load-error` event on `window` to
+   * observe failures.
+   */
+  chunkLoadRetry?: TurboChunkLoadRetry
+
+  /**
+   * The hash function for the content hashes of static assets and for the
+   * module ids of the deterministic module id strategy. 64-bit hashes are
+   * more likely to collide in large projects sharing a remote cache, which
+   * can opt into `xxh3-128`. Defaults to `xxh3-64`. Client chunks of the
+   * development server always use `xxh3-64`.
+   */
+  hashAlgorithm?: 'xxh3-64' | 'xxh3-128' | 'blake3'
+
+  /**
+   * Plugin processes which resolve requests and transform sources. They are
+   * started in the project directory and talk to Turbopack over their
+   * stdin and stdout.
+   */
+  plugins?: TurboPluginConfig[]
+
+  /**
+   * (`next build --turbopack` only) Makes the build output reproducible by
+   * sorting the keys of all emitted JSON files, e.g. the manifests, so they
+   * don't depend on the order in which routes were built.
+   */
+  deterministic?: boolean
+
+  /**
+   * (`next build --turbopack` only) Writes `fingerprint-manifest.json` to the
+   * dist dir, which maps the logical path of every emitted asset to its
+   * fingerprinted path, content hash and size, for differential CDN uploads.
+   */
+  fingerprintManifest?: boolean
+}
+
+export interface TurboPluginConfig {
+  /**
+   * The command starting the plugin process, e.g. `node`.
+   */
+  command: string
+  /**
+   * The arguments of the command, e.g. `['./plugin.js']`.
+   */
+  args?: string[]
+}
+
+export interface TurboChunkLoadRetry {
+  /**
+   * How often a failed chunk is requested again. Defaults to 2.
+   */
+  retries?: number
+  /**
+   * The delay before the first retry, which doubles with every retry.
+   * Defaults to 500.
+   */
+  initialDelayMs?: number
+  /**
+   * Defaults to 4000.
+   */
+  maxDelayMs?: number
+  /**
+   * `'event'` (the default) fails the import with a `ChunkLoadError` whose
+   * `staleDeployment` is set. `'reload'` reloads the page unless a listener
+   * calls `preventDefault()` on the `turbopack:chunk-load-error` event.
+   */
+  onStaleDeployment?: 'event' | 'reload'
+}
+
+export interface TurboHermeticConfig {
+  /**
+   * Directories outside of the project directory which can be read, relative
+   * to the project directory, e.g. `'../../packages/ui'`.
+   */
+  roots?: string[]
+  /**
+   * Env variables which can be read. A trailing `*` matches every variable
+   * with the prefix, e.g. `'NEXT_PUBLIC_*'`.
+   */
+  env?: string[]
+}
+
+export interface TurboBuildParallelism {
+  /**
+   * The maximum number of routes compiled at the same time. Defaults to 10.
+   */
+  endpoints?: number
+  /**
+   * The maximum number of chunk groups whose modules are collected at the
+   * same time. Unlimited by default.
+   */
+  chunking?: number
+  /**
+   * The maximum number of output files generated and written at the same
+   * time. Unlimited by default.
+   */
+  emit?: number
+}
+
+export interface TurboRouteBudget {
+  /**
+   * A glob for the routes the budget applies to, e.g. `/blog/*`.
+   */
+  route: string
+  /**
+   * The maximum size in bytes.
+   */
+  raw?: number
+  /**
+   * The maximum gzip compressed size in bytes.
+   */
+  gzip?: number
+  /**
+   * Whether exceeding the budget is reported as warning or fails the build.
+   * Defaults to `'warning'`.
+   */
+  severity?: 'warning' | 'error'
 }
 
 export interface WebpackConfigContext {
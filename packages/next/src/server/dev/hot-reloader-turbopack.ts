@@ -31,6 +31,7 @@ import { BLOCKED_PAGES } from '../../shared/lib/constants'
 import {
   getOverlayMiddleware,
   getSourceMapMiddleware,
+  getResolveTraceMiddleware,
 } from '../../client/components/react-dev-overlay/server/middleware-turbopack'
 import { PageNotFoundError } from '../../shared/lib/utils'
 import { debounce } from '../utils'
@@ -183,6 +184,7 @@ export async function createHotReloaderTurbopack(
     {
       persistentCaching: isPersistentCachingEnabled(opts.nextConfig),
       memoryLimit: opts.nextConfig.experimental.turbo?.memoryLimit,
+      memoryTarget: opts.nextConfig.experimental.turbo?.memoryTarget,
     }
   )
   opts.onDevServerCleanup?.(() => project.onExit())
@@ -570,6 +572,7 @@ export async function createHotReloaderTurbopack(
   const middlewares = [
     getOverlayMiddleware(project),
     getSourceMapMiddleware(project),
+    getResolveTraceMiddleware(project),
   ]
 
   const versionInfoPromise = getVersionInfo(
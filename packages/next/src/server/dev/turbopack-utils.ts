@@ -211,7 +211,24 @@ export function formatIssue(issue: Issue) {
   //   message += renderStyledStringToErrorAnsi(detail) + '\n\n'
   // }
 
-  // TODO: Include a trace from the issue.
+  if (issue.environment || issue.transform) {
+    const context = [
+      issue.environment && `environment: ${issue.environment}`,
+      issue.transform && `transform: ${issue.transform}`,
+    ].filter(Boolean)
+    message += `${context.join(', ')}\n\n`
+  }
+
+  if (issue.importChain && issue.importChain.length > 1) {
+    message +=
+      'Import trace:\n' +
+      issue.importChain
+        .slice()
+        .reverse()
+        .map((ident) => `  ${ident.replace('[project]/', './')}`)
+        .join('\n') +
+      '\n\n'
+  }
 
   if (documentationLink) {
     message += documentationLink + '\n\n'
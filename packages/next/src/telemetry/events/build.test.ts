@@ -0,0 +1,55 @@
+import { eventTurbopackBuildTelemetry } from './build'
+
+describe('eventTurbopackBuildTelemetry', () => {
+  const module = (pkg: string, totalMs: number) => ({
+    id: `${pkg}-hash`,
+    package: pkg,
+    extension: 'js',
+    parseMs: totalMs / 2,
+    transformMs: totalMs / 4,
+    codeGenerationMs: totalMs / 4,
+    transformsMs: { 'styled-jsx': totalMs / 4 },
+  })
+
+  it('should sum up the compile times of the sampled modules', () => {
+    const { eventName, payload } = eventTurbopackBuildTelemetry({
+      sampleRate: 0.01,
+      modules: [module('react', 8), module('(project)', 4)],
+      transforms: [{ name: 'styled-jsx', modules: 2, totalMs: 3 }],
+    })
+
+    expect(eventName).toBe('NEXT_TURBOPACK_BUILD_TELEMETRY')
+    expect(payload).toEqual({
+      sampleRate: 0.01,
+      sampledModulesCount: 2,
+      parseMs: 6,
+      transformMs: 3,
+      codeGenerationMs: 3,
+      transforms: [{ name: 'styled-jsx', modules: 2, totalMs: 3 }],
+      slowestModules: [
+        { package: 'react', extension: 'js', totalMs: 8 },
+        { package: '(project)', extension: 'js', totalMs: 4 },
+      ],
+    })
+  })
+
+  it('should only report the slowest modules without their ids', () => {
+    const modules = Array.from({ length: 20 }, (_, i) =>
+      module(`pkg-${i}`, 20 - i)
+    )
+    const { payload } = eventTurbopackBuildTelemetry({
+      sampleRate: 1,
+      modules,
+      transforms: [],
+    })
+
+    expect(payload.sampledModulesCount).toBe(20)
+    expect(payload.slowestModules).toHaveLength(10)
+    expect(payload.slowestModules[0]).toEqual({
+      package: 'pkg-0',
+      extension: 'js',
+      totalMs: 20,
+    })
+    expect(JSON.stringify(payload)).not.toContain('hash')
+  })
+})
@@ -1,5 +1,6 @@
 import type { TelemetryPlugin } from '../../build/webpack/plugins/telemetry-plugin'
 import type { SWC_TARGET_TRIPLE } from '../../build/webpack/plugins/telemetry-plugin'
+import type { BuildTelemetryReport } from '../../build/swc/types'
 
 const REGEXP_DIRECTORY_DUNDER =
   /[\\/]__[^\\/]+(?<![\\/]__(?:tests|mocks))__[\\/]/i
@@ -83,6 +84,57 @@ export function eventBuildCompleted(
   }
 }
 
+const EVENT_TURBOPACK_BUILD_TELEMETRY = 'NEXT_TURBOPACK_BUILD_TELEMETRY'
+const TURBOPACK_BUILD_TELEMETRY_SLOWEST_MODULES = 10
+type SampledModule = BuildTelemetryReport['modules'][number]
+type EventTurbopackBuildTelemetry = {
+  sampleRate: number
+  sampledModulesCount: number
+  parseMs: number
+  transformMs: number
+  codeGenerationMs: number
+  transforms: BuildTelemetryReport['transforms']
+  slowestModules: Array<{
+    package: string
+    extension: string | null
+    totalMs: number
+  }>
+}
+
+/**
+ * Summarizes the compile times of the modules sampled by Turbopack. Modules
+ * are only described by their npm package and extension.
+ */
+export function eventTurbopackBuildTelemetry(
+  report: BuildTelemetryReport
+): {
+  eventName: string
+  payload: EventTurbopackBuildTelemetry
+} {
+  const sum = (ms: (module: SampledModule) => number) =>
+    report.modules.reduce((total, module) => total + ms(module), 0)
+  return {
+    eventName: EVENT_TURBOPACK_BUILD_TELEMETRY,
+    payload: {
+      sampleRate: report.sampleRate,
+      sampledModulesCount: report.modules.length,
+      parseMs: sum((module) => module.parseMs),
+      transformMs: sum((module) => module.transformMs),
+      codeGenerationMs: sum((module) => module.codeGenerationMs),
+      transforms: report.transforms,
+      // The modules of the report are sorted slowest first.
+      slowestModules: report.modules
+        .slice(0, TURBOPACK_BUILD_TELEMETRY_SLOWEST_MODULES)
+        .map((module) => ({
+          package: module.package,
+          extension: module.extension,
+          totalMs:
+            module.parseMs + module.transformMs + module.codeGenerationMs,
+        })),
+    },
+  }
+}
+
 const EVENT_BUILD_OPTIMIZED = 'NEXT_BUILD_OPTIMIZED'
 type EventBuildOptimized = {
   durationInSeconds: number
@@ -0,0 +1,116 @@
+import { NextInstance, createNext } from 'e2e-utils'
+import { PHASE_DEVELOPMENT_SERVER } from 'next/constants'
+import { createDefineEnv, loadBindings } from 'next/dist/build/swc'
+import type { Project } from 'next/dist/build/swc/types'
+import loadConfig from 'next/dist/server/config'
+import fs from 'fs/promises'
+import path from 'path'
+
+async function projectOptions(next: NextInstance, app: string) {
+  const projectPath = path.join(next.testDir, app)
+  const nextConfig = await loadConfig(PHASE_DEVELOPMENT_SERVER, projectPath)
+  const distDir = path.join(projectPath, '.next')
+  return {
+    env: {},
+    jsConfig: {
+      compilerOptions: {},
+    },
+    nextConfig,
+    projectPath,
+    distDir,
+    rootPath: next.testDir,
+    watch: {
+      enable: true,
+    },
+    dev: true,
+    defineEnv: createDefineEnv({
+      isTurbopack: true,
+      clientRouterFilters: undefined,
+      config: nextConfig,
+      dev: true,
+      distDir,
+      fetchCacheKeyPrefix: undefined,
+      hasRewrites: false,
+      middlewareMatchers: undefined,
+    }),
+    buildId: 'development',
+    encryptionKey: '12345',
+    previewProps: {
+      previewModeId: 'development',
+      previewModeEncryptionKey: '12345',
+      previewModeSigningKey: '12345',
+    },
+    browserslistQuery: 'last 2 versions',
+  }
+}
+
+async function routes(project: Project) {
+  const subscription = project.entrypointsSubscribe()
+  const entrypoints = (await subscription.next()).value
+  subscription.return()
+  return entrypoints
+}
+
+describe('next.rs api sibling projects', () => {
+  let next: NextInstance
+  beforeAll(async () => {
+    next = await createNext({
+      skipStart: true,
+      files: {
+        'a/pages/index.js': 'export default () => <div>app a</div>',
+        'b/pages/index.js': 'export default () => <div>app b</div>',
+        'b/pages/only-b.js': 'export default () => <div>only in b</div>',
+      },
+    })
+  })
+  afterAll(() => next.destroy())
+
+  let project: Project
+  let sibling: Project
+  beforeAll(async () => {
+    const bindings = await loadBindings()
+    project = await bindings.turbo.createProject(
+      await projectOptions(next, 'a')
+    )
+    sibling = await project.createSiblingProject(
+      await projectOptions(next, 'b')
+    )
+  })
+
+  it('should keep the options of each project apart', async () => {
+    const a = await routes(project)
+    const b = await routes(sibling)
+    expect(Array.from(a.routes.keys()).sort()).toEqual(['/'])
+    expect(Array.from(b.routes.keys()).sort()).toEqual(['/', '/only-b'])
+
+    for (const [entrypoints, app] of [
+      [a, 'a'],
+      [b, 'b'],
+    ] as const) {
+      const route = entrypoints.routes.get('/')
+      if (route.type !== 'page') throw new Error('expected a page')
+      await route.htmlEndpoint.writeToDisk()
+      const output = await fs.readFile(
+        path.join(next.testDir, app, '.next/server/pages/index.js'),
+        'utf8'
+      )
+      expect(output).toContain(`app ${app}`)
+      expect(output).not.toContain(`app ${app === 'a' ? 'b' : 'a'}`)
+    }
+  })
+
+  it('should keep the instance running until all projects are shut down', async () => {
+    await project.shutdown()
+    // The sibling still compiles in the shared instance.
+    const b = await routes(sibling)
+    expect(b.routes.has('/only-b')).toBe(true)
+    await expect(
+      project.createSiblingProject(await projectOptions(next, 'b'))
+    ).rejects.toThrow('shut down')
+
+    await sibling.shutdown()
+    await expect(
+      sibling.createSiblingProject(await projectOptions(next, 'a'))
+    ).rejects.toThrow('shut down')
+  })
+})
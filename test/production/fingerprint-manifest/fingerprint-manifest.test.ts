@@ -0,0 +1,33 @@
+import { nextTestSetup } from 'e2e-utils'
+
+// The manifest is only written by `next build --turbopack`.
+;(process.env.TURBOPACK_BUILD ? describe : describe.skip)(
+  'fingerprint manifest',
+  () => {
+    const { next } = nextTestSetup({
+      files: __dirname,
+      skipStart: true,
+    })
+
+    it('should not write the manifest by default', async () => {
+      await next.build()
+      expect(await next.hasFile('.next/fingerprint-manifest.json')).toBe(false)
+    })
+
+    it('should write the manifest when enabled', async () => {
+      await next.patchFile(
+        'next.config.js',
+        'module.exports = { experimental: { turbo: { fingerprintManifest: true } } }'
+      )
+      await next.build()
+      const manifest = JSON.parse(
+        await next.readFile('.next/fingerprint-manifest.json')
+      )
+      expect(manifest.version).toBe(2)
+      const paths = Object.values(manifest.assets).map(
+        (asset: any) => asset.path
+      )
+      expect(paths).toContainEqual(expect.stringMatching(/^static\/chunks\//))
+    })
+  }
+)
@@ -0,0 +1,105 @@
+use std::{
+    num::NonZeroU64,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use triomphe::Arc;
+
+use crate::{str_hash, RcStr};
+
+static INTERNING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The interned strings by their precomputed hash, so looking up a string only hashes it once.
+static INTERNER: Lazy<DashMap<NonZeroU64, Vec<RcStr>>> = Lazy::new(DashMap::new);
+
+/// Enables or disables the global interner mode. When enabled, all conversions into [`RcStr`]
+/// intern the string. Already created strings are not affected.
+pub fn set_interning(enabled: bool) {
+    INTERNING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` when the global interner mode is enabled.
+pub fn is_interning_enabled() -> bool {
+    INTERNING_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the number of strings currently stored in the interner.
+pub fn interned_count() -> usize {
+    INTERNER.iter().map(|entry| entry.value().len()).sum()
+}
+
+/// Removes all interned strings that are not referenced anymore outside of the interner. Called
+/// by the turbo-tasks backends when they are idle.
+pub fn collect_interned() {
+    // Strings are only cloned out of the interner while holding the lock of their shard, so a
+    // string which is unique here can't be revived concurrently.
+    INTERNER.retain(|_, strings| {
+        strings.retain(|s| !s.value.is_unique());
+        !strings.is_empty()
+    });
+}
+
+pub(crate) fn intern(s: RcStr) -> RcStr {
+    let hash = s.hash.unwrap_or_else(|| str_hash(s.as_str()));
+    let mut strings = INTERNER.entry(hash).or_default();
+    if let Some(existing) = strings
+        .iter()
+        .find(|existing| existing.as_str() == s.as_str())
+    {
+        return existing.clone();
+    }
+    let s = RcStr {
+        hash: Some(hash),
+        ..s
+    };
+    strings.push(s.clone());
+    s
+}
+
+pub(crate) fn intern_str(s: &str) -> RcStr {
+    let hash = str_hash(s);
+    if let Some(strings) = INTERNER.get(&hash) {
+        if let Some(existing) = strings.iter().find(|existing| existing.as_str() == s) {
+            return existing.clone();
+        }
+    }
+    intern(RcStr {
+        value: Arc::new(s.to_string()),
+        hash: Some(hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_interned(s: &str) -> bool {
+        INTERNER
+            .get(&str_hash(s))
+            .is_some_and(|strings| strings.iter().any(|existing| existing.as_str() == s))
+    }
+
+    #[test]
+    fn interned_strings_share_their_allocation() {
+        let a = RcStr::from("interner-test-shared".to_string()).intern();
+        let b = RcStr::from("interner-test-shared".to_string()).intern();
+        let c = RcStr::from("interner-test-shared".to_string());
+        assert!(a.ptr_eq(&b));
+        assert!(!a.ptr_eq(&c));
+        assert_eq!(a, c);
+        assert!(a.ptr_eq(&intern_str("interner-test-shared")));
+    }
+
+    #[test]
+    fn collects_unreferenced_strings() {
+        let kept = RcStr::from("interner-test-kept").intern();
+        let dropped = RcStr::from("interner-test-dropped").intern();
+        drop(dropped);
+        collect_interned();
+        assert!(is_interned("interner-test-kept"));
+        assert!(!is_interned("interner-test-dropped"));
+        assert!(kept.ptr_eq(&intern_str("interner-test-kept")));
+    }
+}
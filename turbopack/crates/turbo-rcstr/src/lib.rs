@@ -1,14 +1,21 @@
+mod interner;
+
 use std::{
     borrow::{Borrow, Cow},
+    cmp::Ordering,
     ffi::OsStr,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    num::NonZeroU64,
     ops::Deref,
     path::{Path, PathBuf},
 };
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use triomphe::Arc;
-use turbo_tasks_hash::{DeterministicHash, DeterministicHasher};
+use turbo_tasks_hash::{DeterministicHash, DeterministicHasher, Xxh3Hash64Hasher};
+
+pub use crate::interner::{collect_interned, interned_count, is_interning_enabled, set_interning};
 
 /// An immutable reference counted [`String`], similar to [`Arc<String>`][std::sync::Arc].
 ///
@@ -36,21 +43,63 @@ use turbo_tasks_hash::{DeterministicHash, DeterministicHasher};
 /// Converting from an [`RcStr`] to a `&str` should be done with [`RcStr::as_str`]. Converting to a
 /// `String` should be done with [`RcStr::into_owned`].
 ///
+/// ## Interning
+///
+/// Strings can be deduplicated with [`RcStr::intern`]. Interned strings with equal content share
+/// the same allocation, so comparing them only needs a pointer comparison. When the global interner
+/// mode is enabled with [`set_interning`], all conversions into `RcStr` (including deserialization
+/// from the persistent cache) intern automatically.
+///
+/// Interned strings store the hash of their content, which the interner looks up strings by.
+/// Interned strings with different hashes are compared without looking at their content. [`Hash`]
+/// still hashes the content, as it must be consistent with `Borrow<str>`.
+///
 /// ## Future Optimizations
 ///
 /// This type is intentionally opaque to allow for optimizations to the underlying representation.
-/// Future implementations may use inline representations or interning.
+/// Future implementations may use inline representations.
 //
 // If you want to change the underlying string type to `Arc<str>`, please ensure that you profile
 // performance. The current implementation offers very cheap `String -> RcStr -> String`, meaning we
 // only pay for the allocation for `Arc` when we pass `format!("").into()` to a function.
-#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(transparent)]
-pub struct RcStr(Arc<String>);
+#[derive(Clone)]
+pub struct RcStr {
+    value: Arc<String>,
+    /// See [`str_hash`]. Only computed when the string is interned.
+    hash: Option<NonZeroU64>,
+}
+
+/// The hash stored in interned [`RcStr`]s.
+fn str_hash(s: &str) -> NonZeroU64 {
+    let mut hasher = Xxh3Hash64Hasher::new();
+    hasher.write_bytes(s.as_bytes());
+    NonZeroU64::new(hasher.finish()).unwrap_or(NonZeroU64::MIN)
+}
 
 impl RcStr {
+    fn new(value: Arc<String>) -> Self {
+        let s = RcStr { value, hash: None };
+        if interner::is_interning_enabled() {
+            s.intern()
+        } else {
+            s
+        }
+    }
+
     pub fn as_str(&self) -> &str {
-        self.0.as_str()
+        self.value.as_str()
+    }
+
+    /// Returns an `RcStr` that shares its allocation with all other interned `RcStr`s of the same
+    /// content.
+    pub fn intern(self) -> Self {
+        interner::intern(self)
+    }
+
+    /// Returns `true` if both strings share the same allocation. This is always the case for equal
+    /// interned strings.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
     }
 
     /// Returns an owned mutable [`String`].
@@ -61,14 +110,91 @@ impl RcStr {
     ///   underlying string without cloning in `O(1)` time.
     /// - This avoids some of the potential overhead of the `Display` trait.
     pub fn into_owned(self) -> String {
-        match Arc::try_unwrap(self.0) {
-            Ok(v) => v,
+        match Arc::try_unwrap(self.value) {
+            Ok(value) => value,
             Err(arc) => (*arc).clone(),
         }
     }
 
     pub fn map(self, f: impl FnOnce(String) -> String) -> Self {
-        RcStr(Arc::new(f(self.into_owned())))
+        RcStr::from(f(self.into_owned()))
+    }
+}
+
+impl Default for RcStr {
+    fn default() -> Self {
+        RcStr::from(String::new())
+    }
+}
+
+impl PartialEq for RcStr {
+    fn eq(&self, other: &Self) -> bool {
+        // Fast path for interned strings and clones, and for interned strings with different
+        // hashes
+        if self.ptr_eq(other) {
+            return true;
+        }
+        if let (Some(hash), Some(other_hash)) = (self.hash, other.hash) {
+            if hash != other_hash {
+                return false;
+            }
+        }
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for RcStr {}
+
+impl PartialOrd for RcStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RcStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Serialize for RcStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_str().serialize(serializer)
+    }
+}
+
+impl Hash for RcStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must be consistent with `Borrow<str>`
+        self.as_str().hash(state)
+    }
+}
+
+impl<'de> Deserialize<'de> for RcStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(RcStr::from(String::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for RcStr {
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        String::schema_id()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+
+    fn is_referenceable() -> bool {
+        false
     }
 }
 
@@ -83,70 +209,77 @@ impl Deref for RcStr {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        self.0.as_str()
+        self.as_str()
     }
 }
 
 impl Borrow<str> for RcStr {
     fn borrow(&self) -> &str {
-        self.0.as_str()
+        self.as_str()
     }
 }
 
 impl From<Arc<String>> for RcStr {
     fn from(s: Arc<String>) -> Self {
-        RcStr(s)
+        RcStr::new(s)
     }
 }
 
 impl From<String> for RcStr {
     fn from(s: String) -> Self {
-        RcStr(Arc::new(s))
+        RcStr::new(Arc::new(s))
     }
 }
 
 impl From<&'_ str> for RcStr {
     fn from(s: &str) -> Self {
-        RcStr(Arc::new(s.to_string()))
+        if interner::is_interning_enabled() {
+            interner::intern_str(s)
+        } else {
+            RcStr::new(Arc::new(s.to_string()))
+        }
     }
 }
 
 impl From<Cow<'_, str>> for RcStr {
     fn from(s: Cow<str>) -> Self {
-        RcStr(Arc::new(s.into_owned()))
+        match s {
+            Cow::Borrowed(s) => RcStr::from(s),
+            Cow::Owned(s) => RcStr::from(s),
+        }
     }
 }
 
 /// Mimic `&str`
 impl AsRef<Path> for RcStr {
     fn as_ref(&self) -> &Path {
-        (*self.0).as_ref()
+        self.as_str().as_ref()
     }
 }
 
 /// Mimic `&str`
 impl AsRef<OsStr> for RcStr {
     fn as_ref(&self) -> &OsStr {
-        (*self.0).as_ref()
+        self.as_str().as_ref()
     }
 }
 
 /// Mimic `&str`
 impl AsRef<[u8]> for RcStr {
     fn as_ref(&self) -> &[u8] {
-        (*self.0).as_ref()
+        self.as_str().as_ref()
     }
 }
 
 impl PartialEq<str> for RcStr {
     fn eq(&self, other: &str) -> bool {
-        self.0.as_str() == other
+        self.as_str() == other
     }
 }
 
 impl PartialEq<&'_ str> for RcStr {
     fn eq(&self, other: &&str) -> bool {
-        self.0.as_str() == *other
+        self.as_str() == *other
     }
 }
 
@@ -158,13 +291,13 @@ impl PartialEq<String> for RcStr {
 
 impl Debug for RcStr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(&self.0, f)
+        Debug::fmt(self.as_str(), f)
     }
 }
 
 impl Display for RcStr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(self.as_str(), f)
     }
 }
 
@@ -179,3 +312,24 @@ impl From<RcStr> for PathBuf {
         String::from(s).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_the_allocation_of_an_arc_string() {
+        let value = Arc::new("rcstr-test-arc".to_string());
+        let s = RcStr::from(value.clone());
+        assert!(std::ptr::eq(s.as_str(), value.as_str()));
+    }
+
+    #[test]
+    fn only_interned_strings_store_their_hash() {
+        let s = RcStr::from("rcstr-test-hash".to_string());
+        assert_eq!(s.hash, None);
+        let interned = s.clone().intern();
+        assert_eq!(interned.hash, Some(str_hash("rcstr-test-hash")));
+        assert_eq!(s, interned);
+    }
+}
@@ -9,6 +9,7 @@ use std::{
     future::Future,
     hash::BuildHasherDefault,
     mem::take,
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
@@ -30,11 +31,13 @@ use turbo_tasks::{
         TransientTaskType, TypedCellContent,
     },
     event::{Event, EventListener},
+    graph_export::{TaskGraphNode, TaskGraphRead},
     registry,
     util::IdFactoryWithReuse,
-    CellId, FunctionId, RawVc, ReadConsistency, SessionId, TaskId, TraitTypeId,
-    TurboTasksBackendApi, ValueTypeId, TRANSIENT_TASK_BIT,
+    CellId, Durability, FunctionId, PersistFlushed, RawVc, ReadConsistency, SessionId, TaskId,
+    TraitTypeId, TurboTasksBackendApi, ValueTypeId, TRANSIENT_TASK_BIT,
 };
+use turbo_tasks_malloc::TurboMalloc;
 
 pub use self::{operation::AnyOperation, storage::TaskDataCategory};
 use crate::{
@@ -61,6 +64,13 @@ const BACKEND_JOB_FOLLOW_UP_SNAPSHOT: BackendJobId = unsafe { BackendJobId::new_
 
 const SNAPSHOT_REQUESTED_BIT: usize = 1 << (usize::BITS - 1);
 
+/// The changes which haven't been persisted yet.
+struct PersistedLogs {
+    task_cache: Vec<ChunkedVec<(Arc<CachedTaskType>, TaskId)>>,
+    meta: Vec<ChunkedVec<CachedDataUpdate>>,
+    data: Vec<ChunkedVec<CachedDataUpdate>>,
+}
+
 struct SnapshotRequest {
     snapshot_requested: bool,
     suspended_operations: HashSet<PtrEqArc<AnyOperation>>,
@@ -120,6 +130,14 @@ pub struct BackendOptions {
 
     /// Enables the backing storage.
     pub storage_mode: Option<StorageMode>,
+
+    /// The memory usage in bytes above which the data of tasks which haven't
+    /// been accessed recently is dropped from memory after a snapshot. It's
+    /// restored from the backing storage on demand.
+    ///
+    /// Requires [`StorageMode::ReadWrite`] and the `TurboMalloc` allocator to
+    /// measure the memory usage.
+    pub memory_target: Option<usize>,
 }
 
 impl Default for BackendOptions {
@@ -128,6 +146,7 @@ impl Default for BackendOptions {
             dependency_tracking: true,
             children_tracking: true,
             storage_mode: Some(StorageMode::ReadWrite),
+            memory_target: None,
         }
     }
 }
@@ -170,6 +189,12 @@ struct TurboTasksBackendInner<B: BackingStorage> {
     snapshot_completed: Condvar,
     /// The timestamp of the last started snapshot since [`Self::start_time`].
     last_snapshot: AtomicU64,
+    /// Whether saving a snapshot failed. Its changes are lost, so the persisted
+    /// data is incomplete and the session must not be marked as cleanly exited.
+    persisting_failed: AtomicBool,
+    /// Held while taking and saving the logs, and while compacting, so tasks
+    /// with changes that are taken but not saved yet aren't compacted.
+    persist_lock: Mutex<()>,
 
     stopping: AtomicBool,
     stopping_event: Event,
@@ -186,6 +211,17 @@ impl<B: BackingStorage> TurboTasksBackend<B> {
             backing_storage,
         )))
     }
+
+    /// Persists all changes and drops the data of tasks which haven't been
+    /// accessed since the previous compaction from memory, as it happens after
+    /// a snapshot when [`BackendOptions::memory_target`] is exceeded. Returns
+    /// the number of compacted tasks.
+    pub fn compact(&self) -> usize {
+        if !self.0.should_persist() || self.0.snapshot().is_none() {
+            return 0;
+        }
+        self.0.compact()
+    }
 }
 
 impl<B: BackingStorage> TurboTasksBackendInner<B> {
@@ -216,6 +252,8 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
             operations_suspended: Condvar::new(),
             snapshot_completed: Condvar::new(),
             last_snapshot: AtomicU64::new(0),
+            persisting_failed: AtomicBool::new(false),
+            persist_lock: Mutex::new(()),
             stopping: AtomicBool::new(false),
             stopping_event: Event::new(|| "TurboTasksBackend::stopping_event".to_string()),
             idle_start_event: Event::new(|| "TurboTasksBackend::idle_start_event".to_string()),
@@ -675,8 +713,10 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         }
     }
 
-    fn snapshot(&self) -> Option<(Instant, bool)> {
-        debug_assert!(self.should_persist());
+    /// Waits until all operations are completed or suspended and returns the
+    /// suspended operations. New operations wait until
+    /// [`Self::resume_operations`] is called.
+    fn suspend_operations(&self) -> Vec<Arc<AnyOperation>> {
         let mut snapshot_request = self.snapshot_request.lock();
         snapshot_request.snapshot_requested = true;
         let active_operations = self
@@ -688,30 +728,43 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                     self.in_progress_operations.load(Ordering::Relaxed) != SNAPSHOT_REQUESTED_BIT
                 });
         }
-        let suspended_operations = snapshot_request
+        snapshot_request
             .suspended_operations
             .iter()
             .map(|op| op.arc().clone())
-            .collect::<Vec<_>>();
-        drop(snapshot_request);
-        fn take_from_log(log: &Option<PersistedStorageLog>) -> Vec<ChunkedVec<CachedDataUpdate>> {
-            log.as_ref().map(|l| l.take()).unwrap_or_default()
-        }
-        let persisted_storage_meta_log = take_from_log(&self.persisted_storage_meta_log);
-        let persisted_storage_data_log = take_from_log(&self.persisted_storage_data_log);
-        let persisted_task_cache_log = self
-            .persisted_task_cache_log
-            .as_ref()
-            .map(|l| l.take(|i| i))
-            .unwrap_or_default();
+            .collect::<Vec<_>>()
+    }
+
+    fn resume_operations(&self) {
         let mut snapshot_request = self.snapshot_request.lock();
         snapshot_request.snapshot_requested = false;
         self.in_progress_operations
             .fetch_sub(SNAPSHOT_REQUESTED_BIT, Ordering::Relaxed);
         self.snapshot_completed.notify_all();
-        let snapshot_time = Instant::now();
-        drop(snapshot_request);
+    }
+
+    fn take_logs(&self) -> PersistedLogs {
+        fn take_from_log(log: &Option<PersistedStorageLog>) -> Vec<ChunkedVec<CachedDataUpdate>> {
+            log.as_ref().map(|l| l.take()).unwrap_or_default()
+        }
+        PersistedLogs {
+            task_cache: self
+                .persisted_task_cache_log
+                .as_ref()
+                .map(|l| l.take(|i| i))
+                .unwrap_or_default(),
+            meta: take_from_log(&self.persisted_storage_meta_log),
+            data: take_from_log(&self.persisted_storage_data_log),
+        }
+    }
 
+    /// Saves the logs to the backing storage. Returns whether there was
+    /// anything to save, or `None` when saving failed.
+    fn save_logs(
+        &self,
+        suspended_operations: Vec<Arc<AnyOperation>>,
+        logs: PersistedLogs,
+    ) -> Option<bool> {
         // TODO track which items are persisting
         // TODO This is very inefficient, maybe the BackingStorage could compute that since it need
         // to iterate items anyway.
@@ -725,27 +778,23 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         //     }
         // }
 
-        let mut new_items = false;
-
         fn shards_empty<T>(shards: &[ChunkedVec<T>]) -> bool {
             shards.iter().all(|shard| shard.is_empty())
         }
 
-        if !shards_empty(&persisted_task_cache_log)
-            || !shards_empty(&persisted_storage_meta_log)
-            || !shards_empty(&persisted_storage_data_log)
-        {
-            new_items = true;
-            if let Err(err) = self.backing_storage.save_snapshot(
-                self.session_id,
-                suspended_operations,
-                persisted_task_cache_log,
-                persisted_storage_meta_log,
-                persisted_storage_data_log,
-            ) {
-                println!("Persisting failed: {:?}", err);
-                return None;
-            }
+        if shards_empty(&logs.task_cache) && shards_empty(&logs.meta) && shards_empty(&logs.data) {
+            return Some(false);
+        }
+        if let Err(err) = self.backing_storage.save_snapshot(
+            self.session_id,
+            suspended_operations,
+            logs.task_cache,
+            logs.meta,
+            logs.data,
+        ) {
+            tracing::error!("Persisting failed: {:?}", err);
+            self.persisting_failed.store(true, Ordering::Release);
+            return None;
         }
 
         // TODO add when we need to track persisted items
@@ -756,9 +805,67 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         //         .finish_persisting_items(count);
         // }
 
+        Some(true)
+    }
+
+    fn snapshot(&self) -> Option<(Instant, bool)> {
+        debug_assert!(self.should_persist());
+        let _persist_lock = self.persist_lock.lock();
+        let suspended_operations = self.suspend_operations();
+        let logs = self.take_logs();
+        self.resume_operations();
+        let snapshot_time = Instant::now();
+
+        let new_items = self.save_logs(suspended_operations, logs)?;
         Some((snapshot_time, new_items))
     }
 
+    /// Drops the data of persistent tasks which haven't been accessed since the
+    /// previous compaction from memory, see [`BackendOptions::memory_target`].
+    /// The data is restored from the backing storage when the task is accessed
+    /// again. Returns the number of compacted tasks.
+    ///
+    /// Operations continue meanwhile. The storage is visited one shard at a
+    /// time, and only tasks without unpersisted changes are compacted. Changes
+    /// made after collecting the tasks with unpersisted changes mark the task
+    /// as accessed, so it's skipped too.
+    fn compact(&self) -> usize {
+        debug_assert!(self.should_persist());
+        // Logs taken by a concurrent snapshot are not persisted yet.
+        let _persist_lock = self.persist_lock.lock();
+        // The changes of a failed snapshot are lost, so the persisted data can't
+        // be restored.
+        if self.persisting_failed.load(Ordering::Acquire) {
+            return 0;
+        }
+        let unpersisted = self.persisted_storage_data_log.as_ref().unwrap().tasks();
+        let mut compacted = 0;
+        self.storage.for_each_mut(|&task_id, task| {
+            if task_id.is_transient()
+                || !task.persistance_state().is_restored(TaskDataCategory::Data)
+                // Clears the flag, so the task is compacted next time when it isn't accessed in
+                // between.
+                || task.persistance_state_mut().take_data_accessed()
+                || unpersisted.contains(&task_id)
+            {
+                return;
+            }
+            // Only tasks whose data is fully persisted can be restored later.
+            let fully_persisted = task.iter_all().all(|(key, value)| {
+                key.category() != TaskDataCategory::Data
+                    || (key.is_persistent() && value.is_persistent())
+            });
+            if !fully_persisted {
+                return;
+            }
+            task.retain_all(|key, _| key.category() != TaskDataCategory::Data);
+            task.persistance_state_mut()
+                .set_unrestored(TaskDataCategory::Data);
+            compacted += 1;
+        });
+        compacted
+    }
+
     fn startup(&self, turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>) {
         if self.should_restore() {
             // Continue all uncompleted operations
@@ -784,6 +891,50 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         self.stopping_event.notify(usize::MAX);
     }
 
+    fn stop(&self, turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>) {
+        // The snapshot job might have finished before the last in-flight tasks
+        // completed, or might not have been started at all after stopping.
+        if !self.should_persist() {
+            return;
+        }
+        if matches!(self.snapshot(), Some((_, true))) {
+            turbo_tasks
+                .event_bus()
+                .publish(PersistFlushed { stopping: true });
+        }
+        // Otherwise the next session verifies the persisted data, as after a crash.
+        if self.persisting_failed.load(Ordering::Acquire) {
+            return;
+        }
+        if let Err(err) = self.backing_storage.shutdown() {
+            tracing::error!("Shutting down persistence failed: {:?}", err);
+        }
+    }
+
+    fn output_write_started(&self, path: &Path) {
+        if self.should_persist() {
+            if let Err(err) = self.backing_storage.output_write_started(path) {
+                tracing::warn!(
+                    "Recording the write of {} failed: {:?}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+
+    fn output_write_finished(&self, path: &Path) {
+        if self.should_persist() {
+            if let Err(err) = self.backing_storage.output_write_finished(path) {
+                tracing::warn!(
+                    "Recording the write of {} failed: {:?}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+
     fn idle_start(&self) {
         self.idle_start_event.notify(usize::MAX);
     }
@@ -945,6 +1096,74 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         )
     }
 
+    fn get_task_stable_hash(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) -> Option<u64> {
+        if task_id.is_transient() {
+            return None;
+        }
+        let mut ctx = self.execute_context(turbo_tasks);
+        let task = ctx.task(task_id, TaskDataCategory::Meta);
+        if let Some(&stable_hash) = get!(task, StableHash) {
+            return Some(stable_hash);
+        }
+        // Hashing the task type looks up the stable hashes of the tasks of its `Vc` inputs, so
+        // the task must not stay locked meanwhile.
+        drop(task);
+        // Computed on first use and persisted with the task, so later sessions don't need to
+        // serialize the task type again.
+        let stable_hash = self.lookup_task_type(task_id)?.stable_hash()?;
+        let mut task = ctx.task(task_id, TaskDataCategory::Meta);
+        task.add(CachedDataItem::StableHash { value: stable_hash });
+        Some(stable_hash)
+    }
+
+    fn task_graph(
+        &self,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) -> Vec<TaskGraphNode> {
+        let mut task_ids = Vec::new();
+        self.storage
+            .for_each_mut(|&task_id, _| task_ids.push(task_id));
+        if self.should_restore() {
+            // Cached tasks which weren't used in this session yet are restored without executing
+            // them.
+            task_ids.extend((1..*self.backing_storage.next_free_task_id()).map(TaskId::from));
+        }
+        task_ids.sort_unstable();
+        task_ids.dedup();
+
+        let mut ctx = self.execute_context(turbo_tasks);
+        task_ids
+            .into_iter()
+            .filter_map(|task_id| {
+                let task_type = self.lookup_task_type(task_id)?;
+                let mut node = TaskGraphNode::new(task_id, &task_type);
+                let task = ctx.task(task_id, TaskDataCategory::All);
+                node.dirty = get!(task, Dirty).map_or(false, |dirty| dirty.get(self.session_id));
+                node.children = iter_many!(task, Child { task } => *task).collect();
+                node.reads = task
+                    .iter(CachedDataItemIndex::Dependencies)
+                    .filter_map(|(key, _)| match *key {
+                        CachedDataItemKey::OutputDependency { target } => {
+                            Some(TaskGraphRead::Output(target))
+                        }
+                        CachedDataItemKey::CellDependency { target } => {
+                            Some(TaskGraphRead::Cell(target.task, target.cell))
+                        }
+                        CachedDataItemKey::CollectiblesDependency { target } => Some(
+                            TaskGraphRead::Collectibles(target.task, target.collectible_type),
+                        ),
+                        _ => None,
+                    })
+                    .collect();
+                Some(node)
+            })
+            .collect()
+    }
+
     fn try_get_function_id(&self, task_id: TaskId) -> Option<FunctionId> {
         self.lookup_task_type(task_id)
             .and_then(|task_type| match &*task_type {
@@ -986,6 +1205,7 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                     once_task,
                     done_event,
                     session_dependent: false,
+                    durability: None,
                 },
             });
 
@@ -1394,6 +1614,7 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
             once_task: _,
             stale,
             session_dependent,
+            durability,
         } = in_progress
         else {
             panic!("Task execution completed, but task is not in progress: {task:#?}");
@@ -1407,8 +1628,10 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
             return true;
         }
 
-        // Update the dirty state
-        let new_dirty_state = if session_dependent {
+        // Update the dirty state. Session dependent tasks that only read high durability inputs
+        // are trusted when restored from the persistent cache and don't need to be verified again.
+        // They are still invalidated by their dependencies, which are verified as usual.
+        let new_dirty_state = if session_dependent && durability != Some(Durability::High) {
             Some(DirtyState {
                 clean_in_session: Some(self.session_id),
             })
@@ -1541,8 +1764,23 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
                     if let Some((snapshot_start, new_data)) = snapshot {
                         last_snapshot = snapshot_start;
                         if new_data {
+                            turbo_tasks
+                                .event_bus()
+                                .publish(PersistFlushed { stopping: false });
                             continue;
                         }
+                        if let Some(memory_target) = self.options.memory_target {
+                            if TurboMalloc::memory_usage() > memory_target {
+                                let this = self.clone();
+                                let compacted =
+                                    turbo_tasks::spawn_blocking(move || this.compact()).await;
+                                tracing::debug!(compacted, "compacted task data");
+                            }
+                        }
+                        // Nothing changed since the last snapshot, so this is a good time to drop
+                        // the interned strings which aren't used anymore.
+                        turbo_tasks::spawn_blocking(turbo_rcstr::collect_interned).await;
+
                         let last_snapshot = last_snapshot.duration_since(self.start_time);
                         self.last_snapshot.store(
                             last_snapshot.as_millis().try_into().unwrap(),
@@ -1729,10 +1967,55 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         let mut ctx = self.execute_context(turbo_tasks);
         let mut task = ctx.task(task, TaskDataCategory::Data);
         if let Some(InProgressState::InProgress {
-            session_dependent, ..
+            session_dependent,
+            durability,
+            ..
         }) = get_mut!(task, InProgress)
         {
             *session_dependent = true;
+            *durability = Some(Durability::Low);
+        }
+    }
+
+    fn mark_own_task_durability(
+        &self,
+        task: TaskId,
+        durability: Durability,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) {
+        let mut ctx = self.execute_context(turbo_tasks);
+        let mut task = ctx.task(task, TaskDataCategory::Data);
+        if let Some(InProgressState::InProgress {
+            session_dependent,
+            durability: current,
+            ..
+        }) = get_mut!(task, InProgress)
+        {
+            *session_dependent = true;
+            *current = Some(current.map_or(durability, |current| current.min(durability)));
+        }
+    }
+
+    fn prefetch_task(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<TurboTasksBackend<B>>,
+    ) {
+        let mut ctx = self.execute_context(turbo_tasks);
+        let mut task = ctx.task(task_id, TaskDataCategory::All);
+        if task.has_key(&CachedDataItemKey::InProgress {}) {
+            // Already scheduled or executing
+            return;
+        }
+        let is_dirty =
+            get!(task, Dirty).map_or(false, |dirty_state| dirty_state.get(self.session_id));
+        if !is_dirty && task.has_key(&CachedDataItemKey::Output {}) {
+            // Already computed and up to date
+            return;
+        }
+        let description = self.get_task_desc_fn(task_id);
+        if task.add(CachedDataItem::new_scheduled(description)) {
+            turbo_tasks.schedule(task_id);
         }
     }
 
@@ -1813,6 +2096,10 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.stopping();
     }
 
+    fn stop(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        self.0.stop(turbo_tasks);
+    }
+
     fn idle_start(&self, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
         self.0.idle_start();
     }
@@ -1821,6 +2108,14 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.idle_end();
     }
 
+    fn output_write_started(&self, path: &Path, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        self.0.output_write_started(path);
+    }
+
+    fn output_write_finished(&self, path: &Path, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        self.0.output_write_finished(path);
+    }
+
     fn get_or_create_persistent_task(
         &self,
         task_type: CachedTaskType,
@@ -1869,6 +2164,18 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.get_task_description(task)
     }
 
+    fn get_task_stable_hash(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Option<u64> {
+        self.0.get_task_stable_hash(task_id, turbo_tasks)
+    }
+
+    fn task_graph(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) -> Vec<TaskGraphNode> {
+        self.0.task_graph(turbo_tasks)
+    }
+
     fn try_get_function_id(&self, task_id: TaskId) -> Option<FunctionId> {
         self.0.try_get_function_id(task_id)
     }
@@ -2023,6 +2330,20 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.mark_own_task_as_session_dependent(task, turbo_tasks);
     }
 
+    fn mark_own_task_durability(
+        &self,
+        task: TaskId,
+        durability: Durability,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) {
+        self.0
+            .mark_own_task_durability(task, durability, turbo_tasks);
+    }
+
+    fn prefetch_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        self.0.prefetch_task(task, turbo_tasks);
+    }
+
     fn connect_task(
         &self,
         task: TaskId,
@@ -200,6 +200,9 @@ where
                 }
             }
         }
+        if category != TaskDataCategory::Meta {
+            task.persistance_state_mut().mark_data_accessed();
+        }
         TaskGuardImpl {
             task,
             task_id,
@@ -253,6 +256,10 @@ where
                 }
             }
         }
+        if category != TaskDataCategory::Meta {
+            task1.persistance_state_mut().mark_data_accessed();
+            task2.persistance_state_mut().mark_data_accessed();
+        }
         (
             TaskGuardImpl {
                 task: task1,
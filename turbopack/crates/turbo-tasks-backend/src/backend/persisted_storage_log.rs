@@ -1,3 +1,4 @@
+use rustc_hash::FxHashSet;
 use turbo_tasks::{KeyValuePair, TaskId};
 
 use crate::{
@@ -72,6 +73,18 @@ impl PersistedStorageLog {
         guard.data.extend(updates);
     }
 
+    /// Returns the tasks which have changes in the log.
+    pub fn tasks(&self) -> FxHashSet<TaskId> {
+        let mut tasks = FxHashSet::default();
+        self.data.for_each(|shard| {
+            tasks.extend(shard.data.iter().filter_map(|update| match update {
+                CachedDataUpdate::Task { task } => Some(*task),
+                _ => None,
+            }))
+        });
+        tasks
+    }
+
     pub fn take(&self) -> Vec<ChunkedVec<CachedDataUpdate>> {
         self.data.take(|shard| shard.data)
     }
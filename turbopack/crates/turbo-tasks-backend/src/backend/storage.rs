@@ -19,6 +19,7 @@ use crate::{
 
 const META_UNRESTORED: u32 = 1 << 31;
 const DATA_UNRESTORED: u32 = 1 << 30;
+const DATA_ACCESSED: u32 = 1 << 29;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TaskDataCategory {
@@ -97,6 +98,21 @@ impl PersistanceState {
         self.value &= !category.flag();
     }
 
+    pub fn set_unrestored(&mut self, category: TaskDataCategory) {
+        self.value |= category.flag();
+    }
+
+    pub fn mark_data_accessed(&mut self) {
+        self.value |= DATA_ACCESSED;
+    }
+
+    /// Returns whether the data has been accessed since the last call.
+    pub fn take_data_accessed(&mut self) -> bool {
+        let accessed = (self.value & DATA_ACCESSED) != 0;
+        self.value &= !DATA_ACCESSED;
+        accessed
+    }
+
     pub fn add_persisting_item(&mut self) {
         // TODO add when we need to track unpersisted items
         // self.value += 1;
@@ -306,6 +322,24 @@ where
             .map(|(key, value)| T::from_key_and_value(key, value))
     }
 
+    /// Removes all items for which `f` returns `false` and shrinks the maps.
+    pub fn retain_all(&mut self, mut f: impl FnMut(&T::Key, &T::Value) -> bool) {
+        match self {
+            InnerStorage::Plain { map, .. } => {
+                map.retain(|k, v| f(k, v));
+                map.shrink_to_fit();
+            }
+            InnerStorage::Indexed { map, .. } => {
+                map.retain(|_, m| {
+                    m.retain(|k, v| f(k, v));
+                    m.shrink_to_fit();
+                    !m.is_empty()
+                });
+                map.shrink_to_fit();
+            }
+        }
+    }
+
     pub fn extract_if_all<'l, F>(&'l mut self, mut f: F) -> impl Iterator<Item = T> + use<'l, T, F>
     where
         F: for<'a, 'b> FnMut(&'a T::Key, &'b T::Value) -> bool + 'l,
@@ -381,6 +415,15 @@ where
         }
     }
 
+    /// Calls `f` for every entry. The entries of a shard are locked while
+    /// they are visited.
+    pub fn for_each_mut(&self, mut f: impl FnMut(&K, &mut InnerStorage<T>)) {
+        for mut entry in self.map.iter_mut() {
+            let (key, value) = entry.pair_mut();
+            f(key, value);
+        }
+    }
+
     pub fn access_pair_mut(
         &self,
         key1: K,
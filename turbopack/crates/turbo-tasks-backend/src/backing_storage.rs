@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use anyhow::Result;
 use turbo_tasks::{backend::CachedTaskType, SessionId, TaskId};
@@ -25,6 +25,13 @@ pub trait BackingStorage: 'static + Send + Sync {
         meta_updates: Vec<ChunkedVec<CachedDataUpdate>>,
         data_updates: Vec<ChunkedVec<CachedDataUpdate>>,
     ) -> Result<()>;
+    /// Records that an effect is writing the output file at `path`, so it can
+    /// be removed when the process crashes meanwhile.
+    fn output_write_started(&self, path: &Path) -> Result<()>;
+    fn output_write_finished(&self, path: &Path) -> Result<()>;
+    /// Called after the last snapshot has been saved successfully. Marks the
+    /// persisted data as complete.
+    fn shutdown(&self) -> Result<()>;
     fn start_read_transaction(&self) -> Option<Self::ReadTransaction<'_>>;
     /// # Safety
     ///
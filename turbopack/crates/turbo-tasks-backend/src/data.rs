@@ -5,7 +5,8 @@ use turbo_tasks::{
     event::{Event, EventListener},
     registry,
     util::SharedError,
-    CellId, KeyValuePair, SessionId, TaskId, TraitTypeId, TypedSharedReference, ValueTypeId,
+    CellId, Durability, KeyValuePair, SessionId, TaskId, TraitTypeId, TypedSharedReference,
+    ValueTypeId,
 };
 
 use crate::backend::{indexed::Indexed, TaskDataCategory};
@@ -258,6 +259,9 @@ pub enum InProgressState {
         #[allow(dead_code)]
         once_task: bool,
         session_dependent: bool,
+        /// The lowest durability marked during this execution, if any. Marking the task as
+        /// session dependent marks it with [`Durability::Low`].
+        durability: Option<Durability>,
         done_event: Event,
     },
 }
@@ -294,6 +298,12 @@ pub struct AggregationNumber {
 
 #[derive(Debug, Clone, KeyValuePair, Serialize, Deserialize)]
 pub enum CachedDataItem {
+    // Identity
+    /// See [`turbo_tasks::backend::CachedTaskType::stable_hash`].
+    StableHash {
+        value: u64,
+    },
+
     // Output
     Output {
         value: OutputValue,
@@ -454,6 +464,7 @@ impl CachedDataItem {
             CachedDataItem::OutputDependent { task, .. } => !task.is_transient(),
             CachedDataItem::CellDependent { task, .. } => !task.is_transient(),
             CachedDataItem::CollectiblesDependent { task, .. } => !task.is_transient(),
+            CachedDataItem::StableHash { .. } => true,
             CachedDataItem::AggregationNumber { .. } => true,
             CachedDataItem::Follower { task, .. } => !task.is_transient(),
             CachedDataItem::Upper { task, .. } => !task.is_transient(),
@@ -516,6 +527,7 @@ impl CachedDataItemKey {
             CachedDataItemKey::OutputDependent { task, .. } => !task.is_transient(),
             CachedDataItemKey::CellDependent { task, .. } => !task.is_transient(),
             CachedDataItemKey::CollectiblesDependent { task, .. } => !task.is_transient(),
+            CachedDataItemKey::StableHash { .. } => true,
             CachedDataItemKey::AggregationNumber { .. } => true,
             CachedDataItemKey::Follower { task, .. } => !task.is_transient(),
             CachedDataItemKey::Upper { task, .. } => !task.is_transient(),
@@ -564,6 +576,7 @@ impl CachedDataItemKey {
             | CachedDataItemKey::Error { .. } => TaskDataCategory::Data,
 
             CachedDataItemKey::Output { .. }
+            | CachedDataItemKey::StableHash { .. }
             | CachedDataItemKey::AggregationNumber { .. }
             | CachedDataItemKey::Dirty { .. }
             | CachedDataItemKey::Follower { .. }
@@ -0,0 +1,159 @@
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use memmap2::Mmap;
+use turbo_tasks::blob::{Blob, BlobId, BlobStore};
+use turbo_tasks_hash::hash_xxh3_hash128;
+
+/// A [BlobStore] that keeps every blob in a separate, content addressed file.
+///
+/// Blob files are never modified after they have been written, so they can be
+/// memory mapped on read. This makes restoring large values (e.g. file
+/// contents) from the persistent cache a zero-copy operation.
+///
+/// Unreferenced blobs are removed by the
+/// [KeyValueDatabaseBackingStorage][crate::KeyValueDatabaseBackingStorage],
+/// which counts the references to them.
+pub struct FileBlobStore {
+    path: PathBuf,
+    next_temp_file: AtomicUsize,
+}
+
+impl FileBlobStore {
+    pub fn new(path: &Path) -> Result<Self> {
+        fs::create_dir_all(path)
+            .with_context(|| format!("Unable to create blob directory {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            next_temp_file: AtomicUsize::new(0),
+        })
+    }
+
+    fn blob_path(&self, id: BlobId) -> PathBuf {
+        self.path.join(format!("{id:032x}.blob"))
+    }
+
+    /// Verifies the blobs written since `since` against their content hash and
+    /// removes incomplete ones, together with leftover temporary files. Returns
+    /// the ids of the removed blobs, so the task data referencing them can be
+    /// dropped, see
+    /// [KeyValueDatabaseBackingStorage::forget_blobs][crate::KeyValueDatabaseBackingStorage::forget_blobs].
+    pub fn remove_incomplete_blobs(&self, since: SystemTime) -> Result<Vec<BlobId>> {
+        let mut removed = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("blob") => {
+                    let written_since = fs::metadata(&path)?
+                        .modified()
+                        .map_or(true, |modified| modified >= since);
+                    if written_since && !is_complete_blob(&path)? {
+                        fs::remove_file(&path)?;
+                        // A blob without a valid id can't be referenced.
+                        removed.extend(blob_id(&path));
+                    }
+                }
+                Some(extension) if extension.starts_with("tmp") => fs::remove_file(&path)?,
+                _ => {}
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn blob_id(path: &Path) -> Option<BlobId> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| BlobId::from_str_radix(stem, 16).ok())
+}
+
+fn is_complete_blob(path: &Path) -> Result<bool> {
+    let Some(id) = blob_id(path) else {
+        return Ok(false);
+    };
+    Ok(hash_xxh3_hash128(&fs::read(path)?) == id)
+}
+
+impl BlobStore for FileBlobStore {
+    fn write_blob(&self, data: &[u8]) -> Result<BlobId> {
+        let id = hash_xxh3_hash128(data);
+        let path = self.blob_path(id);
+        if path.exists() {
+            return Ok(id);
+        }
+        // Write to a temporary file first, so readers never observe a partially
+        // written blob.
+        let temp_path = path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            self.next_temp_file.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = File::create(&temp_path)
+            .with_context(|| format!("Unable to create blob file {}", temp_path.display()))?;
+        file.write_all(data)?;
+        file.sync_data()?;
+        drop(file);
+        match fs::rename(&temp_path, &path) {
+            Ok(()) => {}
+            // Another writer stored the same blob concurrently.
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                let _ = fs::remove_file(&temp_path);
+            }
+            Err(err) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(err).with_context(|| {
+                    format!("Unable to move blob file into place {}", path.display())
+                });
+            }
+        }
+        Ok(id)
+    }
+
+    fn read_blob(&self, id: BlobId) -> Result<Blob> {
+        let path = self.blob_path(id);
+        let file = File::open(&path)
+            .with_context(|| format!("Unable to open blob file {}", path.display()))?;
+        // Safety: Blob files are content addressed and never modified after they have
+        // been moved into place.
+        let mmap = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("Unable to map blob file {}", path.display()))?;
+        Ok(Arc::new(mmap))
+    }
+
+    fn remove_blob(&self, id: BlobId) -> Result<()> {
+        let path = self.blob_path(id);
+        match fs::remove_file(&path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("Unable to remove blob file {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn retain_blobs(&self, is_referenced: &dyn Fn(BlobId) -> bool) -> Result<usize> {
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("blob") {
+                continue;
+            }
+            let Some(id) = blob_id(&path) else {
+                continue;
+            };
+            if !is_referenced(id) {
+                self.remove_blob(id)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
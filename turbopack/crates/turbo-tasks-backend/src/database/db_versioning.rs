@@ -1,17 +1,25 @@
 use std::{
-    env,
     fs::{metadata, read_dir, remove_dir_all},
     path::{Path, PathBuf},
     time::Duration,
 };
 
 use anyhow::Result;
+use turbo_tasks::feature_flags::FeatureFlag;
 
 /// Specifies many databases that have a different version than the current one are retained.
 /// For example if MAX_OTHER_DB_VERSIONS is 2, there can be at most 3 databases in the directory,
 /// the current one and two older/newer ones.
 const MAX_OTHER_DB_VERSIONS: usize = 2;
 
+static IGNORE_DIRTY: FeatureFlag<bool> =
+    FeatureFlag::new("ignore-dirty", "TURBO_ENGINE_IGNORE_DIRTY", false);
+static DISABLE_VERSIONING: FeatureFlag<bool> = FeatureFlag::new(
+    "disable-versioning",
+    "TURBO_ENGINE_DISABLE_VERSIONING",
+    false,
+);
+
 pub fn handle_db_versioning(base_path: &Path) -> Result<PathBuf> {
     // Database versioning. Pass `TURBO_ENGINE_IGNORE_DIRTY` at runtime to ignore a
     // dirty git repository. Pass `TURBO_ENGINE_DISABLE_VERSIONING` at runtime to disable
@@ -23,8 +31,8 @@ pub fn handle_db_versioning(base_path: &Path) -> Result<PathBuf> {
     } else {
         (version_info, false)
     };
-    let ignore_dirty = env::var("TURBO_ENGINE_IGNORE_DIRTY").ok().is_some();
-    let disabled_versioning = env::var("TURBO_ENGINE_DISABLE_VERSIONING").ok().is_some();
+    let ignore_dirty = IGNORE_DIRTY.get();
+    let disabled_versioning = DISABLE_VERSIONING.get();
     let version = if disabled_versioning {
         println!(
             "WARNING: Persistent Caching versioning is disabled. Manual removal of the persistent \
@@ -1,3 +1,4 @@
+pub mod blob_store;
 mod by_key_space;
 pub mod db_versioning;
 pub mod fresh_db_optimization;
@@ -5,12 +6,15 @@ pub mod key_value_database;
 pub mod lmdb;
 pub mod noop_kv;
 pub mod read_transaction_cache;
+pub mod session_journal;
 mod startup_cache;
 pub mod write_batch;
 
+pub use blob_store::FileBlobStore;
 pub use db_versioning::handle_db_versioning;
 pub use fresh_db_optimization::{is_fresh, FreshDbOptimization};
 #[allow(unused_imports)]
 pub use noop_kv::NoopKvDb;
 pub use read_transaction_cache::ReadTransactionCache;
+pub use session_journal::SessionJournal;
 pub use startup_cache::StartupCacheLayer;
@@ -0,0 +1,189 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use turbo_tasks::FxIndexSet;
+
+/// A small append-only journal next to the database, which records the
+/// lifecycle of a session: when it started, when snapshots and output files
+/// are being written and whether it exited cleanly.
+///
+/// Every lifecycle record is synced to disk before the action it describes, so
+/// after a crash the journal tells which files the persistent cache might have
+/// been writing at that time. Output writes aren't synced, as the file system
+/// keeps them across a crash of the process.
+pub struct SessionJournal {
+    file: Mutex<File>,
+}
+
+/// How the previous session ended when it didn't exit cleanly.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct UncleanShutdown {
+    /// The start time of a snapshot that was still being written. Files
+    /// written since then might be incomplete.
+    pub interrupted_snapshot: Option<SystemTime>,
+    /// Output files which were still being written. They might be truncated.
+    pub interrupted_writes: Vec<PathBuf>,
+}
+
+const STARTED: &str = "started";
+const SNAPSHOT_STARTED: &str = "snapshot-started";
+const SNAPSHOT_FINISHED: &str = "snapshot-finished";
+const CLEAN_EXIT: &str = "clean-exit";
+const WRITE_STARTED: &str = "write-started";
+const WRITE_FINISHED: &str = "write-finished";
+
+impl SessionJournal {
+    /// Reads the journal of the previous session at `path` and replaces it
+    /// with the journal of a new session. Returns `None` as second value when
+    /// the previous session exited cleanly or there was none.
+    pub fn start(path: &Path) -> Result<(Self, Option<UncleanShutdown>)> {
+        let unclean_shutdown = match fs::read_to_string(path) {
+            Ok(journal) => unclean_shutdown(&journal),
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            // A journal that can't be read could be anything.
+            Err(_) => Some(UncleanShutdown::default()),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Unable to create session journal {}", path.display()))?;
+        let journal = Self {
+            file: Mutex::new(file),
+        };
+        journal.append(STARTED, Some(SystemTime::now()))?;
+        Ok((journal, unclean_shutdown))
+    }
+
+    pub fn snapshot_started(&self) -> Result<()> {
+        self.append(SNAPSHOT_STARTED, Some(SystemTime::now()))
+    }
+
+    pub fn snapshot_finished(&self) -> Result<()> {
+        self.append(SNAPSHOT_FINISHED, None)
+    }
+
+    /// Records that the output file at `path` is being written.
+    pub fn write_started(&self, path: &Path) -> Result<()> {
+        self.append_path(WRITE_STARTED, path)
+    }
+
+    pub fn write_finished(&self, path: &Path) -> Result<()> {
+        self.append_path(WRITE_FINISHED, path)
+    }
+
+    /// Marks the session as cleanly exited. Nothing must be written to the
+    /// database afterwards.
+    pub fn clean_exit(&self) -> Result<()> {
+        self.append(CLEAN_EXIT, None)
+    }
+
+    fn append(&self, record: &str, time: Option<SystemTime>) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        match time {
+            Some(time) => writeln!(file, "{record} {}", unix_millis(time))?,
+            None => writeln!(file, "{record}")?,
+        }
+        file.sync_data()
+            .context("Unable to write to the session journal")?;
+        Ok(())
+    }
+
+    fn append_path(&self, record: &str, path: &Path) -> Result<()> {
+        let Some(path) = path.to_str().filter(|path| !path.contains('\n')) else {
+            // Can't be represented in the journal, so it can't be verified after a crash.
+            return Ok(());
+        };
+        // A single write, so concurrent records don't interleave.
+        self.file
+            .lock()
+            .unwrap()
+            .write_all(format!("{record} {path}\n").as_bytes())
+            .context("Unable to write to the session journal")
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// Replays a journal. Unknown lines and a last line without line break, i.e. a
+/// record torn by a crash, are ignored.
+fn unclean_shutdown(journal: &str) -> Option<UncleanShutdown> {
+    let mut started = false;
+    let mut result = UncleanShutdown::default();
+    let mut writes = FxIndexSet::default();
+    for line in journal.split_inclusive('\n') {
+        let Some(line) = line.strip_suffix('\n') else {
+            break;
+        };
+        let (record, argument) = line.split_once(' ').unwrap_or((line, ""));
+        match record {
+            STARTED => started = true,
+            SNAPSHOT_STARTED => {
+                result.interrupted_snapshot = Some(argument.parse().map_or(UNIX_EPOCH, |millis| {
+                    UNIX_EPOCH + Duration::from_millis(millis)
+                }));
+            }
+            SNAPSHOT_FINISHED => result.interrupted_snapshot = None,
+            WRITE_STARTED => {
+                writes.insert(argument);
+            }
+            WRITE_FINISHED => {
+                writes.shift_remove(argument);
+            }
+            CLEAN_EXIT => return None,
+            _ => {}
+        }
+    }
+    result.interrupted_writes = writes.into_iter().map(PathBuf::from).collect();
+    started.then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay() {
+        assert_eq!(unclean_shutdown(""), None);
+        assert_eq!(
+            unclean_shutdown("started 1\nsnapshot-started 2\nsnapshot-finished\nclean-exit\n"),
+            None
+        );
+        assert_eq!(
+            unclean_shutdown("started 1\nsnapshot-started 2\nsnapshot-finished\n"),
+            Some(UncleanShutdown::default())
+        );
+        assert_eq!(
+            unclean_shutdown("started 1\nsnapshot-started 2\nclean-e"),
+            Some(UncleanShutdown {
+                interrupted_snapshot: Some(UNIX_EPOCH + Duration::from_millis(2)),
+                interrupted_writes: vec![],
+            })
+        );
+        assert_eq!(
+            unclean_shutdown(
+                "started 1\nwrite-started /out/a.js\nwrite-started /out/b c.js\nwrite-started \
+                 /out/d.js\nwrite-finished /out/a.js\nwrite-finished /out/d.js\nwrite-started \
+                 /out/d.js\nwrite-started /out/e"
+            ),
+            Some(UncleanShutdown {
+                interrupted_snapshot: None,
+                interrupted_writes: vec!["/out/b c.js".into(), "/out/d.js".into()],
+            })
+        );
+        assert_eq!(
+            unclean_shutdown("started 1\nwrite-started /out/a.js\nclean-exit\n"),
+            None
+        );
+    }
+}
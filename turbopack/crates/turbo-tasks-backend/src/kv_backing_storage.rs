@@ -2,15 +2,20 @@ use std::{
     borrow::{Borrow, Cow},
     cmp::max,
     collections::hash_map::Entry,
+    path::Path,
     sync::Arc,
 };
 
 use anyhow::{anyhow, Context, Result};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
-use rustc_hash::FxHashMap;
-use serde::{ser::SerializeSeq, Serialize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize};
 use tracing::Span;
-use turbo_tasks::{backend::CachedTaskType, turbo_tasks_scope, KeyValuePair, SessionId, TaskId};
+use turbo_tasks::{
+    backend::CachedTaskType,
+    blob::{collect_blob_references, enter_blob_store, Blob, BlobId, BlobStore},
+    turbo_tasks_scope, KeyValuePair, SessionId, TaskId,
+};
 
 use crate::{
     backend::{AnyOperation, TaskDataCategory},
@@ -18,6 +23,7 @@ use crate::{
     data::{CachedDataItem, CachedDataItemKey, CachedDataItemValue, CachedDataUpdate},
     database::{
         key_value_database::{KeySpace, KeyValueDatabase},
+        session_journal::SessionJournal,
         write_batch::{
             BaseWriteBatch, ConcurrentWriteBatch, SerialWriteBatch, WriteBatch, WriteBatchRef,
         },
@@ -38,6 +44,7 @@ fn pot_de_symbol_list<'l>() -> pot::de::SymbolList<'l> {
 const META_KEY_OPERATIONS: u32 = 0;
 const META_KEY_NEXT_FREE_TASK_ID: u32 = 1;
 const META_KEY_SESSION_ID: u32 = 2;
+const META_KEY_UNREFERENCED_BLOBS: u32 = 3;
 
 struct IntKey([u8; 4]);
 
@@ -58,13 +65,206 @@ fn as_u32(bytes: impl Borrow<[u8]>) -> Result<u32> {
     Ok(n)
 }
 
+/// The key of the [KeySpace::Infra] entry which stores the [BlobReferrer]s of a
+/// blob. The entry only exists while the blob is referenced. It doesn't collide
+/// with the [IntKey]s, as it's longer.
+struct BlobKey([u8; 16]);
+
+impl BlobKey {
+    fn new(blob: BlobId) -> Self {
+        Self(blob.to_le_bytes())
+    }
+}
+
+impl AsRef<[u8]> for BlobKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The stored task data referencing a blob.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+enum BlobReferrer {
+    TaskMeta(TaskId),
+    TaskData(TaskId),
+}
+
+impl BlobReferrer {
+    fn new(key_space: KeySpace, task: TaskId) -> Self {
+        match key_space {
+            KeySpace::TaskMeta => BlobReferrer::TaskMeta(task),
+            _ => BlobReferrer::TaskData(task),
+        }
+    }
+
+    fn key(self) -> (KeySpace, TaskId) {
+        match self {
+            BlobReferrer::TaskMeta(task) => (KeySpace::TaskMeta, task),
+            BlobReferrer::TaskData(task) => (KeySpace::TaskData, task),
+        }
+    }
+}
+
+fn deserialize_blob_referrers(bytes: Option<impl Borrow<[u8]>>) -> Result<Vec<BlobReferrer>> {
+    let Some(bytes) = bytes else {
+        return Ok(Vec::new());
+    };
+    POT_CONFIG
+        .deserialize(bytes.borrow())
+        .with_context(|| anyhow!("Unable to deserialize blob references"))
+}
+
 pub struct KeyValueDatabaseBackingStorage<T: KeyValueDatabase> {
     database: T,
+    blob_store: Option<Arc<dyn BlobStore>>,
+    session_journal: Option<SessionJournal>,
 }
 
 impl<T: KeyValueDatabase> KeyValueDatabaseBackingStorage<T> {
     pub fn new(database: T) -> Self {
-        Self { database }
+        Self {
+            database,
+            blob_store: None,
+            session_journal: None,
+        }
+    }
+
+    /// Stores large binary values (e.g. file contents) in the given
+    /// [BlobStore] instead of inlining them into the task data.
+    pub fn with_blob_store(self, blob_store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            blob_store: Some(blob_store),
+            ..self
+        }
+    }
+
+    /// Records the snapshots, the output writes and the clean exit of this
+    /// session in the given [SessionJournal].
+    pub fn with_session_journal(self, session_journal: SessionJournal) -> Self {
+        Self {
+            session_journal: Some(session_journal),
+            ..self
+        }
+    }
+
+    /// Removes all blobs which aren't referenced by the stored task data, e.g.
+    /// blobs written by a snapshot that was never committed. Must be called
+    /// before any task data is read.
+    pub fn remove_unreferenced_blobs(&self) -> Result<usize> {
+        let Some(blob_store) = &self.blob_store else {
+            return Ok(0);
+        };
+        let tx = self.database.begin_read_transaction()?;
+        // Blobs whose references can't be read are kept.
+        blob_store.retain_blobs(&|blob| {
+            !matches!(
+                self.database
+                    .get(&tx, KeySpace::Infra, BlobKey::new(blob).as_ref()),
+                Ok(None)
+            )
+        })
+    }
+
+    /// Drops the stored task data which references the given blobs, e.g. blobs
+    /// which were removed because they were incomplete after a crash. Tasks
+    /// whose cells are dropped this way are recomputed when the cells are read.
+    /// Must be called before any task data is read. Returns the number of
+    /// affected tasks.
+    pub fn forget_blobs(&self, removed: &[BlobId]) -> Result<usize> {
+        let Some(blob_store) = &self.blob_store else {
+            return Ok(0);
+        };
+        if removed.is_empty() {
+            return Ok(0);
+        }
+        let removed: FxHashSet<BlobId> = removed.iter().copied().collect();
+        let placeholder_store: Arc<dyn BlobStore> = Arc::new(ForgottenBlobStore {
+            inner: blob_store.clone(),
+            removed: removed.clone(),
+        });
+
+        let mut updates = Vec::new();
+        {
+            let tx = self.database.begin_read_transaction()?;
+            let mut referrers = FxHashSet::default();
+            for &blob in &removed {
+                referrers.extend(deserialize_blob_referrers(self.database.get(
+                    &tx,
+                    KeySpace::Infra,
+                    BlobKey::new(blob).as_ref(),
+                )?)?);
+            }
+            for referrer in referrers {
+                let (key_space, task) = referrer.key();
+                let Some(bytes) = self
+                    .database
+                    .get(&tx, key_space, IntKey::new(*task).as_ref())?
+                else {
+                    continue;
+                };
+                let items: Vec<ItemWithBlobs> = {
+                    let _blob_store = enter_blob_store(placeholder_store.clone());
+                    POT_CONFIG
+                        .deserialize(bytes.borrow())
+                        .with_context(|| anyhow!("Unable to deserialize data items for {task}"))?
+                };
+                let mut old_blobs: Vec<BlobId> = items
+                    .iter()
+                    .flat_map(|item| item.blobs.iter().copied())
+                    .collect();
+                old_blobs.sort_unstable();
+                old_blobs.dedup();
+                let items: Vec<CachedDataItem> = items
+                    .into_iter()
+                    .filter(|item| !item.blobs.iter().any(|blob| removed.contains(blob)))
+                    .map(|item| item.item)
+                    .collect();
+                updates.push((key_space, task, old_blobs, items));
+            }
+        }
+
+        let affected_tasks = updates.len();
+        let mut batch = self.database.write_batch()?;
+        let mut task_meta_blob_references = Vec::new();
+        let mut task_data_blob_references = Vec::new();
+        for (key_space, task, old_blobs, items) in updates {
+            let _blob_store = enter_blob_store(blob_store.clone());
+            let (value, blobs) = collect_blob_references(|| POT_CONFIG.serialize(&items));
+            let value =
+                value.with_context(|| anyhow!("Unable to serialize data items for {task}"))?;
+            batch.put(
+                key_space,
+                Cow::Borrowed(IntKey::new(*task).as_ref()),
+                Cow::Owned(value),
+            )?;
+            let blob_references = match key_space {
+                KeySpace::TaskMeta => &mut task_meta_blob_references,
+                _ => &mut task_data_blob_references,
+            };
+            blob_references.push((task, old_blobs, blobs));
+        }
+        // The removed blobs are no longer referenced. Other blobs which were only
+        // referenced by the dropped items are removed by
+        // [KeyValueDatabaseBackingStorage::remove_unreferenced_blobs].
+        update_blob_referrers(
+            &mut batch,
+            task_meta_blob_references,
+            task_data_blob_references,
+        )?;
+        let unreferenced = read_unreferenced_blobs(&batch)?;
+        if unreferenced.iter().any(|blob| removed.contains(blob)) {
+            write_unreferenced_blobs(
+                &mut batch,
+                unreferenced
+                    .into_iter()
+                    .filter(|blob| !removed.contains(blob))
+                    .collect(),
+            )?;
+        }
+        batch
+            .commit()
+            .with_context(|| anyhow!("Unable to commit forgotten blobs"))?;
+        Ok(affected_tasks)
     }
 
     fn with_tx<R>(
@@ -83,6 +283,47 @@ impl<T: KeyValueDatabase> KeyValueDatabaseBackingStorage<T> {
     }
 }
 
+/// Reads removed blobs as empty, so the task data referencing them can still be
+/// deserialized, see [KeyValueDatabaseBackingStorage::forget_blobs].
+struct ForgottenBlobStore {
+    inner: Arc<dyn BlobStore>,
+    removed: FxHashSet<BlobId>,
+}
+
+impl BlobStore for ForgottenBlobStore {
+    fn write_blob(&self, data: &[u8]) -> Result<BlobId> {
+        self.inner.write_blob(data)
+    }
+
+    fn read_blob(&self, id: BlobId) -> Result<Blob> {
+        if self.removed.contains(&id) {
+            return Ok(Arc::new([0u8; 0]));
+        }
+        self.inner.read_blob(id)
+    }
+
+    fn remove_blob(&self, id: BlobId) -> Result<()> {
+        self.inner.remove_blob(id)
+    }
+
+    fn retain_blobs(&self, is_referenced: &dyn Fn(BlobId) -> bool) -> Result<usize> {
+        self.inner.retain_blobs(is_referenced)
+    }
+}
+
+/// A [CachedDataItem] together with the blobs read while deserializing it.
+struct ItemWithBlobs {
+    item: CachedDataItem,
+    blobs: Vec<BlobId>,
+}
+
+impl<'de> Deserialize<'de> for ItemWithBlobs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (item, blobs) = collect_blob_references(|| CachedDataItem::deserialize(deserializer));
+        Ok(Self { item: item?, blobs })
+    }
+}
+
 fn get_infra_u32(database: &impl KeyValueDatabase, key: u32) -> Option<u32> {
     let tx = database.begin_read_transaction().ok()?;
     let value = database
@@ -138,9 +379,14 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
         data_updates: Vec<ChunkedVec<CachedDataUpdate>>,
     ) -> Result<()> {
         let _span = tracing::trace_span!("save snapshot", session_id = ?session_id, operations = operations.len());
+        if let Some(session_journal) = &self.session_journal {
+            session_journal.snapshot_started()?;
+        }
         let mut batch = self.database.write_batch()?;
-        let mut task_meta_items_result = Ok(Vec::new());
-        let mut task_data_items_result = Ok(Vec::new());
+        let mut task_meta_items_result = Ok(Default::default());
+        let mut task_data_items_result = Ok(Default::default());
+
+        let removed_blobs;
 
         // Start organizing the updates in parallel
         match &mut batch {
@@ -150,6 +396,7 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                         let _span = tracing::trace_span!("update task meta").entered();
                         task_meta_items_result = process_task_data(
                             &self.database,
+                            self.blob_store.as_ref(),
                             KeySpace::TaskMeta,
                             meta_updates,
                             Some(batch),
@@ -159,6 +406,7 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                         let _span = tracing::trace_span!("update task data").entered();
                         task_data_items_result = process_task_data(
                             &self.database,
+                            self.blob_store.as_ref(),
                             KeySpace::TaskData,
                             data_updates,
                             Some(batch),
@@ -237,14 +485,21 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                     anyhow::Ok(())
                 })?;
 
-                task_meta_items_result?;
-                task_data_items_result?;
+                let (_, task_meta_blob_references) = task_meta_items_result?;
+                let (_, task_data_blob_references) = task_data_items_result?;
+                removed_blobs = save_blob_references(
+                    &mut WriteBatchRef::concurrent(batch),
+                    self.blob_store.is_some(),
+                    task_meta_blob_references,
+                    task_data_blob_references,
+                )?;
             }
             WriteBatch::Serial(batch) => {
                 turbo_tasks::scope(|s| {
                     s.spawn(|_| {
                         task_meta_items_result = process_task_data(
                             &self.database,
+                            self.blob_store.as_ref(),
                             KeySpace::TaskMeta,
                             meta_updates,
                             None::<&T::ConcurrentWriteBatch<'_>>,
@@ -253,6 +508,7 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                     s.spawn(|_| {
                         task_data_items_result = process_task_data(
                             &self.database,
+                            self.blob_store.as_ref(),
                             KeySpace::TaskData,
                             data_updates,
                             None::<&T::ConcurrentWriteBatch<'_>>,
@@ -307,16 +563,24 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                     anyhow::Ok(())
                 })?;
 
+                let (task_meta_items, task_meta_blob_references) = task_meta_items_result?;
+                let (task_data_items, task_data_blob_references) = task_data_items_result?;
+                removed_blobs = save_blob_references(
+                    &mut WriteBatchRef::serial(batch),
+                    self.blob_store.is_some(),
+                    task_meta_blob_references,
+                    task_data_blob_references,
+                )?;
                 let jobs = [
                     (
                         KeySpace::TaskMeta,
                         tracing::trace_span!("update task meta"),
-                        task_meta_items_result?,
+                        task_meta_items,
                     ),
                     (
                         KeySpace::TaskData,
                         tracing::trace_span!("update task data"),
-                        task_data_items_result?,
+                        task_data_items,
                     ),
                 ];
                 for (key_space, span, task_items) in jobs {
@@ -340,6 +604,36 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
                 .commit()
                 .with_context(|| anyhow!("Unable to commit operations"))?;
         }
+        if let Some(blob_store) = &self.blob_store {
+            let _span = tracing::trace_span!("remove blobs", blobs = removed_blobs.len()).entered();
+            for blob in removed_blobs {
+                blob_store.remove_blob(blob)?;
+            }
+        }
+        if let Some(session_journal) = &self.session_journal {
+            session_journal.snapshot_finished()?;
+        }
+        Ok(())
+    }
+
+    fn output_write_started(&self, path: &Path) -> Result<()> {
+        if let Some(session_journal) = &self.session_journal {
+            session_journal.write_started(path)?;
+        }
+        Ok(())
+    }
+
+    fn output_write_finished(&self, path: &Path) -> Result<()> {
+        if let Some(session_journal) = &self.session_journal {
+            session_journal.write_finished(path)?;
+        }
+        Ok(())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        if let Some(session_journal) = &self.session_journal {
+            session_journal.clean_exit()?;
+        }
         Ok(())
     }
 
@@ -431,6 +725,7 @@ impl<T: KeyValueDatabase + Send + Sync + 'static> BackingStorage
             let result: Vec<CachedDataItem> = POT_CONFIG.deserialize(bytes.borrow())?;
             Ok(result)
         }
+        let _blob_store = self.blob_store.clone().map(enter_blob_store);
         self.with_tx(tx, |tx| lookup(&self.database, tx, task_id, category))
             .inspect_err(|err| println!("Looking up data for {task_id} failed: {err:?}"))
             .unwrap_or_default()
@@ -501,6 +796,133 @@ where
     Ok(())
 }
 
+/// Updates the blob references of the snapshot and returns the blobs which can be
+/// removed after the batch is committed.
+///
+/// Blobs which are no longer referenced are only removed by the next snapshot,
+/// as read transactions started before this snapshot might still read them.
+fn save_blob_references<'a, S, C>(
+    batch: &mut WriteBatchRef<'_, 'a, S, C>,
+    has_blob_store: bool,
+    task_meta: BlobReferenceUpdates,
+    task_data: BlobReferenceUpdates,
+) -> Result<Vec<BlobId>>
+where
+    S: SerialWriteBatch<'a>,
+    C: ConcurrentWriteBatch<'a>,
+{
+    if !has_blob_store {
+        return Ok(Vec::new());
+    }
+    let _span = tracing::trace_span!("update blob references").entered();
+    let changed = update_blob_referrers(batch, task_meta, task_data)?;
+    let mut removed = Vec::new();
+    for blob in read_unreferenced_blobs(&*batch)? {
+        // Blobs can be referenced again when the same content was written again.
+        // Blobs which became unreferenced again wait for the next snapshot.
+        if !changed.contains_key(&blob)
+            && batch
+                .get(KeySpace::Infra, BlobKey::new(blob).as_ref())?
+                .is_none()
+        {
+            removed.push(blob);
+        }
+    }
+    let mut unreferenced: Vec<BlobId> = changed
+        .into_iter()
+        .filter(|&(_, is_referenced)| !is_referenced)
+        .map(|(blob, _)| blob)
+        .collect();
+    unreferenced.sort_unstable();
+    write_unreferenced_blobs(batch, unreferenced)?;
+    Ok(removed)
+}
+
+/// Applies the changed blob references of the tasks to the [BlobReferrer]s of
+/// the blobs. Returns whether each blob whose references changed is still
+/// referenced. Only the entries of these blobs are read and written.
+fn update_blob_referrers<'a>(
+    batch: &mut impl SerialWriteBatch<'a>,
+    task_meta: BlobReferenceUpdates,
+    task_data: BlobReferenceUpdates,
+) -> Result<FxHashMap<BlobId, bool>> {
+    // The referrers of the blobs and whether they were stored before.
+    let mut referrers: FxHashMap<BlobId, (Vec<BlobReferrer>, bool)> = FxHashMap::default();
+    for (key_space, updates) in [
+        (KeySpace::TaskMeta, task_meta),
+        (KeySpace::TaskData, task_data),
+    ] {
+        for (task, old_blobs, new_blobs) in updates {
+            let referrer = BlobReferrer::new(key_space, task);
+            for (blobs, is_referenced) in [(old_blobs, false), (new_blobs, true)] {
+                for blob in blobs {
+                    let (blob_referrers, _) = match referrers.entry(blob) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let bytes = batch.get(KeySpace::Infra, BlobKey::new(blob).as_ref())?;
+                            let existed = bytes.is_some();
+                            entry.insert((deserialize_blob_referrers(bytes)?, existed))
+                        }
+                    };
+                    blob_referrers.retain(|r| *r != referrer);
+                    if is_referenced {
+                        blob_referrers.push(referrer);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut changed = FxHashMap::default();
+    for (blob, (blob_referrers, existed)) in referrers {
+        let key = BlobKey::new(blob);
+        changed.insert(blob, !blob_referrers.is_empty());
+        if !blob_referrers.is_empty() {
+            let value = POT_CONFIG
+                .serialize(&blob_referrers)
+                .with_context(|| anyhow!("Unable to serialize blob references"))?;
+            batch
+                .put(KeySpace::Infra, Cow::Borrowed(key.as_ref()), value.into())
+                .with_context(|| anyhow!("Unable to write blob references"))?;
+        } else if existed {
+            batch
+                .delete(KeySpace::Infra, Cow::Borrowed(key.as_ref()))
+                .with_context(|| anyhow!("Unable to delete blob references"))?;
+        }
+    }
+    Ok(changed)
+}
+
+fn read_unreferenced_blobs<'a>(batch: &impl BaseWriteBatch<'a>) -> Result<Vec<BlobId>> {
+    let Some(bytes) = batch.get(
+        KeySpace::Infra,
+        IntKey::new(META_KEY_UNREFERENCED_BLOBS).as_ref(),
+    )?
+    else {
+        return Ok(Vec::new());
+    };
+    POT_CONFIG
+        .deserialize(bytes.borrow())
+        .with_context(|| anyhow!("Unable to deserialize unreferenced blobs"))
+}
+
+fn write_unreferenced_blobs<'a>(
+    batch: &mut impl SerialWriteBatch<'a>,
+    unreferenced: Vec<BlobId>,
+) -> Result<()> {
+    let value = POT_CONFIG
+        .serialize(&unreferenced)
+        .with_context(|| anyhow!("Unable to serialize unreferenced blobs"))?;
+    batch
+        .put(
+            KeySpace::Infra,
+            Cow::Borrowed(IntKey::new(META_KEY_UNREFERENCED_BLOBS).as_ref()),
+            value.into(),
+        )
+        .with_context(|| anyhow!("Unable to write unreferenced blobs"))?;
+    Ok(())
+}
+
 fn serialize_task_type(
     task_type: &Arc<CachedTaskType>,
     mut task_type_bytes: &mut Vec<u8>,
@@ -524,24 +946,29 @@ fn serialize_task_type(
 }
 
 type SerializedTasks = Vec<Vec<(TaskId, Vec<u8>)>>;
+/// The blobs referenced by updated tasks before and after the update, for the
+/// tasks whose references changed.
+type BlobReferenceUpdates = Vec<(TaskId, Vec<BlobId>, Vec<BlobId>)>;
 type TaskUpdates =
     FxHashMap<CachedDataItemKey, (Option<CachedDataItemValue>, Option<CachedDataItemValue>)>;
 
 fn process_task_data<'a, B: ConcurrentWriteBatch<'a> + Send + Sync>(
     database: &(impl KeyValueDatabase + Sync),
+    blob_store: Option<&Arc<dyn BlobStore>>,
     key_space: KeySpace,
     updates: Vec<ChunkedVec<CachedDataUpdate>>,
     batch: Option<&B>,
-) -> Result<SerializedTasks> {
+) -> Result<(SerializedTasks, BlobReferenceUpdates)> {
     let span = Span::current();
     let turbo_tasks = turbo_tasks::turbo_tasks();
     let handle = tokio::runtime::Handle::current();
-    updates
+    let results = updates
         .into_par_iter()
         .with_max_len(1)
         .map(|updates| {
             let _span = span.clone().entered();
             let _guard = handle.clone().enter();
+            let _blob_store = blob_store.cloned().map(enter_blob_store);
             turbo_tasks_scope(turbo_tasks.clone(), || {
                 let mut task_updates: FxHashMap<TaskId, TaskUpdates> =
                     FxHashMap::with_capacity_and_hasher(updates.len(), Default::default());
@@ -669,24 +1096,33 @@ fn process_task_data<'a, B: ConcurrentWriteBatch<'a> + Send + Sync>(
                 } else {
                     Vec::with_capacity(task_updates.len())
                 };
+                let mut references = Vec::new();
                 for (task, mut updates) in task_updates {
+                    let mut old_blobs = Vec::new();
                     // Restore the old task data
                     if let Some(old_data) =
                         database.get(&tx, key_space, IntKey::new(*task).as_ref())?
                     {
-                        let old_data: Vec<CachedDataItem> = match POT_CONFIG
-                            .deserialize(old_data.borrow())
-                        {
-                            Ok(d) => d,
-                            Err(_) => serde_path_to_error::deserialize(
-                                &mut pot_de_symbol_list()
-                                    .deserializer_for_slice(old_data.borrow())?,
-                            )
-                            .with_context(|| {
-                                let old_data: &[u8] = old_data.borrow();
-                                anyhow!("Unable to deserialize old value of {task}: {old_data:?}")
-                            })?,
-                        };
+                        let (old_data, blobs) = collect_blob_references(|| {
+                            let old_data: Vec<CachedDataItem> = match POT_CONFIG
+                                .deserialize(old_data.borrow())
+                            {
+                                Ok(d) => d,
+                                Err(_) => serde_path_to_error::deserialize(
+                                    &mut pot_de_symbol_list()
+                                        .deserializer_for_slice(old_data.borrow())?,
+                                )
+                                .with_context(|| {
+                                    let old_data: &[u8] = old_data.borrow();
+                                    anyhow!(
+                                        "Unable to deserialize old value of {task}: {old_data:?}"
+                                    )
+                                })?,
+                            };
+                            anyhow::Ok(old_data)
+                        });
+                        let old_data = old_data?;
+                        old_blobs = blobs;
 
                         // Reserve capacity to avoid rehashing later
                         updates.reserve(old_data.len());
@@ -703,7 +1139,11 @@ fn process_task_data<'a, B: ConcurrentWriteBatch<'a> + Send + Sync>(
                     updates.retain(|_, (_, value)| value.is_some());
 
                     // Serialize new data
-                    let value = serialize(task, &mut updates)?;
+                    let (value, blobs) = collect_blob_references(|| serialize(task, &mut updates));
+                    let value = value?;
+                    if old_blobs != blobs {
+                        references.push((task, old_blobs, blobs));
+                    }
 
                     if let Some(batch) = batch {
                         batch.put(
@@ -718,10 +1158,12 @@ fn process_task_data<'a, B: ConcurrentWriteBatch<'a> + Send + Sync>(
                 }
 
                 span.record("restored_tasks", restored_tasks);
-                Ok(tasks)
+                Ok((tasks, references))
             })
         })
-        .collect::<Result<Vec<_>>>()
+        .collect::<Result<Vec<_>>>()?;
+    let (tasks, references): (SerializedTasks, Vec<_>) = results.into_iter().unzip();
+    Ok((tasks, references.into_iter().flatten().collect()))
 }
 
 fn serialize(task: TaskId, data: &mut TaskUpdates) -> Result<Vec<u8>> {
@@ -9,7 +9,7 @@ mod database;
 mod kv_backing_storage;
 mod utils;
 
-use std::path::Path;
+use std::{fs, io::ErrorKind, path::Path, sync::Arc};
 
 use anyhow::Result;
 
@@ -18,8 +18,8 @@ pub use self::{
     kv_backing_storage::KeyValueDatabaseBackingStorage,
 };
 use crate::database::{
-    handle_db_versioning, is_fresh, lmdb::LmbdKeyValueDatabase, FreshDbOptimization, NoopKvDb,
-    ReadTransactionCache, StartupCacheLayer,
+    handle_db_versioning, is_fresh, lmdb::LmbdKeyValueDatabase, FileBlobStore, FreshDbOptimization,
+    NoopKvDb, ReadTransactionCache, SessionJournal, StartupCacheLayer,
 };
 
 pub type LmdbBackingStorage = KeyValueDatabaseBackingStorage<
@@ -29,11 +29,48 @@ pub type LmdbBackingStorage = KeyValueDatabaseBackingStorage<
 pub fn lmdb_backing_storage(path: &Path) -> Result<LmdbBackingStorage> {
     let path = handle_db_versioning(path)?;
     let fresh_db = is_fresh(&path);
+    let blob_store = FileBlobStore::new(&path.join("blobs"))?;
+    let startup_cache_path = path.join("startup.cache");
+    let (session_journal, unclean_shutdown) = SessionJournal::start(&path.join("session.journal"))?;
+    let mut removed_blobs = Vec::new();
+    if let Some(unclean_shutdown) = &unclean_shutdown {
+        tracing::warn!(
+            "The previous session didn't shut down cleanly. Verifying the persistent cache."
+        );
+        // The startup cache is only a copy of database values, so it's cheaper to drop it
+        // than to verify it.
+        let _ = fs::remove_file(&startup_cache_path);
+        if let Some(snapshot_start) = unclean_shutdown.interrupted_snapshot {
+            removed_blobs = blob_store.remove_incomplete_blobs(snapshot_start)?;
+        }
+        // The writes are session dependent, so they are executed again and recreate the files.
+        for path in &unclean_shutdown.interrupted_writes {
+            if let Err(err) = fs::remove_file(path) {
+                if err.kind() != ErrorKind::NotFound {
+                    tracing::warn!(
+                        "Unable to remove the partially written output file {}: {err}",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
     let database = LmbdKeyValueDatabase::new(&path)?;
     let database = FreshDbOptimization::new(database, fresh_db);
-    let database = StartupCacheLayer::new(database, path.join("startup.cache"), fresh_db)?;
+    let database = StartupCacheLayer::new(database, startup_cache_path, fresh_db)?;
     let database = ReadTransactionCache::new(database);
-    Ok(KeyValueDatabaseBackingStorage::new(database))
+    let storage = KeyValueDatabaseBackingStorage::new(database)
+        .with_blob_store(Arc::new(blob_store))
+        .with_session_journal(session_journal);
+    let forgotten_tasks = storage.forget_blobs(&removed_blobs)?;
+    if forgotten_tasks > 0 {
+        tracing::warn!(
+            "Dropped the cached data of {forgotten_tasks} tasks, as it referenced incomplete \
+             blobs."
+        );
+    }
+    storage.remove_unreferenced_blobs()?;
+    Ok(storage)
 }
 
 pub type NoopBackingStorage = KeyValueDatabaseBackingStorage<NoopKvDb>;
@@ -38,6 +38,13 @@ impl<T, H> Sharded<T, H> {
         self.data[shard as usize].lock()
     }
 
+    /// Calls `f` for each shard. Only one shard is locked at a time.
+    pub fn for_each(&self, mut f: impl FnMut(&T)) {
+        for shard in self.data.iter() {
+            f(&shard.lock());
+        }
+    }
+
     pub fn take<R>(&self, map: impl Fn(T) -> R) -> Vec<R>
     where
         T: Default,
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/bulk_invalidation.rs
\ No newline at end of file
@@ -0,0 +1,55 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::Result;
+use turbo_tasks::{TurboTasks, Vc};
+use turbo_tasks_backend::{default_backing_storage, BackendOptions, TurboTasksBackend};
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static COMPUTATIONS: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn restores_compacted_tasks_without_recomputing() {
+    REGISTRATION.ensure_registered();
+    let path = std::path::PathBuf::from(concat!(env!("OUT_DIR"), "/.cache/compaction"));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    let tt = TurboTasks::new(TurboTasksBackend::new(
+        BackendOptions::default(),
+        default_backing_storage(&path).unwrap(),
+    ));
+
+    let read = || {
+        tt.run_once(async {
+            let value = double(21).await?;
+            Ok(*value)
+        })
+    };
+    assert_eq!(read().await.unwrap(), 42);
+    assert_eq!(COMPUTATIONS.load(Ordering::SeqCst), 1);
+
+    // The first compaction only forgets that the task was accessed.
+    tt.backend().compact();
+    assert!(tt.backend().compact() > 0);
+    // Nothing was accessed in between.
+    assert_eq!(tt.backend().compact(), 0);
+
+    assert_eq!(read().await.unwrap(), 42);
+    assert_eq!(COMPUTATIONS.load(Ordering::SeqCst), 1);
+    // The restored task was accessed again.
+    tt.backend().compact();
+    assert!(tt.backend().compact() > 0);
+
+    tt.stop_and_wait().await;
+}
+
+#[turbo_tasks::function]
+fn double(value: u32) -> Vc<u32> {
+    COMPUTATIONS.fetch_add(1, Ordering::SeqCst);
+    Vc::cell(value * 2)
+}
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/cycle_detection.rs
\ No newline at end of file
@@ -0,0 +1,138 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use anyhow::Result;
+use turbo_tasks::{
+    mark_durability, mark_session_dependent, run_once, Durability, TurboTasksApi, Vc,
+};
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+/// Stand-ins for a lockfile and for a file of an installed package. They are
+/// read without invalidators, so only a new session notices changes.
+static LOCKFILE: AtomicU32 = AtomicU32::new(0);
+static PACKAGE: AtomicU32 = AtomicU32::new(0);
+/// A stand-in for a source file.
+static SOURCE: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn restores_high_durability_tasks_until_their_dependencies_change() {
+    REGISTRATION.ensure_registered();
+    let name = "restores_high_durability_tasks";
+    LOCKFILE.store(1, Ordering::SeqCst);
+    PACKAGE.store(1, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, true);
+    assert_eq!(read_package(&tt).await, 1);
+    tt.stop_and_wait().await;
+
+    // A package changed without installing packages, it's restored without
+    // verifying it.
+    PACKAGE.store(2, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, false);
+    assert_eq!(read_package(&tt).await, 1);
+    tt.stop_and_wait().await;
+
+    // Installing packages changes the lockfile, which invalidates the package.
+    LOCKFILE.store(2, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, false);
+    assert_eq!(read_package(&tt).await, 2);
+    tt.stop_and_wait().await;
+}
+
+#[tokio::test]
+async fn verifies_low_durability_tasks_on_restore() {
+    REGISTRATION.ensure_registered();
+    let name = "verifies_low_durability_tasks";
+    SOURCE.store(1, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, true);
+    assert_eq!(read_source(&tt).await, 1);
+    tt.stop_and_wait().await;
+
+    SOURCE.store(2, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, false);
+    assert_eq!(read_source(&tt).await, 2);
+    tt.stop_and_wait().await;
+}
+
+#[tokio::test]
+async fn verifies_tasks_reading_inputs_of_mixed_durability_on_restore() {
+    REGISTRATION.ensure_registered();
+    let name = "verifies_mixed_durability_tasks";
+    LOCKFILE.store(1, Ordering::SeqCst);
+    PACKAGE.store(1, Ordering::SeqCst);
+    SOURCE.store(1, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, true);
+    assert_eq!(read_package_and_source(&tt).await, (1, 1));
+    tt.stop_and_wait().await;
+
+    // The source is read without being marked with a durability, so the task
+    // is verified even though it reads a package too.
+    SOURCE.store(2, Ordering::SeqCst);
+    let tt = REGISTRATION.create_turbo_tasks(name, false);
+    assert_eq!(read_package_and_source(&tt).await, (1, 2));
+    tt.stop_and_wait().await;
+}
+
+async fn read_package(tt: &Arc<dyn TurboTasksApi>) -> u32 {
+    run_once(tt.clone(), async {
+        Ok(*package().strongly_consistent().await?)
+    })
+    .await
+    .unwrap()
+}
+
+async fn read_source(tt: &Arc<dyn TurboTasksApi>) -> u32 {
+    run_once(tt.clone(), async {
+        Ok(*source().strongly_consistent().await?)
+    })
+    .await
+    .unwrap()
+}
+
+async fn read_package_and_source(tt: &Arc<dyn TurboTasksApi>) -> (u32, u32) {
+    run_once(tt.clone(), async {
+        Ok(*package_and_source().strongly_consistent().await?)
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value(transparent)]
+struct PackageAndSource((u32, u32));
+
+#[turbo_tasks::function]
+fn lockfile() -> Vc<u32> {
+    mark_session_dependent();
+    Vc::cell(LOCKFILE.load(Ordering::SeqCst))
+}
+
+#[turbo_tasks::function]
+async fn package() -> Result<Vc<u32>> {
+    mark_durability(Durability::High);
+    lockfile().await?;
+    Ok(Vc::cell(PACKAGE.load(Ordering::SeqCst)))
+}
+
+#[turbo_tasks::function]
+fn source() -> Vc<u32> {
+    mark_session_dependent();
+    Vc::cell(SOURCE.load(Ordering::SeqCst))
+}
+
+#[turbo_tasks::function]
+async fn package_and_source() -> Result<Vc<PackageAndSource>> {
+    mark_durability(Durability::High);
+    lockfile().await?;
+    mark_session_dependent();
+    Ok(Vc::cell((
+        PACKAGE.load(Ordering::SeqCst),
+        SOURCE.load(Ordering::SeqCst),
+    )))
+}
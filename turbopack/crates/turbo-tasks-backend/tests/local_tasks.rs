@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/local_tasks.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/stable_hash.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/stream.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/timeout.rs
\ No newline at end of file
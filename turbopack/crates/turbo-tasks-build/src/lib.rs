@@ -247,7 +247,8 @@ impl RegisterContext<'_> {
     }
 
     fn process_enum_inner(&mut self, enum_item: &ItemEnum) -> Result<()> {
-        if has_turbo_attribute(&enum_item.attrs, "value") {
+        // Instantiations of generic value types are registered when they're used.
+        if has_turbo_attribute(&enum_item.attrs, "value") && enum_item.generics.params.is_empty() {
             self.add_value(&enum_item.ident);
             self.add_value_debug_impl(&enum_item.ident);
         }
@@ -351,7 +352,10 @@ impl RegisterContext<'_> {
     }
 
     fn process_struct_inner(&mut self, struct_item: &ItemStruct) -> Result<()> {
-        if has_turbo_attribute(&struct_item.attrs, "value") {
+        // Instantiations of generic value types are registered when they're used.
+        if has_turbo_attribute(&struct_item.attrs, "value")
+            && struct_item.generics.params.is_empty()
+        {
             self.add_value(&struct_item.ident);
             self.add_value_debug_impl(&struct_item.ident);
         }
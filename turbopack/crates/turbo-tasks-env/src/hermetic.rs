@@ -0,0 +1,90 @@
+use std::env;
+
+use anyhow::{bail, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, Vc};
+
+use crate::{EnvMap, ProcessEnv};
+
+/// The env variable which tells processes started with the env, e.g. Node.js workers, that the
+/// build is hermetic.
+pub const HERMETIC_ENV_VAR: &str = "TURBOPACK_HERMETIC";
+
+/// The env variable with the paths processes started with the env can read in hermetic builds,
+/// separated like `PATH`.
+pub const HERMETIC_ROOTS_ENV_VAR: &str = "TURBOPACK_HERMETIC_ROOTS";
+
+/// Only allows reading declared env variables, so a build can't depend on undeclared inputs.
+/// Reading any other variable fails. Casing of the env vars is ignored.
+#[turbo_tasks::value]
+pub struct HermeticProcessEnv {
+    prior: ResolvedVc<Box<dyn ProcessEnv>>,
+    /// Names of allowed variables. A trailing `*` matches every variable with the prefix.
+    allowed: Vec<RcStr>,
+    /// Absolute paths which can be read, passed on to processes as [HERMETIC_ROOTS_ENV_VAR].
+    roots: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl HermeticProcessEnv {
+    #[turbo_tasks::function]
+    pub fn new(
+        prior: ResolvedVc<Box<dyn ProcessEnv>>,
+        allowed: Vec<RcStr>,
+        roots: Vec<RcStr>,
+    ) -> Vc<Self> {
+        HermeticProcessEnv {
+            prior,
+            allowed: allowed
+                .into_iter()
+                .map(|a| a.to_uppercase().into())
+                .collect(),
+            roots,
+        }
+        .cell()
+    }
+}
+
+impl HermeticProcessEnv {
+    fn is_allowed(&self, name: &str) -> bool {
+        let uppercase = name.to_uppercase();
+        self.allowed
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => uppercase.starts_with(prefix),
+                None => uppercase == **allowed,
+            })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProcessEnv for HermeticProcessEnv {
+    /// Only contains the allowed variables, [HERMETIC_ENV_VAR] and [HERMETIC_ROOTS_ENV_VAR].
+    #[turbo_tasks::function]
+    async fn read_all(&self) -> Result<Vc<EnvMap>> {
+        let prior = self.prior.read_all().await?;
+        let mut filtered = prior
+            .iter()
+            .filter(|(key, _)| self.is_allowed(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<FxIndexMap<_, _>>();
+        filtered.insert(HERMETIC_ENV_VAR.into(), "1".into());
+        let roots = env::join_paths(self.roots.iter().map(|root| &**root))?;
+        filtered.insert(
+            HERMETIC_ROOTS_ENV_VAR.into(),
+            roots.to_string_lossy().into(),
+        );
+        Ok(Vc::cell(filtered))
+    }
+
+    #[turbo_tasks::function]
+    fn read(&self, name: RcStr) -> Result<Vc<Option<RcStr>>> {
+        if !self.is_allowed(&name) {
+            bail!(
+                "Hermetic build: reading the env variable {name} is not allowed, as it isn't \
+                 declared as an input of the build"
+            );
+        }
+        Ok(self.prior.read(name))
+    }
+}
@@ -5,6 +5,7 @@ mod command_line;
 mod custom;
 mod dotenv;
 mod filter;
+mod hermetic;
 
 use std::{env, sync::Mutex};
 
@@ -13,8 +14,11 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, Vc};
 
 pub use self::{
-    command_line::CommandLineProcessEnv, custom::CustomProcessEnv, dotenv::DotenvProcessEnv,
+    command_line::CommandLineProcessEnv,
+    custom::CustomProcessEnv,
+    dotenv::DotenvProcessEnv,
     filter::FilterProcessEnv,
+    hermetic::{HermeticProcessEnv, HERMETIC_ENV_VAR, HERMETIC_ROOTS_ENV_VAR},
 };
 
 #[turbo_tasks::value(transparent)]
@@ -6,7 +6,9 @@ use anyhow::Result;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{mark_session_dependent, ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 pub fn register() {
     turbo_tasks::register();
@@ -160,6 +162,11 @@ pub struct FetchIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for FetchIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::FETCH_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.issue_context
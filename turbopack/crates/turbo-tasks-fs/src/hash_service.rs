@@ -0,0 +1,421 @@
+//! Serves content hashes and change notifications of a [DiskFileSystem] to other processes.
+//!
+//! Tools running next to the build (e.g. a type-check wrapper or a lint daemon) can connect to
+//! the socket instead of watching and hashing the same files on their own. The protocol is
+//! newline-delimited JSON:
+//!
+//! ```text
+//! > {"type":"hash","id":1,"path":"src/index.ts"}
+//! < {"type":"hash","id":1,"hash":"8c7e5b2f0a1d3e4f"}
+//! > {"type":"subscribe","id":2}
+//! < {"type":"subscribed","id":2}
+//! < {"type":"changed","paths":["src/index.ts"]}
+//! ```
+//!
+//! Paths are relative to the root of the filesystem and use `/` as separator. Paths leading out
+//! of the root, also through symlinks, are rejected. The hash of a missing file is `null`.
+//! Change notifications are only sent while the filesystem is watched, and a `resync` message
+//! replaces them when a client falls behind, after which it should drop everything it derived
+//! from earlier hashes.
+//!
+//! Only processes of the user owning the socket can connect to it.
+
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::{bail, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+
+use crate::{
+    util::{normalize_path, sys_to_unix, unix_to_sys},
+    DiskFileSystemInner,
+};
+
+/// Forwards the paths the watcher saw changing to the hash service.
+pub(crate) struct ChangeNotifier(broadcast::Sender<Arc<[PathBuf]>>);
+
+impl Default for ChangeNotifier {
+    fn default() -> Self {
+        Self(broadcast::channel(64).0)
+    }
+}
+
+impl ChangeNotifier {
+    pub(crate) fn notify(&self, paths: impl IntoIterator<Item = PathBuf>) {
+        if self.0.receiver_count() == 0 {
+            return;
+        }
+        let paths: Arc<[PathBuf]> = paths.into_iter().collect();
+        if !paths.is_empty() {
+            // Fails only when all receivers were dropped in the meantime.
+            let _ = self.0.send(paths);
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<[PathBuf]>> {
+        self.0.subscribe()
+    }
+}
+
+/// Hashes of file contents by their path with symlinks resolved, dropped when the watcher
+/// reports a change to the file or one of its parent directories.
+struct HashCache {
+    /// The root of the filesystem, as the watcher reports changed paths.
+    root: PathBuf,
+    /// The root of the filesystem with symlinks resolved.
+    real_root: PathBuf,
+    hashes: DashMap<PathBuf, u64>,
+    /// Incremented on every change, so a hash computed from a file that changed while it was read
+    /// isn't cached.
+    generation: AtomicU64,
+}
+
+impl HashCache {
+    fn new(root: PathBuf) -> Result<Self> {
+        Ok(Self {
+            real_root: std::fs::canonicalize(&root)?,
+            root,
+            hashes: Default::default(),
+            generation: Default::default(),
+        })
+    }
+
+    fn invalidate(&self, changed: &[PathBuf]) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        let changed: Vec<_> = changed
+            .iter()
+            .map(|path| match path.strip_prefix(&self.root) {
+                Ok(relative) => self.real_root.join(relative),
+                Err(_) => path.clone(),
+            })
+            .collect();
+        self.hashes
+            .retain(|path, _| !changed.iter().any(|changed| path.starts_with(changed)));
+    }
+
+    fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.hashes.clear();
+    }
+
+    async fn hash(&self, path: &Path) -> Result<Option<u64>> {
+        if let Some(hash) = self.hashes.get(path) {
+            return Ok(Some(*hash));
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+        let content = match tokio::fs::read(path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let hash = hash_xxh3_hash64(&content);
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.hashes.insert(path.to_path_buf(), hash);
+        }
+        Ok(Some(hash))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Request {
+    Hash { id: u64, path: String },
+    Subscribe { id: u64 },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Response {
+    Hash { id: u64, hash: Option<String> },
+    Subscribed { id: u64 },
+    Changed { paths: Vec<String> },
+    Resync,
+    Error { id: Option<u64>, message: String },
+}
+
+/// A running hash service, see [crate::DiskFileSystem::start_hash_service]. The service stops
+/// and removes its socket when this is dropped.
+pub struct HashServiceHandle {
+    socket_path: PathBuf,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl HashServiceHandle {
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for HashServiceHandle {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn start(
+    inner: Arc<DiskFileSystemInner>,
+    socket_path: PathBuf,
+) -> Result<HashServiceHandle> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+
+    use anyhow::Context;
+    use tokio::net::UnixListener;
+
+    // A socket left behind by a previous process is replaced, but nothing else.
+    match std::fs::symlink_metadata(&socket_path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            bail!(
+                "{} already exists and is not a socket",
+                socket_path.display()
+            )
+        }
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    // Only processes of the same user may connect. The socket is bound in a directory only the
+    // user can access and moved into place after its permissions are restricted, so no other
+    // process can connect in between.
+    let Some(file_name) = socket_path.file_name() else {
+        bail!("{} is not a valid socket path", socket_path.display());
+    };
+    let private_dir = socket_path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("Unable to create {}", private_dir.display()))?;
+    let private_socket_path = private_dir.join("socket");
+    let listener = UnixListener::bind(&private_socket_path).and_then(|listener| {
+        std::fs::set_permissions(&private_socket_path, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&private_socket_path, &socket_path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&private_socket_path);
+    std::fs::remove_dir(&private_dir)?;
+    let listener = listener?;
+
+    let cache = Arc::new(HashCache::new(inner.root_path().to_path_buf())?);
+    let mut changes = inner.changes.subscribe();
+    let invalidate_task = tokio::spawn({
+        let cache = cache.clone();
+        async move {
+            loop {
+                match changes.recv().await {
+                    Ok(changed) => cache.invalidate(&changed),
+                    Err(broadcast::error::RecvError::Lagged(_)) => cache.clear(),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    });
+    let accept_task = tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::warn!("hash service failed to accept a connection: {err}");
+                    continue;
+                }
+            };
+            let inner = inner.clone();
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                if let Err(err) = handle_connection(stream, &inner, &cache).await {
+                    tracing::debug!("hash service connection closed: {err}");
+                }
+            });
+        }
+    });
+
+    Ok(HashServiceHandle {
+        socket_path,
+        tasks: vec![invalidate_task, accept_task],
+    })
+}
+
+#[cfg(not(unix))]
+pub(crate) fn start(
+    _inner: Arc<DiskFileSystemInner>,
+    _socket_path: PathBuf,
+) -> Result<HashServiceHandle> {
+    bail!("The hash service is only supported on unix platforms")
+}
+
+#[cfg(unix)]
+async fn handle_connection(
+    stream: tokio::net::UnixStream,
+    inner: &DiskFileSystemInner,
+    cache: &HashCache,
+) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    let mut changes = None;
+    loop {
+        let response = tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                match serde_json::from_str(&line) {
+                    Ok(Request::Hash { id, path }) => match hash(inner, cache, &path).await {
+                        Ok(hash) => Response::Hash {
+                            id,
+                            hash: hash.map(encode_hex),
+                        },
+                        Err(err) => Response::Error {
+                            id: Some(id),
+                            message: err.to_string(),
+                        },
+                    },
+                    Ok(Request::Subscribe { id }) => {
+                        changes = Some(inner.changes.subscribe());
+                        Response::Subscribed { id }
+                    }
+                    Err(err) => Response::Error {
+                        id: None,
+                        message: format!("invalid request: {err}"),
+                    },
+                }
+            }
+            changed = next_change(&mut changes) => match changed {
+                Ok(paths) => Response::Changed {
+                    paths: paths
+                        .iter()
+                        .filter_map(|path| path.strip_prefix(inner.root_path()).ok())
+                        .map(|path| sys_to_unix(&path.to_string_lossy()).into_owned())
+                        .collect(),
+                },
+                Err(broadcast::error::RecvError::Lagged(_)) => Response::Resync,
+                Err(broadcast::error::RecvError::Closed) => {
+                    changes = None;
+                    continue;
+                }
+            },
+        };
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        write.write_all(&response).await?;
+    }
+}
+
+async fn next_change(
+    changes: &mut Option<broadcast::Receiver<Arc<[PathBuf]>>>,
+) -> Result<Arc<[PathBuf]>, broadcast::error::RecvError> {
+    match changes {
+        Some(changes) => changes.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn hash(inner: &DiskFileSystemInner, cache: &HashCache, path: &str) -> Result<Option<u64>> {
+    let Some(normalized) = normalize_path(path) else {
+        bail!("{path} is outside of the filesystem root");
+    };
+    // Symlinks are resolved, so a link inside of the root can't expose files outside of it.
+    let real_path =
+        match tokio::fs::canonicalize(inner.root_path().join(&*unix_to_sys(&normalized))).await {
+            Ok(real_path) => real_path,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+    if !real_path.starts_with(&cache.real_root) {
+        bail!("{path} is outside of the filesystem root");
+    }
+    cache.hash(&real_path).await
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixStream,
+    };
+
+    use super::*;
+    use crate::DiskFileSystem;
+
+    #[tokio::test]
+    async fn serves_hashes() {
+        crate::register();
+
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.txt"),
+            dir.path().join("linked.txt"),
+        )
+        .unwrap();
+        let socket_path = dir.path().join("hash.sock");
+
+        turbo_tasks_testing::VcStorage::with(async {
+            let fs =
+                DiskFileSystem::new("test".into(), dir.path().to_string_lossy().into(), vec![])
+                    .await?;
+            let _service = fs.start_hash_service(socket_path.clone())?;
+            assert_eq!(
+                std::fs::metadata(&socket_path)?.permissions().mode() & 0o777,
+                0o600
+            );
+
+            let (read, mut write) = UnixStream::connect(&socket_path).await?.into_split();
+            let mut lines = BufReader::new(read).lines();
+            write
+                .write_all(
+                    b"{\"type\":\"hash\",\"id\":1,\"path\":\"a.txt\"}\n\
+                      {\"type\":\"hash\",\"id\":2,\"path\":\"missing.txt\"}\n\
+                      {\"type\":\"hash\",\"id\":3,\"path\":\"../a.txt\"}\n\
+                      {\"type\":\"hash\",\"id\":4,\"path\":\"linked.txt\"}\n",
+                )
+                .await?;
+
+            let expected = encode_hex(hash_xxh3_hash64(b"hello"));
+            assert_eq!(
+                lines.next_line().await?.unwrap(),
+                format!(r#"{{"type":"hash","id":1,"hash":"{expected}"}}"#)
+            );
+            assert_eq!(
+                lines.next_line().await?.unwrap(),
+                r#"{"type":"hash","id":2,"hash":null}"#
+            );
+            assert!(lines
+                .next_line()
+                .await?
+                .unwrap()
+                .starts_with(r#"{"type":"error","id":3,"#));
+            assert!(lines
+                .next_line()
+                .await?
+                .unwrap()
+                .starts_with(r#"{"type":"error","id":4,"#));
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap()
+    }
+}
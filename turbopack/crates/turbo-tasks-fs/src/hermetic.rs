@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, Completion, ResolvedVc, Vc};
+
+use crate::FileSystemPath;
+
+/// Reports reads of a hermetic [DiskFileSystem][crate::DiskFileSystem] outside of its declared
+/// roots, e.g. by emitting an Issue. The reads themselves behave as if the path didn't exist.
+#[turbo_tasks::value_trait]
+pub trait HermeticViolationReporter {
+    fn report(self: Vc<Self>, path: Vc<FileSystemPath>) -> Vc<Completion>;
+}
+
+/// The declared inputs of a hermetic [DiskFileSystem][crate::DiskFileSystem].
+#[derive(Debug, Serialize, Deserialize, TraceRawVcs)]
+pub(crate) struct HermeticRoots {
+    /// Absolute paths with symlinks resolved.
+    #[turbo_tasks(trace_ignore)]
+    roots: Vec<PathBuf>,
+    pub reporter: ResolvedVc<Box<dyn HermeticViolationReporter>>,
+}
+
+impl HermeticRoots {
+    /// `roots` are absolute paths. Symlinks in them are resolved, so they can be compared with
+    /// the real paths of reads.
+    pub fn new(
+        roots: Vec<RcStr>,
+        reporter: ResolvedVc<Box<dyn HermeticViolationReporter>>,
+    ) -> Self {
+        let roots = roots
+            .iter()
+            .map(|root| dunce::canonicalize(&**root).unwrap_or_else(|_| PathBuf::from(&**root)))
+            .collect();
+        HermeticRoots { roots, reporter }
+    }
+
+    /// Whether `full_path` can be read. Symlinks are resolved first, so a link inside of a root
+    /// can't be used to read a file outside of the roots. When `follow_link` is false, only the
+    /// links in the parent directories are resolved, as for reading the link itself.
+    ///
+    /// Paths which don't exist can always be read, as probing them, e.g. while resolving, doesn't
+    /// make the result depend on undeclared inputs. Directories containing a root can be read
+    /// too, as resolving walks up the directory tree.
+    pub async fn allows(&self, full_path: &Path, is_dir: bool, follow_link: bool) -> bool {
+        let real_path = match (follow_link, full_path.parent(), full_path.file_name()) {
+            (false, Some(parent), Some(name)) => canonicalize(parent).await.map(|p| p.join(name)),
+            _ => canonicalize(full_path).await,
+        };
+        let Some(real_path) = real_path else {
+            return true;
+        };
+        self.roots
+            .iter()
+            .any(|root| real_path.starts_with(root) || (is_dir && root.starts_with(&real_path)))
+    }
+}
+
+async fn canonicalize(path: &Path) -> Option<PathBuf> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || dunce::canonicalize(path).ok())
+        .await
+        .ok()
+        .flatten()
+}
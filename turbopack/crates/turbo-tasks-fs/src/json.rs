@@ -42,6 +42,26 @@ impl UnparseableJson {
         }
     }
 
+    pub fn from_json5_error(e: json5::Error) -> Self {
+        let json5::Error::Message { msg, location } = e;
+        // Syntax errors embed a rendered snippet of the source, which
+        // `write_with_content` renders itself, so only keep the description.
+        let message = msg.lines().last().map_or(msg.as_str(), |line| {
+            line.trim_start().trim_start_matches("= ")
+        });
+        Self {
+            message: message.to_string().into(),
+            path: None,
+            start_location: location.map(|location| {
+                (
+                    location.line.saturating_sub(1),
+                    location.column.saturating_sub(1),
+                )
+            }),
+            end_location: None,
+        }
+    }
+
     pub fn from_serde_path_to_error(e: serde_path_to_error::Error<serde_json::Error>) -> Self {
         let inner = e.inner();
         Self {
@@ -12,6 +12,8 @@
 pub mod attach;
 pub mod embed;
 pub mod glob;
+pub mod hash_service;
+mod hermetic;
 mod invalidation;
 mod invalidator_map;
 pub mod json;
@@ -22,7 +24,9 @@ pub mod rope;
 pub mod source_context;
 pub mod util;
 pub(crate) mod virtual_fs;
+mod walk_dir;
 mod watcher;
+pub mod zip;
 
 use std::{
     borrow::Cow,
@@ -42,6 +46,9 @@ use auto_hash_map::AutoMap;
 use bitflags::bitflags;
 use dunce::simplified;
 use glob::Glob;
+use hash_service::{ChangeNotifier, HashServiceHandle};
+use hermetic::HermeticRoots;
+pub use hermetic::HermeticViolationReporter;
 use invalidation::InvalidateFilesystem;
 use invalidator_map::InvalidatorMap;
 use jsonc_parser::{parse_to_serde_value, ParseOptions};
@@ -59,14 +66,17 @@ use tokio::{
 use tracing::Instrument;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
-    debug::ValueDebugFormat, effect, mark_session_dependent, mark_stateful, trace::TraceRawVcs,
-    Completion, Invalidator, ReadRef, ResolvedVc, ValueToString, Vc,
+    debug::ValueDebugFormat, effect, mark_durability, mark_session_dependent, mark_stateful,
+    trace::TraceRawVcs, Completion, Durability, Invalidator, ReadRef, ResolvedVc, TryJoinIterExt,
+    ValueToString, Vc,
 };
 use turbo_tasks_hash::{
-    hash_xxh3_hash128, hash_xxh3_hash64, DeterministicHash, DeterministicHasher,
+    hash_xxh3_hash128, hash_xxh3_hash64, DeterministicHash, DeterministicHasher, Xxh3Hash64Hasher,
 };
 use util::{extract_disk_access, join_path, normalize_path, sys_to_unix, unix_to_sys};
 pub use virtual_fs::VirtualFileSystem;
+use walk_dir::walk_dir;
+pub use walk_dir::{WalkDirEntries, WalkDirOptions, WalkDirResult};
 use watcher::DiskWatcher;
 
 use self::{invalidation::Write, json::UnparseableJson, mutex_map::MutexMap};
@@ -209,6 +219,13 @@ struct DiskFileSystemInner {
 
     #[turbo_tasks(debug_ignore, trace_ignore)]
     watcher: DiskWatcher,
+    /// Paths changed on disk, for the [hash_service].
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    #[serde(skip)]
+    changes: ChangeNotifier,
+    /// The only paths which can be read in hermetic mode, see [DiskFileSystem::new_hermetic].
+    #[turbo_tasks(debug_ignore)]
+    hermetic: Option<HermeticRoots>,
 }
 
 impl DiskFileSystemInner {
@@ -217,6 +234,25 @@ impl DiskFileSystemInner {
         simplified(Path::new(&*self.root))
     }
 
+    /// Whether `full_path` can be read, see [HermeticRoots::allows]. Otherwise the violation is
+    /// reported and the read has to behave as if the path didn't exist.
+    async fn check_hermetic(
+        &self,
+        fs_path: Vc<FileSystemPath>,
+        full_path: &Path,
+        is_dir: bool,
+        follow_link: bool,
+    ) -> Result<bool> {
+        let Some(hermetic) = &self.hermetic else {
+            return Ok(true);
+        };
+        if hermetic.allows(full_path, is_dir, follow_link).await {
+            return Ok(true);
+        }
+        hermetic.reporter.report(fs_path).await?;
+        Ok(false)
+    }
+
     /// registers the path as an invalidator for the current task,
     /// has to be called within a turbo-tasks function
     fn register_invalidator(&self, path: &Path) -> Result<()> {
@@ -354,6 +390,33 @@ pub struct DiskFileSystem {
 }
 
 impl DiskFileSystem {
+    fn new_instance(
+        name: RcStr,
+        root: RcStr,
+        ignored_subpaths: Vec<RcStr>,
+        hermetic: Option<HermeticRoots>,
+    ) -> Vc<Self> {
+        mark_stateful();
+
+        let instance = DiskFileSystem {
+            inner: Arc::new(DiskFileSystemInner {
+                name,
+                root,
+                mutex_map: Default::default(),
+                invalidation_lock: Default::default(),
+                invalidator_map: InvalidatorMap::new(),
+                dir_invalidator_map: InvalidatorMap::new(),
+                watcher: DiskWatcher::new(
+                    ignored_subpaths.into_iter().map(PathBuf::from).collect(),
+                ),
+                changes: Default::default(),
+                hermetic,
+            }),
+        };
+
+        Self::cell(instance)
+    }
+
     pub fn name(&self) -> &RcStr {
         &self.inner.name
     }
@@ -389,6 +452,13 @@ impl DiskFileSystem {
         self.inner.watcher.stop_watching();
     }
 
+    /// Serves content hashes and change notifications of this filesystem to other processes on
+    /// a unix socket at `socket_path`, see [hash_service]. Change notifications require the
+    /// filesystem to be watched.
+    pub fn start_hash_service(&self, socket_path: PathBuf) -> Result<HashServiceHandle> {
+        hash_service::start(self.inner.clone(), socket_path)
+    }
+
     pub async fn to_sys_path(&self, fs_path: Vc<FileSystemPath>) -> Result<PathBuf> {
         // just in case there's a windows unc path prefix we remove it with `dunce`
         let path = self.inner.root_path();
@@ -422,6 +492,107 @@ fn format_absolute_fs_path(path: &Path, name: &str, root_path: &Path) -> Option<
     path
 }
 
+/// Files inside of `node_modules` which package managers update when
+/// installing packages.
+const PACKAGE_MANAGER_STATE_FILES: &[&str] = &[
+    ".modules.yaml",
+    ".package-lock.json",
+    ".yarn-state.yml",
+    ".yarn-integrity",
+];
+
+/// Lockfiles next to `node_modules`, or at the root of the workspace it belongs to.
+const LOCKFILES: &[&str] = &[
+    "pnpm-lock.yaml",
+    "package-lock.json",
+    "yarn.lock",
+    "bun.lockb",
+];
+
+/// Returns the outermost `node_modules` directory containing `path`, unless
+/// `path` is one of the [PACKAGE_MANAGER_STATE_FILES] in it.
+fn installed_packages_dir(path: &str) -> Option<&str> {
+    let end = if path == "node_modules" || path.starts_with("node_modules/") {
+        "node_modules".len()
+    } else {
+        path.find("/node_modules/")
+            .or_else(|| path.strip_suffix("/node_modules").map(|dir| dir.len()))?
+            + "/node_modules".len()
+    };
+    let (dir, rest) = path.split_at(end);
+    let is_state_file = rest
+        .strip_prefix('/')
+        .is_some_and(|file| PACKAGE_MANAGER_STATE_FILES.contains(&file));
+    (!is_state_file).then_some(dir)
+}
+
+/// The [LOCKFILES] in the directory containing `node_modules` and in all of its
+/// ancestors, since workspaces keep a single lockfile at their root.
+fn lockfile_paths(node_modules: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut dir = Some(node_modules.rsplit_once('/').map_or("", |(dir, _)| dir));
+    while let Some(current) = dir {
+        paths.extend(LOCKFILES.iter().map(|file| {
+            if current.is_empty() {
+                file.to_string()
+            } else {
+                format!("{current}/{file}")
+            }
+        }));
+        dir = match current.rsplit_once('/') {
+            Some((parent, _)) => Some(parent),
+            None if !current.is_empty() => Some(""),
+            None => None,
+        };
+    }
+    paths
+}
+
+/// Marks the current task as session dependent. Reads of installed packages
+/// are marked as [`Durability::High`], since they rarely change compared to
+/// application source code. Restoring them from the persistent cache doesn't
+/// verify them, so they depend on the [package_manager_state] instead, which
+/// invalidates them after packages were installed.
+async fn mark_path_durability(fs_path: &FileSystemPath) -> Result<()> {
+    let Some(node_modules) = installed_packages_dir(&fs_path.path) else {
+        mark_session_dependent();
+        return Ok(());
+    };
+    mark_durability(Durability::High);
+    package_manager_state(FileSystemPath::new_normalized(
+        *fs_path.fs,
+        node_modules.into(),
+    ))
+    .await?;
+    Ok(())
+}
+
+/// A hash of the lockfiles and of the package manager state files in
+/// `node_modules`, which changes when packages are installed.
+#[turbo_tasks::function]
+async fn package_manager_state(node_modules: Vc<FileSystemPath>) -> Result<Vc<u64>> {
+    let node_modules_ref = node_modules.await?;
+    let files = PACKAGE_MANAGER_STATE_FILES
+        .iter()
+        .map(|file| node_modules.join((*file).into()))
+        .chain(
+            lockfile_paths(&node_modules_ref.path)
+                .into_iter()
+                .map(|path| FileSystemPath::new_normalized(*node_modules_ref.fs, path.into())),
+        );
+    let mut hasher = Xxh3Hash64Hasher::new();
+    for file in files {
+        match &*file.read().await? {
+            FileContent::Content(file) => {
+                hasher.write_value(1u8);
+                hasher.write_ref(file.content());
+            }
+            FileContent::NotFound => hasher.write_value(0u8),
+        }
+    }
+    Ok(Vc::cell(hasher.finish()))
+}
+
 pub fn path_to_key(path: impl AsRef<Path>) -> String {
     path.as_ref().to_string_lossy().to_string()
 }
@@ -437,24 +608,31 @@ impl DiskFileSystem {
     ///   be a full path, since it is possible that root & project dir is different and requires to
     ///   ignore specific subpaths from each.
     #[turbo_tasks::function]
-    pub async fn new(name: RcStr, root: RcStr, ignored_subpaths: Vec<RcStr>) -> Result<Vc<Self>> {
-        mark_stateful();
-
-        let instance = DiskFileSystem {
-            inner: Arc::new(DiskFileSystemInner {
-                name,
-                root,
-                mutex_map: Default::default(),
-                invalidation_lock: Default::default(),
-                invalidator_map: InvalidatorMap::new(),
-                dir_invalidator_map: InvalidatorMap::new(),
-                watcher: DiskWatcher::new(
-                    ignored_subpaths.into_iter().map(PathBuf::from).collect(),
-                ),
-            }),
-        };
+    pub fn new(name: RcStr, root: RcStr, ignored_subpaths: Vec<RcStr>) -> Vc<Self> {
+        Self::new_instance(name, root, ignored_subpaths, None)
+    }
 
-        Ok(Self::cell(instance))
+    /// Create a new instance of `DiskFileSystem` which doesn't read paths outside of `roots`,
+    /// so a build can't depend on files which aren't declared as its inputs. Such paths are
+    /// reported to `reporter` and read as if they didn't exist.
+    ///
+    /// * `roots` - Absolute paths. They can be outside of `root` too, as symlinks are resolved
+    ///   before checking a path. Directories containing a root can be listed, but not the other
+    ///   entries in them.
+    #[turbo_tasks::function]
+    pub fn new_hermetic(
+        name: RcStr,
+        root: RcStr,
+        ignored_subpaths: Vec<RcStr>,
+        roots: Vec<RcStr>,
+        reporter: ResolvedVc<Box<dyn HermeticViolationReporter>>,
+    ) -> Vc<Self> {
+        Self::new_instance(
+            name,
+            root,
+            ignored_subpaths,
+            Some(HermeticRoots::new(roots, reporter)),
+        )
     }
 
     #[turbo_tasks::function(fs)]
@@ -462,9 +640,16 @@ impl DiskFileSystem {
         &self,
         fs_path: Vc<FileSystemPath>,
     ) -> Result<Vc<InternalDirectoryContent>> {
-        mark_session_dependent();
+        mark_path_durability(&*fs_path.await?).await?;
         let full_path = self.to_sys_path(fs_path).await?;
         self.inner.register_dir_invalidator(&full_path)?;
+        if !self
+            .inner
+            .check_hermetic(fs_path, &full_path, true, true)
+            .await?
+        {
+            return Ok(InternalDirectoryContent::not_found());
+        }
 
         // we use the sync std function here as it's a lot faster (600%) in
         // node-file-trace
@@ -530,9 +715,25 @@ impl Debug for DiskFileSystem {
 impl FileSystem for DiskFileSystem {
     #[turbo_tasks::function(fs)]
     async fn read(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<FileContent>> {
-        mark_session_dependent();
+        let fs_path_ref = fs_path.await?;
+        mark_path_durability(&fs_path_ref).await?;
         let full_path = self.to_sys_path(fs_path).await?;
         self.inner.register_invalidator(&full_path)?;
+        // Lockfiles are read for the package manager state of the `node_modules` in or below
+        // their directory, so they can be read when their directory can be.
+        let allowed = match full_path.parent() {
+            Some(dir) if LOCKFILES.contains(&fs_path_ref.file_name()) => {
+                self.inner.check_hermetic(fs_path, dir, true, true).await?
+            }
+            _ => {
+                self.inner
+                    .check_hermetic(fs_path, &full_path, false, true)
+                    .await?
+            }
+        };
+        if !allowed {
+            return Ok(FileContent::NotFound.cell());
+        }
 
         let _lock = self.inner.lock_path(&full_path).await;
         let content = match retry_future(|| File::from_path(full_path.clone()))
@@ -561,35 +762,49 @@ impl FileSystem for DiskFileSystem {
                 let fs = *fs_path.await?.fs;
                 let normalize =
                     |path: &RcStr| FileSystemPath::new_normalized(fs, path.clone()).to_resolved();
-                let mut normalized_entries = AutoMap::new();
-                for (name, entry) in entries {
-                    let entry = match entry {
-                        InternalDirectoryEntry::File(path) => {
-                            DirectoryEntry::File(normalize(path).await?)
-                        }
-                        InternalDirectoryEntry::Directory(path) => {
-                            DirectoryEntry::Directory(normalize(path).await?)
-                        }
-                        InternalDirectoryEntry::Symlink(path) => {
-                            DirectoryEntry::Symlink(normalize(path).await?)
-                        }
-                        InternalDirectoryEntry::Other(path) => {
-                            DirectoryEntry::Other(normalize(path).await?)
-                        }
-                        InternalDirectoryEntry::Error => DirectoryEntry::Error,
-                    };
-                    normalized_entries.insert(name.clone(), entry);
-                }
-                Ok(DirectoryContent::new(normalized_entries))
+                // The paths of all entries are created in one batch instead of
+                // one after the other, which matters for large directories.
+                let normalized_entries = entries
+                    .iter()
+                    .map(|(name, entry)| async move {
+                        let entry = match entry {
+                            InternalDirectoryEntry::File(path) => {
+                                DirectoryEntry::File(normalize(path).await?)
+                            }
+                            InternalDirectoryEntry::Directory(path) => {
+                                DirectoryEntry::Directory(normalize(path).await?)
+                            }
+                            InternalDirectoryEntry::Symlink(path) => {
+                                DirectoryEntry::Symlink(normalize(path).await?)
+                            }
+                            InternalDirectoryEntry::Other(path) => {
+                                DirectoryEntry::Other(normalize(path).await?)
+                            }
+                            InternalDirectoryEntry::Error => DirectoryEntry::Error,
+                        };
+                        anyhow::Ok((name.clone(), entry))
+                    })
+                    .try_join()
+                    .await?;
+                Ok(DirectoryContent::new(
+                    normalized_entries.into_iter().collect::<AutoMap<_, _>>(),
+                ))
             }
         }
     }
 
     #[turbo_tasks::function(fs)]
     async fn read_link(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<LinkContent>> {
-        mark_session_dependent();
+        mark_path_durability(&*fs_path.await?).await?;
         let full_path = self.to_sys_path(fs_path).await?;
         self.inner.register_invalidator(&full_path)?;
+        if !self
+            .inner
+            .check_hermetic(fs_path, &full_path, false, false)
+            .await?
+        {
+            return Ok(LinkContent::NotFound.cell());
+        }
 
         let _lock = self.inner.lock_path(&full_path).await;
         let link_path = match retry_future(|| fs::read_link(&full_path))
@@ -672,7 +887,7 @@ impl FileSystem for DiskFileSystem {
 
     #[turbo_tasks::function(fs)]
     async fn track(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<Completion>> {
-        mark_session_dependent();
+        mark_path_durability(&*fs_path.await?).await?;
         let full_path = self.to_sys_path(fs_path).await?;
         self.inner.register_invalidator(&full_path)?;
         Ok(Completion::new())
@@ -736,6 +951,9 @@ impl FileSystem for DiskFileSystem {
                                 })?;
                         }
                     }
+                    // A crash while writing would leave a truncated file behind, which the
+                    // next session would consider to be up to date.
+                    turbo_tasks::output_write_started(&full_path);
                     let full_path_to_write = full_path.clone();
                     retry_future(move || {
                         let full_path = full_path_to_write.clone();
@@ -771,6 +989,7 @@ impl FileSystem for DiskFileSystem {
                     ))
                     .await
                     .with_context(|| format!("failed to write to {}", full_path.display()))?;
+                    turbo_tasks::output_write_finished(&full_path);
                 }
                 FileContent::NotFound => {
                     retry_future(|| fs::remove_file(full_path.clone()))
@@ -919,9 +1138,20 @@ impl FileSystem for DiskFileSystem {
 
     #[turbo_tasks::function(fs)]
     async fn metadata(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<FileMeta>> {
-        mark_session_dependent();
+        mark_path_durability(&*fs_path.await?).await?;
         let full_path = self.to_sys_path(fs_path).await?;
         self.inner.register_invalidator(&full_path)?;
+        if !self
+            .inner
+            .check_hermetic(fs_path, &full_path, true, true)
+            .await?
+        {
+            bail!(
+                "reading metadata for {} is not allowed, as it is outside of the declared inputs \
+                 of the hermetic build",
+                full_path.display()
+            );
+        }
 
         let _lock = self.inner.lock_path(&full_path).await;
         let meta = retry_future(|| fs::metadata(full_path.clone()))
@@ -1282,6 +1512,11 @@ impl FileSystemPath {
         read_glob(self, glob, include_dot_files)
     }
 
+    #[turbo_tasks::function]
+    pub fn walk_dir(self: Vc<Self>, options: Vc<WalkDirOptions>) -> Vc<WalkDirResult> {
+        walk_dir(self, options)
+    }
+
     #[turbo_tasks::function]
     pub fn root(self: Vc<Self>) -> Vc<Self> {
         self.fs().root()
@@ -2009,23 +2244,11 @@ impl FileContent {
     pub fn parse_json5_ref(&self) -> FileJsonContent {
         match self {
             FileContent::Content(file) => match file.content.to_str() {
-                Ok(string) => match parse_to_serde_value(
-                    &string,
-                    &ParseOptions {
-                        allow_comments: true,
-                        allow_trailing_commas: true,
-                        allow_loose_object_property_names: true,
-                    },
-                ) {
-                    Ok(data) => match data {
-                        Some(value) => FileJsonContent::Content(value),
-                        None => FileJsonContent::unparseable(
-                            "text content doesn't contain any json data",
-                        ),
-                    },
-                    Err(e) => FileJsonContent::Unparseable(Box::new(
-                        UnparseableJson::from_jsonc_error(e, string.as_ref()),
-                    )),
+                Ok(string) => match json5::from_str(&string) {
+                    Ok(data) => FileJsonContent::Content(data),
+                    Err(e) => {
+                        FileJsonContent::Unparseable(Box::new(UnparseableJson::from_json5_error(e)))
+                    }
                 },
                 Err(_) => FileJsonContent::unparseable("binary is not valid utf-8 text"),
             },
@@ -2351,6 +2574,88 @@ pub fn register() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn finds_installed_packages_dir() {
+        assert_eq!(installed_packages_dir("src/index.js"), None);
+        assert_eq!(
+            installed_packages_dir("node_modules/react/index.js"),
+            Some("node_modules")
+        );
+        assert_eq!(installed_packages_dir("node_modules"), Some("node_modules"));
+        assert_eq!(
+            installed_packages_dir("apps/web/node_modules/.pnpm/a/node_modules/b/index.js"),
+            Some("apps/web/node_modules")
+        );
+        assert_eq!(installed_packages_dir("node_modules/.modules.yaml"), None);
+        assert_eq!(
+            installed_packages_dir("apps/web/node_modules/.package-lock.json"),
+            None
+        );
+        assert_eq!(installed_packages_dir("my_node_modules/a.js"), None);
+    }
+
+    #[test]
+    fn finds_lockfiles_up_to_the_workspace_root() {
+        assert_eq!(lockfile_paths("node_modules"), LOCKFILES);
+        let paths = lockfile_paths("apps/web/node_modules");
+        assert_eq!(paths.len(), 3 * LOCKFILES.len());
+        assert!(paths.contains(&"apps/web/pnpm-lock.yaml".to_string()));
+        assert!(paths.contains(&"apps/pnpm-lock.yaml".to_string()));
+        assert!(paths.contains(&"pnpm-lock.yaml".to_string()));
+    }
+
+    fn parse(
+        content: &str,
+        parse: fn(&FileContent) -> FileJsonContent,
+    ) -> Result<Value, Box<UnparseableJson>> {
+        match parse(&FileContent::Content(File::from(content))) {
+            FileJsonContent::Content(value) => Ok(value),
+            FileJsonContent::Unparseable(e) => Err(e),
+            FileJsonContent::NotFound => panic!("content should be found"),
+        }
+    }
+
+    #[test]
+    fn parses_json_with_comments() {
+        let value = parse(
+            "// comment\n{ \"a\": [1, 2,], /* comment */ \"b\": \"c\", }",
+            FileContent::parse_json_with_comments_ref,
+        )
+        .unwrap();
+        assert_eq!(value, serde_json::json!({ "a": [1, 2], "b": "c" }));
+
+        // Only JSON5 allows unquoted keys.
+        assert!(parse("{ a: 1 }", FileContent::parse_json_with_comments_ref).is_err());
+    }
+
+    #[test]
+    fn parses_json5() {
+        let value = parse(
+            "// comment\n{ a: 'single', hex: 0xff, half: .5, plus: +1, list: [1, 2,], /* comment \
+             */ text: 'multi\\\nline', }",
+            FileContent::parse_json5_ref,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "a": "single",
+                "hex": 255,
+                "half": 0.5,
+                "plus": 1,
+                "list": [1, 2],
+                "text": "multiline",
+            })
+        );
+
+        let error = parse("{\n  a: 1,\n  b: }", FileContent::parse_json5_ref).unwrap_err();
+        assert_eq!(
+            &*error.message,
+            "expected array, boolean, null, number, object, or string"
+        );
+        assert_eq!(error.start_location, Some((2, 5)));
+    }
+
     #[tokio::test]
     async fn with_extension() {
         crate::register();
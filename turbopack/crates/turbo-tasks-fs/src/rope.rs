@@ -1,21 +1,25 @@
 use std::{
     borrow::Cow,
-    cmp::{min, Ordering},
+    cmp::{min, Ordering, Reverse},
     fmt,
     io::{BufRead, Read, Result as IoResult, Write},
     mem,
-    ops::{AddAssign, Deref},
+    ops::{AddAssign, Deref, Range},
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, OnceLock},
     task::{Context as TaskContext, Poll},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use bytes::{Buf, Bytes};
 use futures::Stream;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use serde_bytes::ByteBuf;
 use tokio::io::{AsyncRead, ReadBuf};
+use turbo_tasks::blob::{current_blob_store, Blob, BlobStore, BLOB_THRESHOLD};
 use turbo_tasks_hash::{DeterministicHash, DeterministicHasher};
 use RopeElem::{Local, Shared};
 
@@ -36,6 +40,10 @@ pub struct Rope {
     /// A shareable container holding the rope's bytes.
     #[turbo_tasks(debug_ignore, trace_ignore)]
     data: InnerRope,
+
+    /// Lazily computed line index, see [Rope::line_index].
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    line_index: OnceLock<Arc<LineIndex>>,
 }
 
 /// An Arc container for ropes. This indirection allows for easily sharing the
@@ -116,6 +124,276 @@ impl Rope {
     pub fn to_bytes(&self) -> Result<Cow<'_, [u8]>> {
         self.data.to_bytes(self.length)
     }
+
+    /// Returns an iterator over the contiguous byte sections of the rope,
+    /// without copying any data.
+    pub fn chunks(&self) -> RopeChunks<'_> {
+        RopeChunks {
+            stack: vec![self.data.iter()],
+        }
+    }
+
+    /// Returns the index of line starts in this rope. The index is computed on
+    /// first use and shared between clones of the rope.
+    pub fn line_index(&self) -> Arc<LineIndex> {
+        self.line_index
+            .get_or_init(|| Arc::new(LineIndex::new(self)))
+            .clone()
+    }
+
+    /// Returns a new Rope that shares the bytes in `range` with this rope.
+    ///
+    /// Fails if the range is out of bounds or splits a UTF-8 encoded character,
+    /// like slicing a [str] would.
+    pub fn slice(&self, range: Range<usize>) -> Result<Rope> {
+        if range.start > range.end || range.end > self.length {
+            bail!(
+                "range {range:?} out of bounds of rope with length {}",
+                self.length
+            );
+        }
+        for index in [range.start, range.end] {
+            if !self.is_char_boundary(index) {
+                bail!("rope index {index} is not a char boundary");
+            }
+        }
+        Ok(self.slice_bytes(range))
+    }
+
+    /// Whether `index` is the start or end of a UTF-8 encoded character, i.e.
+    /// it doesn't point at a continuation byte.
+    fn is_char_boundary(&self, index: usize) -> bool {
+        if index == 0 || index >= self.length {
+            return true;
+        }
+        let mut offset = 0;
+        for chunk in self.chunks() {
+            if index < offset + chunk.len() {
+                return chunk[index - offset] & 0b1100_0000 != 0b1000_0000;
+            }
+            offset += chunk.len();
+        }
+        true
+    }
+
+    /// Like [Rope::slice], but the range might split characters.
+    ///
+    /// Panics if the range is out of bounds.
+    fn slice_bytes(&self, range: Range<usize>) -> Rope {
+        assert!(
+            range.start <= range.end && range.end <= self.length,
+            "range {range:?} out of bounds of rope with length {}",
+            self.length
+        );
+        let mut builder = RopeBuilder::default();
+        let mut offset = 0;
+        for bytes in self.read() {
+            let chunk_start = offset;
+            offset += bytes.len();
+            if offset <= range.start {
+                continue;
+            }
+            if chunk_start >= range.end {
+                break;
+            }
+            let start = range.start.saturating_sub(chunk_start);
+            let end = min(bytes.len(), range.end - chunk_start);
+            builder.length += end - start;
+            builder.committed.push(Local(bytes.slice(start..end)));
+        }
+        builder.build()
+    }
+
+    /// Returns the string in `range`. This only copies the bytes when the
+    /// range spans multiple sections of the rope.
+    ///
+    /// Fails if the range is out of bounds or the bytes are not valid UTF-8.
+    pub fn slice_str(&self, range: Range<usize>) -> Result<Cow<'_, str>> {
+        if range.start > range.end || range.end > self.length {
+            bail!(
+                "range {range:?} out of bounds of rope with length {}",
+                self.length
+            );
+        }
+        let mut offset = 0;
+        let mut owned: Option<Vec<u8>> = None;
+        for chunk in self.chunks() {
+            let chunk_start = offset;
+            offset += chunk.len();
+            if offset <= range.start {
+                continue;
+            }
+            if chunk_start >= range.end {
+                break;
+            }
+            let start = range.start.saturating_sub(chunk_start);
+            let end = min(chunk.len(), range.end - chunk_start);
+            if owned.is_none() && chunk_start + end == range.end {
+                // The whole range is within this section.
+                return std::str::from_utf8(&chunk[start..end])
+                    .context("failed to convert rope slice into string")
+                    .map(Cow::Borrowed);
+            }
+            owned
+                .get_or_insert_with(|| Vec::with_capacity(range.len()))
+                .extend_from_slice(&chunk[start..end]);
+        }
+        let bytes = owned.unwrap_or_default();
+        String::from_utf8(bytes)
+            .context("failed to convert rope slice into string")
+            .map(Cow::Owned)
+    }
+
+    /// Returns the byte ranges of all non-overlapping matches of `regex`.
+    ///
+    /// Each section of the rope is searched without copying it. Matches
+    /// crossing a section boundary are found by searching a window of
+    /// [REGEX_BOUNDARY_WINDOW] bytes around the boundary, so matches longer
+    /// than that may be missed when they cross a boundary.
+    pub fn find_all(&self, regex: &regex::bytes::Regex) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        let mut boundaries = Vec::new();
+        let mut offset = 0;
+        for chunk in self.chunks() {
+            if offset > 0 {
+                boundaries.push(offset);
+            }
+            matches.extend(
+                regex
+                    .find_iter(chunk)
+                    .map(|m| offset + m.start()..offset + m.end()),
+            );
+            offset += chunk.len();
+        }
+
+        if boundaries.is_empty() {
+            return matches;
+        }
+
+        for boundary in boundaries {
+            let window_start = boundary.saturating_sub(REGEX_BOUNDARY_WINDOW);
+            let window_end = min(self.length, boundary + REGEX_BOUNDARY_WINDOW);
+            let window = self.slice_bytes(window_start..window_end);
+            let Ok(window) = window.to_bytes() else {
+                continue;
+            };
+            matches.extend(
+                regex
+                    .find_iter(&window)
+                    .map(|m| window_start + m.start()..window_start + m.end())
+                    .filter(|m| m.start < boundary && boundary < m.end),
+            );
+        }
+
+        matches.sort_by_key(|m| (m.start, Reverse(m.end)));
+        let mut last_end = 0;
+        matches.retain(|m| {
+            if m.start < last_end {
+                return false;
+            }
+            last_end = m.end;
+            true
+        });
+        matches
+    }
+}
+
+/// Matches crossing a section boundary of a [Rope] are searched for within
+/// this many bytes on either side of the boundary, see [Rope::find_all].
+pub const REGEX_BOUNDARY_WINDOW: usize = 4 * 1024;
+
+/// Iterator over the contiguous byte sections of a [Rope], see
+/// [Rope::chunks].
+pub struct RopeChunks<'a> {
+    stack: Vec<std::slice::Iter<'a, RopeElem>>,
+}
+
+impl<'a> Iterator for RopeChunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let iter = self.stack.last_mut()?;
+            match iter.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some(Local(bytes)) => return Some(bytes),
+                Some(Shared(inner)) => self.stack.push(inner.iter()),
+            }
+        }
+    }
+}
+
+/// The byte offsets of all line starts in a [Rope], used to map byte offsets
+/// to line and column positions without materializing the rope as a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset of the start of each line. The first line always starts at
+    /// offset 0.
+    line_starts: Vec<usize>,
+
+    /// Total length of the indexed rope.
+    length: usize,
+}
+
+impl LineIndex {
+    fn new(rope: &Rope) -> Self {
+        let mut line_starts = vec![0];
+        let mut offset = 0;
+        for chunk in rope.chunks() {
+            line_starts.extend(
+                chunk
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| **b == b'\n')
+                    .map(|(i, _)| offset + i + 1),
+            );
+            offset += chunk.len();
+        }
+        Self {
+            line_starts,
+            length: offset,
+        }
+    }
+
+    /// Returns the number of lines. A trailing newline starts an additional
+    /// empty line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte offset of the start of the 0-based `line`.
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.line_starts.get(line).copied()
+    }
+
+    /// Returns the length in bytes of the 0-based `line`, excluding the line
+    /// break.
+    pub fn line_len(&self, line: usize) -> Option<usize> {
+        let start = self.line_start(line)?;
+        Some(match self.line_starts.get(line + 1) {
+            Some(next) => next - 1 - start,
+            None => self.length - start,
+        })
+    }
+
+    /// Maps a byte offset to a 0-based line and byte column. Columns pointing
+    /// past the end of a line are clamped to the line's length.
+    pub fn line_and_column(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let start = self.line_starts[line];
+        let len = self.line_len(line).unwrap_or_default();
+        (line, min(len, offset - start))
+    }
+
+    /// Maps a 0-based line and byte column to a byte offset.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        Some(self.line_start(line)? + column)
+    }
 }
 
 impl<T: Into<Bytes>> From<T> for Rope {
@@ -128,6 +406,7 @@ impl<T: Into<Bytes>> From<T> for Rope {
             Rope {
                 length: bytes.len(),
                 data: InnerRope(Arc::from([Local(bytes)])),
+                line_index: OnceLock::new(),
             }
         }
     }
@@ -210,6 +489,7 @@ impl RopeBuilder {
         Rope {
             length: self.length,
             data: InnerRope::from(self.committed),
+            line_index: OnceLock::new(),
         }
     }
 }
@@ -342,9 +622,18 @@ impl Serialize for Rope {
     /// Ropes are always serialized into contiguous strings, because
     /// deserialization won't deduplicate and share the Arcs (being the only
     /// possible owner of a individual "shared" data doesn't make sense).
+    ///
+    /// When a [BlobStore] is available, large ropes are written to it and
+    /// only the blob id is serialized.
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         use serde::ser::Error;
         let bytes = self.to_bytes().map_err(Error::custom)?;
+        if bytes.len() >= BLOB_THRESHOLD {
+            if let Some(store) = current_blob_store() {
+                let id = store.write_blob(&bytes).map_err(Error::custom)?;
+                return serializer.serialize_u128(id);
+            }
+        }
         match bytes {
             Cow::Borrowed(b) => serde_bytes::Bytes::new(b).serialize(serializer),
             Cow::Owned(b) => ByteBuf::from(b).serialize(serializer),
@@ -354,9 +643,57 @@ impl Serialize for Rope {
 
 impl<'de> Deserialize<'de> for Rope {
     /// Deserializes strings into a contiguous, immutable Rope.
+    ///
+    /// Ropes that were stored in a [BlobStore] reference the blob's bytes
+    /// directly, without copying them.
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let bytes = ByteBuf::deserialize(deserializer)?.into_vec();
-        Ok(Rope::from(bytes))
+        let Some(store) = current_blob_store() else {
+            let bytes = ByteBuf::deserialize(deserializer)?.into_vec();
+            return Ok(Rope::from(bytes));
+        };
+
+        struct RopeVisitor(Arc<dyn BlobStore>);
+
+        impl<'de> Visitor<'de> for RopeVisitor {
+            type Value = Rope;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array or a blob id")
+            }
+
+            fn visit_u128<E: de::Error>(self, id: u128) -> Result<Self::Value, E> {
+                let blob = self.0.read_blob(id).map_err(E::custom)?;
+                Ok(Rope::from(Bytes::from_owner(BlobOwner(blob))))
+            }
+
+            fn visit_u64<E: de::Error>(self, id: u64) -> Result<Self::Value, E> {
+                self.visit_u128(id as u128)
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(Rope::from(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Rope::from(v))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let bytes = ByteBuf::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+                Ok(Rope::from(bytes.into_vec()))
+            }
+        }
+
+        deserializer.deserialize_any(RopeVisitor(store))
+    }
+}
+
+/// Adapts a [Blob] to the `AsRef<[u8]>` owner expected by [Bytes::from_owner].
+struct BlobOwner(Blob);
+
+impl AsRef<[u8]> for BlobOwner {
+    fn as_ref(&self) -> &[u8] {
+        (*self.0).as_ref()
     }
 }
 
@@ -777,6 +1114,7 @@ mod test {
             Rope {
                 length: data.len(),
                 data,
+                line_index: OnceLock::new(),
             }
         }
     }
@@ -1012,4 +1350,54 @@ mod test {
         assert_eq!(rope.to_bytes()?, Cow::Borrowed::<[u8]>(&[0x61, 0x62, 0x63]));
         Ok(())
     }
+
+    #[test]
+    fn line_index_across_sections() {
+        let rope = Rope::new(vec!["ab\nc".into(), "d\n".into(), "e".into()]);
+        let index = rope.line_index();
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_start(2), Some(6));
+        assert_eq!(index.line_len(1), Some(2));
+        assert_eq!(index.line_and_column(4), (1, 1));
+        // Offsets pointing at the line break are clamped to the line length.
+        assert_eq!(index.line_and_column(2), (0, 2));
+        assert_eq!(index.offset(2, 0), Some(6));
+        assert_eq!(index.offset(3, 0), None);
+    }
+
+    #[test]
+    fn slice_rejects_split_characters() -> Result<()> {
+        // "é" is encoded in two bytes, the second one in the next section.
+        let rope = Rope::new(vec![
+            Local(Bytes::from_static(b"ab\xc3")),
+            Local(Bytes::from_static(b"\xa9c")),
+        ]);
+        assert_eq!(rope.slice(2..4)?.to_str()?, "é");
+        assert!(rope.slice(0..3).is_err());
+        assert!(rope.slice(3..5).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn slice_shares_sections() -> Result<()> {
+        let rope = Rope::new(vec!["abc".into(), vec!["def".into(), "ghi".into()].into()]);
+        assert_eq!(rope.slice(2..7)?, Rope::from("cdefg"));
+        assert_eq!(rope.slice(3..3)?, Rope::default());
+        assert!(rope.slice(5..10).is_err());
+
+        assert!(matches!(rope.slice_str(3..5)?, Cow::Borrowed("de")));
+        assert!(matches!(rope.slice_str(1..8)?, Cow::Owned(s) if s == "bcdefgh"));
+        assert!(rope.slice_str(5..10).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn find_all_across_sections() {
+        let rope = Rope::new(vec![
+            "/* webpack".into(),
+            "Ignore */ x /* webpackIgnore */".into(),
+        ]);
+        let regex = regex::bytes::Regex::new(r"webpackIgnore").unwrap();
+        assert_eq!(rope.find_all(&regex), vec![3..16, 25..38]);
+    }
 }
@@ -0,0 +1,419 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, ResolvedVc, TryJoinIterExt, Vc};
+
+use crate::{glob::Glob, DirectoryContent, DirectoryEntry, FileContent, FileSystemPath};
+
+#[turbo_tasks::value(shared)]
+#[derive(Default, Debug, Clone)]
+pub struct WalkDirOptions {
+    /// Whether entries starting with a `.` are walked.
+    pub include_dot_files: bool,
+    /// Whether entries ignored by a `.gitignore` file in the walked directory
+    /// or one of its walked subdirectories are skipped.
+    pub respect_gitignore: bool,
+    /// Entries whose path relative to the walked directory matches this glob
+    /// are skipped, including all their contents.
+    pub ignore: Option<ResolvedVc<Glob>>,
+}
+
+/// A directory tree. Every directory is walked in its own task, so changes
+/// only invalidate the directories which have been changed.
+#[turbo_tasks::value]
+#[derive(Default, Debug)]
+pub struct WalkDirResult {
+    /// The non-directory entries, keyed by their path relative to the walked
+    /// directory and sorted by it.
+    pub entries: Vec<(RcStr, DirectoryEntry)>,
+    /// The subdirectories, keyed and sorted like `entries`.
+    pub directories: Vec<(RcStr, ResolvedVc<WalkDirResult>)>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct WalkDirEntries(Vec<(RcStr, DirectoryEntry)>);
+
+#[turbo_tasks::value_impl]
+impl WalkDirResult {
+    /// All non-directory entries of the tree, sorted by their path.
+    #[turbo_tasks::function]
+    pub async fn flatten(&self) -> Result<Vc<WalkDirEntries>> {
+        let mut entries = self.entries.clone();
+        for inner in self
+            .directories
+            .iter()
+            .map(|(_, inner)| inner.flatten())
+            .try_join()
+            .await?
+        {
+            entries.extend(inner.iter().cloned());
+        }
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(Vc::cell(entries))
+    }
+}
+
+/// Walks a directory tree. Symlinks are resolved and the subdirectories of a
+/// directory are walked in parallel, which makes the first walk of a large
+/// tree much faster than reading it one directory after the other.
+///
+/// A symlink to a directory which is already being walked, like one of its
+/// parents, is returned as a [DirectoryEntry::Symlink] entry instead of being
+/// walked again.
+#[turbo_tasks::function(fs)]
+pub async fn walk_dir(
+    directory: Vc<FileSystemPath>,
+    options: Vc<WalkDirOptions>,
+) -> Result<Vc<WalkDirResult>> {
+    // The cycle detection compares real paths, so the walk starts from one.
+    let directory = directory.realpath();
+    Ok(*walk_dir_internal("", directory, options, IgnoreRules::empty(), &[]).await?)
+}
+
+#[turbo_tasks::function(fs)]
+async fn walk_dir_inner(
+    prefix: RcStr,
+    directory: Vc<FileSystemPath>,
+    options: Vc<WalkDirOptions>,
+    ignore_rules: Vc<IgnoreRules>,
+    symlink_targets: Vec<RcStr>,
+) -> Result<Vc<WalkDirResult>> {
+    Ok(*walk_dir_internal(&prefix, directory, options, ignore_rules, &symlink_targets).await?)
+}
+
+/// `symlink_targets` are the real paths of the directories which have been
+/// entered through a symlink on the way from the walked directory to
+/// `directory`.
+async fn walk_dir_internal(
+    prefix: &str,
+    directory: Vc<FileSystemPath>,
+    options: Vc<WalkDirOptions>,
+    ignore_rules: Vc<IgnoreRules>,
+    symlink_targets: &[RcStr],
+) -> Result<ResolvedVc<WalkDirResult>> {
+    let directory_value = directory.await?;
+    let dir = directory.read_dir().await?;
+    let DirectoryContent::Entries(entries) = &*dir else {
+        return Ok(WalkDirResult::default().resolved_cell());
+    };
+    let options_value = options.await?;
+    let ignore_rules = if options_value.respect_gitignore && entries.contains_key(".gitignore") {
+        ignore_rules.with_gitignore(prefix.into(), directory.join(".gitignore".into()))
+    } else {
+        ignore_rules
+    };
+    let ignore_rules_value = ignore_rules.await?;
+    let ignore = match options_value.ignore {
+        Some(ignore) => Some(ignore.await?),
+        None => None,
+    };
+
+    let entries = entries
+        .iter()
+        .filter(|(segment, _)| options_value.include_dot_files || !segment.starts_with('.'))
+        .map(|(segment, entry)| async move {
+            anyhow::Ok((segment, *entry, entry.resolve_symlink().await?))
+        })
+        .try_join()
+        .await?;
+
+    let mut result = WalkDirResult::default();
+    let mut directories = Vec::new();
+    for (segment, original_entry, entry) in entries {
+        let path = format!("{prefix}{segment}");
+        let is_directory = matches!(entry, DirectoryEntry::Directory(_));
+        if ignore.as_ref().is_some_and(|ignore| ignore.execute(&path))
+            || ignore_rules_value.is_ignored(&path, is_directory).await?
+        {
+            continue;
+        }
+        match entry {
+            DirectoryEntry::Directory(path_vc) => {
+                let mut symlink_targets = symlink_targets.to_vec();
+                if matches!(original_entry, DirectoryEntry::Symlink(_)) {
+                    let target = path_vc.await?;
+                    // Walking a directory which contains the symlink, or which
+                    // has already been entered through a symlink, would never
+                    // end.
+                    if directory_value.is_inside_or_equal_ref(&target)
+                        || symlink_targets.contains(&target.path)
+                    {
+                        result.entries.push((path.into(), original_entry));
+                        continue;
+                    }
+                    symlink_targets.push(target.path.clone());
+                }
+                // Calling the task function schedules the walk right away, so
+                // all subdirectories are walked in parallel below.
+                let inner = walk_dir_inner(
+                    format!("{path}/").into(),
+                    *path_vc,
+                    options,
+                    ignore_rules,
+                    symlink_targets,
+                );
+                directories.push((RcStr::from(path), inner));
+            }
+            entry => result.entries.push((path.into(), entry)),
+        }
+    }
+    result.directories = directories
+        .into_iter()
+        .map(|(path, inner)| async move { anyhow::Ok((path, inner.to_resolved().await?)) })
+        .try_join()
+        .await?;
+
+    result.entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    result.directories.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(result.resolved_cell())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TraceRawVcs, Serialize, Deserialize)]
+struct IgnoreRule {
+    /// The path of the directory containing the `.gitignore` file, relative
+    /// to the walked directory and with a trailing `/`.
+    base: RcStr,
+    glob: ResolvedVc<Glob>,
+    directory_only: bool,
+}
+
+/// The rules of all `.gitignore` files which apply to a directory.
+#[turbo_tasks::value]
+#[derive(Default, Debug)]
+struct IgnoreRules(Vec<IgnoreRule>);
+
+#[turbo_tasks::value_impl]
+impl IgnoreRules {
+    #[turbo_tasks::function]
+    fn empty() -> Vc<Self> {
+        IgnoreRules::default().cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn with_gitignore(
+        self: Vc<Self>,
+        base: RcStr,
+        gitignore: Vc<FileSystemPath>,
+    ) -> Result<Vc<Self>> {
+        let mut rules = self.await?.0.clone();
+        if let FileContent::Content(file) = &*gitignore.read().await? {
+            for line in file.content().to_str()?.lines() {
+                let Some((pattern, directory_only)) = parse_gitignore_line(line) else {
+                    continue;
+                };
+                // Patterns git understands but we can't parse are skipped
+                // rather than failing the walk.
+                let Ok(glob) = Glob::parse(&pattern) else {
+                    continue;
+                };
+                rules.push(IgnoreRule {
+                    base: base.clone(),
+                    glob: glob.resolved_cell(),
+                    directory_only,
+                });
+            }
+        }
+        Ok(IgnoreRules(rules).cell())
+    }
+}
+
+impl IgnoreRules {
+    async fn is_ignored(&self, path: &str, is_directory: bool) -> Result<bool> {
+        for rule in &self.0 {
+            if rule.directory_only && !is_directory {
+                continue;
+            }
+            let Some(relative) = path.strip_prefix(&*rule.base) else {
+                continue;
+            };
+            if rule.glob.await?.execute(relative) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Converts a line of a `.gitignore` file into a glob relative to its
+/// directory, and whether it only matches directories.
+///
+/// Negated patterns (`!pattern`) aren't supported and are skipped, so a walk
+/// might skip entries which git doesn't ignore.
+fn parse_gitignore_line(line: &str) -> Option<(String, bool)> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+        return None;
+    }
+    let line = line.strip_prefix('\\').unwrap_or(line);
+    let (line, directory_only) = match line.strip_suffix('/') {
+        Some(line) => (line, true),
+        None => (line, false),
+    };
+    let pattern = if let Some(line) = line.strip_prefix('/') {
+        line.to_string()
+    } else if line.contains('/') {
+        line.to_string()
+    } else {
+        format!("**/{line}")
+    };
+    Some((pattern, directory_only))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use turbo_rcstr::RcStr;
+    use turbo_tasks::Vc;
+
+    use super::{parse_gitignore_line, WalkDirOptions};
+    use crate::{glob::Glob, DirectoryEntry, DiskFileSystem, FileSystem, FileSystemPath};
+
+    fn disk_root(root: &Path) -> Vc<FileSystemPath> {
+        let fs = DiskFileSystem::new("test".into(), root.to_string_lossy().into(), vec![]);
+        Vc::upcast::<Box<dyn FileSystem>>(fs).root()
+    }
+
+    async fn walk(root: &Path, options: WalkDirOptions) -> anyhow::Result<Vec<(RcStr, bool)>> {
+        let entries = disk_root(root).walk_dir(options.cell()).flatten().await?;
+        Ok(entries
+            .iter()
+            .map(|(path, entry)| (path.clone(), matches!(entry, DirectoryEntry::Symlink(_))))
+            .collect())
+    }
+
+    fn files(paths: &[&str]) -> Vec<(RcStr, bool)> {
+        paths.iter().map(|path| ((*path).into(), false)).collect()
+    }
+
+    #[test]
+    fn gitignore_lines() {
+        assert_eq!(parse_gitignore_line("# comment"), None);
+        assert_eq!(parse_gitignore_line("!keep.log"), None);
+        assert_eq!(
+            parse_gitignore_line("node_modules/"),
+            Some(("**/node_modules".to_string(), true))
+        );
+        assert_eq!(
+            parse_gitignore_line("/dist"),
+            Some(("dist".to_string(), false))
+        );
+        assert_eq!(
+            parse_gitignore_line("docs/*.md  "),
+            Some(("docs/*.md".to_string(), false))
+        );
+        assert_eq!(
+            parse_gitignore_line("\\#file"),
+            Some(("**/#file".to_string(), false))
+        );
+    }
+
+    #[tokio::test]
+    async fn walks_ignore_aware() {
+        crate::register();
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        std::fs::write(root.join("a.txt"), "").unwrap();
+        std::fs::write(root.join("debug.log"), "").unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::write(root.join("build/out.js"), "").unwrap();
+        std::fs::create_dir_all(root.join("src/generated")).unwrap();
+        std::fs::write(root.join("src/index.js"), "").unwrap();
+        std::fs::write(root.join("src/.env"), "").unwrap();
+        std::fs::write(root.join("src/generated/types.js"), "").unwrap();
+
+        turbo_tasks_testing::VcStorage::with(async {
+            assert_eq!(
+                walk(root, WalkDirOptions::default()).await?,
+                files(&[
+                    "a.txt",
+                    "build/out.js",
+                    "debug.log",
+                    "src/generated/types.js",
+                    "src/index.js",
+                ])
+            );
+
+            let ignore = Glob::new("src/generated".into())?.to_resolved().await?;
+            assert_eq!(
+                walk(
+                    root,
+                    WalkDirOptions {
+                        include_dot_files: true,
+                        respect_gitignore: true,
+                        ignore: Some(ignore),
+                    }
+                )
+                .await?,
+                files(&[".gitignore", "a.txt", "src/.env", "src/index.js"])
+            );
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn does_not_walk_symlinks_to_parents() {
+        crate::register();
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/index.js"), "").unwrap();
+        std::os::unix::fs::symlink("..", root.join("src/root")).unwrap();
+        std::os::unix::fs::symlink(".", root.join("src/self")).unwrap();
+
+        turbo_tasks_testing::VcStorage::with(async {
+            assert_eq!(
+                walk(root, WalkDirOptions::default()).await?,
+                vec![
+                    ("src/index.js".into(), false),
+                    ("src/root".into(), true),
+                    ("src/self".into(), true),
+                ]
+            );
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn walks_symlink_cycles_once() {
+        crate::register();
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+        std::fs::write(root.join("a/file.txt"), "").unwrap();
+        std::os::unix::fs::symlink("../b", root.join("a/to_b")).unwrap();
+        std::os::unix::fs::symlink("../a", root.join("b/to_a")).unwrap();
+
+        turbo_tasks_testing::VcStorage::with(async {
+            // Every directory is entered through each symlink at most once,
+            // after that the symlink is returned as an entry.
+            assert_eq!(
+                walk(root, WalkDirOptions::default()).await?,
+                vec![
+                    ("a/file.txt".into(), false),
+                    ("a/to_b/to_a/file.txt".into(), false),
+                    ("a/to_b/to_a/to_b".into(), true),
+                    ("b/to_a/file.txt".into(), false),
+                    ("b/to_a/to_b/to_a".into(), true),
+                ]
+            );
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap()
+    }
+}
@@ -6,6 +6,7 @@ use std::{
         mpsc::{channel, Receiver, TryRecvError},
         Arc, Mutex,
     },
+    thread::{self, JoinHandle},
     time::Duration,
 };
 
@@ -17,7 +18,7 @@ use notify::{
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
-use turbo_tasks::{spawn_thread, Invalidator};
+use turbo_tasks::{invalidate_all, invalidate_all_with_reason, spawn_thread, Invalidator};
 
 use crate::{
     format_absolute_fs_path,
@@ -44,6 +45,10 @@ pub(crate) struct DiskWatcher {
     #[serde(skip)]
     watcher: Mutex<Option<DiskWatcherInternal>>,
 
+    /// The thread processing the events of `watcher`.
+    #[serde(skip)]
+    watch_thread: Mutex<Option<JoinHandle<()>>>,
+
     /// Array of paths that should not notify invalidations.
     /// `notify` currently doesn't support unwatching subpaths from the root,
     /// so underlying we still watches filesystem event but only skips to
@@ -213,21 +218,30 @@ impl DiskWatcher {
         watcher_guard.replace(watcher);
         drop(watcher_guard);
 
-        spawn_thread(move || {
+        let watch_thread = spawn_thread(move || {
             inner
                 .clone()
                 .watcher
                 .watch_thread(rx, inner, report_invalidation_reason)
         });
+        self.watch_thread.lock().unwrap().replace(watch_thread);
 
         Ok(())
     }
 
+    /// Stops watching and waits for the watch thread to process the pending
+    /// events and exit. When called from the watch thread itself, it exits
+    /// after the current event instead, as it can't wait for itself.
     pub(crate) fn stop_watching(&self) {
         if let Some(watcher) = self.watcher.lock().unwrap().take() {
             drop(watcher);
             // thread will detect the stop because the channel is disconnected
         }
+        if let Some(watch_thread) = self.watch_thread.lock().unwrap().take() {
+            if watch_thread.thread().id() != thread::current().id() {
+                let _ = watch_thread.join();
+            }
+        }
     }
 
     /// Internal thread that processes the events from the watcher
@@ -405,6 +419,13 @@ impl DiskWatcher {
                 }
             }
 
+            inner.changes.notify(
+                batched_invalidate_path
+                    .iter()
+                    .chain(batched_invalidate_path_and_children.iter())
+                    .cloned(),
+            );
+
             let _lock = inner.invalidation_lock.blocking_write();
             {
                 let mut invalidator_map = inner.invalidator_map.lock().unwrap();
@@ -480,10 +501,36 @@ fn invalidate_path_and_children_execute(
 ) {
     for path in paths {
         let path_key = path_to_key(&path);
-        for (_, invalidators) in invalidator_map.extract_if(|key, _| key.starts_with(&path_key)) {
-            invalidators
-                .into_iter()
-                .for_each(|i| invalidate(inner, report_invalidation_reason, &path, i));
+        let invalidators = invalidator_map
+            .extract_if(|key, _| key.starts_with(&path_key))
+            .flat_map(|(_, invalidators)| invalidators)
+            .collect::<Vec<_>>();
+        invalidate_subtree(inner, report_invalidation_reason, &path, invalidators);
+    }
+}
+
+/// Invalidates all reads within a subtree (e.g. a renamed directory or a branch switch) with a
+/// single bulk invalidation, instead of invalidating every read one by one.
+#[instrument(parent = None, level = "info", name = "DiskFileSystem subtree change", skip_all, fields(name = display(path.display()), count = invalidators.len()))]
+fn invalidate_subtree(
+    inner: &DiskFileSystemInner,
+    report_invalidation_reason: bool,
+    path: &Path,
+    invalidators: Vec<Invalidator>,
+) {
+    match invalidators.len() {
+        0 => {}
+        1 => invalidators
+            .into_iter()
+            .for_each(|i| invalidate(inner, report_invalidation_reason, path, i)),
+        _ => {
+            if report_invalidation_reason {
+                if let Some(path) = format_absolute_fs_path(path, &inner.name, inner.root_path()) {
+                    invalidate_all_with_reason(invalidators, WatchChange { path });
+                    return;
+                }
+            }
+            invalidate_all(invalidators);
         }
     }
 }
@@ -0,0 +1,348 @@
+use std::{collections::BTreeMap, io::Read};
+
+use anyhow::{bail, Context, Result};
+use auto_hash_map::AutoMap;
+use bytes::Bytes;
+use flate2::read::DeflateDecoder;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{Completion, ResolvedVc, ValueToString, Vc};
+
+use crate::{
+    DirectoryContent, DirectoryEntry, File, FileContent, FileMeta, FileSystem, FileSystemPath,
+    LinkContent,
+};
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+
+const COMPRESSION_STORED: u16 = 0;
+const COMPRESSION_DEFLATE: u16 = 8;
+
+/// A read-only [FileSystem] exposing the contents of a zip archive, e.g. a
+/// package in the cache of Yarn Plug'n'Play.
+///
+/// The archive is read through its [FileSystemPath], so changes to the
+/// archive invalidate all reads from this file system.
+#[turbo_tasks::value]
+pub struct ZipFileSystem {
+    archive: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl ZipFileSystem {
+    #[turbo_tasks::function]
+    pub fn new(archive: ResolvedVc<FileSystemPath>) -> Vc<Self> {
+        ZipFileSystem { archive }.cell()
+    }
+
+    /// The path of the zip archive.
+    #[turbo_tasks::function]
+    pub fn archive(&self) -> Vc<FileSystemPath> {
+        *self.archive
+    }
+
+    /// Parses the central directory once and keeps the archive in memory, so
+    /// reading a file only needs to extract it.
+    #[turbo_tasks::function]
+    async fn index(&self) -> Result<Vc<ZipIndex>> {
+        let content = self.archive.read().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(ZipIndex::default().cell());
+        };
+        let data = Bytes::from(file.content().to_bytes()?.into_owned());
+        let entries = match read_central_directory(&data) {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Err(err.context(format!(
+                    "Unable to read zip archive {}",
+                    self.archive.to_string().await?
+                )))
+            }
+        };
+        Ok(ZipIndex { data, entries }.cell())
+    }
+}
+
+#[turbo_tasks::value(serialization = "none")]
+#[derive(Default)]
+struct ZipIndex {
+    /// The contents of the archive.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    data: Bytes,
+    /// File entries by their path inside of the archive. Directories are not
+    /// listed, as archives don't need to contain entries for them.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    entries: BTreeMap<RcStr, ZipEntry>,
+}
+
+impl ZipIndex {
+    fn is_dir(&self, path: &str) -> bool {
+        if path.is_empty() {
+            return true;
+        }
+        let prefix = format!("{path}/");
+        self.entries
+            .range::<str, _>(prefix.as_str()..)
+            .next()
+            .is_some_and(|(name, _)| name.starts_with(&prefix))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ZipEntry {
+    compression: u16,
+    compressed_size: usize,
+    size: usize,
+    local_header_offset: usize,
+}
+
+impl ZipEntry {
+    fn extract(&self, archive: &[u8]) -> Result<Vec<u8>> {
+        let header = archive
+            .get(self.local_header_offset..)
+            .context("local file header out of bounds")?;
+        if read_u32(header, 0)? != LOCAL_FILE_HEADER_SIGNATURE {
+            bail!("invalid local file header signature");
+        }
+        let data_offset = 30 + read_u16(header, 26)? as usize + read_u16(header, 28)? as usize;
+        let data = header
+            .get(data_offset..data_offset + self.compressed_size)
+            .context("file data out of bounds")?;
+        match self.compression {
+            COMPRESSION_STORED => Ok(data.to_vec()),
+            COMPRESSION_DEFLATE => {
+                let mut content = Vec::with_capacity(self.size);
+                DeflateDecoder::new(data).read_to_end(&mut content)?;
+                Ok(content)
+            }
+            compression => bail!("unsupported compression method {compression}"),
+        }
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .context("unexpected end of data")?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data
+        .get(offset..offset + 4)
+        .context("unexpected end of data")?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads the file entries from the central directory of a zip archive.
+/// Zip64 archives are not supported.
+fn read_central_directory(data: &[u8]) -> Result<BTreeMap<RcStr, ZipEntry>> {
+    if data.len() < END_OF_CENTRAL_DIRECTORY_SIZE {
+        bail!("file is too small to be a zip archive");
+    }
+    // The end of central directory record is followed by a comment of up to
+    // 64 KiB, so search backwards for its signature.
+    let min_offset = data
+        .len()
+        .saturating_sub(END_OF_CENTRAL_DIRECTORY_SIZE + u16::MAX as usize);
+    let eocd_offset = (min_offset..=data.len() - END_OF_CENTRAL_DIRECTORY_SIZE)
+        .rev()
+        .find(|&offset| read_u32(data, offset).ok() == Some(END_OF_CENTRAL_DIRECTORY_SIGNATURE))
+        .context("end of central directory record not found")?;
+    let eocd = &data[eocd_offset..];
+    let entry_count = read_u16(eocd, 10)? as usize;
+    let mut offset = read_u32(eocd, 16)? as usize;
+    if entry_count == u16::MAX as usize || offset == u32::MAX as usize {
+        bail!("zip64 archives are not supported");
+    }
+
+    let mut entries = BTreeMap::new();
+    for _ in 0..entry_count {
+        if read_u32(data, offset)? != CENTRAL_DIRECTORY_SIGNATURE {
+            bail!("invalid central directory entry signature");
+        }
+        let compression = read_u16(data, offset + 10)?;
+        let compressed_size = read_u32(data, offset + 20)? as usize;
+        let size = read_u32(data, offset + 24)? as usize;
+        let name_len = read_u16(data, offset + 28)? as usize;
+        let extra_len = read_u16(data, offset + 30)? as usize;
+        let comment_len = read_u16(data, offset + 32)? as usize;
+        let local_header_offset = read_u32(data, offset + 42)? as usize;
+        let name = data
+            .get(offset + 46..offset + 46 + name_len)
+            .context("file name out of bounds")?;
+        let name = std::str::from_utf8(name)
+            .context("file name is not valid UTF-8")?
+            .trim_start_matches("./");
+        if !name.is_empty() && !name.ends_with('/') {
+            entries.insert(
+                name.into(),
+                ZipEntry {
+                    compression,
+                    compressed_size,
+                    size,
+                    local_header_offset,
+                },
+            );
+        }
+        offset += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(entries)
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for ZipFileSystem {
+    #[turbo_tasks::function]
+    async fn read(self: Vc<Self>, path: Vc<FileSystemPath>) -> Result<Vc<FileContent>> {
+        let index = self.index().await?;
+        let Some(entry) = index.entries.get(&path.await?.path) else {
+            return Ok(FileContent::NotFound.cell());
+        };
+        Ok(File::from(entry.extract(&index.data)?).into())
+    }
+
+    #[turbo_tasks::function]
+    fn read_link(&self, _path: Vc<FileSystemPath>) -> Vc<LinkContent> {
+        LinkContent::NotFound.cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn read_dir(self: Vc<Self>, path: Vc<FileSystemPath>) -> Result<Vc<DirectoryContent>> {
+        let index = self.index().await?;
+        let path_str = &path.await?.path;
+        if !index.is_dir(path_str) {
+            return Ok(DirectoryContent::not_found());
+        }
+        let prefix = if path_str.is_empty() {
+            String::new()
+        } else {
+            format!("{path_str}/")
+        };
+
+        let mut entries = AutoMap::new();
+        for name in index
+            .entries
+            .range::<str, _>(prefix.as_str()..)
+            .map(|(name, _)| name)
+            .take_while(|name| name.starts_with(&prefix))
+        {
+            let (entry_name, is_dir) = match name[prefix.len()..].split_once('/') {
+                Some((dir, _)) => (dir, true),
+                None => (&name[prefix.len()..], false),
+            };
+            if entries.contains_key(entry_name) {
+                continue;
+            }
+            let entry_name: RcStr = entry_name.into();
+            let entry_path = path.join(entry_name.clone()).to_resolved().await?;
+            entries.insert(
+                entry_name,
+                if is_dir {
+                    DirectoryEntry::Directory(entry_path)
+                } else {
+                    DirectoryEntry::File(entry_path)
+                },
+            );
+        }
+
+        Ok(DirectoryContent::new(entries))
+    }
+
+    #[turbo_tasks::function]
+    async fn track(&self, _path: Vc<FileSystemPath>) -> Result<Vc<Completion>> {
+        self.archive.track().await?;
+        Ok(Completion::new())
+    }
+
+    #[turbo_tasks::function]
+    fn write(&self, _path: Vc<FileSystemPath>, _content: Vc<FileContent>) -> Result<Vc<()>> {
+        bail!("Writing is not possible to a zip file system")
+    }
+
+    #[turbo_tasks::function]
+    fn write_link(&self, _path: Vc<FileSystemPath>, _target: Vc<LinkContent>) -> Result<Vc<()>> {
+        bail!("Writing is not possible to a zip file system")
+    }
+
+    #[turbo_tasks::function]
+    async fn metadata(self: Vc<Self>, path: Vc<FileSystemPath>) -> Result<Vc<FileMeta>> {
+        let index = self.index().await?;
+        let path = &path.await?.path;
+        if !index.entries.contains_key(path) && !index.is_dir(path) {
+            bail!("path not found, can't read metadata");
+        }
+        Ok(FileMeta::default().cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for ZipFileSystem {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!("zip:{}", self.archive.to_string().await?).into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an archive with uncompressed entries.
+    fn stored_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut central_directory = Vec::new();
+        for (name, content) in files {
+            let offset = data.len() as u32;
+            data.extend(LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+            data.extend([0; 22]);
+            data.extend((name.len() as u16).to_le_bytes());
+            data.extend(0u16.to_le_bytes());
+            data.extend(name.as_bytes());
+            data.extend(*content);
+
+            central_directory.extend(CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+            central_directory.extend([0; 6]);
+            central_directory.extend(COMPRESSION_STORED.to_le_bytes());
+            central_directory.extend([0; 8]);
+            central_directory.extend((content.len() as u32).to_le_bytes());
+            central_directory.extend((content.len() as u32).to_le_bytes());
+            central_directory.extend((name.len() as u16).to_le_bytes());
+            central_directory.extend([0; 12]);
+            central_directory.extend(offset.to_le_bytes());
+            central_directory.extend(name.as_bytes());
+        }
+        let central_directory_offset = data.len() as u32;
+        data.extend(&central_directory);
+        data.extend(END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+        data.extend([0; 6]);
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((central_directory.len() as u32).to_le_bytes());
+        data.extend(central_directory_offset.to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn read_stored_entries() {
+        let archive = stored_archive(&[
+            ("node_modules/foo/package.json", b"{}"),
+            ("node_modules/foo/index.js", b"module.exports = 42;"),
+        ]);
+        let entries = read_central_directory(&archive).unwrap();
+        assert_eq!(entries.len(), 2);
+        let entry = &entries["node_modules/foo/index.js"];
+        assert_eq!(entry.extract(&archive).unwrap(), b"module.exports = 42;");
+
+        let index = ZipIndex {
+            data: archive.into(),
+            entries,
+        };
+        assert!(index.is_dir("node_modules/foo"));
+        assert!(!index.is_dir("node_modules/fo"));
+        assert!(!index.is_dir("node_modules/foo/index.js"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::{fmt, hash::Hasher};
+
+use serde::{Deserialize, Serialize};
+use twox_hash::xxh3::{self, HasherExt};
+
+use crate::{DeterministicHash, DeterministicHasher};
+
+/// The hash function used to address content, e.g. for the content hashes of output assets or
+/// the fallback module ids of a global module id strategy.
+///
+/// The default is the 64-bit xxh3 hash, which keeps output names and module ids stable across
+/// versions. 64-bit hashes are fast, but collisions become likely with millions of hashed items,
+/// e.g. when a remote cache is shared by a monorepo, so those setups can opt into the 128-bit xxh3
+/// hash. [HashAlgorithm::Blake3] is a cryptographic hash for when the content comes from untrusted
+/// sources.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashAlgorithm {
+    #[default]
+    #[serde(rename = "xxh3-64")]
+    Xxh3Hash64,
+    #[serde(rename = "xxh3-128")]
+    Xxh3Hash128,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Creates a new hasher for this algorithm.
+    pub fn hasher(self) -> ContentHasher {
+        ContentHasher(match self {
+            HashAlgorithm::Xxh3Hash64 => HasherState::Xxh3Hash64(xxh3::Hash64::with_seed(0)),
+            HashAlgorithm::Xxh3Hash128 => HasherState::Xxh3Hash128(xxh3::Hash128::with_seed(0)),
+            HashAlgorithm::Blake3 => HasherState::Blake3(Box::default()),
+        })
+    }
+
+    /// Hashes some content with this algorithm.
+    pub fn hash<T: DeterministicHash>(self, input: T) -> HashDigest {
+        let mut hasher = self.hasher();
+        input.deterministic_hash(&mut hasher);
+        hasher.finish_digest()
+    }
+
+    /// The length of the digests of this algorithm in bytes.
+    pub fn digest_len(self) -> usize {
+        match self {
+            HashAlgorithm::Xxh3Hash64 => 8,
+            HashAlgorithm::Xxh3Hash128 => 16,
+            HashAlgorithm::Blake3 => 32,
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HashAlgorithm::Xxh3Hash64 => "xxh3-64",
+            HashAlgorithm::Xxh3Hash128 => "xxh3-128",
+            HashAlgorithm::Blake3 => "blake3",
+        })
+    }
+}
+
+impl DeterministicHash for HashAlgorithm {
+    fn deterministic_hash<H: DeterministicHasher>(&self, state: &mut H) {
+        state.write_u8(*self as u8);
+    }
+}
+
+enum HasherState {
+    Xxh3Hash64(xxh3::Hash64),
+    Xxh3Hash128(xxh3::Hash128),
+    Blake3(Box<blake3::Hasher>),
+}
+
+/// A hasher for one of the [HashAlgorithm]s.
+pub struct ContentHasher(HasherState);
+
+impl ContentHasher {
+    /// Uses the DeterministicHash trait to hash the input in a
+    /// cross-platform way.
+    pub fn write_value<T: DeterministicHash>(&mut self, input: T) {
+        input.deterministic_hash(self);
+    }
+
+    /// Uses the DeterministicHash trait to hash the input in a
+    /// cross-platform way.
+    pub fn write_ref<T: DeterministicHash>(&mut self, input: &T) {
+        input.deterministic_hash(self);
+    }
+
+    /// Finish the hash computation and return the full digest.
+    pub fn finish_digest(&self) -> HashDigest {
+        match &self.0 {
+            HasherState::Xxh3Hash64(hasher) => HashDigest::new(&hasher.finish().to_be_bytes()),
+            HasherState::Xxh3Hash128(hasher) => HashDigest::new(&hasher.finish_ext().to_be_bytes()),
+            HasherState::Blake3(hasher) => HashDigest::new(hasher.finalize().as_bytes()),
+        }
+    }
+}
+
+impl DeterministicHasher for ContentHasher {
+    /// Returns the first 64 bits of the digest. Use [ContentHasher::finish_digest] to get all of
+    /// them.
+    fn finish(&self) -> u64 {
+        self.finish_digest().truncate_u64()
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        match &mut self.0 {
+            HasherState::Xxh3Hash64(hasher) => hasher.write(bytes),
+            HasherState::Xxh3Hash128(hasher) => hasher.write(bytes),
+            HasherState::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+}
+
+/// The digest computed by a [ContentHasher]. Integer digests are stored big-endian, so the hex
+/// encoding of a 64-bit digest matches [encode_hex][crate::encode_hex].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HashDigest {
+    bytes: [u8; 32],
+    len: u8,
+}
+
+impl HashDigest {
+    fn new(bytes: &[u8]) -> Self {
+        let mut digest = HashDigest {
+            bytes: [0; 32],
+            len: bytes.len() as u8,
+        };
+        digest.bytes[..bytes.len()].copy_from_slice(bytes);
+        digest
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+
+    /// The first 64 bits of the digest, e.g. for APIs which only accept 64-bit hashes.
+    pub fn truncate_u64(&self) -> u64 {
+        u64::from_be_bytes(self.bytes[..8].try_into().unwrap())
+    }
+
+    /// The first 128 bits of the digest as a big-endian integer. Shorter digests aren't padded,
+    /// so a 64-bit digest yields the same value as the `u64` hash.
+    pub fn as_u128(&self) -> u128 {
+        self.as_bytes()[..self.len.min(16) as usize]
+            .iter()
+            .fold(0, |value, byte| (value << 8) | u128::from(*byte))
+    }
+
+    /// Encodes the digest into a lowercase hex string.
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+
+    /// Encodes the first 128 bits of the digest into a decimal string, see
+    /// [HashDigest::as_u128]. This is shorter than hex for 64-bit digests, e.g. for module ids.
+    pub fn to_decimal(&self) -> String {
+        self.as_u128().to_string()
+    }
+}
+
+impl fmt::Display for HashDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.as_bytes() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for HashDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HashDigest({self})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_hex, hash_xxh3_hash64};
+
+    #[test]
+    fn xxh3_64_digests_match_u64_hashes() {
+        let digest = HashAlgorithm::Xxh3Hash64.hash("content");
+        let hash = hash_xxh3_hash64("content");
+        assert_eq!(digest.to_hex(), encode_hex(hash));
+        assert_eq!(digest.to_decimal(), hash.to_string());
+        assert_eq!(digest.truncate_u64(), hash);
+    }
+
+    #[test]
+    fn digest_encodings_cover_the_digest() {
+        for algorithm in [
+            HashAlgorithm::Xxh3Hash64,
+            HashAlgorithm::Xxh3Hash128,
+            HashAlgorithm::Blake3,
+        ] {
+            let digest = algorithm.hash("content");
+            assert_eq!(digest.as_bytes().len(), algorithm.digest_len());
+            let hex = digest.to_hex();
+            assert_eq!(hex.len(), 2 * algorithm.digest_len());
+            assert!(hex.starts_with(&format!(
+                "{:0width$x}",
+                digest.as_u128(),
+                width = 2 * algorithm.digest_len().min(16)
+            )));
+            assert_eq!(
+                digest.to_decimal().parse::<u128>().unwrap(),
+                digest.as_u128()
+            );
+            assert_ne!(digest, algorithm.hash("other content"));
+        }
+    }
+}
@@ -5,11 +5,13 @@
 //! file name.
 
 mod deterministic_hash;
+mod hash_algorithm;
 mod hex;
 mod xxh3_hash64;
 
 pub use crate::{
     deterministic_hash::{DeterministicHash, DeterministicHasher},
+    hash_algorithm::{ContentHasher, HashAlgorithm, HashDigest},
     hex::encode_hex,
     xxh3_hash64::{hash_xxh3_hash128, hash_xxh3_hash64, Xxh3Hash64Hasher},
 };
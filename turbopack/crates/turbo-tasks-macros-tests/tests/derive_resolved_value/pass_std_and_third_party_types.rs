@@ -0,0 +1,35 @@
+#![allow(dead_code)]
+
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    num::{NonZeroU32, NonZeroUsize, Wrapping},
+    ops::Range,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use either::Either;
+use smallvec::SmallVec;
+use turbo_tasks::{ResolvedValue, ResolvedVc};
+
+#[derive(ResolvedValue)]
+struct ContainsStdTypes {
+    a: Cow<'static, str>,
+    b: NonZeroU32,
+    c: NonZeroUsize,
+    d: Box<[ResolvedVc<i32>]>,
+    e: Arc<[u8]>,
+    f: OsString,
+    g: SystemTime,
+    h: Range<u32>,
+    i: Wrapping<u64>,
+}
+
+#[derive(ResolvedValue)]
+struct ContainsThirdPartyTypes {
+    a: SmallVec<[ResolvedVc<i32>; 2]>,
+    b: Either<ResolvedVc<i32>, String>,
+}
+
+fn main() {}
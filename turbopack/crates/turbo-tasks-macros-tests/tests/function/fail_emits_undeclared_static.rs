@@ -0,0 +1,28 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(dead_code)]
+
+use turbo_tasks::Vc;
+
+#[turbo_tasks::value_trait]
+trait SlowWarning {}
+
+#[turbo_tasks::value_trait]
+trait Unrelated {}
+
+#[turbo_tasks::value]
+struct ExampleWarning;
+
+#[turbo_tasks::value_impl]
+impl SlowWarning for ExampleWarning {}
+
+#[turbo_tasks::value_impl]
+impl Unrelated for ExampleWarning {}
+
+#[turbo_tasks::function(emits = SlowWarning)]
+fn emits_unrelated() -> Vc<()> {
+    turbo_tasks::emit(Vc::upcast::<Box<dyn Unrelated>>(ExampleWarning.cell()));
+    Vc::cell(())
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(dead_code)]
+
+use turbo_tasks::{CollectiblesSource, Vc};
+
+#[turbo_tasks::value_trait]
+trait SlowWarning {
+    fn message(self: Vc<Self>) -> Vc<String>;
+}
+
+#[turbo_tasks::value]
+struct ExampleWarning;
+
+#[turbo_tasks::value_impl]
+impl SlowWarning for ExampleWarning {
+    #[turbo_tasks::function]
+    fn message(self: Vc<Self>) -> Vc<String> {
+        Vc::cell("slow".to_string())
+    }
+}
+
+#[turbo_tasks::function(emits = SlowWarning)]
+fn emits_slow_warning() -> Vc<()> {
+    emit(Vc::upcast::<Box<dyn SlowWarning>>(ExampleWarning.cell()));
+    Vc::cell(())
+}
+
+#[turbo_tasks::function(emits = SlowWarning)]
+fn emits_slow_warning_qualified() -> Vc<()> {
+    turbo_tasks::emit(Vc::upcast::<Box<dyn SlowWarning>>(ExampleWarning.cell()));
+    Vc::cell(())
+}
+
+fn peek(source: impl CollectiblesSource) {
+    let _ = emits_slow_warning_collectibles::peek_slow_warnings(source);
+}
+
+fn main() {}
@@ -0,0 +1,62 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, FieldsNamed, FieldsUnnamed};
+use turbo_tasks_macros_shared::{generate_exhaustive_destructuring, match_expansion};
+
+pub fn derive_estimate_size(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    let ident = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+
+    let estimate_items = match_expansion(
+        &derive_input,
+        &estimate_named,
+        &estimate_unnamed,
+        &estimate_unit,
+    );
+    quote! {
+        impl #impl_generics turbo_tasks::EstimateSize for #ident #ty_generics #where_clause {
+            fn estimate_heap_size(&self) -> usize {
+                #estimate_items
+            }
+        }
+    }
+    .into()
+}
+
+fn estimate_named(_ident: TokenStream2, fields: &FieldsNamed) -> (TokenStream2, TokenStream2) {
+    let (captures, fields_idents) = generate_exhaustive_destructuring(fields.named.iter());
+    (
+        captures,
+        quote! {
+            {
+                0 #(
+                    + turbo_tasks::macro_helpers::EstimateSizeDerefSpecialization::new(
+                        #fields_idents,
+                    ).estimate_heap_size()
+                )*
+            }
+        },
+    )
+}
+
+fn estimate_unnamed(_ident: TokenStream2, fields: &FieldsUnnamed) -> (TokenStream2, TokenStream2) {
+    let (captures, fields_idents) = generate_exhaustive_destructuring(fields.unnamed.iter());
+    (
+        captures,
+        quote! {
+            {
+                0 #(
+                    + turbo_tasks::macro_helpers::EstimateSizeDerefSpecialization::new(
+                        #fields_idents,
+                    ).estimate_heap_size()
+                )*
+            }
+        },
+    )
+}
+
+fn estimate_unit(_ident: TokenStream2) -> TokenStream2 {
+    quote! { 0 }
+}
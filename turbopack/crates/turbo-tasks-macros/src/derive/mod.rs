@@ -1,4 +1,5 @@
 mod deterministic_hash_macro;
+mod estimate_size_macro;
 mod key_value_pair_macro;
 mod resolved_value_macro;
 mod shrink_to_fit_macro;
@@ -8,6 +9,7 @@ mod value_debug_format_macro;
 mod value_debug_macro;
 
 pub use deterministic_hash_macro::derive_deterministic_hash;
+pub use estimate_size_macro::derive_estimate_size;
 pub use key_value_pair_macro::derive_key_value_pair;
 pub use resolved_value_macro::derive_resolved_value;
 pub use shrink_to_fit_macro::derive_shrink_to_fit;
@@ -2,25 +2,66 @@ use either::Either;
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, quote_spanned};
-use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Generics};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput,
+    GenericArgument, Generics, Lit, Meta, MetaList, MetaNameValue, NestedMeta, Path, PathArguments,
+    Type,
+};
 
 use crate::derive::trace_raw_vcs_macro::filter_field;
 
 pub fn derive_resolved_value(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
     let ident = &derive_input.ident;
+    let krate = crate_path(&derive_input.attrs);
 
-    let assertions = assert_fields_impl_resolved_value(&derive_input.generics, &derive_input.data);
+    let assertions =
+        assert_fields_impl_resolved_value(&krate, &derive_input.generics, &derive_input.data);
 
     let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
     quote! {
-        unsafe impl #impl_generics turbo_tasks::ResolvedValue
+        unsafe impl #impl_generics #krate::ResolvedValue
             for #ident #ty_generics #where_clause {}
         #assertions
     }
     .into()
 }
 
+/// The path of the crate defining `ResolvedValue`, set with `#[turbo_tasks(crate = "...")]` for
+/// crates which only depend on `turbo_tasks_types`. Defaults to `turbo_tasks`.
+fn crate_path(attrs: &[Attribute]) -> Path {
+    let mut krate = parse_quote!(turbo_tasks);
+    for attr in attrs {
+        if !attr.path.is_ident("turbo_tasks") {
+            continue;
+        }
+        let Ok(Meta::List(MetaList { nested, .. })) = attr
+            .parse_meta()
+            .map_err(|err| err.span().unwrap().error(err.to_string()).emit())
+        else {
+            continue;
+        };
+        for meta in nested {
+            match &meta {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(lit),
+                    ..
+                })) if path.is_ident("crate") => match lit.parse() {
+                    Ok(path) => krate = path,
+                    Err(err) => err.span().unwrap().error(err.to_string()).emit(),
+                },
+                _ => meta
+                    .span()
+                    .unwrap()
+                    .error("expected `crate = \"...\"`")
+                    .emit(),
+            }
+        }
+    }
+    krate
+}
+
 fn iter_data_fields(data: &Data) -> impl Iterator<Item = &syn::Field> {
     match data {
         Data::Struct(ds) => Either::Left(ds.fields.iter()),
@@ -29,7 +70,11 @@ fn iter_data_fields(data: &Data) -> impl Iterator<Item = &syn::Field> {
     }
 }
 
-fn assert_fields_impl_resolved_value(generics: &Generics, data: &Data) -> TokenStream2 {
+fn assert_fields_impl_resolved_value(
+    krate: &Path,
+    generics: &Generics,
+    data: &Data,
+) -> TokenStream2 {
     // this technique is based on the trick used by
     // `static_assertions::assert_impl_all`, but extended to support generics.
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
@@ -38,6 +83,15 @@ fn assert_fields_impl_resolved_value(generics: &Generics, data: &Data) -> TokenS
         .filter(|field| filter_field(field))
         .map(|field| {
             let ty = &field.ty;
+            if let Some(vc) = find_bare_vc(ty) {
+                // point at the `Vc` itself instead of failing the trait bound of the whole field
+                return syn::Error::new_spanned(
+                    vc,
+                    "`Vc` may refer to an unresolved task output and can't be part of a \
+                     `ResolvedValue`, use `ResolvedVc` instead",
+                )
+                .to_compile_error();
+            }
             quote_spanned! {
                 // attribute type assertion errors to the line where the field is defined
                 ty.span() =>
@@ -53,7 +107,7 @@ fn assert_fields_impl_resolved_value(generics: &Generics, data: &Data) -> TokenS
 
             impl #impl_generics DeriveResolvedValueAssertion #ty_generics #where_clause {
                 fn assert_impl_resolved_value<
-                    ExpectedResolvedValue: turbo_tasks::ResolvedValue + ?Sized
+                    ExpectedResolvedValue: #krate::ResolvedValue + ?Sized
                 >() {}
                 fn field_types() {
                     #(#assertion_calls)*
@@ -62,3 +116,50 @@ fn assert_fields_impl_resolved_value(generics: &Generics, data: &Data) -> TokenS
         };
     }
 }
+
+/// Finds a bare `Vc<T>` anywhere in the type, e.g. in `Option<Box<[Vc<T>; 4]>>`, except inside of
+/// `PhantomData`, which can contain anything. This is a syntactic check: a `Vc` hidden behind a
+/// type alias is still caught by the trait bound assertion, just with a less helpful error.
+fn find_bare_vc(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(type_path) => {
+            if let Some(vc) = type_path
+                .qself
+                .as_ref()
+                .and_then(|qself| find_bare_vc(&qself.ty))
+            {
+                return Some(vc);
+            }
+            if type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Vc")
+            {
+                return Some(ty);
+            }
+            type_path
+                .path
+                .segments
+                .iter()
+                .filter(|segment| segment.ident != "PhantomData")
+                .filter_map(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => Some(&arguments.args),
+                    _ => None,
+                })
+                .flatten()
+                .find_map(|argument| match argument {
+                    GenericArgument::Type(ty) => find_bare_vc(ty),
+                    _ => None,
+                })
+        }
+        Type::Array(array) => find_bare_vc(&array.elem),
+        Type::Group(group) => find_bare_vc(&group.elem),
+        Type::Paren(paren) => find_bare_vc(&paren.elem),
+        Type::Ptr(ptr) => find_bare_vc(&ptr.elem),
+        Type::Reference(reference) => find_bare_vc(&reference.elem),
+        Type::Slice(slice) => find_bare_vc(&slice.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(find_bare_vc),
+        _ => None,
+    }
+}
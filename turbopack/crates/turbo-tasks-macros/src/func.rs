@@ -12,7 +12,7 @@ use syn::{
     visit_mut::VisitMut,
     AngleBracketedGenericArguments, Block, Expr, ExprBlock, ExprPath, FnArg, GenericArgument,
     Local, Meta, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Receiver, ReturnType,
-    Signature, Stmt, Token, Type, TypeGroup, TypePath, TypeTuple,
+    Signature, Stmt, Token, Type, TypeGroup, TypePath, TypeTuple, Visibility,
 };
 
 #[derive(Debug)]
@@ -28,6 +28,9 @@ pub struct TurboFn<'a> {
     output: Type,
     this: Option<Input>,
     inputs: Vec<Input>,
+    /// The pattern of the injected [`ProgressReporter`][turbo_tasks::ProgressReporter] argument,
+    /// which isn't an input of the task.
+    progress: Option<Pat>,
     /// Should we check that the return type contains a `ResolvedValue`?
     resolved: Option<Span>,
     /// Should this function use `TaskPersistence::LocalCells`?
@@ -73,9 +76,58 @@ impl TurboFn<'_> {
             return None;
         }
 
-        let mut raw_inputs = orig_signature.inputs.iter();
+        if let Some(emits) = args.emits.first() {
+            if !matches!(definition_context, DefinitionContext::NakedFn) {
+                emits
+                    .span()
+                    .unwrap()
+                    .error(format!(
+                        "{} do not support `emits`",
+                        definition_context.function_type(),
+                    ))
+                    .emit();
+                return None;
+            }
+        }
+
+        let mut progress = None;
+        for input in &orig_signature.inputs {
+            let FnArg::Typed(typed) = input else {
+                continue;
+            };
+            if !is_progress_reporter(&typed.ty) {
+                continue;
+            }
+            if !matches!(
+                definition_context,
+                DefinitionContext::NakedFn | DefinitionContext::ValueInherentImpl
+            ) {
+                typed
+                    .span()
+                    .unwrap()
+                    .error(format!(
+                        "{} do not support ProgressReporter arguments",
+                        definition_context.function_type(),
+                    ))
+                    .emit();
+                return None;
+            }
+            if progress.is_some() {
+                typed
+                    .span()
+                    .unwrap()
+                    .error("only one ProgressReporter argument can be injected")
+                    .emit();
+                return None;
+            }
+            progress = Some((*typed.pat).clone());
+        }
+
+        let mut raw_inputs = orig_signature.inputs.iter().filter(
+            |input| !matches!(input, FnArg::Typed(typed) if is_progress_reporter(&typed.ty)),
+        );
         let mut this = None;
-        let mut inputs = Vec::with_capacity(raw_inputs.len());
+        let mut inputs = Vec::with_capacity(orig_signature.inputs.len());
 
         if let Some(possibly_receiver) = raw_inputs.next() {
             match possibly_receiver {
@@ -273,6 +325,7 @@ impl TurboFn<'_> {
             output,
             this,
             inputs,
+            progress,
             resolved: args.resolved,
             local_cells: args.local_cells.is_some(),
             inline_ident,
@@ -339,6 +392,9 @@ impl TurboFn<'_> {
             .inputs
             .iter()
             .enumerate()
+            .filter(
+                |(_, arg)| !matches!(arg, FnArg::Typed(typed) if is_progress_reporter(&typed.ty)),
+            )
             .map(|(idx, arg)| match arg {
                 FnArg::Receiver(_) => (arg.clone(), None),
                 FnArg::Typed(pat_type) => {
@@ -414,7 +470,15 @@ impl TurboFn<'_> {
                 }
             })
             .unzip();
-        let transform_stmts: Vec<Stmt> = transform_stmts.into_iter().flatten().collect();
+        let mut transform_stmts: Vec<Stmt> = transform_stmts.into_iter().flatten().collect();
+        if let Some(progress) = &self.progress {
+            transform_stmts.insert(
+                0,
+                parse_quote! {
+                    let #progress = turbo_tasks::ProgressReporter::current();
+                },
+            );
+        }
 
         let inline_signature = Signature {
             ident: self.inline_ident.clone(),
@@ -592,6 +656,19 @@ impl TurboFn<'_> {
     }
 }
 
+/// Whether the argument type is a [`ProgressReporter`][turbo_tasks::ProgressReporter], which is
+/// injected instead of being an input of the task.
+fn is_progress_reporter(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "ProgressReporter"),
+        Type::Group(TypeGroup { elem, .. }) => is_progress_reporter(elem),
+        _ => false,
+    }
+}
+
 /// An indication of what kind of IO this function does. Currently only used for
 /// static analysis, and ignored within this macro.
 #[derive(Hash, PartialEq, Eq)]
@@ -661,7 +738,23 @@ pub struct FunctionArguments {
     /// If there is an error due to this option being set, it should be reported to this span.
     ///
     /// Setting this option will also set [`Self::resolved`] to the same span.
+    ///
+    /// The function is run in a local task of the caller instead of a cached task, see
+    /// `TaskPersistence::LocalCells`. `uncached` is an alias of this option.
     pub local_cells: Option<Span>,
+    /// Collectible traits the function emits, e.g. `emits = Issue`. Calling `emit` or
+    /// `turbo_tasks::emit` with other collectibles in the function body is a compile error, and
+    /// typed accessors for the declared collectibles are generated. Extension methods, e.g.
+    /// `IssueExt::emit`, and the functions the body calls aren't checked.
+    ///
+    /// Only supported on naked functions.
+    pub emits: Vec<Path>,
+    /// Excludes the tasks of the function from garbage collection, e.g. for tasks which are
+    /// expensive to recompute and read all the time. See [`turbo_tasks::GcPolicy`].
+    pub gc_exempt: bool,
+    /// The variant of [`turbo_tasks::TaskPriority`] the tasks of the function are scheduled with
+    /// at least, e.g. `priority = high`.
+    pub priority: Option<Ident>,
 }
 
 impl Parse for FunctionArguments {
@@ -686,16 +779,50 @@ impl Parse for FunctionArguments {
                 ("resolved", Meta::Path(_)) => {
                     parsed_args.resolved = Some(meta.span());
                 }
-                ("local_cells", Meta::Path(_)) => {
+                // Local tasks are never cached, `uncached` states that intent.
+                ("local_cells" | "uncached", Meta::Path(_)) => {
                     let span = Some(meta.span());
                     parsed_args.local_cells = span;
                     parsed_args.resolved = span;
                 }
+                ("gc_exempt", Meta::Path(_)) => {
+                    parsed_args.gc_exempt = true;
+                }
+                ("priority", Meta::NameValue(name_value)) => {
+                    let variant = match &name_value.value {
+                        Expr::Path(ExprPath { path, .. }) => path.get_ident().and_then(|ident| {
+                            match ident.to_string().as_str() {
+                                "low" => Some("Low"),
+                                "normal" => Some("Normal"),
+                                "high" => Some("High"),
+                                _ => None,
+                            }
+                            .map(|variant| Ident::new(variant, ident.span()))
+                        }),
+                        _ => None,
+                    };
+                    let Some(variant) = variant else {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.value,
+                            "expected one of: \"low\", \"normal\", \"high\"",
+                        ));
+                    };
+                    parsed_args.priority = Some(variant);
+                }
+                ("emits", Meta::NameValue(name_value)) => {
+                    let Expr::Path(ExprPath { path, .. }) = &name_value.value else {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.value,
+                            "expected the name of a collectible trait",
+                        ));
+                    };
+                    parsed_args.emits.push(path.clone());
+                }
                 (_, meta) => {
                     return Err(syn::Error::new_spanned(
                         meta,
                         "unexpected token, expected one of: \"fs\", \"network\", \"resolved\", \
-                         \"local_cells\"",
+                         \"local_cells\", \"uncached\", \"emits\", \"gc_exempt\", \"priority\"",
                     ))
                 }
             }
@@ -704,6 +831,84 @@ impl Parse for FunctionArguments {
     }
 }
 
+/// Checks the collectibles the body of a function declaring `emits` emits. This prepends a
+/// shadowing [`turbo_tasks::emit`] which only accepts the declared collectibles, and makes the
+/// calls of `turbo_tasks::emit` in the body use it.
+pub fn add_emits_check(block: &mut Block, emits: &[Path]) {
+    RewriteEmitVisitMut.visit_block_mut(block);
+    block.stmts.splice(0..0, emits_check(emits));
+}
+
+fn emits_check(emits: &[Path]) -> Vec<Stmt> {
+    let block: Block = parse_quote! {{
+        struct __TurboTasksEmits;
+        #(
+            impl turbo_tasks::Emits<Box<dyn #emits>> for __TurboTasksEmits {}
+        )*
+        #[allow(dead_code)]
+        fn emit<T: turbo_tasks::VcValueTrait + ?Sized>(collectible: turbo_tasks::Vc<T>)
+        where
+            __TurboTasksEmits: turbo_tasks::Emits<T>,
+        {
+            turbo_tasks::emit(collectible)
+        }
+    }};
+    block.stmts
+}
+
+/// A module named `<function>_collectibles` with `peek_<collectibles>` and
+/// `take_<collectibles>` accessors for the collectibles declared via `emits`.
+pub fn emits_accessors(ident: &Ident, vis: &Visibility, emits: &[Path]) -> TokenStream {
+    let module_ident = Ident::new(&format!("{ident}_collectibles"), ident.span());
+    let doc = format!("Typed access to the collectibles that [`{ident}`] emits.");
+    let accessors = emits.iter().map(|path| {
+        let name = path
+            .segments
+            .last()
+            .map(|segment| to_snake_case(&segment.ident.to_string()))
+            .unwrap_or_default();
+        let peek_ident = Ident::new(&format!("peek_{name}s"), path.span());
+        let take_ident = Ident::new(&format!("take_{name}s"), path.span());
+        quote! {
+            pub fn #peek_ident(
+                source: impl turbo_tasks::CollectiblesSource,
+            ) -> turbo_tasks::macro_helpers::AutoSet<turbo_tasks::Vc<Box<dyn #path>>> {
+                source.peek_collectibles()
+            }
+
+            pub fn #take_ident(
+                source: impl turbo_tasks::CollectiblesSource,
+            ) -> turbo_tasks::macro_helpers::AutoSet<turbo_tasks::Vc<Box<dyn #path>>> {
+                source.take_collectibles()
+            }
+        }
+    });
+    quote! {
+        #[doc = #doc]
+        #vis mod #module_ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #(#accessors)*
+        }
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (index, char) in name.char_indices() {
+        if char.is_uppercase() {
+            if index > 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(char.to_lowercase());
+        } else {
+            snake_case.push(char);
+        }
+    }
+    snake_case
+}
+
 fn return_type_to_type(return_type: &ReturnType) -> Type {
     match return_type {
         ReturnType::Default => parse_quote! { () },
@@ -931,6 +1136,28 @@ impl VisitMut for RewriteSelfVisitMut {
     }
 }
 
+/// Replaces the callee of `turbo_tasks::emit(...)` calls with the checked `emit` of
+/// [`add_emits_check`].
+struct RewriteEmitVisitMut;
+
+impl VisitMut for RewriteEmitVisitMut {
+    fn visit_expr_call_mut(&mut self, call: &mut syn::ExprCall) {
+        if let Expr::Path(ExprPath {
+            qself: None, path, ..
+        }) = &mut *call.func
+        {
+            let is_turbo_tasks_emit = path.segments.len() == 2
+                && path.segments[0].ident == "turbo_tasks"
+                && path.segments[1].ident == "emit"
+                && path.segments[1].arguments.is_none();
+            if is_turbo_tasks_emit {
+                *path = Ident::new("emit", path.span()).into();
+            }
+        }
+        syn::visit_mut::visit_expr_call_mut(self, call);
+    }
+}
+
 /// The context in which the function is being defined.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum DefinitionContext {
@@ -961,6 +1188,8 @@ pub struct NativeFn {
     function_path: ExprPath,
     is_method: bool,
     local_cells: bool,
+    gc_exempt: bool,
+    priority: Option<Ident>,
 }
 
 impl NativeFn {
@@ -969,12 +1198,16 @@ impl NativeFn {
         function_path: &ExprPath,
         is_method: bool,
         local_cells: bool,
+        gc_exempt: bool,
+        priority: Option<Ident>,
     ) -> NativeFn {
         NativeFn {
             function_path_string: function_path_string.to_owned(),
             function_path: function_path.clone(),
             is_method,
             local_cells,
+            gc_exempt,
+            priority,
         }
     }
 
@@ -988,7 +1221,12 @@ impl NativeFn {
             function_path,
             is_method,
             local_cells,
+            gc_exempt,
+            priority,
         } = self;
+        let priority = priority
+            .clone()
+            .unwrap_or_else(|| Ident::new("Normal", Span::call_site()));
 
         let constructor = if *is_method {
             quote! { new_method }
@@ -996,6 +1234,15 @@ impl NativeFn {
             quote! { new_function }
         };
 
+        // `file!()` and `line!()` resolve to where the function is defined when they are spanned
+        // at its name.
+        let span = function_path
+            .path
+            .segments
+            .last()
+            .map_or_else(Span::call_site, |segment| segment.ident.span());
+        let location = quote_spanned! { span => concat!(file!(), ":", line!()) };
+
         parse_quote! {
             turbo_tasks::macro_helpers::Lazy::new(|| {
                 #[allow(deprecated)]
@@ -1003,6 +1250,9 @@ impl NativeFn {
                     #function_path_string.to_owned(),
                     turbo_tasks::FunctionMeta {
                         local_cells: #local_cells,
+                        gc_exempt: #gc_exempt,
+                        priority: turbo_tasks::TaskPriority::#priority,
+                        location: #location,
                     },
                     #function_path,
                 )
@@ -3,7 +3,9 @@ use quote::quote;
 use syn::{parse_macro_input, parse_quote, ItemFn};
 use turbo_tasks_macros_shared::{get_native_function_id_ident, get_native_function_ident};
 
-use crate::func::{DefinitionContext, FunctionArguments, NativeFn, TurboFn};
+use crate::func::{
+    add_emits_check, emits_accessors, DefinitionContext, FunctionArguments, NativeFn, TurboFn,
+};
 
 /// This macro generates the virtual function that powers turbo tasks.
 /// An annotated task is replaced with a stub function that returns a
@@ -24,6 +26,21 @@ use crate::func::{DefinitionContext, FunctionArguments, NativeFn, TurboFn};
 ///     // access filesystem
 /// }
 /// ```
+///
+/// Functions can declare the collectibles they emit. Calling `emit` or
+/// `turbo_tasks::emit` with other collectibles in the function body doesn't
+/// compile, and a `<function>_collectibles` module with typed `peek_*`/`take_*`
+/// accessors is generated. Extension methods like `IssueExt::emit` aren't
+/// checked.
+///
+/// ```rust
+/// #[turbo_tasks::function(emits = Issue)]
+/// async fn my_task() -> Vc<usize> {
+///     emit(Vc::upcast::<Box<dyn Issue>>(issue));
+/// }
+///
+/// let issues = my_task_collectibles::peek_issues(my_task());
+/// ```
 pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut errors = Vec::new();
 
@@ -38,6 +55,9 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
         .inspect_err(|err| errors.push(err.to_compile_error()))
         .unwrap_or_default();
     let local_cells = args.local_cells.is_some();
+    let gc_exempt = args.gc_exempt;
+    let priority = args.priority.clone();
+    let emits = args.emits.clone();
 
     let Some(turbo_fn) = TurboFn::new(&sig, DefinitionContext::NakedFn, args) else {
         return quote! {
@@ -49,13 +69,20 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
     let ident = &sig.ident;
 
     let inline_function_ident = turbo_fn.inline_ident();
+    let mut block = block;
+    if !emits.is_empty() {
+        add_emits_check(&mut block, &emits);
+    }
     let (inline_signature, inline_block) = turbo_fn.inline_signature_and_block(&block);
+    let emits_accessors = (!emits.is_empty()).then(|| emits_accessors(ident, &vis, &emits));
 
     let native_fn = NativeFn::new(
         &ident.to_string(),
         &parse_quote! { #inline_function_ident },
         turbo_fn.is_method(),
         local_cells,
+        gc_exempt,
+        priority,
     );
     let native_function_ident = get_native_function_ident(ident);
     let native_function_ty = native_fn.ty();
@@ -82,6 +109,8 @@ pub fn function(args: TokenStream, input: TokenStream) -> TokenStream {
         #[doc(hidden)]
         pub(crate) static #native_function_id_ident: #native_function_id_ty = #native_function_id_def;
 
+        #emits_accessors
+
         #(#errors)*
     }
     .into()
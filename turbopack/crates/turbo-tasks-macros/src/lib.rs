@@ -27,6 +27,11 @@ pub fn derive_shrink_to_fit(input: TokenStream) -> TokenStream {
     derive::derive_shrink_to_fit(input)
 }
 
+#[proc_macro_derive(EstimateSize, attributes(turbo_tasks))]
+pub fn derive_estimate_size(input: TokenStream) -> TokenStream {
+    derive::derive_estimate_size(input)
+}
+
 #[proc_macro_derive(ResolvedValue, attributes(turbo_tasks))]
 pub fn derive_resolved_value_attr(input: TokenStream) -> TokenStream {
     derive::derive_resolved_value(input)
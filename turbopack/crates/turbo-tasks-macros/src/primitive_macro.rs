@@ -54,7 +54,17 @@ pub fn primitive(input: TokenStream) -> TokenStream {
             turbo_tasks::VcCellSharedMode<#ty>
         },
         quote! {
-            turbo_tasks::ValueType::new_with_any_serialization::<#ty>()
+            turbo_tasks::ValueType::new_with_any_serialization::<#ty>().with_estimate_size(
+                |value| {
+                    ::std::mem::size_of::<#ty>()
+                        + value.downcast_ref::<#ty>().map_or(0, |value| {
+                            turbo_tasks::macro_helpers::EstimateSizeDerefSpecialization::new(
+                                value,
+                            )
+                            .estimate_heap_size()
+                        })
+                },
+            )
         },
     );
 
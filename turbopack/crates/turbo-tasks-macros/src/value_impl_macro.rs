@@ -120,6 +120,8 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     .inspect_err(|err| errors.push(err.to_compile_error()))
                     .unwrap_or_default();
                 let local_cells = func_args.local_cells.is_some();
+                let gc_exempt = func_args.gc_exempt;
+                let priority = func_args.priority.clone();
 
                 let Some(turbo_fn) =
                     TurboFn::new(sig, DefinitionContext::ValueInherentImpl, func_args)
@@ -136,6 +138,8 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     &parse_quote! { <#ty>::#inline_function_ident },
                     turbo_fn.is_method(),
                     local_cells,
+                    gc_exempt,
+                    priority,
                 );
 
                 let native_function_ident = get_inherent_impl_function_ident(ty_ident, ident);
@@ -221,6 +225,8 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     .inspect_err(|err| errors.push(err.to_compile_error()))
                     .unwrap_or_default();
                 let local_cells = func_args.local_cells.is_some();
+                let gc_exempt = func_args.gc_exempt;
+                let priority = func_args.priority.clone();
 
                 let Some(turbo_fn) =
                     TurboFn::new(sig, DefinitionContext::ValueTraitImpl, func_args)
@@ -248,6 +254,8 @@ pub fn value_impl(args: TokenStream, input: TokenStream) -> TokenStream {
                     },
                     turbo_fn.is_method(),
                     local_cells,
+                    gc_exempt,
+                    priority,
                 );
 
                 let native_function_ident =
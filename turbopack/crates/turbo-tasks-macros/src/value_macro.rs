@@ -9,8 +9,8 @@ use syn::{
     parse_macro_input, parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    Error, Fields, FieldsUnnamed, Generics, Item, ItemEnum, ItemStruct, Lit, LitStr, Meta,
-    MetaNameValue, Result, Token,
+    Error, Fields, FieldsUnnamed, GenericParam, Generics, Item, ItemEnum, ItemStruct, Lit, LitStr,
+    Meta, MetaNameValue, Result, Token, WhereClause, WherePredicate,
 };
 use turbo_tasks_macros_shared::{
     get_register_value_type_ident, get_value_type_id_ident, get_value_type_ident,
@@ -110,6 +110,8 @@ struct ValueArguments {
     cell_mode: CellMode,
     manual_eq: bool,
     transparent: bool,
+    /// Should we implement `turbo_tasks::sharded::Shardable`? Only valid with `transparent`.
+    sharded: bool,
     /// Should we `#[derive(turbo_tasks::ResolvedValue)]`?
     ///
     /// `Some(...)` if enabled, containing the span that enabled the derive.
@@ -125,6 +127,7 @@ impl Parse for ValueArguments {
             manual_eq: false,
             resolved: None,
             transparent: false,
+            sharded: false,
         };
         let punctuated: Punctuated<Meta, Token![,]> = input.parse_terminated(Meta::parse)?;
         for meta in punctuated {
@@ -179,6 +182,9 @@ impl Parse for ValueArguments {
                 ("transparent", Meta::Path(_)) => {
                     result.transparent = true;
                 }
+                ("sharded", Meta::Path(_)) => {
+                    result.sharded = true;
+                }
                 ("resolved", Meta::Path(path)) => {
                     result.resolved = Some(path.span());
                 }
@@ -187,7 +193,7 @@ impl Parse for ValueArguments {
                         &meta,
                         format!(
                             "unexpected {:?}, expected \"shared\", \"into\", \"serialization\", \
-                             \"cell\", \"eq\", \"transparent\"",
+                             \"cell\", \"eq\", \"transparent\", \"sharded\"",
                             meta
                         ),
                     ))
@@ -207,9 +213,17 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         cell_mode,
         manual_eq,
         transparent,
+        sharded,
         resolved,
     } = parse_macro_input!(args as ValueArguments);
 
+    if sharded && !transparent {
+        item.span()
+            .unwrap()
+            .error("#[turbo_tasks::value(sharded)] is only valid with `transparent`")
+            .emit();
+    }
+
     let mut inner_type = None;
     if transparent {
         if let Item::Struct(ItemStruct {
@@ -262,9 +276,13 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
 
-    let ident = match &item {
-        Item::Enum(ItemEnum { ident, .. }) => ident,
-        Item::Struct(ItemStruct { ident, .. }) => ident,
+    let (ident, generics) = match &item {
+        Item::Enum(ItemEnum {
+            ident, generics, ..
+        }) => (ident, generics),
+        Item::Struct(ItemStruct {
+            ident, generics, ..
+        }) => (ident, generics),
         _ => {
             item.span().unwrap().error("unsupported syntax").emit();
 
@@ -275,13 +293,60 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
 
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(_) => {}
+            GenericParam::Lifetime(lt) => {
+                lt.span()
+                    .unwrap()
+                    .error("lifetime parameters are not supported in turbo_tasks::value")
+                    .emit();
+            }
+            GenericParam::Const(c) => {
+                c.span()
+                    .unwrap()
+                    .error("const parameters are not supported in turbo_tasks::value")
+                    .emit();
+            }
+        }
+    }
+    let is_generic = !generics.params.is_empty();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ty = quote! { #ident #ty_generics };
+
+    // Generic value types implement `VcValueType` for every instantiation which implements the
+    // traits required by the value type, e.g. the derived `PartialEq` for the shared cell mode.
+    let where_clause_with = |predicate: WherePredicate| {
+        let mut where_clause = generics
+            .where_clause
+            .clone()
+            .unwrap_or_else(|| WhereClause {
+                where_token: Default::default(),
+                predicates: Punctuated::new(),
+            });
+        where_clause.predicates.push(predicate);
+        where_clause
+    };
+    let mut value_type_where_clause =
+        where_clause_with(parse_quote! { #ty: turbo_tasks::ShrinkToFit + Send + Sync + 'static });
+    let vc_value_type_where_clause = if is_generic {
+        where_clause_with(parse_quote! { #ty: turbo_tasks::VcValueType }).into_token_stream()
+    } else {
+        quote! {}
+    };
+
     let cell_mode = match cell_mode {
         CellMode::New => quote! {
-            turbo_tasks::VcCellNewMode<#ident>
-        },
-        CellMode::Shared => quote! {
-            turbo_tasks::VcCellSharedMode<#ident>
+            turbo_tasks::VcCellNewMode<#ty>
         },
+        CellMode::Shared => {
+            value_type_where_clause
+                .predicates
+                .push(parse_quote! { #ty: PartialEq });
+            quote! {
+                turbo_tasks::VcCellSharedMode<#ty>
+            }
+        }
     };
 
     let (cell_prefix, cell_access_content, read) = if let Some(inner_type) = &inner_type {
@@ -291,7 +356,7 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
                 content.0
             },
             quote! {
-                turbo_tasks::VcTransparentRead::<#ident, #inner_type, #ident>
+                turbo_tasks::VcTransparentRead::<#ty, #inner_type, #ty>
             },
         )
     } else {
@@ -303,7 +368,7 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
             },
             quote! { content },
             quote! {
-                turbo_tasks::VcDefaultRead::<#ident>
+                turbo_tasks::VcDefaultRead::<#ty>
             },
         )
     };
@@ -312,6 +377,7 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         /// Places a value in a cell of the current task.
         ///
         /// Cell is selected based on the value type and call order of `cell`.
+        #[track_caller]
         #cell_prefix fn cell(self) -> turbo_tasks::Vc<Self> {
             let content = self;
             turbo_tasks::Vc::cell_private(#cell_access_content)
@@ -321,6 +387,7 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         /// [`ResolvedVc`][turbo_tasks::ResolvedVc].
         ///
         /// Cell is selected based on the value type and call order of `cell`.
+        #[track_caller]
         #cell_prefix fn resolved_cell(self) -> turbo_tasks::ResolvedVc<Self> {
             let content = self;
             turbo_tasks::ResolvedVc::cell_private(#cell_access_content)
@@ -331,6 +398,7 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         /// Task-local cells are stored in a task-local arena, and do not persist outside the
         /// lifetime of the current task (including child tasks). Task-local cells can be resolved
         /// to be converted into normal cells.
+        #[track_caller]
         #cell_prefix fn local_cell(self) -> turbo_tasks::Vc<Self> {
             let content = self;
             turbo_tasks::Vc::local_cell_private(#cell_access_content)
@@ -339,8 +407,10 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let into = if let IntoMode::New | IntoMode::Shared = into_mode {
         quote! {
-            impl ::std::convert::From<#ident> for turbo_tasks::Vc<#ident> {
-                fn from(value: #ident) -> Self {
+            impl #impl_generics ::std::convert::From<#ty> for turbo_tasks::Vc<#ty>
+                #vc_value_type_where_clause
+            {
+                fn from(value: #ty) -> Self {
                     value.cell()
                 }
             }
@@ -350,7 +420,11 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
     };
 
     let mut struct_attributes = vec![quote! {
-        #[derive(turbo_tasks::ShrinkToFit, turbo_tasks::trace::TraceRawVcs)]
+        #[derive(
+            turbo_tasks::ShrinkToFit,
+            turbo_tasks::EstimateSize,
+            turbo_tasks::trace::TraceRawVcs,
+        )]
     }];
     match serialization_mode {
         SerializationMode::Auto | SerializationMode::AutoForInput => {
@@ -370,6 +444,9 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         struct_attributes.push(quote! {
             #[repr(transparent)]
         });
+    } else if is_generic {
+        // `ValueDebug` is implemented with turbo-tasks functions, which can't be generic. Without
+        // it, fields like `Vc<T>` can't be formatted either.
     } else {
         struct_attributes.push(quote! {
             #[derive(
@@ -390,30 +467,76 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         });
     }
 
+    value_type_where_clause
+        .predicates
+        .push(parse_quote! { #ty: turbo_tasks::EstimateSize });
+    value_type_where_clause
+        .predicates
+        .push(parse_quote! { #ty: turbo_tasks::introspect::IntrospectValue });
     let new_value_type = match serialization_mode {
         SerializationMode::None => quote! {
-            turbo_tasks::ValueType::new::<#ident>()
+            turbo_tasks::ValueType::new::<#ty>()
+                .with_estimate_size(turbo_tasks::macro_helpers::estimate_size_of::<#ty>)
+                .with_introspection::<#ty>()
         },
         SerializationMode::Auto | SerializationMode::Custom => {
+            value_type_where_clause.predicates.push(parse_quote! {
+                #ty: turbo_tasks::macro_helpers::serde::Serialize
+                    + for<'de> turbo_tasks::macro_helpers::serde::Deserialize<'de>
+            });
             quote! {
-                turbo_tasks::ValueType::new_with_any_serialization::<#ident>()
+                turbo_tasks::ValueType::new_with_any_serialization::<#ty>()
+                    .with_estimate_size(turbo_tasks::macro_helpers::estimate_size_of::<#ty>)
+                    .with_introspection::<#ty>()
             }
         }
         SerializationMode::AutoForInput | SerializationMode::CustomForInput => {
+            value_type_where_clause.predicates.push(parse_quote! {
+                #ty: std::fmt::Debug
+                    + Eq
+                    + std::hash::Hash
+                    + turbo_tasks::macro_helpers::serde::Serialize
+                    + for<'de> turbo_tasks::macro_helpers::serde::Deserialize<'de>
+            });
             quote! {
-                turbo_tasks::ValueType::new_with_magic_serialization::<#ident>()
+                turbo_tasks::ValueType::new_with_magic_serialization::<#ty>()
+                    .with_estimate_size(turbo_tasks::macro_helpers::estimate_size_of::<#ty>)
+                    .with_introspection::<#ty>()
             }
         }
     };
 
+    let introspect_where_clause = if is_generic {
+        where_clause_with(parse_quote! { #ty: turbo_tasks::trace::TraceRawVcs }).into_token_stream()
+    } else {
+        quote! {}
+    };
+    let introspect_impl = quote! {
+        impl #impl_generics turbo_tasks::introspect::IntrospectValue for #ty
+            #introspect_where_clause
+        {
+        }
+    };
+
     let for_input_marker = match serialization_mode {
         SerializationMode::None | SerializationMode::Auto | SerializationMode::Custom => quote! {},
         SerializationMode::AutoForInput | SerializationMode::CustomForInput => quote! {
-            impl turbo_tasks::TypedForInput for #ident {}
+            impl #impl_generics turbo_tasks::TypedForInput for #ty #vc_value_type_where_clause {}
         },
     };
 
-    let value_debug_impl = if inner_type.is_some() {
+    let shardable_impl = match &inner_type {
+        Some(inner_type) if sharded => quote! {
+            impl #impl_generics turbo_tasks::sharded::Shardable for #ty
+                #vc_value_type_where_clause
+            {
+                type Collection = #inner_type;
+            }
+        },
+        _ => quote! {},
+    };
+
+    let value_debug_impl = if inner_type.is_some() && !is_generic {
         // For transparent values, we defer directly to the inner type's `ValueDebug`
         // implementation.
         quote! {
@@ -436,20 +559,35 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         quote! {}
     };
 
-    let value_type_and_register_code = value_type_and_register(
-        ident,
-        quote! { #ident },
-        None,
-        read,
-        cell_mode,
-        new_value_type,
-    );
+    let value_type_and_register_code = if is_generic {
+        quote! {
+            unsafe impl #impl_generics turbo_tasks::VcValueType for #ty #value_type_where_clause {
+                type Read = #read;
+                type CellMode = #cell_mode;
+
+                fn get_value_type_id() -> turbo_tasks::ValueTypeId {
+                    turbo_tasks::registry::get_or_register_generic_value_type_id::<Self>(
+                        || #new_value_type,
+                    )
+                }
+            }
+        }
+    } else {
+        value_type_and_register(
+            ident,
+            quote! { #ident },
+            None,
+            read,
+            cell_mode,
+            new_value_type,
+        )
+    };
 
     let expanded = quote! {
         #(#struct_attributes)*
         #item
 
-        impl #ident {
+        impl #impl_generics #ty #vc_value_type_where_clause {
             #cell_struct
         }
 
@@ -459,6 +597,10 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
 
         #for_input_marker
 
+        #introspect_impl
+
+        #shardable_impl
+
         #value_debug_impl
     };
 
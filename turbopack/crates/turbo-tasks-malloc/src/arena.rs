@@ -0,0 +1,88 @@
+use std::{cell::RefCell, mem::ManuallyDrop, ops::Deref};
+
+pub use bumpalo;
+use bumpalo::Bump;
+
+/// Arenas that have grown larger than this are freed instead of being returned
+/// to the pool, so a single huge analysis doesn't keep its memory forever.
+const MAX_POOLED_CAPACITY: usize = 4 * 1024 * 1024;
+/// The maximum number of arenas kept in the pool of each thread.
+const MAX_POOLED_ARENAS: usize = 4;
+
+thread_local! {
+    static POOL: RefCell<Vec<Bump>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A bump allocator for short-lived data structures that are created and
+/// dropped within a single task execution, e.g. temporary graphs and effect
+/// lists of an analysis.
+///
+/// Allocating many small values from an arena is much cheaper than going
+/// through the global allocator. All memory is freed at once when the arena is
+/// dropped. Note that [`Drop`] implementations of values allocated in the arena
+/// are not executed.
+///
+/// The arena's chunks are allocated with [`TurboMalloc`][crate::TurboMalloc],
+/// so they are included in [`TurboMalloc::memory_usage`][crate::TurboMalloc::memory_usage]
+/// and the allocation counters of the task which allocated them. Dropped arenas
+/// are reset and kept in a per-thread pool, so subsequent tasks can reuse the
+/// chunks without allocating again.
+pub struct Arena {
+    bump: ManuallyDrop<Bump>,
+}
+
+impl Arena {
+    /// Takes an arena from the pool of the current thread, or creates a new
+    /// one.
+    pub fn new() -> Self {
+        let bump = POOL
+            .with(|pool| pool.borrow_mut().pop())
+            .unwrap_or_default();
+        Self {
+            bump: ManuallyDrop::new(bump),
+        }
+    }
+
+    /// The number of bytes currently allocated by this arena, including unused
+    /// capacity of its chunks.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Provides access to the underlying [`Bump`], e.g. to create
+    /// [`bumpalo::collections::Vec`]s.
+    pub fn bump(&self) -> &Bump {
+        &self.bump
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for Arena {
+    type Target = Bump;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bump
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        // SAFETY: `bump` is not used after this point.
+        let mut bump = unsafe { ManuallyDrop::take(&mut self.bump) };
+        if bump.allocated_bytes() > MAX_POOLED_CAPACITY {
+            return;
+        }
+        bump.reset();
+        let _ = POOL.try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < MAX_POOLED_ARENAS {
+                pool.push(bump);
+            }
+        });
+    }
+}
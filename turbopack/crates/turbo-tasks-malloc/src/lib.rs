@@ -1,3 +1,4 @@
+mod arena;
 mod counter;
 
 use std::{
@@ -5,6 +6,7 @@ use std::{
     marker::PhantomData,
 };
 
+pub use self::arena::{bumpalo, Arena};
 use self::counter::{add, flush, get, remove, update};
 
 #[derive(Default, Clone, Debug)]
@@ -45,6 +47,37 @@ impl AllocationCounters {
     }
 }
 
+/// Statistics of the allocator, see [`TurboMalloc::stats`].
+#[derive(Default, Clone, Debug)]
+pub struct AllocatorStats {
+    /// Bytes currently allocated through [`TurboMalloc`].
+    pub allocated: usize,
+    /// Bytes the allocator has committed from the OS. `None` when the allocator doesn't report
+    /// it, e.g. for the system allocator.
+    pub committed: Option<usize>,
+    /// The resident set size of the process in bytes.
+    pub resident: Option<usize>,
+    /// The peak resident set size of the process in bytes.
+    pub peak_resident: Option<usize>,
+}
+
+impl AllocatorStats {
+    /// Bytes the allocator keeps committed without being allocated, which
+    /// [`TurboMalloc::purge`] can return to the OS.
+    pub fn retained(&self) -> Option<usize> {
+        Some(self.committed?.saturating_sub(self.allocated))
+    }
+
+    /// The share of committed memory that isn't allocated, between 0 and 1.
+    pub fn fragmentation(&self) -> Option<f64> {
+        let committed = self.committed?;
+        if committed == 0 {
+            return Some(0.0);
+        }
+        Some(self.retained()? as f64 / committed as f64)
+    }
+}
+
 /// Turbo's preferred global allocator. This is a new type instead of a type
 /// alias because you can't use type aliases to instantiate unit types (E0423).
 pub struct TurboMalloc;
@@ -65,6 +98,26 @@ impl TurboMalloc {
     pub fn reset_allocation_counters(start: AllocationCounters) {
         self::counter::reset_allocation_counters(start);
     }
+
+    pub fn stats() -> AllocatorStats {
+        AllocatorStats {
+            allocated: get(),
+            ..base_stats()
+        }
+    }
+
+    /// Returns memory the allocator retains for future allocations to the OS, e.g. after a large
+    /// amount of memory was freed. Does nothing for the system allocator.
+    pub fn purge() {
+        #[cfg(all(
+            feature = "custom_allocator",
+            not(any(target_family = "wasm", target_env = "musl"))
+        ))]
+        // SAFETY: Only frees memory which isn't allocated.
+        unsafe {
+            libmimalloc_sys::mi_collect(true)
+        };
+    }
 }
 
 /// Get the allocator for this platform that we should wrap with TurboMalloc.
@@ -82,6 +135,47 @@ fn base_alloc() -> &'static impl GlobalAlloc {
     return &std::alloc::System;
 }
 
+/// The statistics reported by the allocator for this platform, without
+/// [`AllocatorStats::allocated`].
+#[cfg(all(
+    feature = "custom_allocator",
+    not(any(target_family = "wasm", target_env = "musl"))
+))]
+fn base_stats() -> AllocatorStats {
+    let mut current_rss = 0;
+    let mut peak_rss = 0;
+    let mut current_commit = 0;
+    let mut peak_commit = 0;
+    // SAFETY: mimalloc only writes to the pointers it's given, null pointers are skipped.
+    unsafe {
+        libmimalloc_sys::mi_process_info(
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut current_rss,
+            &mut peak_rss,
+            &mut current_commit,
+            &mut peak_commit,
+            std::ptr::null_mut(),
+        );
+    }
+    AllocatorStats {
+        allocated: 0,
+        committed: Some(current_commit),
+        resident: Some(current_rss),
+        peak_resident: Some(peak_rss),
+    }
+}
+
+/// The system allocator doesn't report statistics.
+#[cfg(any(
+    not(feature = "custom_allocator"),
+    any(target_family = "wasm", target_env = "musl")
+))]
+fn base_stats() -> AllocatorStats {
+    AllocatorStats::default()
+}
+
 unsafe impl GlobalAlloc for TurboMalloc {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         let ret = base_alloc().alloc(layout);
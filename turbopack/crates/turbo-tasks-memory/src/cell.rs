@@ -3,7 +3,7 @@ use std::{fmt::Debug, mem::replace};
 use turbo_tasks::{
     backend::CellContent,
     event::{Event, EventListener},
-    TaskId, TaskIdSet, TurboTasksBackendApi,
+    SharedReference, TaskId, TaskIdSet, TurboTasksBackendApi,
 };
 
 use crate::MemoryBackend;
@@ -55,6 +55,11 @@ pub enum ReadContentError {
 }
 
 impl Cell {
+    /// The tasks which read the cell since it was last assigned.
+    pub fn dependent_tasks(&self) -> &TaskIdSet {
+        &self.dependent_tasks
+    }
+
     /// Removes a task from the list of dependent tasks.
     pub fn remove_dependent_task(&mut self, task: TaskId) {
         self.dependent_tasks.remove(&task);
@@ -156,7 +161,7 @@ impl Cell {
     }
 
     /// Assigns a new content to the cell. Will notify dependent tasks if the
-    /// content has changed.
+    /// content has changed, and returns whether it did.
     /// If clean = true, the task inputs weren't changes since the last
     /// execution and can be assumed to produce the same content again.
     ///
@@ -168,7 +173,7 @@ impl Cell {
         content: CellContent,
         clean: bool,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
-    ) {
+    ) -> bool {
         match &self.state {
             CellState::Empty => {}
             CellState::Computing { event } => {
@@ -177,7 +182,7 @@ impl Cell {
                     // We can assume that the task is deterministic and produces the same content
                     // again. No need to notify dependent tasks.
                     self.state = CellState::Value { content };
-                    return;
+                    return false;
                 }
             }
             CellState::TrackedValueless => {
@@ -185,24 +190,26 @@ impl Cell {
                     // We can assume that the task is deterministic and produces the same content
                     // again. No need to notify dependent tasks.
                     self.state = CellState::Value { content };
-                    return;
+                    return false;
                 }
             }
             CellState::Value {
                 content: cell_content,
             } => {
                 if content == *cell_content {
-                    return;
+                    return false;
                 }
             }
         }
         self.state = CellState::Value { content };
         // Assigning to a cell will invalidate all dependent tasks as the content might
         // have changed.
-        if !self.dependent_tasks.is_empty() {
-            turbo_tasks.schedule_notify_tasks_set(&self.dependent_tasks);
-            self.dependent_tasks.clear();
+        if self.dependent_tasks.is_empty() {
+            return false;
         }
+        turbo_tasks.schedule_notify_tasks_set(&self.dependent_tasks);
+        self.dependent_tasks.clear();
+        true
     }
 
     pub fn empty(
@@ -243,6 +250,21 @@ impl Cell {
         self.dependent_tasks.is_empty() && matches!(self.state, CellState::Empty)
     }
 
+    /// Returns true if the cell holds its content.
+    pub fn has_content(&self) -> bool {
+        matches!(self.state, CellState::Value { .. })
+    }
+
+    /// The content of the cell, when it holds one.
+    pub fn content(&self) -> Option<&SharedReference> {
+        match &self.state {
+            CellState::Value {
+                content: CellContent(Some(content)),
+            } => Some(content),
+            _ => None,
+        }
+    }
+
     /// Takes the content out of the cell. Make sure to drop the content outside
     /// of the task state lock.
     #[must_use]
@@ -525,6 +525,12 @@ impl TaskEdgesSet {
             _ => None,
         })
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = TaskEdge> + '_ {
+        self.edges
+            .iter()
+            .flat_map(|(task, entry)| entry.iter().map(move |e| e.into_dependency(*task)))
+    }
 }
 
 impl IntoIterator for TaskEdgesSet {
@@ -570,6 +576,12 @@ impl TaskEdgesList {
             _ => None,
         })
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = TaskEdge> + '_ {
+        self.edges
+            .iter()
+            .flat_map(|(task, entry)| entry.iter().map(move |e| e.into_dependency(*task)))
+    }
 }
 
 impl IntoIterator for TaskEdgesList {
@@ -5,14 +5,14 @@ use std::{
     mem::take,
     num::NonZeroU32,
     sync::atomic::{AtomicU32, AtomicUsize, Ordering},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use concurrent_queue::ConcurrentQueue;
 use dashmap::DashSet;
 use parking_lot::Mutex;
 use tracing::field::{debug, Empty};
-use turbo_tasks::{TaskId, TurboTasksBackendApi};
+use turbo_tasks::{GcEviction, TaskId, TurboTasksBackendApi};
 
 use crate::{task::GcResult, MemoryBackend};
 
@@ -33,6 +33,8 @@ pub struct GcTaskState {
     pub priority: GcPriority,
     /// The generation where the task was last accessed.
     pub generation: Option<NonZeroU32>,
+    /// The number of generations in which the task was accessed.
+    pub accesses: u32,
 }
 
 impl GcTaskState {
@@ -55,15 +57,26 @@ impl GcTaskState {
         if let Some(old_generation) = self.generation {
             if old_generation < generation {
                 self.generation = Some(generation);
+                self.accesses = self.accesses.saturating_add(1);
                 true
             } else {
                 false
             }
         } else {
             self.generation = Some(generation);
+            self.accesses = self.accesses.saturating_add(1);
             true
         }
     }
+
+    /// The order in which the tasks of a generation are collected, lowest first.
+    fn eviction_key(&self, eviction: GcEviction) -> u64 {
+        match eviction {
+            GcEviction::MemoryPerTime => (u16::MAX - self.priority.memory_per_time) as u64,
+            GcEviction::LeastRecentlyUsed => self.generation.map_or(0, |gen| gen.get() as u64),
+            GcEviction::LeastFrequentlyUsed => self.accesses as u64,
+        }
+    }
 }
 
 const MAX_DEACTIVATIONS: usize = 100_000;
@@ -81,6 +94,8 @@ pub const MAX_GC_STEPS: usize = 100;
 struct OldGeneration {
     tasks: Vec<TaskId>,
     generation: NonZeroU32,
+    /// When the generation was created. The tasks weren't accessed since then.
+    created: Instant,
 }
 
 #[derive(Default)]
@@ -194,6 +209,7 @@ impl GcQueue {
             self.generations.lock().push_front(OldGeneration {
                 tasks,
                 generation: gen,
+                created: Instant::now(),
             });
             gen
         } else {
@@ -201,6 +217,33 @@ impl GcQueue {
         }
     }
 
+    /// Moves the tasks accessed since the last generation was created into a new generation, so
+    /// that they age while turbo-tasks is idle.
+    fn flush_incoming_tasks(&self) {
+        // Generations of `TASKS_PER_NEW_GENERATION` tasks are created by `add_task`.
+        let Ok(count) =
+            self.incoming_tasks_count
+                .fetch_update(Ordering::Acquire, Ordering::Relaxed, |count| {
+                    (count > 0 && count < TASKS_PER_NEW_GENERATION).then_some(0)
+                })
+        else {
+            return;
+        };
+        let gen = unsafe {
+            // SAFETY: We are sure that the generation is not 0, since we start at 1.
+            NonZeroU32::new_unchecked(self.generation.fetch_add(1, Ordering::Relaxed))
+        };
+        // Tasks are pushed before they are counted, so there are at least `count` tasks.
+        let tasks = (0..count)
+            .map_while(|_| self.incoming_tasks.pop().ok())
+            .collect();
+        self.generations.lock().push_front(OldGeneration {
+            tasks,
+            generation: gen,
+            created: Instant::now(),
+        });
+    }
+
     fn process_deactivations(
         &self,
         backend: &MemoryBackend,
@@ -226,6 +269,8 @@ impl GcQueue {
 
     fn process_old_generation(
         &self,
+        eviction: GcEviction,
+        percentage_to_collect: usize,
         backend: &MemoryBackend,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> Option<ProcessGenerationResult> {
@@ -238,6 +283,7 @@ impl GcQueue {
             OldGeneration {
                 mut tasks,
                 generation,
+                created,
             },
             old_generations,
         )) = old_generation_info
@@ -253,7 +299,11 @@ impl GcQueue {
                 if let Some(state) = task.gc_state() {
                     if let Some(gen) = state.generation {
                         if gen <= generation {
-                            indices.push((Reverse(state.priority), i as u32));
+                            indices.push((
+                                state.eviction_key(eviction),
+                                i as u32,
+                                Reverse(state.priority),
+                            ));
                         }
                     }
                 }
@@ -280,8 +330,8 @@ impl GcQueue {
         }
         tasks.truncate(indices.len());
 
-        let tasks_to_collect = max(1, tasks.len() * PERCENTAGE_TO_COLLECT / 100);
-        let (Reverse(max_priority), _) = indices[0];
+        let tasks_to_collect = max(1, tasks.len() * percentage_to_collect / 100);
+        let (_, _, Reverse(max_priority)) = indices[0];
         drop(indices);
 
         // Put back remaining tasks into the queue
@@ -303,16 +353,19 @@ impl GcQueue {
                             }
                         }
                         let generation = first.generation;
+                        let created = first.created;
                         first.tasks = gen_a;
                         guard.push_front(OldGeneration {
                             tasks: gen_b,
                             generation,
+                            created,
                         });
                     }
                 } else {
                     guard.push_front(OldGeneration {
                         tasks: remaining_tasks.to_vec(),
                         generation,
+                        created,
                     });
                 }
             }
@@ -353,6 +406,7 @@ impl GcQueue {
     /// been made. Returns the number of old generations.
     pub fn run_gc(
         &self,
+        eviction: GcEviction,
         backend: &MemoryBackend,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> Option<usize> {
@@ -376,7 +430,7 @@ impl GcQueue {
             content_dropped_count,
             unloaded_count,
             already_unloaded_count,
-        }) = self.process_old_generation(backend, turbo_tasks)
+        }) = self.process_old_generation(eviction, PERCENTAGE_TO_COLLECT, backend, turbo_tasks)
         {
             span.record("deactivations_count", deactivations_count);
             span.record("content_dropped_count", content_dropped_count);
@@ -393,4 +447,33 @@ impl GcQueue {
             (deactivations_count > 0).then_some(0)
         }
     }
+
+    /// Collects all tasks which weren't accessed for at least `max_idle_age`. Returns the number
+    /// of collected generations.
+    pub fn run_idle_age_gc(
+        &self,
+        max_idle_age: Duration,
+        eviction: GcEviction,
+        backend: &MemoryBackend,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> usize {
+        let _span = tracing::trace_span!("garbage collection of idle tasks").entered();
+        self.flush_incoming_tasks();
+        let mut count = 0;
+        while self
+            .generations
+            .lock()
+            .back()
+            .is_some_and(|generation| generation.created.elapsed() >= max_idle_age)
+        {
+            if self
+                .process_old_generation(eviction, 100, backend, turbo_tasks)
+                .is_none()
+            {
+                break;
+            }
+            count += 1;
+        }
+        count
+    }
 }
@@ -23,10 +23,13 @@ use turbo_tasks::{
         TransientTaskType, TypedCellContent,
     },
     event::EventListener,
+    graph_export::TaskGraphNode,
     util::{IdFactoryWithReuse, NoMoveVec},
-    CellId, FunctionId, RawVc, ReadConsistency, TaskId, TaskIdSet, TraitTypeId,
-    TurboTasksBackendApi, Unused, ValueTypeId, TRANSIENT_TASK_BIT,
+    CellId, FunctionId, GcCompleted, GcPolicy, InvalidationCause, InvalidationReason, RawVc,
+    ReadConsistency, TaskId, TaskIdSet, TaskMemoryUsage, TraitTypeId, TurboTasksBackendApi, Unused,
+    ValueTypeId, TRANSIENT_TASK_BIT,
 };
+use turbo_tasks_hash::HashAlgorithm;
 
 use crate::{
     edges_set::{TaskEdge, TaskEdgesSet},
@@ -60,9 +63,15 @@ pub struct MemoryBackend {
         DashMap<Arc<PreHashed<CachedTaskType>>, TaskId, BuildHasherDefault<PassThroughHash>>,
     memory_limit: AtomicUsize,
     gc_queue: Option<GcQueue>,
+    gc_policy: GcPolicy,
     idle_gc_active: AtomicBool,
     task_statistics: TaskStatisticsApi,
     pub(crate) print_task_invalidation: bool,
+    /// The cause of the last invalidation of each task, when enabled with
+    /// [MemoryBackend::track_invalidation_causes].
+    invalidation_causes: Option<DashMap<TaskId, InvalidationCause>>,
+    /// Hashes the content of changed cells for the invalidation causes.
+    cell_hash_algorithm: HashAlgorithm,
 }
 
 impl Default for MemoryBackend {
@@ -87,9 +96,12 @@ impl MemoryBackend {
             ),
             memory_limit: AtomicUsize::new(memory_limit_bytes),
             gc_queue: (memory_limit_bytes != usize::MAX).then(GcQueue::new),
+            gc_policy: GcPolicy::default(),
             idle_gc_active: AtomicBool::new(false),
             task_statistics: TaskStatisticsApi::default(),
             print_task_invalidation: false,
+            invalidation_causes: None,
+            cell_hash_algorithm: HashAlgorithm::default(),
         }
     }
 
@@ -101,6 +113,34 @@ impl MemoryBackend {
         self.print_task_invalidation = value;
     }
 
+    /// A debug feature that records why tasks are invalidated, see
+    /// [turbo_tasks::why_invalidated]. Computing the hashes of changed cells makes updates slower.
+    pub fn track_invalidation_causes(&mut self, value: bool) {
+        self.invalidation_causes = value.then(DashMap::new);
+    }
+
+    /// The algorithm hashing the content of changed cells for
+    /// [MemoryBackend::track_invalidation_causes]. Defaults to the 64-bit xxh3 hash.
+    pub fn cell_hash_algorithm(&mut self, hash_algorithm: HashAlgorithm) {
+        self.cell_hash_algorithm = hash_algorithm;
+    }
+
+    pub(crate) fn is_tracking_invalidation_causes(&self) -> bool {
+        self.invalidation_causes.is_some()
+    }
+
+    pub(crate) fn record_invalidation_cause(
+        &self,
+        tasks: impl IntoIterator<Item = TaskId>,
+        cause: impl Fn() -> InvalidationCause,
+    ) {
+        if let Some(causes) = &self.invalidation_causes {
+            for task in tasks {
+                causes.insert(task, cause());
+            }
+        }
+    }
+
     fn connect_task_child(
         &self,
         parent: TaskId,
@@ -179,11 +219,38 @@ impl MemoryBackend {
         &self,
         idle: bool,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> bool {
+        let collected = self.collect_garbage(idle, turbo_tasks);
+        if collected {
+            turbo_tasks.event_bus().publish(GcCompleted {
+                idle,
+                memory_usage: turbo_tasks_malloc::TurboMalloc::memory_usage(),
+            });
+        }
+        collected
+    }
+
+    fn collect_garbage(
+        &self,
+        idle: bool,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> bool {
         if let Some(gc_queue) = &self.gc_queue {
             let mut did_something = false;
+            if let Some(max_idle_age) = self.gc_policy.max_idle_age.filter(|_| idle) {
+                did_something = gc_queue.run_idle_age_gc(
+                    max_idle_age,
+                    self.gc_policy.eviction,
+                    self,
+                    turbo_tasks,
+                ) > 0;
+            }
             let mut remaining_generations = 0;
             let mut mem_limit = self.memory_limit.load(Ordering::Relaxed);
+            if mem_limit == usize::MAX {
+                // Only collecting idle tasks
+                return did_something;
+            }
             let mut span = None;
             'outer: loop {
                 let mut collected_generations = 0;
@@ -211,7 +278,7 @@ impl MemoryBackend {
                             Some(tracing::trace_span!(parent: None, "garbage collection", usage));
                     }
 
-                    let progress = gc_queue.run_gc(self, turbo_tasks);
+                    let progress = gc_queue.run_gc(self.gc_policy.eviction, self, turbo_tasks);
 
                     if progress.is_some() {
                         did_something = true;
@@ -407,6 +474,18 @@ impl MemoryBackend {
 }
 
 impl Backend for MemoryBackend {
+    fn set_gc_policy(&mut self, policy: GcPolicy) {
+        if let Some(max_memory) = policy.max_memory {
+            *self.memory_limit.get_mut() = max_memory;
+        }
+        if self.gc_queue.is_none()
+            && (*self.memory_limit.get_mut() != usize::MAX || policy.max_idle_age.is_some())
+        {
+            self.gc_queue = Some(GcQueue::new());
+        }
+        self.gc_policy = policy;
+    }
+
     fn idle_start(&self, turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>) {
         if self
             .idle_gc_active
@@ -419,9 +498,55 @@ impl Backend for MemoryBackend {
     }
 
     fn invalidate_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>) {
+        self.record_invalidation_cause([task], || InvalidationCause::External { reason: None });
         self.with_task(task, |task| task.invalidate(self, turbo_tasks));
     }
 
+    fn invalidate_task_with_reason(
+        &self,
+        task: TaskId,
+        reason: &dyn InvalidationReason,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) {
+        self.record_invalidation_cause([task], || InvalidationCause::External {
+            reason: Some(reason.to_string()),
+        });
+        self.with_task(task, |task| task.invalidate(self, turbo_tasks));
+    }
+
+    fn invalidation_cause(
+        &self,
+        task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Option<InvalidationCause> {
+        self.invalidation_causes
+            .as_ref()?
+            .get(&task)
+            .map(|cause| cause.clone())
+    }
+
+    fn task_memory_usage(
+        &self,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Vec<TaskMemoryUsage> {
+        let mut usage = Vec::new();
+        self.with_all_cached_tasks(|task| {
+            usage.extend(self.with_task(task, |task| task.memory_usage()));
+        });
+        usage
+    }
+
+    fn task_graph(
+        &self,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Vec<TaskGraphNode> {
+        let mut nodes = Vec::new();
+        self.with_all_cached_tasks(|task| {
+            nodes.extend(self.with_task(task, |task| task.graph_node()));
+        });
+        nodes
+    }
+
     fn invalidate_tasks(
         &self,
         tasks: &[TaskId],
@@ -450,6 +575,14 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| task.get_description())
     }
 
+    fn get_task_stable_hash(
+        &self,
+        task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> Option<u64> {
+        self.with_task(task, |task| task.get_stable_hash())
+    }
+
     type TaskState = TaskState;
     fn new_task_state(&self, _task: TaskId) -> Self::TaskState {
         TaskState {
@@ -627,6 +760,28 @@ impl Backend for MemoryBackend {
         })
     }
 
+    fn task_execution_canceled(
+        &self,
+        task_id: TaskId,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) {
+        self.with_task(task_id, |task| {
+            task.drop_cell_contents();
+            task.invalidate(self, turbo_tasks);
+        });
+    }
+
+    fn is_task_cell_alive(
+        &self,
+        task_id: TaskId,
+        index: CellId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) -> bool {
+        self.with_task(task_id, |task| {
+            task.with_cell(index, |cell| cell.has_content())
+        })
+    }
+
     fn read_task_collectibles(
         &self,
         id: TaskId,
@@ -675,7 +830,25 @@ impl Backend for MemoryBackend {
     ) {
         self.with_task(task, |task| {
             task.access_cell_for_write(index, |cell, clean| {
-                cell.assign(content, clean, turbo_tasks)
+                if !self.is_tracking_invalidation_causes() {
+                    cell.assign(content, clean, turbo_tasks);
+                    return;
+                }
+                let dependent_tasks = cell.dependent_tasks().clone();
+                let old_hash = cell
+                    .read_own_content_untracked()
+                    .content_hash(index.type_id, self.cell_hash_algorithm);
+                let new_hash = content.content_hash(index.type_id, self.cell_hash_algorithm);
+                if cell.assign(content, clean, turbo_tasks) {
+                    self.record_invalidation_cause(dependent_tasks, || {
+                        InvalidationCause::CellChanged {
+                            task: task.id,
+                            cell: index,
+                            old_hash,
+                            new_hash,
+                        }
+                    });
+                }
             })
         })
     }
@@ -807,6 +980,14 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| task.mark_as_finished(self, turbo_tasks))
     }
 
+    fn prefetch_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>) {
+        // Tasks are created dirty and are only scheduled once they are read, or once they are
+        // connected to an active root. Prefetching schedules them right away.
+        self.with_task(task, |task| {
+            task.schedule_when_dirty_from_aggregation(self, turbo_tasks)
+        });
+    }
+
     fn create_transient_task(
         &self,
         task_type: TransientTaskType,
@@ -835,6 +1016,10 @@ impl Backend for MemoryBackend {
     fn dispose_root_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
         Task::unset_root(task, self, turbo_tasks);
     }
+
+    fn pin_root_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        Task::set_pinned(task, self, turbo_tasks);
+    }
 }
 
 pub(crate) enum Job {
@@ -857,6 +1042,9 @@ impl Job {
                     let job = backend.create_backend_job(Job::GarbageCollection);
                     turbo_tasks.schedule_backend_background_job(job);
                 } else {
+                    // The collected tasks might have held the last references to interned
+                    // strings.
+                    turbo_rcstr::collect_interned();
                     backend.idle_gc_active.store(false, Ordering::Release);
                 }
             }
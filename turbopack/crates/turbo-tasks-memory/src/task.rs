@@ -21,8 +21,10 @@ use turbo_prehash::PreHashed;
 use turbo_tasks::{
     backend::{CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec},
     event::{Event, EventListener},
-    get_invalidator, registry, CellId, Invalidator, RawVc, ReadConsistency, TaskId, TaskIdSet,
-    TraitTypeId, TurboTasksBackendApi, TurboTasksBackendApiExt, ValueTypeId,
+    get_invalidator,
+    graph_export::{TaskGraphNode, TaskGraphRead},
+    registry, CellId, InvalidationCause, Invalidator, RawVc, ReadConsistency, TaskId, TaskIdSet,
+    TaskMemoryUsage, TraitTypeId, TurboTasksBackendApi, TurboTasksBackendApiExt, ValueTypeId,
 };
 
 use crate::{
@@ -417,6 +419,27 @@ impl TaskStateType {
         }
     }
 
+    fn edges(&self) -> impl Iterator<Item = TaskEdge> + '_ {
+        match self {
+            TaskStateType::Done { edges, .. } => Either::Left(edges.iter()),
+            TaskStateType::InProgress(box InProgressState {
+                outdated_edges,
+                new_children,
+                ..
+            }) => Either::Right(Either::Left(
+                outdated_edges
+                    .iter()
+                    .chain(new_children.iter().map(|&child| TaskEdge::Child(child))),
+            )),
+            TaskStateType::Dirty { outdated_edges, .. } => {
+                Either::Right(Either::Right(outdated_edges.iter()))
+            }
+            TaskStateType::Scheduled(box ScheduledState { outdated_edges, .. }) => {
+                Either::Right(Either::Right(outdated_edges.iter()))
+            }
+        }
+    }
+
     fn into_dependencies_and_children(self) -> (TaskEdgesSet, SmallVec<[TaskId; 6]>) {
         match self {
             TaskStateType::Done { edges, .. } => {
@@ -447,7 +470,7 @@ impl TaskStateType {
 use TaskStateType::*;
 
 use self::{
-    aggregation::{ActiveQuery, RootType, TaskAggregationNode, TaskGuard},
+    aggregation::{ActiveQuery, PinnedQuery, RootType, TaskAggregationNode, TaskGuard},
     meta_state::{
         FullTaskWriteGuard, TaskMetaState, TaskMetaStateReadGuard, TaskMetaStateWriteGuard,
     },
@@ -536,6 +559,20 @@ impl Task {
         }
     }
 
+    /// Tasks of functions annotated with `#[turbo_tasks::function(gc_exempt)]` are never garbage
+    /// collected.
+    fn is_gc_exempt(&self) -> bool {
+        match &self.ty {
+            TaskType::Persistent { ty } | TaskType::Transient { ty } => match &***ty {
+                CachedTaskType::Native { fn_type, .. } => {
+                    registry::get_function(*fn_type).function_meta.gc_exempt
+                }
+                _ => false,
+            },
+            TaskType::Root(_) | TaskType::Once(_) => false,
+        }
+    }
+
     pub(crate) fn is_once(&self) -> bool {
         match &self.ty {
             TaskType::Persistent { .. } => false,
@@ -561,6 +598,22 @@ impl Task {
         aggregation_context.apply_queued_updates();
     }
 
+    pub(crate) fn set_pinned(
+        id: TaskId,
+        backend: &MemoryBackend,
+        turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
+    ) {
+        let mut aggregation_context = TaskAggregationContext::new(turbo_tasks, backend);
+        {
+            Self::set_root_type(
+                &aggregation_context,
+                &mut aggregation_context.aggregation_data(id),
+                RootType::Pinned,
+            );
+        }
+        aggregation_context.apply_queued_updates();
+    }
+
     pub(crate) fn set_once(
         id: TaskId,
         backend: &MemoryBackend,
@@ -610,6 +663,14 @@ impl Task {
         }
     }
 
+    pub(crate) fn get_stable_hash(&self) -> Option<u64> {
+        if let TaskType::Persistent { ty } = &self.ty {
+            ty.stable_hash()
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn get_description(&self) -> String {
         Self::format_description(&TaskTypeForDescription::from(&self.ty), self.id)
     }
@@ -905,6 +966,7 @@ impl Task {
                                 });
                             }
                         }
+                        self.record_output_changed(&state.output, backend);
                         state.output.link(result, turbo_tasks)
                     }
                 }
@@ -912,9 +974,13 @@ impl Task {
                     if let Some(name) = self.get_function_name() {
                         err = err.context(format!("Execution of {} failed", name));
                     }
+                    self.record_output_changed(&state.output, backend);
                     state.output.error(err, turbo_tasks)
                 }
-                Err(message) => state.output.panic(message, turbo_tasks),
+                Err(message) => {
+                    self.record_output_changed(&state.output, backend);
+                    state.output.panic(message, turbo_tasks)
+                }
             },
 
             Dirty { .. } | Scheduled { .. } | Done { .. } => {
@@ -926,6 +992,12 @@ impl Task {
         };
     }
 
+    fn record_output_changed(&self, output: &Output, backend: &MemoryBackend) {
+        backend.record_invalidation_cause(output.dependent_tasks.iter().copied(), || {
+            InvalidationCause::OutputChanged { task: self.id }
+        });
+    }
+
     #[must_use]
     pub(crate) fn execution_completed(
         &self,
@@ -1750,12 +1822,16 @@ impl Task {
         backend: &MemoryBackend,
         turbo_tasks: &dyn TurboTasksBackendApi<MemoryBackend>,
     ) -> GcResult {
-        if !self.is_pure() {
+        if !self.is_pure() || self.is_gc_exempt() {
             return GcResult::NotPossible;
         }
 
         let aggregation_context = TaskAggregationContext::new(turbo_tasks, backend);
         let active = query_root_info(&aggregation_context, ActiveQuery::default(), self.id);
+        // Pinned tasks keep their content, so reading them never needs to recompute them.
+        if active && query_root_info(&aggregation_context, PinnedQuery::default(), self.id) {
+            return GcResult::NotPossible;
+        }
 
         match self.state_mut() {
             TaskMetaStateWriteGuard::Full(mut state) => {
@@ -1820,6 +1896,80 @@ impl Task {
         }
     }
 
+    /// Drops the content of the cells of a task whose execution was canceled, unless the task is
+    /// stateful. Reading one of these cells recomputes the task.
+    pub(crate) fn drop_cell_contents(&self) {
+        let cells_to_drop = match self.state_mut() {
+            TaskMetaStateWriteGuard::Full(mut state) => {
+                if let TaskStateType::Done { stateful: true, .. } = state.state_type {
+                    return;
+                }
+                state
+                    .cells
+                    .values_mut()
+                    .flat_map(|cells| cells.iter_mut())
+                    .filter_map(|cell| cell.gc_content())
+                    .collect::<Vec<_>>()
+            }
+            _ => return,
+        };
+        // Dropping cells outside of the lock
+        drop(cells_to_drop);
+    }
+
+    /// Estimates the memory retained by the content of the cells. `None` when no cell holds
+    /// content.
+    pub(crate) fn memory_usage(&self) -> Option<TaskMemoryUsage> {
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            return None;
+        };
+        let mut cells = 0;
+        let mut bytes = 0;
+        for (type_id, list) in state.cells.iter() {
+            let value_type = registry::get_value_type(*type_id);
+            for content in list.iter().filter_map(|cell| cell.content()) {
+                cells += 1;
+                bytes += value_type.estimate_size(&*content.0);
+            }
+        }
+        (cells > 0).then_some(TaskMemoryUsage {
+            task: self.id,
+            cells,
+            bytes,
+        })
+    }
+
+    pub(crate) fn graph_node(&self) -> Option<TaskGraphNode> {
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            return None;
+        };
+        let mut node = match &self.ty {
+            TaskType::Root(..) | TaskType::Once(..) => TaskGraphNode {
+                task: self.id,
+                name: Self::format_description(&TaskTypeForDescription::from(&self.ty), self.id),
+                inputs: Vec::new(),
+                reads: Vec::new(),
+                children: Vec::new(),
+                dirty: false,
+            },
+            TaskType::Persistent { ty } | TaskType::Transient { ty } => {
+                TaskGraphNode::new(self.id, ty)
+            }
+        };
+        node.dirty = !matches!(state.state_type, TaskStateType::Done { .. });
+        for edge in state.state_type.edges() {
+            match edge {
+                TaskEdge::Output(task) => node.reads.push(TaskGraphRead::Output(task)),
+                TaskEdge::Cell(task, cell) => node.reads.push(TaskGraphRead::Cell(task, cell)),
+                TaskEdge::Collectibles(task, trait_type) => node
+                    .reads
+                    .push(TaskGraphRead::Collectibles(task, trait_type)),
+                TaskEdge::Child(task) => node.children.push(task),
+            }
+        }
+        Some(node)
+    }
+
     pub(crate) fn gc_state(&self) -> Option<GcTaskState> {
         if let TaskMetaStateReadGuard::Full(state) = self.state() {
             Some(state.gc)
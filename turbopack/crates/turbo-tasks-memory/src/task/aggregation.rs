@@ -30,6 +30,8 @@ use crate::{
 pub enum RootType {
     Once,
     Root,
+    /// A root task pinning its subgraph, see [turbo_tasks::TurboTasks::pin_vc].
+    Pinned,
     ReadingStronglyConsistent,
 }
 
@@ -65,6 +67,7 @@ pub struct Aggregated {
 
     /// Only used for the aggregation root. Which kind of root is this?
     /// [RootType::Once] for OnceTasks or [RootType::Root] for Root Tasks.
+    /// [RootType::Pinned] for Root Tasks pinning their subgraph.
     /// [RootType::ReadingStronglyConsistent] while currently reading a task
     /// strongly consistent. It's set to None for other tasks, when the once
     /// task is done or when the root task is disposed.
@@ -432,6 +435,30 @@ impl RootQuery for ActiveQuery {
     }
 }
 
+/// Whether the task is in the subgraph of a pinned root task.
+#[derive(Default)]
+pub struct PinnedQuery {
+    pinned: bool,
+}
+
+impl RootQuery for PinnedQuery {
+    type Data = Aggregated;
+    type Result = bool;
+
+    fn query(&mut self, data: &Self::Data) -> ControlFlow<()> {
+        if matches!(data.root_type, Some(RootType::Pinned)) {
+            self.pinned = true;
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn result(self) -> Self::Result {
+        self.pinned
+    }
+}
+
 pub struct TaskGuard<'l> {
     id: TaskId,
     guard: TaskMetaStateWriteGuard<'l>,
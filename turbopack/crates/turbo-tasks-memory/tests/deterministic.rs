@@ -0,0 +1,54 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use turbo_tasks::{TryJoinIterExt, TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+/// The order in which the `leaf` tasks started.
+static ORDER: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn same_order_for_same_seed() {
+    REGISTRATION.ensure_registered();
+    let first = execution_order(42).await;
+    let second = execution_order(42).await;
+    assert_eq!(first, second);
+    let mut sorted = first.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+}
+
+async fn execution_order(seed: u64) -> Vec<u32> {
+    ORDER.lock().unwrap().clear();
+    let tt = TurboTasks::builder(MemoryBackend::default())
+        .deterministic(seed)
+        .build();
+    tt.run_once(async move {
+        assert_eq!(*fan_out(20).strongly_consistent().await?, 190);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+    tt.stop_and_wait().await;
+    std::mem::take(&mut *ORDER.lock().unwrap())
+}
+
+#[turbo_tasks::function]
+async fn fan_out(count: u32) -> Result<Vc<u32>> {
+    let values = (0..count).map(leaf).try_join().await?;
+    Ok(Vc::cell(values.iter().map(|value| **value).sum()))
+}
+
+#[turbo_tasks::function]
+async fn leaf(value: u32) -> Result<Vc<u32>> {
+    ORDER.lock().unwrap().push(value);
+    tokio::task::yield_now().await;
+    Ok(Vc::cell(value))
+}
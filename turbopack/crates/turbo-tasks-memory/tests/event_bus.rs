@@ -0,0 +1,42 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{publish_event, GcCompleted, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Written(u32);
+
+#[tokio::test]
+async fn delivers_events_published_by_tasks() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let mut written = tt.subscribe_events::<Written>();
+    let mut gc = tt.subscribe_events::<GcCompleted>();
+    tt.run_once(async move {
+        write(1).await?;
+        write(2).await?;
+        // Taken from the cache, so it isn't published again.
+        write(1).await?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    let mut events = vec![written.recv().await.unwrap(), written.recv().await.unwrap()];
+    events.sort_unstable_by_key(|event| event.0);
+    assert_eq!(events, [Written(1), Written(2)]);
+    assert_eq!(written.try_recv(), None);
+    assert_eq!(gc.try_recv(), None);
+}
+
+#[turbo_tasks::function]
+async fn write(value: u32) -> Result<()> {
+    publish_event(Written(value));
+    Ok(())
+}
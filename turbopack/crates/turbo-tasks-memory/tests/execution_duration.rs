@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/execution_duration.rs
\ No newline at end of file
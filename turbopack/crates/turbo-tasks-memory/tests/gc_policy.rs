@@ -0,0 +1,60 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::time::Duration;
+
+use turbo_tasks::{GcPolicy, ResolvedVc, TurboTasks, Vc, WeakVc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn collects_idle_tasks_except_exempt_ones() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::builder(MemoryBackend::default())
+        .gc_policy(GcPolicy {
+            max_idle_age: Some(Duration::ZERO),
+            ..Default::default()
+        })
+        .build();
+    let (collected, exempt) = tt
+        .run_once(async move {
+            let collected: WeakVc<Value> = ResolvedVc::downgrade(value(1).to_resolved().await?);
+            let exempt: WeakVc<Value> = ResolvedVc::downgrade(exempt_value(2).to_resolved().await?);
+            Ok((collected, exempt))
+        })
+        .await
+        .unwrap();
+
+    // Garbage collection runs in the background when turbo-tasks becomes idle.
+    let mut alive = (true, true);
+    for _ in 0..100 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        tt.wait_background_done().await;
+        alive = tt
+            .run_once(
+                async move { Ok((collected.upgrade().is_some(), exempt.upgrade().is_some())) },
+            )
+            .await
+            .unwrap();
+        if !alive.0 {
+            break;
+        }
+    }
+    assert_eq!(alive, (false, true));
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::function]
+fn value(value: u32) -> Vc<Value> {
+    Vc::cell(value)
+}
+
+#[turbo_tasks::function(gc_exempt)]
+fn exempt_value(value: u32) -> Vc<Value> {
+    Vc::cell(value)
+}
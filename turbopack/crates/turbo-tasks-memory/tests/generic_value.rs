@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/generic_value.rs
\ No newline at end of file
@@ -0,0 +1,81 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{
+    graph_export::{GraphEdgeKind, GraphExportFilter},
+    TurboTasks, Vc,
+};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn exports_calls_and_reads() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let tt_clone = tt.clone();
+    tt.run_once(async move {
+        let sum = sum(3);
+        sum.await?;
+        let sum_task = Vc::into_raw(sum).get_task_id();
+
+        let graph = tt_clone.export_graph(&GraphExportFilter {
+            roots: vec![sum_task],
+            ..Default::default()
+        });
+        assert_eq!(graph.nodes.len(), 4, "{}", graph.to_dot());
+        assert_eq!(graph.nodes[0].tasks, [sum_task]);
+        assert!(graph.nodes[0].name.contains("sum"));
+        assert_eq!(graph.nodes[0].inputs.len(), 1);
+        assert!(graph.nodes[0].inputs[0].contains('3'));
+        for kind in [GraphEdgeKind::Child, GraphEdgeKind::Cell] {
+            assert_eq!(
+                graph
+                    .edges
+                    .iter()
+                    .filter(|edge| edge.from == 0 && edge.kind == kind)
+                    .count(),
+                3,
+                "{}",
+                graph.to_dot()
+            );
+        }
+
+        let collapsed = tt_clone.export_graph(&GraphExportFilter {
+            roots: vec![sum_task],
+            collapse_by_function: true,
+            ..Default::default()
+        });
+        assert_eq!(collapsed.nodes.len(), 2, "{}", collapsed.to_dot());
+        assert_eq!(collapsed.nodes[1].tasks.len(), 3);
+        let calls = collapsed
+            .edges
+            .iter()
+            .find(|edge| edge.kind == GraphEdgeKind::Child)
+            .unwrap();
+        assert_eq!((calls.from, calls.to, calls.count), (0, 1, 3));
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+async fn sum(count: u32) -> Result<Vc<Number>> {
+    let mut sum = 0;
+    for i in 0..count {
+        sum += *number(i).await?;
+    }
+    Ok(Vc::cell(sum))
+}
+
+#[turbo_tasks::function]
+fn number(value: u32) -> Vc<Number> {
+    Vc::cell(value)
+}
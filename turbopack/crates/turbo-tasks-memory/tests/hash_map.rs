@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/hash_map.rs
\ No newline at end of file
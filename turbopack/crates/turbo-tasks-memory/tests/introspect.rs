@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/introspect.rs
\ No newline at end of file
@@ -0,0 +1,119 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{why_invalidated, InvalidationCause, State, TurboTasks, Vc};
+use turbo_tasks_hash::{DeterministicHasher, HashAlgorithm};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn records_invalidation_chain() {
+    REGISTRATION.ensure_registered();
+    let mut backend = MemoryBackend::default();
+    backend.track_invalidation_causes(true);
+    let tt = TurboTasks::new(backend);
+    tt.run_once(async move {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let output = outer(input);
+        assert_eq!(*output.strongly_consistent().await?, 2);
+        let outer_task = Vc::into_raw(output).get_task_id();
+        let inner_task = Vc::into_raw(inner(input)).get_task_id();
+        assert!(why_invalidated(outer_task).is_empty());
+
+        input.await?.state.set(2);
+        assert_eq!(*output.strongly_consistent().await?, 3);
+
+        let steps = why_invalidated(outer_task);
+        assert_eq!(steps.len(), 2, "{steps:?}");
+        assert_eq!(steps[0].task, outer_task);
+        let InvalidationCause::CellChanged {
+            task,
+            old_hash,
+            new_hash,
+            ..
+        } = steps[0].cause
+        else {
+            panic!("unexpected cause {}", steps[0].cause);
+        };
+        assert_eq!(task, inner_task);
+        assert!(old_hash.is_some());
+        assert_ne!(old_hash, new_hash);
+        assert_eq!(steps[1].task, inner_task);
+        assert_eq!(steps[1].cause, InvalidationCause::External { reason: None });
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn hashes_changed_cells_with_the_configured_algorithm() {
+    REGISTRATION.ensure_registered();
+    let mut backend = MemoryBackend::default();
+    backend.track_invalidation_causes(true);
+    backend.cell_hash_algorithm(HashAlgorithm::Blake3);
+    let tt = TurboTasks::new(backend);
+    tt.run_once(async move {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let output = outer(input);
+        assert_eq!(*output.strongly_consistent().await?, 2);
+        let outer_task = Vc::into_raw(output).get_task_id();
+
+        input.await?.state.set(2);
+        assert_eq!(*output.strongly_consistent().await?, 3);
+
+        let steps = why_invalidated(outer_task);
+        let InvalidationCause::CellChanged {
+            old_hash: Some(old_hash),
+            new_hash: Some(new_hash),
+            ..
+        } = steps[0].cause
+        else {
+            panic!("unexpected cause {}", steps[0].cause);
+        };
+        assert_eq!(
+            old_hash.as_bytes().len(),
+            HashAlgorithm::Blake3.digest_len()
+        );
+        // The hashes are computed from the JSON serialization of the cell content.
+        let json_hash = |json: &str| {
+            let mut hasher = HashAlgorithm::Blake3.hasher();
+            hasher.write_bytes(json.as_bytes());
+            hasher.finish_digest()
+        };
+        assert_eq!(old_hash, json_hash("1"));
+        assert_eq!(new_hash, json_hash("2"));
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::function]
+async fn inner(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let value = *input.await?.state.get();
+    Ok(Vc::cell(value))
+}
+
+#[turbo_tasks::function]
+async fn outer(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let value = *inner(input).await?;
+    Ok(Vc::cell(value + 1))
+}
@@ -0,0 +1,74 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{EstimateSize, TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn reports_tasks_by_retained_bytes() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let tt_clone = tt.clone();
+    tt.run_once(async move {
+        let large = lines(1000);
+        let small = lines(10);
+        large.await?;
+        small.await?;
+        let large_task = Vc::into_raw(large).get_task_id();
+        let small_task = Vc::into_raw(small).get_task_id();
+
+        let report = tt_clone.memory_report(usize::MAX);
+        let position = |task| {
+            report
+                .tasks
+                .iter()
+                .position(|(usage, _)| usage.task == task)
+                .unwrap()
+        };
+        assert!(position(large_task) < position(small_task), "{report}");
+
+        let (usage, description) = &report.tasks[position(large_task)];
+        assert_eq!(usage.cells, 1);
+        // 1000 lines of 10 bytes each, stored on the heap
+        assert!(usage.bytes >= 10_000, "{report}");
+        assert!(description.contains("lines"), "{description}");
+        assert!(report.total_bytes >= usage.bytes);
+
+        assert_eq!(tt_clone.memory_report(1).tasks.len(), 1);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn derives_estimate_size() {
+    let lines = Lines {
+        lines: vec!["0123456789".to_string(); 3],
+        count: 3,
+    };
+    assert_eq!(
+        lines.estimate_heap_size(),
+        3 * std::mem::size_of::<String>() + 3 * 10
+    );
+}
+
+#[turbo_tasks::value]
+struct Lines {
+    lines: Vec<String>,
+    count: u32,
+}
+
+#[turbo_tasks::function]
+fn lines(count: u32) -> Result<Vc<Lines>> {
+    Ok(Lines {
+        lines: (0..count).map(|i| format!("{i:010}")).collect(),
+        count,
+    }
+    .cell())
+}
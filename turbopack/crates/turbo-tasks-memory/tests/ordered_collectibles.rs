@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/ordered_collectibles.rs
\ No newline at end of file
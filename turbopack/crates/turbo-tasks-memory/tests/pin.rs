@@ -0,0 +1,119 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::{GcPolicy, ResolvedVc, State, TurboTasks, Vc, WeakVc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+/// The input last seen by [double].
+static DOUBLED_INPUT: AtomicU32 = AtomicU32::new(0);
+
+#[tokio::test]
+async fn recomputes_pinned_tasks_without_reading_them() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let input = tt
+        .run_once({
+            let tt = tt.clone();
+            async move {
+                let input = ChangingInput {
+                    state: State::new(1),
+                }
+                .resolved_cell();
+                tt.pin_vc(double(*input));
+                Ok(input)
+            }
+        })
+        .await
+        .unwrap();
+    wait_for_doubled_input(&tt, 1).await;
+
+    tt.run_once(async move {
+        input.await?.state.set(2);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    // Nothing reads `double` anymore, the pin recomputes it.
+    wait_for_doubled_input(&tt, 2).await;
+}
+
+#[tokio::test]
+async fn keeps_pinned_tasks_on_garbage_collection() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::builder(MemoryBackend::default())
+        .gc_policy(GcPolicy {
+            max_idle_age: Some(Duration::ZERO),
+            ..Default::default()
+        })
+        .build();
+    let (collected, pinned) = tt
+        .run_once({
+            let tt = tt.clone();
+            async move {
+                tt.pin_vc(value(2));
+                let collected: WeakVc<Value> = ResolvedVc::downgrade(value(1).to_resolved().await?);
+                let pinned: WeakVc<Value> = ResolvedVc::downgrade(value(2).to_resolved().await?);
+                Ok((collected, pinned))
+            }
+        })
+        .await
+        .unwrap();
+
+    // Garbage collection runs in the background when turbo-tasks becomes idle.
+    let mut alive = (true, true);
+    for _ in 0..100 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        tt.wait_background_done().await;
+        alive = tt
+            .run_once(
+                async move { Ok((collected.upgrade().is_some(), pinned.upgrade().is_some())) },
+            )
+            .await
+            .unwrap();
+        if !alive.0 {
+            break;
+        }
+    }
+    assert_eq!(alive, (false, true));
+}
+
+async fn wait_for_doubled_input(tt: &TurboTasks<MemoryBackend>, expected: u32) {
+    for _ in 0..100 {
+        tt.wait_background_done().await;
+        if DOUBLED_INPUT.load(Ordering::SeqCst) == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("the pinned task wasn't recomputed for the input {expected}");
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::function]
+async fn double(input: Vc<ChangingInput>) -> Result<Vc<Value>> {
+    let input = *input.await?.state.get();
+    DOUBLED_INPUT.store(input, Ordering::SeqCst);
+    Ok(Vc::cell(input * 2))
+}
+
+#[turbo_tasks::function]
+fn value(value: u32) -> Vc<Value> {
+    Vc::cell(value)
+}
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/prefetch.rs
\ No newline at end of file
@@ -0,0 +1,40 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{ProgressReporter, TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn reports_progress_to_subscribers() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    let mut subscription = tt.subscribe_progress(None);
+    tt.run_once(async move {
+        assert_eq!(*count_to(3).await?, 3);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    for i in 1..=3 {
+        let event = subscription.recv().await;
+        assert_eq!(event.progress.completed, i);
+        assert_eq!(event.progress.total, Some(3));
+    }
+    let event = subscription.recv().await;
+    assert_eq!(event.progress.message.as_deref(), Some("done"));
+}
+
+#[turbo_tasks::function]
+fn count_to(total: u64, progress: ProgressReporter) -> Result<Vc<u64>> {
+    for i in 1..=total {
+        progress.report(i, Some(total));
+    }
+    progress.report_with_message(total, Some(total), "done");
+    Ok(Vc::cell(total))
+}
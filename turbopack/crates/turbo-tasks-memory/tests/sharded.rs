@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/sharded.rs
\ No newline at end of file
@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/state_transaction.rs
\ No newline at end of file
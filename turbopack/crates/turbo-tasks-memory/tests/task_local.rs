@@ -0,0 +1 @@
+../../turbo-tasks-testing/tests/task_local.rs
\ No newline at end of file
@@ -0,0 +1,39 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::time::Duration;
+
+use turbo_tasks::{TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn returns_once_no_task_ran_for_the_timeout() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        assert_eq!(*double(21).await?, 42);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert!(tt.wait_idle(Duration::from_millis(10)).await);
+}
+
+#[tokio::test]
+async fn returns_false_once_stopped() {
+    REGISTRATION.ensure_registered();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.stop_and_wait().await;
+
+    assert!(!tt.wait_idle(Duration::from_secs(60)).await);
+}
+
+#[turbo_tasks::function]
+fn double(value: u32) -> Vc<u32> {
+    Vc::cell(value * 2)
+}
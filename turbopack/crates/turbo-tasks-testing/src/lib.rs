@@ -9,19 +9,23 @@ use std::{
     future::Future,
     mem::replace,
     panic::AssertUnwindSafe,
+    path::Path,
     sync::{Arc, Mutex, Weak},
 };
 
 use anyhow::{anyhow, Result};
 use futures::FutureExt;
+use tokio::sync::Semaphore;
 use turbo_tasks::{
     backend::{CellContent, TaskCollectiblesMap, TypedCellContent},
     event::{Event, EventListener},
     registry,
+    task::NativeTaskFuture,
     test_helpers::with_turbo_tasks_for_testing,
     util::{SharedError, StaticOrArc},
-    CellId, ExecutionId, InvalidationReason, LocalTaskId, MagicAny, RawVc, ReadConsistency, TaskId,
-    TaskPersistence, TraitTypeId, TurboTasksApi, TurboTasksCallApi,
+    CellId, CollectiblePosition, Durability, EventBus, ExecutionId, InvalidationReason,
+    InvalidationStep, LocalTaskId, MagicAny, Progress, RawVc, ReadConsistency, TaskId,
+    TaskPersistence, TraitTypeId, TurboTasksApi, TurboTasksCallApi, WaitingTask,
 };
 
 pub use crate::run::{run, run_with_tt, run_without_cache_check, Registration};
@@ -36,6 +40,7 @@ pub struct VcStorage {
     this: Weak<Self>,
     cells: Mutex<HashMap<(TaskId, CellId), CellContent>>,
     tasks: Mutex<Vec<Task>>,
+    events: EventBus,
 }
 
 impl VcStorage {
@@ -45,9 +50,12 @@ impl VcStorage {
         this_arg: Option<RawVc>,
         arg: Box<dyn MagicAny>,
     ) -> RawVc {
+        self.spawn(registry::get_function(func).execute(this_arg, &*arg))
+    }
+
+    fn spawn(&self, future: NativeTaskFuture) -> RawVc {
         let this = self.this.upgrade().unwrap();
         let handle = tokio::runtime::Handle::current();
-        let future = registry::get_function(func).execute(this_arg, &*arg);
         let i = {
             let mut tasks = self.tasks.lock().unwrap();
             let i = tasks.len();
@@ -137,6 +145,10 @@ impl TurboTasksCallApi for VcStorage {
         unreachable!()
     }
 
+    fn run_local(&self, future: NativeTaskFuture) -> RawVc {
+        self.spawn(future)
+    }
+
     fn run_once(
         &self,
         _future: std::pin::Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
@@ -177,6 +189,18 @@ impl TurboTasksApi for VcStorage {
         unreachable!()
     }
 
+    fn invalidate_many(&self, _tasks: &[TaskId]) {
+        unreachable!()
+    }
+
+    fn invalidate_many_with_reason(
+        &self,
+        _tasks: &[TaskId],
+        _reason: turbo_tasks::util::StaticOrArc<dyn turbo_tasks::InvalidationReason>,
+    ) {
+        unreachable!()
+    }
+
     fn invalidate_serialization(&self, _task: TaskId) {
         // ingore
     }
@@ -268,6 +292,18 @@ impl TurboTasksApi for VcStorage {
         unimplemented!()
     }
 
+    fn emit_ordered_collectible(&self, _trait_type: turbo_tasks::TraitTypeId, _collectible: RawVc) {
+        unimplemented!()
+    }
+
+    fn collectible_position(
+        &self,
+        _trait_type: TraitTypeId,
+        _collectible: RawVc,
+    ) -> Option<CollectiblePosition> {
+        None
+    }
+
     fn unemit_collectible(
         &self,
         _trait_type: turbo_tasks::TraitTypeId,
@@ -299,6 +335,46 @@ impl TurboTasksApi for VcStorage {
         .into_typed(index.type_id))
     }
 
+    fn is_task_cell_alive(&self, task: TaskId, index: CellId) -> bool {
+        self.cells.lock().unwrap().contains_key(&(task, index))
+    }
+
+    fn cancel_task(&self, _task: TaskId) {
+        // no-op
+    }
+
+    fn why_invalidated(&self, _task: TaskId) -> Vec<InvalidationStep> {
+        Vec::new()
+    }
+
+    fn wait_stack(&self, _task: TaskId) -> Vec<WaitingTask> {
+        Vec::new()
+    }
+
+    fn task_stable_hash(&self, _task: TaskId) -> Option<u64> {
+        None
+    }
+
+    fn parallelism_limit(&self, _name: &str) -> Option<Arc<Semaphore>> {
+        None
+    }
+
+    fn report_progress(&self, _task: TaskId, _progress: Progress) {
+        // no-op
+    }
+
+    fn event_bus(&self) -> &EventBus {
+        &self.events
+    }
+
+    fn output_write_started(&self, _path: &Path) {
+        // no-op
+    }
+
+    fn output_write_finished(&self, _path: &Path) {
+        // no-op
+    }
+
     fn update_own_task_cell(&self, task: TaskId, index: CellId, content: CellContent) {
         let mut map = self.cells.lock().unwrap();
         let cell = map.entry((task, index)).or_default();
@@ -317,6 +393,14 @@ impl TurboTasksApi for VcStorage {
         // no-op
     }
 
+    fn mark_own_task_durability(&self, _task: TaskId, _durability: Durability) {
+        // no-op
+    }
+
+    fn prefetch_task(&self, _task: TaskId) {
+        // no-op
+    }
+
     fn detached_for_testing(
         &self,
         _f: std::pin::Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
@@ -0,0 +1,84 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{
+    fmt::{self, Display},
+    mem::take,
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use turbo_tasks::{
+    get_invalidator, invalidate_all, invalidate_all_with_reason, InvalidationReason, Invalidator,
+    Vc,
+};
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static INVALIDATORS: Mutex<Vec<Invalidator>> = Mutex::new(Vec::new());
+static EXECUTIONS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+fn executions(file: u32) -> usize {
+    EXECUTIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|&&executed| executed == file)
+        .count()
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct SubtreeChange;
+
+impl Display for SubtreeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "subtree changed")
+    }
+}
+
+impl InvalidationReason for SubtreeChange {}
+
+#[tokio::test]
+async fn invalidates_all_tasks_at_once() {
+    run_without_cache_check(&REGISTRATION, async {
+        assert_eq!(*subtree(3).strongly_consistent().await?, 6);
+        for file in 1..=3 {
+            assert_eq!(executions(file), 1);
+        }
+
+        invalidate_all(take(&mut *INVALIDATORS.lock().unwrap()));
+        assert_eq!(*subtree(3).strongly_consistent().await?, 6);
+        for file in 1..=3 {
+            assert_eq!(executions(file), 2);
+        }
+
+        invalidate_all_with_reason(take(&mut *INVALIDATORS.lock().unwrap()), SubtreeChange);
+        assert_eq!(*subtree(3).strongly_consistent().await?, 6);
+        for file in 1..=3 {
+            assert_eq!(executions(file), 3);
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+/// Reads `count` files.
+#[turbo_tasks::function]
+async fn subtree(count: u32) -> Result<Vc<u32>> {
+    let mut sum = 0;
+    for file in 1..=count {
+        sum += *file_content(file).await?;
+    }
+    Ok(Vc::cell(sum))
+}
+
+#[turbo_tasks::function]
+fn file_content(file: u32) -> Vc<u32> {
+    INVALIDATORS.lock().unwrap().push(get_invalidator());
+    EXECUTIONS.lock().unwrap().push(file);
+    Vc::cell(file)
+}
@@ -0,0 +1,41 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use turbo_tasks::{
+    check_canceled, is_canceled, spawn_blocking, test_helpers::current_task_for_testing,
+    turbo_tasks, Canceled, Vc,
+};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static CANCELED: AtomicBool = AtomicBool::new(false);
+
+#[tokio::test]
+async fn cancel_task() {
+    run(&REGISTRATION, || async {
+        // The canceled execution is invalidated, so the result isn't cached.
+        let output = cancels_itself().strongly_consistent().await?;
+        assert_eq!(*output, 42);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn cancels_itself() -> Result<Vc<u32>> {
+    assert!(!is_canceled());
+    if !CANCELED.swap(true, Ordering::SeqCst) {
+        turbo_tasks().cancel_task(current_task_for_testing());
+        assert!(is_canceled());
+        assert!(spawn_blocking(is_canceled).await);
+        let err = check_canceled().unwrap_err();
+        assert!(err.is::<Canceled>());
+    }
+    Ok(Vc::cell(42))
+}
@@ -0,0 +1,43 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn cycle_detection() {
+    run(&REGISTRATION, || async {
+        let error = ping(1).strongly_consistent().await.unwrap_err();
+        let message = format!("{error:?}");
+        assert!(message.contains("Cycle detected"), "{message}");
+        assert!(message.contains("ping @ "), "{message}");
+        assert!(message.contains("-> pong @ "), "{message}");
+        assert!(message.contains("cycle_detection.rs:"), "{message}");
+
+        let error = recurse(1).strongly_consistent().await.unwrap_err();
+        let message = format!("{error:?}");
+        assert!(message.contains("Cycle detected"), "{message}");
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn ping(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*pong(n).await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn pong(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*ping(n).await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn recurse(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*recurse(n).await? + 1))
+}
@@ -0,0 +1,79 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{on_execution_completed, Vc};
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static DURATIONS: Mutex<Vec<(RcStr, Duration)>> = Mutex::new(Vec::new());
+
+fn durations(name: &str) -> Vec<Duration> {
+    DURATIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(task, _)| task == name)
+        .map(|(_, duration)| *duration)
+        .collect()
+}
+
+#[tokio::test]
+async fn reports_the_time_tasks_are_running() {
+    run_without_cache_check(&REGISTRATION, async {
+        let start = Instant::now();
+        waiting("waiting".into()).await?;
+        let waited = start.elapsed();
+        busy("busy".into()).await?;
+
+        // Waiting for another task isn't part of the execution.
+        let waiting = durations("waiting");
+        assert_eq!(waiting.len(), 1);
+        assert!(waited >= Duration::from_millis(100));
+        assert!(waiting[0] < Duration::from_millis(50), "{waiting:?}");
+
+        let busy = durations("busy");
+        assert_eq!(busy.len(), 1);
+        assert!(busy[0] >= Duration::from_millis(20), "{busy:?}");
+
+        // Cached results aren't executed again.
+        busy("busy".into()).await?;
+        assert_eq!(durations("busy").len(), 1);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+fn record(name: RcStr) {
+    on_execution_completed(move |duration| DURATIONS.lock().unwrap().push((name, duration)));
+}
+
+#[turbo_tasks::function]
+async fn waiting(name: RcStr) -> Result<Vc<()>> {
+    record(name);
+    sleeping().await?;
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn sleeping() -> Result<Vc<()>> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+fn busy(name: RcStr) -> Vc<()> {
+    record(name);
+    std::thread::sleep(Duration::from_millis(20));
+    Vc::cell(())
+}
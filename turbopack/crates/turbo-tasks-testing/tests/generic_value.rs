@@ -0,0 +1,71 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Vc, VcValueType};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[turbo_tasks::value]
+struct Pair<T: VcValueType> {
+    a: ResolvedVc<T>,
+    b: ResolvedVc<T>,
+}
+
+#[turbo_tasks::value(transparent)]
+struct Wrapper<T: VcValueType>(Vec<ResolvedVc<T>>);
+
+#[turbo_tasks::function]
+async fn int_pair(a: u32, b: u32) -> Result<Vc<Pair<u32>>> {
+    Ok(Pair {
+        a: ResolvedVc::cell(a),
+        b: ResolvedVc::cell(b),
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+async fn string_pair(a: RcStr, b: RcStr) -> Result<Vc<Pair<RcStr>>> {
+    Ok(Pair {
+        a: ResolvedVc::cell(a),
+        b: ResolvedVc::cell(b),
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+async fn sum(pair: Vc<Pair<u32>>) -> Result<Vc<u32>> {
+    let pair = pair.await?;
+    Ok(Vc::cell(*pair.a.await? + *pair.b.await?))
+}
+
+#[tokio::test]
+async fn instantiations() {
+    run(&REGISTRATION, || async {
+        let ints = int_pair(1, 2);
+        assert_eq!(*sum(ints).await?, 3);
+
+        let strings = string_pair("a".into(), "b".into()).await?;
+        assert_eq!(*strings.a.await?, RcStr::from("a"));
+        assert_eq!(*strings.b.await?, RcStr::from("b"));
+
+        // Every instantiation is a value type of its own.
+        assert_ne!(
+            Pair::<u32>::get_value_type_id(),
+            Pair::<RcStr>::get_value_type_id()
+        );
+        assert_eq!(
+            Pair::<u32>::get_value_type_id(),
+            Pair::<u32>::get_value_type_id()
+        );
+
+        let wrapper: Vc<Wrapper<u32>> = Vc::cell(vec![ResolvedVc::cell(4)]);
+        assert_eq!(*wrapper.await?[0].await?, 4);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
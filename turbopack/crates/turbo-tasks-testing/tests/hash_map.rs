@@ -0,0 +1,107 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{State, Vc, VcHashMap};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn hash_map_keys() {
+    run(&REGISTRATION, || async {
+        let input = Input {
+            state: State::new(vec![("a".into(), 1), ("b".into(), 2)]),
+            map: VcHashMap::new(),
+        }
+        .cell();
+        let input_val = input.await?;
+
+        let produced = produce(input);
+        let a = read_key(input, "a".into());
+        let c = read_key(input, "c".into());
+        let count = key_count(input);
+        produced.strongly_consistent().await?;
+        let a_read = a.strongly_consistent().await?;
+        assert_eq!(a_read.value, Some(1));
+        let c_read = c.strongly_consistent().await?;
+        assert_eq!(c_read.value, None);
+        let count_read = count.strongly_consistent().await?;
+        assert_eq!(count_read.value, Some(2));
+
+        println!("changing b");
+        input_val.state.set(vec![("a".into(), 1), ("b".into(), 3)]);
+        produced.strongly_consistent().await?;
+        let read = a.strongly_consistent().await?;
+        assert_eq!(read.random_value, a_read.random_value);
+        let read = c.strongly_consistent().await?;
+        assert_eq!(read.random_value, c_read.random_value);
+        let read = count.strongly_consistent().await?;
+        assert_eq!(read.random_value, count_read.random_value);
+
+        println!("adding c");
+        input_val
+            .state
+            .set(vec![("a".into(), 1), ("b".into(), 3), ("c".into(), 4)]);
+        produced.strongly_consistent().await?;
+        let read = a.strongly_consistent().await?;
+        assert_eq!(read.random_value, a_read.random_value);
+        let read = c.strongly_consistent().await?;
+        assert_eq!(read.value, Some(4));
+        let read = count.strongly_consistent().await?;
+        assert_eq!(read.value, Some(3));
+
+        println!("removing a");
+        input_val.state.set(vec![("b".into(), 3), ("c".into(), 4)]);
+        produced.strongly_consistent().await?;
+        let read = a.strongly_consistent().await?;
+        assert_eq!(read.value, None);
+        let read = count.strongly_consistent().await?;
+        assert_eq!(read.value, Some(2));
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct Input {
+    state: State<Vec<(RcStr, u32)>>,
+    map: VcHashMap<RcStr, u32>,
+}
+
+#[turbo_tasks::value]
+struct Output {
+    value: Option<u32>,
+    random_value: u32,
+}
+
+#[turbo_tasks::function]
+async fn produce(input: Vc<Input>) -> Result<Vc<()>> {
+    let input = input.await?;
+    input.map.replace(input.state.get().iter().cloned());
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn read_key(input: Vc<Input>, key: RcStr) -> Result<Vc<Output>> {
+    let input = input.await?;
+    Ok(Output {
+        value: input.map.get(&key),
+        random_value: rand::random(),
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+async fn key_count(input: Vc<Input>) -> Result<Vc<Output>> {
+    let input = input.await?;
+    Ok(Output {
+        value: Some(input.map.keys().len() as u32),
+        random_value: rand::random(),
+    }
+    .cell())
+}
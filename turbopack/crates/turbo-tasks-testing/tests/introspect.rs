@@ -0,0 +1,52 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{introspect::introspect, ResolvedVc, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn introspect_cells() {
+    run(&REGISTRATION, || async {
+        let tree = tree();
+        let root = introspect(Vc::into_raw(tree)).await?;
+        assert_eq!(root.display_name, "Node");
+        assert!(root.value_type.ends_with("Node"), "{}", root.value_type);
+        assert!(root.estimated_size > 0);
+        assert_eq!(root.children.len(), 2);
+
+        let leaf = introspect(root.children[0]).await?;
+        assert_eq!(leaf.display_name, "Node");
+        assert!(leaf.children.is_empty());
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct Node {
+    value: u32,
+    children: Vec<ResolvedVc<Node>>,
+}
+
+#[turbo_tasks::function]
+fn leaf(value: u32) -> Vc<Node> {
+    Node {
+        value,
+        children: vec![],
+    }
+    .cell()
+}
+
+#[turbo_tasks::function]
+async fn tree() -> Result<Vc<Node>> {
+    Ok(Node {
+        value: 0,
+        children: vec![leaf(1).to_resolved().await?, leaf(2).to_resolved().await?],
+    }
+    .cell())
+}
@@ -40,7 +40,6 @@ async fn returns_resolved_local_vc() -> Vc<u32> {
     cell.resolve().await.unwrap()
 }
 
-#[ignore]
 #[tokio::test]
 async fn test_return_resolved() -> Result<()> {
     run(&REGISTRATION, || async {
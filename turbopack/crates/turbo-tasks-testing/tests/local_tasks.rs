@@ -0,0 +1,96 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::{bail, Result};
+use turbo_tasks::{run_local, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn uncached_reads_are_tracked() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let output = add_one_to_double(input);
+        assert_eq!(*output.await?, 3);
+
+        input.await?.state.set(2);
+        assert_eq!(*output.strongly_consistent().await?, 5);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn uncached_errors() {
+    run(&REGISTRATION, || async {
+        let error = fails().await.unwrap_err();
+        assert!(error.to_string().contains("uncached failure"), "{error}");
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn run_local_output() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(20),
+        }
+        .cell();
+        let output = add_local(input);
+        assert_eq!(*output.await?, 42);
+
+        input.await?.state.set(40);
+        assert_eq!(*output.strongly_consistent().await?, 62);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::function(uncached)]
+async fn double(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let value = *input.await?.state.get();
+    Ok(Vc::cell(value * 2))
+}
+
+#[turbo_tasks::function]
+async fn add_one_to_double(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let doubled = double(input);
+    assert!(doubled.is_local());
+    Ok(Vc::cell(*doubled.await? + 1))
+}
+
+#[turbo_tasks::function(local_cells, uncached)]
+async fn fail_uncached() -> Result<Vc<u32>> {
+    bail!("uncached failure")
+}
+
+#[turbo_tasks::function]
+async fn fails() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*fail_uncached().await?))
+}
+
+#[turbo_tasks::function]
+async fn add_local(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let sum = run_local(async move {
+        let value = *input.await?.state.get();
+        Ok(Vc::<u32>::cell(value + 2))
+    });
+    Ok(Vc::cell(*sum.await? + 20))
+}
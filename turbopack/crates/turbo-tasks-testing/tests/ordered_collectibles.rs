@@ -0,0 +1,100 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    emit, emit_ordered, filter_collectibles, CollectiblesSource, TryJoinIterExt, ValueToString, Vc,
+};
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+// Positions aren't restored from a persistent cache, so these tests only run once.
+
+#[tokio::test]
+async fn emission_order() {
+    run_without_cache_check(&REGISTRATION, async {
+        let result = emit_in_order();
+        result.strongly_consistent().await?;
+        let collectibles = result.peek_collectibles_ordered::<Box<dyn ValueToString>>();
+        assert_eq!(
+            to_strings(collectibles).await?,
+            ["a3", "a1", "a2", "b3", "b1", "b2", "unordered"]
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn filtered() {
+    run_without_cache_check(&REGISTRATION, async {
+        let result = emit_in_order();
+        result.strongly_consistent().await?;
+        let collectibles = filter_collectibles(
+            result.take_collectibles_ordered::<Box<dyn ValueToString>>(),
+            |collectible| async move { Ok(collectible.to_string().await?.ends_with('1')) },
+        )
+        .await?;
+        assert_eq!(
+            to_strings(collectibles.into_iter().map(|c| *c)).await?,
+            ["a1", "b1"]
+        );
+        assert!(result
+            .peek_collectibles_ordered::<Box<dyn ValueToString>>()
+            .is_empty());
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+async fn to_strings(
+    collectibles: impl IntoIterator<Item = Vc<Box<dyn ValueToString>>>,
+) -> Result<Vec<String>> {
+    collectibles
+        .into_iter()
+        .map(|collectible| async move { Ok(collectible.to_string().await?.to_string()) })
+        .try_join()
+        .await
+}
+
+#[turbo_tasks::function]
+async fn emit_in_order() -> Result<()> {
+    emit(Vc::upcast::<Box<dyn ValueToString>>(Thing::new(
+        "unordered",
+    )));
+    emit_values("a".into()).await?;
+    emit_values("b".into()).await?;
+    Ok(())
+}
+
+#[turbo_tasks::function]
+async fn emit_values(prefix: RcStr) -> Result<()> {
+    for value in [3, 1, 2] {
+        emit_ordered(Vc::upcast::<Box<dyn ValueToString>>(Thing::new(&format!(
+            "{prefix}{value}"
+        ))));
+    }
+    Ok(())
+}
+
+#[turbo_tasks::value(shared)]
+struct Thing(RcStr);
+
+impl Thing {
+    fn new(value: &str) -> Vc<Self> {
+        Self::cell(Thing(value.into()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for Thing {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell(self.0.clone())
+    }
+}
@@ -0,0 +1,62 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{sync::Mutex, time::Duration};
+
+use turbo_tasks::{prefetch, Vc};
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static EXECUTIONS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+fn executions(value: u32) -> usize {
+    EXECUTIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|&&executed| executed == value)
+        .count()
+}
+
+/// Waits until `value` was computed `count` times, or fails after a second.
+async fn wait_for_executions(value: u32, count: usize) {
+    for _ in 0..100 {
+        if executions(value) >= count {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(executions(value), count);
+}
+
+#[tokio::test]
+async fn prefetched_tasks_are_computed_without_being_read() {
+    run_without_cache_check(&REGISTRATION, async {
+        // The task is computed even though nothing reads it.
+        prefetch([computed(1)]);
+        wait_for_executions(1, 1).await;
+
+        // Reading it afterwards uses the prefetched result.
+        assert_eq!(*computed(1).await?, 1);
+        assert_eq!(executions(1), 1);
+
+        // Tasks that are computed already aren't computed again.
+        assert_eq!(*computed(2).await?, 2);
+        prefetch([computed(1), computed(2)]);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(executions(1), 1);
+        assert_eq!(executions(2), 1);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+fn computed(value: u32) -> Vc<u32> {
+    EXECUTIONS.lock().unwrap().push(value);
+    Vc::cell(value)
+}
@@ -0,0 +1,50 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{current_priority, with_priority, TaskPriority, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn priorities() {
+    run(&REGISTRATION, || async {
+        // Tasks inherit the priority of the function and of the scope which scheduled them.
+        assert_eq!(*urgent(1).await?, TaskPriority::High as u32);
+        assert_eq!(*normal(2).await?, TaskPriority::Normal as u32);
+        let low = with_priority(TaskPriority::Low, async { normal(3).await }).await?;
+        assert_eq!(*low, TaskPriority::Low as u32);
+
+        // Low-priority tasks which high-priority tasks wait for don't block them.
+        let speculative = with_priority(TaskPriority::Low, async { sum(100).await }).await?;
+        assert_eq!(*urgent_sum(100).await?, *speculative);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function(priority = high)]
+fn urgent(_n: u32) -> Vc<u32> {
+    Vc::cell(current_priority() as u32)
+}
+
+#[turbo_tasks::function]
+fn normal(_n: u32) -> Vc<u32> {
+    Vc::cell(current_priority() as u32)
+}
+
+#[turbo_tasks::function]
+async fn sum(n: u32) -> Result<Vc<u32>> {
+    if n == 0 {
+        return Ok(Vc::cell(0));
+    }
+    Ok(Vc::cell(*sum(n - 1).await? + n))
+}
+
+#[turbo_tasks::function(priority = high)]
+async fn urgent_sum(n: u32) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*sum(n).await?))
+}
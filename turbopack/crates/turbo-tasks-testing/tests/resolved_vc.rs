@@ -3,7 +3,8 @@
 #![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
 
 use anyhow::Result;
-use turbo_tasks::{ReadRef, ResolvedVc, Vc};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ReadRef, ResolvedVc, Vc, WeakVc};
 use turbo_tasks_testing::{register, run, Registration};
 
 static REGISTRATION: Registration = register!();
@@ -11,6 +12,12 @@ static REGISTRATION: Registration = register!();
 #[turbo_tasks::value]
 struct Wrapper(u32);
 
+#[turbo_tasks::value]
+struct Pair {
+    left: u32,
+    right: u32,
+}
+
 #[turbo_tasks::function]
 fn returns_int(value: u32) -> Vc<u32> {
     Vc::cell(value)
@@ -77,3 +84,78 @@ async fn test_into_future() -> Result<()> {
     })
     .await
 }
+
+#[tokio::test]
+async fn test_try_map_cell() -> Result<()> {
+    run(&REGISTRATION, || async {
+        let pair = Pair { left: 1, right: 2 }.resolved_cell();
+        let right: ResolvedVc<u32> = ResolvedVc::try_map_cell(pair, |pair| &pair.right).await?;
+        assert!(right.is_resolved());
+        assert_eq!(*right.await?, 2);
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_weak_vc_upgrade() -> Result<()> {
+    run(&REGISTRATION, || async {
+        let resolved: ResolvedVc<u32> = returns_int(42).to_resolved().await?;
+        let weak: WeakVc<u32> = ResolvedVc::downgrade(resolved);
+        assert_eq!(weak.upgrade(), Some(resolved));
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_resolve_all() -> Result<()> {
+    run(&REGISTRATION, || async {
+        let unresolved: Vec<Vc<u32>> = (0..10).map(returns_int).collect();
+        let resolved = ResolvedVc::resolve_all(unresolved).await?;
+        assert_eq!(resolved.len(), 10);
+        for (i, vc) in resolved.into_iter().enumerate() {
+            assert!(vc.is_resolved());
+            assert_eq!(*vc.await?, i as u32);
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_cell_eq() -> Result<()> {
+    run(&REGISTRATION, || async {
+        let a = returns_int(1).to_resolved().await?;
+        let b = ResolvedVc::cell(1);
+        let c = ResolvedVc::cell(2);
+        assert_ne!(a, b);
+        assert!(ResolvedVc::cell_eq(a, a).await?);
+        assert!(ResolvedVc::cell_eq(a, b).await?);
+        assert!(!ResolvedVc::cell_eq(a, c).await?);
+        Ok(())
+    })
+    .await
+}
+
+#[turbo_tasks::function]
+async fn int_ident(vc: ResolvedVc<u32>) -> Result<Vc<RcStr>> {
+    // Inverted, so that the order differs from the creation order.
+    Ok(Vc::cell(format!("{}", 9 - *vc.await?).into()))
+}
+
+#[tokio::test]
+async fn test_sort_and_dedup() -> Result<()> {
+    run(&REGISTRATION, || async {
+        let a = returns_int(1).to_resolved().await?;
+        let b = returns_int(2).to_resolved().await?;
+        let c = ResolvedVc::cell(2);
+        let mut vcs = ResolvedVc::dedup_resolved([a, b, a, c, b]);
+        assert_eq!(vcs, vec![a, b, c]);
+        ResolvedVc::sort_by_ident(&mut vcs, int_ident).await?;
+        // `b` and `c` have the same ident and keep their order.
+        assert_eq!(vcs, vec![b, c, a]);
+        Ok(())
+    })
+    .await
+}
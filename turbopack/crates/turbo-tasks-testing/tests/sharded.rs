@@ -0,0 +1,77 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{sharded::Sharded, FxIndexMap, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn only_changed_shards_invalidate() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let numbers = numbers(input);
+        assert!(numbers.await?.shards().len() > 1);
+
+        let read = first_shard_sum(numbers).await?;
+        assert_eq!(read.sum, (0..read.len).sum::<u32>());
+        let random_value = read.random_value;
+
+        input.await?.state.set(2);
+        numbers.strongly_consistent().await?;
+        let all = Sharded::read(numbers).await?;
+        assert_eq!(all.len(), 1000);
+        assert_eq!(all[&999], 2);
+        assert_eq!(
+            all.keys().copied().collect::<Vec<_>>(),
+            (0..1000).collect::<Vec<_>>()
+        );
+
+        // The first shard didn't change, so it isn't read again.
+        let read = first_shard_sum(numbers).strongly_consistent().await?;
+        assert_eq!(read.random_value, random_value);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value(transparent, sharded)]
+struct Numbers(FxIndexMap<u32, u32>);
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::value]
+struct ShardSum {
+    len: u32,
+    sum: u32,
+    random_value: u32,
+}
+
+#[turbo_tasks::function]
+async fn numbers(input: Vc<ChangingInput>) -> Result<Vc<Sharded<Numbers>>> {
+    let last = *input.await?.state.get();
+    let mut numbers = (0..1000).map(|i| (i, i)).collect::<FxIndexMap<_, _>>();
+    numbers.insert(999, last);
+    Ok(Sharded::with_shard_size(numbers, 16))
+}
+
+#[turbo_tasks::function]
+async fn first_shard_sum(numbers: Vc<Sharded<Numbers>>) -> Result<Vc<ShardSum>> {
+    let first = numbers.await?.shards()[0].await?;
+    Ok(ShardSum {
+        len: first.len() as u32,
+        sum: first.values().sum(),
+        random_value: rand::random(),
+    }
+    .cell())
+}
@@ -0,0 +1,90 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{run_once, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn stable_hash() {
+    run(&REGISTRATION, || async {
+        let one = Vc::into_raw(double(1)).get_task_id().stable_hash();
+        let two = Vc::into_raw(double(2)).get_task_id().stable_hash();
+        let triple_one = Vc::into_raw(triple(1)).get_task_id().stable_hash();
+        assert!(one.is_some());
+        assert_ne!(one, two);
+        assert_ne!(one, triple_one);
+        assert_eq!(one, Vc::into_raw(double(1)).get_task_id().stable_hash());
+
+        // Vc inputs are hashed by the task they belong to.
+        let nested = Vc::into_raw(double_vc(double(1)))
+            .get_task_id()
+            .stable_hash();
+        assert!(nested.is_some());
+        assert_eq!(
+            nested,
+            Vc::into_raw(double_vc(double(1)))
+                .get_task_id()
+                .stable_hash()
+        );
+        assert_ne!(
+            nested,
+            Vc::into_raw(double_vc(double(2)))
+                .get_task_id()
+                .stable_hash()
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn stable_hash_across_instances() {
+    REGISTRATION.ensure_registered();
+
+    let tt = REGISTRATION.create_turbo_tasks("stable_hash_across_instances_1", true);
+    let (first_id, first_hash) = run_once(tt.clone(), async {
+        let task_id = Vc::into_raw(double_vc(double(1))).get_task_id();
+        Ok((task_id, task_id.stable_hash()))
+    })
+    .await
+    .unwrap();
+    tt.stop_and_wait().await;
+
+    let tt = REGISTRATION.create_turbo_tasks("stable_hash_across_instances_2", true);
+    let (second_id, second_hash) = run_once(tt.clone(), async {
+        // Other tasks are created first, so the tasks get different ids than in the first
+        // instance.
+        for n in 0..10 {
+            triple(n).await?;
+        }
+        let task_id = Vc::into_raw(double_vc(double(1))).get_task_id();
+        Ok((task_id, task_id.stable_hash()))
+    })
+    .await
+    .unwrap();
+    tt.stop_and_wait().await;
+
+    assert_ne!(first_id, second_id);
+    assert!(first_hash.is_some());
+    assert_eq!(first_hash, second_hash);
+}
+
+#[turbo_tasks::function]
+fn double(n: u32) -> Vc<u32> {
+    Vc::cell(n * 2)
+}
+
+#[turbo_tasks::function]
+fn triple(n: u32) -> Vc<u32> {
+    Vc::cell(n * 3)
+}
+
+#[turbo_tasks::function]
+async fn double_vc(n: Vc<u32>) -> Result<Vc<u32>> {
+    Ok(Vc::cell(*n.await? * 2))
+}
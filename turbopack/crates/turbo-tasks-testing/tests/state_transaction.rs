@@ -0,0 +1,80 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{state_transaction, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn compare_and_set() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let input = input.await?;
+
+        let version = input.state.version();
+        input.state.set(2);
+        assert!(input.state.compare_and_set(version, 3).is_err());
+        assert_eq!(*input.state.get_untracked(), 2);
+
+        let version = input.state.get_untracked().version();
+        input.state.compare_and_set(version, 3)?;
+        assert_eq!(*input.state.get_untracked(), 3);
+
+        assert!(!input.state.replace_with(|_| None));
+        assert!(input.state.replace_with(|old| Some(old * 2)));
+        assert_eq!(*input.state.get_untracked(), 6);
+        assert_ne!(input.state.version(), version);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn transaction() {
+    run(&REGISTRATION, || async {
+        let a = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let b = ChangingInput {
+            state: State::new(10),
+        }
+        .cell();
+        let output = sum(a, b);
+        assert_eq!(*output.strongly_consistent().await?, 11);
+
+        state_transaction(async {
+            a.await?.state.set(2);
+            b.await?.state.set(20);
+            // The task isn't invalidated before the transaction ends.
+            assert_eq!(*output.strongly_consistent().await?, 11);
+            anyhow::Ok(())
+        })
+        .await?;
+        assert_eq!(*output.strongly_consistent().await?, 22);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::function]
+async fn sum(a: Vc<ChangingInput>, b: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    let a = *a.await?.state.get();
+    let b = *b.await?.state.get();
+    Ok(Vc::cell(a + b))
+}
@@ -0,0 +1,110 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_tasks::{State, Vc, VcStream};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+#[tokio::test]
+async fn stream_segments() {
+    run(&REGISTRATION, || async {
+        let input = Input {
+            state: State::new(vec![vec![1, 2], vec![3]]),
+            stream: VcStream::new(),
+        }
+        .cell();
+        let input_val = input.await?;
+
+        let first = first_segment_sum(input);
+        let all = sum_all(input);
+        let produced = produce(input);
+        produced.strongly_consistent().await?;
+        let first_read = first.strongly_consistent().await?;
+        assert_eq!(first_read.sum, 3);
+        let all_read = all.strongly_consistent().await?;
+        assert_eq!(all_read.sum, 6);
+
+        println!("changing the second segment");
+        input_val.state.set(vec![vec![1, 2], vec![3, 4]]);
+        produced.strongly_consistent().await?;
+        let read = first.strongly_consistent().await?;
+        assert_eq!(read.sum, 3);
+        assert_eq!(read.random_value, first_read.random_value);
+        let read = all.strongly_consistent().await?;
+        assert_eq!(read.sum, 10);
+        assert_ne!(read.random_value, all_read.random_value);
+        let all_read = read;
+
+        println!("removing the second segment");
+        input_val.state.set(vec![vec![1, 2]]);
+        produced.strongly_consistent().await?;
+        let read = first.strongly_consistent().await?;
+        assert_eq!(read.random_value, first_read.random_value);
+        let read = all.strongly_consistent().await?;
+        assert_eq!(read.sum, 3);
+        assert_ne!(read.random_value, all_read.random_value);
+
+        println!("changing the first segment");
+        input_val.state.set(vec![vec![5]]);
+        produced.strongly_consistent().await?;
+        let read = first.strongly_consistent().await?;
+        assert_eq!(read.sum, 5);
+        assert_ne!(read.random_value, first_read.random_value);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct Input {
+    state: State<Vec<Vec<u32>>>,
+    stream: VcStream<u32>,
+}
+
+#[turbo_tasks::value]
+struct Output {
+    sum: u32,
+    random_value: u32,
+}
+
+#[turbo_tasks::function]
+async fn produce(input: Vc<Input>) -> Result<Vc<()>> {
+    let input = input.await?;
+    input.stream.restart();
+    for segment in input.state.get().iter() {
+        input.stream.push(segment.clone());
+    }
+    input.stream.close();
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn first_segment_sum(input: Vc<Input>) -> Result<Vc<Output>> {
+    let input = input.await?;
+    let sum = input
+        .stream
+        .read_segment(0)
+        .await
+        .map_or(0, |segment| segment.iter().sum());
+    Ok(Output {
+        sum,
+        random_value: rand::random(),
+    }
+    .cell())
+}
+
+#[turbo_tasks::function]
+async fn sum_all(input: Vc<Input>) -> Result<Vc<Output>> {
+    let input = input.await?;
+    let sum = input.stream.read_all().await.into_iter().sum();
+    Ok(Output {
+        sum,
+        random_value: rand::random(),
+    }
+    .cell())
+}
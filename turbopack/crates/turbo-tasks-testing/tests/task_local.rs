@@ -0,0 +1,58 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TaskLocal, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static CONTEXT: TaskLocal<RcStr> = TaskLocal::new("CONTEXT");
+
+#[tokio::test]
+async fn task_local() {
+    run(&REGISTRATION, || async {
+        let values = outer().await?;
+        assert_eq!(values.in_task.as_deref(), Some("outer"));
+        assert_eq!(values.in_called_task, None);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct Values {
+    in_task: Option<RcStr>,
+    in_called_task: Option<RcStr>,
+}
+
+#[turbo_tasks::function]
+async fn outer() -> Result<Vc<Values>> {
+    assert_eq!(CONTEXT.get(), None);
+    CONTEXT.set("outer".into());
+    let in_task = nested().await;
+    let in_called_task = inner().await?.clone_value();
+    // Calling another task doesn't change the value.
+    assert_eq!(CONTEXT.get(), in_task);
+    Ok(Values {
+        in_task,
+        in_called_task,
+    }
+    .cell())
+}
+
+async fn nested() -> Option<RcStr> {
+    tokio::task::yield_now().await;
+    CONTEXT.get()
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionalContext(Option<RcStr>);
+
+#[turbo_tasks::function]
+fn inner() -> Vc<OptionalContext> {
+    Vc::cell(CONTEXT.get())
+}
@@ -0,0 +1,56 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::time::Duration;
+
+use anyhow::Result;
+use turbo_tasks::{with_timeout, TaskTimeout, Vc};
+use turbo_tasks_testing::{register, run_without_cache_check, Registration};
+
+static REGISTRATION: Registration = register!();
+
+// The result depends on timing, so this only runs once.
+
+#[tokio::test]
+async fn timeout_stack() {
+    run_without_cache_check(&REGISTRATION, async {
+        let error = with_timeout(Duration::from_millis(100), outer())
+            .await
+            .unwrap_err();
+        let timeout = error.downcast::<TaskTimeout>()?;
+        let stack = timeout
+            .stack
+            .iter()
+            .map(|task| task.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(stack.len(), 3, "{stack:?}");
+        assert!(stack[0].contains("outer"), "{stack:?}");
+        assert!(stack[1].contains("inner"), "{stack:?}");
+        assert!(stack[2].contains("hang"), "{stack:?}");
+        assert!(stack[2].contains("timeout.rs:"), "{stack:?}");
+
+        // The task keeps executing, so it can still be read.
+        let value = with_timeout(Duration::from_secs(10), outer()).await?;
+        assert_eq!(*value, 3);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::function]
+async fn outer() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*inner().await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn inner() -> Result<Vc<u32>> {
+    Ok(Vc::cell(*hang().await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn hang() -> Result<Vc<u32>> {
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    Ok(Vc::cell(1))
+}
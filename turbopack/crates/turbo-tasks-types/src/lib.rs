@@ -0,0 +1,172 @@
+//! The marker traits of turbo-tasks, without the runtime.
+//!
+//! Crates that only need to declare properties of their types, e.g. plugins
+//! implementing traits of turbopack, can depend on this crate instead of
+//! compiling all of turbo-tasks. `turbo_tasks` re-exports everything in here.
+//!
+//! By default only types of the standard library are covered. Implementations
+//! for types of other crates are behind features named after the crates.
+//!
+//! The `ResolvedValue` derive macro is re-exported with the `derive` feature.
+//! It implements `turbo_tasks::ResolvedValue` by default, so crates without a
+//! dependency on turbo-tasks have to point it at this crate:
+//!
+//! ```ignore
+//! #[derive(turbo_tasks_types::ResolvedValue)]
+//! #[turbo_tasks(crate = "turbo_tasks_types")]
+//! struct PluginOptions {
+//!     name: String,
+//! }
+//! ```
+//!
+//! `TraceRawVcs` and `TaskInput` stay in turbo-tasks: their methods take the
+//! `RawVc`s of the runtime and resolve `Vc`s through the task scheduler, so
+//! they can't be declared without it.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    ffi::{OsStr, OsString},
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+    },
+    ops::{Range, RangeInclusive},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{
+            AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicU16, AtomicU32, AtomicU64,
+            AtomicU8, AtomicUsize,
+        },
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+
+/// Indicates that a type does not contain any instances of `Vc`. It may
+/// contain `ResolvedVc`.
+///
+/// # Safety
+///
+/// This trait is marked as unsafe. You should not derive it yourself, but
+/// instead you should rely on `#[turbo_tasks::value(resolved)]` to do it for
+/// you.
+pub unsafe trait ResolvedValue {}
+
+#[cfg(feature = "derive")]
+pub use turbo_tasks_macros::ResolvedValue;
+
+macro_rules! impl_resolved {
+    ($ty:ty) => {
+        unsafe impl ResolvedValue for $ty {}
+    };
+
+    ($ty:ty, $($tys:ty),+) => {
+        impl_resolved!($ty);
+        impl_resolved!($($tys),+);
+    }
+}
+
+impl_resolved!(
+    i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64, char, bool, isize, usize
+);
+impl_resolved!(
+    NonZeroI8,
+    NonZeroU8,
+    NonZeroI16,
+    NonZeroU16,
+    NonZeroI32,
+    NonZeroU32,
+    NonZeroI64,
+    NonZeroU64,
+    NonZeroI128,
+    NonZeroU128,
+    NonZeroIsize,
+    NonZeroUsize
+);
+impl_resolved!(
+    AtomicI8,
+    AtomicU8,
+    AtomicI16,
+    AtomicU16,
+    AtomicI32,
+    AtomicU32,
+    AtomicI64,
+    AtomicU64,
+    AtomicBool,
+    AtomicUsize
+);
+impl_resolved!((), str, String, Duration, SystemTime);
+impl_resolved!(Path, PathBuf, OsStr, OsString);
+
+// based on stdlib's internal `tuple_impls!` macro
+macro_rules! impl_resolved_tuple {
+    ($T:ident) => {
+        impl_resolved_tuple!(@impl $T);
+    };
+    ($T:ident $( $U:ident )+) => {
+        impl_resolved_tuple!($( $U )+);
+        impl_resolved_tuple!(@impl $T $( $U )+);
+    };
+    (@impl $( $T:ident )+) => {
+        unsafe impl<$($T: ResolvedValue),+> ResolvedValue for ($($T,)+) {}
+    };
+}
+
+impl_resolved_tuple!(E D C B A Z Y X W V U T);
+
+unsafe impl<T: ResolvedValue> ResolvedValue for Option<T> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for Vec<T> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for VecDeque<T> {}
+unsafe impl<T: ResolvedValue, const N: usize> ResolvedValue for [T; N] {}
+unsafe impl<T: ResolvedValue> ResolvedValue for [T] {}
+unsafe impl<T: ResolvedValue, S> ResolvedValue for HashSet<T, S> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for BTreeSet<T> {}
+unsafe impl<K: ResolvedValue, V: ResolvedValue, S> ResolvedValue for HashMap<K, V, S> {}
+unsafe impl<K: ResolvedValue, V: ResolvedValue> ResolvedValue for BTreeMap<K, V> {}
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Box<T> {}
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Arc<T> {}
+unsafe impl<T: ResolvedValue, E: ResolvedValue> ResolvedValue for Result<T, E> {}
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Mutex<T> {}
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for RefCell<T> {}
+unsafe impl<T: ?Sized> ResolvedValue for PhantomData<T> {}
+unsafe impl<T: ResolvedValue + ToOwned + ?Sized> ResolvedValue for Cow<'_, T> where
+    T::Owned: ResolvedValue
+{
+}
+unsafe impl<T: ResolvedValue> ResolvedValue for Range<T> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for RangeInclusive<T> {}
+unsafe impl<T: ResolvedValue> ResolvedValue for Wrapping<T> {}
+
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for &T {}
+unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for &mut T {}
+
+#[cfg(feature = "anyhow")]
+impl_resolved!(anyhow::Error);
+
+#[cfg(feature = "auto-hash-map")]
+unsafe impl<T: ResolvedValue, S, const I: usize> ResolvedValue for auto_hash_map::AutoSet<T, S, I> {}
+#[cfg(feature = "auto-hash-map")]
+unsafe impl<K: ResolvedValue, V: ResolvedValue, S, const I: usize> ResolvedValue
+    for auto_hash_map::AutoMap<K, V, S, I>
+{
+}
+
+#[cfg(feature = "either")]
+unsafe impl<L: ResolvedValue, R: ResolvedValue> ResolvedValue for either::Either<L, R> {}
+
+#[cfg(feature = "indexmap")]
+unsafe impl<T: ResolvedValue, S> ResolvedValue for indexmap::IndexSet<T, S> {}
+#[cfg(feature = "indexmap")]
+unsafe impl<K: ResolvedValue, V: ResolvedValue, S> ResolvedValue for indexmap::IndexMap<K, V, S> {}
+
+#[cfg(feature = "serde_json")]
+impl_resolved!(serde_json::Value);
+
+#[cfg(feature = "smallvec")]
+unsafe impl<A: smallvec::Array> ResolvedValue for smallvec::SmallVec<A> where A::Item: ResolvedValue {}
+
+#[cfg(feature = "turbo-rcstr")]
+impl_resolved!(turbo_rcstr::RcStr);
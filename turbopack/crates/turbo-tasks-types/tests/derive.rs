@@ -0,0 +1,26 @@
+use std::marker::PhantomData;
+
+use turbo_tasks_macros::ResolvedValue;
+use turbo_tasks_types::ResolvedValue;
+
+fn assert_resolved_value<T: ResolvedValue + ?Sized>() {}
+
+#[derive(ResolvedValue)]
+#[turbo_tasks(crate = "turbo_tasks_types")]
+struct Options {
+    _name: String,
+    _sizes: Vec<u32>,
+}
+
+#[derive(ResolvedValue)]
+#[turbo_tasks(crate = "turbo_tasks_types")]
+enum Source {
+    _Inline(Box<[u8]>),
+    _File(Options, PhantomData<()>),
+}
+
+#[test]
+fn derives_without_turbo_tasks() {
+    assert_resolved_value::<Options>();
+    assert_resolved_value::<Source>();
+}
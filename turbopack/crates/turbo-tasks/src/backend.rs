@@ -3,6 +3,7 @@ use std::{
     fmt::{self, Debug, Display, Write},
     future::Future,
     hash::BuildHasherDefault,
+    path::Path,
     pin::Pin,
     sync::Arc,
     time::Duration,
@@ -12,10 +13,17 @@ use anyhow::{anyhow, Result};
 use auto_hash_map::AutoMap;
 use rustc_hash::FxHasher;
 use tracing::Span;
+use turbo_tasks_hash::{ContentHasher, DeterministicHasher, HashAlgorithm, HashDigest};
 
 pub use crate::id::{BackendJobId, ExecutionId};
 use crate::{
+    durability::Durability,
+    estimate_size::TaskMemoryUsage,
     event::EventListener,
+    gc_policy::GcPolicy,
+    graph_export::TaskGraphNode,
+    id::with_stable_task_hashes,
+    invalidation::{InvalidationCause, InvalidationReason},
     magic_any::MagicAny,
     manager::{ReadConsistency, TurboTasksBackendApi},
     raw_vc::CellId,
@@ -416,6 +424,18 @@ impl CachedTaskType {
             Self::ResolveTrait { .. } => None,
         }
     }
+
+    /// A hash of the function and the inputs of the task, which is the same in every process
+    /// running the same code, unlike the [`TaskId`]. Functions and traits are identified by
+    /// their global names. `Vc` inputs are identified by the stable hash of the task they belong
+    /// to, recursively, so the hash doesn't depend on the order in which tasks were created.
+    ///
+    /// Returns `None` when an input isn't serializable or belongs to a transient task. Must be
+    /// called within a turbo-tasks context.
+    pub fn stable_hash(&self) -> Option<u64> {
+        let digest = with_stable_task_hashes(|| hash_serialized(self, HashAlgorithm::Xxh3Hash64))?;
+        Some(digest.truncate_u64())
+    }
 }
 
 pub struct TaskExecutionSpec<'a> {
@@ -495,6 +515,44 @@ impl CellContent {
     pub fn into_typed(self, type_id: ValueTypeId) -> TypedCellContent {
         TypedCellContent(type_id, self)
     }
+
+    /// Hashes the serialized content, for a cell of type `type_id`. Returns `None` for an empty
+    /// cell or a value type without serialization.
+    ///
+    /// Fingerprints of different cells are compared with each other, so `hash_algorithm` should
+    /// have at least 128 bits when there are many cells.
+    pub fn content_hash(
+        &self,
+        type_id: ValueTypeId,
+        hash_algorithm: HashAlgorithm,
+    ) -> Option<HashDigest> {
+        let content = self.0.as_ref()?;
+        let serializable = registry::get_value_type(type_id).any_as_serializable(&content.0)?;
+        hash_serialized(serializable, hash_algorithm)
+    }
+}
+
+/// Hashes the JSON serialization of `value` without allocating it.
+fn hash_serialized(
+    value: &(impl serde::Serialize + ?Sized),
+    hash_algorithm: HashAlgorithm,
+) -> Option<HashDigest> {
+    struct HashWriter(ContentHasher);
+
+    impl std::io::Write for HashWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.write_bytes(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut writer = HashWriter(hash_algorithm.hasher());
+    serde_json::to_writer(&mut writer, value).ok()?;
+    Some(writer.0.finish_digest())
 }
 
 impl From<SharedReference> for CellContent {
@@ -532,13 +590,65 @@ pub trait Backend: Sync + Send {
     #[allow(unused_variables)]
     fn stopping(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
 
+    /// Configures garbage collection before the backend is used, see
+    /// [`crate::TurboTasksBuilder::gc_policy`]. Backends without garbage collection ignore it.
+    #[allow(unused_variables)]
+    fn set_gc_policy(&mut self, policy: GcPolicy) {}
+
     #[allow(unused_variables)]
     fn idle_start(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
     #[allow(unused_variables)]
     fn idle_end(&self, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
 
+    /// Called before an effect writes the output file at `path`. Backends with a persistent cache
+    /// can record it, to remove files which were only partially written when the process crashed.
+    #[allow(unused_variables)]
+    fn output_write_started(&self, path: &Path, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
+    /// Called after the output file at `path` was written completely, see
+    /// [`Self::output_write_started`].
+    #[allow(unused_variables)]
+    fn output_write_finished(&self, path: &Path, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {}
+
     fn invalidate_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>);
 
+    /// Like [`Self::invalidate_task`], for [`crate::TurboTasksApi::invalidate_with_reason`].
+    /// Backends which record [`InvalidationCause`]s can keep the reason.
+    fn invalidate_task_with_reason(
+        &self,
+        task: TaskId,
+        _reason: &dyn InvalidationReason,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) {
+        self.invalidate_task(task, turbo_tasks);
+    }
+
+    /// The cause of the last invalidation of the task, when the backend records them.
+    fn invalidation_cause(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Option<InvalidationCause> {
+        None
+    }
+
+    /// The estimated memory retained by the cells of each task, for
+    /// [`crate::TurboTasks::memory_report`]. Tasks without cell content can be omitted. Empty
+    /// when the backend doesn't estimate memory.
+    fn task_memory_usage(
+        &self,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Vec<TaskMemoryUsage> {
+        Vec::new()
+    }
+
+    /// The tasks with their inputs, reads and children, for [`crate::TurboTasks::export_graph`]
+    /// and [`crate::TurboTasks::dry_run`]. Tasks which aren't loaded can be omitted, except for
+    /// the tasks of a persistent cache, which are restored without executing them. Empty when the
+    /// backend doesn't support exporting the graph.
+    fn task_graph(&self, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) -> Vec<TaskGraphNode> {
+        Vec::new()
+    }
+
     fn invalidate_tasks(&self, tasks: &[TaskId], turbo_tasks: &dyn TurboTasksBackendApi<Self>);
     fn invalidate_tasks_set(&self, tasks: &TaskIdSet, turbo_tasks: &dyn TurboTasksBackendApi<Self>);
 
@@ -551,6 +661,17 @@ pub trait Backend: Sync + Send {
 
     fn get_task_description(&self, task: TaskId) -> String;
 
+    /// The [`CachedTaskType::stable_hash`] of a persistent task, see [`TaskId::stable_hash`].
+    /// `None` for transient tasks, when an input isn't serializable, or when the backend
+    /// doesn't know the type of its tasks.
+    fn get_task_stable_hash(
+        &self,
+        _task: TaskId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> Option<u64> {
+        None
+    }
+
     /// Task-local state that stored inside of [`TurboTasksBackendApi`]. Constructed with
     /// [`Self::new_task_state`].
     ///
@@ -597,6 +718,14 @@ pub trait Backend: Sync + Send {
         turbo_tasks: &dyn TurboTasksBackendApi<Self>,
     ) -> bool;
 
+    /// Called after [`Self::task_execution_completed`] when the execution was canceled with
+    /// [`crate::TurboTasksApi::cancel_task`]. The result of a canceled execution is usually a
+    /// [`crate::Canceled`] error, which must not be cached, so the task is invalidated by default.
+    /// Backends can also release the cells of the task here.
+    fn task_execution_canceled(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        self.invalidate_task(task, turbo_tasks);
+    }
+
     fn run_backend_job<'a>(
         &'a self,
         id: BackendJobId,
@@ -651,6 +780,18 @@ pub trait Backend: Sync + Send {
         }
     }
 
+    /// Returns whether the cell currently holds its content, without reading or recomputing it.
+    ///
+    /// Backends that don't drop cell content treat every cell as alive.
+    fn is_task_cell_alive(
+        &self,
+        _task: TaskId,
+        _index: CellId,
+        _turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) -> bool {
+        true
+    }
+
     fn read_task_collectibles(
         &self,
         task: TaskId,
@@ -725,6 +866,23 @@ pub trait Backend: Sync + Send {
         // Do nothing by default
     }
 
+    /// Marks the task as session dependent, reading inputs with the given durability. Backends
+    /// that don't distinguish durabilities treat it as session dependent.
+    fn mark_own_task_durability(
+        &self,
+        task: TaskId,
+        _durability: Durability,
+        turbo_tasks: &dyn TurboTasksBackendApi<Self>,
+    ) {
+        self.mark_own_task_as_session_dependent(task, turbo_tasks);
+    }
+
+    /// Speculatively schedules the task when it's not computed yet or dirty. This is only a hint
+    /// and backends are free to ignore it.
+    fn prefetch_task(&self, _task: TaskId, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        // Do nothing by default
+    }
+
     fn create_transient_task(
         &self,
         task_type: TransientTaskType,
@@ -732,6 +890,13 @@ pub trait Backend: Sync + Send {
     ) -> TaskId;
 
     fn dispose_root_task(&self, task: TaskId, turbo_tasks: &dyn TurboTasksBackendApi<Self>);
+
+    /// Exempts the tasks the root task `task` depends on from garbage collection, see
+    /// [`TurboTasks::pin_vc`][crate::TurboTasks::pin_vc]. Root tasks already recompute their
+    /// invalidated dependencies eagerly, so backends without garbage collection ignore it.
+    fn pin_root_task(&self, _task: TaskId, _turbo_tasks: &dyn TurboTasksBackendApi<Self>) {
+        // Do nothing by default
+    }
 }
 
 impl CachedTaskType {
@@ -0,0 +1,180 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+
+/// Values with a binary representation larger than this are stored out-of-line
+/// when a [BlobStore] is available during serialization.
+pub const BLOB_THRESHOLD: usize = 64 * 1024;
+
+/// Content addressed identifier of a blob in a [BlobStore].
+pub type BlobId = u128;
+
+/// Shared, immutable bytes of a blob. Depending on the store, this might be
+/// backed by a memory mapped file instead of a heap allocation.
+pub type Blob = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Storage for large binary values that are persisted outside of the regular
+/// task data, so they don't need to be copied when (de)serializing cells.
+///
+/// The store doesn't know which blobs are still in use. The owner of the
+/// serialized values keeps track of that, see [collect_blob_references], and
+/// removes blobs that are no longer referenced.
+pub trait BlobStore: Send + Sync {
+    /// Stores the given bytes and returns an identifier to read them again.
+    /// Storing the same bytes twice returns the same id.
+    fn write_blob(&self, data: &[u8]) -> Result<BlobId>;
+
+    /// Reads a blob previously written with [BlobStore::write_blob].
+    fn read_blob(&self, id: BlobId) -> Result<Blob>;
+
+    /// Removes a blob. Blobs which are currently read stay readable.
+    fn remove_blob(&self, id: BlobId) -> Result<()>;
+
+    /// Removes all blobs for which `is_referenced` returns false, and returns
+    /// how many were removed.
+    fn retain_blobs(&self, is_referenced: &dyn Fn(BlobId) -> bool) -> Result<usize>;
+}
+
+thread_local! {
+    static CURRENT_BLOB_STORE: RefCell<Option<Arc<dyn BlobStore>>> = const { RefCell::new(None) };
+}
+
+/// Makes the given [BlobStore] available to `Serialize` and `Deserialize`
+/// implementations called within `f` on the current thread.
+pub fn with_blob_store<R>(store: &Arc<dyn BlobStore>, f: impl FnOnce() -> R) -> R {
+    let _guard = enter_blob_store(store.clone());
+    f()
+}
+
+/// Makes the given [BlobStore] available to `Serialize` and `Deserialize`
+/// implementations on the current thread until the guard is dropped.
+pub fn enter_blob_store(store: Arc<dyn BlobStore>) -> BlobStoreGuard {
+    let previous = CURRENT_BLOB_STORE.with(|current| current.borrow_mut().replace(store));
+    BlobStoreGuard { previous }
+}
+
+/// Restores the previous [BlobStore] of the thread when dropped, see
+/// [enter_blob_store].
+pub struct BlobStoreGuard {
+    previous: Option<Arc<dyn BlobStore>>,
+}
+
+impl Drop for BlobStoreGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_BLOB_STORE.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Returns the [BlobStore] set by [with_blob_store], if any.
+pub fn current_blob_store() -> Option<Arc<dyn BlobStore>> {
+    CURRENT_BLOB_STORE.with(|current| current.borrow().clone())
+}
+
+/// Calls `f` and returns the ids of the blobs that were written or read
+/// through the current [BlobStore] meanwhile, i.e. the blobs referenced by the
+/// values `f` serialized or deserialized. The ids are sorted and deduplicated.
+pub fn collect_blob_references<R>(f: impl FnOnce() -> R) -> (R, Vec<BlobId>) {
+    let Some(inner) = current_blob_store() else {
+        return (f(), Vec::new());
+    };
+    let recording = Arc::new(RecordingBlobStore {
+        inner,
+        references: Mutex::new(Vec::new()),
+    });
+    let result = {
+        let _guard = enter_blob_store(recording.clone());
+        f()
+    };
+    let mut references = std::mem::take(&mut *recording.references.lock().unwrap());
+    references.sort_unstable();
+    references.dedup();
+    (result, references)
+}
+
+struct RecordingBlobStore {
+    inner: Arc<dyn BlobStore>,
+    references: Mutex<Vec<BlobId>>,
+}
+
+impl BlobStore for RecordingBlobStore {
+    fn write_blob(&self, data: &[u8]) -> Result<BlobId> {
+        let id = self.inner.write_blob(data)?;
+        self.references.lock().unwrap().push(id);
+        Ok(id)
+    }
+
+    fn read_blob(&self, id: BlobId) -> Result<Blob> {
+        self.references.lock().unwrap().push(id);
+        self.inner.read_blob(id)
+    }
+
+    fn remove_blob(&self, id: BlobId) -> Result<()> {
+        self.inner.remove_blob(id)
+    }
+
+    fn retain_blobs(&self, is_referenced: &dyn Fn(BlobId) -> bool) -> Result<usize> {
+        self.inner.retain_blobs(is_referenced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_hash::FxHashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryBlobStore {
+        blobs: Mutex<FxHashMap<BlobId, Vec<u8>>>,
+    }
+
+    impl BlobStore for MemoryBlobStore {
+        fn write_blob(&self, data: &[u8]) -> Result<BlobId> {
+            let id = data.len() as BlobId;
+            self.blobs.lock().unwrap().insert(id, data.to_vec());
+            Ok(id)
+        }
+
+        fn read_blob(&self, id: BlobId) -> Result<Blob> {
+            let data = self.blobs.lock().unwrap()[&id].clone();
+            Ok(Arc::new(data))
+        }
+
+        fn remove_blob(&self, id: BlobId) -> Result<()> {
+            self.blobs.lock().unwrap().remove(&id);
+            Ok(())
+        }
+
+        fn retain_blobs(&self, is_referenced: &dyn Fn(BlobId) -> bool) -> Result<usize> {
+            let mut blobs = self.blobs.lock().unwrap();
+            let before = blobs.len();
+            blobs.retain(|id, _| is_referenced(*id));
+            Ok(before - blobs.len())
+        }
+    }
+
+    #[test]
+    fn collects_written_and_read_blobs() {
+        let store: Arc<dyn BlobStore> = Arc::new(MemoryBlobStore::default());
+        with_blob_store(&store, || {
+            let ((), written) = collect_blob_references(|| {
+                let store = current_blob_store().unwrap();
+                store.write_blob(&[0; 3]).unwrap();
+                store.write_blob(&[0; 1]).unwrap();
+                store.write_blob(&[1; 3]).unwrap();
+            });
+            assert_eq!(written, [1, 3]);
+
+            let ((), read) = collect_blob_references(|| {
+                current_blob_store().unwrap().read_blob(1).unwrap();
+            });
+            assert_eq!(read, [1]);
+        });
+        assert!(current_blob_store().is_none());
+        assert_eq!(collect_blob_references(|| ()).1, []);
+    }
+}
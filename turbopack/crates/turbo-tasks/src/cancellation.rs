@@ -0,0 +1,86 @@
+use std::{
+    fmt::{self, Debug, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::event::Event;
+
+/// Signals that the execution of a task should be aborted.
+///
+/// Every task execution gets its own token, which is canceled by
+/// [`TurboTasksApi::cancel_task`][crate::TurboTasksApi::cancel_task]. Cancellation is cooperative:
+/// long-running task functions check [`is_canceled`][crate::is_canceled] (or return early with
+/// [`check_canceled`][crate::check_canceled]) at convenient points.
+#[derive(Clone)]
+pub struct CancellationToken {
+    inner: Arc<CancellationTokenInner>,
+}
+
+struct CancellationTokenInner {
+    canceled: AtomicBool,
+    event: Event,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(CancellationTokenInner {
+                canceled: AtomicBool::new(false),
+                event: Event::new(|| "CancellationToken::event".to_string()),
+            }),
+        }
+    }
+
+    pub fn cancel(&self) {
+        if !self.inner.canceled.swap(true, Ordering::AcqRel) {
+            self.inner.event.notify(usize::MAX);
+        }
+    }
+
+    pub fn is_canceled(&self) -> bool {
+        self.inner.canceled.load(Ordering::Acquire)
+    }
+
+    /// Resolves once the token is canceled.
+    pub async fn canceled(&self) {
+        loop {
+            if self.is_canceled() {
+                return;
+            }
+            let listener = self.inner.event.listen();
+            if self.is_canceled() {
+                return;
+            }
+            listener.await;
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("canceled", &self.is_canceled())
+            .finish()
+    }
+}
+
+/// The error a task returns when it stops because its execution was canceled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Canceled;
+
+impl Display for Canceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Task execution was canceled")
+    }
+}
+
+impl std::error::Error for Canceled {}
@@ -1,8 +1,124 @@
+use std::{future::Future, hash::BuildHasherDefault};
+
+use anyhow::Result;
 use auto_hash_map::AutoSet;
+use dashmap::DashMap;
+use rustc_hash::{FxHashSet, FxHasher};
 
-use crate::{Vc, VcValueTrait};
+use crate::{RawVc, ResolvedVc, TaskId, TraitTypeId, TryJoinIterExt, Vc, VcValueTrait};
 
 pub trait CollectiblesSource {
     fn take_collectibles<T: VcValueTrait>(self) -> AutoSet<Vc<T>>;
     fn peek_collectibles<T: VcValueTrait>(self) -> AutoSet<Vc<T>>;
+
+    /// Like [`CollectiblesSource::take_collectibles`], but in the order of [`CollectiblePosition`].
+    fn take_collectibles_ordered<T: VcValueTrait>(self) -> Vec<Vc<T>>;
+
+    /// Like [`CollectiblesSource::peek_collectibles`], but in the order of [`CollectiblePosition`].
+    fn peek_collectibles_ordered<T: VcValueTrait>(self) -> Vec<Vc<T>>;
+}
+
+/// Implemented by the marker type that `#[turbo_tasks::function(emits = ...)]`
+/// generates, for each collectible trait the function declares to emit.
+pub trait Emits<T: VcValueTrait + ?Sized> {}
+
+/// Where a collectible was emitted with [`emit_ordered`][crate::emit_ordered].
+///
+/// Positions order collectibles by the task which emitted them, in the order of their ids, and
+/// then in the order the task emitted them. Task ids are allocated when a task is first called,
+/// so for tasks which call their children in source order, e.g. when visiting an AST, this is the
+/// source order. Ids of tasks which were dropped can be reused though. Collectibles emitted with
+/// [`emit`][crate::emit], or by tasks which were restored from a persistent cache and not executed
+/// since, don't have a position and are ordered last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CollectiblePosition {
+    pub task: TaskId,
+    /// The index of the emission in the latest execution of the task.
+    pub index: u32,
+}
+
+type FxDashMap<K, V> = DashMap<K, V, BuildHasherDefault<FxHasher>>;
+
+/// The positions of the collectibles emitted with [`emit_ordered`][crate::emit_ordered].
+#[derive(Default)]
+pub(crate) struct CollectiblePositions {
+    positions: FxDashMap<(TraitTypeId, RawVc), CollectiblePosition>,
+    /// The collectibles emitted by the latest execution of each task, to forget the positions of
+    /// collectibles the task no longer emits.
+    by_task: FxDashMap<TaskId, Vec<(TraitTypeId, RawVc)>>,
+}
+
+impl CollectiblePositions {
+    /// Records an emission. When several tasks emit the same collectible, the earliest position
+    /// wins.
+    pub(crate) fn record(
+        &self,
+        trait_type: TraitTypeId,
+        collectible: RawVc,
+        position: CollectiblePosition,
+    ) {
+        self.positions
+            .entry((trait_type, collectible))
+            .and_modify(|existing| {
+                if existing.task == position.task || position < *existing {
+                    *existing = position;
+                }
+            })
+            .or_insert(position);
+    }
+
+    /// Forgets the positions of the collectibles the previous execution of `task` emitted and
+    /// `emitted` doesn't contain anymore.
+    pub(crate) fn finish_execution(&self, task: TaskId, emitted: Vec<(TraitTypeId, RawVc)>) {
+        let previous = if emitted.is_empty() {
+            self.by_task.remove(&task).map(|(_, previous)| previous)
+        } else {
+            let current = emitted.iter().copied().collect::<FxHashSet<_>>();
+            self.by_task.insert(task, emitted).map(|previous| {
+                previous
+                    .into_iter()
+                    .filter(|key| !current.contains(key))
+                    .collect()
+            })
+        };
+        for key in previous.into_iter().flatten() {
+            self.positions
+                .remove_if(&key, |_, position| position.task == task);
+        }
+    }
+
+    pub(crate) fn get(
+        &self,
+        trait_type: TraitTypeId,
+        collectible: RawVc,
+    ) -> Option<CollectiblePosition> {
+        self.positions
+            .get(&(trait_type, collectible))
+            .map(|position| *position)
+    }
+}
+
+/// Resolves the collectibles and keeps the ones `predicate` returns true for, in their original
+/// order. The predicates are evaluated concurrently.
+pub async fn filter_collectibles<T, F, Fut>(
+    collectibles: impl IntoIterator<Item = Vc<T>>,
+    predicate: F,
+) -> Result<Vec<ResolvedVc<T>>>
+where
+    T: VcValueTrait + ?Sized,
+    F: Fn(ResolvedVc<T>) -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let predicate = &predicate;
+    Ok(collectibles
+        .into_iter()
+        .map(|collectible| async move {
+            let collectible = collectible.to_resolved().await?;
+            Ok(predicate(collectible).await?.then_some(collectible))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect())
 }
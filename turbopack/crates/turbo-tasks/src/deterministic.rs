@@ -0,0 +1,199 @@
+//! Serializes task executions in a reproducible order, see
+//! [`TurboTasksBuilder::deterministic`][crate::TurboTasksBuilder::deterministic].
+
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+use parking_lot::Mutex;
+use pin_project_lite::pin_project;
+use rustc_hash::FxHashMap;
+
+use crate::{feature_flags::FeatureFlag, TaskId};
+
+pub(crate) static DETERMINISTIC: FeatureFlag<bool> =
+    FeatureFlag::new("deterministic", "TURBO_DETERMINISTIC", false);
+pub(crate) static DETERMINISTIC_SEED: FeatureFlag<usize> =
+    FeatureFlag::new("deterministic-seed", "TURBO_DETERMINISTIC_SEED", 0);
+
+/// The order in which task executions get their turn: the task id permuted by the seed, then the
+/// order in which the executions were scheduled.
+type Key = (u64, u64);
+
+#[derive(Default)]
+struct SchedulerState {
+    /// The execution that is allowed to be polled.
+    current: Option<Key>,
+    /// Executions which were woken and wait for their turn.
+    runnable: BTreeSet<Key>,
+    /// All executions that weren't finished yet, with the waker of their last poll.
+    executions: FxHashMap<Key, Option<Waker>>,
+}
+
+impl SchedulerState {
+    /// Gives the turn to the first runnable execution when no execution has it. Returns the waker
+    /// to wake after releasing the lock.
+    fn grant_if_idle(&mut self) -> Option<Waker> {
+        if self.current.is_some() {
+            return None;
+        }
+        let key = *self.runnable.first()?;
+        self.current = Some(key);
+        // Executions which weren't polled yet will be polled by the runtime anyway.
+        self.executions.get(&key).cloned().flatten()
+    }
+}
+
+/// Lets only one task execution be polled at a time. When an execution returns `Pending`, the
+/// turn goes to the runnable execution with the lowest [`Key`].
+///
+/// Wakeups caused by a task, e.g. by completing a task another task waits for, happen during its
+/// turn, so the set of runnable executions, and thereby the order, is the same in every run. Only
+/// wakeups from outside, e.g. by file system events, can change the order.
+pub(crate) struct DeterministicScheduler {
+    seed: u64,
+    next_sequence: AtomicU64,
+    state: Mutex<SchedulerState>,
+}
+
+impl DeterministicScheduler {
+    pub(crate) fn new(seed: u64) -> Arc<Self> {
+        Arc::new(Self {
+            seed,
+            next_sequence: AtomicU64::new(0),
+            state: Default::default(),
+        })
+    }
+
+    fn priority(&self, task_id: TaskId) -> u64 {
+        let id = *task_id as u64;
+        if self.seed == 0 {
+            return id;
+        }
+        // splitmix64, to get a different but stable order for each seed
+        let mut z = (id ^ self.seed).wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Wraps the execution future of `task_id`. It's runnable right away.
+    pub(crate) fn wrap<F: Future>(
+        self: &Arc<Self>,
+        task_id: TaskId,
+        future: F,
+    ) -> DeterministicFuture<F> {
+        let key = (
+            self.priority(task_id),
+            self.next_sequence.fetch_add(1, Ordering::Relaxed),
+        );
+        let waker = {
+            let mut state = self.state.lock();
+            state.executions.insert(key, None);
+            state.runnable.insert(key);
+            state.grant_if_idle()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        DeterministicFuture {
+            scheduler: self.clone(),
+            key,
+            future,
+        }
+    }
+
+    fn wake(&self, key: Key) {
+        let waker = {
+            let mut state = self.state.lock();
+            if !state.executions.contains_key(&key) {
+                return;
+            }
+            state.runnable.insert(key);
+            state.grant_if_idle()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn finish_turn(&self, key: Key, finished: bool) {
+        let waker = {
+            let mut state = self.state.lock();
+            if finished {
+                state.executions.remove(&key);
+                state.runnable.remove(&key);
+            }
+            if state.current == Some(key) {
+                state.current = None;
+            }
+            state.grant_if_idle()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+struct TurnWaker {
+    scheduler: Arc<DeterministicScheduler>,
+    key: Key,
+}
+
+impl Wake for TurnWaker {
+    fn wake(self: Arc<Self>) {
+        self.scheduler.wake(self.key);
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.scheduler.wake(self.key);
+    }
+}
+
+pin_project! {
+    pub(crate) struct DeterministicFuture<F> {
+        scheduler: Arc<DeterministicScheduler>,
+        key: Key,
+        #[pin]
+        future: F,
+    }
+
+    impl<F> PinnedDrop for DeterministicFuture<F> {
+        fn drop(this: Pin<&mut Self>) {
+            let this = this.project();
+            this.scheduler.finish_turn(*this.key, true);
+        }
+    }
+}
+
+impl<F: Future> Future for DeterministicFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let key = *this.key;
+        {
+            let mut state = this.scheduler.state.lock();
+            state.executions.insert(key, Some(cx.waker().clone()));
+            if state.current != Some(key) {
+                return Poll::Pending;
+            }
+            // Wakeups during the poll make it runnable again.
+            state.runnable.remove(&key);
+        }
+        let waker = Waker::from(Arc::new(TurnWaker {
+            scheduler: this.scheduler.clone(),
+            key,
+        }));
+        let result = this.future.poll(&mut Context::from_waker(&waker));
+        this.scheduler.finish_turn(key, result.is_ready());
+        result
+    }
+}
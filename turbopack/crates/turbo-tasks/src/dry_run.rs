@@ -0,0 +1,174 @@
+use std::fmt::{self, Display};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{graph_export::TaskGraphNode, TaskId};
+
+/// How much of a build would come from the persistent cache, see
+/// [`TurboTasks::dry_run`][crate::TurboTasks::dry_run].
+#[derive(Debug, Clone, Default)]
+pub struct DryRunReport {
+    /// The number of cached tasks.
+    pub tasks: usize,
+    /// The number of cached tasks which wouldn't need to be executed again.
+    pub cache_hits: usize,
+    /// The functions and arguments of the inputs, e.g. file reads, which changed since they were
+    /// cached.
+    /// Inputs read by the same invalidated task are all reported, as the check can't tell which
+    /// of them changed.
+    pub changed_inputs: Vec<String>,
+}
+
+impl DryRunReport {
+    /// The fraction of cached tasks which are cache hits, `0.0` when nothing is cached.
+    pub fn hit_rate(&self) -> f64 {
+        if self.tasks == 0 {
+            return 0.0;
+        }
+        self.cache_hits as f64 / self.tasks as f64
+    }
+}
+
+impl Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} cached tasks are cache hits ({:.1}%)",
+            self.cache_hits,
+            self.tasks,
+            self.hit_rate() * 100.0
+        )?;
+        if !self.changed_inputs.is_empty() {
+            writeln!(f, "{} changed inputs:", self.changed_inputs.len())?;
+            for input in &self.changed_inputs {
+                writeln!(f, "  {input}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The dirty tasks and the tasks which (transitively) read one of them, which all need to be
+/// executed again. Tasks which are only called by a dirty task aren't stale, as they might be
+/// called with the same arguments again.
+pub(crate) fn stale_tasks(nodes: &[TaskGraphNode]) -> FxHashSet<TaskId> {
+    let mut readers: FxHashMap<TaskId, Vec<TaskId>> = FxHashMap::default();
+    for node in nodes {
+        for read in &node.reads {
+            readers.entry(read.task()).or_default().push(node.task);
+        }
+    }
+    let mut stale = FxHashSet::default();
+    let mut queue: Vec<_> = nodes
+        .iter()
+        .filter(|node| node.dirty)
+        .map(|node| node.task)
+        .collect();
+    while let Some(task) = queue.pop() {
+        if stale.insert(task) {
+            queue.extend(readers.get(&task).into_iter().flatten().copied());
+        }
+    }
+    stale
+}
+
+/// The dirty tasks which don't read any stale task, e.g. file reads, which are dirty because they
+/// were cached in an earlier session. Executing them again tells whether they changed.
+pub(crate) fn dirty_inputs(nodes: &[TaskGraphNode], stale: &FxHashSet<TaskId>) -> Vec<TaskId> {
+    nodes
+        .iter()
+        .filter(|node| node.dirty && node.reads.iter().all(|read| !stale.contains(&read.task())))
+        .map(|node| node.task)
+        .collect()
+}
+
+/// The inputs which invalidated a task reading them, sorted, by comparing the graph from before
+/// the inputs were executed again with the graph from after.
+pub(crate) fn changed_inputs(
+    before: &[TaskGraphNode],
+    after: &[TaskGraphNode],
+    inputs: &[TaskId],
+) -> Vec<TaskId> {
+    let dirty_before: FxHashSet<_> = before
+        .iter()
+        .filter(|node| node.dirty)
+        .map(|node| node.task)
+        .collect();
+    let inputs: FxHashSet<_> = inputs.iter().copied().collect();
+    let mut changed = FxHashSet::default();
+    for node in after {
+        if node.dirty && !dirty_before.contains(&node.task) {
+            changed.extend(
+                node.reads
+                    .iter()
+                    .map(|read| read.task())
+                    .filter(|task| inputs.contains(task)),
+            );
+        }
+    }
+    let mut changed: Vec<_> = changed.into_iter().collect();
+    changed.sort_unstable();
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_export::TaskGraphRead;
+
+    fn node(task: u32, reads: &[u32], dirty: bool) -> TaskGraphNode {
+        TaskGraphNode {
+            task: TaskId::from(task),
+            name: format!("app::task{task}"),
+            inputs: Vec::new(),
+            reads: reads
+                .iter()
+                .map(|&task| TaskGraphRead::Output(TaskId::from(task)))
+                .collect(),
+            children: Vec::new(),
+            dirty,
+        }
+    }
+
+    fn ids(tasks: &[u32]) -> Vec<TaskId> {
+        tasks.iter().map(|&task| TaskId::from(task)).collect()
+    }
+
+    #[test]
+    fn finds_stale_tasks_and_inputs() {
+        let nodes = vec![
+            node(1, &[2, 3], false),
+            node(2, &[4], false),
+            node(3, &[5], true),
+            node(4, &[], true),
+            node(5, &[], false),
+        ];
+        let stale = stale_tasks(&nodes);
+        let mut stale_ids: Vec<_> = stale.iter().copied().collect();
+        stale_ids.sort_unstable();
+        assert_eq!(stale_ids, ids(&[1, 2, 3, 4]));
+        assert_eq!(dirty_inputs(&nodes, &stale), ids(&[3, 4]));
+    }
+
+    #[test]
+    fn reports_inputs_which_invalidated_readers() {
+        let before = vec![
+            node(1, &[2], false),
+            node(2, &[], true),
+            node(3, &[4], false),
+            node(4, &[], true),
+        ];
+        let after = vec![
+            node(1, &[2], true),
+            node(2, &[], false),
+            node(3, &[4], false),
+            node(4, &[], false),
+        ];
+        assert_eq!(changed_inputs(&before, &after, &ids(&[2, 4])), ids(&[2]));
+    }
+
+    #[test]
+    fn hit_rate_without_tasks() {
+        assert_eq!(DryRunReport::default().hit_rate(), 0.0);
+    }
+}
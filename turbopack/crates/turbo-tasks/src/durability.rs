@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::manager::{current_task, with_turbo_tasks};
+
+/// How likely the inputs read by a task are to change.
+///
+/// Inputs like the contents of `node_modules` rarely change during a session,
+/// while application source code changes on every keystroke. Marking the former
+/// as [`Durability::High`] allows the backend to skip verifying them when a
+/// session dependent task is restored from the persistent cache.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Durability {
+    /// The input is expected to change frequently, e.g. application source code.
+    /// This is the durability of inputs marked with
+    /// [`mark_session_dependent`][crate::mark_session_dependent].
+    #[default]
+    Low,
+    /// The input is expected to change rarely, e.g. installed packages.
+    High,
+}
+
+/// Marks the current task as session dependent, reading inputs with the given
+/// [`Durability`]. When called multiple times during one execution, including
+/// through [`mark_session_dependent`][crate::mark_session_dependent], the
+/// lowest durability wins.
+pub fn mark_durability(durability: Durability) {
+    with_turbo_tasks(|tt| {
+        tt.mark_own_task_durability(current_task("turbo_tasks::mark_durability()"), durability)
+    });
+}
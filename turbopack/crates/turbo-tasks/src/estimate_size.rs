@@ -0,0 +1,231 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    ffi::OsString,
+    fmt::{self, Display},
+    mem::size_of,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use indexmap::{IndexMap, IndexSet};
+use turbo_rcstr::RcStr;
+pub use turbo_tasks_macros::EstimateSize;
+
+use crate::{ReadRef, ResolvedVc, TaskId, Vc};
+
+/// A type that can estimate the memory it owns on the heap, to find the tasks retaining the most
+/// memory with [`TurboTasks::memory_report`][crate::TurboTasks::memory_report].
+///
+/// The estimate doesn't include `size_of::<Self>()`, and doesn't need to be exact. Allocations
+/// which are shared, like the content of an [`Arc`] or other cells, aren't counted.
+///
+/// Like [`ShrinkToFit`][crate::ShrinkToFit], it is derived for every
+/// [`VcValueType`][crate::VcValueType]. Fields whose type doesn't implement it count as owning no
+/// heap memory.
+pub trait EstimateSize {
+    fn estimate_heap_size(&self) -> usize;
+}
+
+macro_rules! impl_no_heap {
+    ($($ty:ty),*) => {
+        $(
+            impl EstimateSize for $ty {
+                fn estimate_heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_no_heap!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    TaskId
+);
+
+impl EstimateSize for String {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl EstimateSize for RcStr {
+    fn estimate_heap_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EstimateSize for OsString {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl EstimateSize for PathBuf {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: ?Sized> EstimateSize for Vc<T> {
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> EstimateSize for ResolvedVc<T> {
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T> EstimateSize for ReadRef<T> {
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: ?Sized> EstimateSize for Arc<T> {
+    fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Box<T> {
+    fn estimate_heap_size(&self) -> usize {
+        size_of::<T>() + (**self).estimate_heap_size()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Option<T> {
+    fn estimate_heap_size(&self) -> usize {
+        self.as_ref().map_or(0, T::estimate_heap_size)
+    }
+}
+
+impl<A: EstimateSize, B: EstimateSize> EstimateSize for (A, B) {
+    fn estimate_heap_size(&self) -> usize {
+        self.0.estimate_heap_size() + self.1.estimate_heap_size()
+    }
+}
+
+impl<A: EstimateSize, B: EstimateSize, C: EstimateSize> EstimateSize for (A, B, C) {
+    fn estimate_heap_size(&self) -> usize {
+        self.0.estimate_heap_size() + self.1.estimate_heap_size() + self.2.estimate_heap_size()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Vec<T> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(T::estimate_heap_size).sum::<usize>()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for VecDeque<T> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(T::estimate_heap_size).sum::<usize>()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for BTreeSet<T> {
+    fn estimate_heap_size(&self) -> usize {
+        self.len() * size_of::<T>() + self.iter().map(T::estimate_heap_size).sum::<usize>()
+    }
+}
+
+impl<K: EstimateSize, V: EstimateSize> EstimateSize for BTreeMap<K, V> {
+    fn estimate_heap_size(&self) -> usize {
+        self.len() * size_of::<(K, V)>() + self.iter().map(entry_heap_size).sum::<usize>()
+    }
+}
+
+// Each bucket of a hashbrown table has one control byte.
+impl<T: EstimateSize, S> EstimateSize for HashSet<T, S> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * (size_of::<T>() + 1)
+            + self.iter().map(T::estimate_heap_size).sum::<usize>()
+    }
+}
+
+impl<K: EstimateSize, V: EstimateSize, S> EstimateSize for HashMap<K, V, S> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * (size_of::<(K, V)>() + 1)
+            + self.iter().map(entry_heap_size).sum::<usize>()
+    }
+}
+
+// The entries of an index map are stored with their hash in a `Vec`, and their indices in a
+// hashbrown table.
+impl<T: EstimateSize, S> EstimateSize for IndexSet<T, S> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * (size_of::<(usize, T)>() + size_of::<usize>() + 1)
+            + self.iter().map(T::estimate_heap_size).sum::<usize>()
+    }
+}
+
+impl<K: EstimateSize, V: EstimateSize, S> EstimateSize for IndexMap<K, V, S> {
+    fn estimate_heap_size(&self) -> usize {
+        self.capacity() * (size_of::<(usize, K, V)>() + size_of::<usize>() + 1)
+            + self.iter().map(entry_heap_size).sum::<usize>()
+    }
+}
+
+fn entry_heap_size<K: EstimateSize, V: EstimateSize>((key, value): (&K, &V)) -> usize {
+    key.estimate_heap_size() + value.estimate_heap_size()
+}
+
+/// The approximate memory retained by the cells of a task, as reported by the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskMemoryUsage {
+    pub task: TaskId,
+    /// The number of cells holding content.
+    pub cells: usize,
+    /// The estimated bytes of the content of the cells.
+    pub bytes: usize,
+}
+
+/// The tasks retaining the most memory, see
+/// [`TurboTasks::memory_report`][crate::TurboTasks::memory_report].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReport {
+    /// The estimated bytes of the content of all cells.
+    pub total_bytes: usize,
+    /// The number of cells holding content.
+    pub total_cells: usize,
+    /// The tasks with the most retained bytes first, with their description.
+    pub tasks: Vec<(TaskMemoryUsage, String)>,
+}
+
+impl Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} bytes in {} cells (estimated)",
+            self.total_bytes, self.total_cells
+        )?;
+        for (usage, description) in &self.tasks {
+            writeln!(
+                f,
+                "  {} bytes in {} cells: {description}",
+                usage.bytes, usage.cells
+            )?;
+        }
+        Ok(())
+    }
+}
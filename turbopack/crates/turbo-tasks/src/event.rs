@@ -38,6 +38,7 @@ impl Event {
     pub fn listen(&self) -> EventListener {
         EventListener {
             listener: self.event.listen(),
+            _drop_guard: None,
         }
     }
 
@@ -48,6 +49,7 @@ impl Event {
     ) -> EventListener {
         EventListener {
             listener: self.event.listen(),
+            _drop_guard: None,
         }
     }
 
@@ -80,6 +82,7 @@ impl Event {
                 self.event.listen(),
             ))),
             duration: Duration::from_secs(10),
+            _drop_guard: None,
         }
     }
 
@@ -96,6 +99,7 @@ impl Event {
                 self.event.listen(),
             ))),
             duration: Duration::from_secs(10),
+            _drop_guard: None,
         }
     }
 
@@ -127,6 +131,15 @@ impl Debug for Event {
 #[cfg(not(feature = "hanging_detection"))]
 pub struct EventListener {
     listener: event_listener::EventListener,
+    _drop_guard: Option<Box<dyn Send + Sync>>,
+}
+
+impl EventListener {
+    /// Keeps `guard` alive until the listener is dropped, i.e. while the event is waited for.
+    pub fn with_drop_guard(mut self, guard: impl Send + Sync + 'static) -> Self {
+        self._drop_guard = Some(Box::new(guard));
+        self
+    }
 }
 
 #[cfg(not(feature = "hanging_detection"))]
@@ -156,6 +169,7 @@ pub struct EventListener {
     // So it's important to put it into a pinned Box to be able to take it out of the Option.
     future: Option<Pin<Box<Timeout<event_listener::EventListener>>>>,
     duration: Duration,
+    _drop_guard: Option<Box<dyn Send + Sync>>,
 }
 
 #[cfg(feature = "hanging_detection")]
@@ -0,0 +1,135 @@
+use std::{
+    any::{Any, TypeId},
+    hash::BuildHasherDefault,
+};
+
+use dashmap::DashMap;
+use rustc_hash::FxHasher;
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+};
+
+/// How many events of a type are buffered for a subscription before it misses events.
+const CAPACITY: usize = 256;
+
+/// Distributes events between subsystems, e.g. from the backend to the dev server, by their
+/// type. Any `Clone + Send + Sync + 'static` type can be published, the crate publishing an
+/// event type defines it.
+///
+/// Subscribe with [`TurboTasks::subscribe_events`][crate::TurboTasks::subscribe_events]. Publish
+/// with [`publish_event`][crate::publish_event] from a task, or with
+/// [`TurboTasksBackendApi::event_bus`][crate::TurboTasksBackendApi::event_bus] from a backend.
+///
+/// Publishing is cheap when nobody is subscribed to the type of the event.
+#[derive(Default)]
+pub struct EventBus {
+    /// A `broadcast::Sender<E>` per event type `E`, created by the first subscription.
+    senders: DashMap<TypeId, Box<dyn Any + Send + Sync>, BuildHasherDefault<FxHasher>>,
+}
+
+impl EventBus {
+    /// Sends the event to the subscriptions for its type.
+    pub fn publish<E: Clone + Send + Sync + 'static>(&self, event: E) {
+        let Some(sender) = self.senders.get(&TypeId::of::<E>()) else {
+            return;
+        };
+        let sender = sender
+            .downcast_ref::<broadcast::Sender<E>>()
+            .expect("senders are keyed by their event type");
+        if sender.receiver_count() > 0 {
+            // Sending only fails when there are no subscriptions.
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Subscribes to the events of type `E` published from now on.
+    pub fn subscribe<E: Clone + Send + Sync + 'static>(&self) -> EventSubscription<E> {
+        let sender = self
+            .senders
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(broadcast::channel::<E>(CAPACITY).0));
+        let sender = sender
+            .downcast_ref::<broadcast::Sender<E>>()
+            .expect("senders are keyed by their event type");
+        EventSubscription {
+            receiver: sender.subscribe(),
+        }
+    }
+}
+
+/// Receives the events of type `E` published after subscribing, see [EventBus].
+///
+/// The subscription doesn't keep the turbo-tasks instance alive and can be held outside of
+/// tasks, e.g. by the dev server. Events must not be received inside of a
+/// `#[turbo_tasks::function]`, as the task wouldn't be invalidated by them.
+pub struct EventSubscription<E> {
+    receiver: broadcast::Receiver<E>,
+}
+
+impl<E: Clone + Send + Sync + 'static> EventSubscription<E> {
+    /// Waits for the next event. Events are skipped when the subscription lags behind. Returns
+    /// `None` when the turbo-tasks instance was dropped.
+    pub async fn recv(&mut self) -> Option<E> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Returns the next event when one was already published.
+    pub fn try_recv(&mut self) -> Option<E> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Published by the backend after garbage collection dropped task data from memory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GcCompleted {
+    /// Whether the collection ran because turbo-tasks was idle, instead of because the memory
+    /// limit was exceeded.
+    pub idle: bool,
+    /// The memory usage in bytes after the collection.
+    pub memory_usage: usize,
+}
+
+/// Published by the backend after changed task data was written to the persistent cache.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersistFlushed {
+    /// Whether this is the last write before turbo-tasks stops.
+    pub stopping: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivers_events_by_type() {
+        let bus = EventBus::default();
+        bus.publish(PersistFlushed { stopping: false });
+        let mut flushed = bus.subscribe::<PersistFlushed>();
+        let mut gc = bus.subscribe::<GcCompleted>();
+        bus.publish(PersistFlushed { stopping: true });
+        assert_eq!(flushed.try_recv(), Some(PersistFlushed { stopping: true }));
+        assert_eq!(flushed.try_recv(), None);
+        assert_eq!(gc.try_recv(), None);
+    }
+
+    #[tokio::test]
+    async fn ends_when_the_bus_is_dropped() {
+        let bus = EventBus::default();
+        let mut subscription = bus.subscribe::<GcCompleted>();
+        drop(bus);
+        assert_eq!(subscription.recv().await, None);
+    }
+}
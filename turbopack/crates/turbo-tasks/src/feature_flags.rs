@@ -0,0 +1,192 @@
+//! A registry of feature flags for experimental code paths.
+//!
+//! Flags are declared as statics next to the code they control:
+//!
+//! ```
+//! use turbo_tasks::feature_flags::FeatureFlag;
+//!
+//! static NEW_THING: FeatureFlag<bool> =
+//!     FeatureFlag::new("new-thing", "TURBOPACK_NEW_THING", false);
+//!
+//! if NEW_THING.get() {
+//!     // ...
+//! }
+//! ```
+//!
+//! A flag is resolved once, on its first read. An override set with [set_feature_flag] (e.g.
+//! forwarded from JS options) takes precedence over the environment variable, which takes
+//! precedence over the default value. The resolved value is logged when it differs from the
+//! default, and listed by [feature_flags].
+
+use std::{
+    fmt::{self, Debug, Display},
+    sync::OnceLock,
+};
+
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+/// A type a feature flag can have.
+pub trait FeatureFlagValue: Copy + Debug + PartialEq + Send + Sync + 'static {
+    fn parse(value: &str) -> Option<Self>;
+}
+
+/// Like the environment variables the flags replaced, a flag is enabled by being set, e.g. to an
+/// empty value or `yes`, unless it's set to `0` or `false`.
+impl FeatureFlagValue for bool {
+    fn parse(value: &str) -> Option<Self> {
+        Some(!matches!(value, "0" | "false"))
+    }
+}
+
+impl FeatureFlagValue for usize {
+    fn parse(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+/// Where the value of a feature flag comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureFlagSource {
+    Default,
+    Env,
+    Override,
+}
+
+/// The resolved state of a feature flag, see [feature_flags].
+#[derive(Clone, Debug)]
+pub struct FeatureFlagState {
+    pub name: &'static str,
+    pub env_var: &'static str,
+    pub value: String,
+    pub source: FeatureFlagSource,
+}
+
+impl Display for FeatureFlagState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
+
+static OVERRIDES: Lazy<Mutex<FxHashMap<String, String>>> = Lazy::new(Default::default);
+static RESOLVED: Mutex<Vec<FeatureFlagState>> = Mutex::new(Vec::new());
+
+pub struct FeatureFlag<T: FeatureFlagValue> {
+    name: &'static str,
+    env_var: &'static str,
+    default: T,
+    value: OnceLock<T>,
+}
+
+impl<T: FeatureFlagValue> FeatureFlag<T> {
+    pub const fn new(name: &'static str, env_var: &'static str, default: T) -> Self {
+        FeatureFlag {
+            name,
+            env_var,
+            default,
+            value: OnceLock::new(),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        *self.value.get_or_init(|| self.resolve())
+    }
+
+    fn resolve(&self) -> T {
+        let overridden = OVERRIDES.lock().get(self.name).cloned();
+        let (value, source) = if let Some(value) = overridden {
+            (self.parse(&value), FeatureFlagSource::Override)
+        } else if let Ok(value) = std::env::var(self.env_var) {
+            (self.parse(&value), FeatureFlagSource::Env)
+        } else {
+            (self.default, FeatureFlagSource::Default)
+        };
+        if value != self.default {
+            tracing::info!(
+                name = self.name,
+                value = ?value,
+                source = ?source,
+                "feature flag enabled"
+            );
+        }
+        RESOLVED.lock().push(FeatureFlagState {
+            name: self.name,
+            env_var: self.env_var,
+            value: format!("{value:?}"),
+            source,
+        });
+        value
+    }
+
+    fn parse(&self, value: &str) -> T {
+        T::parse(value).unwrap_or_else(|| {
+            tracing::warn!(
+                name = self.name,
+                value,
+                "invalid value for feature flag, using the default"
+            );
+            self.default
+        })
+    }
+}
+
+/// Overrides the value of the feature flag `name`, e.g. from JS options.
+///
+/// Fails when the flag was already read with a different value, as the change wouldn't have any
+/// effect.
+pub fn set_feature_flag(name: &str, value: &str) -> Result<()> {
+    if let Some(state) = RESOLVED.lock().iter().find(|state| state.name == name) {
+        if state.value != value {
+            bail!(
+                "Feature flag {name} can't be set to {value}, it was already read as {}",
+                state.value
+            );
+        }
+    }
+    OVERRIDES.lock().insert(name.to_string(), value.to_string());
+    Ok(())
+}
+
+/// The flags read so far, in the order they were read.
+pub fn feature_flags() -> Vec<FeatureFlagState> {
+    RESOLVED.lock().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_takes_precedence() {
+        static FLAG: FeatureFlag<bool> =
+            FeatureFlag::new("test-override", "TURBO_TASKS_TEST_OVERRIDE_FLAG", false);
+        set_feature_flag("test-override", "true").unwrap();
+        assert!(FLAG.get());
+        assert!(set_feature_flag("test-override", "true").is_ok());
+        assert!(set_feature_flag("test-override", "false").is_err());
+        assert!(feature_flags()
+            .iter()
+            .any(|state| state.name == "test-override"
+                && state.source == FeatureFlagSource::Override));
+    }
+
+    #[test]
+    fn bool_is_enabled_unless_disabled() {
+        for value in ["", "1", "true", "yes", "on"] {
+            assert_eq!(bool::parse(value), Some(true), "{value:?}");
+        }
+        for value in ["0", "false"] {
+            assert_eq!(bool::parse(value), Some(false), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_value_falls_back_to_default() {
+        static FLAG: FeatureFlag<usize> =
+            FeatureFlag::new("test-invalid", "TURBO_TASKS_TEST_INVALID_FLAG", 4);
+        set_feature_flag("test-invalid", "many").unwrap();
+        assert_eq!(FLAG.get(), 4);
+    }
+}
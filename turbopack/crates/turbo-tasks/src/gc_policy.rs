@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Configures when and which cells the backend drops to save memory. Dropped cells are recomputed
+/// when they are read again, so the policy trades memory for recomputation.
+///
+/// Set it with [`TurboTasksBuilder::gc_policy`][crate::TurboTasksBuilder::gc_policy]. Backends
+/// without garbage collection ignore it.
+///
+/// Tasks of functions annotated with `#[turbo_tasks::function(gc_exempt)]` are never collected,
+/// e.g. for tasks which are expensive to recompute and read after every change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GcPolicy {
+    /// The memory usage in bytes above which tasks are collected. `None` keeps the limit the
+    /// backend was created with.
+    pub max_memory: Option<usize>,
+    /// Tasks which weren't read for at least this long are collected when turbo-tasks becomes
+    /// idle, even when the memory usage is below [`GcPolicy::max_memory`].
+    pub max_idle_age: Option<Duration>,
+    /// Which tasks are collected first.
+    pub eviction: GcEviction,
+}
+
+/// The order in which [`GcPolicy`] collects tasks that weren't read recently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GcEviction {
+    /// Tasks which use the most memory compared to the time they took to compute.
+    #[default]
+    MemoryPerTime,
+    /// Tasks which weren't read for the longest time.
+    LeastRecentlyUsed,
+    /// Tasks which were read the least often.
+    LeastFrequentlyUsed,
+}
@@ -0,0 +1,397 @@
+use std::fmt::{self, Write};
+
+use anyhow::Result;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+
+use crate::{backend::CachedTaskType, registry, CellId, MagicAny, TaskId, TraitTypeId};
+
+/// A task and its edges, as reported by the backend for
+/// [`TurboTasks::export_graph`][crate::TurboTasks::export_graph].
+#[derive(Debug, Clone)]
+pub struct TaskGraphNode {
+    pub task: TaskId,
+    /// The name of the function, see [`CachedTaskType::get_name`].
+    pub name: String,
+    /// The `self` argument, when there is one, and the other arguments, formatted with [Debug].
+    pub inputs: Vec<String>,
+    /// The task outputs, cells and collectibles the task read during its last execution.
+    pub reads: Vec<TaskGraphRead>,
+    /// The tasks the task called.
+    pub children: Vec<TaskId>,
+    /// The task needs to be executed again before its output can be used, e.g. because
+    /// something it read changed or because it read a file in an earlier session.
+    pub dirty: bool,
+}
+
+impl TaskGraphNode {
+    pub fn new(task: TaskId, ty: &CachedTaskType) -> Self {
+        let (this, arg) = match ty {
+            CachedTaskType::Native { this, arg, .. }
+            | CachedTaskType::ResolveNative { this, arg, .. } => (this.as_ref(), arg),
+            CachedTaskType::ResolveTrait { this, arg, .. } => (Some(this), arg),
+        };
+        Self {
+            task,
+            name: ty.get_name().into_owned(),
+            inputs: this
+                .map(|this| format!("{this:?}"))
+                .into_iter()
+                .chain([format!("{:?}", DebugValue(&**arg))])
+                .collect(),
+            reads: Vec::new(),
+            children: Vec::new(),
+            dirty: false,
+        }
+    }
+}
+
+/// Formats the arguments of a task without the type, which makes the labels unreadable.
+struct DebugValue<'a>(&'a dyn MagicAny);
+
+impl fmt::Debug for DebugValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.magic_debug_value(f)
+    }
+}
+
+/// Something read by a task, see [`TaskGraphNode::reads`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskGraphRead {
+    Output(TaskId),
+    Cell(TaskId, CellId),
+    Collectibles(TaskId, TraitTypeId),
+}
+
+impl TaskGraphRead {
+    pub fn task(&self) -> TaskId {
+        match *self {
+            TaskGraphRead::Output(task)
+            | TaskGraphRead::Cell(task, _)
+            | TaskGraphRead::Collectibles(task, _) => task,
+        }
+    }
+}
+
+/// Selects the tasks of [`TurboTasks::export_graph`][crate::TurboTasks::export_graph].
+#[derive(Debug, Clone, Default)]
+pub struct GraphExportFilter {
+    /// Only exports tasks whose function name contains one of these strings. Exports all tasks
+    /// when empty.
+    pub function_names: Vec<String>,
+    /// Only exports tasks which are (transitively) called or read by one of these tasks,
+    /// including the tasks themselves. Exports all tasks when empty.
+    pub roots: Vec<TaskId>,
+    /// Merges all tasks of the same function into one node. Edges between the merged nodes are
+    /// counted instead of repeated.
+    pub collapse_by_function: bool,
+}
+
+/// What a [`GraphEdge`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphEdgeKind {
+    /// The task called the other task.
+    Child,
+    /// The task read the output of the other task.
+    Output,
+    /// The task read a cell of the other task.
+    Cell,
+    /// The task read the collectibles emitted by the other task and its children.
+    Collectibles,
+}
+
+/// A node of a [`TaskGraph`], either a task or all tasks of a function when collapsed.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+    pub tasks: Vec<TaskId>,
+}
+
+/// An edge of a [`TaskGraph`], from the task which depends on `to` to `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct GraphEdge {
+    /// The index of the node in [`TaskGraph::nodes`].
+    pub from: usize,
+    /// The index of the node in [`TaskGraph::nodes`].
+    pub to: usize,
+    pub kind: GraphEdgeKind,
+    /// The cell or the collectible trait which was read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// How often the edge appears between the tasks of collapsed nodes.
+    #[serde(skip_serializing_if = "is_one")]
+    pub count: usize,
+}
+
+fn is_one(count: &usize) -> bool {
+    *count == 1
+}
+
+/// The task graph exported by [`TurboTasks::export_graph`][crate::TurboTasks::export_graph].
+/// Edges to tasks which weren't exported are omitted.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaskGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl TaskGraph {
+    pub(crate) fn new(tasks: Vec<TaskGraphNode>, filter: &GraphExportFilter) -> Self {
+        let tasks = select_tasks(tasks, filter);
+
+        let mut graph = TaskGraph::default();
+        let mut node_of_task = FxHashMap::default();
+        let mut node_of_name = FxHashMap::default();
+        for task in &tasks {
+            let index = if filter.collapse_by_function {
+                *node_of_name.entry(task.name.as_str()).or_insert_with(|| {
+                    graph.nodes.push(GraphNode {
+                        name: task.name.clone(),
+                        inputs: Vec::new(),
+                        tasks: Vec::new(),
+                    });
+                    graph.nodes.len() - 1
+                })
+            } else {
+                graph.nodes.push(GraphNode {
+                    name: task.name.clone(),
+                    inputs: task.inputs.clone(),
+                    tasks: Vec::new(),
+                });
+                graph.nodes.len() - 1
+            };
+            graph.nodes[index].tasks.push(task.task);
+            node_of_task.insert(task.task, index);
+        }
+
+        let mut edge_index = FxHashMap::default();
+        for task in &tasks {
+            let from = node_of_task[&task.task];
+            let edges = task
+                .children
+                .iter()
+                .map(|&child| (child, GraphEdgeKind::Child, None))
+                .chain(task.reads.iter().map(|read| match *read {
+                    TaskGraphRead::Output(task) => (task, GraphEdgeKind::Output, None),
+                    TaskGraphRead::Cell(task, cell) => {
+                        (task, GraphEdgeKind::Cell, Some(cell.to_string()))
+                    }
+                    TaskGraphRead::Collectibles(task, trait_type) => (
+                        task,
+                        GraphEdgeKind::Collectibles,
+                        Some(registry::get_trait(trait_type).name.clone()),
+                    ),
+                }));
+            for (to, kind, label) in edges {
+                let Some(&to) = node_of_task.get(&to) else {
+                    continue;
+                };
+                let key = (from, to, kind, label.clone());
+                if let Some(&index) = edge_index.get(&key) {
+                    let edge: &mut GraphEdge = &mut graph.edges[index];
+                    edge.count += 1;
+                } else {
+                    edge_index.insert(key, graph.edges.len());
+                    graph.edges.push(GraphEdge {
+                        from,
+                        to,
+                        kind,
+                        label,
+                        count: 1,
+                    });
+                }
+            }
+        }
+        graph
+    }
+
+    /// Serializes the graph into compact JSON, i.e. without whitespace and without empty fields.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Renders the graph in the DOT language of Graphviz. Calls are solid edges, reads are dashed
+    /// edges. Long inputs are shortened.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot).unwrap();
+        dot
+    }
+
+    fn write_dot(&self, out: &mut String) -> fmt::Result {
+        const MAX_INPUT_LEN: usize = 80;
+
+        writeln!(out, "digraph turbo_tasks {{")?;
+        writeln!(out, "  node [shape=box];")?;
+        for (index, node) in self.nodes.iter().enumerate() {
+            let mut label = node.name.clone();
+            if node.tasks.len() > 1 {
+                write!(label, " ({} tasks)", node.tasks.len())?;
+            }
+            for input in &node.inputs {
+                label.push('\n');
+                if input.len() > MAX_INPUT_LEN {
+                    let mut end = MAX_INPUT_LEN;
+                    while !input.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    label.push_str(&input[..end]);
+                    label.push('…');
+                } else {
+                    label.push_str(input);
+                }
+            }
+            writeln!(out, "  n{index} [label=\"{}\"];", escape_dot(&label))?;
+        }
+        for edge in &self.edges {
+            let mut label = edge.label.clone().unwrap_or_default();
+            if edge.count > 1 {
+                if !label.is_empty() {
+                    label.push(' ');
+                }
+                write!(label, "×{}", edge.count)?;
+            }
+            write!(out, "  n{} -> n{}", edge.from, edge.to)?;
+            let style = match edge.kind {
+                GraphEdgeKind::Child => "solid",
+                GraphEdgeKind::Output | GraphEdgeKind::Cell => "dashed",
+                GraphEdgeKind::Collectibles => "dotted",
+            };
+            if label.is_empty() {
+                writeln!(out, " [style={style}];")?;
+            } else {
+                writeln!(out, " [style={style}, label=\"{}\"];", escape_dot(&label))?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
+
+/// Applies the name and root filters, keeping the order of the tasks.
+fn select_tasks(mut tasks: Vec<TaskGraphNode>, filter: &GraphExportFilter) -> Vec<TaskGraphNode> {
+    tasks.sort_unstable_by_key(|task| task.task);
+    if !filter.roots.is_empty() {
+        let index_of_task: FxHashMap<_, _> = tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| (task.task, index))
+            .collect();
+        let mut reachable = FxHashSet::default();
+        let mut queue = filter.roots.clone();
+        while let Some(task) = queue.pop() {
+            if !reachable.insert(task) {
+                continue;
+            }
+            if let Some(&index) = index_of_task.get(&task) {
+                let node = &tasks[index];
+                queue.extend(node.children.iter().copied());
+                queue.extend(node.reads.iter().map(TaskGraphRead::task));
+            }
+        }
+        tasks.retain(|task| reachable.contains(&task.task));
+    }
+    if !filter.function_names.is_empty() {
+        tasks.retain(|task| {
+            filter
+                .function_names
+                .iter()
+                .any(|name| task.name.contains(name.as_str()))
+        });
+    }
+    tasks
+}
+
+fn escape_dot(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(task: u32, name: &str, children: &[u32], reads: &[u32]) -> TaskGraphNode {
+        TaskGraphNode {
+            task: TaskId::from(task),
+            name: name.to_string(),
+            inputs: vec![format!("\"input {task}\"")],
+            reads: reads
+                .iter()
+                .map(|&task| TaskGraphRead::Output(TaskId::from(task)))
+                .collect(),
+            children: children.iter().map(|&task| TaskId::from(task)).collect(),
+            dirty: false,
+        }
+    }
+
+    fn graph() -> Vec<TaskGraphNode> {
+        vec![
+            node(1, "app::root", &[2, 3], &[2, 3]),
+            node(2, "app::parse", &[4], &[4]),
+            node(3, "app::parse", &[4], &[4]),
+            node(4, "app::read", &[], &[]),
+            node(5, "app::other", &[4], &[4]),
+        ]
+    }
+
+    #[test]
+    fn exports_all_tasks() {
+        let graph = TaskGraph::new(graph(), &GraphExportFilter::default());
+        assert_eq!(graph.nodes.len(), 5);
+        assert_eq!(graph.edges.len(), 10);
+        assert_eq!(graph.nodes[1].inputs, ["\"input 2\""]);
+    }
+
+    #[test]
+    fn filters_by_roots_and_names() {
+        let graph = TaskGraph::new(
+            graph(),
+            &GraphExportFilter {
+                function_names: vec!["parse".into(), "read".into()],
+                roots: vec![TaskId::from(2)],
+                ..Default::default()
+            },
+        );
+        let tasks: Vec<_> = graph.nodes.iter().map(|node| node.tasks[0]).collect();
+        assert_eq!(tasks, [TaskId::from(2), TaskId::from(4)]);
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn collapses_by_function() {
+        let graph = TaskGraph::new(
+            graph(),
+            &GraphExportFilter {
+                collapse_by_function: true,
+                ..Default::default()
+            },
+        );
+        let names: Vec<_> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["app::root", "app::parse", "app::read", "app::other"]
+        );
+        assert!(graph.nodes[1].inputs.is_empty());
+        let parse_calls_read = graph
+            .edges
+            .iter()
+            .find(|edge| edge.from == 1 && edge.to == 2 && edge.kind == GraphEdgeKind::Child)
+            .unwrap();
+        assert_eq!(parse_calls_read.count, 2);
+        assert!(graph
+            .to_dot()
+            .contains("n1 -> n2 [style=solid, label=\"×2\"];"));
+        assert!(graph.to_json().unwrap().contains("\"count\":2"));
+    }
+}
@@ -0,0 +1,286 @@
+use std::{borrow::Borrow, fmt::Debug, hash::Hash, mem::take};
+
+use auto_hash_map::AutoSet;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    get_invalidator, mark_stateful, trace::TraceRawVcs, FxIndexMap, Invalidator,
+    SerializationInvalidator,
+};
+
+#[derive(Serialize, Deserialize)]
+struct Entry<V> {
+    /// `None` when the key was read while it wasn't in the map, so that the readers are
+    /// invalidated when it's inserted.
+    value: Option<V>,
+    invalidators: AutoSet<Invalidator>,
+}
+
+impl<V> Entry<V> {
+    fn invalidate(&mut self) {
+        for invalidator in take(&mut self.invalidators) {
+            invalidator.invalidate();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash, V: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>"
+))]
+struct VcHashMapInner<K, V> {
+    entries: FxIndexMap<K, Entry<V>>,
+    /// Readers of the set of keys, invalidated when a key is inserted or removed.
+    keys_invalidators: AutoSet<Invalidator>,
+}
+
+impl<K, V> VcHashMapInner<K, V> {
+    fn invalidate_keys(&mut self) {
+        for invalidator in take(&mut self.keys_invalidators) {
+            invalidator.invalidate();
+        }
+    }
+}
+
+/// A map stored in a value whose readers depend on individual keys.
+///
+/// A `HashMap` stored in a cell invalidates all readers when any entry changes. Like
+/// [`State`][crate::State], a `VcHashMap` is shared between the tasks writing and reading it
+/// instead, and [`VcHashMap::get`] only registers a dependency on the key that was read. A change
+/// to one entry only invalidates the readers of that entry, and readers of [`VcHashMap::keys`]
+/// when keys are added or removed.
+///
+/// ```ignore
+/// #[turbo_tasks::value(eq = "manual", serialization = "none")]
+/// struct ModuleInfos(VcHashMap<RcStr, ModuleInfo>);
+///
+/// #[turbo_tasks::function]
+/// async fn module_info(infos: Vc<ModuleInfos>, path: RcStr) -> Result<Vc<ModuleInfo>> {
+///     let info = infos.await?.0.get(&path).context("unknown module")?;
+///     Ok(info.cell())
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash, V: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>"
+))]
+pub struct VcHashMap<K, V> {
+    serialization_invalidator: SerializationInvalidator,
+    inner: Mutex<VcHashMapInner<K, V>>,
+}
+
+impl<K: Debug, V: Debug> Debug for VcHashMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_map()
+            .entries(
+                inner
+                    .entries
+                    .iter()
+                    .filter_map(|(key, entry)| Some((key, entry.value.as_ref()?))),
+            )
+            .finish()
+    }
+}
+
+impl<K: TraceRawVcs, V: TraceRawVcs> TraceRawVcs for VcHashMap<K, V> {
+    fn trace_raw_vcs(&self, trace_context: &mut crate::trace::TraceRawVcsContext) {
+        for (key, entry) in self.inner.lock().entries.iter() {
+            key.trace_raw_vcs(trace_context);
+            entry.value.trace_raw_vcs(trace_context);
+        }
+    }
+}
+
+impl<K, V> Default for VcHashMap<K, V> {
+    fn default() -> Self {
+        // Need to be explicit to ensure marking as stateful.
+        Self::new()
+    }
+}
+
+impl<K, V> PartialEq for VcHashMap<K, V> {
+    fn eq(&self, _other: &Self) -> bool {
+        false
+    }
+}
+impl<K, V> Eq for VcHashMap<K, V> {}
+
+impl<K, V> VcHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            serialization_invalidator: mark_stateful(),
+            inner: Mutex::new(VcHashMapInner {
+                entries: FxIndexMap::default(),
+                keys_invalidators: AutoSet::new(),
+            }),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> VcHashMap<K, V> {
+    /// Returns a clone of the value of `key`.
+    ///
+    /// The current task is registered as dependency of `key` only, and will be invalidated when
+    /// its value changes, or when it's inserted or removed.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut inner = self.inner.lock();
+        let index = match inner.entries.get_index_of(key) {
+            Some(index) => index,
+            None => {
+                inner
+                    .entries
+                    .insert_full(
+                        key.clone(),
+                        Entry {
+                            value: None,
+                            invalidators: AutoSet::new(),
+                        },
+                    )
+                    .0
+            }
+        };
+        let entry = &mut inner.entries[index];
+        entry.invalidators.insert(get_invalidator());
+        entry.value.clone()
+    }
+
+    /// Returns a clone of the value of `key` without registering a dependency.
+    pub fn get_untracked<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        V: Clone,
+    {
+        self.inner
+            .lock()
+            .entries
+            .get(key)
+            .and_then(|entry| entry.value.clone())
+    }
+
+    /// Returns all keys of the map, in insertion order.
+    ///
+    /// The current task is registered as dependency of the set of keys, but not of their values.
+    pub fn keys(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        let mut inner = self.inner.lock();
+        inner.keys_invalidators.insert(get_invalidator());
+        inner
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.value.is_some())
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Removes `key` from the map. Readers of the key and of [`VcHashMap::keys`] are invalidated
+    /// when it was in the map.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let value = {
+            let mut inner = self.inner.lock();
+            let entry = inner.entries.get_mut(key)?;
+            let value = entry.value.take()?;
+            entry.invalidate();
+            // The readers are invalidated, so there is nobody left to notify when the key is
+            // inserted again.
+            inner.entries.shift_remove(key);
+            inner.invalidate_keys();
+            value
+        };
+        self.serialization_invalidator.invalidate();
+        Some(value)
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq> VcHashMap<K, V> {
+    /// Sets the value of `key`. Readers of the key are only invalidated when the value differs,
+    /// readers of [`VcHashMap::keys`] only when the key is new.
+    pub fn insert(&self, key: K, value: V) {
+        {
+            let mut inner = self.inner.lock();
+            let entry = inner.entries.entry(key).or_insert_with(|| Entry {
+                value: None,
+                invalidators: AutoSet::new(),
+            });
+            if entry.value.as_ref() == Some(&value) {
+                return;
+            }
+            let is_new = entry.value.is_none();
+            entry.value = Some(value);
+            entry.invalidate();
+            if is_new {
+                inner.invalidate_keys();
+            }
+        }
+        self.serialization_invalidator.invalidate();
+    }
+
+    /// Replaces the whole map with `entries`, e.g. when the task producing the map is re-executed.
+    /// Only readers of entries that were added, changed or removed are invalidated.
+    pub fn replace(&self, entries: impl IntoIterator<Item = (K, V)>) {
+        let mut changed = false;
+        {
+            let mut inner = self.inner.lock();
+            let mut keys_changed = false;
+            let mut present = AutoSet::<usize>::new();
+            for (key, value) in entries {
+                let index = match inner.entries.get_index_of(&key) {
+                    Some(index) => index,
+                    None => {
+                        inner
+                            .entries
+                            .insert_full(
+                                key,
+                                Entry {
+                                    value: None,
+                                    invalidators: AutoSet::new(),
+                                },
+                            )
+                            .0
+                    }
+                };
+                present.insert(index);
+                let entry = &mut inner.entries[index];
+                if entry.value.as_ref() == Some(&value) {
+                    continue;
+                }
+                keys_changed |= entry.value.is_none();
+                entry.value = Some(value);
+                entry.invalidate();
+                changed = true;
+            }
+            let mut index = 0;
+            inner.entries.retain(|_, entry| {
+                let present = present.contains(&index);
+                index += 1;
+                if !present && entry.value.take().is_some() {
+                    entry.invalidate();
+                    keys_changed = true;
+                    changed = true;
+                }
+                // Keep missing keys which are read, to invalidate the readers on insertion.
+                present || !entry.invalidators.is_empty()
+            });
+            if keys_changed {
+                inner.invalidate_keys();
+            }
+        }
+        if changed {
+            self.serialization_invalidator.invalidate();
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use std::{
+    cell::Cell,
     fmt::{Debug, Display},
     mem::transmute_copy,
     num::{NonZero, NonZeroU64, TryFromIntError},
     ops::Deref,
 };
 
-use serde::{de::Visitor, Deserialize, Serialize};
+use serde::{de::Visitor, ser::Error, Deserialize, Serialize};
 
 use crate::{registry, TaskPersistence};
 
@@ -72,7 +73,7 @@ macro_rules! define_id {
     };
 }
 
-define_id!(TaskId: u32, derive(Serialize, Deserialize), serde(transparent));
+define_id!(TaskId: u32, derive(Deserialize), serde(transparent));
 define_id!(FunctionId: u32);
 define_id!(ValueTypeId: u32);
 define_id!(TraitTypeId: u32);
@@ -112,6 +113,51 @@ impl TaskId {
             TaskPersistence::Persistent
         }
     }
+
+    /// A hash of the function and the inputs of the task. Unlike the id, which depends on the
+    /// order in which tasks are created, it's the same in every process, so it can be used to
+    /// correlate tasks between traces and caches of different runs. `None` for transient tasks
+    /// and tasks with inputs which aren't serializable.
+    ///
+    /// Must be called within a turbo-tasks context.
+    pub fn stable_hash(self) -> Option<u64> {
+        crate::manager::with_turbo_tasks(|tt| tt.task_stable_hash(self))
+    }
+}
+
+thread_local! {
+    static SERIALIZE_STABLE_HASHES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Serializes [`TaskId`]s as the [`TaskId::stable_hash`] of their task instead of the id within
+/// `f` on the current thread. Serialization fails for tasks without a stable hash.
+pub(crate) fn with_stable_task_hashes<R>(f: impl FnOnce() -> R) -> R {
+    struct Guard(bool);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            SERIALIZE_STABLE_HASHES.set(self.0);
+        }
+    }
+
+    let _guard = Guard(SERIALIZE_STABLE_HASHES.replace(true));
+    f()
+}
+
+impl Serialize for TaskId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if SERIALIZE_STABLE_HASHES.get() {
+            let stable_hash = self
+                .stable_hash()
+                .ok_or_else(|| S::Error::custom(format!("{self:?} has no stable hash")))?;
+            serializer.serialize_u64(stable_hash)
+        } else {
+            serializer.serialize_u32(**self)
+        }
+    }
 }
 
 macro_rules! make_serializable {
@@ -0,0 +1,115 @@
+use std::{
+    any::{type_name, Any},
+    borrow::Cow,
+};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::{registry, trace::TraceRawVcs, RawVc};
+
+/// Lets devtools inspect the value in a cell without knowing its type, see [introspect].
+///
+/// It is implemented for every `#[turbo_tasks::value]` type, which registers it with the
+/// [`ValueType`][crate::ValueType].
+pub trait IntrospectValue: TraceRawVcs {
+    /// A short name to show for the value, the name of its type by default.
+    fn display_name(&self) -> Cow<'static, str> {
+        short_type_name(type_name::<Self>()).into()
+    }
+
+    /// The cells and task outputs referenced by the value.
+    fn children(&self) -> Vec<RawVc> {
+        self.get_raw_vcs()
+    }
+}
+
+pub(crate) type DisplayNameFn = fn(&(dyn Any + Send + Sync)) -> Option<Cow<'static, str>>;
+pub(crate) type ChildrenFn = fn(&(dyn Any + Send + Sync)) -> Vec<RawVc>;
+
+pub(crate) fn display_name_of<T: IntrospectValue + 'static>(
+    value: &(dyn Any + Send + Sync),
+) -> Option<Cow<'static, str>> {
+    value.downcast_ref::<T>().map(T::display_name)
+}
+
+pub(crate) fn children_of<T: IntrospectValue + 'static>(
+    value: &(dyn Any + Send + Sync),
+) -> Vec<RawVc> {
+    value.downcast_ref::<T>().map_or_else(Vec::new, T::children)
+}
+
+/// Strips the module paths from a type name, including the ones of generic arguments.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (index, c) in name.char_indices() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            if c == ':' {
+                segment_start = index + 1;
+            }
+            continue;
+        }
+        short.push_str(&name[segment_start..index]);
+        short.push(c);
+        segment_start = index + c.len_utf8();
+    }
+    short.push_str(&name[segment_start..]);
+    short
+}
+
+/// What [introspect] found out about a cell.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellIntrospection {
+    /// The cell, after resolving task outputs.
+    pub cell: RawVc,
+    /// The global name of the value type, which is the same in every process.
+    pub value_type: &'static str,
+    pub display_name: Cow<'static, str>,
+    /// See [`ValueType::estimate_size`][crate::ValueType::estimate_size]. 0 for an empty cell.
+    pub estimated_size: usize,
+    /// The cells and task outputs referenced by the value, empty for value types without
+    /// [IntrospectValue].
+    pub children: Vec<RawVc>,
+}
+
+/// Resolves `vc` and inspects the value in the cell, for devtools which walk the graph of cells.
+/// The cell is read like an awaited `Vc`, so the calling task is recomputed when it changes.
+pub async fn introspect(vc: RawVc) -> Result<CellIntrospection> {
+    let cell = vc.resolve().await?;
+    if !matches!(cell, RawVc::TaskCell(..)) {
+        bail!("Only cells of tasks can be introspected, but got {cell:?}");
+    }
+    let content = cell.into_read().await?;
+    let value_type_id = content.0;
+    let value_type = registry::get_value_type(value_type_id);
+    let value = content.1 .0.as_ref().map(|value| &*value.0);
+    Ok(CellIntrospection {
+        cell,
+        value_type: registry::get_value_type_global_name(value_type_id),
+        display_name: value
+            .and_then(|value| value_type.introspect_display_name(value))
+            .unwrap_or_else(|| short_type_name(&value_type.name).into()),
+        estimated_size: value.map_or(0, |value| value_type.estimate_size(value)),
+        children: value.map_or_else(Vec::new, |value| value_type.introspect_children(value)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::short_type_name;
+
+    #[test]
+    fn strips_module_paths() {
+        assert_eq!(short_type_name("turbo_tasks::RawVc"), "RawVc");
+        assert_eq!(
+            short_type_name("alloc::vec::Vec<turbo_tasks::vc::Vc<turbo_rcstr::RcStr>>"),
+            "Vec<Vc<RcStr>>"
+        );
+        assert_eq!(
+            short_type_name("(u32, core::option::Option<u8>)"),
+            "(u32, Option<u8>)"
+        );
+    }
+}
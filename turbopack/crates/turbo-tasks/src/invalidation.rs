@@ -10,10 +10,12 @@ use anyhow::Result;
 use indexmap::map::Entry;
 use serde::{de::Visitor, Deserialize, Serialize};
 use tokio::runtime::Handle;
+use turbo_tasks_hash::HashDigest;
 
 use crate::{
     magic_any::HasherMut,
     manager::{current_task, with_turbo_tasks},
+    raw_vc::CellId,
     trace::TraceRawVcs,
     util::StaticOrArc,
     FxIndexMap, FxIndexSet, TaskId, TurboTasksApi,
@@ -78,6 +80,57 @@ impl Invalidator {
     }
 }
 
+/// Invalidates many tasks at once. Compared to calling [`Invalidator::invalidate`] for each of
+/// them, this marks all tasks dirty in a single backend operation, so the aggregation graph is
+/// only updated once. This is useful when a whole directory subtree changes, e.g. on a branch
+/// switch.
+pub fn invalidate_all(invalidators: impl IntoIterator<Item = Invalidator>) {
+    for (turbo_tasks, handle, tasks) in group_by_turbo_tasks(invalidators) {
+        let _guard = handle.enter();
+        if let Some(turbo_tasks) = turbo_tasks.upgrade() {
+            turbo_tasks.invalidate_many(&tasks);
+        }
+    }
+}
+
+/// Like [`invalidate_all`], but reports a single reason for the whole batch.
+pub fn invalidate_all_with_reason<T: InvalidationReason>(
+    invalidators: impl IntoIterator<Item = Invalidator>,
+    reason: T,
+) {
+    let reason: StaticOrArc<dyn InvalidationReason> =
+        (Arc::new(reason) as Arc<dyn InvalidationReason>).into();
+    for (turbo_tasks, handle, tasks) in group_by_turbo_tasks(invalidators) {
+        let _guard = handle.enter();
+        if let Some(turbo_tasks) = turbo_tasks.upgrade() {
+            turbo_tasks.invalidate_many_with_reason(&tasks, reason.clone());
+        }
+    }
+}
+
+fn group_by_turbo_tasks(
+    invalidators: impl IntoIterator<Item = Invalidator>,
+) -> Vec<(Weak<dyn TurboTasksApi>, Handle, Vec<TaskId>)> {
+    // There is usually only a single TurboTasks instance, so a linear search is fine
+    let mut groups: Vec<(Weak<dyn TurboTasksApi>, Handle, Vec<TaskId>)> = Vec::new();
+    for Invalidator {
+        task,
+        turbo_tasks,
+        handle,
+    } in invalidators
+    {
+        if let Some((_, _, tasks)) = groups
+            .iter_mut()
+            .find(|(tt, _, _)| Weak::ptr_eq(tt, &turbo_tasks))
+        {
+            tasks.push(task);
+        } else {
+            groups.push((turbo_tasks, handle, vec![task]));
+        }
+    }
+    groups
+}
+
 impl Hash for Invalidator {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.task.hash(state);
@@ -315,3 +368,74 @@ impl Display for InvalidationReasonSet {
         Ok(())
     }
 }
+
+/// What made the backend invalidate a task, as recorded by backends which support it. See
+/// [`why_invalidated`][crate::why_invalidated].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidationCause {
+    /// A cell the task read was assigned a different value. The hashes are computed from the
+    /// serialized content, and are `None` when the cell was empty or its value isn't serializable.
+    CellChanged {
+        task: TaskId,
+        cell: CellId,
+        old_hash: Option<HashDigest>,
+        new_hash: Option<HashDigest>,
+    },
+    /// The output of a task the task read changed.
+    OutputChanged { task: TaskId },
+    /// The task was invalidated from outside of the task graph, e.g. by a file watcher through an
+    /// [`Invalidator`]. Contains the [`InvalidationReason`] when one was given.
+    External { reason: Option<String> },
+}
+
+impl InvalidationCause {
+    /// The task whose change caused the invalidation, if any.
+    pub fn source_task(&self) -> Option<TaskId> {
+        match self {
+            InvalidationCause::CellChanged { task, .. }
+            | InvalidationCause::OutputChanged { task } => Some(*task),
+            InvalidationCause::External { .. } => None,
+        }
+    }
+}
+
+impl Display for InvalidationCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hash = |hash: &Option<HashDigest>| match hash {
+            Some(hash) => hash.to_hex(),
+            None => "-".to_string(),
+        };
+        match self {
+            InvalidationCause::CellChanged {
+                task,
+                cell,
+                old_hash,
+                new_hash,
+            } => write!(
+                f,
+                "{cell} of {task} changed ({} -> {})",
+                hash(old_hash),
+                hash(new_hash)
+            ),
+            InvalidationCause::OutputChanged { task } => write!(f, "output of {task} changed"),
+            InvalidationCause::External {
+                reason: Some(reason),
+            } => write!(f, "{reason}"),
+            InvalidationCause::External { reason: None } => write!(f, "invalidated externally"),
+        }
+    }
+}
+
+/// A step of the chain returned by [`why_invalidated`][crate::why_invalidated].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidationStep {
+    pub task: TaskId,
+    pub description: String,
+    pub cause: InvalidationCause,
+}
+
+impl Display for InvalidationStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.description, self.task, self.cause)
+    }
+}
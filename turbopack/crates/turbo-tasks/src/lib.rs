@@ -37,17 +37,29 @@
 #![feature(impl_trait_in_assoc_type)]
 
 pub mod backend;
+pub mod blob;
+mod cancellation;
 mod capture_future;
 mod collectibles;
 mod completion;
 pub mod debug;
+mod deterministic;
 mod display;
+mod dry_run;
+mod durability;
 pub mod duration_span;
 mod effect;
+mod estimate_size;
 pub mod event;
+mod event_bus;
+pub mod feature_flags;
+mod gc_policy;
 pub mod graph;
+pub mod graph_export;
+mod hash_map;
 mod id;
 mod id_factory;
+pub mod introspect;
 mod invalidation;
 mod join_iter_ext;
 mod key_value_pair;
@@ -59,17 +71,24 @@ mod native_function;
 mod no_move_vec;
 mod once_map;
 mod output;
+mod parallelism;
 pub mod persisted_graph;
 pub mod primitives;
+mod priority;
+mod progress;
 mod raw_vc;
 mod read_ref;
 pub mod registry;
 mod scope;
 mod serialization_invalidation;
+pub mod sharded;
 mod shrink_to_fit;
 pub mod small_duration;
 mod state;
+mod stream;
 pub mod task;
+mod task_local;
+mod timeout;
 pub mod trace;
 mod trait_helpers;
 mod trait_ref;
@@ -78,43 +97,60 @@ pub mod util;
 mod value;
 mod value_type;
 mod vc;
+mod wait_graph;
 
 use std::hash::BuildHasherDefault;
 
 pub use anyhow::{Error, Result};
 use auto_hash_map::AutoSet;
-pub use collectibles::CollectiblesSource;
+pub use cancellation::{Canceled, CancellationToken};
+pub use collectibles::{filter_collectibles, CollectiblePosition, CollectiblesSource, Emits};
 pub use completion::{Completion, Completions};
 pub use display::ValueToString;
+pub use dry_run::DryRunReport;
+pub use durability::{mark_durability, Durability};
 pub use effect::{apply_effects, effect, get_effects, Effects};
+pub use estimate_size::{EstimateSize, MemoryReport, TaskMemoryUsage};
+pub use event_bus::{EventBus, EventSubscription, GcCompleted, PersistFlushed};
+pub use gc_policy::{GcEviction, GcPolicy};
+pub use hash_map::VcHashMap;
 pub use id::{
     ExecutionId, FunctionId, LocalTaskId, SessionId, TaskId, TraitTypeId, ValueTypeId,
     TRANSIENT_TASK_BIT,
 };
 pub use invalidation::{
-    get_invalidator, DynamicEqHash, InvalidationReason, InvalidationReasonKind,
-    InvalidationReasonSet, Invalidator,
+    get_invalidator, invalidate_all, invalidate_all_with_reason, DynamicEqHash, InvalidationCause,
+    InvalidationReason, InvalidationReasonKind, InvalidationReasonSet, InvalidationStep,
+    Invalidator,
 };
 pub use join_iter_ext::{JoinIterExt, TryFlatJoinIterExt, TryJoinIterExt};
 pub use key_value_pair::KeyValuePair;
 pub use magic_any::MagicAny;
 pub use manager::{
-    dynamic_call, dynamic_this_call, emit, mark_finished, mark_session_dependent, mark_stateful,
-    prevent_gc, run_once, run_once_with_reason, spawn_blocking, spawn_thread, trait_call,
-    turbo_tasks, turbo_tasks_scope, CurrentCellRef, ReadConsistency, TaskPersistence, TurboTasks,
-    TurboTasksApi, TurboTasksBackendApi, TurboTasksBackendApiExt, TurboTasksCallApi, Unused,
-    UpdateInfo,
+    cancellation_token, check_canceled, dynamic_call, dynamic_this_call, emit, emit_ordered,
+    is_canceled, mark_finished, mark_session_dependent, mark_stateful, on_execution_completed,
+    output_write_finished, output_write_started, prefetch, prevent_gc, publish_event, run_local,
+    run_once, run_once_with_reason, spawn_blocking, spawn_thread, trait_call, turbo_tasks,
+    turbo_tasks_scope, why_invalidated, CurrentCellRef, ReadConsistency, TaskPersistence,
+    TurboTasks, TurboTasksApi, TurboTasksBackendApi, TurboTasksBackendApiExt, TurboTasksBuilder,
+    TurboTasksCallApi, Unused, UpdateInfo,
 };
 pub use native_function::{FunctionMeta, NativeFunction};
 pub use output::OutputContent;
+pub use parallelism::ParallelismLimit;
+pub use priority::{current_priority, with_priority, TaskPriority};
+pub use progress::{Progress, ProgressEvent, ProgressReporter, ProgressSubscription};
 pub use raw_vc::{CellId, RawVc, ReadRawVcFuture, ResolveTypeError};
-pub use read_ref::ReadRef;
+pub use read_ref::{MappedReadRef, ReadRef};
 use rustc_hash::FxHasher;
 pub use scope::scope;
 pub use serialization_invalidation::SerializationInvalidator;
 pub use shrink_to_fit::ShrinkToFit;
-pub use state::{State, TransientState};
+pub use state::{state_transaction, State, StateConflict, StateRef, StateVersion, TransientState};
+pub use stream::VcStream;
 pub use task::{task_input::TaskInput, SharedReference, TypedSharedReference};
+pub use task_local::TaskLocal;
+pub use timeout::{with_timeout, TaskTimeout, WaitingTask};
 pub use trait_ref::{IntoTraitRef, TraitRef};
 pub use turbo_tasks_macros::{function, value_impl, value_trait, KeyValuePair, TaskInput};
 pub use value::{TransientInstance, TransientValue, Value};
@@ -122,7 +158,7 @@ pub use value_type::{TraitMethod, TraitType, ValueType};
 pub use vc::{
     Dynamic, ResolvedValue, ResolvedVc, TypedForInput, Upcast, ValueDefault, Vc, VcCast,
     VcCellNewMode, VcCellSharedMode, VcDefaultRead, VcRead, VcTransparentRead, VcValueTrait,
-    VcValueTraitCast, VcValueType, VcValueTypeCast,
+    VcValueTraitCast, VcValueType, VcValueTypeCast, WeakVc,
 };
 
 pub type FxIndexSet<T> = indexmap::IndexSet<T, BuildHasherDefault<FxHasher>>;
@@ -258,6 +294,13 @@ macro_rules! fxindexset {
 ///
 /// [repr-transparent]: https://doc.rust-lang.org/nomicon/other-reprs.html#reprtransparent
 ///
+/// ## `sharded`
+///
+/// Only valid together with `transparent`. Implements [`Shardable`][sharded::Shardable], so the
+/// inner collection (e.g. a [`Vec`] or [`FxIndexMap`]) can be stored in multiple cells with
+/// [`Sharded`][sharded::Sharded]. This is useful for very large collections, which are otherwise
+/// compared, invalidated and serialized as a whole.
+///
 /// ## `resolved`
 ///
 /// Applies the [`#[derive(ResolvedValue)]`][macro@ResolvedValue] macro.
@@ -265,6 +308,29 @@ macro_rules! fxindexset {
 /// Indicates that this struct has no fields containing [`Vc`] by implementing the [`ResolvedValue`]
 /// marker trait. In order to safely implement [`ResolvedValue`], this inserts compile-time
 /// assertions that every field in this struct has a type that is also a [`ResolvedValue`].
+///
+/// ## Generic types
+///
+/// Value types can have type parameters:
+///
+/// ```
+/// # #![feature(arbitrary_self_types)]
+//  # #![feature(arbitrary_self_types_pointers)]
+/// # use turbo_tasks::{ResolvedVc, VcValueType};
+/// #[turbo_tasks::value]
+/// struct Pair<T: VcValueType> {
+///     a: ResolvedVc<T>,
+///     b: ResolvedVc<T>,
+/// }
+/// ```
+///
+/// [`VcValueType`] is implemented for every instantiation which implements the traits the value
+/// type requires, e.g. [`PartialEq`] for `cell = "shared"` and [`serde::Serialize`] for
+/// `serialization = "auto"`. Every instantiation is registered as its own value type the first
+/// time it's used.
+///
+/// Generic value types don't implement [`ValueDebug`][crate::debug::ValueDebug] and can't have
+/// `#[turbo_tasks::value_impl]` blocks, as turbo-tasks functions can't be generic.
 #[rustfmt::skip]
 pub use turbo_tasks_macros::value;
 
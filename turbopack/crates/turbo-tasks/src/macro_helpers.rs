@@ -1,7 +1,12 @@
 //! Runtime helpers for [turbo-tasks-macro].
-use std::ops::{Deref, DerefMut};
+use std::{
+    any::Any,
+    mem::size_of,
+    ops::{Deref, DerefMut},
+};
 
 pub use async_trait::async_trait;
+pub use auto_hash_map::AutoSet;
 pub use once_cell::sync::{Lazy, OnceCell};
 pub use serde;
 pub use tracing;
@@ -11,8 +16,8 @@ pub use super::{
     manager::{find_cell_by_type, notify_scheduled_tasks, spawn_detached_for_testing},
 };
 use crate::{
-    debug::ValueDebugFormatString, shrink_to_fit::ShrinkToFit, task::TaskOutput, RawVc,
-    ResolvedValue, TaskInput, TaskPersistence, Vc,
+    debug::ValueDebugFormatString, estimate_size::EstimateSize, shrink_to_fit::ShrinkToFit,
+    task::TaskOutput, RawVc, ResolvedValue, TaskInput, TaskPersistence, Vc,
 };
 
 #[inline(never)]
@@ -115,3 +120,66 @@ impl<T> ShrinkToFitFallbackNoop<'_, T> {
     /// implement [`ShrinkToFit`].
     pub fn shrink_to_fit(&mut self) {}
 }
+
+/// A wrapper type that uses the [autoderef specialization hack][autoderef] to call
+/// [`EstimateSize::estimate_heap_size`] on types that implement [`EstimateSize`].
+///
+/// Types that do not implement [`EstimateSize`] are counted as owning no heap memory, see
+/// [`EstimateSizeFallbackZero::estimate_heap_size`].
+///
+/// This is used by the derive macro for [`EstimateSize`], which is called by the
+/// [turbo_tasks::value][crate::value] macro.
+///
+/// [autoderef]: http://lukaskalbertodt.github.io/2019/12/05/generalized-autoref-based-specialization.html
+pub struct EstimateSizeDerefSpecialization<'a, T> {
+    inner: EstimateSizeFallbackZero<'a, T>,
+}
+
+impl<'a, T> EstimateSizeDerefSpecialization<'a, T> {
+    pub fn new(real: &'a T) -> Self {
+        Self {
+            inner: EstimateSizeFallbackZero { real },
+        }
+    }
+}
+
+impl<T> EstimateSizeDerefSpecialization<'_, T>
+where
+    T: EstimateSize,
+{
+    pub fn estimate_heap_size(&self) -> usize {
+        // call the real `EstimateSize::estimate_heap_size` method
+        self.inner.real.estimate_heap_size()
+    }
+}
+
+impl<'a, T> Deref for EstimateSizeDerefSpecialization<'a, T> {
+    type Target = EstimateSizeFallbackZero<'a, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+// Implements `EstimateSize` by counting no heap memory.
+pub struct EstimateSizeFallbackZero<'a, T> {
+    real: &'a T,
+}
+
+impl<T> EstimateSizeFallbackZero<'_, T> {
+    /// Called as part of [`EstimateSizeDerefSpecialization`] when `T` does not implement
+    /// [`EstimateSize`].
+    pub fn estimate_heap_size(&self) -> usize {
+        0
+    }
+}
+
+/// The estimated size of a value of type `T`, including its heap memory. Registered for
+/// [`ValueType::estimate_size`][crate::ValueType::estimate_size] by the
+/// [turbo_tasks::value][crate::value] macro.
+pub fn estimate_size_of<T: EstimateSize + 'static>(value: &(dyn Any + Send + Sync)) -> usize {
+    size_of::<T>()
+        + value
+            .downcast_ref::<T>()
+            .map_or(0, EstimateSize::estimate_heap_size)
+}
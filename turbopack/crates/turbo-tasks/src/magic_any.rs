@@ -14,6 +14,9 @@ pub trait MagicAny: mopa::Any + Send + Sync {
 
     fn magic_debug(&self, f: &mut fmt::Formatter) -> fmt::Result;
 
+    /// Formats only the value, unlike [`MagicAny::magic_debug`] which includes its type.
+    fn magic_debug_value(&self, f: &mut fmt::Formatter) -> fmt::Result;
+
     fn magic_eq(&self, other: &dyn MagicAny) -> bool;
 
     fn magic_hash(&self, hasher: &mut dyn Hasher);
@@ -47,6 +50,10 @@ impl<T: Debug + Eq + Hash + Send + Sync + 'static> MagicAny for T {
         d.finish()
     }
 
+    fn magic_debug_value(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+
     fn magic_eq(&self, other: &dyn MagicAny) -> bool {
         match other.downcast_ref::<Self>() {
             None => false,
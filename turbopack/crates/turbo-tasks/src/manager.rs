@@ -3,8 +3,9 @@ use std::{
     borrow::Cow,
     future::Future,
     hash::BuildHasherDefault,
-    mem::take,
-    panic::AssertUnwindSafe,
+    mem::{replace, take},
+    panic::{AssertUnwindSafe, Location},
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
@@ -14,12 +15,15 @@ use std::{
     time::{Duration, Instant},
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use auto_hash_map::AutoMap;
-use futures::FutureExt;
-use rustc_hash::FxHasher;
+use futures::{
+    future::{join_all, Either},
+    FutureExt,
+};
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
 use serde::{Deserialize, Serialize};
-use tokio::{runtime::Handle, select, task_local};
+use tokio::{runtime::Handle, select, sync::Semaphore, task_local};
 use tokio_util::task::TaskTracker;
 use tracing::{info_span, instrument, trace_span, Instrument, Level, Span};
 use turbo_tasks_malloc::TurboMalloc;
@@ -29,24 +33,39 @@ use crate::{
         Backend, CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec,
         TransientTaskType, TypedCellContent,
     },
+    cancellation::{Canceled, CancellationToken},
     capture_future::{self, CaptureFuture},
+    collectibles::{CollectiblePosition, CollectiblePositions},
+    deterministic::{DeterministicScheduler, DETERMINISTIC, DETERMINISTIC_SEED},
+    dry_run::{changed_inputs, dirty_inputs, stale_tasks, DryRunReport},
+    durability::Durability,
     event::{Event, EventListener},
+    event_bus::{EventBus, EventSubscription},
+    graph_export::{GraphExportFilter, TaskGraph},
     id::{
         BackendJobId, ExecutionId, FunctionId, LocalCellId, LocalTaskId, TraitTypeId,
         TRANSIENT_TASK_BIT,
     },
     id_factory::{IdFactory, IdFactoryWithReuse},
     magic_any::MagicAny,
+    priority::{current_priority, with_priority, PriorityScheduler},
+    progress::{Progress, ProgressSubscription, ProgressTracker},
     raw_vc::{CellId, RawVc},
     registry::{self, get_function},
     serialization_invalidation::SerializationInvalidator,
-    task::shared_reference::TypedSharedReference,
+    task::{function::NativeTaskFuture, shared_reference::TypedSharedReference},
+    timeout::WaitingTask,
     trace::TraceRawVcs,
     trait_helpers::get_trait_method,
-    util::StaticOrArc,
-    vc::ReadVcFuture,
-    Completion, FunctionMeta, InvalidationReason, InvalidationReasonSet, SharedReference, TaskId,
-    TaskIdSet, ValueTypeId, Vc, VcRead, VcValueTrait, VcValueType,
+    util::{SharedError, StaticOrArc},
+    vc::{
+        creation_site::{describe_creation_site, record_creation_site},
+        ReadVcFuture,
+    },
+    wait_graph::WaitGraph,
+    Completion, FunctionMeta, GcPolicy, InvalidationReason, InvalidationReasonSet,
+    InvalidationStep, MemoryReport, SharedReference, TaskId, TaskIdSet, ValueTypeId, Vc, VcRead,
+    VcValueTrait, VcValueType,
 };
 
 pub trait TurboTasksCallApi: Sync + Send {
@@ -95,6 +114,9 @@ pub trait TurboTasksCallApi: Sync + Send {
         persistence: TaskPersistence,
     ) -> RawVc;
 
+    /// Runs the future in a local task of the current task, see [`run_local`].
+    fn run_local(&self, future: NativeTaskFuture) -> RawVc;
+
     fn run_once(
         &self,
         future: Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
@@ -115,6 +137,13 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
 
     fn invalidate(&self, task: TaskId);
     fn invalidate_with_reason(&self, task: TaskId, reason: StaticOrArc<dyn InvalidationReason>);
+    /// Invalidates multiple tasks in a single backend operation.
+    fn invalidate_many(&self, tasks: &[TaskId]);
+    fn invalidate_many_with_reason(
+        &self,
+        tasks: &[TaskId],
+        reason: StaticOrArc<dyn InvalidationReason>,
+    );
 
     fn invalidate_serialization(&self, task: TaskId);
 
@@ -169,8 +198,16 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     fn read_task_collectibles(&self, task: TaskId, trait_id: TraitTypeId) -> TaskCollectiblesMap;
 
     fn emit_collectible(&self, trait_type: TraitTypeId, collectible: RawVc);
+    /// Like [`TurboTasksApi::emit_collectible`], but also records the position of the emission,
+    /// see [`emit_ordered`].
+    fn emit_ordered_collectible(&self, trait_type: TraitTypeId, collectible: RawVc);
     fn unemit_collectible(&self, trait_type: TraitTypeId, collectible: RawVc, count: u32);
     fn unemit_collectibles(&self, trait_type: TraitTypeId, collectibles: &TaskCollectiblesMap);
+    fn collectible_position(
+        &self,
+        trait_type: TraitTypeId,
+        collectible: RawVc,
+    ) -> Option<CollectiblePosition>;
 
     /// INVALIDATION: Be careful with this, it will not track dependencies, so
     /// using it could break cache invalidation.
@@ -181,9 +218,38 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     ) -> Result<TypedCellContent>;
 
     fn read_own_task_cell(&self, task: TaskId, index: CellId) -> Result<TypedCellContent>;
+    /// Returns whether the cell currently holds its content, see
+    /// [`Backend::is_task_cell_alive`].
+    fn is_task_cell_alive(&self, task: TaskId, index: CellId) -> bool;
+    /// Cancels the current execution of the task, see [`CancellationToken`]. Does nothing when
+    /// the task isn't executing.
+    fn cancel_task(&self, task: TaskId);
+    /// Explains why the task was last invalidated: the cause recorded for the task, followed by
+    /// the causes recorded for the tasks which caused it, up to an external invalidation. Empty
+    /// when the backend doesn't record causes.
+    fn why_invalidated(&self, task: TaskId) -> Vec<InvalidationStep>;
+    /// The chain of tasks the task currently waits for, starting with the task itself, see
+    /// [`with_timeout`][crate::with_timeout].
+    fn wait_stack(&self, task: TaskId) -> Vec<WaitingTask>;
+    /// A hash of the function and the inputs of the task, which is the same across process
+    /// restarts, see [`TaskId::stable_hash`].
+    fn task_stable_hash(&self, task: TaskId) -> Option<u64>;
+    /// The semaphore of the [`ParallelismLimit`][crate::ParallelismLimit] with the given name, or
+    /// `None` when no limit was configured for it.
+    fn parallelism_limit(&self, name: &str) -> Option<Arc<Semaphore>>;
+    /// Sends the progress of the task to progress subscriptions, see
+    /// [`ProgressReporter`][crate::ProgressReporter].
+    fn report_progress(&self, task: TaskId, progress: Progress);
+    /// The events published between subsystems, see [`publish_event`].
+    fn event_bus(&self) -> &EventBus;
+    /// Brackets the write of an output file by an effect, see [`output_write_started`].
+    fn output_write_started(&self, path: &Path);
+    fn output_write_finished(&self, path: &Path);
     fn update_own_task_cell(&self, task: TaskId, index: CellId, content: CellContent);
     fn mark_own_task_as_finished(&self, task: TaskId);
     fn mark_own_task_as_session_dependent(&self, task: TaskId);
+    fn mark_own_task_durability(&self, task: TaskId, durability: Durability);
+    fn prefetch_task(&self, task: TaskId);
 
     fn connect_task(&self, task: TaskId);
 
@@ -232,6 +298,9 @@ impl<T> Unused<T> {
 pub trait TurboTasksBackendApi<B: Backend + 'static>: TurboTasksCallApi + Sync + Send {
     fn pin(&self) -> Arc<dyn TurboTasksBackendApi<B>>;
 
+    /// Publishes lifecycle events of the backend, e.g. [`GcCompleted`][crate::GcCompleted].
+    fn event_bus(&self) -> &EventBus;
+
     fn get_fresh_persistent_task_id(&self) -> Unused<TaskId>;
     fn get_fresh_transient_task_id(&self) -> Unused<TaskId>;
     /// # Safety
@@ -378,6 +447,62 @@ pub struct TurboTasks<B: Backend + 'static> {
     event_foreground: Event,
     event_background: Event,
     program_start: Instant,
+    /// The cancellation tokens of the tasks that are currently executing.
+    cancellation_tokens: Mutex<FxHashMap<TaskId, CancellationToken>>,
+    /// Set in [deterministic mode][TurboTasksBuilder::deterministic].
+    deterministic: Option<Arc<DeterministicScheduler>>,
+    /// See [TurboTasksBuilder::parallelism_limit].
+    parallelism_limits: FxHashMap<&'static str, Arc<Semaphore>>,
+    progress: Arc<ProgressTracker>,
+    /// See [TurboTasks::subscribe_events].
+    events: EventBus,
+    /// Detects tasks which wait for each other, which would never finish.
+    wait_graph: Arc<WaitGraph>,
+    /// Delays executions while executions with a higher priority are pending.
+    priority: PriorityScheduler,
+    /// The positions of collectibles emitted with [`emit_ordered`].
+    collectible_positions: CollectiblePositions,
+}
+
+/// Creates a [`TurboTasks`] instance with non-default options, see [`TurboTasks::builder`].
+pub struct TurboTasksBuilder<B: Backend + 'static> {
+    backend: B,
+    deterministic_seed: Option<u64>,
+    parallelism_limits: FxHashMap<&'static str, Arc<Semaphore>>,
+}
+
+impl<B: Backend + 'static> TurboTasksBuilder<B> {
+    /// Executes one task at a time, in an order that only depends on the task ids and `seed`, to
+    /// reproduce bugs caused by the order in which tasks are executed. Different seeds result in
+    /// different orders.
+    ///
+    /// When not set, this is enabled by the `TURBO_DETERMINISTIC=1` environment variable, with
+    /// the seed taken from `TURBO_DETERMINISTIC_SEED`.
+    pub fn deterministic(mut self, seed: u64) -> Self {
+        self.deterministic_seed = Some(seed);
+        self
+    }
+
+    /// Configures when and which cells the backend drops to save memory.
+    pub fn gc_policy(mut self, policy: GcPolicy) -> Self {
+        self.backend.set_gc_policy(policy);
+        self
+    }
+
+    /// Lets at most `limit` futures of the [`ParallelismLimit`][crate::ParallelismLimit] with the
+    /// given name run at the same time. A `limit` of 0 is treated as 1.
+    pub fn parallelism_limit(mut self, name: &'static str, limit: usize) -> Self {
+        self.parallelism_limits
+            .insert(name, Arc::new(Semaphore::new(limit.max(1))));
+        self
+    }
+
+    pub fn build(self) -> Arc<TurboTasks<B>> {
+        let deterministic_seed = self
+            .deterministic_seed
+            .or_else(|| DETERMINISTIC.get().then(|| DETERMINISTIC_SEED.get() as u64));
+        TurboTasks::new_internal(self.backend, deterministic_seed, self.parallelism_limits)
+    }
 }
 
 /// Information about a "global" task. A global task can contain multiple "local" tasks (see
@@ -413,6 +538,20 @@ struct CurrentGlobalTaskState {
     /// complete.
     local_task_tracker: TaskTracker,
 
+    /// Local tasks spawned by the task, see [`TaskPersistence::LocalCells`]. Indexed by
+    /// [`LocalTaskId`] - 1.
+    local_tasks: Vec<LocalTask>,
+
+    /// Values of [`TaskLocal`][crate::TaskLocal]s, by the address of the `TaskLocal`.
+    task_locals: FxHashMap<usize, Arc<dyn Any + Send + Sync>>,
+
+    /// Collectibles emitted with [`emit_ordered`] during this execution, in emission order.
+    ordered_collectibles: Vec<(TraitTypeId, RawVc)>,
+
+    /// Called with the execution duration when the execution finishes, see
+    /// [`on_execution_completed`].
+    execution_completed_callbacks: Vec<Box<dyn FnOnce(Duration) + Send + Sync>>,
+
     backend_state: Box<dyn Any + Send + Sync>,
 }
 
@@ -425,11 +564,21 @@ impl CurrentGlobalTaskState {
             cell_counters: Some(AutoMap::default()),
             local_cells: Vec::new(),
             local_task_tracker: TaskTracker::new(),
+            local_tasks: Vec::new(),
+            task_locals: FxHashMap::default(),
+            ordered_collectibles: Vec::new(),
+            execution_completed_callbacks: Vec::new(),
             backend_state,
         }
     }
 }
 
+/// The state of a local task, see [`TaskPersistence::LocalCells`].
+enum LocalTask {
+    Scheduled { done_event: Event },
+    Done { output: Result<RawVc, SharedError> },
+}
+
 /// Information specific to the current "local" task. A local task re-uses it's parent global task's
 /// [`CurrentGlobalTaskState`].
 ///
@@ -462,6 +611,10 @@ task_local! {
 
     static CURRENT_GLOBAL_TASK_STATE: Arc<RwLock<CurrentGlobalTaskState>>;
     static CURRENT_LOCAL_TASK_STATE: CurrentLocalTaskState;
+
+    /// The cancellation token of the current task execution, inherited by futures and blocking
+    /// closures spawned from it.
+    static CANCELLATION_TOKEN: CancellationToken;
 }
 
 impl<B: Backend + 'static> TurboTasks<B> {
@@ -471,6 +624,22 @@ impl<B: Backend + 'static> TurboTasks<B> {
     // so we probably want to make sure that all tasks are joined
     // when trying to drop turbo tasks
     pub fn new(backend: B) -> Arc<Self> {
+        Self::builder(backend).build()
+    }
+
+    pub fn builder(backend: B) -> TurboTasksBuilder<B> {
+        TurboTasksBuilder {
+            backend,
+            deterministic_seed: None,
+            parallelism_limits: FxHashMap::default(),
+        }
+    }
+
+    fn new_internal(
+        backend: B,
+        deterministic_seed: Option<u64>,
+        parallelism_limits: FxHashMap<&'static str, Arc<Semaphore>>,
+    ) -> Arc<Self> {
         let task_id_factory = IdFactoryWithReuse::new(1, (TRANSIENT_TASK_BIT - 1) as u64);
         let transient_task_id_factory =
             IdFactoryWithReuse::new(TRANSIENT_TASK_BIT as u64, u32::MAX as u64);
@@ -492,6 +661,14 @@ impl<B: Backend + 'static> TurboTasks<B> {
             event_foreground: Event::new(|| "TurboTasks::event_foreground".to_string()),
             event_background: Event::new(|| "TurboTasks::event_background".to_string()),
             program_start: Instant::now(),
+            cancellation_tokens: Default::default(),
+            deterministic: deterministic_seed.map(DeterministicScheduler::new),
+            parallelism_limits,
+            progress: Default::default(),
+            events: Default::default(),
+            wait_graph: Default::default(),
+            priority: PriorityScheduler::new(),
+            collectible_positions: CollectiblePositions::default(),
         });
         this.backend.startup(&*this);
         this
@@ -523,6 +700,144 @@ impl<B: Backend + 'static> TurboTasks<B> {
         self.backend.dispose_root_task(task_id, self);
     }
 
+    /// Pins the subgraph of tasks `vc` depends on, e.g. the runtime code or a
+    /// chunk which is requested all the time.
+    ///
+    /// The subgraph is computed right away. Pinned tasks aren't garbage
+    /// collected, and they are recomputed as soon as they are invalidated
+    /// instead of when they are read the next time. This trades memory for
+    /// the latency of reading `vc`.
+    ///
+    /// Returns the root task which pins the subgraph. Unpin it with
+    /// [TurboTasks::dispose_root_task].
+    pub fn pin_vc<T>(&self, vc: Vc<T>) -> TaskId
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let id =
+            self.spawn_root_task(move || async move { vc.resolve_strongly_consistent().await });
+        self.backend.pin_root_task(id, self);
+        id
+    }
+
+    /// Reports the `top_n` tasks retaining the most memory in their cells, estimated with
+    /// [`EstimateSize`][crate::EstimateSize]. Empty when the backend doesn't estimate memory.
+    /// Content shared between cells is counted for each of them.
+    pub fn memory_report(&self, top_n: usize) -> MemoryReport {
+        let mut usage = self.backend.task_memory_usage(self);
+        let total_bytes = usage.iter().map(|usage| usage.bytes).sum();
+        let total_cells = usage.iter().map(|usage| usage.cells).sum();
+        usage.sort_unstable_by(|a, b| b.bytes.cmp(&a.bytes).then(a.task.cmp(&b.task)));
+        usage.truncate(top_n);
+        MemoryReport {
+            total_bytes,
+            total_cells,
+            tasks: usage
+                .into_iter()
+                .map(|usage| (usage, self.backend.get_task_description(usage.task)))
+                .collect(),
+        }
+    }
+
+    /// Exports the tasks selected by `filter` with their inputs, the tasks, cells and
+    /// collectibles they read, and the tasks they called. Render the result with
+    /// [`TaskGraph::to_dot`] or [`TaskGraph::to_json`]. Empty when the backend doesn't support
+    /// exporting the graph.
+    pub fn export_graph(&self, filter: &GraphExportFilter) -> TaskGraph {
+        TaskGraph::new(self.backend.task_graph(self), filter)
+    }
+
+    /// Reports how much of the next build can be taken from the persistent cache, without
+    /// executing the build. Only the inputs of the cached tasks are executed again, e.g. file
+    /// reads, which are dirty because they were cached in an earlier session. The tasks which
+    /// read a changed input are invalidated, but not executed.
+    pub async fn dry_run(&self) -> DryRunReport {
+        let before = self.backend.task_graph(self);
+        let inputs = dirty_inputs(&before, &stale_tasks(&before));
+        // Failing inputs are cached like successful ones, so their errors can be ignored.
+        join_all(
+            inputs
+                .iter()
+                .map(|&input| read_task_output_untracked(self, input, ReadConsistency::Strong)),
+        )
+        .await;
+
+        let after = self.backend.task_graph(self);
+        let stale = stale_tasks(&after);
+        let changed = changed_inputs(&before, &after, &inputs);
+        DryRunReport {
+            tasks: after.len(),
+            cache_hits: after.len() - stale.len(),
+            changed_inputs: after
+                .iter()
+                .filter(|node| changed.binary_search(&node.task).is_ok())
+                .map(|node| format!("{}({})", node.name, node.inputs.join(", ")))
+                .collect(),
+        }
+    }
+
+    /// Subscribes to the progress reported with [`ProgressReporter`][crate::ProgressReporter],
+    /// either by all tasks or by the tasks of the given root task.
+    ///
+    /// A task belongs to the root task it was first called or read by, directly or through other
+    /// tasks, while there was a subscription for the root task. Tasks which aren't called or read
+    /// again after subscribing, e.g. because they're recomputed after an invalidation, report
+    /// progress without a root task.
+    pub fn subscribe_progress(&self, root: Option<TaskId>) -> ProgressSubscription {
+        self.progress.subscribe(root)
+    }
+
+    /// Subscribes to the events of type `E` published from now on by tasks with
+    /// [`publish_event`] or by the backend, e.g. [`GcCompleted`][crate::GcCompleted] or
+    /// [`PersistFlushed`][crate::PersistFlushed].
+    pub fn subscribe_events<E: Clone + Send + Sync + 'static>(&self) -> EventSubscription<E> {
+        self.events.subscribe()
+    }
+
+    /// Records that `reader` waits for `task` while the listener of the read is alive, i.e. until
+    /// the read is retried or given up. Fails instead of waiting when `task` (transitively) waits
+    /// for `reader`, as none of the tasks would ever finish.
+    fn track_wait<T>(
+        &self,
+        reader: TaskId,
+        task: TaskId,
+        result: Result<Result<T, EventListener>>,
+    ) -> Result<Result<T, EventListener>> {
+        let listener = match result {
+            Ok(Err(listener)) => listener,
+            result => return result,
+        };
+        self.priority.boost(task);
+        match self.wait_graph.start_waiting(reader, task) {
+            Ok(guard) => Ok(Err(listener.with_drop_guard(guard))),
+            Err(cycle) => bail!(
+                "Cycle detected in task execution, the tasks would wait for each other \
+                 forever:\n{}",
+                self.describe_cycle(&cycle)
+            ),
+        }
+    }
+
+    /// Lists the tasks of a cycle as `function_name @ file:line`, one task per line.
+    fn describe_cycle(&self, cycle: &[TaskId]) -> String {
+        cycle
+            .iter()
+            .enumerate()
+            .map(|(i, &task)| {
+                let arrow = if i == 0 { "  " } else { "  -> " };
+                let description = self.backend.get_task_description(task);
+                match self.backend.try_get_function_id(task) {
+                    Some(function_id) => format!(
+                        "{arrow}{description} @ {}",
+                        get_function(function_id).function_meta.location
+                    ),
+                    None => format!("{arrow}{description}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     // TODO make sure that all dependencies settle before reading them
     /// Creates a new root task, that is only executed once.
     /// Dependencies will not invalidate the task.
@@ -573,22 +888,22 @@ impl<B: Backend + 'static> TurboTasks<B> {
             arg,
         };
         match persistence {
-            TaskPersistence::LocalCells => {
-                todo!("bgw: local tasks");
-            }
+            TaskPersistence::LocalCells => self.schedule_local_task(task_type),
             TaskPersistence::Transient => {
-                RawVc::TaskOutput(self.backend.get_or_create_transient_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_transient_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
             TaskPersistence::Persistent => {
-                RawVc::TaskOutput(self.backend.get_or_create_persistent_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_persistent_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
         }
     }
@@ -606,22 +921,22 @@ impl<B: Backend + 'static> TurboTasks<B> {
             arg,
         };
         match persistence {
-            TaskPersistence::LocalCells => {
-                todo!("bgw: local tasks");
-            }
+            TaskPersistence::LocalCells => self.schedule_local_task(task_type),
             TaskPersistence::Transient => {
-                RawVc::TaskOutput(self.backend.get_or_create_transient_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_transient_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
             TaskPersistence::Persistent => {
-                RawVc::TaskOutput(self.backend.get_or_create_persistent_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_persistent_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
         }
     }
@@ -632,35 +947,31 @@ impl<B: Backend + 'static> TurboTasks<B> {
         arg: Box<dyn MagicAny>,
         persistence: TaskPersistence,
     ) -> RawVc {
-        // TODO(bgw): Don't create a full turbo task if this is a function using local_cells
         if registry::get_function(func).arg_meta.is_resolved(&*arg) {
             return self.native_call(func, arg, persistence);
         }
+        let task_type = CachedTaskType::ResolveNative {
+            fn_type: func,
+            this: None,
+            arg,
+        };
         match persistence {
-            TaskPersistence::LocalCells => {
-                todo!("bgw: local tasks");
-            }
+            TaskPersistence::LocalCells => self.schedule_local_task(task_type),
             TaskPersistence::Transient => {
-                RawVc::TaskOutput(self.backend.get_or_create_transient_task(
-                    CachedTaskType::ResolveNative {
-                        fn_type: func,
-                        this: None,
-                        arg,
-                    },
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_transient_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
             TaskPersistence::Persistent => {
-                RawVc::TaskOutput(self.backend.get_or_create_persistent_task(
-                    CachedTaskType::ResolveNative {
-                        fn_type: func,
-                        this: None,
-                        arg,
-                    },
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_persistent_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
         }
     }
@@ -681,22 +992,22 @@ impl<B: Backend + 'static> TurboTasks<B> {
             arg,
         };
         match persistence {
-            TaskPersistence::LocalCells => {
-                todo!("bgw: local tasks");
-            }
+            TaskPersistence::LocalCells => self.schedule_local_task(task_type),
             TaskPersistence::Transient => {
-                RawVc::TaskOutput(self.backend.get_or_create_transient_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_transient_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
             TaskPersistence::Persistent => {
-                RawVc::TaskOutput(self.backend.get_or_create_persistent_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_persistent_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
         }
     }
@@ -731,26 +1042,161 @@ impl<B: Backend + 'static> TurboTasks<B> {
             arg,
         };
         match persistence {
-            TaskPersistence::LocalCells => {
-                todo!("bgw: local tasks");
-            }
+            TaskPersistence::LocalCells => self.schedule_local_task(task_type),
             TaskPersistence::Transient => {
-                RawVc::TaskOutput(self.backend.get_or_create_transient_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_transient_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
             TaskPersistence::Persistent => {
-                RawVc::TaskOutput(self.backend.get_or_create_persistent_task(
-                    task_type,
-                    current_task("turbo_function calls"),
-                    self,
-                ))
+                let parent = current_task("turbo_function calls");
+                let task = self
+                    .backend
+                    .get_or_create_persistent_task(task_type, parent, self);
+                self.progress.track_child(parent, task);
+                RawVc::TaskOutput(task)
             }
         }
     }
 
+    /// Runs the call in a local task of the current task, see [`TaskPersistence::LocalCells`].
+    fn schedule_local_task(&self, task_type: CachedTaskType) -> RawVc {
+        let parent = current_task("turbo_function calls");
+        let function_meta = task_type
+            .try_get_function_id()
+            .map(|func_id| &get_function(func_id).function_meta);
+        let turbo_tasks: Arc<dyn TurboTasksBackendApi<B>> = self.pin();
+        let (future, span): (NativeTaskFuture, Span) = match task_type {
+            CachedTaskType::Native { fn_type, this, arg } => {
+                let func = get_function(fn_type);
+                let span = func.span(parent);
+                let future = span.in_scope(|| func.execute(this, &*arg));
+                (future, span)
+            }
+            CachedTaskType::ResolveNative { fn_type, this, arg } => {
+                let span = get_function(fn_type).resolve_span(parent);
+                let future = Box::pin(async move {
+                    CachedTaskType::run_resolve_native(
+                        fn_type,
+                        this,
+                        &*arg,
+                        TaskPersistence::LocalCells,
+                        turbo_tasks,
+                    )
+                    .await
+                });
+                (future, span)
+            }
+            CachedTaskType::ResolveTrait {
+                trait_type,
+                method_name,
+                this,
+                arg,
+            } => {
+                let span = registry::get_trait(trait_type).resolve_span(&method_name);
+                let future = Box::pin(async move {
+                    CachedTaskType::run_resolve_trait(
+                        trait_type,
+                        method_name,
+                        this,
+                        &*arg,
+                        TaskPersistence::LocalCells,
+                        turbo_tasks,
+                    )
+                    .await
+                });
+                (future, span)
+            }
+        };
+        self.spawn_local_task(future, function_meta, span)
+    }
+
+    /// Spawns `future` as a local task of the current task. It shares the task id, and therefore
+    /// the dependencies and cells, of the current task, but has its own local cells. The current
+    /// task waits for its local tasks before it finishes.
+    fn spawn_local_task(
+        &self,
+        future: NativeTaskFuture,
+        function_meta: Option<&'static FunctionMeta>,
+        span: Span,
+    ) -> RawVc {
+        let parent = current_task("spawning local tasks");
+        let global_task_state = CURRENT_GLOBAL_TASK_STATE.with(|ts| ts.clone());
+        let index = {
+            let CurrentGlobalTaskState { local_tasks, .. } =
+                &mut *global_task_state.write().unwrap();
+            local_tasks.push(LocalTask::Scheduled {
+                done_event: Event::new(move || format!("LocalTask({parent})::done_event")),
+            });
+            local_tasks.len() - 1
+        };
+        // generate a one-indexed id
+        let local_task_id = LocalTaskId::from(u32::try_from(index + 1).unwrap());
+        let local_task_state =
+            CurrentLocalTaskState::new(self.execution_id_factory.get(), function_meta);
+        let cancellation_token = cancellation_token().unwrap_or_default();
+
+        let future = async move {
+            // Local cells of the local task can't be read by the caller, so a local output is
+            // resolved into cells of the parent task.
+            let future = async move {
+                let output = future.await?;
+                if output.is_local() {
+                    output.resolve().await
+                } else {
+                    Ok(output)
+                }
+            };
+            let result = AssertUnwindSafe(future).catch_unwind().await;
+
+            // Convert the unwind panic to an anyhow error that can be cloned.
+            let output = result
+                .map_err(|any| match any.downcast::<String>() {
+                    Ok(owned) => anyhow!("Local task panicked: {owned}"),
+                    Err(any) => match any.downcast::<&'static str>() {
+                        Ok(str) => anyhow!("Local task panicked: {str}"),
+                        Err(_) => anyhow!("Local task panicked"),
+                    },
+                })
+                .and_then(|r| r)
+                .map_err(SharedError::new);
+
+            let done_event = CURRENT_GLOBAL_TASK_STATE.with(|ts| {
+                let CurrentGlobalTaskState { local_tasks, .. } = &mut *ts.write().unwrap();
+                match replace(&mut local_tasks[index], LocalTask::Done { output }) {
+                    LocalTask::Scheduled { done_event } => done_event,
+                    LocalTask::Done { .. } => unreachable!("local task finished twice"),
+                }
+            });
+            done_event.notify(usize::MAX);
+        }
+        .instrument(span);
+
+        let future = global_task_state
+            .read()
+            .unwrap()
+            .local_task_tracker
+            .track_future(future);
+        let future = TURBO_TASKS.scope(
+            self.pin(),
+            CURRENT_GLOBAL_TASK_STATE.scope(
+                global_task_state,
+                CURRENT_LOCAL_TASK_STATE.scope(
+                    local_task_state,
+                    CANCELLATION_TOKEN.scope(
+                        cancellation_token,
+                        with_priority(current_priority(), future),
+                    ),
+                ),
+            ),
+        );
+        tokio::task::spawn(future);
+        RawVc::LocalOutput(parent, local_task_id)
+    }
+
     #[track_caller]
     pub(crate) fn schedule(&self, task_id: TaskId) {
         self.begin_primary_job();
@@ -759,8 +1205,27 @@ impl<B: Backend + 'static> TurboTasks<B> {
         #[cfg(feature = "tokio_tracing")]
         let description = self.backend.get_task_description(task_id);
 
+        // The backend might hold locks while scheduling, so the priority of the function is only
+        // looked up once the execution started.
+        let scheduled_priority = current_priority();
+        self.priority.schedule(scheduled_priority);
+
         let this = self.pin();
         let future = async move {
+            let priority =
+                this.backend
+                    .try_get_function_id(task_id)
+                    .map_or(scheduled_priority, |func_id| {
+                        get_function(func_id)
+                            .function_meta
+                            .priority
+                            .max(scheduled_priority)
+                    });
+            if priority != scheduled_priority {
+                this.priority.schedule(priority);
+                this.priority.finish_priority(scheduled_priority);
+            }
+            this.priority.wait_for_turn(task_id, priority).await;
             let mut schedule_again = true;
             while schedule_again {
                 let backend_state = this.backend.new_task_state(task_id);
@@ -774,6 +1239,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         .try_get_function_id(task_id)
                         .map(|func_id| &get_function(func_id).function_meta),
                 );
+                let cancellation_token = CancellationToken::new();
                 let single_execution_future = async {
                     if this.stopped.load(Ordering::Acquire) {
                         return false;
@@ -785,6 +1251,22 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         return false;
                     };
 
+                    this.cancellation_tokens
+                        .lock()
+                        .unwrap()
+                        .insert(task_id, cancellation_token.clone());
+
+                    // A local output can't be read by other tasks, so it's resolved while the
+                    // task state still exists.
+                    let future = async move {
+                        let output = future.await?;
+                        if output.is_local() {
+                            output.resolve().await
+                        } else {
+                            Ok(output)
+                        }
+                    };
+
                     async {
                         let (result, duration, memory_usage) =
                             CaptureFuture::new(AssertUnwindSafe(future).catch_unwind()).await;
@@ -795,6 +1277,13 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         ltt.close();
                         ltt.wait().await;
 
+                        let callbacks = CURRENT_GLOBAL_TASK_STATE.with(|ts| {
+                            take(&mut ts.write().unwrap().execution_completed_callbacks)
+                        });
+                        for callback in callbacks {
+                            callback(duration);
+                        }
+
                         let result = result.map_err(|any| match any.downcast::<String>() {
                             Ok(owned) => Some(Cow::Owned(*owned)),
                             Err(any) => match any.downcast::<&'static str>() {
@@ -814,6 +1303,10 @@ impl<B: Backend + 'static> TurboTasks<B> {
                             stateful,
                             &*this,
                         );
+                        this.cancellation_tokens.lock().unwrap().remove(&task_id);
+                        if cancellation_token.is_canceled() {
+                            this.backend.task_execution_canceled(task_id, &*this);
+                        }
                         // task_execution_completed might need to notify tasks
                         this.notify_scheduled_tasks();
                         schedule_again
@@ -824,15 +1317,26 @@ impl<B: Backend + 'static> TurboTasks<B> {
                 schedule_again = CURRENT_GLOBAL_TASK_STATE
                     .scope(
                         global_task_state,
-                        CURRENT_LOCAL_TASK_STATE.scope(local_task_state, single_execution_future),
+                        CURRENT_LOCAL_TASK_STATE.scope(
+                            local_task_state,
+                            CANCELLATION_TOKEN.scope(
+                                cancellation_token.clone(),
+                                with_priority(priority, single_execution_future),
+                            ),
+                        ),
                     )
                     .await;
             }
+            this.priority.finish(task_id, priority);
             this.finish_primary_job();
             anyhow::Ok(())
         };
 
         let future = TURBO_TASKS.scope(self.pin(), future).in_current_span();
+        let future = match &self.deterministic {
+            Some(scheduler) => Either::Left(scheduler.wrap(task_id, future)),
+            None => Either::Right(future),
+        };
 
         #[cfg(feature = "tokio_tracing")]
         tokio::task::Builder::new()
@@ -1044,6 +1548,49 @@ impl<B: Backend + 'static> TurboTasks<B> {
         }
     }
 
+    /// Waits until tasks start executing while turbo-tasks is idle, e.g. to
+    /// report that a compilation started. Returns immediately when tasks are
+    /// executing.
+    pub async fn wait_start(&self) {
+        let listener = self
+            .event_start
+            .listen_with_note(|| "wait for start".to_string());
+        if self.currently_scheduled_tasks.load(Ordering::Acquire) == 0 {
+            listener.await;
+        }
+    }
+
+    /// Waits until no task has been executed for `idle_timeout`, e.g. to run
+    /// low priority work that shouldn't compete with the user's work. Returns
+    /// `false` when turbo-tasks has been stopped instead.
+    pub async fn wait_idle(&self, idle_timeout: Duration) -> bool {
+        loop {
+            if self.stopped.load(Ordering::Acquire) {
+                return false;
+            }
+            let listener = self.event.listen_with_note(|| "wait for idle".to_string());
+            if self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
+                listener.await;
+                continue;
+            }
+            drop(listener);
+            let start_listener = self
+                .event_start
+                .listen_with_note(|| "wait for idle".to_string());
+            if self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
+                continue;
+            }
+            select! {
+                () = tokio::time::sleep(idle_timeout) => {
+                    return !self.stopped.load(Ordering::Acquire);
+                }
+                () = start_listener => {
+                    // Work has started, wait until it's done
+                }
+            }
+        }
+    }
+
     pub async fn wait_background_done(&self) {
         let listener = self.event_background.listen();
         if self
@@ -1056,12 +1603,32 @@ impl<B: Backend + 'static> TurboTasks<B> {
     }
 
     pub async fn stop_and_wait(&self) {
+        self.stop_and_wait_internal(None).await;
+    }
+
+    /// Shuts down in order: stops accepting new work, waits for in-flight tasks
+    /// for at most `drain_timeout`, waits for background jobs (which includes
+    /// persisting the cache) and finally lets the backend flush what's left.
+    ///
+    /// Tasks still running after `drain_timeout` are abandoned, their results
+    /// are not persisted. Returns `false` in that case.
+    pub async fn stop_and_wait_with_timeout(&self, drain_timeout: Duration) -> bool {
+        self.stop_and_wait_internal(Some(drain_timeout)).await
+    }
+
+    async fn stop_and_wait_internal(&self, drain_timeout: Option<Duration>) -> bool {
         self.backend.stopping(self);
         self.stopped.store(true, Ordering::Release);
+        let mut drained = true;
         {
             let listener = self.event.listen_with_note(|| "wait for stop".to_string());
             if self.currently_scheduled_tasks.load(Ordering::Acquire) != 0 {
-                listener.await;
+                match drain_timeout {
+                    Some(drain_timeout) => {
+                        drained = tokio::time::timeout(drain_timeout, listener).await.is_ok();
+                    }
+                    None => listener.await,
+                }
             }
         }
         {
@@ -1075,6 +1642,7 @@ impl<B: Backend + 'static> TurboTasks<B> {
             }
         }
         self.backend.stop(self);
+        drained
     }
 
     #[track_caller]
@@ -1138,18 +1706,28 @@ impl<B: Backend + 'static> TurboTasks<B> {
     }
 
     fn finish_current_task_state(&self) -> bool {
-        let (stateful, tasks) = CURRENT_GLOBAL_TASK_STATE.with(|cell| {
-            let CurrentGlobalTaskState {
-                tasks_to_notify,
-                stateful,
-                ..
-            } = &mut *cell.write().unwrap();
-            (*stateful, take(tasks_to_notify))
-        });
+        let (task_id, stateful, tasks, ordered_collectibles) =
+            CURRENT_GLOBAL_TASK_STATE.with(|cell| {
+                let CurrentGlobalTaskState {
+                    task_id,
+                    tasks_to_notify,
+                    stateful,
+                    ordered_collectibles,
+                    ..
+                } = &mut *cell.write().unwrap();
+                (
+                    *task_id,
+                    *stateful,
+                    take(tasks_to_notify),
+                    take(ordered_collectibles),
+                )
+            });
 
         if !tasks.is_empty() {
             self.backend.invalidate_tasks(&tasks, self);
         }
+        self.collectible_positions
+            .finish_execution(task_id, ordered_collectibles);
         stateful
     }
 
@@ -1204,6 +1782,10 @@ impl<B: Backend + 'static> TurboTasksCallApi for TurboTasks<B> {
         self.trait_call(trait_type, trait_fn_name, this, arg, persistence)
     }
 
+    fn run_local(&self, future: NativeTaskFuture) -> RawVc {
+        self.spawn_local_task(future, None, trace_span!("turbo_tasks::run_local"))
+    }
+
     #[track_caller]
     fn run_once(
         &self,
@@ -1258,11 +1840,30 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
 
     #[instrument(level = Level::INFO, skip_all, name = "invalidate", fields(name = display(&reason)))]
     fn invalidate_with_reason(&self, task: TaskId, reason: StaticOrArc<dyn InvalidationReason>) {
+        {
+            let (_, reason_set) = &mut *self.aggregated_update.lock().unwrap();
+            reason_set.insert(reason.clone());
+        }
+        self.backend
+            .invalidate_task_with_reason(task, &*reason, self);
+    }
+
+    #[instrument(level = Level::INFO, skip_all, name = "invalidate many", fields(count = tasks.len()))]
+    fn invalidate_many(&self, tasks: &[TaskId]) {
+        self.backend.invalidate_tasks(tasks, self);
+    }
+
+    #[instrument(level = Level::INFO, skip_all, name = "invalidate many", fields(count = tasks.len(), name = display(&reason)))]
+    fn invalidate_many_with_reason(
+        &self,
+        tasks: &[TaskId],
+        reason: StaticOrArc<dyn InvalidationReason>,
+    ) {
         {
             let (_, reason_set) = &mut *self.aggregated_update.lock().unwrap();
             reason_set.insert(reason);
         }
-        self.backend.invalidate_task(task, self);
+        self.backend.invalidate_tasks(tasks, self);
     }
 
     fn invalidate_serialization(&self, task: TaskId) {
@@ -1289,8 +1890,12 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         consistency: ReadConsistency,
     ) -> Result<Result<RawVc, EventListener>> {
-        self.backend
-            .try_read_task_output(task, current_task("reading Vcs"), consistency, self)
+        let reader = current_task("reading Vcs");
+        self.progress.track_child(reader, task);
+        let result = self
+            .backend
+            .try_read_task_output(task, reader, consistency, self);
+        self.track_wait(reader, task, result)
     }
 
     fn try_read_task_output_untracked(
@@ -1298,8 +1903,13 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         consistency: ReadConsistency,
     ) -> Result<Result<RawVc, EventListener>> {
-        self.backend
-            .try_read_task_output_untracked(task, consistency, self)
+        let result = self
+            .backend
+            .try_read_task_output_untracked(task, consistency, self);
+        if let Ok(Err(_)) = &result {
+            self.priority.boost(task);
+        }
+        result
     }
 
     fn try_read_task_cell(
@@ -1307,8 +1917,9 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         index: CellId,
     ) -> Result<Result<TypedCellContent, EventListener>> {
-        self.backend
-            .try_read_task_cell(task, index, current_task("reading Vcs"), self)
+        let reader = current_task("reading Vcs");
+        let result = self.backend.try_read_task_cell(task, index, reader, self);
+        self.track_wait(reader, task, result)
     }
 
     fn try_read_task_cell_untracked(
@@ -1316,7 +1927,11 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         task: TaskId,
         index: CellId,
     ) -> Result<Result<TypedCellContent, EventListener>> {
-        self.backend.try_read_task_cell_untracked(task, index, self)
+        let result = self.backend.try_read_task_cell_untracked(task, index, self);
+        if let Ok(Err(_)) = &result {
+            self.priority.boost(task);
+        }
+        result
     }
 
     fn try_read_own_task_cell_untracked(
@@ -1328,24 +1943,109 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
             .try_read_own_task_cell_untracked(current_task, index, self)
     }
 
+    fn is_task_cell_alive(&self, task: TaskId, index: CellId) -> bool {
+        self.backend.is_task_cell_alive(task, index, self)
+    }
+
+    fn cancel_task(&self, task: TaskId) {
+        if let Some(cancellation_token) = self.cancellation_tokens.lock().unwrap().get(&task) {
+            cancellation_token.cancel();
+        }
+    }
+
+    fn task_stable_hash(&self, task: TaskId) -> Option<u64> {
+        self.backend.get_task_stable_hash(task, self)
+    }
+
+    fn why_invalidated(&self, task: TaskId) -> Vec<InvalidationStep> {
+        let mut steps = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut next = Some(task);
+        // A task can be invalidated by a task it invalidated before, so guard against cycles.
+        while let Some(task) = next.filter(|task| visited.insert(*task)) {
+            let Some(cause) = self.backend.invalidation_cause(task, self) else {
+                break;
+            };
+            next = cause.source_task();
+            steps.push(InvalidationStep {
+                task,
+                description: self.backend.get_task_description(task),
+                cause,
+            });
+        }
+        steps
+    }
+
+    fn wait_stack(&self, task: TaskId) -> Vec<WaitingTask> {
+        self.wait_graph
+            .waiting_chain(task)
+            .into_iter()
+            .map(|task| WaitingTask {
+                task,
+                description: self.backend.get_task_description(task),
+                location: self
+                    .backend
+                    .try_get_function_id(task)
+                    .map(|function_id| get_function(function_id).function_meta.location),
+            })
+            .collect()
+    }
+
+    fn parallelism_limit(&self, name: &str) -> Option<Arc<Semaphore>> {
+        self.parallelism_limits.get(name).cloned()
+    }
+
+    fn report_progress(&self, task: TaskId, progress: Progress) {
+        self.progress.report(task, progress);
+    }
+
+    fn event_bus(&self) -> &EventBus {
+        &self.events
+    }
+
+    fn output_write_started(&self, path: &Path) {
+        self.backend.output_write_started(path, self);
+    }
+
+    fn output_write_finished(&self, path: &Path) {
+        self.backend.output_write_finished(path, self);
+    }
+
     fn try_read_local_output(
         &self,
-        _parent_task_id: TaskId,
-        _local_task_id: LocalTaskId,
-        _consistency: ReadConsistency,
+        parent_task_id: TaskId,
+        local_task_id: LocalTaskId,
+        consistency: ReadConsistency,
     ) -> Result<Result<RawVc, EventListener>> {
-        todo!("bgw: local outputs");
+        // INVALIDATION: The reads of the local task are tracked as reads of the parent task
+        self.try_read_local_output_untracked(parent_task_id, local_task_id, consistency)
     }
 
     /// INVALIDATION: Be careful with this, it will not track dependencies, so
     /// using it could break cache invalidation.
     fn try_read_local_output_untracked(
         &self,
-        _parent_task_id: TaskId,
-        _local_task_id: LocalTaskId,
+        parent_task_id: TaskId,
+        local_task_id: LocalTaskId,
         _consistency: ReadConsistency,
     ) -> Result<Result<RawVc, EventListener>> {
-        todo!("bgw: local outputs");
+        CURRENT_GLOBAL_TASK_STATE.with(|ts| {
+            let CurrentGlobalTaskState {
+                task_id,
+                local_tasks,
+                ..
+            } = &*ts.read().unwrap();
+            assert_eq!(
+                *task_id, parent_task_id,
+                "This Vc is local. Local Vcs must only be accessed within their own task. Resolve \
+                 the Vc to convert it into a non-local version."
+            );
+            // local task ids are one-indexed (they use NonZeroU32)
+            match &local_tasks[(*local_task_id as usize) - 1] {
+                LocalTask::Scheduled { done_event } => Ok(Err(done_event.listen())),
+                LocalTask::Done { output } => Ok(Ok(output.clone()?)),
+            }
+        })
     }
 
     fn read_task_collectibles(&self, task: TaskId, trait_id: TraitTypeId) -> TaskCollectiblesMap {
@@ -1366,6 +2066,33 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         );
     }
 
+    fn emit_ordered_collectible(&self, trait_type: TraitTypeId, collectible: RawVc) {
+        let position = CURRENT_GLOBAL_TASK_STATE.with(|ts| {
+            let CurrentGlobalTaskState {
+                task_id,
+                ordered_collectibles,
+                ..
+            } = &mut *ts.write().unwrap();
+            let index = ordered_collectibles.len() as u32;
+            ordered_collectibles.push((trait_type, collectible));
+            CollectiblePosition {
+                task: *task_id,
+                index,
+            }
+        });
+        self.collectible_positions
+            .record(trait_type, collectible, position);
+        self.emit_collectible(trait_type, collectible);
+    }
+
+    fn collectible_position(
+        &self,
+        trait_type: TraitTypeId,
+        collectible: RawVc,
+    ) -> Option<CollectiblePosition> {
+        self.collectible_positions.get(trait_type, collectible)
+    }
+
     fn unemit_collectible(&self, trait_type: TraitTypeId, collectible: RawVc, count: u32) {
         self.backend.unemit_collectible(
             trait_type,
@@ -1412,6 +2139,15 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         self.backend.mark_own_task_as_session_dependent(task, self);
     }
 
+    fn mark_own_task_durability(&self, task: TaskId, durability: Durability) {
+        self.backend
+            .mark_own_task_durability(task, durability, self);
+    }
+
+    fn prefetch_task(&self, task: TaskId) {
+        self.backend.prefetch_task(task, self);
+    }
+
     /// Creates a future that inherits the current task id and task state. The current global task
     /// will wait for this future to be dropped before exiting.
     fn detached_for_testing(
@@ -1422,6 +2158,7 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
         // state as well.
         let global_task_state = CURRENT_GLOBAL_TASK_STATE.with(|ts| ts.clone());
         let local_task_state = CURRENT_LOCAL_TASK_STATE.with(|ts| ts.clone());
+        let cancellation_token = cancellation_token().unwrap_or_default();
         let tracked_fut = {
             let ts = global_task_state.read().unwrap();
             ts.local_task_tracker.track_future(fut)
@@ -1430,7 +2167,10 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
             turbo_tasks(),
             CURRENT_GLOBAL_TASK_STATE.scope(
                 global_task_state,
-                CURRENT_LOCAL_TASK_STATE.scope(local_task_state, tracked_fut),
+                CURRENT_LOCAL_TASK_STATE.scope(
+                    local_task_state,
+                    CANCELLATION_TOKEN.scope(cancellation_token, tracked_fut),
+                ),
             ),
         ))
     }
@@ -1447,6 +2187,9 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
     fn pin(&self) -> Arc<dyn TurboTasksBackendApi<B>> {
         self.pin()
     }
+    fn event_bus(&self) -> &EventBus {
+        &self.events
+    }
     fn backend(&self) -> &B {
         &self.backend
     }
@@ -1660,6 +2403,25 @@ pub fn trait_call(
     with_turbo_tasks(|tt| tt.trait_call(trait_type, trait_fn_name, this, arg, persistence))
 }
 
+/// Runs the future in a local task of the current task and returns a [`Vc`] of its output.
+///
+/// Like a call to a [`#[turbo_tasks::function(local_cells)]`][crate::function], the result isn't
+/// cached. The reads of the future are tracked as reads of the current task, which is re-executed
+/// (running the future again) when one of them changes. This is cheaper than a task for glue code
+/// which is rarely called with the same inputs.
+///
+/// ```ignore
+/// let size = turbo_tasks::run_local(async move {
+///     Ok(Vc::cell(file.await?.len() as u64))
+/// });
+/// ```
+pub fn run_local<T: ?Sized + 'static>(
+    future: impl Future<Output = Result<Vc<T>>> + Send + 'static,
+) -> Vc<T> {
+    let future = Box::pin(async move { Ok(Vc::into_raw(future.await?)) });
+    Vc::from(with_turbo_tasks(|tt| tt.run_local(future)))
+}
+
 pub fn turbo_tasks() -> Arc<dyn TurboTasksApi> {
     TURBO_TASKS.with(|arc| arc.clone())
 }
@@ -1705,17 +2467,81 @@ pub fn spawn_detached_for_testing(f: impl Future<Output = Result<()>> + Send + '
     tokio::spawn(turbo_tasks().detached_for_testing(Box::pin(f.in_current_span())));
 }
 
+/// The cancellation token of the current task execution, see [`TurboTasksApi::cancel_task`].
+pub fn cancellation_token() -> Option<CancellationToken> {
+    CANCELLATION_TOKEN.try_with(|token| token.clone()).ok()
+}
+
+/// Whether the execution of the current task was canceled. Long-running task functions should
+/// check this at convenient points and stop early, e.g. with [`check_canceled`].
+pub fn is_canceled() -> bool {
+    CANCELLATION_TOKEN
+        .try_with(|token| token.is_canceled())
+        .unwrap_or(false)
+}
+
+/// Explains why a task was last invalidated, see [`TurboTasksApi::why_invalidated`].
+pub fn why_invalidated(task: TaskId) -> Vec<InvalidationStep> {
+    with_turbo_tasks(|tt| tt.why_invalidated(task))
+}
+
+/// Returns a [`Canceled`] error when the execution of the current task was canceled.
+pub fn check_canceled() -> Result<()> {
+    if is_canceled() {
+        return Err(Canceled.into());
+    }
+    Ok(())
+}
+
 pub fn current_task_for_testing() -> TaskId {
     CURRENT_GLOBAL_TASK_STATE.with(|ts| ts.read().unwrap().task_id)
 }
 
-/// Marks the current task as dirty when restored from persistent cache.
+/// Sends an event to the subscriptions of [`TurboTasks::subscribe_events`] for its type, e.g.
+/// after the current task wrote a file. The event isn't published again when the task is taken
+/// from the cache.
+pub fn publish_event<E: Clone + Send + Sync + 'static>(event: E) {
+    with_turbo_tasks(|tt| tt.event_bus().publish(event));
+}
+
+/// Marks the start of writing the output file at `path` from an effect. Call
+/// [`output_write_finished`] once the file was written completely, so a backend with a persistent
+/// cache can remove files which were only partially written when the process crashed.
+pub fn output_write_started(path: &Path) {
+    with_turbo_tasks(|tt| tt.output_write_started(path));
+}
+
+/// Marks the end of writing the output file at `path`, see [`output_write_started`].
+pub fn output_write_finished(path: &Path) {
+    with_turbo_tasks(|tt| tt.output_write_finished(path));
+}
+
+/// Marks the current task as dirty when restored from persistent cache. Same
+/// as [`mark_durability`][crate::mark_durability] with [`Durability::Low`].
 pub fn mark_session_dependent() {
     with_turbo_tasks(|tt| {
         tt.mark_own_task_as_session_dependent(current_task("turbo_tasks::mark_session_dependent()"))
     });
 }
 
+/// Hints that the given [`Vc`]s will likely be read soon, e.g. a module
+/// analysis can hint that its referenced modules will be analyzed next.
+///
+/// This speculatively schedules the tasks backing these [`Vc`]s if they are not
+/// computed yet or are dirty. It doesn't read them, so the current task doesn't
+/// depend on them.
+pub fn prefetch<T: ?Sized>(vcs: impl IntoIterator<Item = Vc<T>>) {
+    with_turbo_tasks(|tt| {
+        for vc in vcs {
+            match vc.node {
+                RawVc::TaskOutput(task) | RawVc::TaskCell(task, _) => tt.prefetch_task(task),
+                // Local tasks are always executed eagerly
+                RawVc::LocalOutput(..) | RawVc::LocalCell(..) => {}
+            }
+        }
+    });
+}
+
 /// Marks the current task as finished. This excludes it from waiting for
 /// strongly consistency.
 pub fn mark_finished() {
@@ -1739,6 +2565,20 @@ pub fn mark_stateful() -> SerializationInvalidator {
     })
 }
 
+/// Calls `callback` with the execution duration of the current task when its execution finishes.
+///
+/// The duration is the time the task was actually running, i.e. the time waiting for other tasks
+/// isn't included, so it can be attributed to the work done by the task itself. The callback isn't
+/// called when the task isn't executed because its result is cached.
+pub fn on_execution_completed(callback: impl FnOnce(Duration) + Send + Sync + 'static) {
+    CURRENT_GLOBAL_TASK_STATE.with(|cell| {
+        cell.write()
+            .unwrap()
+            .execution_completed_callbacks
+            .push(Box::new(callback));
+    })
+}
+
 pub fn prevent_gc() {
     mark_stateful();
 }
@@ -1752,14 +2592,24 @@ pub fn emit<T: VcValueTrait + ?Sized>(collectible: Vc<T>) {
     with_turbo_tasks(|tt| tt.emit_collectible(T::get_trait_type_id(), collectible.node))
 }
 
+/// Like [`emit`], but the collectible can be read in emission order with
+/// [`CollectiblesSource::peek_collectibles_ordered`][crate::CollectiblesSource::peek_collectibles_ordered],
+/// see [`CollectiblePosition`].
+pub fn emit_ordered<T: VcValueTrait + ?Sized>(collectible: Vc<T>) {
+    with_turbo_tasks(|tt| tt.emit_ordered_collectible(T::get_trait_type_id(), collectible.node))
+}
+
 pub async fn spawn_blocking<T: Send + 'static>(func: impl FnOnce() -> T + Send + 'static) -> T {
     let turbo_tasks = turbo_tasks();
+    let cancellation_token = cancellation_token().unwrap_or_default();
     let span = Span::current();
     let (result, duration, alloc_info) = tokio::task::spawn_blocking(|| {
         let _guard = span.entered();
         let start = Instant::now();
         let start_allocations = TurboMalloc::allocation_counters();
-        let r = turbo_tasks_scope(turbo_tasks, func);
+        let r = turbo_tasks_scope(turbo_tasks, || {
+            CANCELLATION_TOKEN.sync_scope(cancellation_token, func)
+        });
         (r, start.elapsed(), start_allocations.until_now())
     })
     .await
@@ -1769,7 +2619,7 @@ pub async fn spawn_blocking<T: Send + 'static>(func: impl FnOnce() -> T + Send +
     result
 }
 
-pub fn spawn_thread(func: impl FnOnce() + Send + 'static) {
+pub fn spawn_thread(func: impl FnOnce() + Send + 'static) -> thread::JoinHandle<()> {
     let handle = Handle::current();
     let span = info_span!("thread").or_current();
     thread::spawn(move || {
@@ -1778,7 +2628,7 @@ pub fn spawn_thread(func: impl FnOnce() + Send + 'static) {
         func();
         drop(guard);
         drop(span);
-    });
+    })
 }
 
 pub(crate) async fn read_task_output(
@@ -2005,11 +2855,19 @@ pub fn find_cell_by_type(ty: ValueTypeId) -> CurrentCellRef {
     })
 }
 
+pub(crate) fn with_task_locals<R>(
+    f: impl FnOnce(&mut FxHashMap<usize, Arc<dyn Any + Send + Sync>>) -> R,
+) -> R {
+    CURRENT_GLOBAL_TASK_STATE.with(|ts| f(&mut ts.write().unwrap().task_locals))
+}
+
 pub(crate) fn try_get_function_meta() -> Option<&'static FunctionMeta> {
     CURRENT_LOCAL_TASK_STATE.with(|ts| ts.function_meta)
 }
 
+#[track_caller]
 pub(crate) fn create_local_cell(value: TypedSharedReference) -> (ExecutionId, LocalCellId) {
+    let location = Location::caller();
     let execution_id = CURRENT_LOCAL_TASK_STATE.with(|ts| ts.execution_id);
     let raw_local_cell_id = CURRENT_GLOBAL_TASK_STATE.with(|ts| {
         let CurrentGlobalTaskState { local_cells, .. } = &mut *ts.write().unwrap();
@@ -2023,6 +2881,7 @@ pub(crate) fn create_local_cell(value: TypedSharedReference) -> (ExecutionId, Lo
     } else {
         unsafe { LocalCellId::new_unchecked(raw_local_cell_id as u32) }
     };
+    record_creation_site(execution_id, local_cell_id, location);
     (execution_id, local_cell_id)
 }
 
@@ -2039,7 +2898,7 @@ pub(crate) fn read_local_cell(
     execution_id: ExecutionId,
     local_cell_id: LocalCellId,
 ) -> TypedSharedReference {
-    assert_execution_id(execution_id);
+    assert_execution_id(execution_id, local_cell_id);
     CURRENT_GLOBAL_TASK_STATE.with(|ts| {
         let CurrentGlobalTaskState { local_cells, .. } = &*ts.write().unwrap();
         // local cell ids are one-indexed (they use NonZeroU32)
@@ -2048,26 +2907,33 @@ pub(crate) fn read_local_cell(
 }
 
 pub(crate) async fn read_local_output(
-    _this: &dyn TurboTasksApi,
-    _task_id: TaskId,
-    _local_output_id: LocalTaskId,
-    _consistency: ReadConsistency,
+    this: &dyn TurboTasksApi,
+    task_id: TaskId,
+    local_task_id: LocalTaskId,
+    consistency: ReadConsistency,
 ) -> Result<RawVc> {
-    todo!("bgw: local outputs");
+    loop {
+        match this.try_read_local_output(task_id, local_task_id, consistency)? {
+            Ok(result) => return Ok(result),
+            Err(listener) => listener.await,
+        }
+    }
 }
 
-/// Panics if the [`ExecutionId`] does not match the current task's
+/// Panics if the [`ExecutionId`] of a local cell does not match the current task's
 /// `execution_id`.
-pub(crate) fn assert_execution_id(execution_id: ExecutionId) {
+pub(crate) fn assert_execution_id(execution_id: ExecutionId, local_cell_id: LocalCellId) {
     CURRENT_LOCAL_TASK_STATE.with(|ts| {
         let CurrentLocalTaskState {
             execution_id: expected_execution_id,
             ..
         } = ts;
         assert_eq!(
-            &execution_id, expected_execution_id,
+            &execution_id,
+            expected_execution_id,
             "This Vc is local. Local Vcs must only be accessed within their own task. Resolve the \
-             Vc to convert it into a non-local version."
+             Vc to convert it into a non-local version. {}",
+            describe_creation_site(execution_id, local_cell_id)
         );
     })
 }
@@ -13,7 +13,7 @@ use crate::{
         function::{IntoTaskFnWithThis, NativeTaskFuture},
         IntoTaskFn, TaskFn,
     },
-    RawVc, TaskId, TaskInput,
+    RawVc, TaskId, TaskInput, TaskPriority,
 };
 
 type ResolveFunctor =
@@ -90,6 +90,13 @@ pub struct FunctionMeta {
     /// cached across task executions. Cells can be converted to their non-local
     /// versions by calling `Vc::resolve`.
     pub local_cells: bool,
+    /// Excludes the tasks of the function from garbage collection, see
+    /// [`GcPolicy`][crate::GcPolicy].
+    pub gc_exempt: bool,
+    /// The lowest priority the tasks of the function are scheduled with, see [`TaskPriority`].
+    pub priority: TaskPriority,
+    /// Where the function is defined, as `file:line`.
+    pub location: &'static str,
 }
 
 /// A native (rust) turbo-tasks function. It's used internally by
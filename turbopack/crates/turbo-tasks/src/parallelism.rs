@@ -0,0 +1,50 @@
+use std::future::Future;
+
+use crate::manager::with_turbo_tasks;
+
+/// Limits how many futures of a phase of the compilation, e.g. chunking or emitting, run at the
+/// same time. Trading parallelism for a lower peak memory usage helps on machines with limited
+/// memory.
+///
+/// Limits are declared as statics next to the code they limit, and configured by name with
+/// [`TurboTasksBuilder::parallelism_limit`][crate::TurboTasksBuilder::parallelism_limit]. Without
+/// a configured limit, futures run without waiting.
+///
+/// ```
+/// use turbo_tasks::ParallelismLimit;
+///
+/// static EXPENSIVE_THING: ParallelismLimit = ParallelismLimit::new("expensive-thing");
+///
+/// async fn expensive_thing() {
+///     EXPENSIVE_THING.run(async { /* ... */ }).await
+/// }
+/// ```
+pub struct ParallelismLimit {
+    name: &'static str,
+}
+
+impl ParallelismLimit {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// The name the limit is configured with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Runs the future once fewer futures than the configured limit are running.
+    ///
+    /// The future must not wait for other futures with the same limit, e.g. by reading a task
+    /// which uses it, as that deadlocks when the limit is reached.
+    pub async fn run<T>(&self, future: impl Future<Output = T>) -> T {
+        let Some(semaphore) = with_turbo_tasks(|tt| tt.parallelism_limit(self.name)) else {
+            return future.await;
+        };
+        let _permit = semaphore
+            .acquire()
+            .await
+            .expect("the semaphore of a parallelism limit is never closed");
+        future.await
+    }
+}
@@ -31,6 +31,7 @@ __turbo_tasks_internal_primitive!(usize);
 __turbo_tasks_internal_primitive!(isize);
 __turbo_tasks_internal_primitive!(serde_json::Value);
 __turbo_tasks_internal_primitive!(Duration);
+__turbo_tasks_internal_primitive!(turbo_tasks_hash::HashAlgorithm);
 __turbo_tasks_internal_primitive!(Vec<u8>, manual_shrink_to_fit);
 __turbo_tasks_internal_primitive!(Vec<bool>, manual_shrink_to_fit);
 
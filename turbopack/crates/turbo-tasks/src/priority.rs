@@ -0,0 +1,131 @@
+//! Lets latency-sensitive task executions run before less urgent ones, see [TaskPriority].
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use tokio::task_local;
+
+use crate::{event::Event, TaskId};
+
+/// How urgently the result of a task is needed.
+///
+/// A task is scheduled with the highest of the priority of its function, set with
+/// `#[turbo_tasks::function(priority = high)]`, and the priority of the code which caused it to
+/// be scheduled, set with [with_priority]. Task executions inherit their priority, so all tasks
+/// scheduled by a high-priority task are high-priority too.
+///
+/// An execution only starts when no execution with a higher priority is scheduled or running.
+/// Executions which are already running aren't interrupted, and an execution which a task with a
+/// higher priority waits for starts right away, so priorities can't cause deadlocks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaskPriority {
+    /// Speculative work, e.g. prefetching routes which might be requested later.
+    Low,
+    #[default]
+    Normal,
+    /// Work a user is waiting for, e.g. updates for hot module replacement.
+    High,
+}
+
+impl TaskPriority {
+    const ALL: [TaskPriority; 3] = [TaskPriority::Low, TaskPriority::Normal, TaskPriority::High];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+task_local! {
+    static PRIORITY: TaskPriority;
+}
+
+/// Runs `future` with the given priority: tasks scheduled by it are scheduled with at least this
+/// priority.
+pub async fn with_priority<T>(priority: TaskPriority, future: impl Future<Output = T>) -> T {
+    PRIORITY.scope(priority, future).await
+}
+
+/// The priority of the current task execution or [with_priority] scope.
+pub fn current_priority() -> TaskPriority {
+    PRIORITY.try_with(|priority| *priority).unwrap_or_default()
+}
+
+pub(crate) struct PriorityScheduler {
+    /// The number of scheduled or running executions of every priority.
+    pending: [AtomicUsize; 3],
+    /// Notified when the last pending execution of a priority finishes, or a waiting execution is
+    /// boosted.
+    event: Event,
+    /// The number of executions waiting for executions with a higher priority.
+    waiting: AtomicUsize,
+    /// Tasks which other tasks wait for, so their executions start right away. A task stays
+    /// boosted until its execution finishes, as it might be boosted before it waits for its turn.
+    boosted: Mutex<FxHashSet<TaskId>>,
+}
+
+impl PriorityScheduler {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Default::default(),
+            event: Event::new(|| "PriorityScheduler::event".to_string()),
+            waiting: Default::default(),
+            boosted: Default::default(),
+        }
+    }
+
+    /// Records that an execution with `priority` was scheduled. It must be followed by
+    /// [Self::finish] or [Self::finish_priority] with the same priority.
+    pub(crate) fn schedule(&self, priority: TaskPriority) {
+        self.pending[priority.index()].fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Records that an execution of `task` with `priority` finished.
+    pub(crate) fn finish(&self, task: TaskId, priority: TaskPriority) {
+        self.boosted.lock().remove(&task);
+        self.finish_priority(priority);
+    }
+
+    /// Records that an execution with `priority` changed its priority or finished.
+    pub(crate) fn finish_priority(&self, priority: TaskPriority) {
+        if self.pending[priority.index()].fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.event.notify(usize::MAX);
+        }
+    }
+
+    fn has_higher_pending(&self, priority: TaskPriority) -> bool {
+        TaskPriority::ALL
+            .into_iter()
+            .filter(|&other| other > priority)
+            .any(|other| self.pending[other.index()].load(Ordering::Acquire) > 0)
+    }
+
+    /// Waits until no execution with a higher priority is pending, or the task is boosted.
+    pub(crate) async fn wait_for_turn(&self, task: TaskId, priority: TaskPriority) {
+        if !self.has_higher_pending(priority) {
+            return;
+        }
+        self.waiting.fetch_add(1, Ordering::AcqRel);
+        loop {
+            let listener = self.event.listen();
+            if self.boosted.lock().contains(&task) || !self.has_higher_pending(priority) {
+                break;
+            }
+            listener.await;
+        }
+        self.waiting.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Lets the execution of the task start right away, e.g. because another task waits for it.
+    pub(crate) fn boost(&self, task: TaskId) {
+        if !self.boosted.lock().insert(task) {
+            return;
+        }
+        if self.waiting.load(Ordering::Acquire) > 0 {
+            self.event.notify(usize::MAX);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use dashmap::DashMap;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    manager::{current_task, with_turbo_tasks},
+    TaskId,
+};
+
+/// How many events are buffered for a subscription before it misses events.
+const CAPACITY: usize = 256;
+
+/// The progress of a long-running task, e.g. how many modules of a module graph have been
+/// visited.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub completed: u64,
+    /// The number of steps in total, when it's known upfront.
+    pub total: Option<u64>,
+    /// Describes what the task is working on.
+    pub message: Option<Arc<str>>,
+}
+
+/// A [Progress] reported by a task, see [ProgressReporter].
+#[derive(Clone, Debug)]
+pub struct ProgressEvent {
+    /// The task which reported the progress.
+    pub task: TaskId,
+    /// The root task the reporting task was first called or read by, when a subscription for
+    /// that root task existed at the time.
+    pub root: Option<TaskId>,
+    pub progress: Progress,
+}
+
+/// Reports the progress of the current task to subscribers of
+/// [`TurboTasks::subscribe_progress`][crate::TurboTasks::subscribe_progress].
+///
+/// A `#[turbo_tasks::function]` gets a reporter injected by declaring an argument of this type,
+/// which isn't part of the inputs of the task:
+///
+/// ```ignore
+/// #[turbo_tasks::function]
+/// async fn build_graph(entries: Vc<Modules>, progress: ProgressReporter) -> Result<Vc<Graph>> {
+///     for (i, module) in modules.iter().enumerate() {
+///         progress.report(i as u64 + 1, Some(modules.len() as u64));
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Reporting is cheap when nobody is subscribed.
+#[derive(Clone, Debug)]
+pub struct ProgressReporter {
+    task: TaskId,
+}
+
+impl ProgressReporter {
+    /// A reporter for the currently executing task.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a task.
+    pub fn current() -> Self {
+        Self {
+            task: current_task("reporting progress"),
+        }
+    }
+
+    /// Reports that `completed` of `total` steps are done.
+    pub fn report(&self, completed: u64, total: Option<u64>) {
+        self.report_progress(Progress {
+            completed,
+            total,
+            message: None,
+        });
+    }
+
+    /// Reports that `completed` of `total` steps are done, along with what the task is working
+    /// on.
+    pub fn report_with_message(
+        &self,
+        completed: u64,
+        total: Option<u64>,
+        message: impl Into<Arc<str>>,
+    ) {
+        self.report_progress(Progress {
+            completed,
+            total,
+            message: Some(message.into()),
+        });
+    }
+
+    pub fn report_progress(&self, progress: Progress) {
+        with_turbo_tasks(|tt| tt.report_progress(self.task, progress));
+    }
+}
+
+/// Distributes [ProgressEvent]s to subscriptions, and attributes the tasks to the root tasks
+/// subscriptions filter by.
+pub(crate) struct ProgressTracker {
+    sender: broadcast::Sender<ProgressEvent>,
+    /// The number of subscriptions which filter by a root task. Roots are only tracked while
+    /// there are any.
+    root_subscriptions: AtomicUsize,
+    /// The root task every task was first called or read by.
+    roots: DashMap<TaskId, TaskId>,
+}
+
+impl Default for ProgressTracker {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+            root_subscriptions: AtomicUsize::new(0),
+            roots: DashMap::new(),
+        }
+    }
+}
+
+impl ProgressTracker {
+    /// Attributes `child` to the root task of `parent`, when `parent` calls or reads `child`.
+    pub(crate) fn track_child(&self, parent: TaskId, child: TaskId) {
+        if self.root_subscriptions.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        let Some(root) = self.roots.get(&parent).map(|root| *root) else {
+            return;
+        };
+        self.roots.entry(child).or_insert(root);
+    }
+
+    pub(crate) fn report(&self, task: TaskId, progress: Progress) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let root = self.roots.get(&task).map(|root| *root);
+        // Sending only fails when there are no subscriptions.
+        let _ = self.sender.send(ProgressEvent {
+            task,
+            root,
+            progress,
+        });
+    }
+
+    pub(crate) fn subscribe(self: &Arc<Self>, root: Option<TaskId>) -> ProgressSubscription {
+        if let Some(root) = root {
+            self.root_subscriptions.fetch_add(1, Ordering::Relaxed);
+            self.roots.insert(root, root);
+        }
+        ProgressSubscription {
+            receiver: self.sender.subscribe(),
+            root,
+            tracker: self.clone(),
+        }
+    }
+}
+
+/// Receives the [ProgressEvent]s of all tasks, or of the tasks of a root task, see
+/// [`TurboTasks::subscribe_progress`][crate::TurboTasks::subscribe_progress].
+pub struct ProgressSubscription {
+    receiver: broadcast::Receiver<ProgressEvent>,
+    root: Option<TaskId>,
+    tracker: Arc<ProgressTracker>,
+}
+
+impl ProgressSubscription {
+    /// Waits for the next event. Events are skipped when the subscription lags behind.
+    pub async fn recv(&mut self) -> ProgressEvent {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => {
+                    if self.root.is_none() || event.root == self.root {
+                        return event;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => unreachable!("the tracker holds the sender"),
+            }
+        }
+    }
+}
+
+impl Drop for ProgressSubscription {
+    fn drop(&mut self) {
+        if self.root.is_some()
+            && self
+                .tracker
+                .root_subscriptions
+                .fetch_sub(1, Ordering::Relaxed)
+                == 1
+        {
+            self.tracker.roots.clear();
+        }
+    }
+}
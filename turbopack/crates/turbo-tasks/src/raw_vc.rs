@@ -9,11 +9,12 @@ use std::{
 
 use anyhow::Result;
 use auto_hash_map::AutoSet;
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    backend::{CellContent, TypedCellContent},
+    backend::{CellContent, TaskCollectiblesMap, TypedCellContent},
     event::EventListener,
     id::{ExecutionId, LocalCellId, LocalTaskId},
     manager::{
@@ -235,6 +236,56 @@ impl RawVc {
         }
     }
 
+    /// Resolves all `vcs` together, in rounds. Each round reads the outputs of all unresolved tasks
+    /// without waiting, and then waits for all pending tasks at once. Compared to resolving each
+    /// [RawVc] separately, this doesn't create a future per [RawVc] and notifies scheduled tasks
+    /// only once.
+    pub(crate) async fn resolve_all(mut vcs: Vec<RawVc>) -> Result<Vec<RawVc>> {
+        let tt = turbo_tasks();
+        let mut notified = false;
+        loop {
+            let mut listeners = Vec::new();
+            for vc in vcs.iter_mut() {
+                loop {
+                    match *vc {
+                        RawVc::TaskOutput(task) => {
+                            if !notified {
+                                tt.notify_scheduled_tasks();
+                                notified = true;
+                            }
+                            match tt.try_read_task_output(task, ReadConsistency::Eventual)? {
+                                Ok(result) => *vc = result,
+                                Err(listener) => {
+                                    listeners.push(listener);
+                                    break;
+                                }
+                            }
+                        }
+                        RawVc::TaskCell(_, _) => break,
+                        RawVc::LocalOutput(task_id, local_cell_id) => {
+                            *vc = read_local_output(
+                                &*tt,
+                                task_id,
+                                local_cell_id,
+                                ReadConsistency::Eventual,
+                            )
+                            .await?;
+                        }
+                        RawVc::LocalCell(execution_id, local_cell_id) => {
+                            let shared_reference = read_local_cell(execution_id, local_cell_id);
+                            let value_type = get_value_type(shared_reference.0);
+                            *vc = (value_type.raw_cell)(shared_reference);
+                        }
+                    }
+                }
+            }
+            if listeners.is_empty() {
+                return Ok(vcs);
+            }
+            join_all(listeners).await;
+        }
+    }
+
     pub(crate) fn connect(&self) {
         let tt = turbo_tasks();
         tt.connect_task(self.get_task_id());
@@ -243,8 +294,8 @@ impl RawVc {
     pub fn get_task_id(&self) -> TaskId {
         match self {
             RawVc::TaskOutput(t) | RawVc::TaskCell(t, _) | RawVc::LocalOutput(t, _) => *t,
-            RawVc::LocalCell(execution_id, _) => {
-                assert_execution_id(*execution_id);
+            RawVc::LocalCell(execution_id, local_cell_id) => {
+                assert_execution_id(*execution_id, *local_cell_id);
                 current_task("RawVc::get_task_id")
             }
         }
@@ -270,6 +321,39 @@ impl CollectiblesSource for RawVc {
             .filter_map(|(raw, count)| (count > 0).then_some(raw.into()))
             .collect()
     }
+
+    fn take_collectibles_ordered<T: VcValueTrait + ?Sized>(self) -> Vec<Vc<T>> {
+        let tt = turbo_tasks();
+        tt.notify_scheduled_tasks();
+        let map = tt.read_task_collectibles(self.get_task_id(), T::get_trait_type_id());
+        tt.unemit_collectibles(T::get_trait_type_id(), &map);
+        order_collectibles(&*tt, T::get_trait_type_id(), map)
+    }
+
+    fn peek_collectibles_ordered<T: VcValueTrait + ?Sized>(self) -> Vec<Vc<T>> {
+        let tt = turbo_tasks();
+        tt.notify_scheduled_tasks();
+        let map = tt.read_task_collectibles(self.get_task_id(), T::get_trait_type_id());
+        order_collectibles(&*tt, T::get_trait_type_id(), map)
+    }
+}
+
+/// Sorts collectibles by their [`CollectiblePosition`], with the ones without a position last.
+fn order_collectibles<T: ?Sized>(
+    tt: &dyn TurboTasksApi,
+    trait_type: TraitTypeId,
+    map: TaskCollectiblesMap,
+) -> Vec<Vc<T>> {
+    let mut collectibles = map
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(raw, _)| (tt.collectible_position(trait_type, raw), raw))
+        .collect::<Vec<_>>();
+    collectibles.sort_by_key(|&(position, _)| (position.is_none(), position));
+    collectibles
+        .into_iter()
+        .map(|(_, raw)| raw.into())
+        .collect()
 }
 
 pub struct ReadRawVcFuture {
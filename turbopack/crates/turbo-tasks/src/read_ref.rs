@@ -2,7 +2,8 @@ use std::{
     fmt::{Debug, Display},
     hash::Hash,
     marker::PhantomData,
-    mem::transmute_copy,
+    mem::{transmute_copy, ManuallyDrop},
+    ptr::NonNull,
 };
 
 use serde::{Deserialize, Serialize};
@@ -262,6 +263,45 @@ where
     }
 }
 
+impl<T> ReadRef<T>
+where
+    T: VcValueType,
+{
+    /// Projects the read value to a part of it, e.g. a field, which keeps the whole value alive
+    /// without cloning it. Like `RwLockReadGuard::map`.
+    ///
+    /// ```ignore
+    /// let path: MappedReadRef<RcStr> = ReadRef::map(fs_path.await?, |fs_path| &fs_path.path);
+    /// ```
+    ///
+    /// This is an associated function, so it doesn't shadow methods of the value, like
+    /// `Option::map`.
+    pub fn map<F: ?Sized>(
+        read_ref: ReadRef<T>,
+        f: impl FnOnce(&VcReadTarget<T>) -> &F,
+    ) -> MappedReadRef<F> {
+        let value = NonNull::from(f(&*read_ref));
+        MappedReadRef {
+            owner: ErasedReadRef::new(read_ref),
+            value,
+        }
+    }
+
+    /// Like [ReadRef::map], but the projection can fail, in which case the `ReadRef` is returned.
+    pub fn filter_map<F: ?Sized>(
+        read_ref: ReadRef<T>,
+        f: impl FnOnce(&VcReadTarget<T>) -> Option<&F>,
+    ) -> Result<MappedReadRef<F>, ReadRef<T>> {
+        match f(&*read_ref).map(NonNull::from) {
+            Some(value) => Ok(MappedReadRef {
+                owner: ErasedReadRef::new(read_ref),
+                value,
+            }),
+            None => Err(read_ref),
+        }
+    }
+}
+
 impl<T> ReadRef<T>
 where
     T: VcValueType,
@@ -273,3 +313,193 @@ where
         (**self).clone()
     }
 }
+
+/// A reference counted [ReadRef] of any type.
+struct ErasedReadRef {
+    ptr: NonNull<()>,
+    clone: unsafe fn(NonNull<()>),
+    drop: unsafe fn(NonNull<()>),
+}
+
+impl ErasedReadRef {
+    fn new<T: VcValueType>(read_ref: ReadRef<T>) -> Self {
+        unsafe fn clone<T>(ptr: NonNull<()>) {
+            // SAFETY: `ptr` was created by `Arc::into_raw` for a `T`, and the reference is still
+            // owned by the `ErasedReadRef`, so only the count is incremented.
+            let arc =
+                ManuallyDrop::new(unsafe { triomphe::Arc::from_raw(ptr.cast::<T>().as_ptr()) });
+            std::mem::forget(triomphe::Arc::clone(&arc));
+        }
+        unsafe fn drop<T>(ptr: NonNull<()>) {
+            // SAFETY: `ptr` was created by `Arc::into_raw` for a `T`, and the reference owned by
+            // the `ErasedReadRef` is given up.
+            std::mem::drop(unsafe { triomphe::Arc::from_raw(ptr.cast::<T>().as_ptr()) });
+        }
+        let ptr = triomphe::Arc::into_raw(read_ref.0);
+        Self {
+            // SAFETY: `Arc::into_raw` never returns null.
+            ptr: unsafe { NonNull::new_unchecked(ptr as *mut T) }.cast(),
+            clone: clone::<T>,
+            drop: drop::<T>,
+        }
+    }
+}
+
+impl Clone for ErasedReadRef {
+    fn clone(&self) -> Self {
+        // SAFETY: `clone` is the function for the type of the `Arc`.
+        unsafe { (self.clone)(self.ptr) };
+        Self {
+            ptr: self.ptr,
+            clone: self.clone,
+            drop: self.drop,
+        }
+    }
+}
+
+impl Drop for ErasedReadRef {
+    fn drop(&mut self) {
+        // SAFETY: `drop` is the function for the type of the `Arc`.
+        unsafe { (self.drop)(self.ptr) };
+    }
+}
+
+/// A part of a read value, e.g. a field, created by [ReadRef::map]. It keeps the whole value
+/// alive, but its type doesn't depend on the type of the value.
+pub struct MappedReadRef<F: ?Sized> {
+    owner: ErasedReadRef,
+    /// Points into the value owned by `owner`.
+    value: NonNull<F>,
+}
+
+// SAFETY: The owned value is `Send + Sync` as it's a `VcValueType`, and only shared references to
+// `F` are handed out.
+unsafe impl<F: ?Sized + Sync> Send for MappedReadRef<F> {}
+unsafe impl<F: ?Sized + Sync> Sync for MappedReadRef<F> {}
+
+impl<F: ?Sized> MappedReadRef<F> {
+    /// Projects to a part of the part, see [ReadRef::map].
+    pub fn map<G: ?Sized>(this: Self, f: impl FnOnce(&F) -> &G) -> MappedReadRef<G> {
+        let value = NonNull::from(f(&*this));
+        MappedReadRef {
+            owner: this.owner,
+            value,
+        }
+    }
+
+    /// Like [MappedReadRef::map], but the projection can fail, in which case `this` is returned.
+    pub fn filter_map<G: ?Sized>(
+        this: Self,
+        f: impl FnOnce(&F) -> Option<&G>,
+    ) -> Result<MappedReadRef<G>, Self> {
+        match f(&*this).map(NonNull::from) {
+            Some(value) => Ok(MappedReadRef {
+                owner: this.owner,
+                value,
+            }),
+            None => Err(this),
+        }
+    }
+
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::ptr::eq(this.value.as_ptr(), other.value.as_ptr())
+    }
+}
+
+impl<F: ?Sized> std::ops::Deref for MappedReadRef<F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        // SAFETY: The value is kept alive by `owner` and never mutated.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<F: ?Sized> Clone for MappedReadRef<F> {
+    fn clone(&self) -> Self {
+        Self {
+            owner: self.owner.clone(),
+            value: self.value,
+        }
+    }
+}
+
+impl<F: ?Sized + Display> Display for MappedReadRef<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<F: ?Sized + Debug> Debug for MappedReadRef<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<F: ?Sized + TraceRawVcs> TraceRawVcs for MappedReadRef<F> {
+    fn trace_raw_vcs(&self, trace_context: &mut TraceRawVcsContext) {
+        (**self).trace_raw_vcs(trace_context);
+    }
+}
+
+impl<F: ?Sized + PartialEq> PartialEq for MappedReadRef<F> {
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<F: ?Sized + Eq> Eq for MappedReadRef<F> {}
+
+impl<F: ?Sized + PartialOrd> PartialOrd for MappedReadRef<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<F: ?Sized + Ord> Ord for MappedReadRef<F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<F: ?Sized + Hash> Hash for MappedReadRef<F> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<F: ?Sized + Serialize> Serialize for MappedReadRef<F> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_rcstr::RcStr;
+
+    use super::{MappedReadRef, ReadRef};
+
+    #[test]
+    fn map_keeps_the_value_alive() {
+        let read_ref = ReadRef::<Vec<RcStr>>::new_owned(vec!["a".into(), "bc".into()]);
+        let second = ReadRef::map(read_ref.clone(), |list| &list[1]);
+        drop(read_ref);
+        let clone = second.clone();
+        drop(second);
+        let suffix = MappedReadRef::map(clone, |s| &s[1..]);
+        assert_eq!(&*suffix, "c");
+    }
+
+    #[test]
+    fn filter_map_returns_the_read_ref_on_failure() {
+        let read_ref = ReadRef::<Vec<RcStr>>::new_owned(vec!["a".into()]);
+        let read_ref = ReadRef::filter_map(read_ref, |list| list.get(1)).unwrap_err();
+        let first = ReadRef::filter_map(read_ref, |list| list.first()).unwrap();
+        assert_eq!(&**first, "a");
+        assert!(MappedReadRef::filter_map(first, |s| s.strip_prefix('b')).is_err());
+    }
+}
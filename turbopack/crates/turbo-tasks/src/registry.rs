@@ -1,4 +1,5 @@
 use std::{
+    any::{type_name, TypeId},
     fmt::Debug,
     hash::{BuildHasherDefault, Hash},
     num::NonZeroU64,
@@ -32,6 +33,10 @@ static VALUE_TYPES_BY_VALUE: Lazy<FxDashMap<&'static ValueType, ValueTypeId>> =
     Lazy::new(DashMap::default);
 static VALUE_TYPES: Lazy<NoMoveVec<(&'static ValueType, &'static str)>> = Lazy::new(NoMoveVec::new);
 
+/// Monomorphized instantiations of generic value types, which are registered when they're used
+/// for the first time.
+static GENERIC_VALUE_TYPES: Lazy<FxDashMap<TypeId, ValueTypeId>> = Lazy::new(DashMap::default);
+
 static TRAIT_TYPE_ID_FACTORY: IdFactory<TraitTypeId> = IdFactory::new(1, u32::MAX as u64);
 static TRAIT_TYPES_BY_NAME: Lazy<FxDashMap<&'static str, TraitTypeId>> =
     Lazy::new(DashMap::default);
@@ -118,6 +123,29 @@ pub fn get_value_type_id(func: &'static ValueType) -> ValueTypeId {
     get_thing_id(func, &VALUE_TYPES_BY_VALUE)
 }
 
+/// Returns the id of an instantiation of a generic `#[turbo_tasks::value]` type. The instantiation
+/// is registered with the value type created by `new_value_type` when it's used for the first
+/// time, with its type name as the global name.
+///
+/// As instantiations are registered lazily, an instantiation can only be looked up by its global
+/// name once it has been used in this process.
+pub fn get_or_register_generic_value_type_id<T: 'static>(
+    new_value_type: impl FnOnce() -> ValueType,
+) -> ValueTypeId {
+    let type_id = TypeId::of::<T>();
+    if let Some(id) = GENERIC_VALUE_TYPES.get(&type_id) {
+        return *id;
+    }
+    // Create the value type outside of the entry lock, so it can't deadlock with other
+    // instantiations.
+    let value_type = new_value_type();
+    *GENERIC_VALUE_TYPES.entry(type_id).or_insert_with(|| {
+        let value_type: &'static ValueType = Box::leak(Box::new(value_type));
+        register_value_type(type_name::<T>(), value_type);
+        get_value_type_id(value_type)
+    })
+}
+
 pub fn get_value_type_id_by_global_name(global_name: &str) -> Option<ValueTypeId> {
     VALUE_TYPES_BY_NAME.get(global_name).map(|x| *x)
 }
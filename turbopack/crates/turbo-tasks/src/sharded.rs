@@ -0,0 +1,210 @@
+//! Stores large collections in multiple cells, see [Sharded].
+
+use std::hash::{Hash, Hasher};
+
+use anyhow::Result;
+use rustc_hash::FxHasher;
+
+use crate::{
+    self as turbo_tasks, FxIndexMap, FxIndexSet, ReadRef, ResolvedVc, TryJoinIterExt, Vc,
+    VcTransparentRead, VcValueType,
+};
+
+/// The average number of items in a shard created by [Sharded::new].
+pub const DEFAULT_SHARD_SIZE: usize = 1024;
+
+/// A transparent value type whose collection can be stored in multiple cells, see [Sharded].
+///
+/// Implemented by `#[turbo_tasks::value(transparent, sharded)]`.
+pub trait Shardable: VcValueType<Read = VcTransparentRead<Self, Self::Collection, Self>> {
+    type Collection: ShardableCollection + Send + Sync + 'static;
+}
+
+/// A collection which can be split into consecutive shards.
+pub trait ShardableCollection: Sized {
+    /// Splits the collection into shards of about `shard_size` items. Where a shard ends only
+    /// depends on the item it ends with, so inserting or removing an item usually only changes the
+    /// shard containing it.
+    fn split(self, shard_size: usize) -> Vec<Self>;
+
+    /// Concatenates the shards created by [ShardableCollection::split].
+    fn concat<'a>(shards: impl IntoIterator<Item = &'a Self>) -> Self
+    where
+        Self: 'a;
+}
+
+fn fx_hash(value: &impl Hash) -> u64 {
+    let mut hasher = FxHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Splits `items` after every item whose hash is divisible by `shard_size`, and after
+/// `4 * shard_size` items at the latest.
+fn split_items<I>(
+    items: impl IntoIterator<Item = I>,
+    shard_size: usize,
+    hash: impl Fn(&I) -> u64,
+) -> Vec<Vec<I>> {
+    let shard_size = shard_size.max(1);
+    let mut shards = Vec::new();
+    let mut shard = Vec::new();
+    for item in items {
+        // The high bits of FxHash are mixed better.
+        let is_boundary = (hash(&item) >> 32) % shard_size as u64 == 0;
+        shard.push(item);
+        if is_boundary || shard.len() >= 4 * shard_size {
+            shards.push(std::mem::take(&mut shard));
+        }
+    }
+    if !shard.is_empty() {
+        shards.push(shard);
+    }
+    shards
+}
+
+impl<T: Hash + Clone> ShardableCollection for Vec<T> {
+    fn split(self, shard_size: usize) -> Vec<Self> {
+        split_items(self, shard_size, fx_hash)
+    }
+
+    fn concat<'a>(shards: impl IntoIterator<Item = &'a Self>) -> Self
+    where
+        Self: 'a,
+    {
+        shards.into_iter().flatten().cloned().collect()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardableCollection for FxIndexMap<K, V> {
+    fn split(self, shard_size: usize) -> Vec<Self> {
+        split_items(self, shard_size, |(key, _)| fx_hash(key))
+            .into_iter()
+            .map(FxIndexMap::from_iter)
+            .collect()
+    }
+
+    fn concat<'a>(shards: impl IntoIterator<Item = &'a Self>) -> Self
+    where
+        Self: 'a,
+    {
+        shards
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<T: Hash + Eq + Clone> ShardableCollection for FxIndexSet<T> {
+    fn split(self, shard_size: usize) -> Vec<Self> {
+        split_items(self, shard_size, fx_hash)
+            .into_iter()
+            .map(FxIndexSet::from_iter)
+            .collect()
+    }
+
+    fn concat<'a>(shards: impl IntoIterator<Item = &'a Self>) -> Self
+    where
+        Self: 'a,
+    {
+        shards.into_iter().flatten().cloned().collect()
+    }
+}
+
+/// A large collection stored in multiple cells of the task which created it, plus this index of
+/// them.
+///
+/// Every shard is compared and persisted on its own, so a small change to the collection only
+/// invalidates the readers of the changed shard, and only the changed shard is serialized again.
+///
+/// ```ignore
+/// #[turbo_tasks::value(transparent, sharded)]
+/// pub struct ModuleIds(FxIndexMap<RcStr, ModuleId>);
+///
+/// #[turbo_tasks::function]
+/// async fn module_ids(..) -> Result<Vc<Sharded<ModuleIds>>> {
+///     Ok(Sharded::new(ids))
+/// }
+/// ```
+#[turbo_tasks::value]
+pub struct Sharded<T: Shardable> {
+    shards: Vec<ResolvedVc<T>>,
+}
+
+impl<T: Shardable> Sharded<T>
+where
+    Self: VcValueType,
+{
+    /// Splits `collection` into shards of about [DEFAULT_SHARD_SIZE] items, and places the shards
+    /// and their index in cells of the current task.
+    pub fn new(collection: T::Collection) -> Vc<Self> {
+        Self::with_shard_size(collection, DEFAULT_SHARD_SIZE)
+    }
+
+    /// Like [Sharded::new], with about `shard_size` items per shard.
+    pub fn with_shard_size(collection: T::Collection, shard_size: usize) -> Vc<Self> {
+        Sharded {
+            shards: collection
+                .split(shard_size)
+                .into_iter()
+                .map(ResolvedVc::cell)
+                .collect(),
+        }
+        .cell()
+    }
+
+    pub fn shards(&self) -> &[ResolvedVc<T>] {
+        &self.shards
+    }
+
+    /// Reads all shards, which avoids copying the items.
+    pub async fn read_shards(this: Vc<Self>) -> Result<Vec<ReadRef<T>>> {
+        this.await?
+            .shards
+            .iter()
+            .map(|&shard| async move { shard.await })
+            .try_join()
+            .await
+    }
+
+    /// Reads all shards and concatenates them to the collection.
+    pub async fn read(this: Vc<Self>) -> Result<T::Collection> {
+        let shards = Self::read_shards(this).await?;
+        Ok(T::Collection::concat(shards.iter().map(|shard| &**shard)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShardableCollection;
+    use crate::FxIndexMap;
+
+    #[test]
+    fn split_is_stable_under_insertion() {
+        let items = (0..10_000u32).collect::<Vec<_>>();
+        let shards = items.clone().split(64);
+        assert!(shards.len() > 1);
+        assert!(shards.iter().all(|shard| shard.len() <= 4 * 64));
+        assert_eq!(<Vec<u32>>::concat(&shards), items);
+
+        let mut changed = items.clone();
+        changed.insert(5_000, 1_000_000);
+        let changed_shards = changed.split(64);
+        let unchanged = changed_shards
+            .iter()
+            .filter(|shard| shards.contains(shard))
+            .count();
+        assert!(unchanged + 2 >= shards.len());
+    }
+
+    #[test]
+    fn map_roundtrip() {
+        let map = (0..1_000u32)
+            .map(|i| (i, i.to_string()))
+            .collect::<FxIndexMap<_, _>>();
+        let shards = map.clone().split(16);
+        assert_eq!(<FxIndexMap<u32, String>>::concat(&shards), map);
+        assert!(FxIndexMap::<u32, String>::default().split(16).is_empty());
+    }
+}
@@ -1,22 +1,67 @@
 use std::{
     fmt::Debug,
+    future::Future,
     mem::take,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use auto_hash_map::AutoSet;
 use parking_lot::{Mutex, MutexGuard};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task_local;
 
 use crate::{
-    get_invalidator, mark_session_dependent, mark_stateful, trace::TraceRawVcs, Invalidator,
-    SerializationInvalidator,
+    get_invalidator, invalidate_all, mark_session_dependent, mark_stateful, trace::TraceRawVcs,
+    Invalidator, SerializationInvalidator,
 };
 
+task_local! {
+    /// The invalidators collected by the current [state_transaction].
+    static TRANSACTION: Arc<Mutex<AutoSet<Invalidator>>>;
+}
+
+/// Invalidates the tasks which read a state, or defers it to the end of the current
+/// [state_transaction].
+fn invalidate(invalidators: AutoSet<Invalidator>) {
+    if invalidators.is_empty() {
+        return;
+    }
+    if let Ok(transaction) = TRANSACTION.try_with(Arc::clone) {
+        transaction.lock().extend(invalidators);
+    } else {
+        for invalidator in invalidators {
+            invalidator.invalidate();
+        }
+    }
+}
+
+/// Invalidates the collected tasks when a [state_transaction] ends, even when it's cancelled.
+struct TransactionGuard(Arc<Mutex<AutoSet<Invalidator>>>);
+
+impl Drop for TransactionGuard {
+    fn drop(&mut self) {
+        invalidate_all(take(&mut *self.0.lock()));
+    }
+}
+
+/// Identifies a value of a [State], see [State::compare_and_set].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateVersion(u64);
+
+/// Returned by [State::compare_and_set] when the state has changed since its version was read.
+#[derive(Error, Debug)]
+#[error("the state was changed since it was read")]
+pub struct StateConflict;
+
 #[derive(Serialize, Deserialize)]
 struct StateInner<T> {
     value: T,
     invalidators: AutoSet<Invalidator>,
+    /// Incremented on every change, so versions are only unique within a process.
+    #[serde(skip)]
+    version: u64,
 }
 
 impl<T> StateInner<T> {
@@ -24,6 +69,7 @@ impl<T> StateInner<T> {
         Self {
             value,
             invalidators: AutoSet::new(),
+            version: 0,
         }
     }
 
@@ -31,20 +77,21 @@ impl<T> StateInner<T> {
         self.invalidators.insert(invalidator);
     }
 
+    fn changed(&mut self) {
+        self.version += 1;
+        invalidate(take(&mut self.invalidators));
+    }
+
     pub fn set_unconditionally(&mut self, value: T) {
         self.value = value;
-        for invalidator in take(&mut self.invalidators) {
-            invalidator.invalidate();
-        }
+        self.changed();
     }
 
     pub fn update_conditionally(&mut self, update: impl FnOnce(&mut T) -> bool) -> bool {
         if !update(&mut self.value) {
             return false;
         }
-        for invalidator in take(&mut self.invalidators) {
-            invalidator.invalidate();
-        }
+        self.changed();
         true
     }
 }
@@ -55,9 +102,7 @@ impl<T: PartialEq> StateInner<T> {
             return false;
         }
         self.value = value;
-        for invalidator in take(&mut self.invalidators) {
-            invalidator.invalidate();
-        }
+        self.changed();
         true
     }
 }
@@ -83,12 +128,17 @@ impl<T> DerefMut for StateRef<'_, T> {
     }
 }
 
+impl<T> StateRef<'_, T> {
+    /// The version of the value, see [State::compare_and_set].
+    pub fn version(&self) -> StateVersion {
+        StateVersion(self.inner.version)
+    }
+}
+
 impl<T> Drop for StateRef<'_, T> {
     fn drop(&mut self) {
         if self.mutated {
-            for invalidator in take(&mut self.inner.invalidators) {
-                invalidator.invalidate();
-            }
+            self.inner.changed();
             if let Some(serialization_invalidator) = self.serialization_invalidator {
                 serialization_invalidator.invalidate();
             }
@@ -187,6 +237,60 @@ impl<T> State<T> {
     }
 }
 
+impl<T> State<T> {
+    /// The version of the current value. Untracked.
+    pub fn version(&self) -> StateVersion {
+        StateVersion(self.inner.lock().version)
+    }
+
+    /// Sets the state to `value`, unless it was changed since `expected` was read with
+    /// [State::version] or [StateRef::version]. Unlike [State::set], updates which are computed
+    /// from a previously read value can't overwrite concurrent updates.
+    pub fn compare_and_set(&self, expected: StateVersion, value: T) -> Result<(), StateConflict> {
+        {
+            let mut inner = self.inner.lock();
+            if inner.version != expected.0 {
+                return Err(StateConflict);
+            }
+            inner.set_unconditionally(value);
+        }
+        self.serialization_invalidator.invalidate();
+        Ok(())
+    }
+
+    /// Replaces the current value with the one computed by `update` from it, atomically. Returns
+    /// whether `update` returned a new value.
+    pub fn replace_with(&self, update: impl FnOnce(&T) -> Option<T>) -> bool {
+        {
+            let mut inner = self.inner.lock();
+            let Some(value) = update(&inner.value) else {
+                return false;
+            };
+            inner.set_unconditionally(value);
+        }
+        self.serialization_invalidator.invalidate();
+        true
+    }
+}
+
+/// Runs `future` and defers invalidating the tasks which read the states it changes until it
+/// completes, then invalidates them in one batch, see [invalidate_all]. So the tasks are
+/// recomputed once instead of once per change.
+///
+/// The values are still written immediately: a task which reads a changed state before the
+/// transaction ends, e.g. because it runs for the first time, sees the changes made so far.
+///
+/// Only changes made by `future` itself are batched, not the ones made by tasks it calls.
+/// Nested transactions are part of the outermost one.
+pub async fn state_transaction<R>(future: impl Future<Output = R>) -> R {
+    if TRANSACTION.try_with(|_| ()).is_ok() {
+        return future.await;
+    }
+    let invalidators = Arc::new(Mutex::new(AutoSet::new()));
+    let _guard = TransactionGuard(invalidators.clone());
+    TRANSACTION.scope(invalidators, future).await
+}
+
 impl<T: PartialEq> State<T> {
     /// Update the current state when the `value` is different from the current
     /// value. `T` must implement [PartialEq] for this to work.
@@ -0,0 +1,245 @@
+use std::{fmt::Debug, mem::take, sync::Arc};
+
+use auto_hash_map::AutoSet;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event::Event, get_invalidator, mark_stateful, trace::TraceRawVcs, Invalidator,
+    SerializationInvalidator,
+};
+
+#[derive(Serialize, Deserialize)]
+struct Segment<T> {
+    items: Arc<[T]>,
+    invalidators: AutoSet<Invalidator>,
+}
+
+impl<T> Segment<T> {
+    fn invalidate(&mut self) {
+        for invalidator in take(&mut self.invalidators) {
+            invalidator.invalidate();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VcStreamInner<T> {
+    segments: Vec<Segment<T>>,
+    /// The number of segments pushed since the producer (re)started.
+    written: usize,
+    closed: bool,
+    /// Readers that saw the end of the stream, invalidated when it changes.
+    end_invalidators: AutoSet<Invalidator>,
+}
+
+impl<T> VcStreamInner<T> {
+    fn invalidate_end(&mut self) {
+        for invalidator in take(&mut self.end_invalidators) {
+            invalidator.invalidate();
+        }
+    }
+}
+
+/// A list of values that is produced incrementally, in segments.
+///
+/// Like [`State`][crate::State], a stream is stored in a value and shared between the task
+/// producing it and the tasks reading it. Readers don't have to wait for the whole list: they can
+/// read segment by segment as they are [pushed][VcStream::push], and each reader only depends on
+/// the segments it has read. When the producer is re-executed, it
+/// [restarts][VcStream::restart] the stream and pushes its segments again. Only readers of
+/// segments that changed are invalidated.
+///
+/// ```ignore
+/// #[turbo_tasks::value(eq = "manual", serialization = "none")]
+/// struct ModuleStream(VcStream<RcStr>);
+///
+/// #[turbo_tasks::function]
+/// async fn produce(stream: Vc<ModuleStream>) -> Result<Vc<Completion>> {
+///     let stream = stream.await?;
+///     stream.0.restart();
+///     for chunk in ... {
+///         stream.0.push(chunk);
+///     }
+///     stream.0.close();
+///     Ok(Completion::new())
+/// }
+///
+/// // in a reader
+/// let mut index = 0;
+/// while let Some(segment) = stream.0.read_segment(index).await {
+///     ...
+///     index += 1;
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+pub struct VcStream<T> {
+    serialization_invalidator: SerializationInvalidator,
+    inner: Mutex<VcStreamInner<T>>,
+    #[serde(skip, default = "new_event")]
+    event: Event,
+}
+
+fn new_event() -> Event {
+    Event::new(|| "VcStream::event".to_string())
+}
+
+impl<T: Debug> Debug for VcStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("VcStream")
+            .field(
+                "segments",
+                &inner
+                    .segments
+                    .iter()
+                    .map(|segment| &segment.items)
+                    .collect::<Vec<_>>(),
+            )
+            .field("closed", &inner.closed)
+            .finish()
+    }
+}
+
+impl<T: TraceRawVcs> TraceRawVcs for VcStream<T> {
+    fn trace_raw_vcs(&self, trace_context: &mut crate::trace::TraceRawVcsContext) {
+        for segment in self.inner.lock().segments.iter() {
+            for item in segment.items.iter() {
+                item.trace_raw_vcs(trace_context);
+            }
+        }
+    }
+}
+
+impl<T> Default for VcStream<T> {
+    fn default() -> Self {
+        // Need to be explicit to ensure marking as stateful.
+        Self::new()
+    }
+}
+
+impl<T> PartialEq for VcStream<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        false
+    }
+}
+impl<T> Eq for VcStream<T> {}
+
+impl<T> VcStream<T> {
+    pub fn new() -> Self {
+        Self {
+            serialization_invalidator: mark_stateful(),
+            inner: Mutex::new(VcStreamInner {
+                segments: Vec::new(),
+                written: 0,
+                closed: false,
+                end_invalidators: AutoSet::new(),
+            }),
+            event: new_event(),
+        }
+    }
+
+    /// Starts producing the stream again, e.g. when the producing task is re-executed. The
+    /// segments that were produced before stay readable until they are replaced by
+    /// [`VcStream::push`] or removed by [`VcStream::close`].
+    pub fn restart(&self) {
+        let mut inner = self.inner.lock();
+        inner.written = 0;
+        if inner.closed {
+            inner.closed = false;
+            inner.invalidate_end();
+        }
+    }
+
+    /// Ends the stream. Segments of a previous run that weren't pushed again are removed.
+    pub fn close(&self) {
+        {
+            let mut inner = self.inner.lock();
+            let written = inner.written;
+            let mut removed = inner.segments.split_off(written);
+            for segment in removed.iter_mut() {
+                segment.invalidate();
+            }
+            if !inner.closed || !removed.is_empty() {
+                inner.closed = true;
+                inner.invalidate_end();
+            }
+        }
+        self.serialization_invalidator.invalidate();
+        self.event.notify(usize::MAX);
+    }
+
+    /// Whether the producer has [closed][VcStream::close] the stream. Untracked.
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().closed
+    }
+
+    /// Returns the segment at `index`, waiting until it's produced. Returns `None` when the stream
+    /// was closed with fewer segments.
+    ///
+    /// The current task is registered as dependency of the segment, and will be invalidated
+    /// when it changes.
+    pub async fn read_segment(&self, index: usize) -> Option<Arc<[T]>> {
+        loop {
+            let listener = {
+                let mut inner = self.inner.lock();
+                if let Some(segment) = inner.segments.get_mut(index) {
+                    segment.invalidators.insert(get_invalidator());
+                    return Some(segment.items.clone());
+                }
+                if inner.closed {
+                    inner.end_invalidators.insert(get_invalidator());
+                    return None;
+                }
+                self.event.listen()
+            };
+            listener.await;
+        }
+    }
+
+    /// Waits for the stream to be closed and returns all items. The current task depends on all
+    /// segments.
+    pub async fn read_all(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut items = Vec::new();
+        let mut index = 0;
+        while let Some(segment) = self.read_segment(index).await {
+            items.extend(segment.iter().cloned());
+            index += 1;
+        }
+        items
+    }
+}
+
+impl<T: PartialEq> VcStream<T> {
+    /// Produces the next segment of the stream. When the segment replaces one from a previous
+    /// run, its readers are only invalidated when the items differ.
+    pub fn push(&self, items: impl Into<Arc<[T]>>) {
+        let items = items.into();
+        {
+            let mut inner = self.inner.lock();
+            let index = inner.written;
+            inner.written += 1;
+            if let Some(segment) = inner.segments.get_mut(index) {
+                if segment.items == items {
+                    return;
+                }
+                segment.items = items;
+                segment.invalidate();
+            } else {
+                inner.segments.push(Segment {
+                    items,
+                    invalidators: AutoSet::new(),
+                });
+                if inner.closed {
+                    inner.closed = false;
+                    inner.invalidate_end();
+                }
+            }
+        }
+        self.serialization_invalidator.invalidate();
+        self.event.notify(usize::MAX);
+    }
+}
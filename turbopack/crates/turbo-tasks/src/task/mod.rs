@@ -5,7 +5,7 @@ pub(crate) mod task_input;
 pub(crate) mod task_output;
 
 pub use from_task_input::FromTaskInput;
-pub use function::{AsyncFunctionMode, FunctionMode, IntoTaskFn, TaskFn};
+pub use function::{AsyncFunctionMode, FunctionMode, IntoTaskFn, NativeTaskFuture, TaskFn};
 pub use shared_reference::{SharedReference, TypedSharedReference};
 pub use task_input::TaskInput;
 pub use task_output::TaskOutput;
@@ -0,0 +1,88 @@
+use std::{any::Any, marker::PhantomData, sync::Arc};
+
+use crate::manager::with_task_locals;
+
+/// A value that is local to the current task execution, like the current tracing span or the
+/// context for issues.
+///
+/// The value is shared by everything running as part of the execution: nested async functions,
+/// local tasks and futures spawned by it. It's dropped when the execution finishes and is never
+/// stored in a cell. Tasks called by the current task don't see it, as their results are cached
+/// independently of the caller. Task locals must therefore not change the result of a task, only
+/// how it's reported.
+///
+/// ```ignore
+/// static ISSUE_CONTEXT: TaskLocal<RcStr> = TaskLocal::new("ISSUE_CONTEXT");
+///
+/// #[turbo_tasks::function]
+/// async fn process(path: Vc<FileSystemPath>) -> Result<Vc<Completion>> {
+///     ISSUE_CONTEXT.set(path.to_string().owned().await?);
+///     deeply_nested_helper().await
+/// }
+///
+/// async fn deeply_nested_helper() -> Result<Vc<Completion>> {
+///     let context = ISSUE_CONTEXT.get();
+///     ...
+/// }
+/// ```
+///
+/// Accessing a task local outside of a task panics.
+pub struct TaskLocal<T> {
+    /// Also ensures that the `TaskLocal` isn't zero-sized, so every static has its own address.
+    name: &'static str,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> TaskLocal<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _t: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn key(&'static self) -> usize {
+        self as *const Self as usize
+    }
+}
+
+impl<T: Send + Sync + 'static> TaskLocal<T> {
+    /// Sets the value for the rest of the current task execution.
+    pub fn set(&'static self, value: T) {
+        let value: Arc<dyn Any + Send + Sync> = Arc::new(value);
+        with_task_locals(|locals| locals.insert(self.key(), value));
+    }
+
+    /// Removes the value from the current task execution.
+    pub fn clear(&'static self) {
+        with_task_locals(|locals| locals.remove(&self.key()));
+    }
+
+    /// Returns the value of the current task execution, if it was set.
+    pub fn get_arc(&'static self) -> Option<Arc<T>> {
+        let value = with_task_locals(|locals| locals.get(&self.key()).cloned())?;
+        Some(
+            value
+                .downcast()
+                .unwrap_or_else(|_| unreachable!("TaskLocal {} has a different type", self.name)),
+        )
+    }
+
+    /// Calls `f` with the value of the current task execution. The value isn't locked, so `f` may
+    /// access other task locals.
+    pub fn with<R>(&'static self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        f(self.get_arc().as_deref())
+    }
+
+    /// Returns a clone of the value of the current task execution, if it was set.
+    pub fn get(&'static self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.with(|value| value.cloned())
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use anyhow::Result;
+
+use crate::{manager::with_turbo_tasks, ReadRef, TaskId, Vc, VcValueType};
+
+/// A task of the stack of a [`TaskTimeout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitingTask {
+    pub task: TaskId,
+    pub description: String,
+    /// Where the function of the task is defined, see [`FunctionMeta::location`]. `None` for
+    /// tasks which don't call a function, e.g. root tasks.
+    ///
+    /// [`FunctionMeta::location`]: crate::FunctionMeta::location
+    pub location: Option<&'static str>,
+}
+
+impl Display for WaitingTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.description, self.task)?;
+        if let Some(location) = self.location {
+            write!(f, " @ {location}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`with_timeout`] when the task didn't finish in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskTimeout {
+    pub task: TaskId,
+    pub timeout: Duration,
+    /// The chain of tasks the task was waiting for when it timed out, starting with the task
+    /// itself. The last task wasn't waiting for another task, so it's usually the one which hangs,
+    /// e.g. on a loader which never calls back.
+    pub stack: Vec<WaitingTask>,
+}
+
+impl Display for TaskTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.stack.split_first() {
+            Some((task, waiting_for)) => {
+                write!(f, "{task} didn't finish within {:?}", self.timeout)?;
+                for task in waiting_for {
+                    write!(f, "\n  waiting for {task}")?;
+                }
+                Ok(())
+            }
+            None => write!(
+                f,
+                "Task {} didn't finish within {:?}",
+                self.task, self.timeout
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TaskTimeout {}
+
+/// Reads `vc` strongly consistent, or fails with a [`TaskTimeout`] when that takes longer than
+/// `duration`. The timed out task keeps executing, so a later read can still succeed.
+///
+/// The [`TaskTimeout`] contains the stack of tasks the task was waiting for, as far as they're
+/// known from the reads between tasks. A local `vc` is attributed to the task which created it.
+pub async fn with_timeout<T>(duration: Duration, vc: Vc<T>) -> Result<ReadRef<T>>
+where
+    T: VcValueType,
+{
+    let task = vc.node.get_task_id();
+    match tokio::time::timeout(duration, vc.strongly_consistent()).await {
+        Ok(result) => result,
+        Err(_) => Err(TaskTimeout {
+            task,
+            timeout: duration,
+            stack: with_turbo_tasks(|tt| tt.wait_stack(task)),
+        }
+        .into()),
+    }
+}
@@ -14,6 +14,7 @@ use tracing::Span;
 
 use crate::{
     id::{FunctionId, TraitTypeId},
+    introspect::{children_of, display_name_of, ChildrenFn, DisplayNameFn, IntrospectValue},
     magic_any::{AnyDeserializeSeed, MagicAny, MagicAnyDeserializeSeed, MagicAnySerializeSeed},
     registry::{register_trait_type, register_value_type},
     task::shared_reference::TypedSharedReference,
@@ -24,6 +25,7 @@ use crate::{
 type MagicSerializationFn = fn(&dyn MagicAny) -> &dyn erased_serde::Serialize;
 type AnySerializationFn = fn(&(dyn Any + Sync + Send)) -> &dyn erased_serde::Serialize;
 type RawCellFactoryFn = fn(TypedSharedReference) -> RawVc;
+type EstimateSizeFn = fn(&(dyn Any + Sync + Send)) -> usize;
 
 // TODO this type need some refactoring when multiple languages are added to
 // turbo-task In this case a trait_method might be of a different function type.
@@ -56,6 +58,12 @@ pub struct ValueType {
     /// Because we allow resolving `Vc<dyn Trait>`, it's otherwise not possible
     /// for `RawVc` to know what the appropriate `VcCellMode` is.
     pub(crate) raw_cell: RawCellFactoryFn,
+
+    /// Estimates the memory used by a value of this type, see [`ValueType::estimate_size`].
+    estimate_size: EstimateSizeFn,
+
+    /// Functors for [`IntrospectValue`], see [`crate::introspect`].
+    introspection: Option<(DisplayNameFn, ChildrenFn)>,
 }
 
 impl Hash for ValueType {
@@ -101,6 +109,12 @@ pub fn any_as_serialize<T: Any + Serialize + Send + Sync + 'static>(
     );
 }
 
+/// Only counts the inline size of the value, used when the value type doesn't implement
+/// [`EstimateSize`][crate::EstimateSize].
+fn shallow_size_of<T>(_value: &(dyn Any + Send + Sync)) -> usize {
+    std::mem::size_of::<T>()
+}
+
 impl ValueType {
     /// This is internally used by `#[turbo_tasks::value]`
     pub fn new<T: VcValueType>() -> Self {
@@ -111,6 +125,8 @@ impl ValueType {
             magic_serialization: None,
             any_serialization: None,
             raw_cell: <T::CellMode as VcCellMode<T>>::raw_cell,
+            estimate_size: shallow_size_of::<T>,
+            introspection: None,
         }
     }
 
@@ -128,6 +144,8 @@ impl ValueType {
             )),
             any_serialization: Some((any_as_serialize::<T>, AnyDeserializeSeed::new::<T>())),
             raw_cell: <T::CellMode as VcCellMode<T>>::raw_cell,
+            estimate_size: shallow_size_of::<T>,
+            introspection: None,
         }
     }
 
@@ -142,9 +160,46 @@ impl ValueType {
             magic_serialization: None,
             any_serialization: Some((any_as_serialize::<T>, AnyDeserializeSeed::new::<T>())),
             raw_cell: <T::CellMode as VcCellMode<T>>::raw_cell,
+            estimate_size: shallow_size_of::<T>,
+            introspection: None,
         }
     }
 
+    /// This is internally used by `#[turbo_tasks::value]`
+    pub fn with_estimate_size(mut self, estimate_size: EstimateSizeFn) -> Self {
+        self.estimate_size = estimate_size;
+        self
+    }
+
+    /// This is internally used by `#[turbo_tasks::value]`
+    pub fn with_introspection<T: IntrospectValue + 'static>(mut self) -> Self {
+        self.introspection = Some((display_name_of::<T>, children_of::<T>));
+        self
+    }
+
+    /// The [`IntrospectValue::display_name`] of `value`, which must be of this type. `None` when
+    /// the type doesn't implement [`IntrospectValue`].
+    pub fn introspect_display_name(
+        &self,
+        value: &(dyn Any + Send + Sync),
+    ) -> Option<Cow<'static, str>> {
+        self.introspection
+            .and_then(|(display_name, _)| display_name(value))
+    }
+
+    /// The [`IntrospectValue::children`] of `value`, which must be of this type. Empty when the
+    /// type doesn't implement [`IntrospectValue`].
+    pub fn introspect_children(&self, value: &(dyn Any + Send + Sync)) -> Vec<RawVc> {
+        self.introspection
+            .map_or_else(Vec::new, |(_, children)| children(value))
+    }
+
+    /// The estimated bytes used by `value`, which must be of this type, including the memory it
+    /// owns on the heap. See [`EstimateSize`][crate::EstimateSize].
+    pub fn estimate_size(&self, value: &(dyn Any + Send + Sync)) -> usize {
+        (self.estimate_size)(value)
+    }
+
     pub fn magic_as_serializable<'a>(
         &self,
         arc: &'a Arc<dyn MagicAny>,
@@ -0,0 +1,78 @@
+use std::panic::Location;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    feature_flags::FeatureFlag,
+    id::{ExecutionId, LocalCellId},
+};
+
+/// Records where local `Vc`s are created, so accessing one outside of its task reports the call
+/// to `.cell()` or `.local_cell()` which created it. Only has an effect in debug builds.
+///
+/// The sites are kept for the lifetime of the process, as escaped `Vc`s are usually accessed
+/// after their task finished, so this should only be enabled while debugging.
+pub(crate) static VC_CREATION_SITES: FeatureFlag<bool> =
+    FeatureFlag::new("vc-creation-sites", "TURBO_TASKS_VC_CREATION_SITES", false);
+
+type CreationSites = FxHashMap<(ExecutionId, LocalCellId), &'static Location<'static>>;
+
+static CREATION_SITES: Lazy<Mutex<CreationSites>> = Lazy::new(Default::default);
+
+fn is_enabled() -> bool {
+    cfg!(debug_assertions) && VC_CREATION_SITES.get()
+}
+
+pub(crate) fn record_creation_site(
+    execution_id: ExecutionId,
+    local_cell_id: LocalCellId,
+    location: &'static Location<'static>,
+) {
+    if is_enabled() {
+        CREATION_SITES
+            .lock()
+            .insert((execution_id, local_cell_id), location);
+    }
+}
+
+/// A sentence for the panic message of an escaped local `Vc`, either with its creation site or
+/// with a hint how to record it.
+pub(crate) fn describe_creation_site(
+    execution_id: ExecutionId,
+    local_cell_id: LocalCellId,
+) -> String {
+    if !is_enabled() {
+        return "Set TURBO_TASKS_VC_CREATION_SITES=1 in a debug build to report where it was \
+                created."
+            .to_string();
+    }
+    match CREATION_SITES.lock().get(&(execution_id, local_cell_id)) {
+        Some(location) => format!("It was created at {location}."),
+        None => "Its creation site wasn't recorded.".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feature_flags::set_feature_flag;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn describes_recorded_sites() {
+        set_feature_flag("vc-creation-sites", "true").unwrap();
+        let execution_id = ExecutionId::from(1);
+        let location = Location::caller();
+        record_creation_site(execution_id, LocalCellId::from(1), location);
+        assert_eq!(
+            describe_creation_site(execution_id, LocalCellId::from(1)),
+            format!("It was created at {location}.")
+        );
+        assert_eq!(
+            describe_creation_site(execution_id, LocalCellId::from(2)),
+            "Its creation site wasn't recorded."
+        );
+    }
+}
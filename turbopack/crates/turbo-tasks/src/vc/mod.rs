@@ -1,9 +1,11 @@
 pub(crate) mod cast;
 mod cell_mode;
+pub(crate) mod creation_site;
 pub(crate) mod default;
 mod read;
 pub(crate) mod resolved;
 mod traits;
+mod weak;
 
 use std::{
     any::Any,
@@ -25,6 +27,7 @@ pub use self::{
     read::{ReadVcFuture, VcDefaultRead, VcRead, VcTransparentRead},
     resolved::{ResolvedValue, ResolvedVc},
     traits::{Dynamic, TypedForInput, Upcast, VcValueTrait, VcValueType},
+    weak::WeakVc,
 };
 use crate::{
     debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatString},
@@ -252,6 +255,7 @@ where
 {
     // called by the `.cell()` method generated by the `#[turbo_tasks::value]` macro
     #[doc(hidden)]
+    #[track_caller]
     pub fn cell_private(mut inner: <T::Read as VcRead<T>>::Target) -> Self {
         // cell contents are immutable, so go ahead and shrink the cell's contents
         ShrinkToFit::shrink_to_fit(<T::Read as VcRead<T>>::target_to_value_mut_ref(&mut inner));
@@ -269,6 +273,7 @@ where
     // called by the `.local_cell()` method generated by the `#[turbo_tasks::value]`
     // macro
     #[doc(hidden)]
+    #[track_caller]
     pub fn local_cell_private(mut inner: <T::Read as VcRead<T>>::Target) -> Self {
         // Cell contents are immutable, so go ahead and shrink the cell's contents. Ideally we'd
         // wait until the cell is upgraded from local to global to pay the cost of shrinking, but by
@@ -296,10 +301,12 @@ where
     Inner: Any + Send + Sync,
     Repr: VcValueType,
 {
+    #[track_caller]
     pub fn cell(inner: Inner) -> Self {
         Self::cell_private(inner)
     }
 
+    #[track_caller]
     pub fn local_cell(inner: Inner) -> Self {
         // `T::CellMode` isn't applicable here, we always create new local cells. Local
         // cells aren't stored across executions, so there can be no concept of
@@ -382,7 +389,8 @@ where
     }
 
     /// Returns `true` if the Vc was created inside a task with
-    /// [`#[turbo_tasks::function(local_cells)]`][crate::function] and has not yet been resolved.
+    /// [`#[turbo_tasks::function(local_cells)]`][crate::function] or is the output of such a
+    /// function, and has not yet been resolved.
     ///
     /// Aside from differences in caching, a function's behavior should not be changed by using
     /// local or non-local cells, so this function is mostly useful inside tests and internally in
@@ -485,6 +493,14 @@ where
     fn peek_collectibles<Vt: VcValueTrait>(self) -> AutoSet<Vc<Vt>> {
         self.node.peek_collectibles()
     }
+
+    fn take_collectibles_ordered<Vt: VcValueTrait>(self) -> Vec<Vc<Vt>> {
+        self.node.take_collectibles_ordered()
+    }
+
+    fn peek_collectibles_ordered<Vt: VcValueTrait>(self) -> Vec<Vc<Vt>> {
+        self.node.peek_collectibles_ordered()
+    }
 }
 
 impl<T> From<RawVc> for Vc<T>
@@ -1,35 +1,28 @@
 use std::{
     any::Any,
-    cell::RefCell,
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Debug,
     future::IntoFuture,
     hash::{Hash, Hasher},
     marker::PhantomData,
     ops::Deref,
-    path::{Path, PathBuf},
-    sync::{
-        atomic::{
-            AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicU16, AtomicU32, AtomicU64,
-            AtomicU8, AtomicUsize,
-        },
-        Arc, Mutex,
-    },
-    time::Duration,
 };
 
-use auto_hash_map::{AutoMap, AutoSet};
-use indexmap::{IndexMap, IndexSet};
+use anyhow::Result;
+use futures::future::try_join;
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
 
 use crate::{
     debug::{ValueDebug, ValueDebugFormat, ValueDebugFormatString},
     trace::{TraceRawVcs, TraceRawVcsContext},
-    vc::Vc,
-    ResolveTypeError, Upcast, VcRead, VcTransparentRead, VcValueTrait, VcValueType,
+    vc::{Vc, VcCellMode},
+    RawVc, ReadRef, ResolveTypeError, ShrinkToFit, TryJoinIterExt, Upcast, VcRead,
+    VcTransparentRead, VcValueTrait, VcValueType,
 };
 
+type VcReadTarget<T> = <<T as VcValueType>::Read as VcRead<T>>::Target;
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent, bound = "")]
 pub struct ResolvedVc<T>
@@ -107,6 +100,7 @@ where
 {
     // called by the `.resolved_cell()` method generated by the `#[turbo_tasks::value]` macro
     #[doc(hidden)]
+    #[track_caller]
     pub fn cell_private(inner: <T::Read as VcRead<T>>::Target) -> Self {
         Self {
             node: Vc::<T>::cell_private(inner),
@@ -114,12 +108,58 @@ where
     }
 }
 
+impl<T> ResolvedVc<T>
+where
+    T: VcValueType,
+{
+    /// Reads the cell and stores a clone of the part of its value selected by `map` in a new cell
+    /// of the current task, e.g. `ResolvedVc::try_map_cell(this, |value| &value.field)`.
+    ///
+    /// This avoids writing a `#[turbo_tasks::function]` only to project into a cell. The new cell
+    /// follows the cell mode of `U`, so tasks reading it are only invalidated when the projected
+    /// value changes. The current task still depends on the whole value of `this`.
+    pub async fn try_map_cell<U>(
+        this: Self,
+        map: impl FnOnce(&VcReadTarget<T>) -> &VcReadTarget<U>,
+    ) -> Result<ResolvedVc<U>>
+    where
+        U: VcValueType,
+        VcReadTarget<U>: Clone,
+    {
+        let value = this.await?;
+        let mut inner = map(&value).clone();
+        ShrinkToFit::shrink_to_fit(<U::Read as VcRead<U>>::target_to_value_mut_ref(&mut inner));
+        // Always create a task cell, as a local cell wouldn't be resolved.
+        Ok(ResolvedVc {
+            node: <U::CellMode as VcCellMode<U>>::cell(inner),
+        })
+    }
+
+    /// Compares the values of two cells, as opposed to [`PartialEq`] which compares the identity
+    /// of the cells.
+    ///
+    /// Returns early without reading when both refer to the same cell, and without comparing the
+    /// values when both cells share the same value, e.g. because one was updated with the value of
+    /// the other.
+    pub async fn cell_eq(a: Self, b: Self) -> Result<bool>
+    where
+        VcReadTarget<T>: PartialEq,
+    {
+        if a == b {
+            return Ok(true);
+        }
+        let (a, b) = try_join(a.into_future(), b.into_future()).await?;
+        Ok(ReadRef::ptr_eq(&a, &b) || *a == *b)
+    }
+}
+
 impl<T, Inner, Repr> ResolvedVc<T>
 where
     T: VcValueType<Read = VcTransparentRead<T, Inner, Repr>>,
     Inner: Any + Send + Sync,
     Repr: VcValueType,
 {
+    #[track_caller]
     pub fn cell(inner: Inner) -> Self {
         Self {
             node: Vc::<T>::cell(inner),
@@ -131,6 +171,43 @@ impl<T> ResolvedVc<T>
 where
     T: ?Sized,
 {
+    /// Resolves all given [`Vc`]s, like calling [`Vc::to_resolved`] on each of them, but waits for
+    /// their tasks together instead of creating a future per [`Vc`].
+    pub async fn resolve_all(vcs: impl IntoIterator<Item = Vc<T>>) -> Result<Vec<ResolvedVc<T>>> {
+        let nodes = RawVc::resolve_all(vcs.into_iter().map(|vc| vc.node).collect()).await?;
+        Ok(nodes
+            .into_iter()
+            .map(|node| ResolvedVc {
+                node: Vc {
+                    node,
+                    _t: PhantomData,
+                },
+            })
+            .collect())
+    }
+
+    /// Removes all but the first occurrence of each cell, keeping the order of the remaining
+    /// ones.
+    pub fn dedup_resolved(vcs: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut seen = FxHashSet::default();
+        vcs.into_iter().filter(|vc| seen.insert(*vc)).collect()
+    }
+
+    /// Sorts `vcs` by the string returned by `ident`, e.g. `|module| module.ident().to_string()`.
+    ///
+    /// Unlike task and cell ids, which depend on the order in which tasks were created, idents are
+    /// the same in every run, so the order is deterministic. The sort is stable: cells with equal
+    /// idents keep their relative order.
+    pub async fn sort_by_ident(vcs: &mut [Self], ident: impl Fn(Self) -> Vc<RcStr>) -> Result<()> {
+        let idents = vcs.iter().map(|&vc| ident(vc)).try_join().await?;
+        let mut keyed: Vec<_> = idents.into_iter().zip(vcs.iter().copied()).collect();
+        keyed.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        for (vc, (_, sorted)) in vcs.iter_mut().zip(keyed) {
+            *vc = sorted;
+        }
+        Ok(())
+    }
+
     /// Upcasts the given `ResolvedVc<T>` to a `ResolvedVc<Box<dyn K>>`.
     ///
     /// See also: [`Vc::upcast`].
@@ -233,85 +310,8 @@ where
     }
 }
 
-/// Indicates that a type does not contain any instances of [`Vc`]. It may
-/// contain [`ResolvedVc`].
-///
-/// # Safety
-///
-/// This trait is marked as unsafe. You should not derive it yourself, but
-/// instead you should rely on [`#[turbo_tasks::value(resolved)]`][macro@
-/// crate::value] to do it for you.
-pub unsafe trait ResolvedValue {}
+pub use turbo_tasks_types::ResolvedValue;
 
 unsafe impl<T: ?Sized + ResolvedValue> ResolvedValue for ResolvedVc<T> {}
 
-macro_rules! impl_resolved {
-    ($ty:ty) => {
-        unsafe impl ResolvedValue for $ty {}
-    };
-
-    ($ty:ty, $($tys:ty),+) => {
-        impl_resolved!($ty);
-        impl_resolved!($($tys),+);
-    }
-}
-
-impl_resolved!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64, char, bool, usize);
-impl_resolved!(
-    AtomicI8,
-    AtomicU8,
-    AtomicI16,
-    AtomicU16,
-    AtomicI32,
-    AtomicU32,
-    AtomicI64,
-    AtomicU64,
-    AtomicBool,
-    AtomicUsize
-);
-impl_resolved!((), str, String, Duration, anyhow::Error, RcStr);
-impl_resolved!(Path, PathBuf);
-impl_resolved!(serde_json::Value);
-
-// based on stdlib's internal `tuple_impls!` macro
-macro_rules! impl_resolved_tuple {
-    ($T:ident) => {
-        impl_resolved_tuple!(@impl $T);
-    };
-    ($T:ident $( $U:ident )+) => {
-        impl_resolved_tuple!($( $U )+);
-        impl_resolved_tuple!(@impl $T $( $U )+);
-    };
-    (@impl $( $T:ident )+) => {
-        unsafe impl<$($T: ResolvedValue),+> ResolvedValue for ($($T,)+) {}
-    };
-}
-
-impl_resolved_tuple!(E D C B A Z Y X W V U T);
-
-unsafe impl<T: ResolvedValue> ResolvedValue for Option<T> {}
-unsafe impl<T: ResolvedValue> ResolvedValue for Vec<T> {}
-unsafe impl<T: ResolvedValue, const N: usize> ResolvedValue for [T; N] {}
-unsafe impl<T: ResolvedValue> ResolvedValue for [T] {}
-unsafe impl<T: ResolvedValue, S> ResolvedValue for HashSet<T, S> {}
-unsafe impl<T: ResolvedValue, S, const I: usize> ResolvedValue for AutoSet<T, S, I> {}
-unsafe impl<T: ResolvedValue> ResolvedValue for BTreeSet<T> {}
-unsafe impl<T: ResolvedValue, S> ResolvedValue for IndexSet<T, S> {}
-unsafe impl<K: ResolvedValue, V: ResolvedValue, S> ResolvedValue for HashMap<K, V, S> {}
-unsafe impl<K: ResolvedValue, V: ResolvedValue, S, const I: usize> ResolvedValue
-    for AutoMap<K, V, S, I>
-{
-}
-unsafe impl<K: ResolvedValue, V: ResolvedValue> ResolvedValue for BTreeMap<K, V> {}
-unsafe impl<K: ResolvedValue, V: ResolvedValue, S> ResolvedValue for IndexMap<K, V, S> {}
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Box<T> {}
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Arc<T> {}
-unsafe impl<T: ResolvedValue, E: ResolvedValue> ResolvedValue for Result<T, E> {}
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for Mutex<T> {}
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for RefCell<T> {}
-unsafe impl<T: ?Sized> ResolvedValue for PhantomData<T> {}
-
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for &T {}
-unsafe impl<T: ResolvedValue + ?Sized> ResolvedValue for &mut T {}
-
 pub use turbo_tasks_macros::ResolvedValue;
@@ -0,0 +1,106 @@
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    manager::with_turbo_tasks,
+    trace::{TraceRawVcs, TraceRawVcsContext},
+    RawVc, ResolvedValue, ResolvedVc,
+};
+
+/// A reference to a cell that doesn't keep the cell alive.
+///
+/// Reading a [`ResolvedVc`] held in long-lived state recomputes the cell when the backend dropped
+/// it, which keeps stale tasks around. A `WeakVc` can only be [upgraded][WeakVc::upgrade] while
+/// the backend still holds the content of the cell, so caches can refer to cells without
+/// extending their lifetime.
+///
+/// A `WeakVc` isn't traced by [`TraceRawVcs`], so it doesn't count as a reference to the cell.
+pub struct WeakVc<T>
+where
+    T: ?Sized,
+{
+    node: ResolvedVc<T>,
+}
+
+impl<T> WeakVc<T>
+where
+    T: ?Sized,
+{
+    pub fn new(vc: ResolvedVc<T>) -> Self {
+        WeakVc { node: vc }
+    }
+
+    /// Returns the referenced cell if it still holds its content.
+    ///
+    /// This doesn't track a dependency on the cell, so the current task isn't invalidated when the
+    /// cell is dropped later. Reading the returned [`ResolvedVc`] does track a dependency as usual.
+    pub fn upgrade(&self) -> Option<ResolvedVc<T>> {
+        let RawVc::TaskCell(task, index) = self.node.node.node else {
+            unreachable!("a ResolvedVc always points to a task cell");
+        };
+        with_turbo_tasks(|tt| tt.is_task_cell_alive(task, index)).then_some(self.node)
+    }
+}
+
+impl<T> ResolvedVc<T>
+where
+    T: ?Sized,
+{
+    /// Creates a [`WeakVc`] referring to the same cell.
+    pub fn downgrade(this: Self) -> WeakVc<T> {
+        WeakVc::new(this)
+    }
+}
+
+impl<T> Copy for WeakVc<T> where T: ?Sized {}
+
+impl<T> Clone for WeakVc<T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for WeakVc<T>
+where
+    T: ?Sized,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T> Eq for WeakVc<T> where T: ?Sized {}
+
+impl<T> Hash for WeakVc<T>
+where
+    T: ?Sized,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.node.hash(state);
+    }
+}
+
+impl<T> Debug for WeakVc<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakVc")
+            .field("node", &self.node.node.node)
+            .finish()
+    }
+}
+
+impl<T> TraceRawVcs for WeakVc<T>
+where
+    T: ?Sized,
+{
+    fn trace_raw_vcs(&self, _trace_context: &mut TraceRawVcsContext) {}
+}
+
+unsafe impl<T: ?Sized + ResolvedValue> ResolvedValue for WeakVc<T> {}
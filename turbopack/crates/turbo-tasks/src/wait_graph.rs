@@ -0,0 +1,179 @@
+use std::{hash::BuildHasherDefault, sync::Arc};
+
+use dashmap::DashMap;
+use rustc_hash::{FxHashSet, FxHasher};
+
+use crate::TaskId;
+
+/// Tracks which tasks wait for the output or a cell of which other tasks.
+///
+/// A task which (transitively) waits for itself would wait forever, so such a wait is refused
+/// with the cycle instead.
+#[derive(Default)]
+pub(crate) struct WaitGraph {
+    /// The tasks every task currently waits for. A task is listed once per wait, as a task can
+    /// wait for multiple cells of the same task at the same time.
+    waiting: DashMap<TaskId, Vec<TaskId>, BuildHasherDefault<FxHasher>>,
+}
+
+impl WaitGraph {
+    /// Records that `reader` waits for `task` until the returned guard is dropped, unless `task`
+    /// (transitively) waits for `reader`. Then the cycle is returned, starting and ending with
+    /// `reader`.
+    ///
+    /// The wait is recorded before looking for a cycle, so when two tasks start waiting for each
+    /// other at the same time, at least one of them finds the cycle.
+    pub(crate) fn start_waiting(
+        self: &Arc<Self>,
+        reader: TaskId,
+        task: TaskId,
+    ) -> Result<WaitGuard, Vec<TaskId>> {
+        self.waiting.entry(reader).or_default().push(task);
+        let guard = WaitGuard {
+            graph: self.clone(),
+            reader,
+            task,
+        };
+        if let Some(path) = self.find_path(task, reader) {
+            let mut cycle = Vec::with_capacity(path.len() + 1);
+            cycle.push(reader);
+            cycle.extend(path);
+            return Err(cycle);
+        }
+        Ok(guard)
+    }
+
+    /// Removes one wait of `reader` for `task`.
+    fn stop_waiting(&self, reader: TaskId, task: TaskId) {
+        self.waiting.remove_if_mut(&reader, |_, tasks| {
+            if let Some(index) = tasks.iter().rposition(|&t| t == task) {
+                tasks.remove(index);
+            }
+            tasks.is_empty()
+        });
+    }
+
+    /// The chain of tasks `task` transitively waits for, starting with `task`. Of multiple tasks a
+    /// task waits for, the one it started waiting for last is followed.
+    pub(crate) fn waiting_chain(&self, task: TaskId) -> Vec<TaskId> {
+        let mut chain = vec![task];
+        let mut visited = FxHashSet::default();
+        visited.insert(task);
+        let mut current = task;
+        while let Some(next) = self
+            .waiting
+            .get(&current)
+            .and_then(|tasks| tasks.last().copied())
+        {
+            if !visited.insert(next) {
+                break;
+            }
+            chain.push(next);
+            current = next;
+        }
+        chain
+    }
+
+    /// Finds a path of waiting tasks from `from` to `to`, including both.
+    fn find_path(&self, from: TaskId, to: TaskId) -> Option<Vec<TaskId>> {
+        let mut path = vec![from];
+        // The index of the next task to visit of every task in `path`.
+        let mut next = vec![0];
+        let mut visited = FxHashSet::default();
+        visited.insert(from);
+        while let Some(&current) = path.last() {
+            if current == to {
+                return Some(path);
+            }
+            let index = next.last_mut().unwrap();
+            let task = self
+                .waiting
+                .get(&current)
+                .and_then(|tasks| tasks.get(*index).copied());
+            match task {
+                Some(task) => {
+                    *index += 1;
+                    if visited.insert(task) {
+                        path.push(task);
+                        next.push(0);
+                    }
+                }
+                None => {
+                    path.pop();
+                    next.pop();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Removes a wait recorded by [WaitGraph::start_waiting] when dropped.
+pub(crate) struct WaitGuard {
+    graph: Arc<WaitGraph>,
+    reader: TaskId,
+    task: TaskId,
+}
+
+impl Drop for WaitGuard {
+    fn drop(&mut self) {
+        self.graph.stop_waiting(self.reader, self.task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: u32) -> TaskId {
+        TaskId::from(id)
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let graph = Arc::new(WaitGraph::default());
+        let _w12 = graph.start_waiting(id(1), id(2)).unwrap();
+        let w23 = graph.start_waiting(id(2), id(3)).unwrap();
+        let _w13 = graph.start_waiting(id(1), id(3)).unwrap();
+        assert_eq!(
+            graph.start_waiting(id(3), id(1)).err(),
+            Some(vec![id(3), id(1), id(2), id(3)])
+        );
+        assert_eq!(
+            graph.start_waiting(id(4), id(4)).err(),
+            Some(vec![id(4), id(4)])
+        );
+
+        drop(w23);
+        let _w32 = graph.start_waiting(id(3), id(2)).unwrap();
+    }
+
+    #[test]
+    fn refused_and_dropped_waits_are_removed() {
+        let graph = Arc::new(WaitGraph::default());
+        let w12 = graph.start_waiting(id(1), id(2)).unwrap();
+        assert!(graph.start_waiting(id(2), id(1)).is_err());
+        assert_eq!(graph.waiting_chain(id(2)), vec![id(2)]);
+
+        // Waiting for the same task twice is only removed with the last guard.
+        let w12_again = graph.start_waiting(id(1), id(2)).unwrap();
+        drop(w12);
+        assert_eq!(graph.waiting_chain(id(1)), vec![id(1), id(2)]);
+        drop(w12_again);
+        assert_eq!(graph.waiting_chain(id(1)), vec![id(1)]);
+        let _w21 = graph.start_waiting(id(2), id(1)).unwrap();
+    }
+
+    #[test]
+    fn follows_waiting_chain() {
+        let graph = Arc::new(WaitGraph::default());
+        let _w12 = graph.start_waiting(id(1), id(2)).unwrap();
+        let _w23 = graph.start_waiting(id(2), id(3)).unwrap();
+        let w24 = graph.start_waiting(id(2), id(4)).unwrap();
+        assert_eq!(graph.waiting_chain(id(1)), vec![id(1), id(2), id(4)]);
+        assert_eq!(graph.waiting_chain(id(4)), vec![id(4)]);
+
+        drop(w24);
+        assert_eq!(graph.waiting_chain(id(1)), vec![id(1), id(2), id(3)]);
+    }
+}
@@ -3,6 +3,7 @@ use tracing::Instrument;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, TryJoinIterExt, Value, ValueToString, Vc};
 use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::{
     chunk::{
         availability_info::AvailabilityInfo,
@@ -89,6 +90,11 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    pub fn content_hash_algorithm(mut self, content_hash_algorithm: HashAlgorithm) -> Self {
+        self.chunking_context.content_hash_algorithm = content_hash_algorithm;
+        self
+    }
+
     pub fn module_id_strategy(
         mut self,
         module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
@@ -97,8 +103,47 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
-    pub fn build(self) -> Vc<BrowserChunkingContext> {
-        BrowserChunkingContext::new(Value::new(self.chunking_context))
+    /// Builds the chunking context, failing when options were combined that
+    /// can't work together.
+    pub fn build(self) -> Result<Vc<BrowserChunkingContext>> {
+        self.validate()?;
+        Ok(BrowserChunkingContext::new(Value::new(
+            self.chunking_context,
+        )))
+    }
+
+    fn validate(&self) -> Result<()> {
+        let context = &self.chunking_context;
+        let name = context.name.as_deref().unwrap_or("browser");
+        if context.enable_hot_module_replacement
+            && !matches!(context.runtime_type, RuntimeType::Development)
+        {
+            bail!(
+                "Invalid {name} chunking context: hot module replacement requires the development \
+                 runtime, but the {:?} runtime was selected",
+                context.runtime_type
+            );
+        }
+        if context.enable_hot_module_replacement
+            && context.content_hash_algorithm != HashAlgorithm::default()
+        {
+            bail!(
+                "Invalid {name} chunking context: the content hash algorithm can only be changed \
+                 for production output, but hot module replacement is enabled with the {:?} \
+                 algorithm",
+                context.content_hash_algorithm
+            );
+        }
+        if context.should_use_file_source_map_uris
+            && !context.reference_chunk_source_maps
+            && !context.reference_css_chunk_source_maps
+        {
+            bail!(
+                "Invalid {name} chunking context: file:// source map URIs were requested, but \
+                 chunks don't reference their source maps"
+            );
+        }
+        Ok(())
     }
 }
 
@@ -145,6 +190,8 @@ pub struct BrowserChunkingContext {
     runtime_type: RuntimeType,
     /// Whether to minify resulting chunks
     minify_type: MinifyType,
+    /// The hash function for the content hashes of static assets
+    content_hash_algorithm: HashAlgorithm,
     /// Whether to use manifest chunks for lazy compilation
     manifest_chunks: bool,
     /// The module id strategy to use
@@ -179,6 +226,7 @@ impl BrowserChunkingContext {
                 environment,
                 runtime_type,
                 minify_type: MinifyType::NoMinify,
+                content_hash_algorithm: HashAlgorithm::default(),
                 manifest_chunks: false,
                 module_id_strategy: ResolvedVc::upcast(DevModuleIdStrategy::new_resolved()),
             },
@@ -299,7 +347,13 @@ impl ChunkingContext for BrowserChunkingContext {
         extension: RcStr,
     ) -> Result<Vc<FileSystemPath>> {
         let root_path = self.chunk_root_path;
-        let name = ident.output_name(*self.context_path, extension).await?;
+        let name = ident
+            .output_name(
+                *self.context_path,
+                extension,
+                Vc::cell(self.content_hash_algorithm),
+            )
+            .await?;
         Ok(root_path.join(name.clone_value()))
     }
 
@@ -343,6 +397,11 @@ impl ChunkingContext for BrowserChunkingContext {
         Ok(Vc::cell(source_maps))
     }
 
+    #[turbo_tasks::function]
+    fn content_hash_algorithm(&self) -> Vc<HashAlgorithm> {
+        Vc::cell(self.content_hash_algorithm)
+    }
+
     #[turbo_tasks::function]
     async fn asset_path(
         &self,
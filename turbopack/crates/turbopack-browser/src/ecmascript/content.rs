@@ -3,12 +3,12 @@ use std::io::Write;
 use anyhow::{bail, Result};
 use indoc::writedoc;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks::{FxIndexMap, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Vc};
 use turbo_tasks_fs::File;
 use turbopack_core::{
     asset::AssetContent,
     chunk::{ChunkingContext, MinifyType, ModuleId},
-    code_builder::{Code, CodeBuilder},
+    code_builder::{code_batches, Code, CodeBuilder},
     output::OutputAsset,
     source_map::{GenerateSourceMap, OptionSourceMap},
     version::{MergeableVersionedContent, Version, VersionedContent, VersionedContentMerger},
@@ -36,9 +36,12 @@ impl EcmascriptDevChunkContent {
         chunk: ResolvedVc<EcmascriptDevChunk>,
         content: Vc<EcmascriptChunkContent>,
     ) -> Result<Vc<Self>> {
-        let entries = EcmascriptDevChunkContentEntries::new(content)
-            .to_resolved()
-            .await?;
+        let entries = EcmascriptDevChunkContentEntries::new(
+            content,
+            chunking_context.content_hash_algorithm(),
+        )
+        .to_resolved()
+        .await?;
         Ok(EcmascriptDevChunkContent {
             entries,
             chunking_context,
@@ -61,6 +64,7 @@ impl EcmascriptDevChunkContent {
             self.chunking_context.output_root(),
             self.chunk.ident().path(),
             *self.entries,
+            self.chunking_context.content_hash_algorithm(),
         )
     }
 
@@ -81,6 +85,31 @@ impl EcmascriptDevChunkContent {
         };
         let mut code = CodeBuilder::default();
 
+        // The runtime needs to know which modules can't be React Refresh boundaries before any of
+        // them is executed, so they are registered ahead of the chunk, in the same way.
+        let refresh_boundaries: FxIndexMap<_, _> = this
+            .entries
+            .await?
+            .iter()
+            .map(|(id, entry)| async move {
+                Ok(entry
+                    .refresh_boundary_info()
+                    .await?
+                    .map(|refresh_boundary| (id.clone(), refresh_boundary)))
+            })
+            .try_flat_join()
+            .await?
+            .into_iter()
+            .collect();
+        if !refresh_boundaries.is_empty() {
+            writeln!(
+                code,
+                "(globalThis.TURBOPACK_REFRESH_BOUNDARIES = \
+                 globalThis.TURBOPACK_REFRESH_BOUNDARIES || []).push({});",
+                StringifyJs(&refresh_boundaries)
+            )?;
+        }
+
         // When a chunk is executed, it will either register itself with the current
         // instance of the runtime, or it will push itself onto the list of pending
         // chunks (`self.TURBOPACK`).
@@ -96,10 +125,21 @@ impl EcmascriptDevChunkContent {
             chunk_path = StringifyJs(chunk_server_path)
         )?;
 
-        for (id, entry) in this.entries.await?.iter() {
-            write!(code, "\n{}: ", StringifyJs(&id))?;
-            code.push_code(&*entry.code.await?);
-            write!(code, ",")?;
+        let entries = this.entries.await?;
+        let batches = code_batches(entries.iter(), |(id, _)| (***id).clone())
+            .into_iter()
+            .map(|batch| {
+                code_batch(
+                    batch
+                        .into_iter()
+                        .map(|(id, entry)| (StringifyJs(id).to_string().into(), entry.code))
+                        .collect(),
+                )
+            })
+            .try_join()
+            .await?;
+        for batch in batches {
+            code.push_code(&batch);
         }
 
         write!(code, "\n}}]);")?;
@@ -127,6 +167,27 @@ impl EcmascriptDevChunkContent {
     }
 }
 
+/// Concatenates the code of a batch of the entries of a chunk, see [code_batches]. Entries are
+/// passed with their ids already stringified. The batches of a chunk are independent tasks, so
+/// they are built in parallel and only merged in order by [EcmascriptDevChunkContent::code].
+#[turbo_tasks::function]
+async fn code_batch(entries: Vec<(RcStr, ResolvedVc<Code>)>) -> Result<Vc<Code>> {
+    let entries = entries
+        .into_iter()
+        .map(|(id, entry_code)| async move { Ok((id, entry_code.await?)) })
+        .try_join()
+        .await?;
+
+    let mut code = CodeBuilder::default();
+    for (id, entry_code) in entries {
+        write!(code, "\n{id}: ")?;
+        code.push_code(&entry_code);
+        write!(code, ",")?;
+    }
+
+    Ok(code.build().cell())
+}
+
 #[turbo_tasks::value_impl]
 impl VersionedContent for EcmascriptDevChunkContent {
     #[turbo_tasks::function]
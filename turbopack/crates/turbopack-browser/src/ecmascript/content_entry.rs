@@ -1,16 +1,20 @@
 use std::io::Write as _;
 
 use anyhow::Result;
+use serde::Serialize;
 use tracing::{info_span, Instrument};
+use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ReadRef, ResolvedVc, TryJoinIterExt, ValueToString, Vc};
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::{
     chunk::{AsyncModuleInfo, ChunkItem, ChunkItemExt, ModuleId},
     code_builder::{Code, CodeBuilder},
     error::PrettyPrintError,
     issue::{code_gen::CodeGenerationIssue, IssueExt, IssueSeverity, StyledString},
 };
-use turbopack_ecmascript::chunk::{
-    EcmascriptChunkContent, EcmascriptChunkItem, EcmascriptChunkItemExt,
+use turbopack_ecmascript::{
+    chunk::{EcmascriptChunkContent, EcmascriptChunkItem, EcmascriptChunkItemExt},
+    refresh_boundary::OptionRefreshBoundary,
 };
 
 /// A chunk item's content entry.
@@ -20,21 +24,48 @@ use turbopack_ecmascript::chunk::{
 /// creating tasks in a hot loop when iterating over thousands of entries when
 /// computing updates.
 #[turbo_tasks::value]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EcmascriptDevChunkContentEntry {
     pub code: ResolvedVc<Code>,
-    pub hash: ResolvedVc<u64>,
+    pub hash: ResolvedVc<u128>,
+    pub refresh_boundary: ResolvedVc<OptionRefreshBoundary>,
+}
+
+/// Lets the runtime reject React Refresh boundaries without inspecting the exports of a module, see
+/// [turbopack_ecmascript::refresh_boundary].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EcmascriptModuleRefreshBoundary {
+    is_boundary: bool,
+    components: Vec<RcStr>,
+    non_components: Vec<RcStr>,
 }
 
 impl EcmascriptDevChunkContentEntry {
+    pub(crate) async fn refresh_boundary_info(
+        &self,
+    ) -> Result<Option<EcmascriptModuleRefreshBoundary>> {
+        Ok(self
+            .refresh_boundary
+            .await?
+            .as_ref()
+            .map(|boundary| EcmascriptModuleRefreshBoundary {
+                is_boundary: boundary.is_boundary(),
+                components: boundary.components.clone(),
+                non_components: boundary.non_components.clone(),
+            }))
+    }
+
     pub async fn new(
         chunk_item: Vc<Box<dyn EcmascriptChunkItem>>,
         async_module_info: Option<Vc<AsyncModuleInfo>>,
+        hash_algorithm: Vc<HashAlgorithm>,
     ) -> Result<Self> {
         let code = chunk_item.code(async_module_info).to_resolved().await?;
         Ok(EcmascriptDevChunkContentEntry {
             code,
-            hash: code.source_code_hash().to_resolved().await?,
+            hash: code.source_code_hash(hash_algorithm).to_resolved().await?,
+            refresh_boundary: chunk_item.refresh_boundary().to_resolved().await?,
         })
     }
 }
@@ -49,6 +80,7 @@ impl EcmascriptDevChunkContentEntries {
     #[turbo_tasks::function]
     pub async fn new(
         chunk_content: Vc<EcmascriptChunkContent>,
+        hash_algorithm: Vc<HashAlgorithm>,
     ) -> Result<Vc<EcmascriptDevChunkContentEntries>> {
         let chunk_content = chunk_content.await?;
 
@@ -59,7 +91,12 @@ impl EcmascriptDevChunkContentEntries {
                 async move {
                     Ok((
                         chunk_item.id().await?,
-                        EcmascriptDevChunkContentEntry::new(chunk_item, async_module_info).await?,
+                        EcmascriptDevChunkContentEntry::new(
+                            chunk_item,
+                            async_module_info,
+                            hash_algorithm,
+                        )
+                        .await?,
                     ))
                 }
                 .instrument(info_span!(
@@ -14,6 +14,7 @@ use turbopack_core::{
 
 use super::{
     super::{
+        content_entry::{EcmascriptDevChunkContentEntry, EcmascriptModuleRefreshBoundary},
         update::{update_ecmascript_chunk, EcmascriptChunkUpdate},
         version::EcmascriptDevChunkVersion,
     },
@@ -78,10 +79,17 @@ struct EcmascriptModuleEntry {
     code: Rope,
     url: String,
     map: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh: Option<EcmascriptModuleRefreshBoundary>,
 }
 
 impl EcmascriptModuleEntry {
-    async fn from_code(id: &ModuleId, code: Vc<Code>, chunk_path: &str) -> Result<Self> {
+    async fn from_entry(
+        id: &ModuleId,
+        entry: &EcmascriptDevChunkContentEntry,
+        chunk_path: &str,
+    ) -> Result<Self> {
+        let code = *entry.code;
         let map = match &*code.generate_source_map().await? {
             Some(map) => {
                 let map = map.await?.to_source_map().await?;
@@ -92,10 +100,18 @@ impl EcmascriptModuleEntry {
             None => None,
         };
 
-        Ok(Self::new(id, code.await?, map, chunk_path))
+        let refresh = entry.refresh_boundary_info().await?;
+
+        Ok(Self::new(id, code.await?, map, refresh, chunk_path))
     }
 
-    fn new(id: &ModuleId, code: ReadRef<Code>, map: Option<String>, chunk_path: &str) -> Self {
+    fn new(
+        id: &ModuleId,
+        code: ReadRef<Code>,
+        map: Option<String>,
+        refresh: Option<EcmascriptModuleRefreshBoundary>,
+        chunk_path: &str,
+    ) -> Self {
         /// serde_qs can't serialize a lone enum when it's [serde::untagged].
         #[derive(Serialize)]
         struct Id<'a> {
@@ -108,6 +124,7 @@ impl EcmascriptModuleEntry {
             code: code.source_code().clone(),
             url: format!("{}?{}", chunk_path, &id),
             map,
+            refresh,
         }
     }
 }
@@ -127,7 +144,7 @@ impl MergedModuleMap {
 
     /// Returns the hash of the module with the given id, or `None` if the
     /// module is not present in any of the versions.
-    fn get(&self, id: &ReadRef<ModuleId>) -> Option<u64> {
+    fn get(&self, id: &ReadRef<ModuleId>) -> Option<u128> {
         for version in &self.versions {
             if let Some(hash) = version.entries_hashes.get(id) {
                 return Some(*hash);
@@ -209,13 +226,13 @@ pub(super) async fn update_ecmascript_merged_chunk(
                     // The chunk was updated.
                     let mut partial = EcmascriptMergedChunkPartial::default();
 
-                    for (module_id, (module_hash, module_code)) in chunk_partial.added {
+                    for (module_id, (module_hash, module_entry)) in chunk_partial.added {
                         partial.added.insert(module_id.clone());
 
                         if merged_module_map.get(&module_id) != Some(module_hash) {
-                            let entry = EcmascriptModuleEntry::from_code(
+                            let entry = EcmascriptModuleEntry::from_entry(
                                 &module_id,
-                                *module_code,
+                                &module_entry,
                                 chunk_path,
                             )
                             .await?;
@@ -225,10 +242,18 @@ pub(super) async fn update_ecmascript_merged_chunk(
 
                     partial.deleted.extend(chunk_partial.deleted.into_keys());
 
-                    for (module_id, module_code) in chunk_partial.modified {
-                        let entry =
-                            EcmascriptModuleEntry::from_code(&module_id, *module_code, chunk_path)
-                                .await?;
+                    for (module_id, module_entry) in chunk_partial.modified {
+                        // Updates to modules that aren't boundaries may lead to a full reload,
+                        // explain why.
+                        if let Some(boundary) = &*module_entry.refresh_boundary.await? {
+                            boundary.emit_issue();
+                        }
+                        let entry = EcmascriptModuleEntry::from_entry(
+                            &module_id,
+                            &module_entry,
+                            chunk_path,
+                        )
+                        .await?;
                         merged_update.entries.insert(module_id, entry);
                     }
 
@@ -244,8 +269,7 @@ pub(super) async fn update_ecmascript_merged_chunk(
                 added.modules.insert(id.clone());
 
                 if merged_module_map.get(id) != Some(hash) {
-                    let entry =
-                        EcmascriptModuleEntry::from_code(id, *entry.code, chunk_path).await?;
+                    let entry = EcmascriptModuleEntry::from_entry(id, entry, chunk_path).await?;
                     merged_update.entries.insert(id.clone(), entry);
                 }
             }
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ReadRef, TryJoinIterExt, Vc};
-use turbo_tasks_hash::{encode_hex, Xxh3Hash64Hasher};
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::version::Version;
 
 use super::super::version::EcmascriptDevChunkVersion;
@@ -18,7 +18,12 @@ pub(super) struct EcmascriptDevMergedChunkVersion {
 impl Version for EcmascriptDevMergedChunkVersion {
     #[turbo_tasks::function]
     async fn id(&self) -> Result<Vc<RcStr>> {
-        let mut hasher = Xxh3Hash64Hasher::new();
+        // The merged chunks come from the same chunking context, and so use the same algorithm.
+        let hash_algorithm = self
+            .versions
+            .first()
+            .map_or_else(HashAlgorithm::default, |version| version.hash_algorithm);
+        let mut hasher = hash_algorithm.hasher();
         hasher.write_value(self.versions.len());
         let sorted_ids = {
             let mut sorted_ids = self
@@ -33,8 +38,6 @@ impl Version for EcmascriptDevMergedChunkVersion {
         for id in sorted_ids {
             hasher.write_value(id);
         }
-        let hash = hasher.finish();
-        let hex_hash = encode_hex(hash);
-        Ok(Vc::cell(hex_hash.into()))
+        Ok(Vc::cell(hasher.finish_digest().to_hex().into()))
     }
 }
@@ -1,8 +1,11 @@
 use anyhow::Result;
-use turbo_tasks::{FxIndexMap, ReadRef, ResolvedVc, Vc};
-use turbopack_core::{chunk::ModuleId, code_builder::Code};
+use turbo_tasks::{FxIndexMap, ReadRef, Vc};
+use turbopack_core::chunk::ModuleId;
 
-use super::{content::EcmascriptDevChunkContent, version::EcmascriptDevChunkVersion};
+use super::{
+    content::EcmascriptDevChunkContent, content_entry::EcmascriptDevChunkContentEntry,
+    version::EcmascriptDevChunkVersion,
+};
 
 #[allow(clippy::large_enum_variant)]
 pub(super) enum EcmascriptChunkUpdate {
@@ -11,9 +14,9 @@ pub(super) enum EcmascriptChunkUpdate {
 }
 
 pub(super) struct EcmascriptChunkPartialUpdate {
-    pub added: FxIndexMap<ReadRef<ModuleId>, (u64, ResolvedVc<Code>)>,
-    pub deleted: FxIndexMap<ReadRef<ModuleId>, u64>,
-    pub modified: FxIndexMap<ReadRef<ModuleId>, ResolvedVc<Code>>,
+    pub added: FxIndexMap<ReadRef<ModuleId>, (u128, EcmascriptDevChunkContentEntry)>,
+    pub deleted: FxIndexMap<ReadRef<ModuleId>, u128>,
+    pub modified: FxIndexMap<ReadRef<ModuleId>, EcmascriptDevChunkContentEntry>,
 }
 
 pub(super) async fn update_ecmascript_chunk(
@@ -40,8 +43,7 @@ pub(super) async fn update_ecmascript_chunk(
     for (id, from_hash) in &from.entries_hashes {
         if let Some(entry) = entries.get(id) {
             if *entry.hash.await? != *from_hash {
-                let resolved_code = entry.code.to_resolved().await?;
-                modified.insert(id.clone(), resolved_code);
+                modified.insert(id.clone(), entry.clone());
             }
         } else {
             deleted.insert(id.clone(), *from_hash);
@@ -51,10 +53,7 @@ pub(super) async fn update_ecmascript_chunk(
     // Remaining entries are added
     for (id, entry) in entries.iter() {
         if !from.entries_hashes.contains_key(id) {
-            added.insert(
-                id.clone(),
-                (*entry.hash.await?, entry.code.to_resolved().await?),
-            );
+            added.insert(id.clone(), (*entry.hash.await?, entry.clone()));
         }
     }
 
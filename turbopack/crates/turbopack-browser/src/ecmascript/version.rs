@@ -2,7 +2,7 @@ use anyhow::{bail, Result};
 use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ReadRef, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbo_tasks_hash::{encode_hex, Xxh3Hash64Hasher};
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::{chunk::ModuleId, version::Version};
 
 use super::content_entry::EcmascriptDevChunkContentEntries;
@@ -10,7 +10,9 @@ use super::content_entry::EcmascriptDevChunkContentEntries;
 #[turbo_tasks::value(serialization = "none")]
 pub(super) struct EcmascriptDevChunkVersion {
     pub(super) chunk_path: String,
-    pub(super) entries_hashes: FxIndexMap<ReadRef<ModuleId>, u64>,
+    pub(super) entries_hashes: FxIndexMap<ReadRef<ModuleId>, u128>,
+    /// The algorithm of the entries hashes, also used for the version id.
+    pub(super) hash_algorithm: HashAlgorithm,
 }
 
 #[turbo_tasks::value_impl]
@@ -20,6 +22,7 @@ impl EcmascriptDevChunkVersion {
         output_root: Vc<FileSystemPath>,
         chunk_path: Vc<FileSystemPath>,
         entries: Vc<EcmascriptDevChunkContentEntries>,
+        hash_algorithm: Vc<HashAlgorithm>,
     ) -> Result<Vc<Self>> {
         let output_root = output_root.await?;
         let chunk_path = chunk_path.await?;
@@ -41,6 +44,7 @@ impl EcmascriptDevChunkVersion {
         Ok(EcmascriptDevChunkVersion {
             chunk_path: chunk_path.to_string(),
             entries_hashes,
+            hash_algorithm: *hash_algorithm.await?,
         }
         .cell())
     }
@@ -50,7 +54,7 @@ impl EcmascriptDevChunkVersion {
 impl Version for EcmascriptDevChunkVersion {
     #[turbo_tasks::function]
     fn id(&self) -> Vc<RcStr> {
-        let mut hasher = Xxh3Hash64Hasher::new();
+        let mut hasher = self.hash_algorithm.hasher();
         hasher.write_ref(&self.chunk_path);
         let sorted_hashes = {
             let mut hashes: Vec<_> = self.entries_hashes.values().copied().collect();
@@ -60,8 +64,6 @@ impl Version for EcmascriptDevChunkVersion {
         for hash in sorted_hashes {
             hasher.write_value(hash);
         }
-        let hash = hasher.finish();
-        let hex_hash = encode_hex(hash);
-        Vc::cell(hex_hash.into())
+        Vc::cell(hasher.finish_digest().to_hex().into())
     }
 }
@@ -6,8 +6,12 @@
 pub(crate) mod chunking_context;
 pub mod ecmascript;
 pub mod react_refresh;
+pub mod service_worker;
+pub mod web_manifest;
 
 pub use chunking_context::{BrowserChunkingContext, BrowserChunkingContextBuilder};
+pub use service_worker::{service_worker_environment, ServiceWorkerEntry};
+pub use web_manifest::WebManifestAsset;
 
 pub fn register() {
     turbo_tasks::register();
@@ -16,5 +20,6 @@ pub fn register() {
     turbopack_ecmascript::register();
     turbopack_ecmascript_runtime::register();
     turbopack_resolve::register();
+    turbopack_static::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
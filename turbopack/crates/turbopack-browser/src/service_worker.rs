@@ -0,0 +1,173 @@
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{
+        availability_info::AvailabilityInfo, ChunkingContext, EvaluatableAsset, EvaluatableAssets,
+    },
+    code_builder::{Code, CodeBuilder},
+    context::AssetContext,
+    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
+    ident::AssetIdent,
+    module::Module,
+    output::{OutputAsset, OutputAssets},
+    reference_type::{ReferenceType, WorkerReferenceSubType},
+    source::Source,
+};
+use turbopack_ecmascript::utils::StringifyJs;
+
+#[turbo_tasks::function]
+fn modifier() -> Vc<RcStr> {
+    Vc::cell("service worker".into())
+}
+
+/// The environment service workers are compiled for. Chunking contexts for
+/// [ServiceWorkerEntry] should use it, so nothing relies on the DOM.
+#[turbo_tasks::function]
+pub fn service_worker_environment(browserslist_query: RcStr) -> Vc<Environment> {
+    Environment::new(Value::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: false,
+            web_worker: false,
+            service_worker: true,
+            browserslist_query,
+        }
+        .resolved_cell(),
+    )))
+}
+
+/// The entry script of a service worker.
+///
+/// The service worker gets its own chunk group, which is loaded with
+/// `importScripts`. Only the entry script is emitted at `path`, which doesn't
+/// change between builds. A service worker controls the pages below the
+/// directory of its script, so `path` is usually at the root of the client
+/// output.
+#[turbo_tasks::value]
+pub struct ServiceWorkerEntry {
+    path: ResolvedVc<FileSystemPath>,
+    source: ResolvedVc<Box<dyn Source>>,
+    asset_context: ResolvedVc<Box<dyn AssetContext>>,
+    chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ServiceWorkerEntry {
+    /// Creates the entry script at `path` for the service worker `source`.
+    /// `source` is processed with `asset_context` as a
+    /// [WorkerReferenceSubType::ServiceWorker] and chunked with
+    /// `chunking_context`, whose environment is usually
+    /// [service_worker_environment].
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<FileSystemPath>,
+        source: ResolvedVc<Box<dyn Source>>,
+        asset_context: ResolvedVc<Box<dyn AssetContext>>,
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Self> {
+        ServiceWorkerEntry {
+            path,
+            source,
+            asset_context,
+            chunking_context,
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn chunks(&self) -> Result<Vc<OutputAssets>> {
+        let module = self
+            .asset_context
+            .process(
+                *self.source,
+                Value::new(ReferenceType::Worker(WorkerReferenceSubType::ServiceWorker)),
+            )
+            .module();
+        let Some(evaluatable) =
+            Vc::try_resolve_sidecast::<Box<dyn EvaluatableAsset>>(module).await?
+        else {
+            bail!(
+                "{} is not evaluatable as a service worker",
+                module.ident().to_string().await?
+            );
+        };
+        Ok(self.chunking_context.evaluated_chunk_group_assets(
+            AssetIdent::from_path(
+                self.chunking_context
+                    .chunk_path(module.ident(), ".js".into()),
+            )
+            .with_modifier(modifier()),
+            EvaluatableAssets::empty().with_entry(evaluatable),
+            Value::new(AvailabilityInfo::Root),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
+        let this = self.await?;
+        let directory = this.path.parent().await?;
+
+        // Chunks are imported relative to the script, which keeps working
+        // when the output is served from a sub path.
+        let mut urls = Vec::new();
+        for chunk in self.chunks().await?.iter() {
+            let path = chunk.ident().path().await?;
+            if path.extension_ref() != Some("js") {
+                continue;
+            }
+            let Some(url) = directory.get_relative_path_to(&path) else {
+                bail!(
+                    "cannot find a relative path from the service worker ({}) to its chunk ({})",
+                    this.path.to_string().await?,
+                    path.to_string()
+                );
+            };
+            urls.push(url);
+        }
+
+        // The runtime loads further chunks relative to the origin, like in
+        // other workers.
+        let mut code = CodeBuilder::default();
+        writeln!(
+            code,
+            "self.TURBOPACK_WORKER_LOCATION = self.location.origin;"
+        )?;
+        writeln!(
+            code,
+            "importScripts({});",
+            urls.iter()
+                .map(|url| StringifyJs(url.as_str()).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        Ok(Code::cell(code.build()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for ServiceWorkerEntry {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path)
+    }
+
+    #[turbo_tasks::function]
+    fn references(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.chunks()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for ServiceWorkerEntry {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let code = self.code().await?;
+        Ok(AssetContent::file(
+            File::from(code.source_code().clone()).into(),
+        ))
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{bail, Result};
+use serde_json::Value as JsonValue;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileJsonContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::ChunkingContext,
+    context::AssetContext,
+    ident::AssetIdent,
+    output::{OutputAsset, OutputAssets},
+    reference_type::{ReferenceType, UrlReferenceSubType},
+    resolve::{handle_resolve_source_error, parse::Request, resolve},
+    source::Source,
+};
+use turbopack_static::output_asset::StaticAsset;
+
+/// The images of a web app manifest, keyed by their `src` in the manifest.
+#[turbo_tasks::value(transparent)]
+struct WebManifestImages(FxIndexMap<RcStr, ResolvedVc<StaticAsset>>);
+
+/// A [web app manifest](https://developer.mozilla.org/docs/Web/Manifest).
+///
+/// The relative image URLs of `icons`, `screenshots` and `shortcuts[].icons`
+/// are resolved like imports and the images are emitted as static assets, so
+/// they get content hashed file names and the manifest points to them.
+/// Absolute paths and URLs are kept as they are.
+#[turbo_tasks::value]
+pub struct WebManifestAsset {
+    path: ResolvedVc<FileSystemPath>,
+    source: ResolvedVc<Box<dyn Source>>,
+    asset_context: ResolvedVc<Box<dyn AssetContext>>,
+    chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl WebManifestAsset {
+    /// Creates the manifest at `path` from `source`. The images are resolved
+    /// with `asset_context` and emitted with `chunking_context`.
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<FileSystemPath>,
+        source: ResolvedVc<Box<dyn Source>>,
+        asset_context: ResolvedVc<Box<dyn AssetContext>>,
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Self> {
+        WebManifestAsset {
+            path,
+            source,
+            asset_context,
+            chunking_context,
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn manifest(&self) -> Result<Vc<JsonValue>> {
+        match &*self.source.content().parse_json().await? {
+            FileJsonContent::Content(manifest) => Ok(Vc::cell(manifest.clone())),
+            FileJsonContent::Unparseable(e) => bail!(
+                "Unable to parse the web app manifest {}: {}",
+                self.source.ident().to_string().await?,
+                e.message
+            ),
+            FileJsonContent::NotFound => bail!(
+                "Web app manifest not found: {}",
+                self.source.ident().to_string().await?
+            ),
+        }
+    }
+
+    #[turbo_tasks::function]
+    async fn images(self: Vc<Self>) -> Result<Vc<WebManifestImages>> {
+        let this = self.await?;
+        let manifest = self.manifest().await?;
+        let origin_path = this.source.ident().path();
+        let reference_type = Value::new(ReferenceType::Url(UrlReferenceSubType::Undefined));
+        let resolve_options = this
+            .asset_context
+            .resolve_options(origin_path, reference_type.clone());
+
+        let mut images = FxIndexMap::default();
+        for src in image_sources(&manifest) {
+            if images.contains_key(src) || !is_relative_url(src) {
+                continue;
+            }
+            let request = Request::parse(Value::new(RcStr::from(src).into()));
+            let result = handle_resolve_source_error(
+                resolve(
+                    origin_path.parent(),
+                    reference_type.clone(),
+                    request,
+                    resolve_options,
+                ),
+                reference_type.clone(),
+                origin_path,
+                request,
+                resolve_options,
+                false,
+                None,
+            )
+            .await?;
+            let Some(source) = *result.first_source().await? else {
+                continue;
+            };
+            images.insert(
+                RcStr::from(src),
+                StaticAsset::new(*this.chunking_context, *source)
+                    .to_resolved()
+                    .await?,
+            );
+        }
+        Ok(Vc::cell(images))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for WebManifestAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path)
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
+        Ok(Vc::cell(
+            self.images()
+                .await?
+                .values()
+                .map(|image| ResolvedVc::upcast(*image))
+                .collect(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WebManifestAsset {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let this = self.await?;
+        let images = self.images().await?;
+        let mut urls = FxIndexMap::default();
+        for (src, image) in images.iter() {
+            urls.insert(
+                src.clone(),
+                this.chunking_context
+                    .asset_url(image.ident())
+                    .await?
+                    .clone_value(),
+            );
+        }
+
+        let mut manifest = self.manifest().await?.clone_value();
+        for_each_image_source(&mut manifest, |src| {
+            if let Some(url) = urls.get(src.as_str()) {
+                *src = url.to_string();
+            }
+        });
+        Ok(AssetContent::file(
+            File::from(serde_json::to_string_pretty(&manifest)?).into(),
+        ))
+    }
+}
+
+/// The members of a manifest which contain image resources.
+const IMAGE_MEMBERS: &[&str] = &["icons", "screenshots"];
+
+fn image_sources(manifest: &JsonValue) -> Vec<&str> {
+    fn add<'a>(sources: &mut Vec<&'a str>, resources: &'a JsonValue) {
+        for resource in resources.as_array().into_iter().flatten() {
+            if let Some(src) = resource["src"].as_str() {
+                sources.push(src);
+            }
+        }
+    }
+
+    let mut sources = Vec::new();
+    for member in IMAGE_MEMBERS {
+        add(&mut sources, &manifest[member]);
+    }
+    for shortcut in manifest["shortcuts"].as_array().into_iter().flatten() {
+        add(&mut sources, &shortcut["icons"]);
+    }
+    sources
+}
+
+fn for_each_image_source(manifest: &mut JsonValue, mut f: impl FnMut(&mut String)) {
+    let mut visit = |resources: Option<&mut JsonValue>| {
+        let Some(JsonValue::Array(resources)) = resources else {
+            return;
+        };
+        for resource in resources {
+            if let Some(JsonValue::String(src)) = resource.get_mut("src") {
+                f(src);
+            }
+        }
+    };
+    for member in IMAGE_MEMBERS {
+        visit(manifest.get_mut(member));
+    }
+    if let Some(JsonValue::Array(shortcuts)) = manifest.get_mut("shortcuts") {
+        for shortcut in shortcuts {
+            visit(shortcut.get_mut("icons"));
+        }
+    }
+}
+
+/// Whether `src` refers to a file relative to the manifest, rather than to a
+/// path on the server or another origin.
+fn is_relative_url(src: &str) -> bool {
+    !src.is_empty() && !src.starts_with('/') && !src.contains(':')
+}
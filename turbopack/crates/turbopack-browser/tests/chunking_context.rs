@@ -0,0 +1,119 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_tasks::{TurboTasks, Value, Vc};
+use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_hash::HashAlgorithm;
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_browser::{BrowserChunkingContext, BrowserChunkingContextBuilder};
+use turbopack_core::environment::{BrowserEnvironment, Environment, ExecutionEnvironment};
+use turbopack_ecmascript_runtime::RuntimeType;
+
+async fn builder(
+    root: Vc<FileSystemPath>,
+    runtime_type: RuntimeType,
+) -> Result<BrowserChunkingContextBuilder> {
+    let root = root.to_resolved().await?;
+    let environment = Environment::new(Value::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: true,
+            web_worker: false,
+            service_worker: false,
+            browserslist_query: "chrome 100".into(),
+        }
+        .resolved_cell(),
+    )));
+    Ok(BrowserChunkingContext::builder(
+        root,
+        root,
+        root,
+        root.join("chunks".into()).to_resolved().await?,
+        root.join("assets".into()).to_resolved().await?,
+        environment.to_resolved().await?,
+        runtime_type,
+    ))
+}
+
+fn error(result: Result<Vc<BrowserChunkingContext>>) -> String {
+    match result {
+        Ok(_) => panic!("the chunking context was built"),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn rejects_invalid_combinations() {
+    turbopack_browser::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+
+        let hmr_in_production = builder(root, RuntimeType::Production)
+            .await?
+            .name("client".into())
+            .hot_module_replacement()
+            .build();
+        assert_eq!(
+            error(hmr_in_production),
+            "Invalid client chunking context: hot module replacement requires the development \
+             runtime, but the Production runtime was selected"
+        );
+
+        let hmr_with_content_hash = builder(root, RuntimeType::Development)
+            .await?
+            .hot_module_replacement()
+            .content_hash_algorithm(HashAlgorithm::Blake3)
+            .build();
+        assert_eq!(
+            error(hmr_with_content_hash),
+            "Invalid browser chunking context: the content hash algorithm can only be changed for \
+             production output, but hot module replacement is enabled with the Blake3 algorithm"
+        );
+
+        let file_uris_without_source_maps = builder(root, RuntimeType::Development)
+            .await?
+            .use_file_source_map_uris()
+            .reference_chunk_source_maps(false)
+            .reference_css_chunk_source_maps(false)
+            .build();
+        assert_eq!(
+            error(file_uris_without_source_maps),
+            "Invalid browser chunking context: file:// source map URIs were requested, but chunks \
+             don't reference their source maps"
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn builds_valid_combinations() {
+    turbopack_browser::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+
+        builder(root, RuntimeType::Development)
+            .await?
+            .hot_module_replacement()
+            .use_file_source_map_uris()
+            .content_hash_algorithm(HashAlgorithm::default())
+            .build()?;
+        builder(root, RuntimeType::Development)
+            .await?
+            .use_file_source_map_uris()
+            .reference_chunk_source_maps(false)
+            .build()?;
+        builder(root, RuntimeType::Production)
+            .await?
+            .content_hash_algorithm(HashAlgorithm::Blake3)
+            .build()?;
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -87,6 +87,26 @@ fn format_source_content(source: &PlainIssueSource, formatted_issue: &mut String
     }
 }
 
+/// Formats a secondary location of an issue, consisting of a header with the
+/// location and label, followed by the source context.
+fn format_related_source(source: &PlainIssueSource, formatted_issue: &mut String) {
+    let path = source.asset.ident.to_string().bright_blue();
+    let label = source.label.as_deref().unwrap_or_default();
+    match source.range {
+        Some((start, _)) => writeln!(
+            formatted_issue,
+            "\n{}:{}:{}  {}",
+            path,
+            start.line + 1,
+            start.column,
+            label
+        ),
+        None => writeln!(formatted_issue, "\n{}  {}", path, label),
+    }
+    .unwrap();
+    format_source_content(source, formatted_issue);
+}
+
 fn format_optional_path(
     path: &Option<Vec<ReadRef<PlainIssueProcessingPathItem>>>,
     formatted_issue: &mut String,
@@ -164,6 +184,21 @@ pub fn format_issue(
         let documentation_link = &plain_issue.documentation_link;
         if !documentation_link.is_empty() {
             writeln!(styled_issue, "\ndocumentation: {documentation_link}").unwrap();
+        } else if let Some(code) = plain_issue.code {
+            writeln!(
+                styled_issue,
+                "\ndocumentation: {}",
+                code.documentation_url()
+            )
+            .unwrap();
+        }
+        if let Some(suggested_fix) = &plain_issue.suggested_fix {
+            writeln!(
+                styled_issue,
+                "\nsuggested fix: {}",
+                suggested_fix.description
+            )
+            .unwrap();
         }
         if let Some(path) = path {
             writeln!(styled_issue, "{}", path).unwrap();
@@ -409,6 +444,12 @@ impl IssueReporter for ConsoleUi {
                 let documentation_link = &plain_issue.documentation_link;
                 if !documentation_link.is_empty() {
                     writeln!(&mut styled_issue, "\ndocumentation: {documentation_link}")?;
+                } else if let Some(code) = plain_issue.code {
+                    writeln!(
+                        &mut styled_issue,
+                        "\ndocumentation: {}",
+                        code.documentation_url()
+                    )?;
                 }
                 format_optional_path(processing_path, &mut styled_issue)?;
             }
@@ -574,10 +615,13 @@ fn render_styled_string_to_ansi(styled_string: &StyledString) -> String {
 
 fn style_issue_source(plain_issue: &PlainIssue, context_path: &str) -> String {
     let title = &plain_issue.title;
-    let formatted_title = match title {
+    let mut formatted_title = match title {
         StyledString::Text(text) => text.bold().to_string(),
         _ => render_styled_string_to_ansi(title),
     };
+    if let Some(code) = plain_issue.code {
+        formatted_title = format!("{}  {}", code.dimmed(), formatted_title);
+    }
 
     if let Some(source) = &plain_issue.source {
         let mut styled_issue = match source.range {
@@ -592,6 +636,12 @@ fn style_issue_source(plain_issue: &PlainIssue, context_path: &str) -> String {
         };
         styled_issue.push('\n');
         format_source_content(source, &mut styled_issue);
+        if let Some(label) = &source.label {
+            writeln!(styled_issue, "{label}").unwrap();
+        }
+        for related in &source.related {
+            format_related_source(related, &mut styled_issue);
+        }
         styled_issue
     } else {
         formatted_title
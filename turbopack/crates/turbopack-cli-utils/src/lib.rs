@@ -7,6 +7,7 @@
 
 pub mod issue;
 pub mod runtime_entry;
+pub mod sarif;
 pub mod source_context;
 
 pub fn register() {
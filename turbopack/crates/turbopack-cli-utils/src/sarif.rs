@@ -0,0 +1,166 @@
+//! Exports issues in the [SARIF] format, which code scanning tools and CI
+//! services can annotate pull requests with.
+//!
+//! Every issue code is a rule of the tool, so results are grouped by their
+//! code across versions.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use turbo_tasks::ReadRef;
+use turbopack_core::issue::{code::ISSUE_CODES, IssueSeverity, PlainIssue, StyledString};
+
+/// The base which artifact locations are relative to, defined as the root
+/// directory of the project in the log.
+const PROJECT_ROOT_URI_BASE: &str = "PROJECTROOT";
+
+/// Builds a SARIF log with a single run containing `issues`. `root_dir` is the
+/// directory the `[project]` file system of the issues is rooted at.
+pub fn sarif_log(issues: &[ReadRef<PlainIssue>], root_dir: &Path) -> Value {
+    let mut root_uri = format!("file://{}", root_dir.to_string_lossy().replace('\\', "/"));
+    if !root_uri.ends_with('/') {
+        root_uri.push('/');
+    }
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "Turbopack",
+                    "informationUri": "https://turbo.build/pack",
+                    "rules": rules(),
+                },
+            },
+            "originalUriBaseIds": {
+                PROJECT_ROOT_URI_BASE: { "uri": root_uri },
+            },
+            "results": issues.iter().map(|issue| sarif_result(issue)).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+/// One rule for each registered issue code.
+fn rules() -> Vec<Value> {
+    ISSUE_CODES
+        .iter()
+        .map(|info| {
+            json!({
+                "id": info.code.to_string(),
+                "name": info.name,
+                "shortDescription": { "text": info.summary },
+                "helpUri": info.code.documentation_url(),
+            })
+        })
+        .collect()
+}
+
+fn sarif_result(issue: &PlainIssue) -> Value {
+    let mut message = styled_string_to_text(&issue.title);
+    if let Some(description) = &issue.description {
+        message.push_str("\n\n");
+        message.push_str(&styled_string_to_text(description));
+    }
+    let mut physical_location = json!({
+        "artifactLocation": artifact_location(&issue.file_path),
+    });
+    if let Some((start, end)) = issue.source.as_ref().and_then(|source| source.range) {
+        physical_location["region"] = json!({
+            "startLine": start.line + 1,
+            "startColumn": start.column + 1,
+            "endLine": end.line + 1,
+            "endColumn": end.column + 1,
+        });
+    }
+    let mut result = json!({
+        "level": level(issue.severity),
+        "message": { "text": message.trim_end() },
+        "locations": [{ "physicalLocation": physical_location }],
+    });
+    if let Some(code) = issue.code {
+        result["ruleId"] = code.to_string().into();
+        if let Some(index) = ISSUE_CODES.iter().position(|info| info.code == code) {
+            result["ruleIndex"] = index.into();
+        }
+    }
+    result
+}
+
+/// Paths in the project file system are relative to the root directory, other
+/// paths are kept as they are.
+fn artifact_location(file_path: &str) -> Value {
+    match file_path.strip_prefix("[project]/") {
+        Some(path) => json!({
+            "uri": path,
+            "uriBaseId": PROJECT_ROOT_URI_BASE,
+        }),
+        None => json!({ "uri": file_path }),
+    }
+}
+
+fn level(severity: IssueSeverity) -> &'static str {
+    match severity {
+        IssueSeverity::Bug | IssueSeverity::Fatal | IssueSeverity::Error => "error",
+        IssueSeverity::Warning => "warning",
+        IssueSeverity::Hint
+        | IssueSeverity::Note
+        | IssueSeverity::Suggestion
+        | IssueSeverity::Info => "note",
+    }
+}
+
+fn styled_string_to_text(styled_string: &StyledString) -> String {
+    match styled_string {
+        StyledString::Line(parts) => {
+            let mut string: String = parts.iter().map(styled_string_to_text).collect();
+            string.push('\n');
+            string
+        }
+        StyledString::Stack(parts) => parts
+            .iter()
+            .map(|part| styled_string_to_text(part) + "\n")
+            .collect(),
+        StyledString::Text(string) | StyledString::Strong(string) => string.to_string(),
+        StyledString::Code(string) => format!("`{string}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_cover_all_codes() {
+        let rules = rules();
+        assert_eq!(rules.len(), ISSUE_CODES.len());
+        assert_eq!(rules[0]["id"], "TP1001");
+        assert_eq!(rules[0]["name"], "MODULE_NOT_FOUND");
+    }
+
+    #[test]
+    fn artifact_locations_are_relative_to_the_project() {
+        assert_eq!(
+            artifact_location("[project]/src/index.js"),
+            json!({ "uri": "src/index.js", "uriBaseId": PROJECT_ROOT_URI_BASE })
+        );
+        assert_eq!(
+            artifact_location("[externals]/fs"),
+            json!({ "uri": "[externals]/fs" })
+        );
+    }
+
+    #[test]
+    fn styled_strings_are_flattened() {
+        let title = StyledString::Line(vec![
+            StyledString::Strong("Module not found".into()),
+            StyledString::Text(": Can't resolve ".into()),
+            StyledString::Code("./missing".into()),
+        ]);
+        assert_eq!(
+            styled_string_to_text(&title),
+            "Module not found: Can't resolve `./missing`\n"
+        );
+    }
+}
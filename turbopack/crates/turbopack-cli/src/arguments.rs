@@ -3,7 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueEnum};
 use turbopack_cli_utils::issue::IssueSeverityCliOption;
 
 #[derive(Debug, Parser)]
@@ -61,6 +61,13 @@ pub struct CommonArguments {
     /// MB.
     #[clap(long)]
     pub memory_limit: Option<usize>,
+
+    /// Starts a plugin process, which can resolve requests and transform
+    /// sources. The command is split at whitespace into the program and its
+    /// arguments, and runs in the project directory. Can be given multiple
+    /// times.
+    #[clap(long = "plugin")]
+    pub plugins: Vec<String>,
 }
 
 #[derive(Debug, Args)]
@@ -89,6 +96,17 @@ pub struct DevArguments {
     #[clap(long)]
     pub no_open: bool,
 
+    /// A web app manifest to serve at `/manifest.webmanifest`, relative to
+    /// the project's directory. The images it references are served as static
+    /// assets.
+    #[clap(long, value_parser)]
+    pub web_manifest: Option<String>,
+
+    /// A service worker to serve at `/sw.js`, relative to the project's
+    /// directory. It's compiled for the service worker environment.
+    #[clap(long, value_parser)]
+    pub service_worker: Option<String>,
+
     // ==
     // = Inherited options from next-dev, need revisit later.
     // ==
@@ -107,4 +125,68 @@ pub struct BuildArguments {
     /// Don't minify build output.
     #[clap(long)]
     pub no_minify: bool,
+
+    /// Print how much compile time is spent on each npm package after the
+    /// build.
+    #[clap(long)]
+    pub compile_time_report: bool,
+
+    /// Build a library instead of an application, which exposes the exports
+    /// of each entry in the given module format.
+    #[clap(long, value_enum)]
+    pub library: Option<LibraryFormatCliOption>,
+
+    /// The name of the library in the error of `--library umd` without a
+    /// module loader. Defaults to the file name of the entry.
+    #[clap(long)]
+    pub library_name: Option<String>,
+
+    /// Bundle each entry and everything it loads into a single self-contained
+    /// file in the given module format, e.g. for deployment targets which
+    /// prefer single-file functions.
+    #[clap(long, value_enum, conflicts_with = "library")]
+    pub single_file: Option<SingleFileFormatCliOption>,
+
+    /// How assets which aren't JavaScript, e.g. native addons, are shipped
+    /// with `--single-file`.
+    #[clap(long, value_enum, requires = "single_file", default_value = "inline")]
+    pub single_file_assets: SingleFileAssetsCliOption,
+
+    /// Make the output reproducible: sort the keys of JSON outputs, reset
+    /// modification times to `SOURCE_DATE_EPOCH` and warn about outputs that
+    /// contain absolute paths.
+    #[clap(long)]
+    pub deterministic: bool,
+
+    /// Build twice with `--deterministic` and report all files that differ
+    /// between the builds.
+    #[clap(long)]
+    pub verify_deterministic: bool,
+
+    /// Write the issues of the build to a SARIF file, e.g. to annotate pull
+    /// requests with code scanning tools.
+    #[clap(long, value_parser)]
+    pub sarif: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LibraryFormatCliOption {
+    Cjs,
+    Esm,
+    Umd,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SingleFileFormatCliOption {
+    Cjs,
+    Esm,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SingleFileAssetsCliOption {
+    /// Inline the assets into the bundle, they are extracted into a temporary
+    /// directory on start.
+    Inline,
+    /// Emit the assets into a directory next to the bundle.
+    Directory,
 }
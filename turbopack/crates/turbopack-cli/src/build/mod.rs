@@ -1,27 +1,37 @@
 use std::{
     collections::HashSet,
     env::current_dir,
-    path::{PathBuf, MAIN_SEPARATOR},
+    fs,
+    path::{Path, PathBuf, MAIN_SEPARATOR},
     sync::Arc,
 };
 
 use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
-    apply_effects, ReadConsistency, ResolvedVc, TransientInstance, TryJoinIterExt, TurboTasks,
-    Value, Vc,
+    apply_effects, trace::TraceRawVcs, ReadConsistency, ResolvedVc, TaskInput, TransientInstance,
+    TryJoinIterExt, TurboTasks, Value, Vc,
 };
 use turbo_tasks_fs::FileSystem;
 use turbo_tasks_memory::MemoryBackend;
-use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
+use turbopack::ecmascript::library::{library_entry_assets, LibraryFormat};
+use turbopack_cli_utils::{
+    issue::{ConsoleUi, LogOptions},
+    sarif::sarif_log,
+};
 use turbopack_core::{
     asset::Asset,
     chunk::{
         availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext, ChunkingContextExt,
         EvaluatableAsset, EvaluatableAssets, MinifyType,
     },
-    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
-    issue::{handle_issues, IssueReporter, IssueSeverity},
+    compile_time_report::{compile_time_report, enable_compile_time_report},
+    environment::{
+        BrowserEnvironment, ChunkLoaderBackend, Environment, ExecutionEnvironment,
+        NodeJsEnvironment,
+    },
+    issue::{handle_issues, IssueDescriptionExt, IssueReporter, IssueSeverity},
     module::Module,
     output::{OutputAsset, OutputAssets},
     reference::all_assets_from_entries,
@@ -34,17 +44,31 @@ use turbopack_core::{
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_env::dotenv::load_env;
 use turbopack_node::execution_context::ExecutionContext;
-use turbopack_nodejs::NodeJsChunkingContext;
+use turbopack_nodejs::{
+    NodeJsChunkingContext, SingleFileAssets, SingleFileFormat, SingleFileServerBundle,
+    SINGLE_FILE_CHUNKS_GLOBAL,
+};
 
+use self::reproducibility::{
+    check_machine_independent, compare_outputs, reset_modification_times, REPRODUCIBILITY_DIR,
+};
+pub use crate::util::EntryRequest;
 use crate::{
-    arguments::BuildArguments,
-    contexts::{get_client_asset_context, get_client_compile_time_info, NodeEnv},
+    arguments::{
+        BuildArguments, LibraryFormatCliOption, SingleFileAssetsCliOption,
+        SingleFileFormatCliOption,
+    },
+    contexts::{
+        get_client_asset_context, get_client_compile_time_info, get_library_asset_context,
+        get_rpc_plugins, NodeEnv,
+    },
     util::{
-        normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest, EntryRequests,
-        NormalizedDirs,
+        normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequests, NormalizedDirs,
     },
 };
 
+mod reproducibility;
+
 pub fn register() {
     turbopack::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
@@ -60,6 +84,32 @@ pub struct TurbopackBuildBuilder {
     show_all: bool,
     log_detail: bool,
     minify_type: MinifyType,
+    compile_time_report: bool,
+    library: Option<LibraryOptions>,
+    single_file: Option<SingleFileOptions>,
+    dist_dir: RcStr,
+    deterministic: bool,
+    plugins: Vec<RcStr>,
+    sarif_path: Option<PathBuf>,
+}
+
+/// Builds the entries as library, see [TurbopackBuildBuilder::library].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs, TaskInput)]
+pub struct LibraryOptions {
+    pub format: LibraryFormat,
+    /// The name of the library in the error of the [LibraryFormat::Umd] facade
+    /// without a module loader. Defaults to the file name of the entry.
+    pub name: Option<RcStr>,
+}
+
+/// Bundles each entry into a single file, see
+/// [TurbopackBuildBuilder::single_file].
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs, TaskInput,
+)]
+pub struct SingleFileOptions {
+    pub format: SingleFileFormat,
+    pub assets: SingleFileAssets,
 }
 
 impl TurbopackBuildBuilder {
@@ -78,6 +128,13 @@ impl TurbopackBuildBuilder {
             show_all: false,
             log_detail: false,
             minify_type: MinifyType::Minify,
+            compile_time_report: false,
+            library: None,
+            single_file: None,
+            dist_dir: "dist".into(),
+            deterministic: false,
+            plugins: vec![],
+            sarif_path: None,
         }
     }
 
@@ -86,6 +143,13 @@ impl TurbopackBuildBuilder {
         self
     }
 
+    /// Starts a plugin process with the command line `plugin`, see
+    /// [CommonArguments::plugins][crate::arguments::CommonArguments::plugins].
+    pub fn plugin(mut self, plugin: RcStr) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
     pub fn browserslist_query(mut self, browserslist_query: RcStr) -> Self {
         self.browserslist_query = browserslist_query;
         self
@@ -111,11 +175,62 @@ impl TurbopackBuildBuilder {
         self
     }
 
+    pub fn compile_time_report(mut self, compile_time_report: bool) -> Self {
+        self.compile_time_report = compile_time_report;
+        self
+    }
+
+    /// Builds a library instead of an application. Each entry gets a facade in
+    /// the given format which exposes its exports, a copy of its hand-written
+    /// declaration file and the peer dependencies of the project stay
+    /// external.
+    pub fn library(mut self, library: LibraryOptions) -> Self {
+        self.library = Some(library);
+        self
+    }
+
+    /// Bundles each entry and all chunks and assets it loads into a single
+    /// file for Node.js, named like the entry with the extension of the
+    /// format.
+    pub fn single_file(mut self, single_file: SingleFileOptions) -> Self {
+        self.single_file = Some(single_file);
+        self
+    }
+
+    /// The output directory relative to the project directory. Defaults to
+    /// `dist`.
+    pub fn dist_dir(mut self, dist_dir: RcStr) -> Self {
+        self.dist_dir = dist_dir;
+        self
+    }
+
+    /// Makes the output independent of the build: the keys of JSON outputs are
+    /// sorted, modification times are reset to `SOURCE_DATE_EPOCH` (or the Unix
+    /// epoch), and outputs containing the absolute root directory are
+    /// reported.
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Writes the issues of the build to a SARIF log at `sarif_path`, see
+    /// [turbopack_cli_utils::sarif].
+    pub fn sarif_path(mut self, sarif_path: PathBuf) -> Self {
+        self.sarif_path = Some(sarif_path);
+        self
+    }
+
     pub async fn build(self) -> Result<()> {
+        if self.compile_time_report {
+            enable_compile_time_report();
+        }
+
+        let dist_path = Path::new(&*self.project_dir).join(&*self.dist_dir);
+        let deterministic = self.deterministic;
         let task = self.turbo_tasks.spawn_once_task::<(), _>(async move {
             let build_result = build_internal(
                 self.project_dir.clone(),
-                self.root_dir,
+                self.root_dir.clone(),
                 EntryRequests(
                     self.entry_requests
                         .iter()
@@ -126,6 +241,11 @@ impl TurbopackBuildBuilder {
                 .cell(),
                 self.browserslist_query,
                 self.minify_type,
+                self.library,
+                self.single_file,
+                self.dist_dir,
+                self.deterministic,
+                self.plugins,
             );
 
             // Await the result to propagate any errors.
@@ -133,6 +253,16 @@ impl TurbopackBuildBuilder {
 
             apply_effects(build_result).await?;
 
+            if let Some(sarif_path) = &self.sarif_path {
+                let issues = build_result.peek_issues_with_path().await?;
+                let sarif = sarif_log(
+                    &issues.get_plain_issues().await?,
+                    Path::new(&*self.root_dir),
+                );
+                fs::write(sarif_path, serde_json::to_string_pretty(&sarif)?)
+                    .with_context(|| format!("Unable to write {}", sarif_path.display()))?;
+            }
+
             let issue_reporter: Vc<Box<dyn IssueReporter>> =
                 Vc::upcast(ConsoleUi::new(TransientInstance::new(LogOptions {
                     project_dir: PathBuf::from(self.project_dir),
@@ -158,6 +288,14 @@ impl TurbopackBuildBuilder {
             .wait_task_completion(task, ReadConsistency::Strong)
             .await?;
 
+        if deterministic {
+            reset_modification_times(&dist_path)?;
+        }
+
+        if let Some(times) = compile_time_report() {
+            print!("{}", times.report());
+        }
+
         Ok(())
     }
 }
@@ -169,16 +307,31 @@ async fn build_internal(
     entry_requests: Vc<EntryRequests>,
     browserslist_query: RcStr,
     minify_type: MinifyType,
+    library: Option<LibraryOptions>,
+    single_file: Option<SingleFileOptions>,
+    dist_dir: RcStr,
+    deterministic: bool,
+    plugins: Vec<RcStr>,
 ) -> Result<Vc<()>> {
-    let env = Environment::new(Value::new(ExecutionEnvironment::Browser(
-        BrowserEnvironment {
-            dom: true,
-            web_worker: false,
-            service_worker: false,
-            browserslist_query: browserslist_query.clone(),
-        }
-        .resolved_cell(),
-    )))
+    let env = if single_file.is_some() {
+        // The chunks are embedded into the bundle and loaded from there.
+        Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+            NodeJsEnvironment::default().resolved_cell(),
+        )))
+        .with_chunk_loader_backend(Value::new(ChunkLoaderBackend::VirtualFileSystem {
+            global: SINGLE_FILE_CHUNKS_GLOBAL.into(),
+        }))
+    } else {
+        Environment::new(Value::new(ExecutionEnvironment::Browser(
+            BrowserEnvironment {
+                dom: true,
+                web_worker: false,
+                service_worker: false,
+                browserslist_query: browserslist_query.clone(),
+            }
+            .resolved_cell(),
+        )))
+    }
     .to_resolved()
     .await?;
     let output_fs = output_fs(project_dir.clone());
@@ -194,7 +347,7 @@ async fn build_internal(
         .join(project_relative)
         .to_resolved()
         .await?;
-    let build_output_root = output_fs.root().join("dist".into()).to_resolved().await?;
+    let build_output_root = output_fs.root().join(dist_dir).to_resolved().await?;
 
     let node_env = NodeEnv::Production.cell();
 
@@ -212,18 +365,32 @@ async fn build_internal(
             },
         )
         .minify_type(minify_type)
-        .build(),
+        .build()?,
     );
 
     let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
     let execution_context =
         ExecutionContext::new(*project_path, chunking_context, load_env(*project_path));
-    let asset_context = get_client_asset_context(
-        *project_path,
-        execution_context,
-        compile_time_info,
-        node_env,
-    );
+    let plugins = get_rpc_plugins(*project_path, plugins);
+    let asset_context = if let Some(library) = &library {
+        get_library_asset_context(
+            *project_path,
+            execution_context,
+            compile_time_info,
+            node_env,
+            plugins,
+            library.format,
+        )
+    } else {
+        get_client_asset_context(
+            *project_path,
+            execution_context,
+            compile_time_info,
+            node_env,
+            plugins,
+            Vc::cell(None),
+        )
+    };
 
     let entry_requests = (*entry_requests
         .await?
@@ -271,6 +438,7 @@ async fn build_internal(
         .try_join()
         .await?;
 
+    let library = &library;
     let entry_chunk_groups = entries
         .into_iter()
         .map(|entry_module| async move {
@@ -278,31 +446,56 @@ async fn build_internal(
                 if let Some(ecmascript) =
                     ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module).await?
                 {
-                    Vc::cell(vec![
-                        Vc::try_resolve_downcast_type::<NodeJsChunkingContext>(chunking_context)
+                    if let Some(library) = library {
+                        library_entry_assets(
+                            chunking_context,
+                            *ecmascript,
+                            *build_output_root,
+                            library.format,
+                            library.name.clone(),
+                        )
+                    } else {
+                        let stem: RcStr = ecmascript
+                            .ident()
+                            .path()
+                            .file_stem()
                             .await?
+                            .as_deref()
                             .unwrap()
-                            .entry_chunk_group(
-                                build_output_root
-                                    .join(
-                                        ecmascript
-                                            .ident()
-                                            .path()
-                                            .file_stem()
-                                            .await?
-                                            .as_deref()
-                                            .unwrap()
-                                            .into(),
-                                    )
-                                    .with_extension("entry.js".into()),
-                                *ResolvedVc::upcast(ecmascript),
-                                EvaluatableAssets::one(*ResolvedVc::upcast(ecmascript)),
-                                OutputAssets::empty(),
-                                Value::new(AvailabilityInfo::Root),
+                            .into();
+                        let entry_chunk = Vc::try_resolve_downcast_type::<NodeJsChunkingContext>(
+                            chunking_context,
+                        )
+                        .await?
+                        .unwrap()
+                        .entry_chunk_group(
+                            build_output_root
+                                .join(stem)
+                                .with_extension("entry.js".into()),
+                            *ResolvedVc::upcast(ecmascript),
+                            EvaluatableAssets::one(*ResolvedVc::upcast(ecmascript)),
+                            OutputAssets::empty(),
+                            Value::new(AvailabilityInfo::Root),
+                        )
+                        .await?
+                        .asset;
+                        if let Some(single_file) = single_file {
+                            let bundle = SingleFileServerBundle::new(
+                                build_output_root.join(
+                                    format!("{stem}{}", single_file.format.extension()).into(),
+                                ),
+                                *build_output_root,
+                                *entry_chunk,
+                                single_file.format,
+                                single_file.assets,
                             )
-                            .await?
-                            .asset,
-                    ])
+                            .to_resolved()
+                            .await?;
+                            Vc::cell(vec![ResolvedVc::upcast(bundle)])
+                        } else {
+                            Vc::cell(vec![entry_chunk])
+                        }
+                    }
                 } else if let Some(chunkable) =
                     ResolvedVc::try_sidecast::<Box<dyn ChunkableModule>>(entry_module).await?
                 {
@@ -323,10 +516,27 @@ async fn build_internal(
     for chunk_group in entry_chunk_groups {
         chunks.extend(&*all_assets_from_entries(chunk_group).await?);
     }
+    if deterministic {
+        chunks
+            .iter()
+            .map(|chunk| check_machine_independent(**chunk, root_dir.clone()))
+            .try_join()
+            .await?;
+    }
 
+    // The writes run concurrently, so their order can't affect the output. What can is the order
+    // of the entries in JSON manifests, which is sorted instead.
     chunks
         .iter()
-        .map(|c| c.content().write(c.ident().path()))
+        .map(|c| async move {
+            let path = c.ident().path();
+            let content = if deterministic && path.await?.extension_ref() == Some("json") {
+                c.content().with_sorted_json_keys()
+            } else {
+                c.content()
+            };
+            content.write(path).await
+        })
         .try_join()
         .await?;
 
@@ -339,6 +549,61 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
         root_dir,
     } = normalize_dirs(&args.common.dir, &args.common.root)?;
 
+    if args.verify_deterministic {
+        return verify_deterministic(args, project_dir, root_dir).await;
+    }
+
+    builder_from_args(args, project_dir, root_dir)
+        .deterministic(args.deterministic)
+        .build()
+        .await
+}
+
+/// Builds the project twice into separate directories and reports every file
+/// that differs between the builds.
+async fn verify_deterministic(
+    args: &BuildArguments,
+    project_dir: RcStr,
+    root_dir: RcStr,
+) -> Result<()> {
+    let dist_dirs = ["first", "second"].map(|name| format!("{REPRODUCIBILITY_DIR}/{name}"));
+    for dist_dir in &dist_dirs {
+        let dist_path = Path::new(&*project_dir).join(dist_dir);
+        if dist_path.exists() {
+            fs::remove_dir_all(&dist_path)
+                .with_context(|| format!("Unable to remove {}", dist_path.display()))?;
+        }
+        builder_from_args(args, project_dir.clone(), root_dir.clone())
+            .deterministic(true)
+            .dist_dir(dist_dir.as_str().into())
+            .build()
+            .await?;
+    }
+
+    let differences = compare_outputs(
+        &Path::new(&*project_dir).join(&dist_dirs[0]),
+        &Path::new(&*project_dir).join(&dist_dirs[1]),
+    )?;
+    if differences.is_empty() {
+        println!("The build is reproducible.");
+        return Ok(());
+    }
+    for difference in &differences {
+        println!("{difference}");
+    }
+    bail!(
+        "The build is not reproducible, {} files differ between {} and {}",
+        differences.len(),
+        dist_dirs[0],
+        dist_dirs[1]
+    )
+}
+
+fn builder_from_args(
+    args: &BuildArguments,
+    project_dir: RcStr,
+    root_dir: RcStr,
+) -> TurbopackBuildBuilder {
     let tt = TurboTasks::new(MemoryBackend::new(
         args.common
             .memory_limit
@@ -357,13 +622,44 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
         } else {
             MinifyType::Minify
         })
-        .show_all(args.common.show_all);
+        .show_all(args.common.show_all)
+        .compile_time_report(args.compile_time_report);
+
+    if let Some(sarif_path) = &args.sarif {
+        builder = builder.sarif_path(sarif_path.clone());
+    }
+
+    if let Some(format) = args.library {
+        builder = builder.library(LibraryOptions {
+            format: match format {
+                LibraryFormatCliOption::Cjs => LibraryFormat::CommonJs,
+                LibraryFormatCliOption::Esm => LibraryFormat::Esm,
+                LibraryFormatCliOption::Umd => LibraryFormat::Umd,
+            },
+            name: args.library_name.as_deref().map(RcStr::from),
+        });
+    }
+
+    if let Some(format) = args.single_file {
+        builder = builder.single_file(SingleFileOptions {
+            format: match format {
+                SingleFileFormatCliOption::Cjs => SingleFileFormat::CommonJs,
+                SingleFileFormatCliOption::Esm => SingleFileFormat::Esm,
+            },
+            assets: match args.single_file_assets {
+                SingleFileAssetsCliOption::Inline => SingleFileAssets::Inline,
+                SingleFileAssetsCliOption::Directory => SingleFileAssets::Directory,
+            },
+        });
+    }
 
     for entry in normalize_entries(&args.common.entries) {
         builder = builder.entry_request(EntryRequest::Relative(entry));
     }
 
-    builder.build().await?;
+    for plugin in &args.common.plugins {
+        builder = builder.plugin(plugin.as_str().into());
+    }
 
-    Ok(())
+    builder
 }
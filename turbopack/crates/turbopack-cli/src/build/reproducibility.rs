@@ -0,0 +1,276 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::Asset,
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
+    output::OutputAsset,
+};
+
+/// The directory inside of the project directory where
+/// `--verify-deterministic` writes both builds.
+pub(crate) const REPRODUCIBILITY_DIR: &str = ".turbopack/reproducibility";
+
+/// Reports an issue when the content of `asset` contains `root_dir`, which
+/// makes the output depend on where the project is checked out.
+#[turbo_tasks::function]
+pub(crate) async fn check_machine_independent(
+    asset: Vc<Box<dyn OutputAsset>>,
+    root_dir: RcStr,
+) -> Result<Vc<()>> {
+    let content = asset.content().file_content().await?;
+    let FileContent::Content(file) = &*content else {
+        return Ok(Default::default());
+    };
+    let Ok(text) = file.content().to_str() else {
+        return Ok(Default::default());
+    };
+    if text.contains(&*root_dir) {
+        MachineDependentOutputIssue {
+            path: asset.ident().path().to_resolved().await?,
+            root_dir,
+        }
+        .cell()
+        .emit();
+    }
+    Ok(Default::default())
+}
+
+#[turbo_tasks::value(shared)]
+struct MachineDependentOutputIssue {
+    path: ResolvedVc<FileSystemPath>,
+    root_dir: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for MachineDependentOutputIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::MACHINE_DEPENDENT_OUTPUT))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::CodeGen.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text("Output contains an absolute path".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                format!(
+                    "The output contains the root directory {}, so it's different when the \
+                     project is built in another location.",
+                    self.root_dir
+                )
+                .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+/// Sets the modification time of all files in `dir` to `SOURCE_DATE_EPOCH`, or
+/// to the Unix epoch when it's not set.
+pub(crate) fn reset_modification_times(dir: &Path) -> Result<()> {
+    let time = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .map_or(UNIX_EPOCH, |seconds| {
+            UNIX_EPOCH + Duration::from_secs(seconds)
+        });
+    set_modification_times(dir, time)
+}
+
+fn set_modification_times(dir: &Path, time: SystemTime) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            set_modification_times(&path, time)?;
+        } else {
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(time))
+                .with_context(|| {
+                    format!("Unable to reset modification time of {}", path.display())
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// A file which differs between two builds.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Difference {
+    /// The path relative to the output directory.
+    pub path: String,
+    pub kind: DifferenceKind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum DifferenceKind {
+    OnlyInFirst,
+    OnlyInSecond,
+    /// The content differs, starting at the given line (1-based).
+    Content {
+        line: usize,
+        first: String,
+        second: String,
+    },
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DifferenceKind::OnlyInFirst => {
+                write!(f, "{}: only emitted by the first build", self.path)
+            }
+            DifferenceKind::OnlyInSecond => {
+                write!(f, "{}: only emitted by the second build", self.path)
+            }
+            DifferenceKind::Content {
+                line,
+                first,
+                second,
+            } => write!(
+                f,
+                "{}:{line}: content differs\n  first:  {first}\n  second: {second}",
+                self.path
+            ),
+        }
+    }
+}
+
+/// Compares the outputs of two builds file by file.
+pub(crate) fn compare_outputs(first: &Path, second: &Path) -> Result<Vec<Difference>> {
+    let mut first = read_tree(first)?;
+    let second = read_tree(second)?;
+    let mut differences = Vec::new();
+    for (path, second_content) in second {
+        let kind = match first.remove(&path) {
+            None => DifferenceKind::OnlyInSecond,
+            Some(first_content) if first_content == second_content => continue,
+            Some(first_content) => first_difference(&first_content, &second_content),
+        };
+        differences.push(Difference { path, kind });
+    }
+    differences.extend(first.into_keys().map(|path| Difference {
+        path,
+        kind: DifferenceKind::OnlyInFirst,
+    }));
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(differences)
+}
+
+/// Reads all files in `dir`, keyed by their path relative to `dir`.
+fn read_tree(dir: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    fn read(dir: &Path, prefix: &str, files: &mut BTreeMap<String, Vec<u8>>) -> Result<()> {
+        for entry in
+            fs::read_dir(dir).with_context(|| format!("Unable to read {}", dir.display()))?
+        {
+            let entry = entry?;
+            let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+            if entry.file_type()?.is_dir() {
+                read(&entry.path(), &format!("{name}/"), files)?;
+            } else {
+                files.insert(name, fs::read(entry.path())?);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = BTreeMap::new();
+    read(dir, "", &mut files)?;
+    Ok(files)
+}
+
+/// The maximum length of the excerpts of a differing line in the report.
+const EXCERPT_LENGTH: usize = 120;
+
+fn first_difference(first: &[u8], second: &[u8]) -> DifferenceKind {
+    let first = String::from_utf8_lossy(first);
+    let second = String::from_utf8_lossy(second);
+    let mut first_lines = first.lines();
+    let mut second_lines = second.lines();
+    let mut line = 1;
+    loop {
+        match (first_lines.next(), second_lines.next()) {
+            (Some(a), Some(b)) if a == b => line += 1,
+            (None, None) => {
+                return DifferenceKind::Content {
+                    line,
+                    first: "<line endings differ>".into(),
+                    second: "<line endings differ>".into(),
+                }
+            }
+            (a, b) => {
+                return DifferenceKind::Content {
+                    line,
+                    first: excerpt(a.unwrap_or("<end of file>")),
+                    second: excerpt(b.unwrap_or("<end of file>")),
+                }
+            }
+        }
+    }
+}
+
+fn excerpt(line: &str) -> String {
+    match line.char_indices().nth(EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_difference_reports_line() {
+        assert_eq!(
+            first_difference(b"a\nb\nc", b"a\nx\nc"),
+            DifferenceKind::Content {
+                line: 2,
+                first: "b".into(),
+                second: "x".into(),
+            }
+        );
+        assert_eq!(
+            first_difference(b"a\n", b"a\nb\n"),
+            DifferenceKind::Content {
+                line: 2,
+                first: "<end of file>".into(),
+                second: "b".into(),
+            }
+        );
+    }
+}
@@ -5,7 +5,10 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, Value, Vc};
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack::{
-    ecmascript::{EcmascriptInputTransform, TreeShakingMode},
+    ecmascript::{
+        library::{peer_dependency_externals, LibraryFormat},
+        EcmascriptInputTransform, TreeShakingMode,
+    },
     module_options::{
         EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext, ModuleRule,
         ModuleRuleEffect, RuleCondition, TypescriptTransformOptions,
@@ -19,7 +22,10 @@ use turbopack_core::{
     condition::ContextCondition,
     context::AssetContext,
     environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
-    resolve::options::{ImportMap, ImportMapping},
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        persistent_cache::OptionPersistentResolveCache,
+    },
 };
 use turbopack_ecmascript_plugins::transform::{
     emotion::{EmotionTransformConfig, EmotionTransformer},
@@ -29,6 +35,7 @@ use turbopack_ecmascript_plugins::transform::{
 use turbopack_node::{
     execution_context::ExecutionContext, transforms::postcss::PostCssTransformOptions,
 };
+use turbopack_plugin_rpc::{RpcPlugin, RpcPlugins};
 use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
 
 #[turbo_tasks::value(shared)]
@@ -78,17 +85,48 @@ pub async fn get_client_import_map(
     Ok(import_map.cell())
 }
 
+/// The plugin processes given as `command arg...`, started in the project
+/// directory.
+#[turbo_tasks::function]
+pub async fn get_rpc_plugins(
+    project_path: ResolvedVc<FileSystemPath>,
+    plugins: Vec<RcStr>,
+) -> Result<Vc<RpcPlugins>> {
+    let mut rpc_plugins = Vec::with_capacity(plugins.len());
+    for plugin in plugins {
+        let mut parts = plugin.split_whitespace().map(RcStr::from);
+        let Some(command) = parts.next() else {
+            continue;
+        };
+        rpc_plugins.push(
+            RpcPlugin::new(project_path, command, parts.collect())
+                .to_resolved()
+                .await?,
+        );
+    }
+    Ok(Vc::cell(rpc_plugins))
+}
+
 #[turbo_tasks::function]
 pub async fn get_client_resolve_options_context(
     project_path: Vc<FileSystemPath>,
+    plugins: Vc<RpcPlugins>,
 ) -> Result<Vc<ResolveOptionsContext>> {
     let next_client_import_map = get_client_import_map(project_path).to_resolved().await?;
+    let mut before_resolve_plugins = Vec::new();
+    for plugin in plugins.await?.iter() {
+        before_resolve_plugins.push(ResolvedVc::upcast(
+            plugin.resolve_plugin().to_resolved().await?,
+        ));
+    }
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
+        enable_pnp: true,
         custom_conditions: vec!["development".into()],
         import_map: Some(next_client_import_map),
         browser: true,
         module: true,
+        before_resolve_plugins,
         ..Default::default()
     };
     Ok(ResolveOptionsContext {
@@ -109,15 +147,17 @@ async fn get_client_module_options_context(
     execution_context: ResolvedVc<ExecutionContext>,
     env: ResolvedVc<Environment>,
     node_env: Vc<NodeEnv>,
+    plugins: Vc<RpcPlugins>,
 ) -> Result<Vc<ModuleOptionsContext>> {
     let module_options_context = ModuleOptionsContext {
         preset_env_versions: Some(env),
         execution_context: Some(execution_context),
         tree_shaking_mode: Some(TreeShakingMode::ReexportsOnly),
+        rpc_plugins: plugins.await?.clone_value(),
         ..Default::default()
     };
 
-    let resolve_options_context = get_client_resolve_options_context(project_path);
+    let resolve_options_context = get_client_resolve_options_context(project_path, plugins);
 
     let enable_react_refresh = matches!(*node_env.await?, NodeEnv::Development)
         && assert_can_resolve_react_refresh(project_path, resolve_options_context)
@@ -181,19 +221,69 @@ async fn get_client_module_options_context(
     Ok(module_options_context)
 }
 
+/// The asset context for client code. When a `resolve_cache` is passed, the
+/// results of requests from within `node_modules` are kept in it.
 #[turbo_tasks::function]
-pub fn get_client_asset_context(
+pub async fn get_client_asset_context(
+    project_path: Vc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    compile_time_info: Vc<CompileTimeInfo>,
+    node_env: Vc<NodeEnv>,
+    plugins: Vc<RpcPlugins>,
+    resolve_cache: Vc<OptionPersistentResolveCache>,
+) -> Result<Vc<Box<dyn AssetContext>>> {
+    let mut resolve_options_context = get_client_resolve_options_context(project_path, plugins);
+    if let Some(resolve_cache) = *resolve_cache.await? {
+        resolve_options_context =
+            resolve_options_context.with_persistent_resolve_cache(*resolve_cache);
+    }
+    Ok(asset_context(
+        project_path,
+        execution_context,
+        compile_time_info,
+        node_env,
+        plugins,
+        resolve_options_context,
+    ))
+}
+
+/// Like [get_client_asset_context], but keeps the peer dependencies of the
+/// project external, as they are provided by the consumer of the library.
+#[turbo_tasks::function]
+pub fn get_library_asset_context(
+    project_path: Vc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    compile_time_info: Vc<CompileTimeInfo>,
+    node_env: Vc<NodeEnv>,
+    plugins: Vc<RpcPlugins>,
+    format: LibraryFormat,
+) -> Vc<Box<dyn AssetContext>> {
+    asset_context(
+        project_path,
+        execution_context,
+        compile_time_info,
+        node_env,
+        plugins,
+        get_client_resolve_options_context(project_path, plugins).with_extended_import_map(
+            peer_dependency_externals(project_path.join("package.json".into()), format),
+        ),
+    )
+}
+
+fn asset_context(
     project_path: Vc<FileSystemPath>,
     execution_context: Vc<ExecutionContext>,
     compile_time_info: Vc<CompileTimeInfo>,
     node_env: Vc<NodeEnv>,
+    plugins: Vc<RpcPlugins>,
+    resolve_options_context: Vc<ResolveOptionsContext>,
 ) -> Vc<Box<dyn AssetContext>> {
-    let resolve_options_context = get_client_resolve_options_context(project_path);
     let module_options_context = get_client_module_options_context(
         project_path,
         execution_context,
         compile_time_info.environment(),
         node_env,
+        plugins,
     );
 
     let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
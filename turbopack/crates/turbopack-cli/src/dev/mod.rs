@@ -13,23 +13,26 @@ use anyhow::{Context, Result};
 use owo_colors::OwoColorize;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
+    backend::Backend,
     util::{FormatBytes, FormatDuration},
-    ResolvedVc, TransientInstance, TurboTasks, UpdateInfo, Value, Vc,
+    FxIndexMap, ProgressEvent, ResolvedVc, TransientInstance, TurboTasks, UpdateInfo, Value, Vc,
 };
-use turbo_tasks_fs::FileSystem;
+use turbo_tasks_fs::{glob::Glob, FileSystem};
 use turbo_tasks_malloc::TurboMalloc;
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::evaluate_context::node_build_environment;
 use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
 use turbopack_core::{
     issue::{IssueReporter, IssueSeverity},
-    resolve::parse::Request,
+    resolve::{parse::Request, persistent_cache::PersistentResolveCache},
     server_fs::ServerFileSystem,
 };
 use turbopack_dev_server::{
-    introspect::IntrospectionSource,
+    events::{BuildEvent, BuildEvents, BuildEventsContentSource, BUILD_EVENTS_PATH},
+    introspect::{graph::IntrospectionGraphSource, IntrospectionSource},
     source::{
-        combined::CombinedContentSource, router::PrefixedRouterContentSource,
+        combined::CombinedContentSource, original_source::OriginalSourceContentSource,
+        resolve_trace::ResolveTraceContentSource, router::PrefixedRouterContentSource,
         static_assets::StaticAssetsContentSource, ContentSource,
     },
     DevServer, DevServerBuilder,
@@ -42,9 +45,10 @@ use turbopack_nodejs::NodeJsChunkingContext;
 use self::web_entry_source::create_web_entry_source;
 use crate::{
     arguments::DevArguments,
-    contexts::NodeEnv,
+    contexts::{get_client_asset_context, get_client_compile_time_info, get_rpc_plugins, NodeEnv},
     util::{
-        normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest, NormalizedDirs,
+        cache_dir, normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest,
+        NormalizedDirs,
     },
 };
 
@@ -64,6 +68,9 @@ pub struct TurbopackDevServerBuilder {
     show_all: bool,
     log_detail: bool,
     allow_retry: bool,
+    plugins: Vec<RcStr>,
+    web_manifest: Option<RcStr>,
+    service_worker: Option<RcStr>,
 }
 
 impl TurbopackDevServerBuilder {
@@ -88,6 +95,9 @@ impl TurbopackDevServerBuilder {
             show_all: false,
             log_detail: false,
             allow_retry: false,
+            plugins: vec![],
+            web_manifest: None,
+            service_worker: None,
         }
     }
 
@@ -96,6 +106,27 @@ impl TurbopackDevServerBuilder {
         self
     }
 
+    /// Starts a plugin process with the command line `plugin`, see
+    /// [CommonArguments::plugins][crate::arguments::CommonArguments::plugins].
+    pub fn plugin(mut self, plugin: RcStr) -> TurbopackDevServerBuilder {
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// Serves the web app manifest `web_manifest`, relative to the project
+    /// directory, at `/manifest.webmanifest`.
+    pub fn web_manifest(mut self, web_manifest: RcStr) -> TurbopackDevServerBuilder {
+        self.web_manifest = Some(web_manifest);
+        self
+    }
+
+    /// Serves the service worker `service_worker`, relative to the project
+    /// directory, at `/sw.js`.
+    pub fn service_worker(mut self, service_worker: RcStr) -> TurbopackDevServerBuilder {
+        self.service_worker = Some(service_worker);
+        self
+    }
+
     pub fn eager_compile(mut self, eager_compile: bool) -> TurbopackDevServerBuilder {
         self.eager_compile = eager_compile;
         self
@@ -197,6 +228,9 @@ impl TurbopackDevServerBuilder {
         let show_all = self.show_all;
         let log_detail: bool = self.log_detail;
         let browserslist_query: RcStr = self.browserslist_query;
+        let plugins = self.plugins;
+        let web_manifest = self.web_manifest;
+        let service_worker = self.service_worker;
         let log_args = TransientInstance::new(LogOptions {
             current_dir: current_dir().unwrap(),
             project_dir: PathBuf::from(project_dir.clone()),
@@ -205,6 +239,7 @@ impl TurbopackDevServerBuilder {
             log_level: self.log_level,
         });
         let entry_requests = TransientInstance::new(self.entry_requests);
+        let build_events = TransientInstance::new(server.build_events.clone());
         let tasks = turbo_tasks.clone();
         let issue_provider = self.issue_reporter.unwrap_or_else(|| {
             // Initialize a ConsoleUi reporter if no custom reporter was provided
@@ -218,6 +253,10 @@ impl TurbopackDevServerBuilder {
                 entry_requests.clone(),
                 eager_compile,
                 browserslist_query.clone(),
+                plugins.clone(),
+                web_manifest.clone(),
+                service_worker.clone(),
+                build_events.clone(),
             )
         };
 
@@ -233,6 +272,10 @@ async fn source(
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     eager_compile: bool,
     browserslist_query: RcStr,
+    plugins: Vec<RcStr>,
+    web_manifest: Option<RcStr>,
+    service_worker: Option<RcStr>,
+    build_events: TransientInstance<BuildEvents>,
 ) -> Result<Vc<Box<dyn ContentSource>>> {
     let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
     let project_relative: RcStr = project_relative
@@ -241,7 +284,7 @@ async fn source(
         .replace(MAIN_SEPARATOR, "/")
         .into();
 
-    let output_fs = output_fs(project_dir);
+    let output_fs = output_fs(project_dir.clone());
     let fs = project_fs(root_dir);
     let project_path = fs.root().join(project_relative).to_resolved().await?;
 
@@ -267,7 +310,7 @@ async fn source(
         node_build_environment().to_resolved().await?,
         RuntimeType::Development,
     )
-    .build();
+    .build()?;
 
     let execution_context =
         ExecutionContext::new(*project_path, Vc::upcast(build_chunking_context), env);
@@ -292,6 +335,22 @@ async fn source(
         })
         .collect();
 
+    let plugins = get_rpc_plugins(*project_path, plugins);
+    let resolve_cache = Vc::cell(Some(
+        client_resolve_cache(project_dir.clone())
+            .to_resolved()
+            .await?,
+    ));
+    let client_asset_context = get_client_asset_context(
+        *project_path,
+        execution_context,
+        get_client_compile_time_info(browserslist_query.clone(), NodeEnv::Development.cell()),
+        NodeEnv::Development.cell(),
+        plugins,
+        resolve_cache,
+    )
+    .to_resolved()
+    .await?;
     let web_source = create_web_entry_source(
         *project_path,
         execution_context,
@@ -301,6 +360,10 @@ async fn source(
         eager_compile,
         NodeEnv::Development.cell(),
         browserslist_query,
+        plugins,
+        resolve_cache,
+        web_manifest,
+        service_worker,
     )
     .to_resolved()
     .await?;
@@ -309,7 +372,22 @@ async fn source(
             .to_resolved()
             .await?,
     );
-    let main_source = CombinedContentSource::new(vec![static_source, web_source])
+    // Source maps refer to files of the project file system, which are served
+    // for devtools, but only source files, so e.g. `.env` isn't exposed.
+    let original_source = ResolvedVc::upcast(
+        OriginalSourceContentSource::new(
+            Vc::cell("__turbopack_sources__/".into()),
+            vec![fs.root().to_resolved().await?],
+            Glob::new(
+                "**/*.{js,jsx,mjs,cjs,ts,tsx,mts,cts,css,scss,sass,less,json,md,mdx,html}".into(),
+            )
+            .to_resolved()
+            .await?,
+        )
+        .to_resolved()
+        .await?,
+    );
+    let main_source = CombinedContentSource::new(vec![static_source, original_source, web_source])
         .to_resolved()
         .await?;
     let introspect = ResolvedVc::upcast(
@@ -318,14 +396,44 @@ async fn source(
         }
         .resolved_cell(),
     );
+    let graph = ResolvedVc::upcast(
+        IntrospectionGraphSource {
+            roots: FxIndexMap::from_iter([("main".into(), ResolvedVc::upcast(main_source))]),
+        }
+        .resolved_cell(),
+    );
+    let resolve_trace = ResolvedVc::upcast(
+        ResolveTraceContentSource {
+            environments: FxIndexMap::from_iter([("client".into(), client_asset_context)]),
+            root: project_path,
+        }
+        .resolved_cell(),
+    );
+    let build_events = ResolvedVc::upcast(
+        BuildEventsContentSource::new(build_events)
+            .to_resolved()
+            .await?,
+    );
     let main_source = ResolvedVc::upcast(main_source);
     Ok(Vc::upcast(PrefixedRouterContentSource::new(
         Default::default(),
-        vec![("__turbopack__".into(), introspect)],
+        vec![
+            ("__turbopack__".into(), introspect),
+            ("__turbopack_graph__".into(), graph),
+            ("__turbopack_resolve_trace__".into(), resolve_trace),
+            (BUILD_EVENTS_PATH.into(), build_events),
+        ],
         *main_source,
     )))
 }
 
+/// Keeps the results of requests from within `node_modules` across restarts
+/// of the dev server. It's written after every compilation.
+#[turbo_tasks::function]
+fn client_resolve_cache(project_dir: RcStr) -> Vc<PersistentResolveCache> {
+    PersistentResolveCache::new(cache_dir(project_dir), "client".into())
+}
+
 pub fn register() {
     turbopack::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
@@ -351,6 +459,7 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
     ));
 
     let tt_clone = tt.clone();
+    let cache_project_dir = project_dir.clone();
 
     let mut server = TurbopackDevServerBuilder::new(tt, project_dir, root_dir)
         .eager_compile(args.eager_compile)
@@ -368,6 +477,18 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
         server = server.entry_request(EntryRequest::Relative(entry))
     }
 
+    for plugin in &args.common.plugins {
+        server = server.plugin(plugin.as_str().into())
+    }
+
+    if let Some(web_manifest) = &args.web_manifest {
+        server = server.web_manifest(web_manifest.as_str().into());
+    }
+
+    if let Some(service_worker) = &args.service_worker {
+        server = server.service_worker(service_worker.as_str().into());
+    }
+
     #[cfg(feature = "serializable")]
     {
         server = server.allow_retry(args.allow_retry);
@@ -402,6 +523,36 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
         }
     }
 
+    let build_events = server.build_events.clone();
+    let compiling_future = {
+        let tt = tt_clone.clone();
+        let build_events = build_events.clone();
+        async move {
+            loop {
+                tt.wait_start().await;
+                build_events.publish(BuildEvent::Compiling);
+                if !tt.wait_idle(Duration::ZERO).await {
+                    break;
+                }
+            }
+        }
+    };
+
+    let progress_future = {
+        let tt = tt_clone.clone();
+        let build_events = build_events.clone();
+        let mut progress = tt.subscribe_progress(None);
+        async move {
+            loop {
+                let ProgressEvent { task, progress, .. } = progress.recv().await;
+                build_events.publish(BuildEvent::Progress {
+                    task: tt.backend().get_task_description(task),
+                    progress,
+                });
+            }
+        }
+    };
+
     let stats_future = async move {
         if args.common.log_detail {
             println!(
@@ -427,6 +578,17 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
             }) = update_future.await
             {
                 progress_counter = 0;
+                build_events.publish(BuildEvent::Compiled { duration, tasks });
+                let project_dir = cache_project_dir.clone();
+                if let Err(err) = tt_clone
+                    .run_once(async move { client_resolve_cache(project_dir).await?.persist() })
+                    .await
+                {
+                    println!(
+                        "{} - failed to write the resolve cache: {err:?}",
+                        "warn ".yellow(),
+                    );
+                }
                 match (args.common.log_detail, !reasons.is_empty()) {
                     (true, true) => {
                         println!(
@@ -483,7 +645,10 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
         }
     };
 
-    join!(stats_future, async { server.future.await.unwrap() }).await;
+    join!(stats_future, compiling_future, progress_future, async {
+        server.future.await.unwrap()
+    })
+    .await;
 
     Ok(())
 }
@@ -1,26 +1,36 @@
 use anyhow::{anyhow, Result};
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, TryJoinIterExt, Value, Vc};
+use turbo_tasks::{fxindexset, ResolvedVc, TryJoinIterExt, Value, Vc};
 use turbo_tasks_env::ProcessEnv;
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_browser::{react_refresh::assert_can_resolve_react_refresh, BrowserChunkingContext};
+use turbopack_browser::{
+    react_refresh::assert_can_resolve_react_refresh, service_worker_environment,
+    BrowserChunkingContext, ServiceWorkerEntry, WebManifestAsset,
+};
 use turbopack_cli_utils::runtime_entry::{RuntimeEntries, RuntimeEntry};
 use turbopack_core::{
     chunk::{ChunkableModule, ChunkingContext, EvaluatableAsset},
     environment::Environment,
     file_source::FileSource,
+    output::OutputAssets,
     reference_type::{EntryReferenceSubType, ReferenceType},
     resolve::{
         origin::{PlainResolveOrigin, ResolveOriginExt},
         parse::Request,
+        persistent_cache::OptionPersistentResolveCache,
     },
 };
 use turbopack_dev_server::{
     html::DevHtmlAsset,
-    source::{asset_graph::AssetGraphContentSource, ContentSource},
+    source::{
+        asset_graph::AssetGraphContentSource, combined::CombinedContentSource, ContentSource,
+    },
 };
 use turbopack_ecmascript_runtime::RuntimeType;
-use turbopack_node::execution_context::ExecutionContext;
+use turbopack_node::{
+    execution_context::ExecutionContext, source_map::content_source::StackFrameContentSource,
+};
+use turbopack_plugin_rpc::RpcPlugins;
 
 use crate::{
     contexts::{
@@ -48,15 +58,41 @@ pub async fn get_client_chunking_context(
         )
         .hot_module_replacement()
         .use_file_source_map_uris()
-        .build(),
+        .build()?,
+    ))
+}
+
+/// The chunking context of the service worker. It has no hot module
+/// replacement, as a service worker is only updated when its script changes.
+#[turbo_tasks::function]
+pub async fn get_service_worker_chunking_context(
+    project_path: ResolvedVc<FileSystemPath>,
+    server_root: ResolvedVc<FileSystemPath>,
+    browserslist_query: RcStr,
+) -> Result<Vc<Box<dyn ChunkingContext>>> {
+    Ok(Vc::upcast(
+        BrowserChunkingContext::builder(
+            project_path,
+            server_root,
+            server_root,
+            server_root.join("/_chunks".into()).to_resolved().await?,
+            server_root.join("/_assets".into()).to_resolved().await?,
+            service_worker_environment(browserslist_query)
+                .to_resolved()
+                .await?,
+            RuntimeType::Development,
+        )
+        .use_file_source_map_uris()
+        .build()?,
     ))
 }
 
 #[turbo_tasks::function]
 pub async fn get_client_runtime_entries(
     project_path: ResolvedVc<FileSystemPath>,
+    plugins: Vc<RpcPlugins>,
 ) -> Result<Vc<RuntimeEntries>> {
-    let resolve_options_context = get_client_resolve_options_context(*project_path);
+    let resolve_options_context = get_client_resolve_options_context(*project_path, plugins);
 
     let mut runtime_entries = Vec::new();
 
@@ -99,13 +135,23 @@ pub async fn create_web_entry_source(
     eager_compile: bool,
     node_env: Vc<NodeEnv>,
     browserslist_query: RcStr,
+    plugins: Vc<RpcPlugins>,
+    resolve_cache: Vc<OptionPersistentResolveCache>,
+    web_manifest: Option<RcStr>,
+    service_worker: Option<RcStr>,
 ) -> Result<Vc<Box<dyn ContentSource>>> {
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
-    let asset_context =
-        get_client_asset_context(project_path, execution_context, compile_time_info, node_env);
+    let compile_time_info = get_client_compile_time_info(browserslist_query.clone(), node_env);
+    let asset_context = get_client_asset_context(
+        project_path,
+        execution_context,
+        compile_time_info,
+        node_env,
+        plugins,
+        resolve_cache,
+    );
     let chunking_context =
         get_client_chunking_context(project_path, server_root, compile_time_info.environment());
-    let entries = get_client_runtime_entries(project_path);
+    let entries = get_client_runtime_entries(project_path, plugins);
 
     let runtime_entries = entries.resolve_entries(asset_context);
 
@@ -156,15 +202,67 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entry_asset = Vc::upcast(DevHtmlAsset::new(
-        server_root.join("index.html".into()).to_resolved().await?,
-        entries,
-    ));
-
-    let graph = Vc::upcast(if eager_compile {
-        AssetGraphContentSource::new_eager(server_root, entry_asset)
-    } else {
-        AssetGraphContentSource::new_lazy(server_root, entry_asset)
-    });
-    Ok(graph)
+    let entry_asset = ResolvedVc::upcast(
+        DevHtmlAsset::new(
+            server_root.join("index.html".into()).to_resolved().await?,
+            entries,
+        )
+        .to_resolved()
+        .await?,
+    );
+    let mut root_assets = fxindexset! { entry_asset };
+
+    // The manifest and the service worker are served at fixed paths, so pages
+    // can link to them and the service worker controls all pages.
+    if let Some(web_manifest) = web_manifest {
+        root_assets.insert(ResolvedVc::upcast(
+            WebManifestAsset::new(
+                server_root.join("manifest.webmanifest".into()),
+                Vc::upcast(FileSource::new(project_path.join(web_manifest))),
+                asset_context,
+                chunking_context,
+            )
+            .to_resolved()
+            .await?,
+        ));
+    }
+    if let Some(service_worker) = service_worker {
+        root_assets.insert(ResolvedVc::upcast(
+            ServiceWorkerEntry::new(
+                server_root.join("sw.js".into()),
+                Vc::upcast(FileSource::new(project_path.join(service_worker))),
+                asset_context,
+                get_service_worker_chunking_context(project_path, server_root, browserslist_query),
+            )
+            .to_resolved()
+            .await?,
+        ));
+    }
+
+    // Error overlays map the frames of runtime errors in the served chunks to
+    // their original positions.
+    let stack_frame = ResolvedVc::upcast(
+        StackFrameContentSource::new(
+            Vc::cell("__turbopack_stack_frame__".into()),
+            server_root,
+            Vc::<OutputAssets>::cell(root_assets.iter().copied().collect()),
+        )
+        .to_resolved()
+        .await?,
+    );
+
+    let root_assets = Vc::cell(root_assets);
+    let graph = ResolvedVc::upcast(
+        if eager_compile {
+            AssetGraphContentSource::new_eager_multiple(server_root, root_assets)
+        } else {
+            AssetGraphContentSource::new_lazy_multiple(server_root, root_assets)
+        }
+        .to_resolved()
+        .await?,
+    );
+    Ok(Vc::upcast(CombinedContentSource::new(vec![
+        stack_frame,
+        graph,
+    ])))
 }
@@ -4,7 +4,7 @@ use anyhow::{Context, Result};
 use dunce::canonicalize;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, Vc};
-use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
 
 #[turbo_tasks::value(transparent)]
 pub struct EntryRequests(pub Vec<ResolvedVc<EntryRequest>>);
@@ -67,6 +67,15 @@ pub async fn project_fs(project_dir: RcStr) -> Result<Vc<Box<dyn FileSystem>>> {
     Ok(Vc::upcast(disk_fs))
 }
 
+/// The directory the persistent caches are stored in. Its file system isn't
+/// watched, as the caches are written outside of turbo-tasks.
+#[turbo_tasks::function]
+pub fn cache_dir(project_dir: RcStr) -> Vc<FileSystemPath> {
+    DiskFileSystem::new("cache".into(), project_dir, vec![])
+        .root()
+        .join(".turbopack/cache".into())
+}
+
 #[turbo_tasks::function]
 pub async fn output_fs(project_dir: RcStr) -> Result<Vc<Box<dyn FileSystem>>> {
     let disk_fs = DiskFileSystem::new("output".into(), project_dir, vec![]);
@@ -1,8 +1,9 @@
 use anyhow::Result;
+use serde_json::Value;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::{
-    FileContent, FileJsonContent, FileLinesContent, FileSystemPath, LinkContent, LinkType,
+    File, FileContent, FileJsonContent, FileLinesContent, FileSystemPath, LinkContent, LinkType,
 };
 
 use crate::version::{VersionedAssetContent, VersionedContent};
@@ -85,6 +86,22 @@ impl AssetContent {
         }
     }
 
+    /// The content with the keys of all JSON objects sorted, so that manifests don't depend on
+    /// the order in which their entries were collected. Content which isn't JSON is returned
+    /// unchanged.
+    #[turbo_tasks::function]
+    pub async fn with_sorted_json_keys(self: Vc<Self>) -> Result<Vc<Self>> {
+        let json = self.parse_json().await?;
+        let FileJsonContent::Content(value) = &*json else {
+            return Ok(self);
+        };
+        let mut value = value.clone();
+        sort_json_keys(&mut value);
+        Ok(AssetContent::file(
+            FileContent::Content(File::from(serde_json::to_string_pretty(&value)?)).cell(),
+        ))
+    }
+
     #[turbo_tasks::function]
     pub async fn write(self: Vc<Self>, path: Vc<FileSystemPath>) -> Result<()> {
         let this = self.await?;
@@ -105,3 +122,36 @@ impl AssetContent {
         Ok(())
     }
 }
+
+/// Sorts the keys of all objects in the value, recursively.
+fn sort_json_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut value) in entries {
+                sort_json_keys(&mut value);
+                map.insert(key, value);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_json_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::sort_json_keys;
+
+    #[test]
+    fn sorts_nested_keys() {
+        let mut value = json!({ "b": [{ "y": 1, "x": 2 }], "a": { "d": 3, "c": 4 } });
+        sort_json_keys(&mut value);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"a":{"c":4,"d":3},"b":[{"x":2,"y":1}]}"#
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::HashMap,
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use turbo_rcstr::RcStr;
+use turbo_tasks::FxIndexMap;
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+
+use crate::compile_time_report::{package_name, CompilePhase};
+
+/// Collects per-module compile times of a sample of all modules, see
+/// [enable_build_telemetry].
+pub struct BuildTelemetry {
+    sample_rate: f64,
+    modules: Mutex<HashMap<u64, SampledModule>>,
+}
+
+#[derive(Default)]
+struct SampledModule {
+    package: RcStr,
+    extension: Option<RcStr>,
+    parse: Duration,
+    transform: Duration,
+    code_generation: Duration,
+    transforms: FxIndexMap<&'static str, Duration>,
+}
+
+static BUILD_TELEMETRY: OnceLock<BuildTelemetry> = OnceLock::new();
+
+/// Enables collecting build telemetry for the rest of the process. A share of `sample_rate`
+/// (between 0 and 1) of all modules is sampled. Modules are sampled by their path, so the same
+/// modules are sampled in every build. Only the first call sets the sample rate.
+pub fn enable_build_telemetry(sample_rate: f64) -> &'static BuildTelemetry {
+    BUILD_TELEMETRY.get_or_init(|| BuildTelemetry {
+        sample_rate: sample_rate.clamp(0.0, 1.0),
+        modules: Default::default(),
+    })
+}
+
+/// Returns the collected telemetry, if collecting has been enabled with
+/// [enable_build_telemetry].
+pub fn build_telemetry() -> Option<&'static BuildTelemetry> {
+    BUILD_TELEMETRY.get()
+}
+
+/// Returns a handle to record the compile times of the module at `path`, when collecting is
+/// enabled and the module is sampled.
+pub fn sample_module(path: &str) -> Option<ModuleSample> {
+    let telemetry = build_telemetry()?;
+    let key = hash_xxh3_hash64(path);
+    if !is_sampled(key, telemetry.sample_rate) {
+        return None;
+    }
+    Some(ModuleSample {
+        telemetry,
+        key,
+        path: path.into(),
+    })
+}
+
+/// Whether the module with the hash `key` of its path is part of a sample of
+/// `sample_rate` of all modules.
+fn is_sampled(key: u64, sample_rate: f64) -> bool {
+    sample_rate >= 1.0 || (key as f64) < sample_rate * u64::MAX as f64
+}
+
+/// A sampled module, see [sample_module].
+#[derive(Clone)]
+pub struct ModuleSample {
+    telemetry: &'static BuildTelemetry,
+    key: u64,
+    path: RcStr,
+}
+
+impl ModuleSample {
+    pub fn record(&self, phase: CompilePhase, duration: Duration) {
+        self.with_module(|module| match phase {
+            CompilePhase::Parse => module.parse += duration,
+            CompilePhase::Transform => module.transform += duration,
+            CompilePhase::CodeGeneration => module.code_generation += duration,
+        });
+    }
+
+    /// Runs `transform` and records the time it took as the time of the transform with the
+    /// given name. The time is also part of [CompilePhase::Transform]. Like for the phases, only
+    /// the time the transform is actually running counts, not the time it waits for other tasks.
+    pub async fn measure_transform<T>(
+        &self,
+        name: &'static str,
+        transform: impl Future<Output = T>,
+    ) -> T {
+        let mut transform = pin!(transform);
+        let mut duration = Duration::ZERO;
+        let output = poll_fn(|cx| {
+            let start = Instant::now();
+            let poll = transform.as_mut().poll(cx);
+            duration += start.elapsed();
+            poll
+        })
+        .await;
+        self.with_module(|module| {
+            *module.transforms.entry(name).or_default() += duration;
+        });
+        output
+    }
+
+    fn with_module(&self, f: impl FnOnce(&mut SampledModule)) {
+        let mut modules = self.telemetry.modules.lock().unwrap();
+        let module = modules.entry(self.key).or_insert_with(|| SampledModule {
+            package: package_name(&self.path).into(),
+            extension: self
+                .path
+                .rsplit_once('/')
+                .map_or(&*self.path, |(_, file_name)| file_name)
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.into()),
+            ..Default::default()
+        });
+        f(module);
+    }
+}
+
+impl BuildTelemetry {
+    /// Returns a report of the modules sampled so far, slowest first.
+    pub fn report(&self) -> BuildTelemetryReport {
+        let modules = self.modules.lock().unwrap();
+        let mut transforms: FxIndexMap<&'static str, TransformTelemetry> = FxIndexMap::default();
+        let mut module_reports = modules
+            .iter()
+            .map(|(key, module)| {
+                for (&name, &duration) in &module.transforms {
+                    let transform = transforms
+                        .entry(name)
+                        .or_insert_with(|| TransformTelemetry {
+                            name: name.into(),
+                            modules: 0,
+                            total_ms: 0.0,
+                        });
+                    transform.modules += 1;
+                    transform.total_ms += millis(duration);
+                }
+                ModuleTelemetry {
+                    id: encode_hex(*key).into(),
+                    package: module.package.clone(),
+                    extension: module.extension.clone(),
+                    parse_ms: millis(module.parse),
+                    transform_ms: millis(module.transform),
+                    code_generation_ms: millis(module.code_generation),
+                    transforms_ms: module
+                        .transforms
+                        .iter()
+                        .map(|(&name, &duration)| (name.into(), millis(duration)))
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        module_reports.sort_by(|a, b| b.total_ms().total_cmp(&a.total_ms()));
+        let mut transforms = transforms.into_values().collect::<Vec<_>>();
+        transforms.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        BuildTelemetryReport {
+            sample_rate: self.sample_rate,
+            modules: module_reports,
+            transforms,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.modules.lock().unwrap().clear();
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The compile times of the sampled modules. Modules are anonymized: they're only identified by
+/// a hash of their path, the npm package they belong to and their extension.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildTelemetryReport {
+    pub sample_rate: f64,
+    /// Slowest first.
+    pub modules: Vec<ModuleTelemetry>,
+    /// The time of every transform across all sampled modules, slowest first.
+    pub transforms: Vec<TransformTelemetry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleTelemetry {
+    /// A hash of the path of the module.
+    pub id: RcStr,
+    /// The npm package of the module, see [package_name].
+    pub package: RcStr,
+    pub extension: Option<RcStr>,
+    pub parse_ms: f64,
+    pub transform_ms: f64,
+    pub code_generation_ms: f64,
+    /// The time of every transform of the module, which is part of `transform_ms`.
+    pub transforms_ms: FxIndexMap<RcStr, f64>,
+}
+
+impl ModuleTelemetry {
+    pub fn total_ms(&self) -> f64 {
+        self.parse_ms + self.transform_ms + self.code_generation_ms
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformTelemetry {
+    pub name: RcStr,
+    /// The number of sampled modules the transform ran on.
+    pub modules: usize,
+    pub total_ms: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_a_share_of_all_modules() {
+        let keys = (0..10_000)
+            .map(|i| hash_xxh3_hash64(format!("src/module{i}.js")))
+            .collect::<Vec<_>>();
+        let sampled = |sample_rate: f64| keys.iter().filter(|&&key| is_sampled(key, sample_rate));
+
+        assert_eq!(sampled(0.0).count(), 0);
+        assert_eq!(sampled(1.0).count(), keys.len());
+        assert!(is_sampled(u64::MAX, 1.0));
+        let count = sampled(0.1).count();
+        assert!((800..1200).contains(&count), "{count}");
+        // A larger sample contains all modules of a smaller one, so the same
+        // modules are sampled in every build.
+        assert!(sampled(0.01).all(|&key| is_sampled(key, 0.1)));
+    }
+}
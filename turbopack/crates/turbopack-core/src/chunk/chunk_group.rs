@@ -3,13 +3,17 @@ use std::collections::HashSet;
 use anyhow::Result;
 use auto_hash_map::AutoSet;
 use turbo_tasks::{
-    FxIndexMap, FxIndexSet, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Value, Vc,
+    FxIndexMap, FxIndexSet, ResolvedVc, TryFlatJoinIterExt, TryJoinIterExt, Value, ValueToString,
+    Vc,
 };
 
 use super::{
-    availability_info::AvailabilityInfo, available_chunk_items::AvailableChunkItemInfo,
-    chunk_content, chunking::make_chunks, AsyncModuleInfo, Chunk, ChunkContentResult, ChunkItem,
-    ChunkingContext,
+    availability_info::AvailabilityInfo,
+    available_chunk_items::AvailableChunkItemInfo,
+    chunk_content,
+    chunking::make_chunks,
+    invariants::{check_chunk_graph_enabled, ChunkGraphViolations},
+    AsyncModuleInfo, Chunk, ChunkContentResult, ChunkItem, ChunkableModule, ChunkingContext,
 };
 use crate::{
     module::Module, output::OutputAssets, rebase::RebasedAsset, reference::ModuleReference,
@@ -36,6 +40,16 @@ pub async fn make_chunk_group(
         available_async_modules_back_edges_inherit_async,
     } = chunk_content(chunking_context, chunk_group_entries, availability_info).await?;
 
+    if check_chunk_graph_enabled() {
+        check_chunk_group(
+            chunking_context,
+            &chunk_items,
+            &async_modules,
+            availability_info,
+        )
+        .await?;
+    }
+
     // Find all local chunk items that are self async
     let self_async_children = chunk_items
         .iter()
@@ -204,6 +218,41 @@ pub async fn make_chunk_group(
     })
 }
 
+/// Checks that the content of a chunk group is consistent with the chunk
+/// items which are already available to it, see [check_chunk_graph_enabled].
+async fn check_chunk_group(
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+    chunk_items: &FxIndexSet<Vc<Box<dyn ChunkItem>>>,
+    async_modules: &FxIndexSet<ResolvedVc<Box<dyn ChunkableModule>>>,
+    availability_info: AvailabilityInfo,
+) -> Result<()> {
+    let mut violations = ChunkGraphViolations::new("chunk group");
+    if let Some(available_chunk_items) = availability_info.available_chunk_items() {
+        for &chunk_item in chunk_items {
+            if available_chunk_items
+                .get(chunk_item.to_resolved().await?)
+                .await?
+                .is_some()
+            {
+                violations.push(format!(
+                    "{} is already available to the chunk group, but is included again",
+                    chunk_item.asset_ident().to_string().await?
+                ));
+            }
+        }
+    }
+    for module in async_modules {
+        let chunk_item = module.as_chunk_item(chunking_context).resolve().await?;
+        if chunk_items.contains(&chunk_item) {
+            violations.push(format!(
+                "{} is part of the chunk group, but is also loaded asynchronously from it",
+                module.ident().to_string().await?
+            ));
+        }
+    }
+    violations.emit(chunking_context.output_root()).await
+}
+
 async fn references_to_output_assets(
     references: FxIndexSet<Vc<Box<dyn ModuleReference>>>,
 ) -> Result<Vc<OutputAssets>> {
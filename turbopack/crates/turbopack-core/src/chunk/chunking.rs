@@ -11,6 +11,7 @@ use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ReadRef, TryJoinIterExt, ValueToString, Vc};
 
 use super::{
+    invariants::{check_chunk_graph_enabled, ChunkGraphViolations},
     AsyncModuleInfo, Chunk, ChunkItem, ChunkItemsWithAsyncModuleInfo, ChunkType, ChunkingContext,
     Chunks,
 };
@@ -49,8 +50,8 @@ pub async fn make_chunks(
     key_prefix: RcStr,
     mut referenced_output_assets: Vc<OutputAssets>,
 ) -> Result<Vc<Chunks>> {
-    let chunk_items = chunk_items
-        .await?
+    let input_chunk_items = chunk_items.await?;
+    let chunk_items = input_chunk_items
         .iter()
         .map(|&(chunk_item, async_info)| async move {
             let chunk_item_info = chunk_item_info(chunking_context, chunk_item, async_info).await?;
@@ -65,6 +66,8 @@ pub async fn make_chunks(
             .push((chunk_item, async_info, chunk_item_info));
     }
 
+    let check = check_chunk_graph_enabled();
+    let mut assigned_chunk_items = Vec::new();
     let mut chunks = Vec::new();
     for (ty, chunk_items) in map {
         let ty_name = ty.to_string().await?;
@@ -88,6 +91,7 @@ pub async fn make_chunks(
             chunks: &mut chunks,
             referenced_output_assets: &mut referenced_output_assets,
             empty_referenced_output_assets: OutputAssets::empty().resolve().await?,
+            assigned_chunk_items: check.then_some(&mut assigned_chunk_items),
         };
 
         if !*ty.must_keep_item_order().await? {
@@ -107,6 +111,17 @@ pub async fn make_chunks(
         }
     }
 
+    if check {
+        let mut violations = ChunkGraphViolations::new(format!("chunks \"{key_prefix}\""));
+        violations
+            .check_assigned(
+                input_chunk_items.iter().map(|&(chunk_item, _)| chunk_item),
+                assigned_chunk_items,
+            )
+            .await?;
+        violations.emit(chunking_context.output_root()).await?;
+    }
+
     // Resolve all chunks before returning
     let resolved_chunks = chunks
         .into_iter()
@@ -130,6 +145,9 @@ struct SplitContext<'a> {
     chunks: &'a mut Vec<Vc<Box<dyn Chunk>>>,
     referenced_output_assets: &'a mut Vc<OutputAssets>,
     empty_referenced_output_assets: Vc<OutputAssets>,
+    /// Collects the chunk items of all created chunks when the chunk graph is
+    /// checked.
+    assigned_chunk_items: Option<&'a mut Vec<Vc<Box<dyn ChunkItem>>>>,
 }
 
 /// Handle chunk items based on their total size. If the total size is too
@@ -162,6 +180,9 @@ async fn make_chunk(
     key: &mut String,
     split_context: &mut SplitContext<'_>,
 ) -> Result<()> {
+    if let Some(assigned_chunk_items) = &mut split_context.assigned_chunk_items {
+        assigned_chunk_items.extend(chunk_items.iter().map(|&(chunk_item, ..)| chunk_item));
+    }
     split_context.chunks.push(
         split_context.ty.chunk(
             split_context.chunking_context,
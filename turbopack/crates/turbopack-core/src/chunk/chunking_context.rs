@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
 use turbo_tasks::{trace::TraceRawVcs, ResolvedVc, TaskInput, Upcast, Value, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbo_tasks_hash::DeterministicHash;
+use turbo_tasks_hash::{DeterministicHash, HashAlgorithm};
 
 use super::{availability_info::AvailabilityInfo, ChunkableModule, EvaluatableAssets};
 use crate::{
@@ -75,6 +75,11 @@ pub trait ChunkingContext {
     /// the static asset based on its `ident`.
     fn asset_url(self: Vc<Self>, ident: Vc<AssetIdent>) -> Result<Vc<RcStr>>;
 
+    /// The hash function for the `content_hash` passed to [ChunkingContext::asset_path].
+    fn content_hash_algorithm(self: Vc<Self>) -> Vc<HashAlgorithm> {
+        Vc::cell(HashAlgorithm::default())
+    }
+
     fn asset_path(
         self: Vc<Self>,
         content_hash: RcStr,
@@ -0,0 +1,190 @@
+use std::hash::Hash;
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{feature_flags::FeatureFlag, FxIndexMap, ResolvedVc, ValueToString, Vc};
+use turbo_tasks_fs::FileSystemPath;
+
+use super::ChunkItem;
+use crate::issue::{
+    Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+    StyledString,
+};
+
+/// Whether the chunk graph is validated after each chunking run. Enabled by
+/// setting `TURBOPACK_CHECK_CHUNK_GRAPH=1`.
+///
+/// The checks are slow and only meant for finding bugs in chunking.
+pub fn check_chunk_graph_enabled() -> bool {
+    static CHECK_CHUNK_GRAPH: FeatureFlag<bool> =
+        FeatureFlag::new("check-chunk-graph", "TURBOPACK_CHECK_CHUNK_GRAPH", false);
+    CHECK_CHUNK_GRAPH.get()
+}
+
+/// Collects the violations found by a check and reports them as a single
+/// issue.
+pub(super) struct ChunkGraphViolations {
+    /// Describes the checked chunking run, e.g. the key prefix of its chunks.
+    context: String,
+    violations: Vec<StyledString>,
+}
+
+impl ChunkGraphViolations {
+    pub fn new(context: impl Into<String>) -> Self {
+        ChunkGraphViolations {
+            context: context.into(),
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, violation: String) {
+        self.violations.push(StyledString::Text(violation.into()));
+    }
+
+    /// Checks that every item is assigned to exactly one chunk.
+    pub async fn check_assigned(
+        &mut self,
+        chunk_items: impl IntoIterator<Item = Vc<Box<dyn ChunkItem>>>,
+        assigned: impl IntoIterator<Item = Vc<Box<dyn ChunkItem>>>,
+    ) -> Result<()> {
+        for (chunk_item, misassignment) in misassigned(chunk_items, assigned) {
+            let ident = chunk_item.asset_ident().to_string().await?;
+            self.push(match misassignment {
+                Misassignment::Duplicated(count) => {
+                    format!("{ident} is assigned to {count} chunks")
+                }
+                Misassignment::Unassigned => format!("{ident} is not assigned to any chunk"),
+                Misassignment::Unexpected => {
+                    format!("{ident} is assigned to a chunk but wasn't passed to chunking")
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Emits an issue at `path` when violations were found.
+    pub async fn emit(self, path: Vc<FileSystemPath>) -> Result<()> {
+        if self.violations.is_empty() {
+            return Ok(());
+        }
+        ChunkGraphInvariantIssue {
+            path: path.to_resolved().await?,
+            context: self.context.into(),
+            violations: StyledString::Stack(self.violations).resolved_cell(),
+        }
+        .cell()
+        .emit();
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Misassignment {
+    /// Assigned to this many chunks.
+    Duplicated(usize),
+    Unassigned,
+    /// Assigned to a chunk, but not one of the chunked items.
+    Unexpected,
+}
+
+/// The items that aren't assigned to exactly one chunk, in the order of
+/// `items`, followed by the unexpected ones.
+fn misassigned<T: Hash + Eq>(
+    items: impl IntoIterator<Item = T>,
+    assigned: impl IntoIterator<Item = T>,
+) -> Vec<(T, Misassignment)> {
+    let mut counts = FxIndexMap::default();
+    for item in assigned {
+        *counts.entry(item).or_insert(0usize) += 1;
+    }
+    let mut misassigned = Vec::new();
+    for item in items {
+        match counts.swap_remove(&item) {
+            Some(1) => {}
+            Some(count) => misassigned.push((item, Misassignment::Duplicated(count))),
+            None => misassigned.push((item, Misassignment::Unassigned)),
+        }
+    }
+    misassigned.extend(
+        counts
+            .into_keys()
+            .map(|item| (item, Misassignment::Unexpected)),
+    );
+    misassigned
+}
+
+/// A violation of an invariant of the chunk graph, which is a bug in chunking.
+#[turbo_tasks::value(shared)]
+struct ChunkGraphInvariantIssue {
+    path: ResolvedVc<FileSystemPath>,
+    context: RcStr,
+    violations: ResolvedVc<StyledString>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for ChunkGraphInvariantIssue {
+    #[turbo_tasks::function]
+    fn code(&self) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::CHUNK_GRAPH_INVARIANT))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Bug.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(format!("Chunk graph invariants violated in {}", self.context).into())
+            .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Other("chunking".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(*self.violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{misassigned, Misassignment};
+
+    #[test]
+    fn items_assigned_once_are_valid() {
+        assert_eq!(misassigned(["a", "b"], ["b", "a"]), vec![]);
+        assert_eq!(misassigned::<&str>([], []), vec![]);
+    }
+
+    #[test]
+    fn finds_duplicated_items() {
+        assert_eq!(
+            misassigned(["a", "b"], ["a", "b", "b", "a", "b"]),
+            vec![
+                ("a", Misassignment::Duplicated(2)),
+                ("b", Misassignment::Duplicated(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_unassigned_and_unexpected_items() {
+        assert_eq!(
+            misassigned(["a", "b", "c"], ["b", "d"]),
+            vec![
+                ("a", Misassignment::Unassigned),
+                ("c", Misassignment::Unassigned),
+                ("d", Misassignment::Unexpected),
+            ]
+        );
+    }
+}
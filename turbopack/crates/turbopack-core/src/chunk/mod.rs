@@ -6,6 +6,7 @@ pub(crate) mod chunking_context;
 pub(crate) mod containment_tree;
 pub(crate) mod data;
 pub(crate) mod evaluate;
+pub mod invariants;
 pub mod module_id_strategies;
 pub mod optimize;
 
@@ -25,8 +26,8 @@ use turbo_tasks::{
     debug::ValueDebugFormat,
     graph::{AdjacencyMap, GraphTraversal, GraphTraversalResult, Visit, VisitControlFlow},
     trace::TraceRawVcs,
-    FxIndexMap, FxIndexSet, ReadRef, ResolvedVc, TaskInput, TryFlatJoinIterExt, TryJoinIterExt,
-    Upcast, ValueToString, Vc,
+    FxIndexMap, FxIndexSet, ParallelismLimit, ReadRef, ResolvedVc, TaskInput, TryFlatJoinIterExt,
+    TryJoinIterExt, Upcast, ValueToString, Vc,
 };
 use turbo_tasks_fs::FileSystemPath;
 use turbo_tasks_hash::DeterministicHash;
@@ -218,12 +219,22 @@ pub struct ChunkContentResult {
     pub available_async_modules_back_edges_inherit_async: AsyncInfo,
 }
 
+/// Limits the number of chunk groups whose module graph is walked at the same time, which
+/// parses every module of the chunk group.
+pub static CHUNKING_PARALLELISM: ParallelismLimit = ParallelismLimit::new("chunking");
+
 pub async fn chunk_content(
     chunking_context: Vc<Box<dyn ChunkingContext>>,
     chunk_entries: impl IntoIterator<Item = Vc<Box<dyn Module>>>,
     availability_info: AvailabilityInfo,
 ) -> Result<ChunkContentResult> {
-    chunk_content_internal_parallel(chunking_context, chunk_entries, availability_info).await
+    CHUNKING_PARALLELISM
+        .run(chunk_content_internal_parallel(
+            chunking_context,
+            chunk_entries,
+            availability_info,
+        ))
+        .await
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs, Debug)]
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{FxIndexMap, ResolvedVc, ValueToString, Vc};
-use turbo_tasks_hash::hash_xxh3_hash64;
+use turbo_tasks_hash::HashAlgorithm;
 
 use super::ModuleId;
 use crate::ident::AssetIdent;
@@ -35,11 +35,21 @@ impl ModuleIdStrategy for DevModuleIdStrategy {
 #[turbo_tasks::value]
 pub struct GlobalModuleIdStrategy {
     module_id_map: FxIndexMap<RcStr, ModuleId>,
+    /// Hashes the idents of modules which aren't in `module_id_map`. The hashes are encoded as
+    /// decimal numbers, as those are shorter than hex for the common 64-bit hashes.
+    hash_algorithm: HashAlgorithm,
 }
 
 impl GlobalModuleIdStrategy {
-    pub async fn new(module_id_map: FxIndexMap<RcStr, ModuleId>) -> Result<Vc<Self>> {
-        Ok(GlobalModuleIdStrategy { module_id_map }.cell())
+    pub async fn new(
+        module_id_map: FxIndexMap<RcStr, ModuleId>,
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<Vc<Self>> {
+        Ok(GlobalModuleIdStrategy {
+            module_id_map,
+            hash_algorithm,
+        }
+        .cell())
     }
 }
 
@@ -52,8 +62,9 @@ impl ModuleIdStrategy for GlobalModuleIdStrategy {
             return Ok(module_id.clone().cell());
         }
         Ok(ModuleId::String(
-            hash_xxh3_hash64(ident.to_string().await?)
-                .to_string()
+            self.hash_algorithm
+                .hash(ident_string.as_str())
+                .to_decimal()
                 .into(),
         )
         .cell())
@@ -1,18 +1,19 @@
 use std::{
     cmp::min,
     io::{BufRead, Result as IoResult, Write},
+    mem::take,
     ops,
 };
 
 use anyhow::{Context, Result};
 use indexmap::{IndexMap, IndexSet};
-use turbo_tasks::Vc;
+use turbo_tasks::{TryJoinIterExt, Vc};
 use turbo_tasks_fs::{
     rope::{Rope, RopeBuilder},
     util::uri_from_file,
     DiskFileSystem, FileSystemPath,
 };
-use turbo_tasks_hash::hash_xxh3_hash64;
+use turbo_tasks_hash::{hash_xxh3_hash64, DeterministicHash, HashAlgorithm};
 
 use crate::{
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMap, SourceMapSection},
@@ -20,6 +21,34 @@ use crate::{
     SOURCE_MAP_PREFIX,
 };
 
+/// The average number of chunk items whose code is concatenated by a single sub-task when
+/// building the code of a chunk, see [code_batches]. Batches are built in parallel and merged in
+/// order afterwards.
+pub const CODE_BATCH_SIZE: usize = 64;
+
+/// Splits the chunk items of a chunk into batches of [CODE_BATCH_SIZE] items on average. A batch
+/// ends after an item whose key hashes to a multiple of the batch size, so a batch only depends on
+/// its own items: adding or removing an item only changes its batch, and the sub-tasks of the
+/// other batches are still cached.
+pub fn code_batches<T, K: DeterministicHash>(
+    items: impl IntoIterator<Item = T>,
+    key: impl Fn(&T) -> K,
+) -> Vec<Vec<T>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    for item in items {
+        let ends_batch = hash_xxh3_hash64(key(&item)) % CODE_BATCH_SIZE as u64 == 0;
+        batch.push(item);
+        if ends_batch {
+            batches.push(take(&mut batch));
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
 /// A mapping of byte-offset in the code string to an associated source map.
 pub type Mapping = (usize, Option<Vc<Box<dyn GenerateSourceMap>>>);
 
@@ -159,12 +188,22 @@ impl GenerateSourceMap for Code {
     /// chunk items into a single map file.
     #[turbo_tasks::function]
     pub async fn generate_source_map(&self) -> Result<Vc<OptionSourceMap>> {
+        // Generating and rewriting each section's map is independent of the others, so
+        // it happens concurrently. Only the offset computation below needs to walk the
+        // code in order.
+        let encoded_maps = self
+            .mappings
+            .iter()
+            .map(|(_, map)| async move { encode_section_map(*map).await })
+            .try_join()
+            .await?;
+
         let mut pos = SourcePos::new();
         let mut last_byte_pos = 0;
 
         let mut sections = Vec::with_capacity(self.mappings.len());
         let mut read = self.code.read();
-        for (byte_pos, map) in &self.mappings {
+        for ((byte_pos, _), encoded) in self.mappings.iter().zip(encoded_maps) {
             let mut want = byte_pos - last_byte_pos;
             while want > 0 {
                 let buf = read.fill_buf()?;
@@ -178,38 +217,6 @@ impl GenerateSourceMap for Code {
             }
             last_byte_pos = *byte_pos;
 
-            let encoded = match map {
-                None => SourceMap::empty(),
-                Some(map) => match *map.generate_source_map().await? {
-                    None => SourceMap::empty(),
-                    Some(map) => {
-                        let map = &*map.await?;
-                        let map = map.to_source_map().await?;
-                        match map.as_regular_source_map() {
-                            None => SourceMap::empty(),
-                            Some(map) => {
-                                let mut map = map.into_owned();
-                                let mut ignored_ids = IndexSet::new();
-                                for (src_id, src) in map.sources().enumerate() {
-                                    if src.starts_with("turbopack://[next]")
-                                        || src.starts_with("turbopack://[turbopack]")
-                                        || src.contains("/node_modules/")
-                                    {
-                                        ignored_ids.insert(src_id);
-                                    }
-                                }
-
-                                for ignored_id in ignored_ids {
-                                    map.add_to_ignore_list(ignored_id as _);
-                                }
-
-                                SourceMap::new_decoded(sourcemap::DecodedMap::Regular(map)).cell()
-                            }
-                        }
-                    }
-                },
-            };
-
             sections.push(SourceMapSection::new(pos, encoded))
         }
 
@@ -217,14 +224,46 @@ impl GenerateSourceMap for Code {
     }
 }
 
+/// Resolves the source map of a single [Code] section, marking framework and
+/// `node_modules` sources as ignored.
+async fn encode_section_map(map: Option<Vc<Box<dyn GenerateSourceMap>>>) -> Result<Vc<SourceMap>> {
+    let Some(map) = map else {
+        return Ok(SourceMap::empty());
+    };
+    let Some(map) = *map.generate_source_map().await? else {
+        return Ok(SourceMap::empty());
+    };
+    let map = &*map.await?;
+    let map = map.to_source_map().await?;
+    let Some(map) = map.as_regular_source_map() else {
+        return Ok(SourceMap::empty());
+    };
+    let mut map = map.into_owned();
+    let mut ignored_ids = IndexSet::new();
+    for (src_id, src) in map.sources().enumerate() {
+        if src.starts_with("turbopack://[next]")
+            || src.starts_with("turbopack://[turbopack]")
+            || src.contains("/node_modules/")
+        {
+            ignored_ids.insert(src_id);
+        }
+    }
+
+    for ignored_id in ignored_ids {
+        map.add_to_ignore_list(ignored_id as _);
+    }
+
+    Ok(SourceMap::new_decoded(sourcemap::DecodedMap::Regular(map)).cell())
+}
+
 #[turbo_tasks::value_impl]
 impl Code {
-    /// Returns the hash of the source code of this Code.
+    /// Returns the hash of the source code of this Code. Digests longer than 128 bits are
+    /// truncated.
     #[turbo_tasks::function]
-    pub fn source_code_hash(&self) -> Vc<u64> {
-        let code = self;
-        let hash = hash_xxh3_hash64(code.source_code());
-        Vc::cell(hash)
+    pub async fn source_code_hash(&self, hash_algorithm: Vc<HashAlgorithm>) -> Result<Vc<u128>> {
+        let hash = hash_algorithm.await?.hash(self.source_code());
+        Ok(Vc::cell(hash.as_u128()))
     }
 }
 
@@ -273,3 +312,33 @@ pub async fn fileify_source_map(
         SourceMap::new_decoded(sourcemap::DecodedMap::Regular(transformed)).cell(),
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_only_depend_on_their_items() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let batches = code_batches(items.clone(), |&item| item);
+        assert_eq!(batches.concat(), items);
+        assert!(
+            batches.len() > 1 && batches.len() < 100,
+            "{}",
+            batches.len()
+        );
+
+        // Removing an item only changes the batch it was part of.
+        let changed = code_batches(items.iter().copied().filter(|&item| item != 500), |&item| {
+            item
+        });
+        assert_eq!(changed.len(), batches.len());
+        let different = batches
+            .iter()
+            .zip(&changed)
+            .filter(|(batch, changed)| batch != changed)
+            .collect::<Vec<_>>();
+        assert_eq!(different.len(), 1);
+        assert!(different[0].0.contains(&500));
+    }
+}
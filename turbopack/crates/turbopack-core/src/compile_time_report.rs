@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use turbo_rcstr::RcStr;
+use turbo_tasks::on_execution_completed;
+
+use crate::build_telemetry::{sample_module, ModuleSample};
+
+/// The name used for modules that are not part of an npm package.
+pub const PROJECT_PACKAGE_NAME: &str = "(project)";
+
+/// The phases of compiling a module that are attributed to its package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilePhase {
+    Parse,
+    Transform,
+    CodeGeneration,
+}
+
+/// The time spent compiling the modules of a single package.
+#[derive(Debug, Default, Clone)]
+pub struct PackageCompileTime {
+    pub modules: usize,
+    pub parse: Duration,
+    pub transform: Duration,
+    pub code_generation: Duration,
+}
+
+impl PackageCompileTime {
+    pub fn total(&self) -> Duration {
+        self.parse + self.transform + self.code_generation
+    }
+}
+
+/// Collects compile time per npm package. Only the actual execution of the
+/// parse, transform and code generation tasks is measured, so cached results
+/// don't count towards the time of a package, see [record_compile_time].
+#[derive(Default)]
+pub struct PackageCompileTimes {
+    inner: Mutex<HashMap<RcStr, PackageCompileTime>>,
+}
+
+static PACKAGE_COMPILE_TIMES: OnceLock<PackageCompileTimes> = OnceLock::new();
+
+/// Enables collecting compile time per package for the rest of the process.
+pub fn enable_compile_time_report() -> &'static PackageCompileTimes {
+    PACKAGE_COMPILE_TIMES.get_or_init(Default::default)
+}
+
+/// Returns the collected compile times, if collecting has been enabled with
+/// [enable_compile_time_report].
+pub fn compile_time_report() -> Option<&'static PackageCompileTimes> {
+    PACKAGE_COMPILE_TIMES.get()
+}
+
+/// Attributes the execution time of the current task to the package containing
+/// `path`, and to the module when it's sampled by the
+/// [build telemetry][crate::build_telemetry]. The time is the execution
+/// duration of the task, see [turbo_tasks::on_execution_completed], so waiting
+/// for other tasks doesn't count and cached results aren't counted at all.
+/// Returns the sampled module, to measure individual transforms.
+pub fn record_compile_time(path: &str, phase: CompilePhase) -> Option<ModuleSample> {
+    let times = compile_time_report().map(|times| (times, RcStr::from(package_name(path))));
+    let sample = sample_module(path);
+    if times.is_none() && sample.is_none() {
+        return None;
+    }
+    let recorded_sample = sample.clone();
+    on_execution_completed(move |duration| {
+        if let Some((times, package)) = times {
+            times.record(package, phase, duration);
+        }
+        if let Some(sample) = recorded_sample {
+            sample.record(phase, duration);
+        }
+    });
+    sample
+}
+
+/// Returns the name of the npm package containing `path`, or
+/// [PROJECT_PACKAGE_NAME] when it's not inside of `node_modules`.
+pub fn package_name(path: &str) -> &str {
+    let Some(index) = path.rfind("node_modules/") else {
+        return PROJECT_PACKAGE_NAME;
+    };
+    let rest = &path[index + "node_modules/".len()..];
+    let mut segments = rest.splitn(3, '/');
+    let end = match (segments.next(), segments.next()) {
+        (Some(scope), Some(name)) if scope.starts_with('@') => scope.len() + 1 + name.len(),
+        (Some(name), _) => name.len(),
+        (None, _) => 0,
+    };
+    if end == 0 {
+        return PROJECT_PACKAGE_NAME;
+    }
+    &rest[..end]
+}
+
+impl PackageCompileTimes {
+    pub fn record(&self, package: RcStr, phase: CompilePhase, duration: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.entry(package).or_default();
+        match phase {
+            CompilePhase::Parse => {
+                entry.modules += 1;
+                entry.parse += duration;
+            }
+            CompilePhase::Transform => entry.transform += duration,
+            CompilePhase::CodeGeneration => entry.code_generation += duration,
+        }
+    }
+
+    /// Returns a report of the times collected so far, sorted by total time.
+    pub fn report(&self) -> CompileTimeReport {
+        let mut packages = self
+            .inner
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, time)| (name.clone(), time.clone()))
+            .collect::<Vec<_>>();
+        packages.sort_by(|(a_name, a), (b_name, b)| {
+            b.total().cmp(&a.total()).then_with(|| a_name.cmp(b_name))
+        });
+        CompileTimeReport { packages }
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().clear();
+    }
+}
+
+/// Compile times per package, sorted by total time in descending order.
+pub struct CompileTimeReport {
+    pub packages: Vec<(RcStr, PackageCompileTime)>,
+}
+
+impl CompileTimeReport {
+    /// The number of packages shown by the [Display] implementation.
+    pub const DISPLAY_LIMIT: usize = 20;
+
+    pub fn total(&self) -> Duration {
+        self.packages.iter().map(|(_, time)| time.total()).sum()
+    }
+}
+
+impl Display for CompileTimeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+        writeln!(
+            f,
+            "Compile time by package ({:.2}s total):",
+            total.as_secs_f64()
+        )?;
+        writeln!(
+            f,
+            "  {:>6}  {:>9}  {:>9}  {:>9}  {:>7}  package",
+            "share", "parse", "transform", "codegen", "modules"
+        )?;
+        for (name, time) in self.packages.iter().take(Self::DISPLAY_LIMIT) {
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                time.total().as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            writeln!(
+                f,
+                "  {:>5.1}%  {:>8.0}ms  {:>8.0}ms  {:>8.0}ms  {:>7}  {}",
+                share,
+                time.parse.as_secs_f64() * 1000.0,
+                time.transform.as_secs_f64() * 1000.0,
+                time.code_generation.as_secs_f64() * 1000.0,
+                time.modules,
+                name
+            )?;
+        }
+        if self.packages.len() > Self::DISPLAY_LIMIT {
+            writeln!(
+                f,
+                "  ... and {} more packages",
+                self.packages.len() - Self::DISPLAY_LIMIT
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("src/index.js"), PROJECT_PACKAGE_NAME);
+        assert_eq!(package_name("node_modules/react/index.js"), "react");
+        assert_eq!(
+            package_name("node_modules/@mui/icons-material/esm/Add.js"),
+            "@mui/icons-material"
+        );
+        assert_eq!(
+            package_name("node_modules/.pnpm/lodash@4.17.21/node_modules/lodash/lodash.js"),
+            "lodash"
+        );
+        assert_eq!(package_name("node_modules/"), PROJECT_PACKAGE_NAME);
+    }
+}
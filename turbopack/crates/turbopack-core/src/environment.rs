@@ -37,10 +37,74 @@ pub enum ChunkLoading {
     Dom,
 }
 
+/// How the runtime reads the code of chunks. Embedders can replace the
+/// default strategy of the [ChunkLoading] with their own loader, e.g. to read
+/// chunks from a virtual file system embedded into a single binary.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Default, Hash, Clone)]
+pub enum ChunkLoaderBackend {
+    /// `<script>` and `<link>` tags in the browser, the file system in
+    /// Node.js.
+    #[default]
+    Default,
+    /// Chunks are fetched by calling the function stored in the given global
+    /// with the URL of the chunk. It has to return a promise of a
+    /// `Response`-like object. Chunks can only be loaded asynchronously.
+    Fetch { global: RcStr },
+    /// Chunks are looked up by their path relative to the output root in the
+    /// object or `Map` stored in the given global.
+    VirtualFileSystem { global: RcStr },
+}
+
+/// How the browser runtime recovers when loading a chunk fails, e.g. after a
+/// flaky request or because the app was deployed again while the page was
+/// open and the chunk no longer exists.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Hash, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkLoadRetry {
+    /// How often a failed chunk is requested again. Retries bypass the HTTP
+    /// cache, so they also recover from truncated cached responses.
+    pub retries: u32,
+    /// The delay before the first retry, which doubles with every retry.
+    pub initial_delay_ms: u32,
+    pub max_delay_ms: u32,
+    pub on_stale_deployment: StaleDeploymentAction,
+}
+
+impl Default for ChunkLoadRetry {
+    fn default() -> Self {
+        ChunkLoadRetry {
+            retries: 2,
+            initial_delay_ms: 500,
+            max_delay_ms: 4000,
+            on_stale_deployment: StaleDeploymentAction::Event,
+        }
+    }
+}
+
+/// What the browser runtime does when a chunk is gone from the server, i.e.
+/// it responds with 404 or 410.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Default, Hash, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum StaleDeploymentAction {
+    /// The import fails with a `ChunkLoadError` whose `staleDeployment` is
+    /// set, after the `turbopack:chunk-load-error` event was dispatched.
+    #[default]
+    Event,
+    /// The page is reloaded, unless a listener of the
+    /// `turbopack:chunk-load-error` event prevents it or the page was already
+    /// reloaded for a stale deployment in the last seconds.
+    Reload,
+}
+
 #[turbo_tasks::value]
 pub struct Environment {
     // members must be private to avoid leaking non-custom types
     execution: ExecutionEnvironment,
+    chunk_loader_backend: ChunkLoaderBackend,
+    chunk_load_retry: ChunkLoadRetry,
 }
 
 #[turbo_tasks::value_impl]
@@ -49,8 +113,50 @@ impl Environment {
     pub fn new(execution: Value<ExecutionEnvironment>) -> Vc<Self> {
         Self::cell(Environment {
             execution: execution.into_value(),
+            chunk_loader_backend: ChunkLoaderBackend::Default,
+            chunk_load_retry: ChunkLoadRetry::default(),
         })
     }
+
+    /// Returns a copy of this environment which loads chunks with the given
+    /// backend.
+    #[turbo_tasks::function]
+    pub async fn with_chunk_loader_backend(
+        self: Vc<Self>,
+        chunk_loader_backend: Value<ChunkLoaderBackend>,
+    ) -> Result<Vc<Self>> {
+        let this = self.await?;
+        Ok(Self::cell(Environment {
+            execution: this.execution,
+            chunk_loader_backend: chunk_loader_backend.into_value(),
+            chunk_load_retry: this.chunk_load_retry.clone(),
+        }))
+    }
+
+    #[turbo_tasks::function]
+    pub fn chunk_loader_backend(&self) -> Vc<ChunkLoaderBackend> {
+        self.chunk_loader_backend.clone().cell()
+    }
+
+    /// Returns a copy of this environment which retries loading chunks as
+    /// configured.
+    #[turbo_tasks::function]
+    pub async fn with_chunk_load_retry(
+        self: Vc<Self>,
+        chunk_load_retry: Value<ChunkLoadRetry>,
+    ) -> Result<Vc<Self>> {
+        let this = self.await?;
+        Ok(Self::cell(Environment {
+            execution: this.execution,
+            chunk_loader_backend: this.chunk_loader_backend.clone(),
+            chunk_load_retry: chunk_load_retry.into_value(),
+        }))
+    }
+
+    #[turbo_tasks::function]
+    pub fn chunk_load_retry(&self) -> Vc<ChunkLoadRetry> {
+        self.chunk_load_retry.clone().cell()
+    }
 }
 
 #[turbo_tasks::value(serialization = "auto_for_input")]
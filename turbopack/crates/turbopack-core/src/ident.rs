@@ -4,7 +4,7 @@ use anyhow::Result;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, Value, ValueToString, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64, DeterministicHash, Xxh3Hash64Hasher};
+use turbo_tasks_hash::{DeterministicHash, HashAlgorithm};
 
 use crate::resolve::ModulePart;
 
@@ -27,6 +27,9 @@ pub struct AssetIdent {
     pub layer: Option<ResolvedVc<RcStr>>,
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionAssetIdent(Option<ResolvedVc<AssetIdent>>);
+
 impl AssetIdent {
     pub fn add_modifier(&mut self, modifier: Vc<RcStr>) {
         self.modifiers.push(modifier);
@@ -188,12 +191,18 @@ impl AssetIdent {
     /// TODO(alexkirsz) This is `turbopack-browser` specific, as
     /// `turbopack-nodejs` would use a content hash instead. But for now
     /// both are using the same name generation logic.
+    ///
+    /// The hashes in the name are computed with `content_hash_algorithm` and truncated, so the
+    /// algorithm only changes which names collide, not their length.
     #[turbo_tasks::function]
     pub async fn output_name(
         &self,
         context_path: Vc<FileSystemPath>,
         expected_extension: RcStr,
+        content_hash_algorithm: Vc<HashAlgorithm>,
     ) -> Result<Vc<RcStr>> {
+        let content_hash_algorithm = *content_hash_algorithm.await?;
+
         // TODO(PACK-2140): restrict character set to A–Za–z0–9-_.~'()
         // to be compatible with all operating systems + URLs.
 
@@ -219,7 +228,7 @@ impl AssetIdent {
             _ => None,
         };
 
-        let mut hasher = Xxh3Hash64Hasher::new();
+        let mut hasher = content_hash_algorithm.hasher();
         let mut has_hash = false;
         let AssetIdent {
             path: _,
@@ -308,7 +317,7 @@ impl AssetIdent {
         }
 
         if has_hash {
-            let hash = encode_hex(hasher.finish());
+            let hash = hasher.finish_digest().to_hex();
             let truncated_hash = &hash[..6];
             write!(name, "_{}", truncated_hash)?;
         }
@@ -330,7 +339,7 @@ impl AssetIdent {
             }
         }
         if i > 0 {
-            let hash = encode_hex(hash_xxh3_hash64(name[..i].as_bytes()));
+            let hash = content_hash_algorithm.hash(name[..i].as_bytes()).to_hex();
             let truncated_hash = &hash[..5];
             name = format!("{}_{}", truncated_hash, &name[i..]);
         }
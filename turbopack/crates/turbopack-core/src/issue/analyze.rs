@@ -4,10 +4,10 @@ use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
 
 use super::{
-    Issue, IssueSeverity, IssueSource, IssueStage, OptionIssueSource, OptionStyledString,
-    StyledString,
+    Issue, IssueCode, IssueSeverity, IssueSource, IssueStage, OptionIssueCode, OptionIssueSource,
+    OptionStyledString, StyledString,
 };
-use crate::ident::AssetIdent;
+use crate::ident::{AssetIdent, OptionAssetIdent};
 
 #[turbo_tasks::value(shared)]
 pub struct AnalyzeIssue {
@@ -21,6 +21,11 @@ pub struct AnalyzeIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for AnalyzeIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::ANALYZE_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         *self.severity
@@ -51,6 +56,17 @@ impl Issue for AnalyzeIssue {
         self.source_ident.path()
     }
 
+    /// Issues about a module are reported with its ident, which has a layer,
+    /// while issues about a source only know the file.
+    #[turbo_tasks::function]
+    async fn module_ident(&self) -> Result<Vc<OptionAssetIdent>> {
+        Ok(Vc::cell(if self.source_ident.await?.layer.is_some() {
+            Some(self.source_ident.to_resolved().await?)
+        } else {
+            None
+        }))
+    }
+
     #[turbo_tasks::function]
     fn description(&self) -> Vc<OptionStyledString> {
         Vc::cell(Some(*self.message))
@@ -0,0 +1,226 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use turbo_tasks::trace::TraceRawVcs;
+use turbo_tasks_hash::DeterministicHash;
+
+/// Base URL of the documentation of issue codes. The documentation of a
+/// specific code is found at the anchor of its lowercase code, e.g.
+/// `#tp1001`.
+pub const ISSUE_CODES_DOCUMENTATION_URL: &str =
+    "https://nextjs.org/docs/messages/turbopack-issue-codes";
+
+/// A stable identifier of an issue type, displayed as e.g. `TP1001`.
+///
+/// Codes are never reused or renumbered, so users can search for them and
+/// tooling can group issues across versions. All codes are listed in
+/// [ISSUE_CODES], and documented in `errors/turbopack-issue-codes.mdx`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    TraceRawVcs,
+    Serialize,
+    Deserialize,
+    DeterministicHash,
+)]
+pub struct IssueCode(u16);
+
+/// An entry in the registry of issue codes.
+#[derive(Debug)]
+pub struct IssueCodeInfo {
+    pub code: IssueCode,
+    /// The name of the associated constant on [IssueCode].
+    pub name: &'static str,
+    /// A short summary of the issues reported with this code.
+    pub summary: &'static str,
+}
+
+macro_rules! issue_codes {
+    ($($name:ident = $code:literal: $summary:literal,)*) => {
+        impl IssueCode {
+            $(
+                #[doc = $summary]
+                pub const $name: IssueCode = IssueCode($code);
+            )*
+        }
+
+        /// The registry of all issue codes.
+        pub static ISSUE_CODES: &[IssueCodeInfo] = &[
+            $(
+                IssueCodeInfo {
+                    code: IssueCode::$name,
+                    name: stringify!($name),
+                    summary: $summary,
+                },
+            )*
+        ];
+    };
+}
+
+// 1xxx: core, resolving and infrastructure
+// 2xxx: module types and transforms
+// 3xxx: Node.js evaluation and rendering
+// 4xxx: Next.js
+issue_codes! {
+    MODULE_NOT_FOUND = 1001: "A request could not be resolved to a module.",
+    REACT_REFRESH_NOT_FOUND = 1002: "The React Refresh runtime could not be resolved.",
+    INVALID_PACKAGE_JSON = 1003: "A package.json file could not be parsed.",
+    INVALID_TSCONFIG = 1004: "A tsconfig.json file could not be parsed or is invalid.",
+    MODULE_ERROR = 1005: "A module could not be processed.",
+    ANALYZE_ERROR = 1006: "The analysis of a module failed.",
+    CODE_GENERATION_ERROR = 1007: "Code generation for a module failed.",
+    FETCH_ERROR = 1008: "Fetching a remote resource failed.",
+    UPDATE_STREAM_ERROR = 1009: "The update stream of the dev server failed.",
+    PROCESS_ENV_ERROR = 1010: "Environment variables could not be loaded.",
+    CHUNK_GRAPH_INVARIANT = 1011: "A chunking run produced an inconsistent chunk graph.",
+    TASK_TIMEOUT = 1012: "A task didn't finish within its timeout.",
+    HERMETIC_VIOLATION = 1013: "A hermetic build read a path outside of its declared inputs.",
+    MACHINE_DEPENDENT_OUTPUT = 1014: "An output file contains an absolute path of the build machine.",
+    READ_SOURCE_ERROR = 2001: "The source of an ECMAScript module could not be read.",
+    INVALID_EXPORT = 2002: "An imported export does not exist in the target module.",
+    MODULE_TYPE_MISMATCH = 2003: "The syntax of a module doesn't match its specified type.",
+    UNSUPPORTED_SERVER_ACTION = 2004: "Server Actions are not supported in this context.",
+    INVALID_SIDE_EFFECTS = 2005: "The sideEffects field of a package.json is invalid.",
+    UNSUPPORTED_SWC_PLUGIN = 2006: "SWC plugins are not supported in this build.",
+    WASM_PLUGIN_ERROR = 2007: "A WASM plugin failed or reported an issue.",
+    PLUGIN_PROCESS_ERROR = 2008: "A plugin process failed or reported an issue.",
+    NOT_REFRESH_BOUNDARY = 2009: "Fast Refresh can't update a module in place.",
+    CSS_PARSING_ERROR = 2101: "A CSS file could not be parsed.",
+    CSS_MODULE_COMPOSES = 2102: "An invalid `composes` rule was used in a CSS module.",
+    UNSUPPORTED_SASS = 2103: "Sass files are not supported in this configuration.",
+    MDX_ERROR = 2201: "An MDX file could not be compiled.",
+    IMAGE_PROCESSING_ERROR = 2301: "An image could not be processed.",
+    POSTCSS_ERROR = 3001: "The PostCSS transform failed.",
+    BUILD_DEPENDENCY_ERROR = 3002: "A build dependency of a webpack loader failed.",
+    WEBPACK_LOADER_ERROR = 3003: "A webpack loader emitted an error.",
+    WEBPACK_LOADER_LOG = 3004: "A webpack loader logged an error.",
+    EVALUATION_ERROR = 3005: "Evaluating code in Node.js failed.",
+    RENDERING_ERROR = 3006: "Rendering a page failed.",
+    OUTPUT_CONFLICT = 4001: "Multiple assets are emitted to the same output path.",
+    INVALID_SOURCE_CONFIG = 4002: "The exported config of a source file is invalid.",
+    DUPLICATE_PARALLEL_ROUTE = 4003: "Two parallel routes resolve to the same path.",
+    APP_DIRECTORY_ERROR = 4004: "The app directory structure is invalid.",
+    NEXT_FONT_ERROR = 4005: "A font from next/font could not be loaded.",
+    NEXT_FONT_NOT_FOUND = 4006: "A local font file from next/font could not be found.",
+    INVALID_SEGMENT_CONFIG = 4007: "The segment config of a route is invalid.",
+    OUTDATED_CONFIG = 4008: "The Next.js config uses outdated options.",
+    EXTERNALIZE_ERROR = 4009: "A package could not be externalized.",
+    INVALID_IMPORT = 4010: "A module can't be imported in this environment.",
+    PAGE_STATIC_INFO_ERROR = 4011: "The static info of a page is invalid.",
+    BABEL_ERROR = 4012: "Babel is configured, but could not be used.",
+    INVALID_ENV_PRAGMA = 4013: "A `// @turbopack-env` comment names an unknown environment.",
+    ROUTE_BUDGET_EXCEEDED = 4014: "A route exceeds its size budget.",
+}
+
+impl IssueCode {
+    /// Returns the registry entry of this code.
+    pub fn info(self) -> Option<&'static IssueCodeInfo> {
+        ISSUE_CODES.iter().find(|info| info.code == self)
+    }
+
+    /// Returns the URL to the documentation of this code.
+    pub fn documentation_url(self) -> String {
+        format!(
+            "{ISSUE_CODES_DOCUMENTATION_URL}#{}",
+            self.to_string().to_lowercase()
+        )
+    }
+}
+
+impl Display for IssueCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TP{:04}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = HashSet::new();
+        for info in ISSUE_CODES {
+            assert!(seen.insert(info.code), "duplicate issue code {}", info.code);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(IssueCode::MODULE_NOT_FOUND.to_string(), "TP1001");
+        assert_eq!(
+            IssueCode::MODULE_NOT_FOUND.documentation_url(),
+            format!("{ISSUE_CODES_DOCUMENTATION_URL}#tp1001")
+        );
+    }
+
+    /// Every issue type needs a code, so the documentation covers every issue.
+    #[test]
+    fn issues_have_codes() {
+        fn visit(dir: &Path, missing: &mut Vec<String>) {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    let name = path.file_name().unwrap();
+                    if name != "node_modules" && name != "target" && name != "tests" {
+                        visit(&path, missing);
+                    }
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let source = fs::read_to_string(&path).unwrap();
+                    for (start, _) in source.match_indices("impl Issue for ") {
+                        let body = &source[start..];
+                        let Some(open) = body.find('{') else {
+                            continue;
+                        };
+                        let mut depth = 0;
+                        let end = body[open..]
+                            .find(|c| {
+                                match c {
+                                    '{' => depth += 1,
+                                    '}' => depth -= 1,
+                                    _ => {}
+                                }
+                                depth == 0
+                            })
+                            .map_or(body.len(), |end| open + end);
+                        if !body[..end].contains("fn code(") {
+                            missing.push(format!("{}: {}", path.display(), body[..open].trim()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../..");
+        let mut missing = Vec::new();
+        visit(&root.join("crates"), &mut missing);
+        visit(&root.join("turbopack/crates"), &mut missing);
+        assert!(
+            missing.is_empty(),
+            "issue types without a code, add one to errors/turbopack-issue-codes.mdx and return \
+             it from `Issue::code`:\n{}",
+            missing.join("\n")
+        );
+    }
+
+    #[test]
+    fn codes_are_documented() {
+        let documentation = include_str!("../../../../../errors/turbopack-issue-codes.mdx");
+        for info in ISSUE_CODES {
+            assert!(
+                documentation.contains(&format!("\n### {}\n", info.code)),
+                "issue code {} ({}) is missing in errors/turbopack-issue-codes.mdx",
+                info.code,
+                info.name
+            );
+        }
+    }
+}
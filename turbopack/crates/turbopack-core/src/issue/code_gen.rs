@@ -1,7 +1,9 @@
 use turbo_tasks::Vc;
 use turbo_tasks_fs::FileSystemPath;
 
-use super::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
+use super::{
+    Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 #[turbo_tasks::value(shared)]
 pub struct CodeGenerationIssue {
@@ -13,6 +15,11 @@ pub struct CodeGenerationIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for CodeGenerationIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::CODE_GENERATION_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         self.severity
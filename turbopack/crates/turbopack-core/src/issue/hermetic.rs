@@ -0,0 +1,92 @@
+use turbo_rcstr::RcStr;
+use turbo_tasks::{Completion, ResolvedVc, Vc};
+use turbo_tasks_fs::{FileSystemPath, HermeticViolationReporter};
+
+use super::{
+    Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+    StyledString,
+};
+
+/// Reported when a hermetic build reads a path outside of its declared input roots. The read
+/// behaves as if the path didn't exist, so this usually comes with a module not found error.
+#[turbo_tasks::value(shared)]
+pub struct HermeticViolationIssue {
+    pub path: ResolvedVc<FileSystemPath>,
+    /// The declared input roots, for the description.
+    pub roots: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for HermeticViolationIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::HERMETIC_VIOLATION))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Load.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            "Reading a path outside of the declared inputs of the hermetic build".into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut lines = vec![StyledString::Text(
+            "The path was read as if it didn't exist. Declare it as an input root of the build, \
+             or remove the dependency on it. The declared input roots are:"
+                .into(),
+        )];
+        lines.extend(
+            self.roots
+                .iter()
+                .map(|root| StyledString::Code(root.clone())),
+        );
+        Vc::cell(Some(StyledString::Stack(lines).cell()))
+    }
+}
+
+/// Emits a [HermeticViolationIssue] for every read a hermetic
+/// [DiskFileSystem][turbo_tasks_fs::DiskFileSystem] rejects.
+#[turbo_tasks::value(shared)]
+pub struct HermeticViolationIssueReporter {
+    pub roots: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl HermeticViolationIssueReporter {
+    #[turbo_tasks::function]
+    pub fn new(roots: Vec<RcStr>) -> Vc<Self> {
+        HermeticViolationIssueReporter { roots }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl HermeticViolationReporter for HermeticViolationIssueReporter {
+    #[turbo_tasks::function]
+    fn report(&self, path: ResolvedVc<FileSystemPath>) -> Vc<Completion> {
+        HermeticViolationIssue {
+            path,
+            roots: self.roots.clone(),
+        }
+        .cell()
+        .emit();
+        Completion::new()
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, ValueToString, Vc};
+
+use crate::{
+    module::{Module, Modules},
+    reference::primary_referenced_modules,
+};
+
+/// The chain of imports from an entry module to the module an issue was
+/// reported for.
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct ImportChain {
+    /// Idents of the modules on the chain, starting with the entry module and
+    /// ending with the module the issue was reported for.
+    pub modules: Vec<RcStr>,
+    /// The layer of the module the issue was reported for, e.g. `ssr` or
+    /// `app-client`.
+    pub layer: Option<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionImportChain(Option<ResolvedVc<ImportChain>>);
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+struct ImportGraphNode {
+    ident: RcStr,
+    layer: Option<RcStr>,
+    /// The index of the module which first imported this one, [None] for
+    /// entries.
+    parent: Option<usize>,
+}
+
+/// The modules reachable from a set of entries, in breadth-first order, so
+/// following the parents of a module gives the shortest chain of imports to
+/// it. Computed once per set of entries and shared by all issues.
+#[turbo_tasks::value]
+struct ImportGraph {
+    nodes: Vec<ImportGraphNode>,
+    /// The indices of the modules created from each file, by the path of the
+    /// file.
+    by_path: FxIndexMap<RcStr, Vec<usize>>,
+}
+
+#[derive(Default)]
+struct ImportGraphBuilder {
+    indices: HashMap<ResolvedVc<Box<dyn Module>>, usize>,
+    queue: VecDeque<(ResolvedVc<Box<dyn Module>>, usize)>,
+    nodes: Vec<ImportGraphNode>,
+    by_path: FxIndexMap<RcStr, Vec<usize>>,
+}
+
+impl ImportGraphBuilder {
+    async fn visit(
+        &mut self,
+        module: ResolvedVc<Box<dyn Module>>,
+        parent: Option<usize>,
+    ) -> Result<()> {
+        let Entry::Vacant(e) = self.indices.entry(module) else {
+            return Ok(());
+        };
+        let index = self.nodes.len();
+        e.insert(index);
+        self.queue.push_back((module, index));
+        let ident = module.ident();
+        self.by_path
+            .entry(ident.path().to_string().await?.clone_value())
+            .or_default()
+            .push(index);
+        self.nodes.push(ImportGraphNode {
+            ident: ident.to_string().await?.clone_value(),
+            layer: match ident.await?.layer {
+                Some(layer) => Some(layer.await?.clone_value()),
+                None => None,
+            },
+            parent,
+        });
+        Ok(())
+    }
+}
+
+#[turbo_tasks::function]
+async fn import_graph(entries: Vc<Modules>) -> Result<Vc<ImportGraph>> {
+    let mut builder = ImportGraphBuilder::default();
+    for &entry in entries.await?.iter() {
+        builder.visit(entry, None).await?;
+    }
+    while let Some((module, index)) = builder.queue.pop_front() {
+        for &referenced in primary_referenced_modules(*module).await?.iter() {
+            builder.visit(referenced, Some(index)).await?;
+        }
+    }
+
+    Ok(ImportGraph {
+        nodes: builder.nodes,
+        by_path: builder.by_path,
+    }
+    .cell())
+}
+
+/// Finds the shortest chain of imports from one of the `entries` to the module
+/// an issue was reported for.
+///
+/// `file_path` is formatted like
+/// [PlainIssue::file_path][super::PlainIssue::file_path]. When the issue knows
+/// its module, `module_ident` is its
+/// [PlainIssue::module_ident][super::PlainIssue::module_ident] and picks the
+/// module in the right layer. Otherwise the file must only be used in one
+/// layer, as the issue could come from any of them.
+#[turbo_tasks::function]
+pub async fn find_import_chain(
+    entries: Vc<Modules>,
+    file_path: RcStr,
+    module_ident: Option<RcStr>,
+) -> Result<Vc<OptionImportChain>> {
+    let graph = import_graph(entries).await?;
+    let Some(candidates) = graph.by_path.get(&file_path) else {
+        return Ok(Vc::cell(None));
+    };
+
+    let by_ident = module_ident.as_ref().and_then(|module_ident| {
+        candidates
+            .iter()
+            .copied()
+            .find(|&index| graph.nodes[index].ident == *module_ident)
+    });
+    let found = match by_ident {
+        Some(index) => index,
+        None => {
+            let layer = &graph.nodes[candidates[0]].layer;
+            if candidates
+                .iter()
+                .any(|&index| graph.nodes[index].layer != *layer)
+            {
+                return Ok(Vc::cell(None));
+            }
+            // Candidates are in breadth-first order, so the first one is closest
+            // to an entry.
+            candidates[0]
+        }
+    };
+
+    let mut modules = Vec::new();
+    let mut current = Some(found);
+    while let Some(index) = current {
+        modules.push(graph.nodes[index].ident.clone());
+        current = graph.nodes[index].parent;
+    }
+    modules.reverse();
+    Ok(Vc::cell(Some(
+        ImportChain {
+            modules,
+            layer: graph.nodes[found].layer.clone(),
+        }
+        .resolved_cell(),
+    )))
+}
@@ -1,27 +1,34 @@
 pub mod analyze;
+pub mod code;
 pub mod code_gen;
+pub mod hermetic;
+pub mod import_chain;
 pub mod module;
 pub mod resolve;
+pub mod timeout;
 
 use std::{
-    cmp::{min, Ordering},
+    cmp::Ordering,
     fmt::{Display, Formatter},
+    sync::Arc,
 };
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use auto_hash_map::AutoSet;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
-    emit, CollectiblesSource, RawVc, ReadRef, ResolvedVc, TransientInstance, TransientValue,
-    TryJoinIterExt, Upcast, ValueToString, Vc,
+    emit, trace::TraceRawVcs, CollectiblesSource, RawVc, ReadRef, ResolvedVc, TransientInstance,
+    TransientValue, TryFlatJoinIterExt, TryJoinIterExt, Upcast, ValueToString, Vc,
 };
-use turbo_tasks_fs::{FileContent, FileLine, FileLinesContent, FileSystemPath};
+use turbo_tasks_fs::{rope::LineIndex, to_sys_path, FileContent, FileSystemPath};
 use turbo_tasks_hash::{DeterministicHash, Xxh3Hash64Hasher};
 
+pub use self::code::IssueCode;
 use crate::{
     asset::{Asset, AssetContent},
+    ident::OptionAssetIdent,
     source::Source,
     source_map::{convert_to_turbopack_source_map, GenerateSourceMap, TokenWithSource},
     source_pos::SourcePos,
@@ -133,6 +140,24 @@ pub trait Issue {
         Vc::cell(None)
     }
 
+    /// A stable code identifying the type of the issue, see [IssueCode].
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(None)
+    }
+
+    /// The name of the rule or transform that was applied when the issue
+    /// occurred, e.g. a webpack loader or `postcss`.
+    fn transform(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(None)
+    }
+
+    /// The ident of the module the issue was reported for, when the issue is
+    /// specific to one module. Unlike the [Issue::file_path], it tells apart
+    /// modules created from the same file in different layers.
+    fn module_ident(self: Vc<Self>) -> Vc<OptionAssetIdent> {
+        Vc::cell(None)
+    }
+
     /// A link to relevant documentation of the issue. Only displayed in console
     /// if the user explicitly asks for detailed messages.
     fn documentation_link(self: Vc<Self>) -> Vc<RcStr> {
@@ -150,6 +175,12 @@ pub trait Issue {
         Vc::cell(Vec::new())
     }
 
+    /// A machine-readable fix for the issue, consisting of text edits that
+    /// editors and other tooling can apply automatically.
+    fn suggested_fix(self: Vc<Self>) -> Vc<OptionSuggestedFix> {
+        Vc::cell(None)
+    }
+
     async fn into_plain(
         self: Vc<Self>,
         processing_path: Vc<OptionIssueProcessingPathItems>,
@@ -165,6 +196,12 @@ pub trait Issue {
 
         Ok(PlainIssue {
             severity: *self.severity().await?,
+            code: *self.code().await?,
+            transform: self.transform().await?.clone_value(),
+            module_ident: match *self.module_ident().await? {
+                Some(ident) => Some(ident.to_string().await?.clone_value()),
+                None => None,
+            },
             file_path: self.file_path().to_string().await?.clone_value(),
             stage: self.stage().await?.clone_value(),
             title: self.title().await?.clone_value(),
@@ -187,6 +224,10 @@ pub trait Issue {
                 })
                 .try_join()
                 .await?,
+            suggested_fix: match *self.suggested_fix().await? {
+                Some(fix) => Some(fix.into_plain().await?),
+                None => None,
+            },
             processing_path: processing_path.into_plain().await?,
         }
         .cell())
@@ -432,6 +473,11 @@ impl CapturedIssues {
 pub struct IssueSource {
     source: ResolvedVc<Box<dyn Source>>,
     range: Option<ResolvedVc<SourceRange>>,
+    /// A short note describing the span, e.g. "conflicting import here".
+    label: Option<RcStr>,
+    /// Secondary locations that are related to the issue. Each of them might
+    /// carry its own label.
+    related: Vec<ResolvedVc<IssueSource>>,
 }
 
 /// The end position is the first character after the range
@@ -451,6 +497,8 @@ impl IssueSource {
         Self::cell(IssueSource {
             source,
             range: None,
+            label: None,
+            related: Vec::new(),
         })
     }
 
@@ -463,9 +511,13 @@ impl IssueSource {
         Self::cell(IssueSource {
             source,
             range: Some(SourceRange::LineColumn(start, end).resolved_cell()),
+            label: None,
+            related: Vec::new(),
         })
     }
 
+    /// Maps the range of this source and of its related sources to the original sources, if
+    /// the sources have source maps.
     #[turbo_tasks::function]
     pub async fn resolve_source_map(
         self: Vc<Self>,
@@ -473,33 +525,50 @@ impl IssueSource {
     ) -> Result<Vc<Self>> {
         let this = self.await?;
 
+        let related = this
+            .related
+            .iter()
+            .map(|related| async move { related.resolve_source_map(origin).to_resolved().await })
+            .try_join()
+            .await?;
+
         if let Some(range) = this.range {
-            let (start, end) = match &*range.await? {
-                SourceRange::LineColumn(start, end) => (*start, *end),
+            let range = match &*range.await? {
+                SourceRange::LineColumn(start, end) => Some((*start, *end)),
 
                 SourceRange::ByteOffset(start, end) => {
-                    if let FileLinesContent::Lines(lines) = &*this.source.content().lines().await? {
-                        let start = find_line_and_column(lines.as_ref(), *start);
-                        let end = find_line_and_column(lines.as_ref(), *end);
-                        (start, end)
-                    } else {
-                        return Ok(self);
-                    }
+                    source_line_index(*this.source).await?.map(|line_index| {
+                        (
+                            find_line_and_column(&line_index, *start),
+                            find_line_and_column(&line_index, *end),
+                        )
+                    })
                 }
             };
 
             // If we have a source map, map the line/column to the original source.
-            let mapped = source_pos(this.source, origin, start, end).await?;
+            let mapped = match range {
+                Some((start, end)) => source_pos(this.source, origin, start, end).await?,
+                None => None,
+            };
 
             if let Some((source, start, end)) = mapped {
                 return Ok(Self::cell(IssueSource {
                     source,
                     range: Some(SourceRange::LineColumn(start, end).resolved_cell()),
+                    label: this.label.clone(),
+                    related,
                 }));
             }
         }
 
-        Ok(self)
+        if related == this.related {
+            return Ok(self);
+        }
+        Ok(Self::cell(IssueSource {
+            related,
+            ..(*this).clone()
+        }))
     }
 
     /// Create a [`IssueSource`] from byte offsets given by an swc ast node
@@ -526,6 +595,8 @@ impl IssueSource {
                 }
                 (true, false) => Some(SourceRange::ByteOffset(end - 1, end - 1).resolved_cell()),
             },
+            label: None,
+            related: Vec::new(),
         })
     }
 
@@ -546,16 +617,38 @@ impl IssueSource {
     ) -> Result<Vc<Self>> {
         Ok(Self::cell(IssueSource {
             source,
-            range: if let FileLinesContent::Lines(lines) = &*source.content().lines().await? {
-                let start = find_line_and_column(lines.as_ref(), start);
-                let end = find_line_and_column(lines.as_ref(), end);
+            range: if let Some(line_index) = source_line_index(*source).await? {
+                let start = find_line_and_column(&line_index, start);
+                let end = find_line_and_column(&line_index, end);
                 Some(SourceRange::LineColumn(start, end).resolved_cell())
             } else {
                 None
             },
+            label: None,
+            related: Vec::new(),
         }))
     }
 
+    /// Returns a copy of this [`IssueSource`] with the given label describing
+    /// the span, e.g. "first import here".
+    #[turbo_tasks::function]
+    pub fn with_label(&self, label: RcStr) -> Vc<Self> {
+        Self::cell(IssueSource {
+            label: Some(label),
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy of this [`IssueSource`] with an additional related
+    /// location. Label the related location with [`IssueSource::with_label`]
+    /// to explain its relation to the issue.
+    #[turbo_tasks::function]
+    pub fn with_related(&self, related: ResolvedVc<IssueSource>) -> Vc<Self> {
+        let mut this = self.clone();
+        this.related.push(related);
+        Self::cell(this)
+    }
+
     /// Returns the file path for the source file.
     #[turbo_tasks::function]
     pub fn file_path(&self) -> Vc<FileSystemPath> {
@@ -570,9 +663,9 @@ impl IssueSource {
             Some(range) => match &*range.await? {
                 SourceRange::ByteOffset(start, end) => Some((*start + 1, *end + 1)),
                 SourceRange::LineColumn(start, end) => {
-                    if let FileLinesContent::Lines(lines) = &*self.source.content().lines().await? {
-                        let start = find_offset(lines.as_ref(), *start) + 1;
-                        let end = find_offset(lines.as_ref(), *end) + 1;
+                    if let Some(line_index) = source_line_index(*self.source).await? {
+                        let start = find_offset(&line_index, *start) + 1;
+                        let end = find_offset(&line_index, *end) + 1;
                         Some((start, end))
                     } else {
                         None
@@ -650,6 +743,77 @@ pub struct OptionIssueSource(Option<Vc<IssueSource>>);
 #[turbo_tasks::value(transparent)]
 pub struct OptionStyledString(Option<Vc<StyledString>>);
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionIssueCode(Option<IssueCode>);
+
+/// A single text replacement in a file. The range is replaced with
+/// `replacement`, an empty range inserts it.
+#[derive(Clone, Debug, PartialEq, Eq, TraceRawVcs, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub file_path: ResolvedVc<FileSystemPath>,
+    pub start: SourcePos,
+    pub end: SourcePos,
+    pub replacement: RcStr,
+}
+
+/// A fix for an [Issue] that can be applied automatically.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+pub struct SuggestedFix {
+    /// A short description of the fix, e.g. "Add the missing file extension".
+    pub description: RcStr,
+    pub edits: Vec<TextEdit>,
+}
+
+#[turbo_tasks::value_impl]
+impl SuggestedFix {
+    /// Edits of files which aren't on disk are left out, as editors can't
+    /// apply them.
+    #[turbo_tasks::function]
+    pub async fn into_plain(&self) -> Result<Vc<PlainSuggestedFix>> {
+        Ok(PlainSuggestedFix {
+            description: self.description.clone(),
+            edits: self
+                .edits
+                .iter()
+                .map(|edit| async move {
+                    let Some(file_path) = to_sys_path(*edit.file_path).await? else {
+                        return Ok(None);
+                    };
+                    Ok(Some(PlainTextEdit {
+                        file_path: file_path.to_string_lossy().into(),
+                        start: edit.start,
+                        end: edit.end,
+                        replacement: edit.replacement.clone(),
+                    }))
+                })
+                .try_flat_join()
+                .await?,
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionSuggestedFix(Option<ResolvedVc<SuggestedFix>>);
+
+#[turbo_tasks::value(serialization = "none")]
+#[derive(Clone, Debug, PartialOrd, Ord, DeterministicHash, Serialize)]
+pub struct PlainSuggestedFix {
+    pub description: RcStr,
+    pub edits: Vec<PlainTextEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, DeterministicHash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlainTextEdit {
+    /// The absolute path of the file on disk.
+    pub file_path: RcStr,
+    pub start: SourcePos,
+    pub end: SourcePos,
+    pub replacement: RcStr,
+}
+
 #[turbo_tasks::value(shared, serialization = "none")]
 #[derive(Clone, Debug, PartialOrd, Ord, DeterministicHash, Serialize)]
 pub enum IssueStage {
@@ -696,7 +860,11 @@ impl Display for IssueStage {
 #[derive(Clone, Debug, PartialOrd, Ord)]
 pub struct PlainIssue {
     pub severity: IssueSeverity,
+    pub code: Option<IssueCode>,
     pub stage: IssueStage,
+    pub transform: Option<RcStr>,
+    /// See [Issue::module_ident].
+    pub module_ident: Option<RcStr>,
 
     pub title: StyledString,
     pub file_path: RcStr,
@@ -707,13 +875,16 @@ pub struct PlainIssue {
 
     pub source: Option<ReadRef<PlainIssueSource>>,
     pub sub_issues: Vec<ReadRef<PlainIssue>>,
+    pub suggested_fix: Option<ReadRef<PlainSuggestedFix>>,
     pub processing_path: ReadRef<PlainIssueProcessingPath>,
 }
 
 fn hash_plain_issue(issue: &PlainIssue, hasher: &mut Xxh3Hash64Hasher, full: bool) {
     hasher.write_ref(&issue.severity);
+    hasher.write_ref(&issue.code);
     hasher.write_ref(&issue.file_path);
     hasher.write_ref(&issue.stage);
+    hasher.write_ref(&issue.transform);
     hasher.write_ref(&issue.title);
     hasher.write_ref(&issue.description);
     hasher.write_ref(&issue.detail);
@@ -724,6 +895,13 @@ fn hash_plain_issue(issue: &PlainIssue, hasher: &mut Xxh3Hash64Hasher, full: boo
         // I'm assuming we don't need to hash the contents. Not 100% correct, but
         // probably 99%.
         hasher.write_ref(&source.range);
+        hasher.write_ref(&source.label);
+        hasher.write_value(source.related.len());
+        for related in &source.related {
+            hasher.write_ref(&*related.asset.ident);
+            hasher.write_ref(&related.range);
+            hasher.write_ref(&related.label);
+        }
     } else {
         hasher.write_value(0_u8);
     }
@@ -775,6 +953,8 @@ impl PlainIssue {
 pub struct PlainIssueSource {
     pub asset: ReadRef<PlainSource>,
     pub range: Option<(SourcePos, SourcePos)>,
+    pub label: Option<RcStr>,
+    pub related: Vec<ReadRef<PlainIssueSource>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -787,11 +967,9 @@ impl IssueSource {
                 Some(range) => match &*range.await? {
                     SourceRange::LineColumn(start, end) => Some((*start, *end)),
                     SourceRange::ByteOffset(start, end) => {
-                        if let FileLinesContent::Lines(lines) =
-                            &*self.source.content().lines().await?
-                        {
-                            let start = find_line_and_column(lines.as_ref(), *start);
-                            let end = find_line_and_column(lines.as_ref(), *end);
+                        if let Some(line_index) = source_line_index(*self.source).await? {
+                            let start = find_line_and_column(&line_index, *start);
+                            let end = find_line_and_column(&line_index, *end);
                             Some((start, end))
                         } else {
                             None
@@ -800,6 +978,13 @@ impl IssueSource {
                 },
                 _ => None,
             },
+            label: self.label.clone(),
+            related: self
+                .related
+                .iter()
+                .map(|related| related.into_plain())
+                .try_join()
+                .await?,
         }
         .cell())
     }
@@ -1015,27 +1200,21 @@ pub async fn handle_issues<T: Send>(
     }
 }
 
-fn find_line_and_column(lines: &[FileLine], offset: usize) -> SourcePos {
-    match lines.binary_search_by(|line| line.bytes_offset.cmp(&offset)) {
-        Ok(i) => SourcePos { line: i, column: 0 },
-        Err(i) => {
-            if i == 0 {
-                SourcePos {
-                    line: 0,
-                    column: offset,
-                }
-            } else {
-                let line = &lines[i - 1];
-                SourcePos {
-                    line: i - 1,
-                    column: min(line.content.len(), offset - line.bytes_offset),
-                }
-            }
-        }
-    }
+/// Returns the cached line index of the source's content, if it is a file.
+async fn source_line_index(source: Vc<Box<dyn Source>>) -> Result<Option<Arc<LineIndex>>> {
+    Ok(match &*source.content().file_content().await? {
+        FileContent::Content(file) => Some(file.content().line_index()),
+        FileContent::NotFound => None,
+    })
+}
+
+fn find_line_and_column(line_index: &LineIndex, offset: usize) -> SourcePos {
+    let (line, column) = line_index.line_and_column(offset);
+    SourcePos { line, column }
 }
 
-fn find_offset(lines: &[FileLine], pos: SourcePos) -> usize {
-    let line = &lines[pos.line];
-    line.bytes_offset + pos.column
+fn find_offset(line_index: &LineIndex, pos: SourcePos) -> usize {
+    line_index
+        .offset(pos.line, pos.column)
+        .expect("line of source position must exist in the source")
 }
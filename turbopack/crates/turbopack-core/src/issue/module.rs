@@ -1,7 +1,7 @@
 use turbo_tasks::Vc;
 use turbo_tasks_fs::FileSystemPath;
 
-use super::{Issue, IssueStage, OptionStyledString, StyledString};
+use super::{Issue, IssueCode, IssueStage, OptionIssueCode, OptionStyledString, StyledString};
 use crate::{ident::AssetIdent, issue::IssueExt, source::Source};
 
 #[turbo_tasks::value(shared)]
@@ -13,6 +13,11 @@ pub struct ModuleIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ModuleIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::MODULE_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn stage(&self) -> Vc<IssueStage> {
         IssueStage::ProcessModule.cell()
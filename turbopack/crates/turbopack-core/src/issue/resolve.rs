@@ -1,18 +1,32 @@
 use std::fmt::Write;
 
 use anyhow::Result;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ReadRef, ResolvedVc, ValueToString, Vc};
-use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_fs::{
+    DirectoryContent, DirectoryEntry, FileContent, FileJsonContent, FileSystemPath,
+};
 
-use super::{Issue, IssueSource, IssueStage, OptionIssueSource, OptionStyledString, StyledString};
+use super::{
+    Issue, IssueCode, IssueSource, IssueStage, OptionIssueCode, OptionIssueSource,
+    OptionStyledString, OptionSuggestedFix, StyledString, SuggestedFix, TextEdit,
+};
 use crate::{
+    asset::Asset,
     error::PrettyPrintError,
     issue::IssueSeverity,
     resolve::{
+        find_context_file,
         options::{ImportMap, ImportMapResult, ResolveOptions},
+        package_json,
         parse::Request,
+        pattern::Pattern,
+        FindContextFileResult,
     },
+    source::Source,
+    source_pos::SourcePos,
 };
 
 #[turbo_tasks::value(shared)]
@@ -28,6 +42,11 @@ pub struct ResolvingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ResolvingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::MODULE_NOT_FOUND))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         *self.severity
@@ -123,6 +142,28 @@ impl Issue for ResolvingIssue {
         Vc::cell(self.source.map(|s| s.resolve_source_map(*self.file_path)))
     }
 
+    #[turbo_tasks::function]
+    async fn suggested_fix(&self) -> Result<Vc<OptionSuggestedFix>> {
+        let fix = match &*self.request.await? {
+            Request::Relative {
+                path: Pattern::Constant(request),
+                ..
+            } => match self.source {
+                Some(source) => relative_request_fix(*self.file_path, request, *source).await?,
+                None => None,
+            },
+            // A request for a subpath of a package that is installed fails because the subpath
+            // doesn't exist, not because the package is missing.
+            Request::Module {
+                module,
+                path: Pattern::Constant(path),
+                ..
+            } if path.is_empty() => missing_dependency_fix(*self.file_path, module).await?,
+            _ => None,
+        };
+        Ok(Vc::cell(fix))
+    }
+
     // TODO add sub_issue for a description of resolve_options
     // TODO add source link
 }
@@ -139,3 +180,361 @@ async fn lookup_import_map(
     }
     Ok(Some(result.cell().to_string().await?))
 }
+
+/// Extensions that are suggested when a relative request is missing one.
+const FIX_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx", "json"];
+
+/// Suggests a fix for a relative request that points to an existing file, but
+/// is missing the file extension or uses the wrong casing.
+async fn relative_request_fix(
+    origin_path: Vc<FileSystemPath>,
+    request: &str,
+    source: Vc<IssueSource>,
+) -> Result<Option<ResolvedVc<SuggestedFix>>> {
+    let Some(target) = &*origin_path.parent().try_join(request.into()).await? else {
+        return Ok(None);
+    };
+    let target_name = target.await?.file_name().to_string();
+    let DirectoryContent::Entries(entries) = &*target.parent().read_dir().await? else {
+        return Ok(None);
+    };
+    let is_file = |name: &str| {
+        matches!(
+            entries.get(name),
+            Some(DirectoryEntry::File(_) | DirectoryEntry::Symlink(_))
+        )
+    };
+    let request_dir = &request[..request.len() - target_name.len()];
+
+    let (description, replacement) = if let Some(ext) = FIX_EXTENSIONS
+        .iter()
+        .find(|ext| is_file(&format!("{target_name}.{ext}")))
+    {
+        ("Add the missing file extension", format!("{request}.{ext}"))
+    } else if let Some(name) = entries.keys().find(|name| {
+        name.as_str() != target_name
+            && (name.eq_ignore_ascii_case(&target_name)
+                || FIX_EXTENSIONS
+                    .iter()
+                    .any(|ext| name.eq_ignore_ascii_case(&format!("{target_name}.{ext}"))))
+            && is_file(name)
+    }) {
+        (
+            "Fix the casing of the import path",
+            format!("{request_dir}{name}"),
+        )
+    } else {
+        return Ok(None);
+    };
+
+    let Some(edit) = replace_in_source(source, request, &replacement).await? else {
+        return Ok(None);
+    };
+    Ok(Some(
+        SuggestedFix {
+            description: description.into(),
+            edits: vec![edit],
+        }
+        .resolved_cell(),
+    ))
+}
+
+/// Creates an edit that replaces the quoted `text` within the span of `source`
+/// with `replacement`.
+async fn replace_in_source(
+    source: Vc<IssueSource>,
+    text: &str,
+    replacement: &str,
+) -> Result<Option<TextEdit>> {
+    let source = source.await?;
+    let Some((start, end)) = source.to_swc_offsets().await? else {
+        return Ok(None);
+    };
+    let FileContent::Content(file) = &*source.source.content().file_content().await? else {
+        return Ok(None);
+    };
+    let content = file.content();
+    let Ok(span) = content.slice_str(start - 1..end - 1) else {
+        return Ok(None);
+    };
+    let Some(index) = ["'", "\"", "`"]
+        .iter()
+        .find_map(|quote| span.find(&format!("{quote}{text}{quote}")))
+    else {
+        return Ok(None);
+    };
+    let text_start = start - 1 + index + 1;
+    let line_index = content.line_index();
+    let (start_line, start_column) = line_index.line_and_column(text_start);
+    let (end_line, end_column) = line_index.line_and_column(text_start + text.len());
+    Ok(Some(TextEdit {
+        file_path: source.source.ident().path().to_resolved().await?,
+        start: SourcePos {
+            line: start_line,
+            column: start_column,
+        },
+        end: SourcePos {
+            line: end_line,
+            column: end_column,
+        },
+        replacement: replacement.into(),
+    }))
+}
+
+/// Lockfiles which record the installed versions in a text format.
+const LOCKFILES: &[&str] = &["pnpm-lock.yaml", "package-lock.json", "yarn.lock"];
+
+/// The version range of `module` to depend on, taken from the workspace which
+/// contains `origin_path`. A module which can't be resolved is often installed
+/// for another package of the workspace, so it isn't hoisted to a
+/// `node_modules` directory above `origin_path`, but its version is recorded in
+/// the lockfile at the root of the workspace. Otherwise the range the root
+/// package.json depends on is used.
+async fn workspace_version_range(
+    origin_path: Vc<FileSystemPath>,
+    module: &str,
+) -> Result<Option<RcStr>> {
+    let mut dir = origin_path.parent();
+    let mut root_package_json = None;
+    loop {
+        for &lockfile in LOCKFILES {
+            let FileContent::Content(file) = &*dir.join(lockfile.into()).read().await? else {
+                continue;
+            };
+            let Ok(text) = file.content().to_str() else {
+                continue;
+            };
+            if let Some(version) = lockfile_version(lockfile, &text, module) {
+                return Ok(Some(format!("^{version}").into()));
+            }
+            // The lockfile is at the root of the workspace.
+            return dependency_range(dir.join("package.json".into()), module).await;
+        }
+        let package_json = dir.join("package.json".into());
+        if matches!(
+            &*package_json.read_json().await?,
+            FileJsonContent::Content(_)
+        ) {
+            root_package_json = Some(package_json);
+        }
+        if dir.await?.is_root() {
+            break;
+        }
+        dir = dir.parent();
+    }
+    // Without a lockfile the outermost package.json is the root of the workspace.
+    match root_package_json {
+        Some(package_json) => dependency_range(package_json, module).await,
+        None => Ok(None),
+    }
+}
+
+/// The fields of a package.json which list dependencies.
+const DEPENDENCY_FIELDS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// The version range with which the package.json at `package_json` depends on
+/// `module`.
+async fn dependency_range(package_json: Vc<FileSystemPath>, module: &str) -> Result<Option<RcStr>> {
+    let FileJsonContent::Content(json) = &*package_json.read_json().await? else {
+        return Ok(None);
+    };
+    Ok(DEPENDENCY_FIELDS
+        .iter()
+        .find_map(|field| json[field][module].as_str())
+        .filter(|range| !range.starts_with("workspace:"))
+        .map(RcStr::from))
+}
+
+/// Looks up the version of `module` in the contents of a lockfile. If several
+/// versions are installed, the first one is returned.
+fn lockfile_version(lockfile: &str, text: &str, module: &str) -> Option<String> {
+    match lockfile {
+        "package-lock.json" => {
+            let json: serde_json::Value = serde_json::from_str(text).ok()?;
+            let path = format!("node_modules/{module}");
+            let nested_path = format!("/{path}");
+            let version = json["packages"]
+                .as_object()
+                .and_then(|packages| {
+                    packages.iter().find_map(|(package_path, package)| {
+                        (package_path == &path || package_path.ends_with(&nested_path))
+                            .then(|| package["version"].as_str())
+                            .flatten()
+                    })
+                })
+                // lockfileVersion 1
+                .or_else(|| json["dependencies"][module]["version"].as_str())?;
+            Some(version.to_string())
+        }
+        "pnpm-lock.yaml" => {
+            // Packages are keyed as `/name@1.0.0` or `name@1.0.0`, and as `/name/1.0.0`
+            // before lockfile version 6, with an optional suffix for peer dependencies.
+            let key = Regex::new(&format!(
+                r#"(?m)^\s+['"]?/?{}[@/](\d[^:('"_\s]*)"#,
+                regex::escape(module)
+            ))
+            .ok()?;
+            Some(key.captures(text)?[1].to_string())
+        }
+        "yarn.lock" => {
+            // An entry starts with the comma-separated ranges it satisfies, e.g.
+            // `"name@^1.0.0", name@~1.1.0:`, followed by an indented `version "1.1.2"`
+            // (`version: 1.1.2` in yarn 2+).
+            let prefix = format!("{module}@");
+            let mut in_entry = false;
+            for line in text.lines() {
+                if !line.starts_with(char::is_whitespace) {
+                    in_entry = line.strip_suffix(':').is_some_and(|ranges| {
+                        ranges
+                            .split(", ")
+                            .any(|range| range.trim_matches('"').starts_with(&prefix))
+                    });
+                } else if in_entry {
+                    if let Some(version) = line.trim_start().strip_prefix("version") {
+                        let version = version.trim_start_matches(':').trim().trim_matches('"');
+                        return Some(version.to_string());
+                    }
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Suggests adding a module that couldn't be resolved to the dependencies of
+/// the closest package.json, in the version that is used elsewhere in the
+/// workspace.
+async fn missing_dependency_fix(
+    origin_path: Vc<FileSystemPath>,
+    module: &str,
+) -> Result<Option<ResolvedVc<SuggestedFix>>> {
+    if module.starts_with('#') || module.contains(':') {
+        return Ok(None);
+    }
+    let FindContextFileResult::Found(package_json_path, _) =
+        &*find_context_file(origin_path.parent(), package_json()).await?
+    else {
+        return Ok(None);
+    };
+    // A declared dependency which can't be resolved isn't installed, adding it again doesn't
+    // help.
+    if dependency_range(**package_json_path, module)
+        .await?
+        .is_some()
+    {
+        return Ok(None);
+    }
+    // Without a version used in the workspace there is no version to suggest.
+    let Some(range) = workspace_version_range(origin_path, module).await? else {
+        return Ok(None);
+    };
+    let FileContent::Content(file) = &*package_json_path.read().await? else {
+        return Ok(None);
+    };
+    let content = file.content();
+    let Ok(text) = content.to_str() else {
+        return Ok(None);
+    };
+    static DEPENDENCIES: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#""dependencies"\s*:\s*\{"#).unwrap());
+    let Some(dependencies) = DEPENDENCIES.find(&text) else {
+        return Ok(None);
+    };
+    let insert_at = dependencies.end();
+    let is_empty = text[insert_at..].trim_start().starts_with('}');
+    let replacement = if is_empty {
+        format!("\n    \"{module}\": \"{range}\"\n  ")
+    } else {
+        format!("\n    \"{module}\": \"{range}\",")
+    };
+    let (line, column) = content.line_index().line_and_column(insert_at);
+    let pos = SourcePos { line, column };
+    Ok(Some(
+        SuggestedFix {
+            description: format!("Add \"{module}\" to the dependencies in package.json").into(),
+            edits: vec![TextEdit {
+                file_path: *package_json_path,
+                start: pos,
+                end: pos,
+                replacement: replacement.into(),
+            }],
+        }
+        .resolved_cell(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lockfile_version;
+
+    #[test]
+    fn package_lock_version() {
+        let lockfile = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "name": "root" },
+                "packages/app/node_modules/left-pad": { "version": "1.3.0" },
+                "node_modules/@scope/pkg": { "version": "2.0.1" }
+            }
+        }"#;
+        assert_eq!(
+            lockfile_version("package-lock.json", lockfile, "left-pad").as_deref(),
+            Some("1.3.0")
+        );
+        assert_eq!(
+            lockfile_version("package-lock.json", lockfile, "@scope/pkg").as_deref(),
+            Some("2.0.1")
+        );
+        assert_eq!(lockfile_version("package-lock.json", lockfile, "pad"), None);
+    }
+
+    #[test]
+    fn pnpm_lock_version() {
+        let lockfile = "lockfileVersion: '9.0'\n\npackages:\n\n  left-pad@1.3.0:\n    resolution: \
+                        {integrity: sha512-abc}\n\n  '@scope/pkg@2.0.1(react@18.2.0)':\n    \
+                        resolution: {}\n";
+        assert_eq!(
+            lockfile_version("pnpm-lock.yaml", lockfile, "left-pad").as_deref(),
+            Some("1.3.0")
+        );
+        assert_eq!(
+            lockfile_version("pnpm-lock.yaml", lockfile, "@scope/pkg").as_deref(),
+            Some("2.0.1")
+        );
+        assert_eq!(
+            lockfile_version("pnpm-lock.yaml", "packages:\n  /pad/1.0.0:\n", "left-pad"),
+            None
+        );
+        assert_eq!(
+            lockfile_version(
+                "pnpm-lock.yaml",
+                "packages:\n  /left-pad/1.0.0:\n",
+                "left-pad"
+            )
+            .as_deref(),
+            Some("1.0.0")
+        );
+    }
+
+    #[test]
+    fn yarn_lock_version() {
+        let lockfile = "# yarn lockfile v1\n\n\"left-pad@^1.0.0\", left-pad@~1.3.0:\n  version \
+                        \"1.3.0\"\n  resolved \"https://registry.yarnpkg.com/left-pad\"\n\n\
+                        \"@scope/pkg@npm:^2.0.0\":\n  version: 2.0.1\n";
+        assert_eq!(
+            lockfile_version("yarn.lock", lockfile, "left-pad").as_deref(),
+            Some("1.3.0")
+        );
+        assert_eq!(
+            lockfile_version("yarn.lock", lockfile, "@scope/pkg").as_deref(),
+            Some("2.0.1")
+        );
+        assert_eq!(lockfile_version("yarn.lock", lockfile, "pad"), None);
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{with_timeout, ReadRef, ResolvedVc, TaskTimeout, Vc, VcValueType};
+use turbo_tasks_fs::FileSystemPath;
+
+use super::{
+    Issue, IssueCode, IssueExt, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
+
+/// Reported by [with_timeout_issue] when a task didn't finish within its timeout.
+#[turbo_tasks::value(shared)]
+pub struct TaskTimeoutIssue {
+    pub file_path: ResolvedVc<FileSystemPath>,
+    pub stage: ResolvedVc<IssueStage>,
+    /// What didn't finish, e.g. `Webpack loaders`.
+    pub operation: RcStr,
+    pub timeout: Duration,
+    /// The tasks the timed out task was waiting for, see [TaskTimeout::stack].
+    pub stack: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for TaskTimeoutIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::TASK_TIMEOUT))
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        *self.stage
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.file_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            format!(
+                "{} didn't finish within {}s",
+                self.operation,
+                self.timeout.as_secs_f64()
+            )
+            .into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let Some((task, waiting_for)) = self.stack.split_first() else {
+            return Vc::cell(None);
+        };
+        let mut lines = vec![StyledString::Line(vec![
+            StyledString::Text("The timed out task is ".into()),
+            StyledString::Code(task.clone()),
+        ])];
+        for task in waiting_for {
+            lines.push(StyledString::Line(vec![
+                StyledString::Text("  waiting for ".into()),
+                StyledString::Code(task.clone()),
+            ]));
+        }
+        if !waiting_for.is_empty() {
+            lines.push(StyledString::Text(
+                "The last task is still executing, which usually means it hangs.".into(),
+            ));
+        }
+        Vc::cell(Some(StyledString::Stack(lines).cell()))
+    }
+}
+
+/// Like [turbo_tasks::with_timeout], but reports a [TaskTimeoutIssue] for `file_path` when the
+/// task times out, and returns `None` then. Other errors are returned as they are.
+pub async fn with_timeout_issue<T>(
+    duration: Duration,
+    vc: Vc<T>,
+    file_path: ResolvedVc<FileSystemPath>,
+    stage: IssueStage,
+    operation: RcStr,
+) -> Result<Option<ReadRef<T>>>
+where
+    T: VcValueType,
+{
+    let error = match with_timeout(duration, vc).await {
+        Ok(value) => return Ok(Some(value)),
+        Err(error) => error,
+    };
+    let timeout = error.downcast::<TaskTimeout>()?;
+    TaskTimeoutIssue {
+        file_path,
+        stage: stage.resolved_cell(),
+        operation,
+        timeout: timeout.timeout,
+        stack: timeout
+            .stack
+            .iter()
+            .map(|task| task.to_string().into())
+            .collect(),
+    }
+    .cell()
+    .emit();
+    Ok(None)
+}
@@ -7,10 +7,12 @@
 #![feature(iter_intersperse)]
 
 pub mod asset;
+pub mod build_telemetry;
 pub mod changed;
 pub mod chunk;
 pub mod code_builder;
 pub mod compile_time_info;
+pub mod compile_time_report;
 pub mod condition;
 pub mod context;
 pub mod diagnostics;
@@ -36,8 +38,10 @@ pub mod source_map;
 pub mod source_pos;
 pub mod source_transform;
 pub mod target;
+pub mod text_transform;
 mod utils;
 pub mod version;
+pub mod virtual_module;
 pub mod virtual_output;
 pub mod virtual_source;
 
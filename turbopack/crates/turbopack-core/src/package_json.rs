@@ -7,7 +7,9 @@ use turbo_tasks::{debug::ValueDebugFormat, trace::TraceRawVcs, ReadRef, Vc};
 use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemPath};
 
 use super::issue::Issue;
-use crate::issue::{IssueExt, IssueStage, OptionStyledString, StyledString};
+use crate::issue::{
+    IssueCode, IssueExt, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 /// PackageJson wraps the parsed JSON content of a `package.json` file. The
 /// wrapper is necessary so that we can reference the [FileJsonContent]'s inner
@@ -64,6 +66,11 @@ pub struct PackageJsonIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for PackageJsonIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_PACKAGE_JSON))
+    }
+
     #[turbo_tasks::function]
     fn title(&self) -> Vc<StyledString> {
         StyledString::Text("Error parsing package.json file".into()).cell()
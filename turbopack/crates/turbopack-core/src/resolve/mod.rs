@@ -28,6 +28,10 @@ use self::{
     pattern::Pattern,
     plugin::BeforeResolvePlugin,
     remap::{ExportsField, ImportsField},
+    trace::{
+        describe_import_map_result, describe_value, emit_resolve_trace_step, request_kind,
+        request_string, ResolveTraceStepKind,
+    },
 };
 use crate::{
     context::AssetContext,
@@ -44,6 +48,7 @@ use crate::{
         node::{node_cjs_resolve_options, node_esm_resolve_options},
         pattern::{read_matches, PatternMatch},
         plugin::AfterResolvePlugin,
+        pnp::find_pnp_package,
     },
     source::{OptionSource, Source, Sources},
 };
@@ -54,8 +59,11 @@ pub mod options;
 pub mod origin;
 pub mod parse;
 pub mod pattern;
+pub mod persistent_cache;
 pub mod plugin;
+pub mod pnp;
 pub(crate) mod remap;
+pub mod trace;
 
 pub use alias_map::{
     AliasMap, AliasMapIntoIter, AliasMapLookupIterator, AliasMatch, AliasPattern, AliasTemplate,
@@ -1303,14 +1311,27 @@ async fn find_package(
                     for name in names.iter() {
                         let fs_path = lookup_path.join(name.clone());
                         if let Some(fs_path) = dir_exists(fs_path, &mut affecting_sources).await? {
-                            let fs_path = fs_path.join(package_name.clone());
-                            if let Some(fs_path) =
-                                dir_exists(fs_path, &mut affecting_sources).await?
+                            let package_dir = fs_path.join(package_name.clone());
+                            if let Some(package_dir) =
+                                dir_exists(package_dir, &mut affecting_sources).await?
                             {
                                 packages.push(FindPackageItem::PackageDirectory(
-                                    fs_path.to_resolved().await?,
+                                    package_dir.to_resolved().await?,
                                 ));
                             }
+                            if options.enable_module_files {
+                                for extension in &options.extensions {
+                                    let package_file =
+                                        fs_path.join(format!("{package_name}{extension}").into());
+                                    if let Some(package_file) =
+                                        exists(package_file, &mut affecting_sources).await?
+                                    {
+                                        packages.push(FindPackageItem::PackageFile(
+                                            package_file.to_resolved().await?,
+                                        ));
+                                    }
+                                }
+                            }
                         }
                     }
                     lookup_path = lookup_path.parent().resolve().await?;
@@ -1357,6 +1378,18 @@ async fn find_package(
                     }
                 }
             }
+            ResolveModules::Pnp { manifest } => {
+                affecting_sources.push(ResolvedVc::upcast(
+                    FileSource::new(**manifest).to_resolved().await?,
+                ));
+                if let Some(package_dir) =
+                    find_pnp_package(**manifest, lookup_path, &package_name).await?
+                {
+                    packages.push(FindPackageItem::PackageDirectory(
+                        package_dir.to_resolved().await?,
+                    ));
+                }
+            }
         }
     }
     Ok(FindPackageResult::cell(FindPackageResult {
@@ -1492,6 +1525,16 @@ pub async fn resolve_inline(
         )
     };
     async {
+        if options.await?.trace {
+            emit_resolve_trace_step(
+                ResolveTraceStepKind::Request,
+                lookup_path,
+                request_string(request).await?,
+                format!("resolving a {reference_type} reference"),
+            )
+            .await?;
+        }
+
         let reference_type = Value::new(reference_type);
         let before_plugins_result =
             handle_before_resolve_plugins(lookup_path, reference_type.clone(), request, options)
@@ -1499,11 +1542,7 @@ pub async fn resolve_inline(
 
         let raw_result = match before_plugins_result {
             Some(result) => result,
-            None => {
-                resolve_internal(lookup_path, request, options)
-                    .resolve()
-                    .await?
-            }
+            None => resolve_internal_persistently_cached(lookup_path, request, options).await?,
         };
 
         let result =
@@ -1564,22 +1603,80 @@ pub async fn url_resolve(
     .await
 }
 
+/// Like [resolve_internal], but restores the result from
+/// [ResolveOptions::persistent_cache] for module requests from within
+/// `node_modules`, and records it there otherwise. The cache is bypassed while
+/// tracing, as restored results don't emit any steps.
+async fn resolve_internal_persistently_cached(
+    lookup_path: Vc<FileSystemPath>,
+    request: Vc<Request>,
+    options: Vc<ResolveOptions>,
+) -> Result<Vc<ResolveResult>> {
+    let options_value = options.await?;
+    let lookup_path_value = lookup_path.await?;
+    let request_value = request.await?;
+    let (cache, module) = match (options_value.persistent_cache, &*request_value) {
+        (Some(cache), Request::Module { module, .. })
+            if !options_value.trace
+                && persistent_cache::is_in_node_modules(&lookup_path_value.path) =>
+        {
+            (cache, module)
+        }
+        _ => {
+            return resolve_internal(lookup_path, request, options)
+                .resolve()
+                .await
+        }
+    };
+
+    let key = persistent_cache::cache_key(&lookup_path_value.path, &request.to_string().await?);
+    if let Some(result) = *cache.restore(key.clone(), lookup_path).await? {
+        return Ok(*result);
+    }
+    let result = resolve_internal(lookup_path, request, options)
+        .resolve()
+        .await?;
+    cache
+        .await?
+        .record(key, lookup_path, module, result)
+        .await?;
+    Ok(result)
+}
+
 async fn handle_before_resolve_plugins(
     lookup_path: Vc<FileSystemPath>,
     reference_type: Value<ReferenceType>,
     request: Vc<Request>,
     options: Vc<ResolveOptions>,
 ) -> Result<Option<Vc<ResolveResult>>> {
-    for plugin in &options.await?.before_resolve_plugins {
+    let options_value = options.await?;
+    for plugin in &options_value.before_resolve_plugins {
         let condition = plugin.before_resolve_condition().resolve().await?;
         if !condition.await?.matches(request).await? {
             continue;
         }
 
-        if let Some(result) = *plugin
+        let result = *plugin
             .before_resolve(lookup_path, reference_type.clone(), request)
-            .await?
-        {
+            .await?;
+        if options_value.trace {
+            emit_resolve_trace_step(
+                ResolveTraceStepKind::Plugin,
+                lookup_path,
+                request_string(request).await?,
+                format!(
+                    "before resolve plugin {} {}",
+                    describe_value(**plugin).await?,
+                    if result.is_some() {
+                        "resolved the request"
+                    } else {
+                        "didn't resolve the request"
+                    }
+                ),
+            )
+            .await?;
+        }
+        if let Some(result) = result {
             return Ok(Some(result));
         }
     }
@@ -1600,13 +1697,28 @@ async fn handle_after_resolve_plugins(
         request: Vc<Request>,
         options: Vc<ResolveOptions>,
     ) -> Result<Option<Vc<ResolveResult>>> {
-        for plugin in &options.await?.plugins {
+        let options_value = options.await?;
+        for plugin in &options_value.plugins {
             let after_resolve_condition = plugin.after_resolve_condition().resolve().await?;
             if *after_resolve_condition.matches(path).await? {
-                if let Some(result) = *plugin
+                let result = *plugin
                     .after_resolve(path, lookup_path, reference_type.clone(), request)
-                    .await?
-                {
+                    .await?;
+                if options_value.trace {
+                    emit_resolve_trace_step(
+                        ResolveTraceStepKind::Plugin,
+                        lookup_path,
+                        request_string(request).await?,
+                        format!(
+                            "after resolve plugin {} {} {}",
+                            describe_value(**plugin).await?,
+                            if result.is_some() { "replaced" } else { "kept" },
+                            path.to_string().await?
+                        ),
+                    )
+                    .await?;
+                }
+                if let Some(result) = result {
                     return Ok(Some(result));
                 }
             }
@@ -1686,6 +1798,16 @@ async fn resolve_internal_inline(
         let options_value: &ResolveOptions = &*options.await?;
         let request_value = request.await?;
 
+        if options_value.trace {
+            emit_resolve_trace_step(
+                ResolveTraceStepKind::Request,
+                lookup_path,
+                request_string(request).await?,
+                format!("resolving a {} request", request_kind(&request_value)),
+            )
+            .await?;
+        }
+
         // Apply import mappings if provided
         let mut has_alias = false;
         if let Some(import_map) = &options_value.import_map {
@@ -1695,6 +1817,15 @@ async fn resolve_internal_inline(
             };
             for &request in request_parts {
                 let result = import_map.await?.lookup(lookup_path, *request).await?;
+                if options_value.trace {
+                    emit_resolve_trace_step(
+                        ResolveTraceStepKind::Alias,
+                        lookup_path,
+                        request_string(*request).await?,
+                        format!("import map: {}", describe_import_map_result(&result).await?),
+                    )
+                    .await?;
+                }
                 if !matches!(result, ImportMapResult::NoEntry) {
                     has_alias = true;
                     let resolved_result = resolve_import_map_result(
@@ -1946,6 +2077,18 @@ async fn resolve_internal_inline(
         if let Some(import_map) = &options_value.fallback_import_map {
             if *result.is_unresolvable().await? {
                 let result = import_map.await?.lookup(lookup_path, request).await?;
+                if options_value.trace {
+                    emit_resolve_trace_step(
+                        ResolveTraceStepKind::Alias,
+                        lookup_path,
+                        request_string(request).await?,
+                        format!(
+                            "fallback import map: {}",
+                            describe_import_map_result(&result).await?
+                        ),
+                    )
+                    .await?;
+                }
                 let resolved_result = resolve_import_map_result(
                     &result,
                     lookup_path,
@@ -1989,6 +2132,15 @@ async fn resolve_into_folder(
                         {
                             continue;
                         }
+                        if options_value.trace {
+                            emit_resolve_trace_step(
+                                ResolveTraceStepKind::MainField,
+                                *package_path,
+                                ".".into(),
+                                format!("main field `{name}` points to `{normalized_request}`"),
+                            )
+                            .await?;
+                        }
                         let request = Request::parse(Value::new(normalized_request.into()))
                             .to_resolved()
                             .await?;
@@ -2099,41 +2251,28 @@ async fn resolve_relative_request(
         new_path.normalize();
     };
 
-    if options_value.enable_typescript_with_output_extension {
+    if !options_value.extension_alias.is_empty() {
         new_path.replace_final_constants(&|c: &RcStr| -> Option<Pattern> {
-            let (base, replacement) = match c.rsplit_once(".") {
-                Some((base, "js")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".ts".into()),
-                        Pattern::Constant(".tsx".into()),
-                        Pattern::Constant(".js".into()),
-                    ],
-                ),
-                Some((base, "mjs")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".mts".into()),
-                        Pattern::Constant(".mjs".into()),
-                    ],
-                ),
-                Some((base, "cjs")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".cts".into()),
-                        Pattern::Constant(".cjs".into()),
-                    ],
-                ),
-                _ => {
-                    return None;
-                }
-            };
+            let (base, replacement) =
+                options_value
+                    .extension_alias
+                    .iter()
+                    .find_map(|(extension, alternatives)| {
+                        let base = c.strip_suffix(&**extension)?;
+                        Some((base, alternatives))
+                    })?;
+            let replacement = Pattern::Alternatives(
+                replacement
+                    .iter()
+                    .map(|extension| Pattern::Constant(extension.clone()))
+                    .collect(),
+            );
             if base.is_empty() {
-                Some(Pattern::Alternatives(replacement))
+                Some(replacement)
             } else {
                 Some(Pattern::Concatenation(vec![
                     Pattern::Constant(base.into()),
-                    Pattern::Alternatives(replacement),
+                    replacement,
                 ]))
             }
         });
@@ -2141,6 +2280,7 @@ async fn resolve_relative_request(
     }
 
     let mut results = Vec::new();
+    let probed_pattern = options_value.trace.then(|| new_path.to_string());
     let matches = read_matches(
         lookup_path,
         "".into(),
@@ -2149,6 +2289,29 @@ async fn resolve_relative_request(
     )
     .await?;
 
+    if let Some(probed_pattern) = probed_pattern {
+        let found = matches
+            .iter()
+            .map(|m| match m {
+                PatternMatch::File(matched_pattern, _) => format!("file `{matched_pattern}`"),
+                PatternMatch::Directory(matched_pattern, _) => {
+                    format!("directory `{matched_pattern}`")
+                }
+            })
+            .collect::<Vec<_>>();
+        emit_resolve_trace_step(
+            ResolveTraceStepKind::Probe,
+            lookup_path,
+            request_string(request).await?,
+            if found.is_empty() {
+                format!("probed {probed_pattern}, nothing exists")
+            } else {
+                format!("probed {probed_pattern}, found {}", found.join(", "))
+            },
+        )
+        .await?;
+    }
+
     for m in matches.iter() {
         if let PatternMatch::File(matched_pattern, path) = m {
             let mut pushed = false;
@@ -2440,6 +2603,35 @@ async fn resolve_module_request(
     )
     .await?;
 
+    if options_value.trace {
+        let packages = result
+            .packages
+            .iter()
+            .map(|item| async move {
+                Ok(match item {
+                    FindPackageItem::PackageDirectory(path) => {
+                        format!("directory {}", path.to_string().await?)
+                    }
+                    FindPackageItem::PackageFile(path) => {
+                        format!("file {}", path.to_string().await?)
+                    }
+                })
+            })
+            .try_join()
+            .await?;
+        emit_resolve_trace_step(
+            ResolveTraceStepKind::Module,
+            lookup_path,
+            request_string(request).await?,
+            if packages.is_empty() {
+                format!("package `{module}` wasn't found in the modules directories")
+            } else {
+                format!("package `{module}` was found: {}", packages.join(", "))
+            },
+        )
+        .await?;
+    }
+
     if result.packages.is_empty() {
         return Ok(ResolveResult::unresolvable_with_affecting_sources(
             result.affecting_sources.clone(),
@@ -2793,6 +2985,44 @@ async fn handle_exports_imports_field(
         }
     }
 
+    if options.await?.trace {
+        let mut evaluated = conditions_state
+            .iter()
+            .map(|(condition, value)| format!("{condition}: {value:?}"))
+            .collect::<Vec<_>>();
+        evaluated.sort();
+        let targets = results
+            .iter()
+            .map(|(target, _)| format!("`{target}`"))
+            .collect::<Vec<_>>();
+        emit_resolve_trace_step(
+            ResolveTraceStepKind::ExportsConditions,
+            package_path,
+            format!("{path}{query_str}").into(),
+            format!(
+                "{} of {} matched {} entries (conditions: {}), targets: {}",
+                if path.starts_with('#') {
+                    "imports field"
+                } else {
+                    "exports field"
+                },
+                package_json_path.to_string().await?,
+                values.len(),
+                if evaluated.is_empty() {
+                    "none".to_string()
+                } else {
+                    evaluated.join(", ")
+                },
+                if targets.is_empty() {
+                    "none".to_string()
+                } else {
+                    targets.join(", ")
+                }
+            ),
+        )
+        .await?;
+    }
+
     let mut resolved_results = Vec::new();
     for (result_path, conditions) in results {
         if let Some(result_path) = result_path.with_normalized_path() {
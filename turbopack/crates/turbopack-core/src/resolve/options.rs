@@ -12,6 +12,7 @@ use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use super::{
     alias_map::{AliasMap, AliasTemplate},
     pattern::Pattern,
+    persistent_cache::PersistentResolveCache,
     plugin::BeforeResolvePlugin,
     AliasPattern, ExternalType, ResolveResult, ResolveResultItem,
 };
@@ -38,6 +39,10 @@ pub enum ResolveModules {
         dir: ResolvedVc<FileSystemPath>,
         excluded_extensions: ResolvedVc<ExcludedExtensions>,
     },
+    /// look up packages in a Yarn Plug'n'Play manifest (`.pnp.cjs`)
+    Pnp {
+        manifest: ResolvedVc<FileSystemPath>,
+    },
 }
 
 #[derive(TraceRawVcs, Hash, PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
@@ -590,10 +595,24 @@ pub struct ResolveOptions {
     pub resolved_map: Option<ResolvedVc<ResolvedMap>>,
     pub before_resolve_plugins: Vec<ResolvedVc<Box<dyn BeforeResolvePlugin>>>,
     pub plugins: Vec<ResolvedVc<Box<dyn AfterResolvePlugin>>>,
-    /// Support resolving *.js requests to *.ts files
-    pub enable_typescript_with_output_extension: bool,
+    /// Maps the extension of a request to extensions that are tried instead,
+    /// in order, like webpack's `resolve.extensionAlias`. The original
+    /// extension is only tried when it's part of the list. Also applies to
+    /// fully specified requests.
+    pub extension_alias: Vec<(RcStr, Vec<RcStr>)>,
+    /// Also resolve module requests to files directly inside of a modules
+    /// directory, e.g. `foo` to `node_modules/foo.js`. Such modules don't have
+    /// a package.json, like with webpack's `resolve.descriptionFiles` set to
+    /// an empty list.
+    pub enable_module_files: bool,
     /// Warn instead of error for resolve errors
     pub loose_errors: bool,
+    /// Keeps the results of module requests from within `node_modules` across
+    /// restarts, see [PersistentResolveCache].
+    pub persistent_cache: Option<ResolvedVc<PersistentResolveCache>>,
+    /// Emits a [ResolveTraceStep][super::trace::ResolveTraceStep] for every step of the
+    /// resolver, see [trace_resolve][super::trace::trace_resolve].
+    pub trace: bool,
 
     pub placeholder_for_future_extensions: (),
 }
@@ -649,6 +668,26 @@ impl ResolveOptions {
         Ok(resolve_options.into())
     }
 
+    /// Overrides the extension alias used for resolving
+    #[turbo_tasks::function]
+    pub async fn with_extension_alias(
+        self: Vc<Self>,
+        extension_alias: Vec<(RcStr, Vec<RcStr>)>,
+    ) -> Result<Vc<Self>> {
+        let mut resolve_options = self.await?.clone_value();
+        resolve_options.extension_alias = extension_alias;
+        Ok(resolve_options.into())
+    }
+
+    /// Enables [ResolveOptions::trace]. The persistent cache is skipped while tracing.
+    #[turbo_tasks::function]
+    pub async fn with_trace(self: Vc<Self>) -> Result<Vc<Self>> {
+        let mut resolve_options = self.await?.clone_value();
+        resolve_options.trace = true;
+        resolve_options.persistent_cache = None;
+        Ok(resolve_options.into())
+    }
+
     /// Overrides the fully_specified flag for resolving
     #[turbo_tasks::function]
     pub async fn with_fully_specified(self: Vc<Self>, fully_specified: bool) -> Result<Vc<Self>> {
@@ -662,11 +701,26 @@ impl ResolveOptions {
     }
 }
 
+/// The extension alias used for TypeScript, which allows to import `.ts`
+/// files with the extension of their output, e.g. `import "./foo.js"`.
+pub fn typescript_extension_alias() -> Vec<(RcStr, Vec<RcStr>)> {
+    vec![
+        (
+            ".js".into(),
+            vec![".ts".into(), ".tsx".into(), ".js".into()],
+        ),
+        (".jsx".into(), vec![".tsx".into(), ".jsx".into()]),
+        (".mjs".into(), vec![".mts".into(), ".mjs".into()]),
+        (".cjs".into(), vec![".cts".into(), ".cjs".into()]),
+    ]
+}
+
 #[turbo_tasks::value(shared)]
 #[derive(Hash, Clone, Debug)]
 pub struct ResolveModulesOptions {
     pub modules: Vec<ResolveModules>,
     pub extensions: Vec<RcStr>,
+    pub enable_module_files: bool,
 }
 
 #[turbo_tasks::function]
@@ -677,6 +731,7 @@ pub async fn resolve_modules_options(
     Ok(ResolveModulesOptions {
         modules: options.modules.clone(),
         extensions: options.extensions.clone(),
+        enable_module_files: options.enable_module_files,
     }
     .into())
 }
@@ -0,0 +1,599 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, TryJoinIterExt, Vc};
+use turbo_tasks_fs::{to_sys_path, FileContent, FileSystemPath};
+use turbo_tasks_hash::hash_xxh3_hash64;
+
+use super::{RequestKey, ResolveResult, ResolveResultItem};
+use crate::{file_source::FileSource, source::Source};
+
+/// The version of the cache file format. Files with a different version are
+/// ignored.
+const VERSION: u32 = 1;
+
+/// A resolve result from a previous session.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CachedResolveResult {
+    primary: Vec<CachedResolveResultItem>,
+    /// The paths of the affecting sources.
+    affecting_sources: Vec<RcStr>,
+    /// The paths which must still be of the same type, see [lookup_chain].
+    entries: Vec<RcStr>,
+    /// The paths of the files which must still have the same content: the
+    /// affecting sources and the package.json files of the resolved packages.
+    files: Vec<RcStr>,
+    /// The hash of the types of all `entries` and the contents of all
+    /// `files`, see [hash_chain].
+    hash_chain: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct CachedResolveResultItem {
+    request: Option<RcStr>,
+    conditions: BTreeMap<String, bool>,
+    /// The path of the resolved file, relative to the root of its file system.
+    path: RcStr,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<RcStr, CachedResolveResult>,
+}
+
+/// Keeps resolve results of module requests from within `node_modules`
+/// across restarts.
+///
+/// A result is restored when the whole lookup chain is unchanged: no package
+/// of the requested name was added to or removed from a `node_modules`
+/// directory between the requesting file and the resolved package, the
+/// resolved files still exist and the contents of all package.json files and
+/// other affecting sources are unchanged. This is the case unless packages
+/// have been installed or updated. Only results which consist of plain files
+/// are persisted.
+///
+/// The cache doesn't know the resolve options it's used with, so a cache must
+/// only be shared by resolve options which resolve requests from
+/// `node_modules` in the same way, see [PersistentResolveCache::new].
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+pub struct PersistentResolveCache {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    path: PathBuf,
+    /// The entries read from disk when the cache was created.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    restored: Arc<HashMap<RcStr, CachedResolveResult>>,
+    /// The entries recorded in this session.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    recorded: Arc<Mutex<HashMap<RcStr, CachedResolveResult>>>,
+    /// The restored entries which turned out to be outdated.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    outdated: Arc<Mutex<HashSet<RcStr>>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionResolveResult(Option<ResolvedVc<ResolveResult>>);
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionPersistentResolveCache(Option<ResolvedVc<PersistentResolveCache>>);
+
+#[turbo_tasks::value_impl]
+impl PersistentResolveCache {
+    /// Creates a cache which is stored in `cache_dir`, which must be on a
+    /// [DiskFileSystem][turbo_tasks_fs::DiskFileSystem]. Caches with
+    /// different `name`s are stored in different files, so a name must
+    /// identify the resolve options the cache is used with, e.g. `"client"`
+    /// and `"server"`.
+    ///
+    /// The cache file is only read here. Call
+    /// [PersistentResolveCache::persist] to write it. As that doesn't go
+    /// through the file system, `cache_dir` shouldn't be watched, or every
+    /// write invalidates the cache.
+    #[turbo_tasks::function]
+    pub async fn new(cache_dir: Vc<FileSystemPath>, name: RcStr) -> Result<Vc<Self>> {
+        let file =
+            cache_dir.join(format!("resolve-{:016x}.json", hash_xxh3_hash64(name.as_str())).into());
+        let path = to_sys_path(file)
+            .await?
+            .context("the persistent resolve cache must be stored on disk")?;
+        // A missing or broken cache file only means that nothing is restored.
+        let restored = match &*file.read().await? {
+            FileContent::Content(content) => {
+                serde_json::from_reader::<_, CacheFile>(content.content().read()).ok()
+            }
+            FileContent::NotFound => None,
+        }
+        .filter(|file| file.version == VERSION)
+        .map(|file| file.entries)
+        .unwrap_or_default();
+        Ok(PersistentResolveCache {
+            path,
+            restored: Arc::new(restored),
+            recorded: Default::default(),
+            outdated: Default::default(),
+        }
+        .cell())
+    }
+
+    /// Restores the result for `key` when the lookup chain it depends on is
+    /// unchanged. The paths of the chain become dependencies of the calling
+    /// task, so changing them later invalidates the result.
+    #[turbo_tasks::function]
+    pub(crate) async fn restore(
+        &self,
+        key: RcStr,
+        lookup_path: Vc<FileSystemPath>,
+    ) -> Result<Vc<OptionResolveResult>> {
+        let Some(cached) = self.restored.get(&key) else {
+            return Ok(Vc::cell(None));
+        };
+        let fs = lookup_path.await?.fs;
+        let path = |path: &RcStr| FileSystemPath::new_normalized(*fs, path.clone());
+        if hash_chain(&cached.entries, &cached.files, path).await? != cached.hash_chain {
+            self.outdated.lock().unwrap().insert(key);
+            return Ok(Vc::cell(None));
+        }
+
+        let mut primary = FxIndexMap::default();
+        for item in &cached.primary {
+            primary.insert(
+                RequestKey {
+                    request: item.request.clone(),
+                    conditions: item.conditions.clone(),
+                },
+                ResolveResultItem::Source(ResolvedVc::upcast(
+                    FileSource::new(path(&item.path).to_resolved().await?)
+                        .to_resolved()
+                        .await?,
+                )),
+            );
+        }
+        let affecting_sources = cached
+            .affecting_sources
+            .iter()
+            .map(|affecting| async move {
+                anyhow::Ok(ResolvedVc::upcast(
+                    FileSource::new(path(affecting).to_resolved().await?)
+                        .to_resolved()
+                        .await?,
+                ))
+            })
+            .try_join()
+            .await?;
+        Ok(Vc::cell(Some(
+            ResolveResult {
+                primary,
+                affecting_sources,
+            }
+            .resolved_cell(),
+        )))
+    }
+}
+
+impl PersistentResolveCache {
+    /// Records the result of the request for `module` from `lookup_path`
+    /// under `key`, to be written by the next
+    /// [PersistentResolveCache::persist]. Results which aren't made of plain
+    /// files on the file system of `lookup_path` are skipped.
+    pub(crate) async fn record(
+        &self,
+        key: RcStr,
+        lookup_path: Vc<FileSystemPath>,
+        module: &str,
+        result: Vc<ResolveResult>,
+    ) -> Result<()> {
+        let result = result.await?;
+        if result.primary.is_empty() {
+            return Ok(());
+        }
+        let lookup_path = lookup_path.await?;
+        let fs = lookup_path.fs;
+        let file_path = move |source: ResolvedVc<Box<dyn Source>>| async move {
+            let Some(file) = ResolvedVc::try_downcast_type::<FileSource>(source).await? else {
+                return Ok(None);
+            };
+            let file = file.await?;
+            if !file.query.await?.is_empty() {
+                return Ok(None);
+            }
+            let path = file.path.await?;
+            if path.fs != fs {
+                return Ok(None);
+            }
+            anyhow::Ok(Some(path.path.clone()))
+        };
+
+        let mut entries = lookup_chain(&lookup_path.path, module);
+        let mut files = Vec::new();
+        let mut primary = Vec::new();
+        for (request, item) in &result.primary {
+            let ResolveResultItem::Source(source) = item else {
+                return Ok(());
+            };
+            let Some(path) = file_path(*source).await? else {
+                return Ok(());
+            };
+            if let Some(package_json) = package_json_of(&path) {
+                files.push(package_json);
+            }
+            entries.push(path.clone());
+            primary.push(CachedResolveResultItem {
+                request: request.request.clone(),
+                conditions: request.conditions.clone(),
+                path,
+            });
+        }
+        let mut affecting_sources = Vec::new();
+        for source in &result.affecting_sources {
+            let Some(path) = file_path(*source).await? else {
+                return Ok(());
+            };
+            files.push(path.clone());
+            affecting_sources.push(path);
+        }
+        files.sort();
+        files.dedup();
+
+        let hash_chain = hash_chain(&entries, &files, |path| {
+            FileSystemPath::new_normalized(*fs, path.clone())
+        })
+        .await?;
+        self.recorded.lock().unwrap().insert(
+            key,
+            CachedResolveResult {
+                primary,
+                affecting_sources,
+                entries,
+                files,
+                hash_chain,
+            },
+        );
+        Ok(())
+    }
+
+    /// Writes the restored results which are still valid and all results
+    /// recorded in this session to disk.
+    pub fn persist(&self) -> Result<()> {
+        let mut entries = HashMap::new();
+        {
+            let outdated = self.outdated.lock().unwrap();
+            for (key, entry) in self.restored.iter() {
+                if !outdated.contains(key) {
+                    entries.insert(key.clone(), entry.clone());
+                }
+            }
+        }
+        entries.extend(
+            self.recorded
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.clone())),
+        );
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        std::fs::write(
+            &self.path,
+            serde_json::to_vec(&CacheFile {
+                version: VERSION,
+                entries,
+            })?,
+        )
+        .with_context(|| format!("writing resolve cache {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// The key of the request `request` from `lookup_path`.
+pub(crate) fn cache_key(lookup_path: &str, request: &str) -> RcStr {
+    format!("{lookup_path}\0{request}").into()
+}
+
+/// Hashes the types of `entries` and the contents of `files` one after the
+/// other, so a result is only restored when every link of the chain is
+/// unchanged.
+async fn hash_chain(
+    entries: &[RcStr],
+    files: &[RcStr],
+    path: impl Fn(&RcStr) -> Vc<FileSystemPath>,
+) -> Result<u64> {
+    let types = entries
+        .iter()
+        .map(|entry| path(entry).get_type())
+        .try_join()
+        .await?;
+    let hashes = files
+        .iter()
+        .map(|file| path(file).read().hash())
+        .try_join()
+        .await?;
+    Ok(entries
+        .iter()
+        .zip(types)
+        .map(|(entry, ty)| format!("{entry}:{:?}", *ty))
+        .chain(
+            files
+                .iter()
+                .zip(hashes)
+                .map(|(file, hash)| format!("{file}:{:x}", *hash)),
+        )
+        .fold(0, |chain, link| {
+            hash_xxh3_hash64(format!("{chain:x}:{link}").as_str())
+        }))
+}
+
+/// The paths a request for `module` from `lookup_path` looks at to find the
+/// package: `node_modules/<module>` in `lookup_path` and each of its parents.
+/// A package appearing at or disappearing from one of them changes the
+/// result.
+fn lookup_chain(lookup_path: &str, module: &str) -> Vec<RcStr> {
+    let mut chain = Vec::new();
+    let mut dir = lookup_path;
+    loop {
+        if dir.is_empty() {
+            chain.push(format!("node_modules/{module}").into());
+            return chain;
+        }
+        chain.push(format!("{dir}/node_modules/{module}").into());
+        dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+    }
+}
+
+/// Whether a path is inside of a `node_modules` directory.
+pub(crate) fn is_in_node_modules(path: &str) -> bool {
+    path.split('/').any(|segment| segment == "node_modules")
+}
+
+/// The package.json of the package containing `path`, when `path` is inside
+/// of a `node_modules` directory.
+fn package_json_of(path: &str) -> Option<RcStr> {
+    let (node_modules, package_path) = path.rsplit_once("node_modules/")?;
+    let mut segments = package_path.split('/');
+    let mut name = segments.next()?.to_string();
+    if name.starts_with('@') {
+        name = format!("{name}/{}", segments.next()?);
+    }
+    Some(format!("{node_modules}node_modules/{name}/package.json").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use anyhow::Context;
+    use turbo_rcstr::RcStr;
+    use turbo_tasks::{TurboTasks, Value};
+    use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::{cache_key, lookup_chain, package_json_of, PersistentResolveCache};
+    use crate::{
+        reference_type::ReferenceType,
+        resolve::{
+            options::{ResolveIntoPackage, ResolveModules, ResolveOptions},
+            parse::Request,
+            resolve,
+        },
+        source::Source,
+    };
+
+    /// Creates an empty directory for the test, with the given files in it.
+    fn test_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("turbopack-resolve-cache-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        write(&dir, files);
+        dir
+    }
+
+    fn write(dir: &Path, files: &[(&str, &str)]) {
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    /// Resolves `request` from `node_modules/a` in a new session and persists
+    /// the cache. Returns the path of the result and whether it was restored
+    /// from the previous session.
+    async fn resolve_in_new_session(dir: &Path, request: &str) -> (String, bool) {
+        crate::register();
+        let dir: RcStr = dir.to_string_lossy().into();
+        let request: RcStr = request.into();
+        let tt = TurboTasks::new(MemoryBackend::default());
+        tt.run_once(async move {
+            let root = DiskFileSystem::new("project".into(), dir.clone(), vec![])
+                .root()
+                .to_resolved()
+                .await?;
+            let cache = PersistentResolveCache::new(
+                DiskFileSystem::new("cache".into(), dir, vec![])
+                    .root()
+                    .join(".cache".into()),
+                "test".into(),
+            )
+            .to_resolved()
+            .await?;
+            let lookup_path = root.join("node_modules/a".into());
+            let restored = cache
+                .restore(cache_key("node_modules/a", &request), lookup_path)
+                .await?
+                .is_some();
+
+            let options = ResolveOptions {
+                modules: vec![ResolveModules::Nested(root, vec!["node_modules".into()])],
+                into_package: vec![ResolveIntoPackage::MainField {
+                    field: "main".into(),
+                }],
+                extensions: vec![".js".into()],
+                default_files: vec!["index".into()],
+                persistent_cache: Some(cache),
+                ..Default::default()
+            }
+            .cell();
+            let source = resolve(
+                lookup_path,
+                Value::new(ReferenceType::Undefined),
+                Request::parse_string(request),
+                options,
+            )
+            .first_source()
+            .await?
+            .context("request should resolve")?;
+            let path = source.ident().path().await?.path.to_string();
+
+            cache.await?.persist()?;
+            anyhow::Ok((path, restored))
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn restores_results_of_previous_sessions() {
+        let dir = test_dir(
+            "round-trip",
+            &[
+                ("node_modules/a/index.js", ""),
+                ("node_modules/b/package.json", r#"{ "main": "lib.js" }"#),
+                ("node_modules/b/lib.js", ""),
+            ],
+        );
+
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib.js".to_string(), false)
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib.js".to_string(), true)
+        );
+        // Other requests aren't restored.
+        assert_eq!(
+            resolve_in_new_session(&dir, "b/lib").await,
+            ("node_modules/b/lib.js".to_string(), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_restore_results_of_changed_packages() {
+        let dir = test_dir(
+            "changed-package",
+            &[
+                ("node_modules/a/index.js", ""),
+                ("node_modules/b/package.json", r#"{ "main": "lib.js" }"#),
+                ("node_modules/b/lib.js", ""),
+                ("node_modules/b/other.js", ""),
+            ],
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib.js".to_string(), false)
+        );
+
+        write(
+            &dir,
+            &[("node_modules/b/package.json", r#"{ "main": "other.js" }"#)],
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/other.js".to_string(), false)
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/other.js".to_string(), true)
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_restore_results_of_shadowed_packages() {
+        let dir = test_dir(
+            "shadowed-package",
+            &[
+                ("node_modules/a/index.js", ""),
+                ("node_modules/b/package.json", r#"{ "main": "lib.js" }"#),
+                ("node_modules/b/lib.js", ""),
+            ],
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib.js".to_string(), false)
+        );
+
+        // A package closer to the requesting file takes precedence.
+        write(
+            &dir,
+            &[
+                ("node_modules/a/node_modules/b/package.json", "{}"),
+                ("node_modules/a/node_modules/b/index.js", ""),
+            ],
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/a/node_modules/b/index.js".to_string(), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_restore_results_of_removed_files() {
+        let dir = test_dir(
+            "removed-file",
+            &[
+                ("node_modules/a/index.js", ""),
+                ("node_modules/b/package.json", r#"{ "main": "lib" }"#),
+                ("node_modules/b/lib.js", ""),
+                ("node_modules/b/lib/index.js", ""),
+            ],
+        );
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib.js".to_string(), false)
+        );
+
+        fs::remove_file(dir.join("node_modules/b/lib.js")).unwrap();
+        assert_eq!(
+            resolve_in_new_session(&dir, "b").await,
+            ("node_modules/b/lib/index.js".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn chain() {
+        assert_eq!(
+            lookup_chain("node_modules/a/lib", "b"),
+            vec![
+                RcStr::from("node_modules/a/lib/node_modules/b"),
+                RcStr::from("node_modules/a/node_modules/b"),
+                RcStr::from("node_modules/node_modules/b"),
+                RcStr::from("node_modules/b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn package_json() {
+        assert_eq!(
+            package_json_of("node_modules/react/index.js").as_deref(),
+            Some("node_modules/react/package.json")
+        );
+        assert_eq!(
+            package_json_of("app/node_modules/@scope/pkg/dist/index.js").as_deref(),
+            Some("app/node_modules/@scope/pkg/package.json")
+        );
+        assert_eq!(
+            package_json_of("node_modules/a/node_modules/b/lib/b.js").as_deref(),
+            Some("node_modules/a/node_modules/b/package.json")
+        );
+        assert_eq!(package_json_of("src/index.js"), None);
+    }
+}
@@ -0,0 +1,383 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, FxIndexSet, ResolvedVc, Vc};
+use turbo_tasks_fs::{zip::ZipFileSystem, FileContent, FileSystemPath};
+
+/// Identifies a package in a Plug'n'Play manifest. The top-level workspace has
+/// neither a name nor a reference.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PnpLocator {
+    name: Option<RcStr>,
+    reference: Option<RcStr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PnpPackage {
+    /// The location of the package relative to the manifest, without a leading
+    /// `./` and trailing `/`.
+    location: RcStr,
+    /// The locators of the dependencies by their name. `None` for missing
+    /// optional peer dependencies.
+    dependencies: FxIndexMap<RcStr, Option<PnpLocator>>,
+}
+
+/// The data of a Yarn Plug'n'Play manifest, which describes where each
+/// package is located and which dependencies it is allowed to access.
+#[turbo_tasks::value(serialization = "none")]
+pub struct PnpManifest {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    packages: FxIndexMap<PnpLocator, PnpPackage>,
+    /// Package locations sorted by descending length, so the first match is
+    /// the innermost package.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    locations: Vec<(RcStr, PnpLocator)>,
+    enable_top_level_fallback: bool,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    fallback_pool: FxIndexMap<RcStr, Option<PnpLocator>>,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    fallback_exclusion_list: FxIndexSet<PnpLocator>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionPnpManifest(Option<ResolvedVc<PnpManifest>>);
+
+/// The result of looking up a package for an issuer in a [PnpManifest].
+#[derive(Debug, PartialEq, Eq)]
+pub enum PnpResolution<'a> {
+    /// The issuer is not part of any package of the manifest, so regular
+    /// `node_modules` resolution applies.
+    NotManaged,
+    /// The package is located at the given path relative to the manifest.
+    Found(&'a str),
+    /// The issuer doesn't depend on the package.
+    NotFound,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPnpData {
+    #[serde(default)]
+    enable_top_level_fallback: bool,
+    #[serde(default)]
+    fallback_pool: Vec<(RcStr, RawDependency)>,
+    #[serde(default)]
+    fallback_exclusion_list: Vec<(RcStr, Vec<RcStr>)>,
+    package_registry_data: Vec<(Option<RcStr>, Vec<(Option<RcStr>, RawPackageInformation)>)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPackageInformation {
+    package_location: RcStr,
+    #[serde(default)]
+    package_dependencies: Vec<(RcStr, RawDependency)>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDependency {
+    Reference(RcStr),
+    Alias(RcStr, RcStr),
+    Missing,
+}
+
+impl RawDependency {
+    fn into_locator(self, name: &RcStr) -> Option<PnpLocator> {
+        match self {
+            RawDependency::Reference(reference) => Some(PnpLocator {
+                name: Some(name.clone()),
+                reference: Some(reference),
+            }),
+            RawDependency::Alias(name, reference) => Some(PnpLocator {
+                name: Some(name),
+                reference: Some(reference),
+            }),
+            RawDependency::Missing => None,
+        }
+    }
+}
+
+/// Normalizes a location to the format of [FileSystemPath::path], relative to
+/// the manifest.
+fn normalize_location(location: &str) -> RcStr {
+    let location = location.trim_end_matches('/');
+    let location = location.strip_prefix("./").unwrap_or(location);
+    if location == "." {
+        RcStr::default()
+    } else {
+        location.into()
+    }
+}
+
+impl PnpManifest {
+    pub fn parse(data: &str) -> Result<Self> {
+        let raw: RawPnpData = serde_json::from_str(data)?;
+        let mut packages = FxIndexMap::default();
+        let mut locations = Vec::new();
+        for (name, versions) in raw.package_registry_data {
+            for (reference, information) in versions {
+                let locator = PnpLocator {
+                    name: name.clone(),
+                    reference,
+                };
+                let location = normalize_location(&information.package_location);
+                locations.push((location.clone(), locator.clone()));
+                packages.insert(
+                    locator,
+                    PnpPackage {
+                        location,
+                        dependencies: information
+                            .package_dependencies
+                            .into_iter()
+                            .map(|(name, dependency)| {
+                                let locator = dependency.into_locator(&name);
+                                (name, locator)
+                            })
+                            .collect(),
+                    },
+                );
+            }
+        }
+        locations.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        Ok(PnpManifest {
+            packages,
+            locations,
+            enable_top_level_fallback: raw.enable_top_level_fallback,
+            fallback_pool: raw
+                .fallback_pool
+                .into_iter()
+                .map(|(name, dependency)| {
+                    let locator = dependency.into_locator(&name);
+                    (name, locator)
+                })
+                .collect(),
+            fallback_exclusion_list: raw
+                .fallback_exclusion_list
+                .into_iter()
+                .flat_map(|(name, references)| {
+                    references.into_iter().map(move |reference| PnpLocator {
+                        name: Some(name.clone()),
+                        reference: Some(reference),
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    /// Finds the innermost package containing `path`, which is relative to the
+    /// manifest.
+    fn find_locator(&self, path: &str) -> Option<&PnpLocator> {
+        self.locations
+            .iter()
+            .find(|(location, _)| {
+                location.is_empty()
+                    || path
+                        .strip_prefix(location.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .map(|(_, locator)| locator)
+    }
+
+    /// Looks up the location of the package `name` as seen from the file or
+    /// directory `issuer`, which is relative to the manifest.
+    pub fn resolve_package(&self, issuer: &str, name: &str) -> PnpResolution<'_> {
+        let Some(issuer_locator) = self.find_locator(issuer) else {
+            return PnpResolution::NotManaged;
+        };
+        let Some(issuer_package) = self.packages.get(issuer_locator) else {
+            return PnpResolution::NotManaged;
+        };
+        let dependency = issuer_package.dependencies.get(name).or_else(|| {
+            if !self.enable_top_level_fallback
+                || self.fallback_exclusion_list.contains(issuer_locator)
+            {
+                return None;
+            }
+            self.packages
+                .get(&PnpLocator {
+                    name: None,
+                    reference: None,
+                })
+                .and_then(|top_level| top_level.dependencies.get(name))
+                .or_else(|| self.fallback_pool.get(name))
+        });
+        match dependency
+            .and_then(|locator| locator.as_ref())
+            .and_then(|locator| self.packages.get(locator))
+        {
+            Some(package) => PnpResolution::Found(&package.location),
+            None => PnpResolution::NotFound,
+        }
+    }
+}
+
+/// Extracts the inlined manifest from the `RAW_RUNTIME_STATE` string literal
+/// of a `.pnp.cjs` file.
+fn extract_raw_runtime_state(source: &str) -> Option<String> {
+    let start = source.find("RAW_RUNTIME_STATE")?;
+    let rest = &source[start..];
+    let literal = &rest[rest.find('\'')? + 1..];
+    let mut result = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                // line continuation
+                '\n' => {}
+                'n' => result.push('\n'),
+                c => result.push(c),
+            },
+            '\'' => return Some(result),
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+/// Reads a Plug'n'Play manifest from a `.pnp.cjs` file. When the data isn't
+/// inlined, it is read from the `.pnp.data.json` file next to it.
+#[turbo_tasks::function]
+pub async fn read_pnp_manifest(path: ResolvedVc<FileSystemPath>) -> Result<Vc<OptionPnpManifest>> {
+    let FileContent::Content(file) = &*path.read().await? else {
+        return Ok(Vc::cell(None));
+    };
+    let source = file.content().to_str()?;
+    let data = match extract_raw_runtime_state(&source) {
+        Some(data) => data,
+        None => {
+            let data_path = path.parent().join(".pnp.data.json".into());
+            let FileContent::Content(file) = &*data_path.read().await? else {
+                return Ok(Vc::cell(None));
+            };
+            file.content().to_str()?.into_owned()
+        }
+    };
+    let manifest = PnpManifest::parse(&data)
+        .with_context(|| format!("Unable to parse Plug'n'Play manifest {}", path.await?.path))?;
+    Ok(Vc::cell(Some(manifest.resolved_cell())))
+}
+
+/// Returns the path of `path` relative to `dir`, in the format of
+/// [FileSystemPath::path]. Paths inside of a [ZipFileSystem] are mapped to
+/// their location inside of the archive.
+async fn relative_path(dir: &FileSystemPath, path: Vc<FileSystemPath>) -> Result<Option<String>> {
+    let path = path.await?;
+    let (relative, inner) =
+        if let Some(zip_fs) = ResolvedVc::try_downcast_type::<ZipFileSystem>(path.fs).await? {
+            let archive = zip_fs.archive().await?;
+            (dir.get_relative_path_to(&archive), Some(&path.path))
+        } else {
+            (dir.get_relative_path_to(&path), None)
+        };
+    let Some(relative) = relative else {
+        return Ok(None);
+    };
+    let mut relative = normalize_location(&relative).to_string();
+    if let Some(inner) = inner.filter(|inner| !inner.is_empty()) {
+        if !relative.is_empty() {
+            relative.push('/');
+        }
+        relative.push_str(inner);
+    }
+    Ok(Some(relative))
+}
+
+/// Finds the directory of the package `name` as seen from `lookup_path` using
+/// the Plug'n'Play manifest at `manifest_path`. Packages stored in zip
+/// archives are returned as a path on a [ZipFileSystem].
+pub async fn find_pnp_package(
+    manifest_path: Vc<FileSystemPath>,
+    lookup_path: Vc<FileSystemPath>,
+    name: &str,
+) -> Result<Option<Vc<FileSystemPath>>> {
+    let Some(manifest) = *read_pnp_manifest(manifest_path).await? else {
+        return Ok(None);
+    };
+    let manifest_dir = manifest_path.parent();
+    let Some(issuer) = relative_path(&*manifest_dir.await?, lookup_path).await? else {
+        return Ok(None);
+    };
+    let manifest = manifest.await?;
+    let PnpResolution::Found(location) = manifest.resolve_package(&issuer, name) else {
+        return Ok(None);
+    };
+    let (archive, inner) = match location.find(".zip/") {
+        Some(index) => (&location[..index + 4], Some(&location[index + 5..])),
+        None if location.ends_with(".zip") => (location, Some("")),
+        None => (location, None),
+    };
+    let Some(path) = *manifest_dir.try_join(archive.into()).await? else {
+        return Ok(None);
+    };
+    Ok(Some(match inner {
+        Some(inner) => ZipFileSystem::new(*path).root().join(inner.into()),
+        None => *path,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "enableTopLevelFallback": true,
+        "fallbackPool": [],
+        "fallbackExclusionList": [],
+        "packageRegistryData": [
+            [null, [[null, {
+                "packageLocation": "./",
+                "packageDependencies": [["react", "npm:18.2.0"], ["lodash", "npm:4.17.21"]]
+            }]]],
+            ["react", [["npm:18.2.0", {
+                "packageLocation": "./.yarn/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip/node_modules/react/",
+                "packageDependencies": [["loose-envify", "npm:1.4.0"], ["peer", null]]
+            }]]],
+            ["loose-envify", [["npm:1.4.0", {
+                "packageLocation": "./.yarn/cache/loose-envify-npm-1.4.0-6307b72ccf-6517e24e0c.zip/node_modules/loose-envify/",
+                "packageDependencies": []
+            }]]],
+            ["lodash", [["npm:4.17.21", {
+                "packageLocation": "./.yarn/cache/lodash-npm-4.17.21-6382451519-eb835a2e51.zip/node_modules/lodash/",
+                "packageDependencies": []
+            }]]]
+        ]
+    }"#;
+
+    #[test]
+    fn resolve_package() {
+        let manifest = PnpManifest::parse(MANIFEST).unwrap();
+        assert_eq!(
+            manifest.resolve_package("src/index.js", "react"),
+            PnpResolution::Found(
+                ".yarn/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip/node_modules/react"
+            )
+        );
+        let react = ".yarn/cache/react-npm-18.2.0-1eae08fee2-88e38092da.zip/node_modules/react";
+        assert_eq!(
+            manifest.resolve_package(&format!("{react}/cjs"), "loose-envify"),
+            PnpResolution::Found(
+                ".yarn/cache/loose-envify-npm-1.4.0-6307b72ccf-6517e24e0c.zip/node_modules/\
+                 loose-envify"
+            )
+        );
+        assert_eq!(
+            manifest.resolve_package(react, "peer"),
+            PnpResolution::NotFound
+        );
+        // undeclared dependencies fall back to the top-level package
+        assert!(matches!(
+            manifest.resolve_package(react, "lodash"),
+            PnpResolution::Found(_)
+        ));
+    }
+
+    #[test]
+    fn extract_inlined_manifest() {
+        let source = "const RAW_RUNTIME_STATE =\n'{\\\n  \"__info\": [\"it\\'s\"]\\\n}';\n";
+        assert_eq!(
+            extract_raw_runtime_state(source).unwrap(),
+            "{  \"__info\": [\"it's\"]}"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Records the steps taken by the resolver, for debugging "module not found" reports. See
+//! [trace_resolve].
+
+use anyhow::Result;
+use serde::Serialize;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{emit, introspect::introspect, TryJoinIterExt, Value, ValueToString, Vc};
+use turbo_tasks_fs::FileSystemPath;
+
+use super::{options::ImportMapResult, parse::Request, resolve};
+use crate::{
+    context::AssetContext,
+    reference_type::{
+        CommonJsReferenceSubType, EcmaScriptModulesReferenceSubType, ReferenceType,
+        UrlReferenceSubType,
+    },
+};
+
+/// What the resolver did in a [ResolveTraceStep].
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Copy, Debug, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResolveTraceStepKind {
+    /// A request is resolved, either the traced request or one derived from it.
+    Request,
+    /// A before or after resolve plugin was consulted.
+    Plugin,
+    /// The import map or the fallback import map was looked up.
+    Alias,
+    /// Files and directories matching the request, with extensions appended, were looked up.
+    Probe,
+    /// Packages were looked up in the modules directories.
+    Module,
+    /// The `exports` or `imports` field of a package.json was matched against the conditions.
+    ExportsConditions,
+    /// A main field of a package.json was followed.
+    MainField,
+}
+
+/// A step taken by the resolver while
+/// [`ResolveOptions::trace`][super::options::ResolveOptions::trace] is set. The steps are emitted
+/// as collectibles, and therefore aren't ordered, but every step names the request and the
+/// directory it was taken for.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveTraceStep {
+    pub kind: ResolveTraceStepKind,
+    pub lookup_path: RcStr,
+    pub request: RcStr,
+    pub message: RcStr,
+}
+
+#[turbo_tasks::value_trait]
+pub trait ResolveTraceEvent {
+    fn step(self: Vc<Self>) -> Vc<ResolveTraceStep>;
+}
+
+#[turbo_tasks::value_impl]
+impl ResolveTraceEvent for ResolveTraceStep {
+    #[turbo_tasks::function]
+    fn step(self: Vc<Self>) -> Vc<ResolveTraceStep> {
+        self
+    }
+}
+
+/// Emits a [ResolveTraceStep]. Callers check `ResolveOptions::trace` first, so resolving without
+/// tracing doesn't pay for the descriptions.
+pub(super) async fn emit_resolve_trace_step(
+    kind: ResolveTraceStepKind,
+    lookup_path: Vc<FileSystemPath>,
+    request: RcStr,
+    message: impl Into<RcStr>,
+) -> Result<()> {
+    let step = ResolveTraceStep {
+        kind,
+        lookup_path: lookup_path.to_string().await?.clone_value(),
+        request,
+        message: message.into(),
+    }
+    .cell();
+    emit(Vc::upcast::<Box<dyn ResolveTraceEvent>>(step));
+    Ok(())
+}
+
+/// A short description of a value, e.g. of a resolve plugin.
+pub(super) async fn describe_value<T: ?Sized>(vc: Vc<T>) -> Result<RcStr> {
+    Ok(introspect(Vc::into_raw(vc)).await?.display_name.into())
+}
+
+/// The steps taken to resolve a request, see [trace_resolve].
+#[turbo_tasks::value(shared, serialization = "none")]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveTrace {
+    pub request: RcStr,
+    pub importer: RcStr,
+    /// The description of the [ResolveResult][super::ResolveResult].
+    pub result: RcStr,
+    pub steps: Vec<ResolveTraceStep>,
+}
+
+/// Resolves `request` from the file `importer` with the resolve options of `asset_context` and
+/// `ResolveOptions::trace` set, and returns every step taken: the aliases tried, the extensions
+/// probed, the exports conditions evaluated and the plugins consulted.
+///
+/// The steps are sorted by the request and the directory they were taken for, and then by their
+/// [ResolveTraceStepKind], which roughly follows the order of the resolver.
+#[turbo_tasks::function]
+pub async fn trace_resolve(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    importer: Vc<FileSystemPath>,
+    request: Vc<Request>,
+    reference_type: Value<ReferenceType>,
+) -> Result<Vc<ResolveTrace>> {
+    let options = asset_context
+        .resolve_options(importer, reference_type.clone())
+        .with_trace();
+    let result = resolve(importer.parent(), reference_type, request, options);
+
+    let mut steps = result
+        .peek_collectibles::<Box<dyn ResolveTraceEvent>>()
+        .into_iter()
+        .map(|event| async move { Ok(event.step().await?.clone_value()) })
+        .try_join()
+        .await?;
+    steps.sort_by(|a, b| {
+        a.request
+            .cmp(&b.request)
+            .then_with(|| a.lookup_path.cmp(&b.lookup_path))
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| a.message.cmp(&b.message))
+    });
+
+    Ok(ResolveTrace {
+        request: request.to_string().await?.clone_value(),
+        importer: importer.to_string().await?.clone_value(),
+        result: result.to_string().await?.clone_value(),
+        steps,
+    }
+    .cell())
+}
+
+/// The reference type to trace a resolve with, for the kinds of references users usually ask
+/// about: `import` (the default), `require` and `url`.
+pub fn trace_reference_type(kind: Option<&str>) -> Option<ReferenceType> {
+    Some(match kind.unwrap_or("import") {
+        "import" => ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::Import),
+        "require" => ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined),
+        "url" => ReferenceType::Url(UrlReferenceSubType::Undefined),
+        _ => return None,
+    })
+}
+
+/// The kind of a request, as it's described in the trace.
+pub(super) fn request_kind(request: &Request) -> &'static str {
+    match request {
+        Request::Raw { .. } => "raw",
+        Request::Relative { .. } => "relative",
+        Request::Module { .. } => "module",
+        Request::ServerRelative { .. } => "server relative",
+        Request::Windows { .. } => "windows",
+        Request::Empty => "empty",
+        Request::PackageInternal { .. } => "package internal",
+        Request::Uri { .. } => "uri",
+        Request::Unknown { .. } => "unknown",
+        Request::Dynamic => "dynamic",
+        Request::Alternatives { .. } => "alternatives",
+    }
+}
+
+pub(super) async fn request_string(request: Vc<Request>) -> Result<RcStr> {
+    Ok(request.to_string().await?.clone_value())
+}
+
+/// Describes what the import map maps a request to.
+pub(super) async fn describe_import_map_result(result: &ImportMapResult) -> Result<String> {
+    Ok(match result {
+        ImportMapResult::Result(result) => format!("the result {}", result.to_string().await?),
+        ImportMapResult::External(name, ty, _) => format!("the {ty} external `{name}`"),
+        ImportMapResult::AliasExternal {
+            name,
+            ty,
+            lookup_dir,
+            ..
+        } => format!(
+            "the {ty} external `{name}`, if it can be resolved from {}",
+            lookup_dir.to_string().await?
+        ),
+        ImportMapResult::Alias(request, lookup_path) => match lookup_path {
+            Some(lookup_path) => format!(
+                "`{}` in {}",
+                request.to_string().await?,
+                lookup_path.to_string().await?
+            ),
+            None => format!("`{}`", request.to_string().await?),
+        },
+        ImportMapResult::Alternatives(list) => list
+            .iter()
+            .map(|result| Box::pin(describe_import_map_result(result)))
+            .try_join()
+            .await?
+            .join(" or "),
+        ImportMapResult::NoEntry => "no entry".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traces_imports_by_default() {
+        assert_eq!(
+            trace_reference_type(None),
+            Some(ReferenceType::EcmaScriptModules(
+                EcmaScriptModulesReferenceSubType::Import
+            ))
+        );
+        assert_eq!(
+            trace_reference_type(Some("require")),
+            Some(ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined))
+        );
+        assert_eq!(
+            trace_reference_type(Some("url")),
+            Some(ReferenceType::Url(UrlReferenceSubType::Undefined))
+        );
+        assert_eq!(trace_reference_type(Some("dynamic-import")), None);
+    }
+}
@@ -1,4 +1,4 @@
-use turbo_tasks::Vc;
+use turbo_tasks::{ResolvedVc, Vc};
 
 use crate::source::Source;
 
@@ -19,3 +19,25 @@ impl SourceTransforms {
             .fold(source, |source, transform| transform.transform(source))
     }
 }
+
+/// Replaces every source with a fixed source, e.g. a mock of a module.
+#[turbo_tasks::value]
+pub struct ReplaceSourceTransform {
+    replacement: ResolvedVc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ReplaceSourceTransform {
+    #[turbo_tasks::function]
+    pub fn new(replacement: ResolvedVc<Box<dyn Source>>) -> Vc<Self> {
+        ReplaceSourceTransform { replacement }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for ReplaceSourceTransform {
+    #[turbo_tasks::function]
+    fn transform(&self, _source: Vc<Box<dyn Source>>) -> Vc<Box<dyn Source>> {
+        *self.replacement
+    }
+}
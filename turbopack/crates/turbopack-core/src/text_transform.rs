@@ -0,0 +1,332 @@
+use anyhow::Result;
+use sourcemap::SourceMapBuilder;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, Vc};
+use turbo_tasks_fs::{File, FileContent};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    source::Source,
+    source_map::{GenerateSourceMap, OptionSourceMap, SourceMap, SourceMapSection},
+    source_pos::SourcePos,
+    source_transform::SourceTransform,
+};
+
+/// A transform of the raw text of a source, which runs before the source is
+/// parsed according to its module type. It's a lightweight alternative to a
+/// webpack loader for simple changes like prepending a banner.
+///
+/// The text is passed by value, so results are cached per content and not per
+/// source.
+#[turbo_tasks::value_trait]
+pub trait TextTransform {
+    /// A short name of the transform, which is added as modifier to the ident
+    /// of transformed sources.
+    fn name(self: Vc<Self>) -> Vc<RcStr>;
+
+    fn transform_text(self: Vc<Self>, text: RcStr) -> Vc<TransformedText>;
+}
+
+/// How the lines of a transformed text relate to the lines of the original
+/// text.
+#[derive(Debug, Clone, Copy)]
+#[turbo_tasks::value(shared)]
+pub enum TextLineMapping {
+    /// Each line corresponds to the line with the same number.
+    Unchanged,
+    /// The given number of lines has been inserted at the start, the other
+    /// lines are unchanged.
+    Prepended(u32),
+}
+
+#[turbo_tasks::value(shared)]
+pub struct TransformedText {
+    pub text: RcStr,
+    pub line_mapping: TextLineMapping,
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionTransformedText(Option<ResolvedVc<TransformedText>>);
+
+/// Applies a [TextTransform] to text sources. Sources that aren't valid UTF-8
+/// are left untouched.
+#[turbo_tasks::value]
+pub struct TextSourceTransform {
+    transform: ResolvedVc<Box<dyn TextTransform>>,
+}
+
+#[turbo_tasks::value_impl]
+impl TextSourceTransform {
+    #[turbo_tasks::function]
+    pub fn new(transform: ResolvedVc<Box<dyn TextTransform>>) -> Vc<Self> {
+        TextSourceTransform { transform }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for TextSourceTransform {
+    #[turbo_tasks::function]
+    async fn transform(&self, source: Vc<Box<dyn Source>>) -> Result<Vc<Box<dyn Source>>> {
+        Ok(Vc::upcast(
+            TextTransformedSource {
+                source: source.to_resolved().await?,
+                transform: self.transform,
+            }
+            .cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value]
+struct TextTransformedSource {
+    source: ResolvedVc<Box<dyn Source>>,
+    transform: ResolvedVc<Box<dyn TextTransform>>,
+}
+
+#[turbo_tasks::value_impl]
+impl TextTransformedSource {
+    /// Reads the original text, or `None` when the source isn't a text file.
+    #[turbo_tasks::function]
+    async fn original_text(&self) -> Result<Vc<Option<RcStr>>> {
+        let content = self.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(Vc::cell(None));
+        };
+        Ok(Vc::cell(
+            file.content()
+                .to_str()
+                .ok()
+                .map(|text| text.as_ref().into()),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    async fn transformed(self: Vc<Self>) -> Result<Vc<OptionTransformedText>> {
+        let Some(text) = self.original_text().await?.clone_value() else {
+            return Ok(Vc::cell(None));
+        };
+        Ok(Vc::cell(Some(
+            self.await?
+                .transform
+                .transform_text(text)
+                .to_resolved()
+                .await?,
+        )))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for TextTransformedSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source.ident().with_modifier(self.transform.name())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for TextTransformedSource {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let Some(transformed) = *self.transformed().await? else {
+            return Ok(self.await?.source.content());
+        };
+        let content = self.await?.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(self.await?.source.content());
+        };
+        let mut transformed_file = File::from(transformed.await?.text.clone());
+        if let Some(content_type) = file.content_type() {
+            transformed_file = transformed_file.with_content_type(content_type.clone());
+        }
+        Ok(AssetContent::file(transformed_file.into()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GenerateSourceMap for TextTransformedSource {
+    /// Maps the transformed text to the original source. When the original
+    /// source has a source map itself, the lines are mapped through it.
+    #[turbo_tasks::function]
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let Some(transformed) = *self.transformed().await? else {
+            return Ok(Vc::cell(None));
+        };
+        let line_mapping = transformed.await?.line_mapping;
+        let this = self.await?;
+        let original_map = if let Some(generate_source_map) =
+            ResolvedVc::try_sidecast::<Box<dyn GenerateSourceMap>>(this.source).await?
+        {
+            *generate_source_map.generate_source_map().await?
+        } else {
+            None
+        };
+        let original_map = match original_map {
+            Some(map) => map,
+            None => {
+                let Some(text) = self.original_text().await?.clone_value() else {
+                    return Ok(Vc::cell(None));
+                };
+                let path = this.source.ident().path().await?;
+                SourceMap::new_regular(line_source_map(path.file_name(), &text)).cell()
+            }
+        };
+        Ok(Vc::cell(Some(match line_mapping {
+            TextLineMapping::Unchanged => original_map,
+            TextLineMapping::Prepended(lines) => {
+                SourceMap::new_sectioned(vec![SourceMapSection::new(
+                    SourcePos {
+                        line: lines as usize,
+                        column: 0,
+                    },
+                    original_map,
+                )])
+                .cell()
+            }
+        })))
+    }
+}
+
+/// Creates a source map that maps the start of each line of `text` to itself.
+fn line_source_map(file_name: &str, text: &str) -> sourcemap::SourceMap {
+    let mut builder = SourceMapBuilder::new(None);
+    let source = builder.add_source(file_name);
+    builder.set_source_contents(source, Some(text));
+    for line in 0..text.lines().count() as u32 {
+        builder.add_raw(line, 0, line, 0, Some(source), None, false);
+    }
+    builder.into_sourcemap()
+}
+
+/// Removes a leading byte order mark.
+#[turbo_tasks::value]
+pub struct StripBom;
+
+#[turbo_tasks::value_impl]
+impl StripBom {
+    #[turbo_tasks::function]
+    pub fn new() -> Vc<Self> {
+        StripBom.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl TextTransform for StripBom {
+    #[turbo_tasks::function]
+    fn name(&self) -> Vc<RcStr> {
+        Vc::cell("strip bom".into())
+    }
+
+    #[turbo_tasks::function]
+    fn transform_text(&self, text: RcStr) -> Vc<TransformedText> {
+        TransformedText {
+            text: text
+                .strip_prefix('\u{feff}')
+                .map_or(text.clone(), RcStr::from),
+            line_mapping: TextLineMapping::Unchanged,
+        }
+        .cell()
+    }
+}
+
+/// Prepends a banner, e.g. a license comment or a generated code notice.
+#[turbo_tasks::value]
+pub struct Banner {
+    banner: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Banner {
+    #[turbo_tasks::function]
+    pub fn new(banner: RcStr) -> Vc<Self> {
+        Banner { banner }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl TextTransform for Banner {
+    #[turbo_tasks::function]
+    fn name(&self) -> Vc<RcStr> {
+        Vc::cell("banner".into())
+    }
+
+    #[turbo_tasks::function]
+    fn transform_text(&self, text: RcStr) -> Vc<TransformedText> {
+        let banner = self.banner.trim_end_matches('\n');
+        TransformedText {
+            text: format!("{banner}\n{text}").into(),
+            line_mapping: TextLineMapping::Prepended(banner.lines().count().max(1) as u32),
+        }
+        .cell()
+    }
+}
+
+/// Replaces `{{name}}` placeholders with their values. Values must not
+/// contain line breaks, so lines stay intact.
+#[turbo_tasks::value]
+pub struct Template {
+    values: FxIndexMap<RcStr, RcStr>,
+}
+
+impl Template {
+    pub fn new(values: FxIndexMap<RcStr, RcStr>) -> Result<Vc<Self>> {
+        if let Some((name, _)) = values.iter().find(|(_, value)| value.contains('\n')) {
+            anyhow::bail!("template value of {name} must not contain line breaks");
+        }
+        Ok(Template { values }.cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl TextTransform for Template {
+    #[turbo_tasks::function]
+    fn name(&self) -> Vc<RcStr> {
+        Vc::cell("template".into())
+    }
+
+    #[turbo_tasks::function]
+    fn transform_text(&self, text: RcStr) -> Vc<TransformedText> {
+        TransformedText {
+            text: apply_template(&text, &self.values).into(),
+            line_mapping: TextLineMapping::Unchanged,
+        }
+        .cell()
+    }
+}
+
+fn apply_template(text: &str, values: &FxIndexMap<RcStr, RcStr>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + end].trim();
+        result.push_str(&rest[..start]);
+        match values.get(name) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + 4 + end]),
+        }
+        rest = &rest[start + 4 + end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::fxindexmap;
+
+    use super::*;
+
+    #[test]
+    fn template() {
+        let values = fxindexmap! {
+            "name".into() => "turbopack".into(),
+        };
+        assert_eq!(
+            apply_template("hello {{ name }} {{unknown}} {{", &values),
+            "hello turbopack {{unknown}} {{"
+        );
+    }
+}
@@ -7,7 +7,7 @@ use turbo_tasks::{
     TraitRef, Vc,
 };
 use turbo_tasks_fs::{FileContent, LinkType};
-use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+use turbo_tasks_hash::HashAlgorithm;
 
 use crate::asset::AssetContent;
 
@@ -63,6 +63,8 @@ pub struct VersionedAssetContent {
     // Otherwise, reading `content` and `version` at two different instants in
     // time might return inconsistent values.
     asset_content: ReadRef<AssetContent>,
+    /// Hashes the content for its version.
+    hash_algorithm: HashAlgorithm,
 }
 
 #[turbo_tasks::value]
@@ -82,7 +84,7 @@ impl VersionedContent for VersionedAssetContent {
     #[turbo_tasks::function]
     async fn version(&self) -> Result<Vc<Box<dyn Version>>> {
         Ok(Vc::upcast(
-            FileHashVersion::compute(&self.asset_content).await?,
+            FileHashVersion::compute(&self.asset_content, self.hash_algorithm).await?,
         ))
     }
 }
@@ -91,9 +93,22 @@ impl VersionedContent for VersionedAssetContent {
 impl VersionedAssetContent {
     #[turbo_tasks::function]
     /// Creates a new [Vc<VersionedAssetContent>] from a [Vc<FileContent>].
-    pub async fn new(asset_content: Vc<AssetContent>) -> Result<Vc<Self>> {
+    pub fn new(asset_content: Vc<AssetContent>) -> Vc<Self> {
+        Self::new_with_hash_algorithm(asset_content, Vc::cell(HashAlgorithm::default()))
+    }
+
+    #[turbo_tasks::function]
+    /// Creates a new [Vc<VersionedAssetContent>] whose version hashes the content with
+    /// `hash_algorithm`, e.g. the content hash algorithm of the chunking context.
+    pub async fn new_with_hash_algorithm(
+        asset_content: Vc<AssetContent>,
+        hash_algorithm: Vc<HashAlgorithm>,
+    ) -> Result<Vc<Self>> {
         let asset_content = asset_content.await?;
-        Ok(Self::cell(VersionedAssetContent { asset_content }))
+        Ok(Self::cell(VersionedAssetContent {
+            asset_content,
+            hash_algorithm: *hash_algorithm.await?,
+        }))
     }
 }
 
@@ -217,14 +232,16 @@ pub struct FileHashVersion {
 
 impl FileHashVersion {
     /// Computes a new [`Vc<FileHashVersion>`] from a path.
-    pub async fn compute(asset_content: &AssetContent) -> Result<Vc<Self>> {
+    pub async fn compute(
+        asset_content: &AssetContent,
+        hash_algorithm: HashAlgorithm,
+    ) -> Result<Vc<Self>> {
         match asset_content {
             AssetContent::File(file_vc) => match &*file_vc.await? {
                 FileContent::Content(file) => {
-                    let hash = hash_xxh3_hash64(file.content());
-                    let hex_hash = encode_hex(hash);
+                    let hash = hash_algorithm.hash(file.content());
                     Ok(Self::cell(FileHashVersion {
-                        hash: hex_hash.into(),
+                        hash: hash.to_hex().into(),
                     }))
                 }
                 FileContent::NotFound => Err(anyhow!("file not found")),
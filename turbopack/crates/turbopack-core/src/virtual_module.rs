@@ -0,0 +1,241 @@
+use anyhow::Result;
+use indexmap::map::Entry;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, State, Vc};
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+
+use crate::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    resolve::{
+        options::{
+            ImportMap, ImportMapResult, ImportMapping, ImportMappingReplacement,
+            ReplacedImportMapping,
+        },
+        parse::Request,
+        pattern::Pattern,
+        ResolveResult,
+    },
+    source::Source,
+};
+
+/// The prefix under which [VirtualModules] are resolvable by default.
+pub const DEFAULT_VIRTUAL_MODULE_PREFIX: &str = "virtual:";
+
+/// Supplies the content of a [VirtualModule].
+#[turbo_tasks::value_trait]
+pub trait VirtualModuleContent {
+    /// `revision` is increased on every [VirtualModules::invalidate] call, so
+    /// implementations are executed again and can pick up changes which are
+    /// not tracked by turbo-tasks.
+    fn content(self: Vc<Self>, revision: u32) -> Vc<AssetContent>;
+}
+
+/// Fixed content, which only changes when it's replaced via
+/// [VirtualModules::register].
+#[turbo_tasks::value_impl]
+impl VirtualModuleContent for AssetContent {
+    #[turbo_tasks::function]
+    fn content(self: Vc<Self>, _revision: u32) -> Vc<AssetContent> {
+        self
+    }
+}
+
+/// A [Source] for a specifier of a [VirtualModules] registry. In contrast to a
+/// [VirtualSource][crate::virtual_source::VirtualSource] the content is read
+/// from the registry, so it can be replaced or invalidated from outside of
+/// turbo-tasks.
+///
+/// The module itself is stateless: it's memoized by registry and specifier,
+/// so all state lives in the registry.
+#[turbo_tasks::value]
+pub struct VirtualModule {
+    registry: ResolvedVc<VirtualModules>,
+    specifier: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl VirtualModule {
+    #[turbo_tasks::function]
+    pub fn new(registry: ResolvedVc<VirtualModules>, specifier: RcStr) -> Vc<Self> {
+        VirtualModule {
+            registry,
+            specifier,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for VirtualModule {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
+        let root = *self.registry.await?.root;
+        Ok(AssetIdent::from_path(root.join(self.specifier.clone())))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for VirtualModule {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let entry = self
+            .registry
+            .await?
+            .modules
+            .get()
+            .get(&self.specifier)
+            .cloned();
+        Ok(match entry {
+            Some(entry) => entry.content.content(entry.revision),
+            None => AssetContent::file(FileContent::NotFound.cell()),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+struct VirtualModuleEntry {
+    content: ResolvedVc<Box<dyn VirtualModuleContent>>,
+    /// Increased by [VirtualModules::invalidate].
+    revision: u32,
+}
+
+/// A registry of [VirtualModule]s, which makes them resolvable by their
+/// specifier prefixed with `prefix` (e.g. `virtual:my-module`) from any
+/// importer.
+///
+/// The registry is added to a resolve context via [VirtualModules::import_map],
+/// e.g. with `ResolveOptionsContext::virtual_modules`. Registering, replacing
+/// or removing modules later invalidates the affected resolves and contents.
+///
+/// [VirtualModules::new] is memoized, so the registry is identified by its
+/// root and prefix: creating it again with the same arguments returns the
+/// same registry, including the modules registered so far.
+#[turbo_tasks::value]
+pub struct VirtualModules {
+    root: ResolvedVc<FileSystemPath>,
+    prefix: RcStr,
+    modules: State<FxIndexMap<RcStr, VirtualModuleEntry>>,
+}
+
+#[turbo_tasks::value_impl]
+impl VirtualModules {
+    /// Creates an empty registry. Modules are placed at `root` joined with
+    /// their specifier.
+    #[turbo_tasks::function]
+    pub fn new(root: ResolvedVc<FileSystemPath>, prefix: RcStr) -> Vc<Self> {
+        VirtualModules {
+            root,
+            prefix,
+            modules: State::new(FxIndexMap::default()),
+        }
+        .cell()
+    }
+
+    /// An [ImportMap] that resolves all requests starting with the prefix from
+    /// this registry.
+    #[turbo_tasks::function]
+    pub async fn import_map(self: ResolvedVc<Self>) -> Result<Vc<ImportMap>> {
+        let mut import_map = ImportMap::empty();
+        import_map.insert_wildcard_alias(
+            self.await?.prefix.clone(),
+            ImportMapping::Dynamic(ResolvedVc::upcast(self)).resolved_cell(),
+        );
+        Ok(import_map.cell())
+    }
+
+    /// The module registered under `specifier`, if any.
+    #[turbo_tasks::function]
+    pub async fn get(self: ResolvedVc<Self>, specifier: RcStr) -> Result<Vc<OptionVirtualModule>> {
+        let registered = self.await?.modules.get().contains_key(&specifier);
+        Ok(Vc::cell(if registered {
+            Some(VirtualModule::new(*self, specifier).to_resolved().await?)
+        } else {
+            None
+        }))
+    }
+}
+
+impl VirtualModules {
+    /// Registers a module under `specifier`, or replaces the content of the
+    /// module already registered under it.
+    pub async fn register(
+        self: Vc<Self>,
+        specifier: RcStr,
+        content: ResolvedVc<Box<dyn VirtualModuleContent>>,
+    ) -> Result<()> {
+        self.await?.modules.update_conditionally(|modules| {
+            match modules.entry(specifier) {
+                Entry::Occupied(mut entry) => {
+                    if entry.get().content == content {
+                        return false;
+                    }
+                    entry.get_mut().content = content;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(VirtualModuleEntry {
+                        content,
+                        revision: 0,
+                    });
+                }
+            }
+            true
+        });
+        Ok(())
+    }
+
+    /// Requests the content of the module registered under `specifier` again
+    /// from its [VirtualModuleContent]. Returns whether there is such a
+    /// module.
+    pub async fn invalidate(self: Vc<Self>, specifier: &str) -> Result<bool> {
+        let mut found = false;
+        self.await?.modules.update_conditionally(|modules| {
+            if let Some(entry) = modules.get_mut(specifier) {
+                entry.revision = entry.revision.wrapping_add(1);
+                found = true;
+            }
+            found
+        });
+        Ok(found)
+    }
+
+    /// Removes the module registered under `specifier`. Returns whether there
+    /// was one.
+    pub async fn unregister(self: Vc<Self>, specifier: &str) -> Result<bool> {
+        let mut removed = false;
+        self.await?.modules.update_conditionally(|modules| {
+            removed = modules.shift_remove(specifier).is_some();
+            removed
+        });
+        Ok(removed)
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionVirtualModule(Option<ResolvedVc<VirtualModule>>);
+
+#[turbo_tasks::value_impl]
+impl ImportMappingReplacement for VirtualModules {
+    #[turbo_tasks::function]
+    async fn replace(self: Vc<Self>, capture: Vc<Pattern>) -> Result<Vc<ReplacedImportMapping>> {
+        let module = match &*capture.await? {
+            Pattern::Constant(specifier) => *self.get(specifier.clone()).await?,
+            _ => None,
+        };
+        let result = match module {
+            Some(module) => ResolveResult::source(ResolvedVc::upcast(module)),
+            None => ResolveResult::unresolvable(),
+        };
+        Ok(ReplacedImportMapping::Direct(result.resolved_cell()).cell())
+    }
+
+    #[turbo_tasks::function]
+    fn result(
+        &self,
+        _lookup_path: Vc<FileSystemPath>,
+        _request: Vc<Request>,
+    ) -> Vc<ImportMapResult> {
+        ImportMapResult::NoEntry.cell()
+    }
+}
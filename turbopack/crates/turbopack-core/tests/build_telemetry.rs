@@ -0,0 +1,81 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    build_telemetry::enable_build_telemetry,
+    compile_time_report::{record_compile_time, CompilePhase},
+};
+
+fn register() {
+    turbopack_core::register();
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/register_test_build_telemetry.rs"
+    ));
+}
+
+/// Runs a transform for 20ms after waiting 100ms for another task.
+#[turbo_tasks::function]
+async fn transform(path: RcStr) -> Result<Vc<()>> {
+    let sample = record_compile_time(&path, CompilePhase::Transform).unwrap();
+    sample
+        .measure_transform("test", async {
+            wait().await?;
+            std::thread::sleep(Duration::from_millis(20));
+            anyhow::Ok(())
+        })
+        .await?;
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn wait() -> Result<Vc<()>> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(Vc::cell(()))
+}
+
+#[tokio::test]
+async fn records_the_execution_time_of_sampled_modules() {
+    register();
+    let telemetry = enable_build_telemetry(1.0);
+    telemetry.clear();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        transform("node_modules/react/index.js".into()).await?;
+        // Cached results don't count.
+        transform("node_modules/react/index.js".into()).await?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    let report = telemetry.report();
+    assert_eq!(report.sample_rate, 1.0);
+    assert_eq!(report.modules.len(), 1);
+    let module = &report.modules[0];
+    // The path of the module isn't part of the report.
+    assert!(!module.id.contains("index"));
+    assert_eq!(module.package.as_str(), "react");
+    assert_eq!(module.extension.as_deref(), Some("js"));
+    // The time waiting for the other task isn't part of the compile time.
+    let expected = 20.0..100.0;
+    assert!(
+        expected.contains(&module.transform_ms),
+        "{}",
+        module.transform_ms
+    );
+    assert!(expected.contains(&module.transforms_ms["test"]));
+    assert_eq!(module.parse_ms, 0.0);
+    assert_eq!(report.transforms.len(), 1);
+    assert_eq!(report.transforms[0].modules, 1);
+
+    telemetry.clear();
+    assert!(telemetry.report().modules.is_empty());
+}
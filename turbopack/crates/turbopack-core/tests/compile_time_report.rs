@@ -0,0 +1,76 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TurboTasks, Vc};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::compile_time_report::{
+    enable_compile_time_report, record_compile_time, CompilePhase, PROJECT_PACKAGE_NAME,
+};
+
+fn register() {
+    turbopack_core::register();
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/register_test_compile_time_report.rs"
+    ));
+}
+
+/// Compiles for 20ms after waiting 100ms for another task.
+#[turbo_tasks::function]
+async fn compile(path: RcStr) -> Result<Vc<()>> {
+    record_compile_time(&path, CompilePhase::Parse);
+    wait().await?;
+    std::thread::sleep(Duration::from_millis(20));
+    Ok(Vc::cell(()))
+}
+
+#[turbo_tasks::function]
+async fn wait() -> Result<Vc<()>> {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(Vc::cell(()))
+}
+
+#[tokio::test]
+async fn attributes_the_execution_time_of_tasks_to_packages() {
+    register();
+    let times = enable_compile_time_report();
+    times.clear();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        compile("node_modules/react/index.js".into()).await?;
+        compile("src/index.js".into()).await?;
+        // Cached results don't count.
+        compile("node_modules/react/index.js".into()).await?;
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+
+    let report = times.report();
+    let packages = report
+        .packages
+        .iter()
+        .map(|(name, time)| (name.as_str(), time))
+        .collect::<Vec<_>>();
+    assert_eq!(packages.len(), 2);
+    for name in ["react", PROJECT_PACKAGE_NAME] {
+        let (_, time) = packages
+            .iter()
+            .find(|(package, _)| *package == name)
+            .unwrap();
+        assert_eq!(time.modules, 1);
+        // The time waiting for the other task isn't part of the compile time.
+        assert!(
+            time.parse >= Duration::from_millis(20) && time.parse < Duration::from_millis(100),
+            "{name}: {:?}",
+            time.parse
+        );
+        assert_eq!(time.transform, Duration::ZERO);
+        assert_eq!(time.code_generation, Duration::ZERO);
+    }
+}
@@ -25,8 +25,8 @@ use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{ChunkingContext, MinifyType},
     issue::{
-        Issue, IssueExt, IssueSource, IssueStage, OptionIssueSource, OptionStyledString,
-        StyledString,
+        Issue, IssueCode, IssueExt, IssueSource, IssueStage, OptionIssueCode, OptionIssueSource,
+        OptionStyledString, StyledString,
     },
     reference::ModuleReferences,
     reference_type::ImportContext,
@@ -678,6 +678,11 @@ struct ParsingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ParsingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::CSS_PARSING_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.file
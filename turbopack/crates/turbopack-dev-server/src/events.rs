@@ -0,0 +1,391 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Result;
+use futures::{stream, Stream};
+use parking_lot::Mutex;
+use serde_json::json;
+use tokio::sync::broadcast::{self, error::RecvError};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    Progress, RawVc, ReadRef, TransientInstance, TransientValue, TryJoinIterExt, Value, Vc,
+};
+use turbo_tasks_bytes::Bytes;
+use turbopack_core::{
+    introspect::Introspectable,
+    issue::{CapturedIssues, IssueReporter, IssueSeverity, PlainIssue},
+};
+use turbopack_ecmascript_hmr_protocol::Issue;
+
+use crate::source::{
+    route_tree::{RouteTree, RouteType},
+    Body, BodyChunk, ContentSource, ContentSourceContent, ContentSourceData, ContentSourceDataVary,
+    GetContentSourceContent, ProxyResult,
+};
+
+/// The path hosts mount the [BuildEventsContentSource] at, relative to the
+/// root of the dev server.
+pub const BUILD_EVENTS_PATH: &str = "turbopack-events";
+
+/// How many events are buffered for a client before it misses events.
+const CAPACITY: usize = 64;
+
+/// A build lifecycle event, see [BuildEvents].
+#[derive(Clone, Debug)]
+pub enum BuildEvent {
+    /// Tasks started executing, e.g. after a file change.
+    Compiling,
+    /// All tasks finished executing.
+    Compiled { duration: Duration, tasks: usize },
+    /// The issues reported by any request changed. Contains all current
+    /// issues.
+    IssuesChanged { issues: Vec<ReadRef<PlainIssue>> },
+    /// A long-running task reported its progress, see [ProgressReporter].
+    ///
+    /// [ProgressReporter]: turbo_tasks::ProgressReporter
+    Progress { task: String, progress: Progress },
+}
+
+impl BuildEvent {
+    /// Formats the event as a server-sent event, with the kind of the event as
+    /// its name and a JSON payload.
+    fn to_server_sent_event(&self) -> Result<String> {
+        let (name, data) = match self {
+            BuildEvent::Compiling => ("compiling", json!({})),
+            BuildEvent::Compiled { duration, tasks } => (
+                "compiled",
+                json!({ "durationMs": duration.as_millis() as u64, "tasks": tasks }),
+            ),
+            BuildEvent::IssuesChanged { issues } => (
+                "issues-changed",
+                json!({
+                    "issues": issues
+                        .iter()
+                        .map(|issue| Issue::from(&**issue))
+                        .collect::<Vec<_>>()
+                }),
+            ),
+            BuildEvent::Progress { task, progress } => (
+                "progress",
+                json!({
+                    "task": task,
+                    "completed": progress.completed,
+                    "total": progress.total,
+                    "message": progress.message.as_deref(),
+                }),
+            ),
+        };
+        Ok(format!(
+            "event: {name}\ndata: {}\n\n",
+            serde_json::to_string(&data)?
+        ))
+    }
+}
+
+/// Streams build lifecycle events to clients of the
+/// [BuildEventsContentSource] as server-sent events, so CLIs and IDEs can
+/// follow the build without implementing the HMR websocket protocol.
+///
+/// Issues are collected by the dev server. [BuildEvent::Compiling] and
+/// [BuildEvent::Compiled] are published by the host, which knows when
+/// compilations start and finish.
+#[derive(Clone, Debug)]
+pub struct BuildEvents {
+    sender: broadcast::Sender<BuildEvent>,
+    /// The issues last reported for every source, with their hashes.
+    issues: Arc<Mutex<HashMap<RawVc, Vec<(u64, ReadRef<PlainIssue>)>>>>,
+}
+
+impl Default for BuildEvents {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+            issues: Default::default(),
+        }
+    }
+}
+
+impl BuildEvents {
+    /// Sends the event to all connected clients.
+    pub fn publish(&self, event: BuildEvent) {
+        // Sending only fails when no client is connected.
+        let _ = self.sender.send(event);
+    }
+
+    fn current_issues(&self) -> Vec<ReadRef<PlainIssue>> {
+        let issues = self.issues.lock();
+        let mut seen = HashSet::new();
+        issues
+            .values()
+            .flatten()
+            .filter(|(hash, _)| seen.insert(*hash))
+            .map(|(_, issue)| issue.clone())
+            .collect()
+    }
+
+    fn update_issues(&self, source: RawVc, issues: Vec<(u64, ReadRef<PlainIssue>)>) {
+        let changed = {
+            let mut all_issues = self.issues.lock();
+            let previous = all_issues
+                .get(&source)
+                .map_or(&[][..], |issues| &issues[..]);
+            let changed = previous.len() != issues.len()
+                || previous
+                    .iter()
+                    .zip(issues.iter())
+                    .any(|((a, _), (b, _))| a != b);
+            if issues.is_empty() {
+                all_issues.remove(&source);
+            } else {
+                all_issues.insert(source, issues);
+            }
+            changed
+        };
+        if changed {
+            self.publish(BuildEvent::IssuesChanged {
+                issues: self.current_issues(),
+            });
+        }
+    }
+
+    /// Wraps the issue reporter to collect the issues of every request.
+    pub(crate) fn issue_reporter(
+        &self,
+        inner: Vc<Box<dyn IssueReporter>>,
+    ) -> Vc<Box<dyn IssueReporter>> {
+        Vc::upcast(
+            BuildEventsIssueReporter {
+                inner,
+                events: self.clone(),
+            }
+            .cell(),
+        )
+    }
+
+    /// The server-sent events for a client until it disconnects, starting
+    /// with the current issues.
+    fn server_sent_events(&self) -> impl Stream<Item = BodyChunk> + Send + 'static {
+        let initial = BuildEvent::IssuesChanged {
+            issues: self.current_issues(),
+        };
+        let receiver = self.sender.subscribe();
+        stream::unfold(
+            (Some(initial), receiver),
+            |(mut initial, mut receiver)| async move {
+                let event = match initial.take() {
+                    Some(event) => event,
+                    None => loop {
+                        match receiver.recv().await {
+                            Ok(event) => break event,
+                            // The client was too slow, continue with the newest events.
+                            Err(RecvError::Lagged(_)) => continue,
+                            Err(RecvError::Closed) => return None,
+                        }
+                    },
+                };
+                let message = event.to_server_sent_event().unwrap_or_else(|err| {
+                    format!("event: error\ndata: {}\n\n", json!(err.to_string()))
+                });
+                Some((Ok(Bytes::from(message)), (None, receiver)))
+            },
+        )
+    }
+}
+
+/// Streams [BuildEvents] to every client as server-sent events. Hosts mount it
+/// at [BUILD_EVENTS_PATH].
+#[turbo_tasks::value(serialization = "none", eq = "manual")]
+pub struct BuildEventsContentSource {
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    events: BuildEvents,
+}
+
+impl PartialEq for BuildEventsContentSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.events.issues, &other.events.issues)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl BuildEventsContentSource {
+    #[turbo_tasks::function]
+    pub fn new(events: TransientInstance<BuildEvents>) -> Vc<Self> {
+        BuildEventsContentSource {
+            events: (*events).clone(),
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for BuildEventsContentSource {
+    #[turbo_tasks::function]
+    fn get_routes(self: Vc<Self>) -> Vc<RouteTree> {
+        RouteTree::new_route(Vec::new(), RouteType::Exact, Vc::upcast(self))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for BuildEventsContentSource {
+    #[turbo_tasks::function]
+    fn vary(&self) -> Vc<ContentSourceDataVary> {
+        // Every client subscribes to its own stream.
+        ContentSourceDataVary {
+            cache_buster: true,
+            ..Default::default()
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn get(&self, _path: RcStr, _data: Value<ContentSourceData>) -> Vc<ContentSourceContent> {
+        ContentSourceContent::HttpProxy(
+            ProxyResult {
+                status: 200,
+                headers: vec![
+                    ("content-type".into(), "text/event-stream".into()),
+                    ("cache-control".into(), "no-cache".into()),
+                ],
+                body: Body::from_stream(Box::pin(self.events.server_sent_events())),
+            }
+            .resolved_cell(),
+        )
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for BuildEventsContentSource {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell("build events content source".into())
+    }
+}
+
+#[turbo_tasks::value(serialization = "none", eq = "manual")]
+struct BuildEventsIssueReporter {
+    inner: Vc<Box<dyn IssueReporter>>,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    events: BuildEvents,
+}
+
+impl PartialEq for BuildEventsIssueReporter {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && Arc::ptr_eq(&self.events.issues, &other.events.issues)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl IssueReporter for BuildEventsIssueReporter {
+    #[turbo_tasks::function]
+    async fn report_issues(
+        &self,
+        issues: TransientInstance<CapturedIssues>,
+        source: TransientValue<RawVc>,
+        min_failing_severity: Vc<IssueSeverity>,
+    ) -> Result<Vc<bool>> {
+        let plain_issues = issues
+            .iter_with_shortest_path()
+            .map(|(issue, path)| async move {
+                let plain_issue = issue.into_plain(path);
+                let hash = plain_issue.internal_hash(false).await?;
+                Ok((*hash, plain_issue.await?))
+            })
+            .try_join()
+            .await?;
+        self.events.update_issues(*source, plain_issues);
+        Ok(self
+            .inner
+            .report_issues(issues, source, min_failing_severity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use futures::TryStreamExt;
+    use turbo_tasks::{TransientInstance, TurboTasks, Value, Vc};
+    use turbo_tasks_bytes::StreamRead;
+    use turbo_tasks_memory::MemoryBackend;
+
+    use super::{BuildEvent, BuildEvents, BuildEventsContentSource};
+    use crate::source::{
+        BodyChunk, ContentSourceContent, ContentSourceData, GetContentSourceContent,
+    };
+
+    /// Connects a client, which is told apart from other clients by its cache buster.
+    async fn connect(
+        source: Vc<Box<dyn GetContentSourceContent>>,
+        cache_buster: u64,
+    ) -> Result<StreamRead<BodyChunk>> {
+        let content = source
+            .get(
+                "".into(),
+                Value::new(ContentSourceData {
+                    cache_buster,
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let ContentSourceContent::HttpProxy(proxy) = &*content else {
+            anyhow::bail!("expected a stream of events");
+        };
+        let proxy = proxy.await?;
+        assert_eq!(proxy.status, 200);
+        assert!(proxy
+            .headers
+            .contains(&("content-type".into(), "text/event-stream".into())));
+        Ok(proxy.body.read())
+    }
+
+    async fn next_event(events: &mut StreamRead<BodyChunk>) -> Result<String> {
+        let event = events.try_next().await?.expect("the stream is still open");
+        Ok(String::from_utf8(event.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn streams_events_to_every_client() {
+        crate::register();
+        let events = BuildEvents::default();
+
+        let tt = TurboTasks::new(MemoryBackend::default());
+        tt.run_once(async move {
+            let source = Vc::upcast::<Box<dyn GetContentSourceContent>>(
+                BuildEventsContentSource::new(TransientInstance::new(events.clone())),
+            );
+            assert!(source.vary().await?.cache_buster);
+
+            // Every client starts with the current issues.
+            let mut first = connect(source, 1).await?;
+            let mut second = connect(source, 2).await?;
+            for client in [&mut first, &mut second] {
+                assert_eq!(
+                    next_event(client).await?,
+                    "event: issues-changed\ndata: {\"issues\":[]}\n\n"
+                );
+            }
+
+            events.publish(BuildEvent::Compiling);
+            events.publish(BuildEvent::Compiled {
+                duration: Duration::from_millis(12),
+                tasks: 3,
+            });
+            for client in [&mut first, &mut second] {
+                assert_eq!(next_event(client).await?, "event: compiling\ndata: {}\n\n");
+                assert_eq!(
+                    next_event(client).await?,
+                    "event: compiled\ndata: {\"durationMs\":12,\"tasks\":3}\n\n"
+                );
+            }
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
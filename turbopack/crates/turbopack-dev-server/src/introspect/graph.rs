@@ -0,0 +1,127 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    introspect::{introspect, CellIntrospection},
+    FxIndexMap, ResolvedVc, Vc,
+};
+use turbo_tasks_fs::File;
+use turbopack_core::{
+    asset::AssetContent, introspect::Introspectable, version::VersionedContentExt,
+};
+
+use crate::source::{
+    route_tree::{RouteTree, RouteTrees, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, GetContentSourceContent,
+};
+
+/// Serves the graph of cells reachable from the roots as JSON, for devtools which inspect it
+/// externally. Unlike [`IntrospectionSource`][super::IntrospectionSource], it works for every
+/// value, see [`turbo_tasks::introspect`].
+///
+/// `GET /` lists the roots. `GET /<root>/<child>/<child>...` returns the cell reached by
+/// following the children with the given indices, starting at the root with the given index:
+///
+/// ```json
+/// {
+///   "path": [0, 3],
+///   "cell": { "TaskCell": [12, { "type_id": "...", "index": 0 }] },
+///   "valueType": "...",
+///   "displayName": "AssetIdent",
+///   "estimatedSize": 120,
+///   "children": [{ "TaskOutput": 15 }]
+/// }
+/// ```
+///
+/// Children are only identified, not read, so listing them doesn't compute them. Indices are
+/// only stable as long as the values don't change.
+#[turbo_tasks::value(shared)]
+pub struct IntrospectionGraphSource {
+    pub roots: FxIndexMap<RcStr, ResolvedVc<Box<dyn Introspectable>>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphRoot<'a> {
+    index: usize,
+    name: &'a str,
+    #[serde(flatten)]
+    cell: CellIntrospection,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphNode<'a> {
+    path: &'a [usize],
+    #[serde(flatten)]
+    cell: CellIntrospection,
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for IntrospectionGraphSource {
+    #[turbo_tasks::function]
+    async fn get_routes(self: Vc<Self>) -> Result<Vc<RouteTree>> {
+        Ok(Vc::<RouteTrees>::cell(vec![
+            RouteTree::new_route(Vec::new(), RouteType::Exact, Vc::upcast(self))
+                .to_resolved()
+                .await?,
+            RouteTree::new_route(Vec::new(), RouteType::CatchAll, Vc::upcast(self))
+                .to_resolved()
+                .await?,
+        ])
+        .merge())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for IntrospectionGraphSource {
+    #[turbo_tasks::function]
+    async fn get(
+        &self,
+        path: RcStr,
+        _data: turbo_tasks::Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let path = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                segment
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid child index {segment:?}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let json = if let Some((&root, children)) = path.split_first() {
+            let Some((_, &root)) = self.roots.get_index(root) else {
+                bail!("There is no root with index {root}");
+            };
+            let mut cell = introspect(Vc::into_raw(*root)).await?;
+            for &child in children {
+                let Some(&child) = cell.children.get(child) else {
+                    bail!("There is no child with index {child} at {:?}", cell.cell);
+                };
+                cell = introspect(child).await?;
+            }
+            serde_json::to_string(&GraphNode { path: &path, cell })?
+        } else {
+            let mut roots = Vec::with_capacity(self.roots.len());
+            for (index, (name, root)) in self.roots.iter().enumerate() {
+                roots.push(GraphRoot {
+                    index,
+                    name,
+                    cell: introspect(Vc::into_raw(**root)).await?,
+                });
+            }
+            serde_json::to_string(&roots)?
+        };
+
+        Ok(ContentSourceContent::static_content(
+            AssetContent::file(
+                File::from(json)
+                    .with_content_type(mime::APPLICATION_JSON)
+                    .into(),
+            )
+            .versioned(),
+        ))
+    }
+}
@@ -1,3 +1,5 @@
+pub mod graph;
+
 use std::{borrow::Cow, collections::HashSet, fmt::Display};
 
 use anyhow::Result;
@@ -6,6 +6,7 @@
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
+pub mod events;
 pub mod html;
 mod http;
 pub mod introspect;
@@ -41,7 +42,7 @@ use turbopack_core::{
     issue::{handle_issues, IssueReporter, IssueSeverity},
 };
 
-use self::{source::ContentSource, update::UpdateServer};
+use self::{events::BuildEvents, source::ContentSource, update::UpdateServer};
 use crate::{
     invalidation::{ServerRequest, ServerRequestSideEffects},
     source::ContentSourceSideEffect,
@@ -67,6 +68,10 @@ pub struct DevServerBuilder {
     pub addr: SocketAddr,
     #[turbo_tasks(trace_ignore)]
     server: Builder<AddrIncoming>,
+    /// Streamed to clients of the [events::BuildEventsContentSource], which
+    /// the host mounts in its source at [events::BUILD_EVENTS_PATH].
+    #[turbo_tasks(trace_ignore)]
+    pub build_events: BuildEvents,
 }
 
 #[derive(TraceRawVcs)]
@@ -75,6 +80,10 @@ pub struct DevServer {
     pub addr: SocketAddr,
     #[turbo_tasks(trace_ignore)]
     pub future: Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
+    /// Streamed to clients of the [events::BuildEventsContentSource]. The
+    /// host publishes when compilations start and finish.
+    #[turbo_tasks(trace_ignore)]
+    pub build_events: BuildEvents,
 }
 
 impl DevServer {
@@ -108,7 +117,11 @@ impl DevServer {
             .local_addr()
             .context("not able to get bound address")?;
         let server = Server::from_tcp(listener).context("Not able to start server")?;
-        Ok(DevServerBuilder { addr, server })
+        Ok(DevServerBuilder {
+            addr,
+            server,
+            build_events: BuildEvents::default(),
+        })
     }
 }
 
@@ -119,6 +132,11 @@ impl DevServerBuilder {
         source_provider: impl SourceProvider + Sync,
         get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync>,
     ) -> DevServer {
+        let build_events = self.build_events;
+        let get_issue_reporter: Arc<dyn Fn() -> Vc<Box<dyn IssueReporter>> + Send + Sync> = {
+            let build_events = build_events.clone();
+            Arc::new(move || build_events.issue_reporter(get_issue_reporter()))
+        };
         let ongoing_side_effects = Arc::new(Mutex::new(VecDeque::<
             Arc<tokio::sync::Mutex<Option<JoinHandle<Result<()>>>>>,
         >::with_capacity(16)));
@@ -288,6 +306,7 @@ impl DevServerBuilder {
                 server.await?;
                 Ok(())
             }),
+            build_events,
         }
     }
 }
@@ -4,9 +4,11 @@ pub mod conditional;
 pub mod headers;
 pub mod issue_context;
 pub mod lazy_instantiated;
+pub mod original_source;
 pub mod query;
 pub mod request;
 pub(crate) mod resolve;
+pub mod resolve_trace;
 pub mod route_tree;
 pub mod router;
 pub mod static_assets;
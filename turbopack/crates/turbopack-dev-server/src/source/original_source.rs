@@ -0,0 +1,286 @@
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{glob::Glob, util::normalize_path, FileSystemPath};
+use turbopack_core::{
+    asset::Asset,
+    file_source::FileSource,
+    introspect::{Introspectable, IntrospectableChildren},
+    version::VersionedContentExt,
+};
+
+use super::{
+    route_tree::{BaseSegment, RouteTree, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, GetContentSourceContent, HeaderList,
+};
+
+/// Serves the original source files which source maps refer to, so browser
+/// devtools and error overlays can show them even when the source map doesn't
+/// contain their content.
+///
+/// Source maps refer to files as `turbopack:///[<file system name>]/<path>`,
+/// and the files are served at `<prefix>[<file system name>]/<path>`. Files of
+/// virtual file systems, e.g. generated entry modules, are served as well.
+///
+/// Only files inside of one of the `roots` are served, without following
+/// symlinks out of them, and only when their path relative to the root
+/// matches the `allowlist`, so files like `.env` can't be read.
+#[turbo_tasks::value(shared)]
+pub struct OriginalSourceContentSource {
+    pub prefix: ResolvedVc<RcStr>,
+    pub roots: Vec<ResolvedVc<FileSystemPath>>,
+    pub allowlist: ResolvedVc<Glob>,
+}
+
+#[turbo_tasks::value_impl]
+impl OriginalSourceContentSource {
+    #[turbo_tasks::function]
+    pub async fn new(
+        prefix: ResolvedVc<RcStr>,
+        roots: Vec<ResolvedVc<FileSystemPath>>,
+        allowlist: ResolvedVc<Glob>,
+    ) -> Result<Vc<Self>> {
+        if cfg!(debug_assertions) {
+            let prefix_string = prefix.await?;
+            debug_assert!(prefix_string.is_empty() || prefix_string.ends_with('/'));
+            debug_assert!(!prefix_string.starts_with('/'));
+        }
+        Ok(OriginalSourceContentSource {
+            prefix,
+            roots,
+            allowlist,
+        }
+        .cell())
+    }
+
+    /// Finds the file for a path like `[project]/src/index.ts`.
+    #[turbo_tasks::function]
+    async fn find_file(&self, path: RcStr) -> Result<Vc<OptionFileSystemPath>> {
+        let Some((fs_name, path)) = path
+            .strip_prefix('[')
+            .and_then(|path| path.split_once("]/"))
+        else {
+            return Ok(Vc::cell(None));
+        };
+        for root in &self.roots {
+            let root_value = root.await?;
+            if root_value.fs.to_string().await?.as_str() != fs_name {
+                continue;
+            }
+            let Some(relative) = relative_to_root(&root_value.path, path) else {
+                continue;
+            };
+            if !self.allowlist.await?.execute(&relative) {
+                continue;
+            }
+            let Some(file) = *root.try_join_inside(relative.into()).await? else {
+                continue;
+            };
+            // Symlinks must not lead out of the root.
+            let real = file.realpath().await?;
+            if !real.is_inside_ref(&root_value) {
+                continue;
+            }
+            return Ok(Vc::cell(Some(file)));
+        }
+        Ok(Vc::cell(None))
+    }
+}
+
+/// Returns the normalized `path` relative to `root`, both relative to the root
+/// of the file system, or `None` when it's outside of `root`. `..` segments are
+/// resolved first, so they can't be used to get around the allowlist.
+fn relative_to_root(root: &str, path: &str) -> Option<String> {
+    let path = normalize_path(path)?;
+    if root.is_empty() {
+        return Some(path);
+    }
+    let relative = path.strip_prefix(root)?.strip_prefix('/')?;
+    Some(relative.to_string())
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionFileSystemPath(Option<ResolvedVc<FileSystemPath>>);
+
+#[turbo_tasks::value_impl]
+impl ContentSource for OriginalSourceContentSource {
+    #[turbo_tasks::function]
+    async fn get_routes(self: Vc<Self>) -> Result<Vc<RouteTree>> {
+        let prefix = self.await?.prefix.await?;
+        Ok(RouteTree::new_route(
+            BaseSegment::from_static_pathname(prefix.as_str()).collect(),
+            RouteType::CatchAll,
+            Vc::upcast(self),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for OriginalSourceContentSource {
+    #[turbo_tasks::function]
+    async fn get(
+        self: Vc<Self>,
+        path: RcStr,
+        _data: Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let prefix = self.await?.prefix.await?;
+        let Some(path) = path.strip_prefix(prefix.as_str()) else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let Some(file) = *self.find_file(path.into()).await? else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let content = Vc::upcast::<Box<dyn Asset>>(FileSource::new(*file)).content();
+        // Sources are shown as text, whatever their extension is.
+        Ok(ContentSourceContent::static_with_headers(
+            content.versioned(),
+            200,
+            HeaderList::new(vec![
+                ("content-type".into(), "text/plain; charset=utf-8".into()),
+                ("x-content-type-options".into(), "nosniff".into()),
+            ]),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for OriginalSourceContentSource {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell("original source content source".into())
+    }
+
+    #[turbo_tasks::function]
+    async fn details(&self) -> Result<Vc<RcStr>> {
+        let mut details = format!("prefix: {}\nroots:", self.prefix.await?);
+        for root in &self.roots {
+            details.push_str(&format!("\n  {}", root.to_string().await?));
+        }
+        Ok(Vc::cell(details.into()))
+    }
+
+    #[turbo_tasks::function]
+    fn children(&self) -> Vc<IntrospectableChildren> {
+        Vc::cell(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use turbo_rcstr::RcStr;
+    use turbo_tasks::{TurboTasks, Value, Vc};
+    use turbo_tasks_fs::{glob::Glob, DiskFileSystem, FileContent, FileSystem};
+    use turbo_tasks_memory::MemoryBackend;
+    use turbopack_core::version::VersionedContent;
+
+    use super::{relative_to_root, OriginalSourceContentSource};
+    use crate::source::{ContentSourceContent, GetContentSourceContent};
+
+    /// Returns the served text, or `None` when the file isn't served.
+    async fn get(source: Vc<OriginalSourceContentSource>, path: &str) -> Result<Option<String>> {
+        let content = Vc::upcast::<Box<dyn GetContentSourceContent>>(source)
+            .get(path.into(), Value::new(Default::default()))
+            .await?;
+        match &*content {
+            ContentSourceContent::NotFound => Ok(None),
+            ContentSourceContent::Static(content) => {
+                let content = content.await?;
+                assert_eq!(content.status_code, 200);
+                let FileContent::Content(file) = &*content.content.content().file_content().await?
+                else {
+                    return Ok(None);
+                };
+                Ok(Some(file.content().to_str()?.into_owned()))
+            }
+            _ => panic!("unexpected content for {path}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_only_allowed_files_inside_of_the_roots() {
+        crate::register();
+        let dir = std::env::temp_dir().join("turbopack-dev-server-original-source");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("app/src")).unwrap();
+        fs::write(dir.join("app/src/index.ts"), "export {};\n").unwrap();
+        fs::write(dir.join("app/.env"), "SECRET=1\n").unwrap();
+        fs::write(dir.join("secret.ts"), "export const secret = 1;\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("../../secret.ts", dir.join("app/src/link.ts")).unwrap();
+
+        let tt = TurboTasks::new(MemoryBackend::default());
+        tt.run_once(async move {
+            let root =
+                DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+            let source = OriginalSourceContentSource::new(
+                Vc::cell(RcStr::from("__turbopack_sources__/")),
+                vec![root.join("app".into()).to_resolved().await?],
+                Glob::new("**/*.{js,ts}".into()).to_resolved().await?,
+            );
+
+            assert_eq!(
+                get(source, "__turbopack_sources__/[project]/app/src/index.ts")
+                    .await?
+                    .as_deref(),
+                Some("export {};\n")
+            );
+            // Other prefixes and file systems aren't served.
+            assert_eq!(get(source, "[project]/app/src/index.ts").await?, None);
+            assert_eq!(
+                get(source, "__turbopack_sources__/[other]/app/src/index.ts").await?,
+                None
+            );
+            // Neither are files outside of the allowlist or the roots.
+            assert_eq!(
+                get(source, "__turbopack_sources__/[project]/app/.env").await?,
+                None
+            );
+            assert_eq!(
+                get(source, "__turbopack_sources__/[project]/secret.ts").await?,
+                None
+            );
+            assert_eq!(
+                get(
+                    source,
+                    "__turbopack_sources__/[project]/app/src/../../secret.ts"
+                )
+                .await?,
+                None
+            );
+            #[cfg(unix)]
+            assert_eq!(
+                get(source, "__turbopack_sources__/[project]/app/src/link.ts").await?,
+                None
+            );
+
+            anyhow::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn relative_to_root_resolves_parent_segments() {
+        assert_eq!(
+            relative_to_root("", "src/../index.ts").as_deref(),
+            Some("index.ts")
+        );
+        assert_eq!(
+            relative_to_root("app", "app/src/./index.ts").as_deref(),
+            Some("src/index.ts")
+        );
+        assert_eq!(
+            relative_to_root("app", "app/src/../../.env").as_deref(),
+            None
+        );
+        assert_eq!(relative_to_root("app", "app/../app2/.env").as_deref(), None);
+        assert_eq!(
+            relative_to_root("app", "application/index.ts").as_deref(),
+            None
+        );
+        assert_eq!(relative_to_root("", "../.env").as_deref(), None);
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{FxIndexMap, ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    context::AssetContext,
+    introspect::{Introspectable, IntrospectableChildren},
+    resolve::{
+        parse::Request,
+        trace::{trace_reference_type, trace_resolve},
+    },
+    version::VersionedContentExt,
+};
+
+use super::{
+    query::QueryValue,
+    route_tree::{RouteTree, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, ContentSourceDataFilter,
+    ContentSourceDataVary, GetContentSourceContent,
+};
+
+/// Traces how a request is resolved, for debugging "module not found" errors.
+///
+/// Requests look like `?environment=<environment>&specifier=<specifier>&importer=<importer>`
+/// with an optional `kind` of `import` (the default), `require` or `url`, where `importer` is
+/// a file path relative to `root`. The response is the JSON of the
+/// [ResolveTrace][turbopack_core::resolve::trace::ResolveTrace]. Without a specifier the
+/// environments are listed.
+#[turbo_tasks::value(shared)]
+pub struct ResolveTraceContentSource {
+    /// The asset contexts whose resolve options are traced, by the name of their environment.
+    pub environments: FxIndexMap<RcStr, ResolvedVc<Box<dyn AssetContext>>>,
+    /// The directory importers are relative to.
+    pub root: ResolvedVc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for ResolveTraceContentSource {
+    #[turbo_tasks::function]
+    fn get_routes(self: Vc<Self>) -> Vc<RouteTree> {
+        RouteTree::new_route(Vec::new(), RouteType::Exact, Vc::upcast(self))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for ResolveTraceContentSource {
+    #[turbo_tasks::function]
+    fn vary(&self) -> Vc<ContentSourceDataVary> {
+        ContentSourceDataVary {
+            query: Some(ContentSourceDataFilter::All),
+            ..Default::default()
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn get(
+        &self,
+        _path: RcStr,
+        data: Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let param = |name: &str| match data.query.as_ref()?.get(name) {
+            Some(QueryValue::String(value)) => Some(value.as_str()),
+            _ => None,
+        };
+
+        let json = if let Some(specifier) = param("specifier") {
+            let environment = match param("environment") {
+                Some(environment) => environment,
+                None => self
+                    .environments
+                    .keys()
+                    .next()
+                    .context("There are no environments to trace resolves in")?,
+            };
+            let asset_context = self
+                .environments
+                .get(environment)
+                .with_context(|| format!("Unknown environment {environment:?}"))?;
+            let importer = self
+                .root
+                .join(param("importer").unwrap_or("index.js").into());
+            let kind = param("kind");
+            let reference_type = trace_reference_type(kind)
+                .with_context(|| format!("Unknown reference kind {kind:?}"))?;
+            let trace = trace_resolve(
+                **asset_context,
+                importer,
+                Request::parse_string(specifier.into()),
+                Value::new(reference_type),
+            )
+            .await?;
+            serde_json::to_string(&*trace)?
+        } else {
+            serde_json::to_string(&self.environments.keys().collect::<Vec<_>>())?
+        };
+
+        Ok(ContentSourceContent::static_content(
+            AssetContent::file(
+                File::from(json)
+                    .with_content_type(mime::APPLICATION_JSON)
+                    .into(),
+            )
+            .versioned(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for ResolveTraceContentSource {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell("resolve trace content source".into())
+    }
+
+    #[turbo_tasks::function]
+    async fn details(&self) -> Result<Vc<RcStr>> {
+        let environments = self
+            .environments
+            .keys()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(Vc::cell(
+            format!(
+                "environments: {environments}\nroot: {}",
+                self.root.to_string().await?
+            )
+            .into(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn children(&self) -> Vc<IntrospectableChildren> {
+        Vc::cell(Default::default())
+    }
+}
@@ -11,8 +11,8 @@ use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack_core::{
     error::PrettyPrintError,
     issue::{
-        Issue, IssueDescriptionExt, IssueSeverity, IssueStage, OptionIssueProcessingPathItems,
-        OptionStyledString, PlainIssue, StyledString,
+        Issue, IssueCode, IssueDescriptionExt, IssueSeverity, IssueStage, OptionIssueCode,
+        OptionIssueProcessingPathItems, OptionStyledString, PlainIssue, StyledString,
     },
     server_fs::ServerFileSystem,
     version::{
@@ -288,6 +288,11 @@ struct FatalStreamIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for FatalStreamIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::UPDATE_STREAM_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Fatal.into()
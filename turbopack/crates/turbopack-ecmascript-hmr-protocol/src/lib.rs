@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use turbopack_cli_utils::issue::{format_issue, LogOptions};
 use turbopack_core::{
-    issue::{IssueSeverity, IssueStage, PlainIssue, StyledString},
+    issue::{
+        IssueSeverity, IssueStage, PlainIssue, PlainIssueSource, PlainSuggestedFix, StyledString,
+    },
     source_pos::SourcePos,
 };
 
@@ -125,6 +127,23 @@ pub struct Asset<'a> {
 pub struct IssueSource<'a> {
     pub asset: Asset<'a>,
     pub range: Option<IssueSourceRange>,
+    pub label: Option<&'a str>,
+    pub related: Vec<IssueSource<'a>>,
+}
+
+impl<'a> From<&'a PlainIssueSource> for IssueSource<'a> {
+    fn from(source: &'a PlainIssueSource) -> Self {
+        IssueSource {
+            asset: Asset {
+                path: &source.asset.ident,
+            },
+            range: source
+                .range
+                .map(|(start, end)| IssueSourceRange { start, end }),
+            label: source.label.as_deref(),
+            related: source.related.iter().map(|r| r.deref().into()).collect(),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -136,8 +155,10 @@ pub struct IssueSourceRange {
 #[derive(Serialize)]
 pub struct Issue<'a> {
     pub severity: IssueSeverity,
+    pub code: Option<String>,
     pub file_path: &'a str,
     pub stage: &'a IssueStage,
+    pub transform: Option<&'a str>,
 
     pub title: &'a StyledString,
     pub description: Option<&'a StyledString>,
@@ -146,31 +167,28 @@ pub struct Issue<'a> {
 
     pub source: Option<IssueSource<'a>>,
     pub sub_issues: Vec<Issue<'a>>,
+    pub suggested_fix: Option<&'a PlainSuggestedFix>,
 
     pub formatted: String,
 }
 
 impl<'a> From<&'a PlainIssue> for Issue<'a> {
     fn from(plain: &'a PlainIssue) -> Self {
-        let source = plain.source.as_deref().map(|source| IssueSource {
-            asset: Asset {
-                path: &source.asset.ident,
-            },
-            range: source
-                .range
-                .map(|(start, end)| IssueSourceRange { start, end }),
-        });
+        let source = plain.source.as_deref().map(IssueSource::from);
 
         Issue {
             severity: plain.severity,
+            code: plain.code.map(|code| code.to_string()),
             file_path: &plain.file_path,
             stage: &plain.stage,
+            transform: plain.transform.as_deref(),
             title: &plain.title,
             description: plain.description.as_ref(),
             documentation_link: &plain.documentation_link,
             detail: plain.detail.as_ref(),
             source,
             sub_issues: plain.sub_issues.iter().map(|p| p.deref().into()).collect(),
+            suggested_fix: plain.suggested_fix.as_deref(),
             // TODO(WEB-691) formatting the issue should be handled by the error overlay.
             // The browser could handle error formatting in a better way than the text only
             // formatting here
@@ -80,6 +80,13 @@ const moduleHotState: Map<Module, HotState> = new Map();
  * Modules that call `module.hot.invalidate()` (while being updated).
  */
 const queuedInvalidatedModules: Set<ModuleId> = new Set();
+/**
+ * Maps module IDs to whether they can be React Refresh boundaries, as
+ * determined when the module was compiled. Only the exports of modules which
+ * can be boundaries, or which are missing from this map, are checked at
+ * runtime.
+ */
+const moduleRefreshBoundaries: Map<ModuleId, RefreshBoundaryInfo> = new Map();
 
 /**
  * Gets or instantiates a runtime module.
@@ -286,9 +293,15 @@ function registerExportsAndSetupBoundaryForReactRefresh(
 
   helpers.registerExportsForReactRefresh(currentExports, module.id);
 
+  // The names of the exports can only tell that a module isn't a boundary, the
+  // values of the exports are authoritative.
+  const isBoundary =
+    moduleRefreshBoundaries.get(module.id)?.isBoundary !== false &&
+    helpers.isReactRefreshBoundary(currentExports);
+
   // A module can be accepted automatically based on its exports, e.g. when
   // it is a Refresh Boundary.
-  if (helpers.isReactRefreshBoundary(currentExports)) {
+  if (isBoundary) {
     // Save the previous exports on update, so we can compare the boundary
     // signatures.
     module.hot.dispose((data) => {
@@ -348,6 +361,7 @@ function computeOutdatedModules(
   for (const [moduleId, entry] of added) {
     if (entry != null) {
       newModuleFactories.set(moduleId, _eval(entry));
+      setRefreshBoundary(moduleId, entry);
     }
   }
 
@@ -355,11 +369,37 @@ function computeOutdatedModules(
 
   for (const [moduleId, entry] of modified) {
     newModuleFactories.set(moduleId, _eval(entry));
+    setRefreshBoundary(moduleId, entry);
   }
 
   return { outdatedModules, newModuleFactories };
 }
 
+function setRefreshBoundary(
+  moduleId: ModuleId,
+  entry: EcmascriptModuleEntry
+) {
+  if (entry.refresh != null) {
+    moduleRefreshBoundaries.set(moduleId, entry.refresh);
+  } else {
+    moduleRefreshBoundaries.delete(moduleId);
+  }
+}
+
+/**
+ * Registers the React Refresh boundaries of the modules of a chunk, before any
+ * of them is executed.
+ */
+function registerRefreshBoundaries(
+  refreshBoundaries: Record<ModuleId, RefreshBoundaryInfo>
+) {
+  for (const [moduleId, refreshBoundary] of Object.entries(
+    refreshBoundaries
+  )) {
+    moduleRefreshBoundaries.set(moduleId, refreshBoundary);
+  }
+}
+
 function computedInvalidatedModules(
   invalidated: Iterable<ModuleId>
 ): Set<ModuleId> {
@@ -523,6 +563,7 @@ function disposeModule(moduleId: ModuleId, mode: "clear" | "replace") {
     case "clear":
       delete devModuleCache[module.id];
       moduleHotData.delete(module.id);
+      moduleRefreshBoundaries.delete(module.id);
       break;
     case "replace":
       moduleHotData.set(module.id, data);
@@ -1114,3 +1155,12 @@ globalThis.TURBOPACK_CHUNK_LISTS = {
     registerChunkList(globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS!, chunkList);
   },
 } satisfies ChunkListProvider;
+
+const refreshBoundariesToRegister = globalThis.TURBOPACK_REFRESH_BOUNDARIES;
+if (Array.isArray(refreshBoundariesToRegister)) {
+  refreshBoundariesToRegister.forEach(registerRefreshBoundaries);
+}
+
+globalThis.TURBOPACK_REFRESH_BOUNDARIES = {
+  push: registerRefreshBoundaries,
+} satisfies RefreshBoundariesProvider;
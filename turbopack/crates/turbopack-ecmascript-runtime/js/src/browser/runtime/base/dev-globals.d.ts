@@ -15,6 +15,15 @@ declare var TURBOPACK_CHUNK_UPDATE_LISTENERS:
   | ChunkUpdateProvider
   | [ChunkPath, UpdateCallback][]
   | undefined;
+
+type RefreshBoundariesProvider = {
+  push: (refreshBoundaries: Record<ModuleId, RefreshBoundaryInfo>) => void;
+};
+
+declare var TURBOPACK_REFRESH_BOUNDARIES:
+  | RefreshBoundariesProvider
+  | Record<ModuleId, RefreshBoundaryInfo>[]
+  | undefined;
 // This is used by the Next.js integration test suite to notify it when HMR
 // updates have been completed.
 declare var __NEXT_HMR_CB: undefined | null | (() => void);
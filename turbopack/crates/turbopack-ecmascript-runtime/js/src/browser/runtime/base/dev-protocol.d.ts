@@ -89,6 +89,17 @@ type EcmascriptModuleEntry = {
   code: ModuleFactoryString;
   url: string;
   map?: string;
+  refresh?: RefreshBoundaryInfo;
+};
+
+/**
+ * Whether a module is a React Refresh boundary, as determined statically from
+ * its exports.
+ */
+type RefreshBoundaryInfo = {
+  isBoundary: boolean;
+  components: string[];
+  nonComponents: string[];
 };
 
 type ResourceIdentifier = {
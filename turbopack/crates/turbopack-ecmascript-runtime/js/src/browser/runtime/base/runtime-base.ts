@@ -205,7 +205,7 @@ async function loadChunkPath(
       default:
         invariant(source, (source) => `Unknown source type: ${source?.type}`);
     }
-    throw new Error(
+    const loadError = new Error(
       `Failed to load chunk ${chunkPath} ${loadReason}${
         error ? `: ${error}` : ""
       }`,
@@ -215,6 +215,14 @@ async function loadChunkPath(
           }
         : undefined
     );
+    if (error instanceof Error && error.name === "ChunkLoadError") {
+      // Keep the name and the properties of errors from `loadChunkWithRetry`,
+      // so applications can tell stale deployments apart.
+      loadError.name = error.name;
+      (loadError as any).chunkPath = (error as any).chunkPath;
+      (loadError as any).staleDeployment = (error as any).staleDeployment;
+    }
+    throw loadError;
   }
 }
 
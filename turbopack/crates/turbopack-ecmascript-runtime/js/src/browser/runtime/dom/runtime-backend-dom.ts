@@ -9,6 +9,7 @@
 
 /// <reference path="../../../browser/runtime/base/runtime-base.ts" />
 /// <reference path="../../../shared/runtime-types.d.ts" />
+/// <reference path="../../../shared/chunk-loader.ts" />
 
 type ChunkResolver = {
   resolved: boolean;
@@ -141,6 +142,18 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
     const chunkUrl = getChunkRelativeUrl(chunkPath);
     const decodedChunkUrl = decodeURI(chunkUrl);
 
+    if (CHUNK_LOADER_BACKEND != null) {
+      const backend = CHUNK_LOADER_BACKEND;
+      const load =
+        backend.type === "fetch"
+          ? loadChunkWithRetry(chunkPath, chunkUrl, (url) =>
+              loadChunkFromBackend(backend, chunkPath, url)
+            )
+          : loadChunkFromBackend(backend, chunkPath, chunkUrl);
+      load.catch((error) => resolver.reject(error));
+      return resolver.promise;
+    }
+
     if (typeof importScripts === "function") {
       // We're in a web worker
       if (chunkPath.endsWith(".css")) {
@@ -160,18 +173,14 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
           // loaded instantly.
           resolver.resolve();
         } else {
-          const link = document.createElement("link");
-          link.rel = "stylesheet";
-          link.href = chunkUrl;
-          link.onerror = () => {
-            resolver.reject();
-          };
-          link.onload = () => {
+          loadChunkWithRetry(chunkPath, chunkUrl, (url) =>
+            appendStylesheet(url)
+          ).then(
             // CSS chunks do not register themselves, and as such must be marked as
             // loaded instantly.
-            resolver.resolve();
-          };
-          document.body.appendChild(link);
+            () => resolver.resolve(),
+            (error) => resolver.reject(error)
+          );
         }
       } else if (chunkPath.endsWith(".js")) {
         const previousScripts = document.querySelectorAll(
@@ -186,15 +195,12 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
             });
           }
         } else {
-          const script = document.createElement("script");
-          script.src = chunkUrl;
           // We'll only mark the chunk as loaded once the script has been executed,
           // which happens in `registerChunk`. Hence the absence of `resolve()` in
           // this branch.
-          script.onerror = () => {
-            resolver.reject();
-          };
-          document.body.appendChild(script);
+          loadChunkWithRetry(chunkPath, chunkUrl, (url) =>
+            appendScript(resolver, url)
+          ).catch((error) => resolver.reject(error));
         }
       } else {
         throw new Error(`can't infer type of chunk from path ${chunkPath}`);
@@ -203,4 +209,71 @@ const chunkResolvers: Map<ChunkPath, ChunkResolver> = new Map();
 
     return resolver.promise;
   }
+
+  /**
+   * Appends a stylesheet, and returns a promise that resolves once it has been
+   * loaded. The element is removed when it fails to load, so it can be retried.
+   */
+  function appendStylesheet(url: string): Promise<void> {
+    return new Promise((resolve, reject) => {
+      const link = document.createElement("link");
+      link.rel = "stylesheet";
+      link.href = url;
+      link.onerror = () => {
+        link.remove();
+        reject(new Error(`Failed to load stylesheet ${url}`));
+      };
+      link.onload = () => resolve();
+      document.body.appendChild(link);
+    });
+  }
+
+  /**
+   * Appends a script, and returns a promise that resolves once the chunk it
+   * contains has registered itself. The element is removed when it fails to
+   * load, so it can be retried.
+   */
+  function appendScript(resolver: ChunkResolver, url: string): Promise<void> {
+    return new Promise((resolve, reject) => {
+      const script = document.createElement("script");
+      script.src = url;
+      script.onerror = () => {
+        script.remove();
+        reject(new Error(`Failed to load script ${url}`));
+      };
+      resolver.promise.then(resolve, reject);
+      document.body.appendChild(script);
+    });
+  }
+
+  /**
+   * Loads the given chunk through the chunk loader backend selected at build
+   * time instead of `<script>` and `<link>` tags.
+   */
+  async function loadChunkFromBackend(
+    backend: ChunkLoaderBackend,
+    chunkPath: ChunkPath,
+    chunkUrl: string
+  ) {
+    const resolver = getOrCreateResolver(chunkPath);
+    const contents = await readChunk(backend, chunkPath, chunkUrl);
+
+    if (chunkPath.endsWith(".css")) {
+      if (typeof document !== "undefined") {
+        const style = document.createElement("style");
+        style.textContent = contents;
+        document.head.appendChild(style);
+      }
+      // CSS chunks do not register themselves, and as such must be marked as
+      // loaded instantly.
+      resolver.resolve();
+    } else if (chunkPath.endsWith(".js")) {
+      // The chunk is marked as loaded once it registers itself, which happens
+      // synchronously during evaluation.
+      // eslint-disable-next-line no-eval
+      (0, eval)(`${contents}\n//# sourceURL=${chunkUrl}`);
+    } else {
+      throw new Error(`can't infer type of chunk from path ${chunkPath}`);
+    }
+  }
 })();
@@ -1,6 +1,7 @@
 /* eslint-disable @typescript-eslint/no-unused-vars */
 
 /// <reference path="../shared/runtime-utils.ts" />
+/// <reference path="../shared/chunk-loader.ts" />
 /// <reference path="../shared-node/base-externals-utils.ts" />
 /// <reference path="../shared-node/node-externals-utils.ts" />
 /// <reference path="../shared-node/node-wasm-utils.ts" />
@@ -61,6 +62,10 @@ const fs = require("fs/promises");
 
 const moduleFactories: ModuleFactories = Object.create(null);
 const moduleCache: ModuleCache<ModuleWithDirection> = Object.create(null);
+/**
+ * ES module chunks which have been registered with `registerChunk`.
+ */
+const registeredChunks = new Set<ChunkPath>();
 
 /**
  * Returns an absolute path to the given module's id.
@@ -94,7 +99,34 @@ function loadChunk(chunkData: ChunkData, source?: SourceInfo): void {
   }
 }
 
+/**
+ * Registers the module factories of an ES module chunk. ES module chunks can't
+ * be loaded synchronously, so entry chunks import them statically and register
+ * them before any module is instantiated.
+ */
+function registerChunk(chunkPath: ChunkPath, chunkModules: ModuleFactories) {
+  for (const [moduleId, moduleFactory] of Object.entries(chunkModules)) {
+    if (!moduleFactories[moduleId]) {
+      moduleFactories[moduleId] = moduleFactory;
+    }
+  }
+  registeredChunks.add(chunkPath);
+}
+
 function loadChunkPath(chunkPath: ChunkPath, source?: SourceInfo): void {
+  if (chunkPath.endsWith(".mjs")) {
+    if (registeredChunks.has(chunkPath)) {
+      return;
+    }
+    let errorMessage = `Failed to load chunk ${chunkPath}`;
+    if (source) {
+      errorMessage += ` from ${stringifySourceInfo(source)}`;
+    }
+    throw new Error(
+      `${errorMessage}: ES module chunks need to be imported by the entry chunk or loaded asynchronously`
+    );
+  }
+
   if (!chunkPath.endsWith(".js")) {
     // We only support loading JS chunks in Node.js.
     // This branch can be hit when trying to load a CSS chunk.
@@ -103,7 +135,18 @@ function loadChunkPath(chunkPath: ChunkPath, source?: SourceInfo): void {
 
   try {
     const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
-    const chunkModules: ModuleFactories = require(resolved);
+    let chunkModules: ModuleFactories;
+    if (CHUNK_LOADER_BACKEND != null) {
+      const contents = readChunkSync(CHUNK_LOADER_BACKEND, chunkPath);
+      if (contents == null) {
+        throw new Error(
+          `The ${CHUNK_LOADER_BACKEND.type} chunk loader can only load chunks asynchronously`
+        );
+      }
+      chunkModules = evaluateChunk(contents, resolved);
+    } else {
+      chunkModules = require(resolved);
+    }
 
     for (const [moduleId, moduleFactory] of Object.entries(chunkModules)) {
       if (!moduleFactories[moduleId]) {
@@ -128,6 +171,28 @@ async function loadChunkAsync(
   chunkData: ChunkData
 ): Promise<any> {
   const chunkPath = typeof chunkData === "string" ? chunkData : chunkData.path;
+  if (chunkPath.endsWith(".mjs")) {
+    if (registeredChunks.has(chunkPath)) {
+      return;
+    }
+    const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
+    try {
+      const chunk = await import(url.pathToFileURL(resolved).href);
+      registerChunk(chunkPath, chunk.default);
+    } catch (e) {
+      let errorMessage = `Failed to load chunk ${chunkPath}`;
+
+      if (source) {
+        errorMessage += ` from ${stringifySourceInfo(source)}`;
+      }
+
+      throw new Error(errorMessage, {
+        cause: e,
+      });
+    }
+    return;
+  }
+
   if (!chunkPath.endsWith(".js")) {
     // We only support loading JS chunks in Node.js.
     // This branch can be hit when trying to load a CSS chunk.
@@ -137,25 +202,16 @@ async function loadChunkAsync(
   const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
 
   try {
-    const contents = await fs.readFile(resolved, "utf-8");
-
-    const localRequire = (id: string) => {
-      let resolvedId = require.resolve(id, {paths: [path.dirname(resolved)]});
-      return require(resolvedId);
-    }
-    const module = {
-      exports: {},
-    };
-    // TODO: Use vm.runInThisContext once our minimal supported Node.js version includes https://github.com/nodejs/node/pull/52153
-    // eslint-disable-next-line no-eval -- Can't use vm.runInThisContext due to https://github.com/nodejs/node/issues/52102
-    (0, eval)(
-      "(function(module, exports, require, __dirname, __filename) {" +
-        contents +
-        "\n})" +
-        "\n//# sourceURL=" + url.pathToFileURL(resolved),
-    )(module, module.exports, localRequire, path.dirname(resolved), resolved);
-
-    const chunkModules: ModuleFactories = module.exports;
+    const contents =
+      CHUNK_LOADER_BACKEND != null
+        ? await readChunk(
+            CHUNK_LOADER_BACKEND,
+            chunkPath,
+            url.pathToFileURL(resolved).href
+          )
+        : await fs.readFile(resolved, "utf-8");
+
+    const chunkModules = evaluateChunk(contents, resolved);
     for (const [moduleId, moduleFactory] of Object.entries(chunkModules)) {
       if (!moduleFactories[moduleId]) {
         moduleFactories[moduleId] = moduleFactory;
@@ -174,6 +230,30 @@ async function loadChunkAsync(
   }
 }
 
+/**
+ * Evaluates the code of a CommonJS chunk as if it was located at `resolved`
+ * and returns its module factories.
+ */
+function evaluateChunk(contents: string, resolved: string): ModuleFactories {
+  const localRequire = (id: string) => {
+    let resolvedId = require.resolve(id, {paths: [path.dirname(resolved)]});
+    return require(resolvedId);
+  }
+  const module = {
+    exports: {},
+  };
+  // TODO: Use vm.runInThisContext once our minimal supported Node.js version includes https://github.com/nodejs/node/pull/52153
+  // eslint-disable-next-line no-eval -- Can't use vm.runInThisContext due to https://github.com/nodejs/node/issues/52102
+  (0, eval)(
+    "(function(module, exports, require, __dirname, __filename) {" +
+      contents +
+      "\n})" +
+      "\n//# sourceURL=" + url.pathToFileURL(resolved),
+  )(module, module.exports, localRequire, path.dirname(resolved), resolved);
+
+  return module.exports;
+}
+
 function loadWebAssembly(chunkPath: ChunkPath, imports: WebAssembly.Imports) {
   const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
 
@@ -341,4 +421,5 @@ function getOrInstantiateRuntimeModule(
 module.exports = {
   getOrInstantiateRuntimeModule,
   loadChunk,
+  registerChunk,
 };
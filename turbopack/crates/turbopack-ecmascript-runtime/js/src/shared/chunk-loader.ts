@@ -0,0 +1,268 @@
+/**
+ * This file contains the chunk loader backends, which allow embedders to
+ * supply the code of chunks themselves, e.g. from a virtual file system
+ * embedded into a single binary or through a custom `fetch` in a sandboxed
+ * iframe.
+ *
+ * The backend is selected at build time and passed in `CHUNK_LOADER_BACKEND`.
+ * When it's `null`, the runtime uses its default loading strategy.
+ *
+ * It also contains the retry logic for chunks which fail to load, configured
+ * at build time in `CHUNK_LOAD_RETRY`.
+ */
+
+/* eslint-disable @typescript-eslint/no-unused-vars */
+
+/// <reference path="./runtime-utils.ts" />
+
+type ChunkLoaderBackend =
+  | {
+      type: "fetch";
+      global: string;
+    }
+  | {
+      type: "vfs";
+      global: string;
+    };
+
+type ChunkFetcher = (url: string) => Promise<{
+  ok: boolean;
+  status: number;
+  text(): Promise<string>;
+}>;
+
+type ChunkFileSystem = Record<string, string> | Map<string, string>;
+
+declare var CHUNK_LOADER_BACKEND: ChunkLoaderBackend | null;
+
+type ChunkLoadRetry = {
+  retries: number;
+  initialDelayMs: number;
+  maxDelayMs: number;
+  onStaleDeployment: "event" | "reload";
+};
+
+declare var CHUNK_LOAD_RETRY: ChunkLoadRetry;
+
+/**
+ * The `detail` of the `turbopack:chunk-load-error` event, which is dispatched
+ * on `globalThis` every time loading a chunk fails. Calling `preventDefault()`
+ * on the event prevents the page from being reloaded for a stale deployment.
+ */
+type ChunkLoadErrorDetail = {
+  chunkPath: ChunkPath;
+  chunkUrl: string;
+  /** The failed attempt, starting at 1. */
+  attempt: number;
+  willRetry: boolean;
+  /**
+   * The chunk is gone from the server, which happens when the app was
+   * deployed again while the page was open.
+   */
+  staleDeployment: boolean;
+  error: unknown;
+};
+
+const CHUNK_LOAD_ERROR_EVENT = "turbopack:chunk-load-error";
+const STALE_DEPLOYMENT_RELOAD_KEY = "__turbopack_stale_deployment_reload";
+/**
+ * The page isn't reloaded again for a stale deployment within this time, to
+ * avoid reload loops when the chunk is missing from the new deployment too.
+ */
+const STALE_DEPLOYMENT_RELOAD_INTERVAL_MS = 10_000;
+
+function getChunkLoaderGlobal<T>(backend: ChunkLoaderBackend): T {
+  const value = (globalThis as any)[backend.global];
+  if (value == null) {
+    throw new Error(
+      `The chunk loader \`globalThis.${backend.global}\` is not defined`
+    );
+  }
+  return value;
+}
+
+/**
+ * Reads the code of a chunk synchronously. Returns `undefined` when the
+ * backend can only read chunks asynchronously.
+ */
+function readChunkSync(
+  backend: ChunkLoaderBackend,
+  chunkPath: ChunkPath
+): string | undefined {
+  if (backend.type !== "vfs") {
+    return undefined;
+  }
+
+  const files = getChunkLoaderGlobal<ChunkFileSystem>(backend);
+  const code = files instanceof Map ? files.get(chunkPath) : files[chunkPath];
+  if (code == null) {
+    throw new Error(`Chunk ${chunkPath} is not in the virtual file system`);
+  }
+  return code;
+}
+
+/**
+ * Reads the code of a chunk. `chunkUrl` is the URL the chunk would be loaded
+ * from by default.
+ */
+async function readChunk(
+  backend: ChunkLoaderBackend,
+  chunkPath: ChunkPath,
+  chunkUrl: string
+): Promise<string> {
+  switch (backend.type) {
+    case "vfs":
+      return readChunkSync(backend, chunkPath)!;
+    case "fetch": {
+      const response = await getChunkLoaderGlobal<ChunkFetcher>(backend)(
+        chunkUrl
+      );
+      if (!response.ok) {
+        throw new Error(
+          `Fetching chunk ${chunkPath} failed with status ${response.status}`
+        );
+      }
+      return response.text();
+    }
+    default:
+      invariant(backend, (backend) => `Unknown chunk loader backend: ${backend?.type}`);
+  }
+}
+
+/**
+ * Loads a chunk with `load`, which is called with the URL to load it from,
+ * and retries with backoff as configured in `CHUNK_LOAD_RETRY`. Rejects with
+ * an error named `ChunkLoadError` once all attempts failed.
+ */
+async function loadChunkWithRetry(
+  chunkPath: ChunkPath,
+  chunkUrl: string,
+  load: (url: string) => Promise<void>
+): Promise<void> {
+  for (let attempt = 1; ; attempt++) {
+    const url = attempt === 1 ? chunkUrl : getRetryUrl(chunkUrl, attempt - 1);
+    try {
+      return await load(url);
+    } catch (error) {
+      const staleDeployment = await isChunkGone(url);
+      const willRetry = !staleDeployment && attempt <= CHUNK_LOAD_RETRY.retries;
+      const prevented = dispatchChunkLoadError({
+        chunkPath,
+        chunkUrl,
+        attempt,
+        willRetry,
+        staleDeployment,
+        error,
+      });
+
+      if (willRetry) {
+        await new Promise((resolve) =>
+          setTimeout(resolve, getRetryDelay(attempt))
+        );
+        continue;
+      }
+
+      if (
+        staleDeployment &&
+        !prevented &&
+        CHUNK_LOAD_RETRY.onStaleDeployment === "reload" &&
+        reloadForStaleDeployment()
+      ) {
+        // The page is reloading, so there is nothing left to do.
+        return new Promise<void>(() => {});
+      }
+
+      throw createChunkLoadError(chunkPath, staleDeployment, error);
+    }
+  }
+}
+
+function getRetryUrl(chunkUrl: string, retry: number): string {
+  return `${chunkUrl}${chunkUrl.includes("?") ? "&" : "?"}turbopack-retry=${retry}`;
+}
+
+function getRetryDelay(attempt: number): number {
+  const delay = Math.min(
+    CHUNK_LOAD_RETRY.initialDelayMs * 2 ** (attempt - 1),
+    CHUNK_LOAD_RETRY.maxDelayMs
+  );
+  // Jitter keeps the clients of a failing server from retrying in lockstep.
+  return delay * (0.8 + Math.random() * 0.4);
+}
+
+/**
+ * Whether the server responds that the chunk doesn't exist. Network errors
+ * don't count, as they can be transient.
+ */
+async function isChunkGone(url: string): Promise<boolean> {
+  if (typeof fetch !== "function") {
+    return false;
+  }
+  try {
+    const response = await fetch(url, { method: "HEAD", cache: "no-store" });
+    return response.status === 404 || response.status === 410;
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * Dispatches the `turbopack:chunk-load-error` event. Returns whether a
+ * listener called `preventDefault()`.
+ */
+function dispatchChunkLoadError(detail: ChunkLoadErrorDetail): boolean {
+  if (
+    typeof CustomEvent !== "function" ||
+    typeof (globalThis as any).dispatchEvent !== "function"
+  ) {
+    return false;
+  }
+  const event = new CustomEvent(CHUNK_LOAD_ERROR_EVENT, {
+    detail,
+    cancelable: true,
+  });
+  return !(globalThis as any).dispatchEvent(event);
+}
+
+/**
+ * Reloads the page, unless it was already reloaded for a stale deployment
+ * recently. Returns whether the page is reloading.
+ */
+function reloadForStaleDeployment(): boolean {
+  // Only windows can be reloaded, workers don't have `location.reload`.
+  const { location, sessionStorage } = globalThis as any;
+  if (typeof location?.reload !== "function" || sessionStorage == null) {
+    return false;
+  }
+  try {
+    const lastReload = Number(
+      sessionStorage.getItem(STALE_DEPLOYMENT_RELOAD_KEY)
+    );
+    if (Date.now() - lastReload < STALE_DEPLOYMENT_RELOAD_INTERVAL_MS) {
+      return false;
+    }
+    sessionStorage.setItem(STALE_DEPLOYMENT_RELOAD_KEY, String(Date.now()));
+  } catch {
+    // Storage can be disabled, in which case reload loops can't be detected.
+    return false;
+  }
+  location.reload();
+  return true;
+}
+
+function createChunkLoadError(
+  chunkPath: ChunkPath,
+  staleDeployment: boolean,
+  cause: unknown
+): Error {
+  const error = new Error(
+    staleDeployment
+      ? `Chunk ${chunkPath} no longer exists on the server, the app was probably deployed again`
+      : `Chunk ${chunkPath} failed to load after ${CHUNK_LOAD_RETRY.retries + 1} attempts`,
+    cause ? { cause } : undefined
+  );
+  error.name = "ChunkLoadError";
+  (error as any).chunkPath = chunkPath;
+  (error as any).staleDeployment = staleDeployment;
+  return error;
+}
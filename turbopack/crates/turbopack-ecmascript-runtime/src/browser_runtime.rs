@@ -11,7 +11,10 @@ use turbopack_core::{
 };
 use turbopack_ecmascript::utils::StringifyJs;
 
-use crate::{asset_context::get_runtime_asset_context, embed_js::embed_static_code, RuntimeType};
+use crate::{
+    asset_context::get_runtime_asset_context, chunk_loader::get_chunk_loader_code,
+    embed_js::embed_static_code, RuntimeType,
+};
 
 /// Returns the code for the ECMAScript runtime.
 #[turbo_tasks::function]
@@ -95,6 +98,7 @@ pub async fn get_browser_runtime_code(
     )?;
 
     code.push_code(&*shared_runtime_utils_code.await?);
+    code.push_code(&*get_chunk_loader_code(asset_context, environment).await?);
     for runtime_code in runtime_base_code {
         code.push_code(&*embed_static_code(asset_context, runtime_code.into()).await?);
     }
@@ -0,0 +1,44 @@
+use std::io::Write;
+
+use anyhow::Result;
+use turbo_tasks::Vc;
+use turbopack_core::{
+    code_builder::{Code, CodeBuilder},
+    context::AssetContext,
+    environment::{ChunkLoaderBackend, Environment},
+};
+use turbopack_ecmascript::utils::StringifyJs;
+
+use crate::embed_js::embed_static_code;
+
+/// Returns the code which selects the chunk loader backend of the environment,
+/// configures how failed chunk loads are retried and the helpers to read
+/// chunks through it.
+#[turbo_tasks::function]
+pub(crate) async fn get_chunk_loader_code(
+    asset_context: Vc<Box<dyn AssetContext>>,
+    environment: Vc<Environment>,
+) -> Result<Vc<Code>> {
+    let mut code = CodeBuilder::default();
+    match &*environment.chunk_loader_backend().await? {
+        ChunkLoaderBackend::Default => writeln!(code, "const CHUNK_LOADER_BACKEND = null;")?,
+        ChunkLoaderBackend::Fetch { global } => writeln!(
+            code,
+            "const CHUNK_LOADER_BACKEND = {{ type: \"fetch\", global: {} }};",
+            StringifyJs(global)
+        )?,
+        ChunkLoaderBackend::VirtualFileSystem { global } => writeln!(
+            code,
+            "const CHUNK_LOADER_BACKEND = {{ type: \"vfs\", global: {} }};",
+            StringifyJs(global)
+        )?,
+    }
+    writeln!(
+        code,
+        "const CHUNK_LOAD_RETRY = {};",
+        StringifyJs(&*environment.chunk_load_retry().await?)
+    )?;
+    code.push_code(&*embed_static_code(asset_context, "shared/chunk-loader.ts".into()).await?);
+
+    Ok(Code::cell(code.build()))
+}
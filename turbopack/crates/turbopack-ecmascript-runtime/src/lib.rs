@@ -3,6 +3,7 @@
 
 pub(crate) mod asset_context;
 pub(crate) mod browser_runtime;
+pub(crate) mod chunk_loader;
 #[cfg(feature = "test")]
 pub(crate) mod dummy_runtime;
 pub(crate) mod embed_js;
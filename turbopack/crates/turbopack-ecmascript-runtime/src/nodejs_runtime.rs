@@ -5,7 +5,10 @@ use turbopack_core::{
     environment::Environment,
 };
 
-use crate::{asset_context::get_runtime_asset_context, embed_js::embed_static_code};
+use crate::{
+    asset_context::get_runtime_asset_context, chunk_loader::get_chunk_loader_code,
+    embed_js::embed_static_code,
+};
 
 /// Returns the code for the Node.js production ECMAScript runtime.
 #[turbo_tasks::function]
@@ -24,6 +27,7 @@ pub async fn get_nodejs_runtime_code(environment: Vc<Environment>) -> Result<Vc<
 
     let mut code = CodeBuilder::default();
     code.push_code(&*shared_runtime_utils_code.await?);
+    code.push_code(&*get_chunk_loader_code(asset_context, environment).await?);
     code.push_code(&*shared_base_external_utils_code.await?);
     code.push_code(&*shared_node_external_utils_code.await?);
     code.push_code(&*shared_node_wasm_utils_code.await?);
@@ -300,17 +300,20 @@ impl EvalContext {
     /// Produce a new [EvalContext] from a [Program]. If you wish to support
     /// webpackIgnore or turbopackIgnore comments, you must pass those in,
     /// since the AST does not include comments by default.
+    /// `verbatim_module_syntax` is TypeScript's option of the same name, which
+    /// keeps imports of only types as side effect imports.
     pub fn new(
         module: &Program,
         unresolved_mark: Mark,
         top_level_mark: Mark,
         comments: Option<&dyn Comments>,
         source: Option<Vc<Box<dyn Source>>>,
+        verbatim_module_syntax: bool,
     ) -> Self {
         Self {
             unresolved_mark,
             top_level_mark,
-            imports: ImportMap::analyze(module, source, comments),
+            imports: ImportMap::analyze(module, source, comments, verbatim_module_syntax),
         }
     }
 
@@ -143,6 +143,10 @@ pub(crate) struct ImportMap {
     /// True if the module is an ESM module due to top-level await.
     has_top_level_await: bool,
 
+    /// True when TypeScript's `verbatimModuleSyntax` is enabled, see
+    /// [is_type_only_import].
+    verbatim_module_syntax: bool,
+
     /// Locations of [webpack-style "magic comments"][magic] that override import behaviors.
     ///
     /// Most commonly, these are `/* webpackIgnore: true */` comments. See [ImportAttributes] for
@@ -248,6 +252,10 @@ impl ImportMap {
         None
     }
 
+    pub fn verbatim_module_syntax(&self) -> bool {
+        self.verbatim_module_syntax
+    }
+
     pub fn get_attributes(&self, span: Span) -> &ImportAttributes {
         self.attributes.get(&span.lo).unwrap_or_default()
     }
@@ -276,8 +284,12 @@ impl ImportMap {
         m: &Program,
         source: Option<Vc<Box<dyn Source>>>,
         comments: Option<&dyn Comments>,
+        verbatim_module_syntax: bool,
     ) -> Self {
-        let mut data = ImportMap::default();
+        let mut data = ImportMap {
+            verbatim_module_syntax,
+            ..Default::default()
+        };
 
         m.visit_with(&mut Analyzer {
             data: &mut data,
@@ -334,6 +346,10 @@ impl Visit for Analyzer<'_> {
     fn visit_import_decl(&mut self, import: &ImportDecl) {
         self.data.has_imports = true;
 
+        if is_type_only_import(import, self.data.verbatim_module_syntax) {
+            return;
+        }
+
         let annotations = ImportAnnotations::parse(import.with.as_deref());
 
         let internal_symbol = parse_with(import.with.as_deref());
@@ -348,6 +364,12 @@ impl Visit for Analyzer<'_> {
         }
 
         for s in &import.specifiers {
+            if let ImportSpecifier::Named(ImportNamedSpecifier {
+                is_type_only: true, ..
+            }) = s
+            {
+                continue;
+            }
             let symbol = internal_symbol
                 .clone()
                 .unwrap_or_else(|| get_import_symbol_from_import(s));
@@ -393,6 +415,11 @@ impl Visit for Analyzer<'_> {
     fn visit_export_all(&mut self, export: &ExportAll) {
         self.data.has_exports = true;
 
+        // `export type * from "..."`
+        if export.type_only {
+            return;
+        }
+
         let annotations = ImportAnnotations::parse(export.with.as_deref());
 
         self.ensure_reference(
@@ -421,6 +448,10 @@ impl Visit for Analyzer<'_> {
             return;
         };
 
+        if is_type_only_export(export, self.data.verbatim_module_syntax) {
+            return;
+        }
+
         let annotations = ImportAnnotations::parse(export.with.as_deref());
 
         let internal_symbol = parse_with(export.with.as_deref());
@@ -435,6 +466,12 @@ impl Visit for Analyzer<'_> {
         }
 
         for spec in export.specifiers.iter() {
+            if let ExportSpecifier::Named(ExportNamedSpecifier {
+                is_type_only: true, ..
+            }) = spec
+            {
+                continue;
+            }
             let symbol = internal_symbol
                 .clone()
                 .unwrap_or_else(|| get_import_symbol_from_export(spec));
@@ -614,6 +651,43 @@ fn parse_with(with: Option<&ObjectLit>) -> Option<ImportedSymbol> {
     })
 }
 
+/// Whether the import only refers to types, e.g. `import type { A } from "..."`
+/// or `import { type A } from "..."`. TypeScript elides such imports, so they
+/// must not become edges of the module graph. With `verbatimModuleSyntax`, only
+/// `import type` is elided and `import { type A } from "..."` is kept as a side
+/// effect import.
+pub(crate) fn is_type_only_import(import: &ImportDecl, verbatim_module_syntax: bool) -> bool {
+    import.type_only
+        || (!verbatim_module_syntax
+            && !import.specifiers.is_empty()
+            && import.specifiers.iter().all(|specifier| {
+                matches!(
+                    specifier,
+                    ImportSpecifier::Named(ImportNamedSpecifier {
+                        is_type_only: true,
+                        ..
+                    })
+                )
+            }))
+}
+
+/// Whether the export only refers to types, e.g. `export type { A } from
+/// "..."` or `export { type A }`, see [is_type_only_import].
+pub(crate) fn is_type_only_export(export: &NamedExport, verbatim_module_syntax: bool) -> bool {
+    export.type_only
+        || (!verbatim_module_syntax
+            && !export.specifiers.is_empty()
+            && export.specifiers.iter().all(|specifier| {
+                matches!(
+                    specifier,
+                    ExportSpecifier::Named(ExportNamedSpecifier {
+                        is_type_only: true,
+                        ..
+                    })
+                )
+            }))
+}
+
 fn get_import_symbol_from_import(specifier: &ImportSpecifier) -> ImportedSymbol {
     match specifier {
         ImportSpecifier::Named(ImportNamedSpecifier {
@@ -636,3 +710,66 @@ fn get_import_symbol_from_export(specifier: &ExportSpecifier) -> ImportedSymbol
         ExportSpecifier::Namespace(..) => ImportedSymbol::Exports,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::FileName,
+        ecma::parser::{parse_file_as_program, Syntax, TsSyntax},
+        testing::run_test,
+    };
+
+    use super::*;
+
+    const CODE: &str = r#"
+        import type { A } from "a";
+        import { type B } from "b";
+        import { type C, D } from "c";
+        export type { E } from "e";
+        export { type F } from "f";
+    "#;
+
+    fn references(code: &str, verbatim_module_syntax: bool) -> Vec<(String, ImportedSymbol)> {
+        run_test(false, |cm, _| {
+            let fm = cm.new_source_file(FileName::Anon.into(), code.to_string());
+            let program = parse_file_as_program(
+                &fm,
+                Syntax::Typescript(TsSyntax::default()),
+                EsVersion::latest(),
+                None,
+                &mut vec![],
+            )
+            .unwrap();
+            let imports = ImportMap::analyze(&program, None, None, verbatim_module_syntax);
+            Ok(imports
+                .references()
+                .map(|r| (r.module_path.to_string(), r.imported_symbol.clone()))
+                .collect())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn elides_type_only_imports() {
+        assert_eq!(
+            references(CODE, false),
+            vec![
+                ("c".to_string(), ImportedSymbol::ModuleEvaluation),
+                ("c".to_string(), ImportedSymbol::Symbol("D".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_side_effects_of_type_only_specifiers_with_verbatim_module_syntax() {
+        assert_eq!(
+            references(CODE, true),
+            vec![
+                ("b".to_string(), ImportedSymbol::ModuleEvaluation),
+                ("c".to_string(), ImportedSymbol::ModuleEvaluation),
+                ("c".to_string(), ImportedSymbol::Symbol("D".into())),
+                ("f".to_string(), ImportedSymbol::ModuleEvaluation),
+            ]
+        );
+    }
+}
@@ -1731,6 +1731,10 @@ impl JsValue {
                         "url.pathToFileURL".to_string(),
                         "The Node.js url.pathToFileURL method: https://nodejs.org/api/url.html#urlpathtofileurlpath",
                     ),
+                    WellKnownFunctionKind::FileUrlToPath => (
+                        "url.fileURLToPath".to_string(),
+                        "The Node.js url.fileURLToPath method: https://nodejs.org/api/url.html#urlfileurltopathurl",
+                    ),
                     WellKnownFunctionKind::ChildProcessSpawnMethod(name) => (
                         format!("child_process.{name}"),
                         "A process spawning method from the Node.js child_process module: https://nodejs.org/api/child_process.html",
@@ -2332,6 +2336,7 @@ impl JsValue {
                     | WellKnownFunctionKind::OsPlatform
                     | WellKnownFunctionKind::PathDirname
                     | WellKnownFunctionKind::PathToFileUrl
+                    | WellKnownFunctionKind::FileUrlToPath
                     | WellKnownFunctionKind::ProcessCwd,
                 ),
                 _,
@@ -3795,6 +3800,7 @@ pub enum WellKnownFunctionKind {
     Define,
     FsReadMethod(JsWord),
     PathToFileUrl,
+    FileUrlToPath,
     ChildProcessSpawnMethod(JsWord),
     ChildProcessFork,
     OsArch,
@@ -3842,8 +3848,9 @@ pub mod test_utils {
     use turbopack_core::{compile_time_info::CompileTimeInfo, error::PrettyPrintError};
 
     use super::{
-        builtin::early_replace_builtin, well_known::replace_well_known, ConstantValue, JsValue,
-        JsValueUrlKind, ModuleValue, WellKnownFunctionKind, WellKnownObjectKind,
+        builtin::early_replace_builtin,
+        well_known::{new_url, replace_well_known},
+        JsValue, ModuleValue, WellKnownFunctionKind, WellKnownObjectKind,
     };
     use crate::{
         analyzer::{
@@ -3909,23 +3916,10 @@ pub mod test_utils {
                 _,
                 box JsValue::WellKnownFunction(WellKnownFunctionKind::URLConstructor),
                 ref args,
-            ) => {
-                if let [JsValue::Constant(ConstantValue::Str(url)), JsValue::Member(
-                    _,
-                    box JsValue::WellKnownObject(WellKnownObjectKind::ImportMeta),
-                    box JsValue::Constant(ConstantValue::Str(prop)),
-                )] = &args[..]
-                {
-                    if prop.as_str() == "url" {
-                        // TODO avoid clone
-                        JsValue::Url(url.clone(), JsValueUrlKind::Relative)
-                    } else {
-                        v.into_unknown(true, "new non constant")
-                    }
-                } else {
-                    v.into_unknown(true, "new non constant")
-                }
-            }
+            ) => match new_url(args) {
+                Some(url) => url,
+                None => v.into_unknown(true, "new non constant"),
+            },
             JsValue::FreeVar(ref var) => match &**var {
                 "__dirname" => "__dirname".into(),
                 "__filename" => "__filename".into(),
@@ -4031,8 +4025,14 @@ mod tests {
                 let top_level_mark = Mark::new();
                 m.visit_mut_with(&mut resolver(unresolved_mark, top_level_mark, false));
 
-                let eval_context =
-                    EvalContext::new(&m, unresolved_mark, top_level_mark, Some(&comments), None);
+                let eval_context = EvalContext::new(
+                    &m,
+                    unresolved_mark,
+                    top_level_mark,
+                    Some(&comments),
+                    None,
+                    false,
+                );
 
                 let mut var_graph = create_graph(&m, &eval_context);
 
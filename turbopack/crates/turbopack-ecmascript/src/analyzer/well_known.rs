@@ -73,6 +73,7 @@ pub async fn well_known_function_call(
             require_context_require_resolve(value, args).await?
         }
         WellKnownFunctionKind::PathToFileUrl => path_to_file_url(args),
+        WellKnownFunctionKind::FileUrlToPath => file_url_to_path(args),
         WellKnownFunctionKind::OsArch => compile_time_info
             .environment()
             .compile_target()
@@ -476,6 +477,136 @@ async fn require_context_require_resolve(
     Ok(m.as_str().into())
 }
 
+/// The value of `new URL(...args)` when it's a URL of a file that can be analyzed:
+/// - `new URL("./file.js", import.meta.url)` is kept as a URL relative to the module.
+/// - ``new URL(`./data/${name}.json`, import.meta.url)`` is only used as a file path for `fs`
+///   methods, so it's kept as a path pattern relative to the module, see [is_url_path_pattern].
+/// - ``new URL(`file://${__dirname}/data.json`)`` is the path of the `file:` URL, see
+///   [file_url_path].
+pub fn new_url(args: &[JsValue]) -> Option<JsValue> {
+    match args {
+        [url, base] if is_import_meta_url(base) => match url {
+            JsValue::Constant(ConstantValue::Str(url)) => {
+                Some(JsValue::Url(url.clone(), JsValueUrlKind::Relative))
+            }
+            path if is_url_path_pattern(path) => Some(path.clone()),
+            _ => None,
+        },
+        [url] => file_url_path(url),
+        _ => None,
+    }
+}
+
+fn is_import_meta_url(value: &JsValue) -> bool {
+    matches!(
+        value,
+        JsValue::Member(
+            _,
+            box JsValue::WellKnownObject(WellKnownObjectKind::ImportMeta),
+            box JsValue::Constant(ConstantValue::Str(prop)),
+        ) if prop.as_str() == "url"
+    )
+}
+
+/// Whether `value` is a file path built with a template literal, like
+/// `./data/${name}.json`, `${__dirname}/data/${name}.json` or `${process.cwd()}/data.json`, which
+/// is kept as a path pattern. URLs of other protocols are excluded by requiring a start without a
+/// protocol, or the current directory.
+pub fn is_url_path_pattern(value: &JsValue) -> bool {
+    let JsValue::Concat(_, parts) = value else {
+        return false;
+    };
+    match parts.first() {
+        Some(JsValue::Constant(ConstantValue::Str(start))) => !start.as_str().contains(':'),
+        Some(start) => is_process_cwd(start),
+        None => false,
+    }
+}
+
+/// The parts of `value` when it's a path pattern starting at the root or the current directory,
+/// see [is_url_path_pattern].
+fn absolute_path_pattern_parts(value: &JsValue) -> Option<&[JsValue]> {
+    let JsValue::Concat(_, parts) = value else {
+        return None;
+    };
+    let absolute = is_url_path_pattern(value)
+        && parts[0]
+            .as_str()
+            .map_or(true, |start| start.starts_with('/'));
+    absolute.then_some(parts)
+}
+
+/// Whether `value` is the result of `process.cwd()`, which is unknown when the environment
+/// doesn't specify the current directory.
+fn is_process_cwd(value: &JsValue) -> bool {
+    match value {
+        JsValue::Unknown {
+            original_value: Some(original),
+            ..
+        } => matches!(
+            &**original,
+            JsValue::Call(_, box JsValue::WellKnownFunction(WellKnownFunctionKind::ProcessCwd), _)
+        ),
+        _ => false,
+    }
+}
+
+/// The path of a `file:` URL, e.g. of `file://${__dirname}/data/${name}.json`, of
+/// `file://${process.cwd()}/data.json` or of `pathToFileURL("/data.json")`.
+fn file_url_path(url: &JsValue) -> Option<JsValue> {
+    match url {
+        JsValue::Url(url, JsValueUrlKind::Absolute) => constant_file_url_path(url.as_str()),
+        JsValue::Constant(ConstantValue::Str(url)) => constant_file_url_path(url.as_str()),
+        JsValue::Concat(_, parts) => {
+            let (start, rest) = parts.split_first()?;
+            let start = start.as_str()?.strip_prefix("file://")?;
+            let mut parts = Vec::with_capacity(parts.len());
+            if !start.is_empty() {
+                parts.push(start.into());
+            }
+            parts.extend(rest.iter().cloned());
+            let path = JsValue::concat(parts);
+            is_url_path_pattern(&path).then_some(path)
+        }
+        _ => None,
+    }
+}
+
+fn constant_file_url_path(url: &str) -> Option<JsValue> {
+    let url = Url::parse(url).ok().filter(|url| url.scheme() == "file")?;
+    let path = url.to_file_path().ok()?;
+    Some(path.to_str()?.into())
+}
+
+pub fn file_url_to_path(args: Vec<JsValue>) -> JsValue {
+    match &args[..] {
+        [JsValue::Url(url, JsValueUrlKind::Relative)] => url.as_str().into(),
+        [path] if is_url_path_pattern(path) => path.clone(),
+        [url] => file_url_path(url).unwrap_or_else(|| {
+            JsValue::unknown(
+                JsValue::call(
+                    Box::new(JsValue::WellKnownFunction(
+                        WellKnownFunctionKind::FileUrlToPath,
+                    )),
+                    args,
+                ),
+                true,
+                "only file: URLs and URLs relative to import.meta.url are supported",
+            )
+        }),
+        _ => JsValue::unknown(
+            JsValue::call(
+                Box::new(JsValue::WellKnownFunction(
+                    WellKnownFunctionKind::FileUrlToPath,
+                )),
+                args,
+            ),
+            true,
+            "only a single argument is supported",
+        ),
+    }
+}
+
 pub fn path_to_file_url(args: Vec<JsValue>) -> JsValue {
     if args.len() == 1 {
         if let Some(path) = args[0].as_str() {
@@ -493,6 +624,18 @@ pub fn path_to_file_url(args: Vec<JsValue>) -> JsValue {
                         "url not parseable: path is relative or has an invalid prefix",
                     )
                 })
+        } else if let Some(parts) = absolute_path_pattern_parts(&args[0]) {
+            // e.g. pathToFileURL(`${__dirname}/data/${name}.json`), which is turned back into the
+            // path by fileURLToPath
+            let mut url = Vec::with_capacity(parts.len() + 1);
+            if let Some(start) = parts[0].as_str() {
+                url.push(format!("file://{start}").into());
+                url.extend(parts[1..].iter().cloned());
+            } else {
+                url.push("file://".into());
+                url.extend(parts.iter().cloned());
+            }
+            JsValue::concat(url)
         } else {
             JsValue::unknown(
                 JsValue::call(
@@ -636,8 +779,9 @@ pub fn fs_module_member(kind: WellKnownObjectKind, prop: JsValue) -> JsValue {
         match (kind, word) {
             (
                 ..,
-                "realpath" | "realpathSync" | "stat" | "statSync" | "existsSync"
-                | "createReadStream" | "exists" | "open" | "openSync" | "readFile" | "readFileSync",
+                "realpath" | "realpathSync" | "stat" | "statSync" | "lstat" | "lstatSync"
+                | "access" | "accessSync" | "existsSync" | "createReadStream" | "exists" | "open"
+                | "openSync" | "readFile" | "readFileSync",
             ) => {
                 return JsValue::WellKnownFunction(WellKnownFunctionKind::FsReadMethod(
                     word.into(),
@@ -667,6 +811,12 @@ pub fn url_module_member(kind: WellKnownObjectKind, prop: JsValue) -> JsValue {
         (.., Some("pathToFileURL")) => {
             JsValue::WellKnownFunction(WellKnownFunctionKind::PathToFileUrl)
         }
+        (.., Some("fileURLToPath")) => {
+            JsValue::WellKnownFunction(WellKnownFunctionKind::FileUrlToPath)
+        }
+        (WellKnownObjectKind::UrlModule, Some("default")) => {
+            JsValue::WellKnownObject(WellKnownObjectKind::UrlModuleDefault)
+        }
         (WellKnownObjectKind::UrlModuleDefault, Some("default")) => {
             JsValue::WellKnownObject(WellKnownObjectKind::UrlModuleDefault)
         }
@@ -14,6 +14,7 @@ use turbopack_core::{
 
 use crate::{
     references::async_module::{AsyncModuleOptions, OptionAsyncModuleOptions},
+    refresh_boundary::OptionRefreshBoundary,
     utils::FormatIter,
     EcmascriptModuleContent, EcmascriptOptions,
 };
@@ -225,6 +226,12 @@ pub trait EcmascriptChunkItem: ChunkItem {
     fn need_async_module_info(self: Vc<Self>) -> Vc<bool> {
         Vc::cell(false)
     }
+
+    /// Whether the module is a React Refresh boundary, when that can be determined statically.
+    /// See [crate::refresh_boundary].
+    fn refresh_boundary(self: Vc<Self>) -> Vc<OptionRefreshBoundary> {
+        Vc::cell(None)
+    }
 }
 
 pub trait EcmascriptChunkItemExt {
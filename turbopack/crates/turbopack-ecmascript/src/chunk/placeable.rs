@@ -5,7 +5,10 @@ use turbopack_core::{
     asset::Asset,
     chunk::ChunkableModule,
     error::PrettyPrintError,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     module::Module,
     resolve::{find_context_file, package_json, FindContextFileResult},
 };
@@ -132,6 +135,11 @@ struct SideEffectsInPackageJsonIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for SideEffectsInPackageJsonIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_SIDE_EFFECTS))
+    }
+
     #[turbo_tasks::function]
     fn stage(&self) -> Vc<IssueStage> {
         IssueStage::Parse.into()
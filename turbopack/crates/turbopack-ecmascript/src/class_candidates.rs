@@ -0,0 +1,188 @@
+//! Extracts candidate class names, e.g. for Tailwind CSS, from the string
+//! literals of modules which are parsed anyway. This replaces scanning the
+//! source files a second time.
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use swc_core::ecma::{
+    ast::{Str, TplElement},
+    visit::{Visit, VisitWith},
+};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{
+    graph::{AdjacencyMap, GraphTraversal},
+    ResolvedVc, TryJoinIterExt, Vc,
+};
+use turbopack_core::{
+    module::{Module, Modules},
+    reference::primary_referenced_modules,
+};
+
+use crate::{parse::ParseResult, EcmascriptParsable};
+
+/// The longest string which is considered a class name.
+const MAX_CANDIDATE_LENGTH: usize = 128;
+
+/// A sorted set of strings which might be class names.
+#[turbo_tasks::value(transparent)]
+#[derive(Default)]
+pub struct ClassCandidates(Vec<RcStr>);
+
+#[turbo_tasks::value_impl]
+impl ClassCandidates {
+    #[turbo_tasks::function]
+    pub fn empty() -> Vc<Self> {
+        Vc::cell(Vec::new())
+    }
+}
+
+/// Provides the class candidates a CSS transform, e.g. Tailwind CSS, generates
+/// CSS for.
+#[turbo_tasks::value_trait]
+pub trait ClassCandidatesSource {
+    fn class_candidates(self: Vc<Self>) -> Vc<ClassCandidates>;
+}
+
+/// Returns the class candidates in the string literals of `module`. Modules
+/// which aren't ECMAScript don't have any.
+///
+/// As the result only changes when the set of candidates changes, edits which
+/// don't touch class names don't invalidate anything which depends on it.
+#[turbo_tasks::function]
+pub async fn module_class_candidates(module: Vc<Box<dyn Module>>) -> Result<Vc<ClassCandidates>> {
+    let Some(parsable) = Vc::try_resolve_sidecast::<Box<dyn EcmascriptParsable>>(module).await?
+    else {
+        return Ok(ClassCandidates::empty());
+    };
+    let ParseResult::Ok { program, .. } = &*parsable.failsafe_parse().await? else {
+        return Ok(ClassCandidates::empty());
+    };
+    let mut visitor = CandidatesVisitor::default();
+    program.visit_with(&mut visitor);
+    Ok(Vc::cell(visitor.candidates.into_iter().collect()))
+}
+
+/// Returns the class candidates of all ECMAScript modules in the module graph
+/// below `entries`.
+///
+/// The references of other modules aren't followed. This keeps the
+/// candidates of a graph independent from its CSS, which is generated from
+/// them.
+#[turbo_tasks::function]
+pub async fn module_graph_class_candidates(entries: Vc<Modules>) -> Result<Vc<ClassCandidates>> {
+    let modules = AdjacencyMap::new()
+        .skip_duplicates()
+        .visit(entries.await?.iter().copied(), get_ecmascript_children)
+        .await
+        .completed()?
+        .into_inner()
+        .into_reverse_topological()
+        .collect::<Vec<_>>();
+    let candidates = modules
+        .into_iter()
+        .map(|module| module_class_candidates(*module))
+        .try_join()
+        .await?;
+    let candidates = candidates
+        .iter()
+        .flat_map(|candidates| candidates.iter().cloned())
+        .collect::<BTreeSet<_>>();
+    Ok(Vc::cell(candidates.into_iter().collect()))
+}
+
+async fn get_ecmascript_children(
+    module: ResolvedVc<Box<dyn Module>>,
+) -> Result<impl Iterator<Item = ResolvedVc<Box<dyn Module>>> + Send> {
+    let children = if ResolvedVc::try_sidecast::<Box<dyn EcmascriptParsable>>(module)
+        .await?
+        .is_some()
+    {
+        primary_referenced_modules(*module).await?.clone_value()
+    } else {
+        Vec::new()
+    };
+    Ok(children.into_iter())
+}
+
+/// A [ClassCandidatesSource] for the module graph below `entries`, see
+/// [module_graph_class_candidates].
+///
+/// The entries should not depend on the CSS the candidates are used for, e.g.
+/// the pages of an app, so the candidates don't depend on themselves.
+#[turbo_tasks::value]
+pub struct ModuleGraphClassCandidates {
+    entries: ResolvedVc<Modules>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleGraphClassCandidates {
+    #[turbo_tasks::function]
+    pub fn new(entries: ResolvedVc<Modules>) -> Vc<Self> {
+        ModuleGraphClassCandidates { entries }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ClassCandidatesSource for ModuleGraphClassCandidates {
+    #[turbo_tasks::function]
+    fn class_candidates(&self) -> Vc<ClassCandidates> {
+        module_graph_class_candidates(*self.entries)
+    }
+}
+
+#[derive(Default)]
+struct CandidatesVisitor {
+    candidates: BTreeSet<RcStr>,
+}
+
+impl CandidatesVisitor {
+    fn add(&mut self, text: &str) {
+        for candidate in text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`')) {
+            if is_candidate(candidate) {
+                self.candidates.insert(candidate.into());
+            }
+        }
+    }
+}
+
+impl Visit for CandidatesVisitor {
+    fn visit_str(&mut self, str: &Str) {
+        self.add(&str.value);
+    }
+
+    fn visit_tpl_element(&mut self, element: &TplElement) {
+        self.add(&element.raw);
+    }
+}
+
+/// Whether `text` could be a class name. This is deliberately lenient, as a
+/// superfluous candidate only costs a lookup, while a missing one drops CSS.
+fn is_candidate(text: &str) -> bool {
+    let Some(first) = text.chars().next() else {
+        return false;
+    };
+    text.len() <= MAX_CANDIDATE_LENGTH
+        && (first.is_ascii_alphanumeric() || matches!(first, '-' | '!' | '[' | '@' | '*'))
+        && text.chars().any(|c| c.is_ascii_alphabetic())
+        && !text.contains(['{', '}', '<', '>', ';', '\\'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates() {
+        let mut visitor = CandidatesVisitor::default();
+        visitor.add("flex md:hover:bg-[#fff] -mt-2 !p-4 {} 42 <div>");
+        assert_eq!(
+            visitor
+                .candidates
+                .iter()
+                .map(|c| c.as_str())
+                .collect::<Vec<_>>(),
+            vec!["!p-4", "-mt-2", "flex", "md:hover:bg-[#fff]"]
+        );
+    }
+}
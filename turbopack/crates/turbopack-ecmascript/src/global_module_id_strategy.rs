@@ -4,7 +4,7 @@ use turbo_tasks::{
     graph::{AdjacencyMap, GraphTraversal},
     FxIndexMap, FxIndexSet, ResolvedVc, TryJoinIterExt, ValueToString, Vc,
 };
-use turbo_tasks_hash::hash_xxh3_hash64;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::{
     chunk::ModuleId,
     module::{Module, Modules},
@@ -18,7 +18,7 @@ pub struct PreprocessedChildrenIdents {
     // ident.to_string() -> full hash
     // We save the full hash to avoid re-hashing in `merge_preprocessed_module_ids`
     // if this endpoint did not change.
-    modules_idents: FxIndexMap<RcStr, u64>,
+    modules_idents: FxIndexMap<RcStr, u128>,
 }
 
 #[derive(Clone, Hash)]
@@ -118,7 +118,10 @@ pub async fn get_children_modules(
 #[turbo_tasks::function]
 pub async fn children_modules_idents(
     root_modules: Vc<Modules>,
+    hash_algorithm: Vc<HashAlgorithm>,
 ) -> Result<Vc<PreprocessedChildrenIdents>> {
+    let hash_algorithm = *hash_algorithm.await?;
+    let hash = |ident: &RcStr| hash_algorithm.hash(ident).as_u128();
     let children_modules_iter = AdjacencyMap::new()
         .skip_duplicates()
         .visit(
@@ -141,23 +144,23 @@ pub async fn children_modules_idents(
             ReferencedModule::Module(module) => {
                 let module_ident = module.ident();
                 let ident_str = module_ident.to_string().await?.clone_value();
-                let hash = hash_xxh3_hash64(&ident_str);
-                modules_idents.insert(ident_str, hash);
+                modules_idents.insert(ident_str.clone(), hash(&ident_str));
             }
             ReferencedModule::AsyncLoaderModule(async_loader_module) => {
                 let loader_ident = async_loader_module
                     .ident()
                     .with_modifier(Vc::cell("async loader".into()));
                 let loader_ident_str = loader_ident.to_string().await?.clone_value();
-                let loader_hash = hash_xxh3_hash64(&loader_ident_str);
-                modules_idents.insert(loader_ident_str, loader_hash);
+                modules_idents.insert(loader_ident_str.clone(), hash(&loader_ident_str));
 
                 let loaded_client_ident = async_loader_module
                     .ident()
                     .with_layer(Vc::cell("app-client".into()));
                 let loaded_client_ident_str = loaded_client_ident.to_string().await?.clone_value();
-                let loaded_client_hash = hash_xxh3_hash64(&loaded_client_ident_str);
-                modules_idents.insert(loaded_client_ident_str, loaded_client_hash);
+                modules_idents.insert(
+                    loaded_client_ident_str.clone(),
+                    hash(&loaded_client_ident_str),
+                );
             }
         }
     }
@@ -165,12 +168,13 @@ pub async fn children_modules_idents(
     Ok(PreprocessedChildrenIdents { modules_idents }.cell())
 }
 
-const JS_MAX_SAFE_INTEGER: u64 = (1u64 << 53) - 1;
+const JS_MAX_SAFE_INTEGER: u128 = (1u128 << 53) - 1;
 
 // Note(LichuAcu): This could be split into two functions: one that merges the preprocessed module
 // ids and another that generates the final, optimized module ids. Thoughts?
 pub async fn merge_preprocessed_module_ids(
     preprocessed_module_ids: Vec<Vc<PreprocessedChildrenIdents>>,
+    hash_algorithm: HashAlgorithm,
 ) -> Result<FxIndexMap<RcStr, ModuleId>> {
     let mut merged_module_ids = FxIndexMap::default();
 
@@ -184,7 +188,7 @@ pub async fn merge_preprocessed_module_ids(
     // https://github.com/webpack/webpack/blob/27cf3e59f5f289dfc4d76b7a1df2edbc4e651589/lib/ids/IdHelpers.js#L366-L405
     let optimal_range = merged_module_ids.len() * 20;
     let digit_mask = std::cmp::min(
-        10u64.pow((optimal_range as f64).log10().ceil() as u32),
+        10u128.pow((optimal_range as f64).log10().ceil() as u32),
         JS_MAX_SAFE_INTEGER,
     );
 
@@ -196,11 +200,12 @@ pub async fn merge_preprocessed_module_ids(
         let mut i = 1;
         while used_ids.contains(&trimmed_hash) {
             // If the id is already used, seek to find another available id.
-            trimmed_hash = hash_xxh3_hash64(full_hash + i) % digit_mask;
+            trimmed_hash = hash_algorithm.hash(full_hash.wrapping_add(i)).as_u128() % digit_mask;
             i += 1;
         }
         used_ids.insert(trimmed_hash);
-        module_id_map.insert(module_ident.clone(), ModuleId::Number(trimmed_hash));
+        // The mask is at most `JS_MAX_SAFE_INTEGER`, so the id fits into 64 bits.
+        module_id_map.insert(module_ident.clone(), ModuleId::Number(trimmed_hash as u64));
     }
 
     Ok(module_id_map)
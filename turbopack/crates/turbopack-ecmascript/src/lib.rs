@@ -13,15 +13,18 @@ pub mod annotations;
 pub mod async_chunk;
 pub mod chunk;
 pub mod chunk_group_files_asset;
+pub mod class_candidates;
 pub mod code_gen;
 mod errors;
 pub mod global_module_id_strategy;
+pub mod library;
 pub mod magic_identifier;
 pub mod manifest;
 pub mod minify;
 pub mod parse;
 mod path_visitor;
 pub mod references;
+pub mod refresh_boundary;
 pub mod side_effect_optimization;
 pub(crate) mod special_cases;
 pub(crate) mod static_code;
@@ -70,6 +73,7 @@ use turbopack_core::{
         AsyncModuleInfo, ChunkItem, ChunkType, ChunkableModule, ChunkingContext, EvaluatableAsset,
     },
     compile_time_info::CompileTimeInfo,
+    compile_time_report::{record_compile_time, CompilePhase},
     context::AssetContext,
     ident::AssetIdent,
     module::{Module, OptionModule},
@@ -92,6 +96,7 @@ use self::{
 use crate::{
     chunk::EcmascriptChunkPlaceable,
     references::{analyse_ecmascript_module, async_module::OptionAsyncModule},
+    refresh_boundary::{refresh_boundary, OptionRefreshBoundary},
     transform::remove_shebang,
 };
 
@@ -698,6 +703,17 @@ impl EcmascriptChunkItem for ModuleChunkItem {
             async_module_options,
         ))
     }
+
+    #[turbo_tasks::function]
+    async fn refresh_boundary(&self) -> Result<Vc<OptionRefreshBoundary>> {
+        if !self.module.options().await?.refresh {
+            return Ok(Vc::cell(None));
+        }
+        Ok(refresh_boundary(
+            self.module.ident().path().to_resolved().await?,
+            self.module.get_exports(),
+        ))
+    }
 }
 
 /// The transformed contents of an Ecmascript module.
@@ -811,6 +827,9 @@ async fn gen_content_with_code_gens(
         } => {
             let mut program = program.clone();
 
+            let path = ident.path().await?;
+            record_compile_time(&path.path, CompilePhase::CodeGeneration);
+
             process_content_with_code_gens(
                 &mut program,
                 globals,
@@ -0,0 +1,425 @@
+//! Building packages instead of applications. A library entry is bundled into
+//! a regular Node.js entry chunk, and a small facade in the requested
+//! [LibraryFormat] re-exports its named exports from there.
+
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use indoc::writedoc;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, ResolvedVc, TaskInput, Value, Vc};
+use turbo_tasks_fs::{
+    rope::{Rope, RopeBuilder},
+    File, FileSystemEntryType, FileSystemPath,
+};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{
+        availability_info::AvailabilityInfo, ChunkingContext, EvaluatableAsset, EvaluatableAssets,
+    },
+    file_source::FileSource,
+    ident::AssetIdent,
+    output::{OptionOutputAsset, OutputAsset, OutputAssets},
+    package_json::read_package_json,
+    raw_output::RawOutput,
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        ExternalTraced, ExternalType,
+    },
+};
+
+use crate::{
+    chunk::{EcmascriptChunkPlaceable, EcmascriptExports},
+    utils::StringifyJs,
+};
+
+/// The module format of a library entry.
+#[derive(
+    Debug, Default, TaskInput, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs,
+)]
+pub enum LibraryFormat {
+    #[default]
+    CommonJs,
+    /// An ES module for Node.js. Exports are snapshots of the values after the
+    /// entry has been evaluated, not live bindings.
+    Esm,
+    /// A universal module definition for CommonJS and AMD loaders. The bundle
+    /// is loaded with the `require` of the loader, so without a module system
+    /// the facade throws instead of exposing a global.
+    Umd,
+}
+
+impl LibraryFormat {
+    /// The file extension of the facade, which makes Node.js pick the right
+    /// module system.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LibraryFormat::CommonJs => "cjs",
+            LibraryFormat::Esm => "mjs",
+            LibraryFormat::Umd => "umd.js",
+        }
+    }
+
+    /// How the peer dependencies are loaded by the bundle. ES module consumers
+    /// may provide packages which can't be `require`d.
+    pub fn peer_external_type(&self) -> ExternalType {
+        match self {
+            LibraryFormat::CommonJs | LibraryFormat::Umd => ExternalType::CommonJs,
+            LibraryFormat::Esm => ExternalType::EcmaScriptModule,
+        }
+    }
+}
+
+/// The named exports of a library entry.
+#[turbo_tasks::value(shared)]
+pub struct LibraryExports {
+    /// Statically known export names, excluding `default`.
+    pub names: Vec<RcStr>,
+    pub has_default: bool,
+    /// The entry has exports that can't be determined statically, e.g.
+    /// because it's a CommonJS module.
+    pub dynamic: bool,
+}
+
+#[turbo_tasks::function]
+pub async fn library_exports(
+    module: Vc<Box<dyn EcmascriptChunkPlaceable>>,
+) -> Result<Vc<LibraryExports>> {
+    Ok(match &*module.get_exports().await? {
+        EcmascriptExports::EsmExports(exports) => {
+            let expanded = exports.expand_exports().await?;
+            LibraryExports {
+                names: expanded
+                    .exports
+                    .keys()
+                    .filter(|name| &***name != "default")
+                    .cloned()
+                    .collect(),
+                has_default: expanded.exports.contains_key("default"),
+                dynamic: !expanded.dynamic_exports.is_empty(),
+            }
+        }
+        EcmascriptExports::None | EcmascriptExports::EmptyCommonJs => LibraryExports {
+            names: vec![],
+            has_default: false,
+            dynamic: false,
+        },
+        EcmascriptExports::DynamicNamespace
+        | EcmascriptExports::CommonJs
+        | EcmascriptExports::Value => LibraryExports {
+            names: vec![],
+            has_default: false,
+            dynamic: true,
+        },
+    }
+    .cell())
+}
+
+/// The facade of a library entry, which loads the `entry_chunk` and exposes the
+/// exports of `module` in the given format.
+#[turbo_tasks::value(shared)]
+pub struct LibraryEntryAsset {
+    path: ResolvedVc<FileSystemPath>,
+    entry_chunk: ResolvedVc<Box<dyn OutputAsset>>,
+    module: ResolvedVc<Box<dyn EcmascriptChunkPlaceable>>,
+    format: LibraryFormat,
+    /// The name of the library in the error of the [LibraryFormat::Umd] facade.
+    name: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl LibraryEntryAsset {
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<FileSystemPath>,
+        entry_chunk: ResolvedVc<Box<dyn OutputAsset>>,
+        module: ResolvedVc<Box<dyn EcmascriptChunkPlaceable>>,
+        format: LibraryFormat,
+        name: RcStr,
+    ) -> Vc<Self> {
+        LibraryEntryAsset {
+            path,
+            entry_chunk,
+            module,
+            format,
+            name,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for LibraryEntryAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path)
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<OutputAssets> {
+        Vc::cell(vec![self.entry_chunk])
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for LibraryEntryAsset {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let directory = self.path.parent().await?;
+        let entry_chunk_path = self.entry_chunk.ident().path().await?;
+        let Some(relative_path) = directory.get_relative_path_to(&entry_chunk_path) else {
+            bail!(
+                "cannot find a relative path from the library entry ({}) to its chunk ({})",
+                self.path.await?.to_string(),
+                entry_chunk_path.to_string()
+            );
+        };
+
+        let exports = match self.format {
+            LibraryFormat::Esm => Some(library_exports(*self.module).await?),
+            LibraryFormat::CommonJs | LibraryFormat::Umd => None,
+        };
+        let code = facade_code(self.format, &relative_path, exports.as_deref(), &self.name)?;
+        Ok(AssetContent::file(File::from(code).into()))
+    }
+}
+
+/// The code of a facade which loads the entry chunk at `relative_path`. The
+/// exports are only needed for [LibraryFormat::Esm], which has to name them
+/// statically.
+fn facade_code(
+    format: LibraryFormat,
+    relative_path: &str,
+    exports: Option<&LibraryExports>,
+    name: &str,
+) -> Result<Rope> {
+    let mut code = RopeBuilder::default();
+    match format {
+        LibraryFormat::CommonJs => {
+            writeln!(
+                code,
+                "module.exports = require({});",
+                StringifyJs(relative_path)
+            )?;
+        }
+        LibraryFormat::Esm => {
+            writedoc!(
+                code,
+                r#"
+                    import {{ createRequire }} from "node:module";
+                    const __turbopack_library_exports__ = createRequire(import.meta.url)({});
+                "#,
+                StringifyJs(relative_path)
+            )?;
+            let Some(exports) = exports else {
+                bail!("the exports of an ES module library entry are required");
+            };
+            for (index, name) in exports.names.iter().enumerate() {
+                writeln!(
+                    code,
+                    "const __turbopack_export_{index}__ = \
+                     __turbopack_library_exports__[{name}];\nexport {{ \
+                     __turbopack_export_{index}__ as {name} }};",
+                    name = StringifyJs(name)
+                )?;
+            }
+            if exports.has_default {
+                writeln!(
+                    code,
+                    "export default __turbopack_library_exports__.default;"
+                )?;
+            } else if exports.dynamic {
+                writeln!(code, "export default __turbopack_library_exports__;")?;
+            }
+        }
+        LibraryFormat::Umd => {
+            // The factory gets the `require` of the loader, as there is no global one in AMD
+            // environments.
+            writedoc!(
+                code,
+                r#"
+                    (function (root, factory) {{
+                        if (typeof module === "object" && module.exports) {{
+                            module.exports = factory(require);
+                        }} else if (typeof define === "function" && define.amd) {{
+                            define(["require"], factory);
+                        }} else {{
+                            throw new Error({error});
+                        }}
+                    }})(typeof globalThis !== "undefined" ? globalThis : this, function (load) {{
+                        return load({path});
+                    }});
+                "#,
+                error = StringifyJs(&format!(
+                    "{name} needs a CommonJS or AMD module loader to load its bundle"
+                )),
+                path = StringifyJs(relative_path)
+            )?;
+        }
+    }
+    Ok(code.build())
+}
+
+/// The assets of a library entry: its entry chunk group, the facade in the
+/// given format and the declaration file, all emitted to `output_root` and
+/// named after the entry. `name` names the library in the error of the
+/// [LibraryFormat::Umd] facade and defaults to the file name of the entry.
+#[turbo_tasks::function]
+pub async fn library_entry_assets(
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+    entry: ResolvedVc<Box<dyn EvaluatableAsset>>,
+    output_root: Vc<FileSystemPath>,
+    format: LibraryFormat,
+    name: Option<RcStr>,
+) -> Result<Vc<OutputAssets>> {
+    let entry_path = entry.ident().path();
+    let Some(stem) = entry_path.file_stem().await?.clone() else {
+        bail!(
+            "library entry {} has no file name",
+            entry_path.to_string().await?
+        );
+    };
+    let Some(placeable) =
+        ResolvedVc::try_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(entry).await?
+    else {
+        bail!("library entry {stem} is not an ecmascript module");
+    };
+    let entry_chunk = chunking_context
+        .entry_chunk_group(
+            output_root.join(format!("{stem}.entry.js").into()),
+            *ResolvedVc::upcast(entry),
+            EvaluatableAssets::one(*entry),
+            OutputAssets::empty(),
+            Value::new(AvailabilityInfo::Root),
+        )
+        .await?
+        .asset;
+    let mut assets = vec![
+        entry_chunk,
+        ResolvedVc::upcast(
+            LibraryEntryAsset::new(
+                output_root
+                    .join(format!("{stem}.{}", format.extension()).into())
+                    .to_resolved()
+                    .await?,
+                *entry_chunk,
+                *placeable,
+                format,
+                name.unwrap_or_else(|| stem.clone()),
+            )
+            .to_resolved()
+            .await?,
+        ),
+    ];
+    if let Some(declaration) = *library_declaration(
+        entry_path,
+        output_root
+            .join(format!("{stem}.d.ts").into())
+            .to_resolved()
+            .await?,
+    )
+    .await?
+    {
+        assets.push(declaration);
+    }
+    Ok(Vc::cell(assets))
+}
+
+/// Copies a hand-written declaration file next to the library entry, e.g.
+/// `src/index.d.ts` for `src/index.js`, to `path`.
+#[turbo_tasks::function]
+pub async fn library_declaration(
+    entry_path: Vc<FileSystemPath>,
+    path: ResolvedVc<FileSystemPath>,
+) -> Result<Vc<OptionOutputAsset>> {
+    let Some(stem) = entry_path.file_stem().await?.clone() else {
+        return Ok(Vc::cell(None));
+    };
+    let declaration_path = entry_path.parent().join(format!("{stem}.d.ts").into());
+    if !matches!(
+        &*declaration_path.get_type().await?,
+        FileSystemEntryType::File
+    ) {
+        return Ok(Vc::cell(None));
+    }
+    Ok(Vc::cell(Some(ResolvedVc::upcast(
+        RawOutput::new(*path, Vc::upcast(FileSource::new(declaration_path)))
+            .to_resolved()
+            .await?,
+    ))))
+}
+
+/// An [ImportMap] that keeps the `peerDependencies` of the given package.json
+/// external, so they are resolved by the consumer of the library in the way
+/// [LibraryFormat::peer_external_type] describes.
+#[turbo_tasks::function]
+pub async fn peer_dependency_externals(
+    package_json: Vc<FileSystemPath>,
+    format: LibraryFormat,
+) -> Result<Vc<ImportMap>> {
+    let mut import_map = ImportMap::empty();
+    let Some(package_json) = &*read_package_json(package_json).await? else {
+        return Ok(import_map.cell());
+    };
+    let Some(peer_dependencies) = package_json["peerDependencies"].as_object() else {
+        return Ok(import_map.cell());
+    };
+    let external =
+        ImportMapping::External(None, format.peer_external_type(), ExternalTraced::Untraced)
+            .resolved_cell();
+    for name in peer_dependencies.keys() {
+        import_map.insert_exact_alias(name.as_str(), external);
+        import_map.insert_wildcard_alias(format!("{name}/"), external);
+    }
+    Ok(import_map.cell())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facade(format: LibraryFormat, exports: Option<&LibraryExports>) -> String {
+        facade_code(format, "./index.entry.js", exports, "myLib")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn esm_facade_exports_names() {
+        let exports = LibraryExports {
+            names: vec!["a".into(), "b".into()],
+            has_default: true,
+            dynamic: false,
+        };
+        let code = facade(LibraryFormat::Esm, Some(&exports));
+        assert!(code.contains(r#"export { __turbopack_export_0__ as "a" };"#));
+        assert!(code.contains(r#"export { __turbopack_export_1__ as "b" };"#));
+        assert!(code.contains("export default __turbopack_library_exports__.default;"));
+    }
+
+    #[test]
+    fn umd_facade_requires_only_through_the_loader() {
+        let code = facade(LibraryFormat::Umd, None);
+        let (loaders, global) = code.split_once("} else {").unwrap();
+        assert!(loaders.contains("module.exports = factory(require);"));
+        assert!(loaders.contains(r#"define(["require"], factory);"#));
+        let global = global.split_once("})(").unwrap().0;
+        assert!(!global.contains("require"));
+        assert!(global.contains("myLib needs a CommonJS or AMD module loader"));
+    }
+
+    #[test]
+    fn peer_externals_match_the_format() {
+        assert_eq!(
+            LibraryFormat::Esm.peer_external_type(),
+            ExternalType::EcmaScriptModule
+        );
+        assert_eq!(
+            LibraryFormat::CommonJs.peer_external_type(),
+            ExternalType::CommonJs
+        );
+    }
+}
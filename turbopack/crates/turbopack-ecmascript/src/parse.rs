@@ -27,8 +27,12 @@ use turbo_tasks_fs::{FileContent, FileSystemPath};
 use turbo_tasks_hash::hash_xxh3_hash64;
 use turbopack_core::{
     asset::{Asset, AssetContent},
+    compile_time_report::{record_compile_time, CompilePhase},
     error::PrettyPrintError,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     source::Source,
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMap},
     SOURCE_MAP_PREFIX,
@@ -247,6 +251,177 @@ async fn parse_internal(
     })
 }
 
+/// The syntax options a source file is parsed with. This is a subset of
+/// [EcmascriptModuleAssetType] that only contains the options that affect
+/// parsing, so that module types which only differ in analysis options share
+/// a single parse.
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Hash, Debug, Copy, Clone)]
+pub enum EcmascriptSyntax {
+    Ecmascript,
+    Typescript { tsx: bool },
+    TypescriptDeclaration,
+}
+
+impl From<EcmascriptModuleAssetType> for EcmascriptSyntax {
+    fn from(ty: EcmascriptModuleAssetType) -> Self {
+        match ty {
+            EcmascriptModuleAssetType::Ecmascript => EcmascriptSyntax::Ecmascript,
+            EcmascriptModuleAssetType::Typescript { tsx, .. } => {
+                EcmascriptSyntax::Typescript { tsx }
+            }
+            EcmascriptModuleAssetType::TypescriptDeclaration => {
+                EcmascriptSyntax::TypescriptDeclaration
+            }
+        }
+    }
+}
+
+impl EcmascriptSyntax {
+    fn to_swc_syntax(self) -> Syntax {
+        match self {
+            EcmascriptSyntax::Ecmascript => Syntax::Es(EsSyntax {
+                jsx: true,
+                fn_bind: true,
+                decorators: true,
+                decorators_before_export: true,
+                export_default_from: true,
+                import_attributes: true,
+                allow_super_outside_method: true,
+                allow_return_outside_function: true,
+                auto_accessors: true,
+                explicit_resource_management: true,
+            }),
+            EcmascriptSyntax::Typescript { tsx } => Syntax::Typescript(TsSyntax {
+                decorators: true,
+                dts: false,
+                no_early_errors: true,
+                tsx,
+                disallow_ambiguous_jsx_like: false,
+            }),
+            EcmascriptSyntax::TypescriptDeclaration => Syntax::Typescript(TsSyntax {
+                decorators: true,
+                dts: true,
+                no_early_errors: true,
+                tsx: false,
+                disallow_ambiguous_jsx_like: false,
+            }),
+        }
+    }
+}
+
+/// The raw result of parsing a source file, before the resolver pass and
+/// before any transforms are applied. It doesn't depend on any [Mark]s, so it
+/// can be shared between all transitions (server, client, edge) that parse the
+/// same source with the same [EcmascriptSyntax]. Each consumer clones the
+/// program and applies its own transforms on top.
+#[turbo_tasks::value(shared, serialization = "none", eq = "manual", cell = "new")]
+#[allow(clippy::large_enum_variant)]
+pub enum SharedParseResult {
+    Ok {
+        #[turbo_tasks(debug_ignore, trace_ignore)]
+        program: Program,
+        #[turbo_tasks(debug_ignore, trace_ignore)]
+        comments: Arc<ImmutableComments>,
+    },
+    Unparseable {
+        messages: Option<Vec<RcStr>>,
+    },
+}
+
+impl PartialEq for SharedParseResult {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Ok { .. }, Self::Ok { .. }) => false,
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+/// Parses a source file once per distinct [EcmascriptSyntax]. The result is
+/// shared by all [parse] calls for this source, independent of the
+/// per-transition transforms.
+#[turbo_tasks::function]
+pub async fn parse_shared(
+    source: ResolvedVc<Box<dyn Source>>,
+    syntax: Value<EcmascriptSyntax>,
+) -> Result<Vc<SharedParseResult>> {
+    let ident = &*source.ident().to_string().await?;
+    let span = tracing::info_span!("parse ecmascript (shared)", name = display(ident));
+    async move {
+        let AssetContent::File(file) = &*source.content().await? else {
+            return Ok(SharedParseResult::Unparseable { messages: None }.cell());
+        };
+        let FileContent::Content(file) = &*file.await? else {
+            return Ok(SharedParseResult::Unparseable { messages: None }.cell());
+        };
+        let Ok(string) = file.content().to_str() else {
+            return Ok(SharedParseResult::Unparseable { messages: None }.cell());
+        };
+
+        let source_map: Arc<swc_core::common::SourceMap> = Default::default();
+        let emitter = Box::new(IssueEmitter::new(
+            source,
+            source_map.clone(),
+            Some("Parsing ecmascript source code failed".into()),
+        ));
+        let parser_handler = Handler::with_emitter(true, false, emitter);
+
+        let file_name = FileName::Custom(ident.to_string());
+        let fm = source_map.new_source_file(file_name.into(), string.into_owned());
+        let comments = SwcComments::default();
+
+        let lexer = Lexer::new(
+            syntax.into_value().to_swc_syntax(),
+            EsVersion::latest(),
+            StringInput::from(&*fm),
+            Some(&comments),
+        );
+
+        let path = source.ident().path().await?;
+        let mut parser = Parser::new_from(lexer);
+        let span = tracing::trace_span!("swc_parse").entered();
+        record_compile_time(&path.path, CompilePhase::Parse);
+        let program_result = GLOBALS.set(&Globals::new(), || parser.parse_program());
+        drop(span);
+
+        let mut has_errors = vec![];
+        for e in parser.take_errors() {
+            let mut e = e.into_diagnostic(&parser_handler);
+            has_errors.extend(e.message.iter().map(|m| m.0.as_str().into()));
+            e.emit();
+        }
+
+        if !has_errors.is_empty() {
+            return Ok(SharedParseResult::Unparseable {
+                messages: Some(has_errors),
+            }
+            .cell());
+        }
+
+        Ok(match program_result {
+            Ok(program) => SharedParseResult::Ok {
+                program,
+                comments: Arc::new(ImmutableComments::new(comments)),
+            }
+            .cell(),
+            Err(e) => {
+                let mut e = e.into_diagnostic(&parser_handler);
+                let messages = e.message.iter().map(|m| m.0.as_str().into()).collect();
+
+                e.emit();
+
+                SharedParseResult::Unparseable {
+                    messages: Some(messages),
+                }
+                .cell()
+            }
+        })
+    }
+    .instrument(span)
+    .await
+}
+
 async fn parse_file_content(
     string: String,
     fs_path_vc: Vc<FileSystemPath>,
@@ -257,6 +432,17 @@ async fn parse_file_content(
     ty: EcmascriptModuleAssetType,
     transforms: &[EcmascriptInputTransform],
 ) -> Result<Vc<ParseResult>> {
+    let shared = parse_shared(*source, Value::new(ty.into())).await?;
+    let (program, shared_comments) = match &*shared {
+        SharedParseResult::Ok { program, comments } => (program, comments),
+        SharedParseResult::Unparseable { messages } => {
+            return Ok(ParseResult::Unparseable {
+                messages: messages.clone(),
+            }
+            .cell());
+        }
+    };
+
     let source_map: Arc<swc_core::common::SourceMap> = Default::default();
     let handler = Handler::with_emitter(
         true,
@@ -279,82 +465,17 @@ async fn parse_file_content(
 
     let mut result = WrapFuture::new(
         async {
+            // The source file is the first (and only) file in a fresh source map,
+            // so it gets the same start position as in the shared parse and all
+            // spans of the cloned program stay valid.
             let file_name = FileName::Custom(ident.to_string());
-            let fm = source_map.new_source_file(file_name.clone().into(), string);
-
-            let comments = SwcComments::default();
-
-            let mut parsed_program = {
-                let lexer = Lexer::new(
-                    match ty {
-                        EcmascriptModuleAssetType::Ecmascript => Syntax::Es(EsSyntax {
-                            jsx: true,
-                            fn_bind: true,
-                            decorators: true,
-                            decorators_before_export: true,
-                            export_default_from: true,
-                            import_attributes: true,
-                            allow_super_outside_method: true,
-                            allow_return_outside_function: true,
-                            auto_accessors: true,
-                            explicit_resource_management: true,
-                        }),
-                        EcmascriptModuleAssetType::Typescript { tsx, .. } => {
-                            Syntax::Typescript(TsSyntax {
-                                decorators: true,
-                                dts: false,
-                                no_early_errors: true,
-                                tsx,
-                                disallow_ambiguous_jsx_like: false,
-                            })
-                        }
-                        EcmascriptModuleAssetType::TypescriptDeclaration => {
-                            Syntax::Typescript(TsSyntax {
-                                decorators: true,
-                                dts: true,
-                                no_early_errors: true,
-                                tsx: false,
-                                disallow_ambiguous_jsx_like: false,
-                            })
-                        }
-                    },
-                    EsVersion::latest(),
-                    StringInput::from(&*fm),
-                    Some(&comments),
-                );
-
-                let mut parser = Parser::new_from(lexer);
-                let span = tracing::trace_span!("swc_parse").entered();
-                let program_result = parser.parse_program();
-                drop(span);
-
-                let mut has_errors = vec![];
-                for e in parser.take_errors() {
-                    let mut e = e.into_diagnostic(&parser_handler);
-                    has_errors.extend(e.message.iter().map(|m| m.0.as_str().into()));
-                    e.emit();
-                }
-
-                if !has_errors.is_empty() {
-                    return Ok(ParseResult::Unparseable {
-                        messages: Some(has_errors),
-                    });
-                }
+            let fm = source_map.new_source_file(file_name.into(), string);
 
-                match program_result {
-                    Ok(parsed_program) => parsed_program,
-                    Err(e) => {
-                        let mut e = e.into_diagnostic(&parser_handler);
-                        let messages = e.message.iter().map(|m| m.0.as_str().into()).collect();
+            let comments = shared_comments.to_mutable();
 
-                        e.emit();
+            let mut parsed_program = program.clone();
 
-                        return Ok(ParseResult::Unparseable {
-                            messages: Some(messages),
-                        });
-                    }
-                }
-            };
+            let sample = record_compile_time(&fs_path.path, CompilePhase::Transform);
 
             let unresolved_mark = Mark::new();
             let top_level_mark = Mark::new();
@@ -401,9 +522,22 @@ async fn parse_file_content(
             let span = tracing::trace_span!("transforms");
             async {
                 for transform in transforms.iter() {
-                    transform
-                        .apply(&mut parsed_program, &transform_context)
-                        .await?;
+                    match &sample {
+                        Some(sample) => {
+                            let name = transform.name().await?;
+                            sample
+                                .measure_transform(
+                                    name,
+                                    transform.apply(&mut parsed_program, &transform_context),
+                                )
+                                .await?
+                        }
+                        None => {
+                            transform
+                                .apply(&mut parsed_program, &transform_context)
+                                .await?
+                        }
+                    }
                 }
                 anyhow::Ok(())
             }
@@ -430,12 +564,22 @@ async fn parse_file_content(
                 &mut swc_core::ecma::transforms::base::helpers::inject_helpers(unresolved_mark),
             );
 
+            let verbatim_module_syntax = transforms.iter().any(|transform| {
+                matches!(
+                    transform,
+                    EcmascriptInputTransform::TypeScript {
+                        verbatim_module_syntax: true,
+                        ..
+                    }
+                )
+            });
             let eval_context = EvalContext::new(
                 &parsed_program,
                 unresolved_mark,
                 top_level_mark,
                 Some(&comments),
                 Some(*source),
+                verbatim_module_syntax,
             );
 
             Ok::<ParseResult, anyhow::Error>(ParseResult::Ok {
@@ -473,6 +617,11 @@ struct ReadSourceIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ReadSourceIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::READ_SOURCE_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         self.source.ident().path()
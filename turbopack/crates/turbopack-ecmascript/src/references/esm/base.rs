@@ -14,8 +14,8 @@ use turbopack_core::{
         ChunkingTypeOption,
     },
     issue::{
-        Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource,
-        OptionStyledString, StyledString,
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueCode,
+        OptionIssueSource, OptionStyledString, StyledString,
     },
     module::Module,
     reference::ModuleReference,
@@ -402,6 +402,11 @@ pub struct InvalidExport {
 
 #[turbo_tasks::value_impl]
 impl Issue for InvalidExport {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_EXPORT))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Error.into()
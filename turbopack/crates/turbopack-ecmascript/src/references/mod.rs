@@ -101,7 +101,7 @@ use super::{
         builtin::replace_builtin,
         graph::{create_graph, Effect},
         linker::link,
-        well_known::replace_well_known,
+        well_known::{new_url, replace_well_known},
         ConstantValue as JsConstantValue, JsValue, ObjectPart, WellKnownFunctionKind,
         WellKnownObjectKind,
     },
@@ -120,7 +120,10 @@ use crate::{
     analyzer::{
         builtin::early_replace_builtin,
         graph::{ConditionalKind, EffectArg, EvalContext, VarGraph},
-        imports::{ImportAnnotations, ImportAttributes, ImportedSymbol, Reexport},
+        imports::{
+            is_type_only_export, is_type_only_import, ImportAnnotations, ImportAttributes,
+            ImportedSymbol, Reexport,
+        },
         parse_require_context,
         top_level_await::has_top_level_await,
         ConstantNumber, ConstantString, JsValueUrlKind, RequireContextValue,
@@ -864,7 +867,7 @@ pub(crate) async fn analyse_ecmascript_module_internal(
             code: None,
             message: StyledString::Text("top level await is only supported in ESM modules.".into())
                 .resolved_cell(),
-            source_ident: source.ident(),
+            source_ident: module.ident(),
             severity: IssueSeverity::Error.resolved_cell(),
             source: Some(issue_source(*source, span)),
             title: ResolvedVc::cell("unexpected top level await".into()),
@@ -2651,23 +2654,10 @@ async fn value_visitor_inner(
             _,
             box JsValue::WellKnownFunction(WellKnownFunctionKind::URLConstructor),
             ref args,
-        ) => {
-            if let [JsValue::Constant(super::analyzer::ConstantValue::Str(url)), JsValue::Member(
-                _,
-                box JsValue::WellKnownObject(WellKnownObjectKind::ImportMeta),
-                box JsValue::Constant(super::analyzer::ConstantValue::Str(prop)),
-            )] = &args[..]
-            {
-                if prop.as_str() == "url" {
-                    // TODO avoid clone
-                    JsValue::Url(url.clone(), JsValueUrlKind::Relative)
-                } else {
-                    v.into_unknown(true, "new non constant")
-                }
-            } else {
-                v.into_unknown(true, "new non constant")
-            }
-        }
+        ) => match new_url(args) {
+            Some(url) => url,
+            None => v.into_unknown(true, "new non constant"),
+        },
         JsValue::FreeVar(ref kind) => match &**kind {
             "__dirname" => as_abs_path(origin.origin_path().parent()).await?,
             "__filename" => as_abs_path(origin.origin_path()).await?,
@@ -2983,7 +2973,9 @@ impl VisitAstPath for ModuleReferencesVisitor<'_> {
             .map(find_turbopack_part_id_in_asserts)
             .is_some();
 
-        if export.src.is_none() {
+        if export.src.is_none()
+            && !is_type_only_export(export, self.eval_context.imports.verbatim_module_syntax())
+        {
             for spec in export.specifiers.iter() {
                 fn to_string(name: &ModuleExportName) -> &JsWord {
                     name.atom()
@@ -3001,6 +2993,9 @@ impl VisitAstPath for ModuleReferencesVisitor<'_> {
                              None"
                         );
                     }
+                    ExportSpecifier::Named(ExportNamedSpecifier {
+                        is_type_only: true, ..
+                    }) => {}
                     ExportSpecifier::Named(ExportNamedSpecifier { orig, exported, .. }) => {
                         let key = to_string(exported.as_ref().unwrap_or(orig)).as_str().into();
                         let binding_name = to_string(orig).as_str().into();
@@ -3098,7 +3093,7 @@ impl VisitAstPath for ModuleReferencesVisitor<'_> {
         let path = Vc::cell(as_parent_path(ast_path));
         let src = import.src.value.to_string();
         import.visit_children_with_ast_path(self, ast_path);
-        if import.type_only {
+        if is_type_only_import(import, self.eval_context.imports.verbatim_module_syntax()) {
             return;
         }
         for specifier in &import.specifiers {
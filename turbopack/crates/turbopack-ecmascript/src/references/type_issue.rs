@@ -1,6 +1,8 @@
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 use crate::SpecifiedModuleType;
 
@@ -12,6 +14,11 @@ pub struct SpecifiedModuleTypeIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for SpecifiedModuleTypeIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::MODULE_TYPE_MISMATCH))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.path
@@ -0,0 +1,212 @@
+//! Static detection of React Refresh boundaries.
+//!
+//! A module is a refresh boundary when all of its exports are React components. Updates to a
+//! boundary are applied in place, while updates to other modules propagate to the modules
+//! importing them, and lead to a full reload when no boundary is found on the way.
+//!
+//! The runtime used to find boundaries by inspecting the exports of every module it executed.
+//! The analysis here only looks at the export names instead, like the React Refresh Babel plugin
+//! does: an export is a component when its name (or, for default exports, the name of the
+//! declared function or class) starts with an uppercase letter. Modules whose exports can't be
+//! determined statically are left to the runtime. As names can't tell whether a value really is a
+//! component, the runtime still checks the exports of the boundaries found here, and only uses the
+//! analysis to reject the other modules.
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+    StyledString,
+};
+
+use crate::{chunk::EcmascriptExports, references::esm::EsmExport};
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct RefreshBoundary {
+    pub path: ResolvedVc<FileSystemPath>,
+    /// Exports that look like React components.
+    pub components: Vec<RcStr>,
+    /// Exports that don't look like React components.
+    pub non_components: Vec<RcStr>,
+}
+
+impl RefreshBoundary {
+    /// Whether updates to the module can be applied in place.
+    pub fn is_boundary(&self) -> bool {
+        !self.components.is_empty() && self.non_components.is_empty()
+    }
+
+    /// Explains why updates to the module aren't applied in place, when it exports components
+    /// next to other values. Modules without any components aren't expected to be boundaries,
+    /// so nothing is reported for them.
+    pub fn emit_issue(&self) {
+        if self.components.is_empty() || self.non_components.is_empty() {
+            return;
+        }
+        NotRefreshBoundaryIssue {
+            path: self.path,
+            components: self.components.clone(),
+            non_components: self.non_components.clone(),
+        }
+        .cell()
+        .emit();
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionRefreshBoundary(Option<RefreshBoundary>);
+
+#[derive(Debug, PartialEq, Eq)]
+enum ExportKind {
+    Component,
+    NonComponent,
+    Unknown,
+}
+
+fn export_kind(name: &str) -> ExportKind {
+    match name.chars().next() {
+        // Constants like `THEME` are conventionally uppercase too, but so are some components,
+        // e.g. `UI`.
+        Some(c)
+            if c.is_ascii_uppercase()
+                && name.chars().filter(char::is_ascii_uppercase).count() > 1
+                && !name.chars().any(|c| c.is_ascii_lowercase()) =>
+        {
+            ExportKind::Unknown
+        }
+        Some(c) if c.is_ascii_uppercase() => ExportKind::Component,
+        Some(c) if c.is_ascii_lowercase() => ExportKind::NonComponent,
+        // Mangled names, e.g. of anonymous default exports, don't tell anything about the value.
+        _ => ExportKind::Unknown,
+    }
+}
+
+/// Determines whether the module at `path` with the given exports is a refresh boundary, or
+/// `None` when that depends on the values of the exports.
+#[turbo_tasks::function]
+pub async fn refresh_boundary(
+    path: ResolvedVc<FileSystemPath>,
+    exports: Vc<EcmascriptExports>,
+) -> Result<Vc<OptionRefreshBoundary>> {
+    let EcmascriptExports::EsmExports(exports) = &*exports.await? else {
+        return Ok(Vc::cell(None));
+    };
+    let exports = exports.await?;
+    if !exports.star_exports.is_empty() {
+        return Ok(Vc::cell(None));
+    }
+
+    let mut components = Vec::new();
+    let mut non_components = Vec::new();
+    for (name, export) in &exports.exports {
+        let kind = match export {
+            EsmExport::LocalBinding(binding, _) if name == "default" => export_kind(binding),
+            EsmExport::ImportedBinding(_, imported, _) if name == "default" => {
+                export_kind(imported)
+            }
+            EsmExport::ImportedNamespace(_) => ExportKind::NonComponent,
+            EsmExport::Error => continue,
+            _ => export_kind(name),
+        };
+        match kind {
+            ExportKind::Component => components.push(name.clone()),
+            ExportKind::NonComponent => non_components.push(name.clone()),
+            ExportKind::Unknown => return Ok(Vc::cell(None)),
+        }
+    }
+
+    Ok(Vc::cell(Some(RefreshBoundary {
+        path,
+        components,
+        non_components,
+    })))
+}
+
+#[turbo_tasks::value(shared)]
+pub struct NotRefreshBoundaryIssue {
+    path: ResolvedVc<FileSystemPath>,
+    components: Vec<RcStr>,
+    non_components: Vec<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for NotRefreshBoundaryIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::NOT_REFRESH_BOUNDARY))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Hint.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::CodeGen.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text("Fast Refresh can't update this module in place".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                format!(
+                    "The module exports the React components {} next to {}, which aren't \
+                     components. Updates to it are applied by re-executing the modules importing \
+                     it, which leads to a full reload when none of them can be updated in place. \
+                     Move the other exports into a separate module to keep the state of the \
+                     components on updates.",
+                    self.components.join(", "),
+                    self.non_components.join(", "),
+                )
+                .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_kind, ExportKind};
+
+    #[test]
+    fn classifies_exports_by_name() {
+        assert_eq!(export_kind("Button"), ExportKind::Component);
+        assert_eq!(export_kind("A"), ExportKind::Component);
+        assert_eq!(export_kind("useTheme"), ExportKind::NonComponent);
+        assert_eq!(export_kind("default"), ExportKind::NonComponent);
+    }
+
+    #[test]
+    fn leaves_uppercase_names_to_the_runtime() {
+        assert_eq!(export_kind("THEME"), ExportKind::Unknown);
+        assert_eq!(export_kind("MAX_ITEMS"), ExportKind::Unknown);
+        assert_eq!(export_kind("UI"), ExportKind::Unknown);
+        assert_eq!(export_kind("V2"), ExportKind::Component);
+    }
+
+    #[test]
+    fn leaves_mangled_names_to_the_runtime() {
+        assert_eq!(
+            export_kind("__TURBOPACK__default__export__"),
+            ExportKind::Unknown
+        );
+        assert_eq!(export_kind("_default"), ExportKind::Unknown);
+        assert_eq!(export_kind("$"), ExportKind::Unknown);
+        assert_eq!(export_kind(""), ExportKind::Unknown);
+    }
+}
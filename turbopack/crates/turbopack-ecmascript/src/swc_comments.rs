@@ -41,6 +41,19 @@ impl ImmutableComments {
     pub fn consumable(&self) -> CowComments<'_> {
         CowComments::new(self)
     }
+
+    /// Creates a mutable copy of these comments, e.g. to run transforms on a
+    /// program that was cloned from a shared parse result.
+    pub fn to_mutable(&self) -> SwcComments {
+        let comments = SwcComments::default();
+        for (pos, leading) in &self.leading {
+            comments.add_leading_comments(*pos, leading.clone());
+        }
+        for (pos, trailing) in &self.trailing {
+            comments.add_trailing_comments(*pos, trailing.clone());
+        }
+        comments
+    }
 }
 
 impl Comments for ImmutableComments {
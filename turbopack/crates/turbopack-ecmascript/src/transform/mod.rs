@@ -22,7 +22,7 @@ use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     environment::Environment,
-    issue::{Issue, IssueSeverity, IssueStage, StyledString},
+    issue::{Issue, IssueCode, IssueSeverity, IssueStage, OptionIssueCode, StyledString},
 };
 
 #[turbo_tasks::value(serialization = "auto_for_input")]
@@ -49,6 +49,8 @@ pub enum EcmascriptInputTransform {
     TypeScript {
         #[serde(default)]
         use_define_for_class_fields: bool,
+        #[serde(default)]
+        verbatim_module_syntax: bool,
     },
     Decorators {
         #[serde(default)]
@@ -67,6 +69,12 @@ pub enum EcmascriptInputTransform {
 #[async_trait]
 pub trait CustomTransformer: Debug {
     async fn transform(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()>;
+
+    /// The name of the transformer in reports, the name of the type by default.
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
 }
 
 /// A wrapper around a TransformPlugin instance, allowing it to operate with
@@ -86,6 +94,10 @@ impl CustomTransformer for TransformPlugin {
     async fn transform(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
         self.0.transform(program, ctx).await
     }
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
 }
 
 #[turbo_tasks::value(transparent, serialization = "auto_for_input")]
@@ -119,6 +131,20 @@ pub struct TransformContext<'a> {
 }
 
 impl EcmascriptInputTransform {
+    /// The name of the transform in reports, e.g. of the
+    /// [build telemetry][turbopack_core::build_telemetry].
+    pub async fn name(&self) -> Result<&'static str> {
+        Ok(match self {
+            EcmascriptInputTransform::CommonJs => "commonjs",
+            EcmascriptInputTransform::Plugin(plugin) => plugin.await?.name(),
+            EcmascriptInputTransform::PresetEnv(_) => "preset-env",
+            EcmascriptInputTransform::React { .. } => "react",
+            EcmascriptInputTransform::GlobalTypeofs { .. } => "global-typeofs",
+            EcmascriptInputTransform::TypeScript { .. } => "typescript",
+            EcmascriptInputTransform::Decorators { .. } => "decorators",
+        })
+    }
+
     pub async fn apply(&self, program: &mut Program, ctx: &TransformContext<'_>) -> Result<()> {
         let &TransformContext {
             comments,
@@ -263,9 +289,13 @@ impl EcmascriptInputTransform {
             EcmascriptInputTransform::TypeScript {
                 // TODO(WEB-1213)
                 use_define_for_class_fields: _use_define_for_class_fields,
+                verbatim_module_syntax,
             } => {
-                use swc_core::ecma::transforms::typescript::typescript;
-                let config = Default::default();
+                use swc_core::ecma::transforms::typescript::{typescript, Config};
+                let config = Config {
+                    verbatim_module_syntax: *verbatim_module_syntax,
+                    ..Default::default()
+                };
                 program.mutate(typescript(config, unresolved_mark, top_level_mark));
             }
             EcmascriptInputTransform::Decorators {
@@ -310,6 +340,11 @@ pub struct UnsupportedServerActionIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for UnsupportedServerActionIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::UNSUPPORTED_SERVER_ACTION))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Error.into()
@@ -25,6 +25,10 @@ use swc_core::{
 };
 use turbo_rcstr::RcStr;
 use turbo_tasks::FxIndexSet;
+use turbo_tasks_malloc::{
+    bumpalo::{collections::Vec as BumpVec, Bump},
+    Arena,
+};
 
 use super::{
     util::{
@@ -1477,33 +1481,32 @@ impl DepGraph {
         g: &mut Graph<u32, Dependency>,
         data: &FxHashMap<ItemId, ItemData>,
     ) {
-        fn collect_deps(
+        /// The intermediate lists only live until the dependencies of one export
+        /// are collected, so they are allocated in `arena`.
+        fn collect_deps<'a>(
+            arena: &'a Bump,
             g: &Graph<u32, Dependency>,
             done: &mut FxHashSet<NodeIndex>,
             node: NodeIndex,
-        ) -> Vec<NodeIndex> {
-            let direct_deps = g
-                .edges_directed(node, Direction::Outgoing)
-                .map(|e| e.target())
-                .collect::<Vec<_>>();
+        ) -> BumpVec<'a, NodeIndex> {
+            let direct_deps = BumpVec::from_iter_in(
+                g.edges_directed(node, Direction::Outgoing)
+                    .map(|e| e.target()),
+                arena,
+            );
 
             if direct_deps.iter().all(|dep| done.contains(dep)) {
                 return direct_deps;
             }
 
-            direct_deps
-                .into_iter()
-                .flat_map(|dep| {
-                    let mut v = if !done.insert(dep) {
-                        vec![]
-                    } else {
-                        collect_deps(g, done, dep)
-                    };
-
-                    v.push(dep);
-                    v
-                })
-                .collect()
+            let mut deps = BumpVec::new_in(arena);
+            for dep in direct_deps {
+                if done.insert(dep) {
+                    deps.extend(collect_deps(arena, g, done, dep));
+                }
+                deps.push(dep);
+            }
+            deps
         }
 
         let mut server_action_decls = FxHashMap::default();
@@ -1553,8 +1556,9 @@ impl DepGraph {
 
             // If an export uses $$RSC_SERVER_0, depend on "export $$RSC_SERVER_0"
 
+            let arena = Arena::new();
             let mut done = FxHashSet::default();
-            let dependencies = collect_deps(g, &mut done, node);
+            let dependencies = collect_deps(&arena, g, &mut done, node);
 
             for &dependency in dependencies.iter() {
                 if dependency == node {
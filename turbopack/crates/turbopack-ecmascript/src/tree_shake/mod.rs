@@ -579,6 +579,7 @@ pub(super) async fn split(
                         eval_context.top_level_mark,
                         None,
                         Some(source),
+                        eval_context.imports.verbatim_module_syntax(),
                     );
 
                     ParseResult::resolved_cell(ParseResult::Ok {
@@ -703,6 +704,7 @@ pub(crate) async fn part_of_module(
                         eval_context.top_level_mark,
                         None,
                         None,
+                        eval_context.imports.verbatim_module_syntax(),
                     );
 
                     return Ok(ParseResult::Ok {
@@ -169,6 +169,7 @@ pub fn module_value_to_well_known_object(module_value: &ModuleValue) -> Option<J
             JsValue::WellKnownObject(WellKnownObjectKind::ChildProcess)
         }
         "node:os" | "os" => JsValue::WellKnownObject(WellKnownObjectKind::OsModule),
+        "node:url" | "url" => JsValue::WellKnownObject(WellKnownObjectKind::UrlModule),
         "node:process" | "process" => JsValue::WellKnownObject(WellKnownObjectKind::NodeProcess),
         "@mapbox/node-pre-gyp" => JsValue::WellKnownObject(WellKnownObjectKind::NodePreGyp),
         "node-gyp-build" => JsValue::WellKnownFunction(WellKnownFunctionKind::NodeGypBuild),
@@ -0,0 +1,22 @@
+import fs from "fs";
+import { fileURLToPath, pathToFileURL } from "url";
+
+const name = process.env.NAME;
+
+const relative = fileURLToPath(new URL(`./data/${name}.json`, import.meta.url));
+fs.readFileSync(relative);
+
+const dirname = fileURLToPath(new URL(`file://${__dirname}/data/${name}.json`));
+fs.lstatSync(dirname);
+
+const cwd = fileURLToPath(`file://${process.cwd()}/content/${name}.json`);
+fs.accessSync(cwd);
+
+const constant = fileURLToPath(new URL("file:///data/hello.json"));
+fs.statSync(constant);
+
+const roundTrip = fileURLToPath(pathToFileURL(`${__dirname}/data/${name}.json`));
+fs.existsSync(roundTrip);
+
+const remote = fileURLToPath(new URL(`https://example.com/${name}.json`));
+fs.readFileSync(remote);
@@ -1,6 +1,8 @@
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 /// An issue that occurred while resolving the parsing or evaluating the .env.
 #[turbo_tasks::value(shared)]
@@ -11,6 +13,11 @@ pub struct ProcessEnvIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ProcessEnvIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::PROCESS_ENV_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn title(&self) -> Vc<StyledString> {
         StyledString::Text("Error loading dotenv file".into()).cell()
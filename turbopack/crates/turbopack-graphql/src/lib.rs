@@ -0,0 +1,350 @@
+//! GraphQL document support for turbopack.
+//!
+//! `.graphql` and `.gql` documents are parsed at build time. When imported
+//! from ES modules, they produce a module that exports the `graphql-js`
+//! document AST, like `graphql-tag/loader`. Fragments from `#import` comments
+//! are real module references, so changing a fragment only invalidates the
+//! documents which import it.
+//!
+//! With [GraphQlOptions::persisted_queries], documents containing operations
+//! only export the id of their persisted query instead.
+
+#![feature(min_specialization)]
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+pub mod parse;
+pub mod references;
+
+use std::collections::VecDeque;
+
+use anyhow::{bail, Result};
+use rustc_hash::FxHashSet;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, TryJoinIterExt, Value, ValueToString, Vc};
+use turbo_tasks_fs::FileContent;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{ChunkItem, ChunkItemExt, ChunkType, ChunkableModule, ChunkingContext},
+    context::AssetContext,
+    ident::AssetIdent,
+    module::Module,
+    reference::{ModuleReference, ModuleReferences},
+    resolve::{origin::PlainResolveOrigin, parse::Request},
+    source::Source,
+};
+use turbopack_ecmascript::{
+    chunk::{
+        EcmascriptChunkItem, EcmascriptChunkItemContent, EcmascriptChunkPlaceable,
+        EcmascriptChunkType, EcmascriptExports,
+    },
+    utils::StringifyJs,
+};
+
+use self::references::GraphQlImportReference;
+
+#[turbo_tasks::function]
+fn modifier() -> Vc<RcStr> {
+    Vc::cell("graphql".into())
+}
+
+#[turbo_tasks::value(shared)]
+#[derive(Default, Clone, Debug)]
+pub struct GraphQlOptions {
+    /// Documents containing operations export `{ id, operationName }` instead
+    /// of their AST. The id is the SHA-256 hash of the document and all
+    /// fragments it imports, see [GraphQlModuleAsset::persisted_query].
+    pub persisted_queries: bool,
+}
+
+/// A persisted query, which a server looks up by its id.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone)]
+pub struct PersistedQuery {
+    pub id: RcStr,
+    pub query: RcStr,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct GraphQlModules(Vec<ResolvedVc<GraphQlModuleAsset>>);
+
+#[turbo_tasks::value]
+pub struct GraphQlModuleAsset {
+    source: ResolvedVc<Box<dyn Source>>,
+    asset_context: ResolvedVc<Box<dyn AssetContext>>,
+    options: ResolvedVc<GraphQlOptions>,
+}
+
+#[turbo_tasks::value_impl]
+impl GraphQlModuleAsset {
+    #[turbo_tasks::function]
+    pub fn new(
+        source: ResolvedVc<Box<dyn Source>>,
+        asset_context: ResolvedVc<Box<dyn AssetContext>>,
+        options: ResolvedVc<GraphQlOptions>,
+    ) -> Vc<Self> {
+        Self::cell(GraphQlModuleAsset {
+            source,
+            asset_context,
+            options,
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn text(&self) -> Result<Vc<RcStr>> {
+        let content = self.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            bail!(
+                "GraphQL document not found: {}",
+                self.source.ident().to_string().await?
+            );
+        };
+        Ok(Vc::cell(file.content().to_str()?.into()))
+    }
+
+    /// The `graphql-js` AST of the document, without imported fragments.
+    #[turbo_tasks::function]
+    async fn document(self: Vc<Self>) -> Result<Vc<JsonValue>> {
+        match parse::parse(&self.text().await?) {
+            Ok(document) => Ok(Vc::cell(document)),
+            Err(err) => bail!(
+                "Unable to make a module from an invalid GraphQL document {}: {err}",
+                self.ident().to_string().await?
+            ),
+        }
+    }
+
+    #[turbo_tasks::function]
+    async fn has_operations(self: Vc<Self>) -> Result<Vc<bool>> {
+        let document = self.document().await?;
+        Ok(Vc::cell(
+            document["definitions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|definition| definition["kind"] == "OperationDefinition"),
+        ))
+    }
+
+    /// The GraphQL documents imported with `#import`.
+    #[turbo_tasks::function]
+    async fn imports(self: Vc<Self>) -> Result<Vc<GraphQlModules>> {
+        let imports = self
+            .references()
+            .await?
+            .iter()
+            .map(|reference| async move {
+                let Some(module) = *reference.resolve_reference().first_module().await? else {
+                    return Ok(None);
+                };
+                Ok(ResolvedVc::try_downcast_type::<GraphQlModuleAsset>(module).await?)
+            })
+            .try_join()
+            .await?;
+        Ok(Vc::cell(imports.into_iter().flatten().collect()))
+    }
+
+    /// The persisted query of the document. The query is the text of the
+    /// document, followed by the text of all documents it imports, directly
+    /// or transitively, in breadth-first order.
+    #[turbo_tasks::function]
+    pub async fn persisted_query(self: ResolvedVc<Self>) -> Result<Vc<PersistedQuery>> {
+        let mut visited = FxHashSet::default();
+        let mut queue = VecDeque::from([self]);
+        let mut texts = Vec::new();
+        while let Some(module) = queue.pop_front() {
+            if !visited.insert(module) {
+                continue;
+            }
+            texts.push(module.text().await?);
+            queue.extend(module.imports().await?.iter().copied());
+        }
+        let query = texts
+            .iter()
+            .map(|text| text.trim())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(PersistedQuery {
+            id: format!("{:x}", Sha256::digest(query.as_bytes())).into(),
+            query: query.into(),
+        }
+        .cell())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Module for GraphQlModuleAsset {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source
+            .ident()
+            .with_modifier(modifier())
+            .with_layer(self.asset_context.layer())
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<ModuleReferences>> {
+        let this = self.await?;
+        let origin = ResolvedVc::upcast(
+            PlainResolveOrigin::new(*this.asset_context, this.source.ident().path())
+                .to_resolved()
+                .await?,
+        );
+        let references = parse::imports(&self.text().await?)
+            .into_iter()
+            .map(|path| async move {
+                Ok(ResolvedVc::upcast(
+                    GraphQlImportReference::new(
+                        origin,
+                        Request::parse(Value::new(RcStr::from(path).into()))
+                            .to_resolved()
+                            .await?,
+                    )
+                    .to_resolved()
+                    .await?,
+                ))
+            })
+            .try_join()
+            .await?;
+        Ok(Vc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for GraphQlModuleAsset {
+    #[turbo_tasks::function]
+    fn content(&self) -> Vc<AssetContent> {
+        self.source.content()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModule for GraphQlModuleAsset {
+    #[turbo_tasks::function]
+    fn as_chunk_item(
+        self: ResolvedVc<Self>,
+        chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+    ) -> Vc<Box<dyn ChunkItem>> {
+        Vc::upcast(GraphQlChunkItem::cell(GraphQlChunkItem {
+            module: self,
+            chunking_context,
+        }))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkPlaceable for GraphQlModuleAsset {
+    #[turbo_tasks::function]
+    fn get_exports(&self) -> Vc<EcmascriptExports> {
+        EcmascriptExports::Value.cell()
+    }
+}
+
+#[turbo_tasks::value]
+struct GraphQlChunkItem {
+    module: ResolvedVc<GraphQlModuleAsset>,
+    chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkItem for GraphQlChunkItem {
+    #[turbo_tasks::function]
+    fn asset_ident(&self) -> Vc<AssetIdent> {
+        self.module.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<ModuleReferences> {
+        self.module.references()
+    }
+
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        *self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    async fn ty(&self) -> Result<Vc<Box<dyn ChunkType>>> {
+        Ok(Vc::upcast(
+            Vc::<EcmascriptChunkType>::default().resolve().await?,
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn module(&self) -> Vc<Box<dyn Module>> {
+        *ResolvedVc::upcast(self.module)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptChunkItem for GraphQlChunkItem {
+    #[turbo_tasks::function]
+    fn chunking_context(&self) -> Vc<Box<dyn ChunkingContext>> {
+        *self.chunking_context
+    }
+
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<EcmascriptChunkItemContent>> {
+        let module = *self.module;
+        let document = module.document().await?;
+
+        let inner_code = if self.module.await?.options.await?.persisted_queries
+            && *module.has_operations().await?
+        {
+            let persisted_query = module.persisted_query().await?;
+            let operation_name = document["definitions"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|definition| definition["kind"] == "OperationDefinition")
+                .and_then(|operation| operation["name"]["value"].as_str());
+            format!(
+                "__turbopack_export_value__({{ id: {}, operationName: {} }});",
+                StringifyJs(&persisted_query.id),
+                StringifyJs(&operation_name)
+            )
+        } else {
+            let mut code = format!(
+                "const doc = JSON.parse({});\n",
+                StringifyJs(&document.to_string())
+            );
+            let imports = module.imports().await?;
+            if !imports.is_empty() {
+                // Like `graphql-tag/loader`, fragments which are imported more
+                // than once are only added once.
+                code.push_str(
+                    "const names = new Set(doc.definitions.map((d) => d.kind + (d.name && \
+                     d.name.value)));\nconst add = (imported) => {\n  for (const d of \
+                     imported.definitions) {\n    const name = d.kind + (d.name && \
+                     d.name.value);\n    if (!names.has(name)) {\n      names.add(name);\n      \
+                     doc.definitions.push(d);\n    }\n  }\n};\n",
+                );
+                for import in imports.iter() {
+                    let id = import.as_chunk_item(*self.chunking_context).id().await?;
+                    code.push_str(&format!(
+                        "add(__turbopack_require__({}));\n",
+                        StringifyJs(&*id)
+                    ));
+                }
+            }
+            code.push_str("__turbopack_export_value__(doc);");
+            code
+        };
+
+        Ok(EcmascriptChunkItemContent {
+            inner_code: inner_code.into(),
+            ..Default::default()
+        }
+        .into())
+    }
+}
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    turbopack_ecmascript::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
@@ -0,0 +1,318 @@
+//! Converts executable GraphQL documents parsed by `graphql-parser` into the
+//! same AST as `graphql-js`, so the result can be used by GraphQL clients
+//! without parsing it again at runtime.
+
+use anyhow::{anyhow, Result};
+use graphql_parser::query::{
+    parse_query, Definition, Directive, OperationDefinition, Selection, SelectionSet, Type,
+    TypeCondition, Value, VariableDefinition,
+};
+use serde_json::{json, Map, Value as JsonValue};
+
+/// Returns the paths of the `#import "./fragment.graphql"` comments of a
+/// document, as understood by `graphql-tag/loader`.
+pub fn imports(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| {
+            let path = line.trim().strip_prefix("#import")?.trim();
+            let quote = path.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let path = path[1..].strip_suffix(quote)?;
+            Some(path.to_string())
+        })
+        .collect()
+}
+
+/// Parses an executable GraphQL document into a `graphql-js` `DocumentNode`.
+/// Locations are omitted, except for the location of the whole document.
+///
+/// `graphql-parser` doesn't keep everything `graphql-js` does: floats are
+/// reformatted, block strings are marked as regular strings and directives of
+/// variable definitions are not supported.
+pub fn parse(source: &str) -> Result<JsonValue> {
+    let document =
+        parse_query::<&str>(source).map_err(|err| anyhow!("{}", err.to_string().trim_end()))?;
+    Ok(json!({
+        "kind": "Document",
+        "definitions": document.definitions.iter().map(definition).collect::<Vec<_>>(),
+        "loc": {
+            "start": 0,
+            "end": source.len(),
+            "source": {
+                "body": source,
+                "name": "GraphQL request",
+                "locationOffset": { "line": 1, "column": 1 },
+            },
+        },
+    }))
+}
+
+fn name(name: &str) -> JsonValue {
+    json!({ "kind": "Name", "value": name })
+}
+
+fn definition<'a>(definition: &Definition<'a, &'a str>) -> JsonValue {
+    match definition {
+        Definition::Operation(operation) => match operation {
+            OperationDefinition::SelectionSet(selection_set) => json!({
+                "kind": "OperationDefinition",
+                "operation": "query",
+                "variableDefinitions": [],
+                "directives": [],
+                "selectionSet": self::selection_set(selection_set),
+            }),
+            OperationDefinition::Query(query) => operation_definition(
+                "query",
+                query.name,
+                &query.variable_definitions,
+                &query.directives,
+                &query.selection_set,
+            ),
+            OperationDefinition::Mutation(mutation) => operation_definition(
+                "mutation",
+                mutation.name,
+                &mutation.variable_definitions,
+                &mutation.directives,
+                &mutation.selection_set,
+            ),
+            OperationDefinition::Subscription(subscription) => operation_definition(
+                "subscription",
+                subscription.name,
+                &subscription.variable_definitions,
+                &subscription.directives,
+                &subscription.selection_set,
+            ),
+        },
+        Definition::Fragment(fragment) => json!({
+            "kind": "FragmentDefinition",
+            "name": name(fragment.name),
+            "typeCondition": type_condition(&fragment.type_condition),
+            "directives": directives(&fragment.directives),
+            "selectionSet": selection_set(&fragment.selection_set),
+        }),
+    }
+}
+
+fn operation_definition<'a>(
+    operation: &str,
+    operation_name: Option<&str>,
+    variable_definitions: &[VariableDefinition<'a, &'a str>],
+    operation_directives: &[Directive<'a, &'a str>],
+    operation_selection_set: &SelectionSet<'a, &'a str>,
+) -> JsonValue {
+    let mut node = Map::new();
+    node.insert("kind".into(), "OperationDefinition".into());
+    node.insert("operation".into(), operation.into());
+    if let Some(operation_name) = operation_name {
+        node.insert("name".into(), name(operation_name));
+    }
+    node.insert(
+        "variableDefinitions".into(),
+        variable_definitions
+            .iter()
+            .map(variable_definition)
+            .collect::<Vec<_>>()
+            .into(),
+    );
+    node.insert("directives".into(), directives(operation_directives).into());
+    node.insert(
+        "selectionSet".into(),
+        selection_set(operation_selection_set),
+    );
+    node.into()
+}
+
+fn variable_definition<'a>(definition: &VariableDefinition<'a, &'a str>) -> JsonValue {
+    let mut node = Map::new();
+    node.insert("kind".into(), "VariableDefinition".into());
+    node.insert("variable".into(), variable(definition.name));
+    node.insert("type".into(), type_reference(&definition.var_type));
+    if let Some(default_value) = &definition.default_value {
+        node.insert("defaultValue".into(), value(default_value));
+    }
+    node.insert("directives".into(), json!([]));
+    node.into()
+}
+
+fn variable(variable_name: &str) -> JsonValue {
+    json!({ "kind": "Variable", "name": name(variable_name) })
+}
+
+fn type_reference<'a>(ty: &Type<'a, &'a str>) -> JsonValue {
+    match ty {
+        Type::NamedType(type_name) => json!({ "kind": "NamedType", "name": name(type_name) }),
+        Type::ListType(inner) => json!({ "kind": "ListType", "type": type_reference(inner) }),
+        Type::NonNullType(inner) => {
+            json!({ "kind": "NonNullType", "type": type_reference(inner) })
+        }
+    }
+}
+
+fn type_condition<'a>(condition: &TypeCondition<'a, &'a str>) -> JsonValue {
+    let TypeCondition::On(type_name) = condition;
+    json!({ "kind": "NamedType", "name": name(type_name) })
+}
+
+fn selection_set<'a>(selection_set: &SelectionSet<'a, &'a str>) -> JsonValue {
+    json!({
+        "kind": "SelectionSet",
+        "selections": selection_set.items.iter().map(selection).collect::<Vec<_>>(),
+    })
+}
+
+fn selection<'a>(selection: &Selection<'a, &'a str>) -> JsonValue {
+    match selection {
+        Selection::Field(field) => {
+            let mut node = Map::new();
+            node.insert("kind".into(), "Field".into());
+            if let Some(alias) = field.alias {
+                node.insert("alias".into(), name(alias));
+            }
+            node.insert("name".into(), name(field.name));
+            node.insert("arguments".into(), arguments(&field.arguments).into());
+            node.insert("directives".into(), directives(&field.directives).into());
+            // Leaf fields have an empty selection set in `graphql-parser`.
+            if !field.selection_set.items.is_empty() {
+                node.insert("selectionSet".into(), selection_set(&field.selection_set));
+            }
+            node.into()
+        }
+        Selection::FragmentSpread(spread) => json!({
+            "kind": "FragmentSpread",
+            "name": name(spread.fragment_name),
+            "directives": directives(&spread.directives),
+        }),
+        Selection::InlineFragment(fragment) => {
+            let mut node = Map::new();
+            node.insert("kind".into(), "InlineFragment".into());
+            if let Some(condition) = &fragment.type_condition {
+                node.insert("typeCondition".into(), type_condition(condition));
+            }
+            node.insert("directives".into(), directives(&fragment.directives).into());
+            node.insert(
+                "selectionSet".into(),
+                selection_set(&fragment.selection_set),
+            );
+            node.into()
+        }
+    }
+}
+
+fn arguments<'a>(arguments: &[(&'a str, Value<'a, &'a str>)]) -> Vec<JsonValue> {
+    arguments
+        .iter()
+        .map(|(argument_name, argument_value)| {
+            json!({
+                "kind": "Argument",
+                "name": name(argument_name),
+                "value": value(argument_value),
+            })
+        })
+        .collect()
+}
+
+fn directives<'a>(directives: &[Directive<'a, &'a str>]) -> Vec<JsonValue> {
+    directives
+        .iter()
+        .map(|directive| {
+            json!({
+                "kind": "Directive",
+                "name": name(directive.name),
+                "arguments": arguments(&directive.arguments),
+            })
+        })
+        .collect()
+}
+
+fn value<'a>(value: &Value<'a, &'a str>) -> JsonValue {
+    match value {
+        Value::Variable(variable_name) => variable(variable_name),
+        Value::Int(number) => json!({
+            "kind": "IntValue",
+            "value": number.as_i64().map(|number| number.to_string()),
+        }),
+        Value::Float(number) => json!({ "kind": "FloatValue", "value": number.to_string() }),
+        Value::String(string) => json!({ "kind": "StringValue", "value": string, "block": false }),
+        Value::Boolean(boolean) => json!({ "kind": "BooleanValue", "value": boolean }),
+        Value::Null => json!({ "kind": "NullValue" }),
+        Value::Enum(enum_value) => json!({ "kind": "EnumValue", "value": enum_value }),
+        Value::List(values) => json!({
+            "kind": "ListValue",
+            "values": values.iter().map(self::value).collect::<Vec<_>>(),
+        }),
+        Value::Object(fields) => json!({
+            "kind": "ObjectValue",
+            "fields": fields
+                .iter()
+                .map(|(field_name, field_value)| {
+                    json!({
+                        "kind": "ObjectField",
+                        "name": name(field_name),
+                        "value": self::value(field_value),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operation_with_fragments() {
+        let source = r#"
+            #import "./user.graphql"
+            query Users($first: Int = 10, $ids: [ID!]!) @live {
+              users(first: $first, filter: { ids: $ids, active: true }) {
+                ...User
+                ... on Admin { role }
+                alias: name
+              }
+            }
+        "#;
+        assert_eq!(imports(source), vec!["./user.graphql"]);
+
+        let document = parse(source).unwrap();
+        let operation = &document["definitions"][0];
+        assert_eq!(operation["operation"], "query");
+        assert_eq!(operation["name"]["value"], "Users");
+        assert_eq!(
+            operation["variableDefinitions"][1]["type"],
+            json!({
+                "kind": "NonNullType",
+                "type": {
+                    "kind": "ListType",
+                    "type": {
+                        "kind": "NonNullType",
+                        "type": { "kind": "NamedType", "name": { "kind": "Name", "value": "ID" } },
+                    },
+                },
+            })
+        );
+        let selections = &operation["selectionSet"]["selections"][0]["selectionSet"]["selections"];
+        assert_eq!(selections[0]["kind"], "FragmentSpread");
+        assert_eq!(selections[1]["typeCondition"]["name"]["value"], "Admin");
+        assert_eq!(selections[2]["alias"]["value"], "alias");
+    }
+
+    #[test]
+    fn block_string() {
+        let document = parse("{ a(b: \"\"\"\n    x\n      y\n  \"\"\") }").unwrap();
+        assert_eq!(
+            document["definitions"][0]["selectionSet"]["selections"][0]["arguments"][0]["value"]
+                ["value"],
+            "x\n  y"
+        );
+    }
+
+    #[test]
+    fn error_location() {
+        let error = parse("query {\n  a(\n}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "query parse error: Parse error at 3:1\nUnexpected `}[Punctuator]`\nExpected Name"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Value, ValueToString, Vc};
+use turbopack_core::{
+    chunk::ChunkableModuleReference,
+    reference::ModuleReference,
+    reference_type::ReferenceType,
+    resolve::{origin::ResolveOrigin, parse::Request, url_resolve, ModuleResolveResult},
+};
+
+/// A `#import "./fragment.graphql"` reference of a GraphQL document.
+#[turbo_tasks::value]
+#[derive(Hash, Debug)]
+pub struct GraphQlImportReference {
+    pub origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+    pub request: ResolvedVc<Request>,
+}
+
+#[turbo_tasks::value_impl]
+impl GraphQlImportReference {
+    #[turbo_tasks::function]
+    pub fn new(
+        origin: ResolvedVc<Box<dyn ResolveOrigin>>,
+        request: ResolvedVc<Request>,
+    ) -> Vc<Self> {
+        Self::cell(GraphQlImportReference { origin, request })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleReference for GraphQlImportReference {
+    #[turbo_tasks::function]
+    fn resolve_reference(&self) -> Vc<ModuleResolveResult> {
+        // Like `graphql-tag/loader`, bare paths are resolved relative to the
+        // document first.
+        url_resolve(
+            *self.origin,
+            *self.request,
+            Value::new(ReferenceType::Undefined),
+            None,
+            false,
+        )
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for GraphQlImportReference {
+    #[turbo_tasks::function]
+    async fn to_string(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!("#import {}", self.request.to_string().await?).into(),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ChunkableModuleReference for GraphQlImportReference {}
@@ -0,0 +1,693 @@
+//! Reads the duration, dimensions and codecs of audio and video files from
+//! their container, without decoding any media data.
+
+use std::{borrow::Cow, cmp::min, ops::Range};
+
+use anyhow::{bail, Context, Result};
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks_fs::{rope::Rope, FileContent, FileSystemPath};
+use turbopack_core::{
+    error::PrettyPrintError,
+    ident::AssetIdent,
+    issue::{IssueExt, StyledString},
+};
+
+use super::ImageProcessingIssue;
+
+/// Gathered meta information about an audio or video file.
+#[serde_as]
+#[turbo_tasks::value]
+#[derive(Default, Debug)]
+pub struct MediaMetaData {
+    /// The duration in milliseconds.
+    pub duration_ms: Option<u64>,
+    /// The dimensions of the first video track.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The codecs of all tracks, as named by the container, e.g. `avc1`,
+    /// `mp4a` or `V_VP9`.
+    pub codecs: Vec<String>,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub mime_type: Option<Mime>,
+}
+
+/// The containers [get_media_meta_data] understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaContainer {
+    /// MP4 and QuickTime (ISO base media file format).
+    Mp4,
+    /// WebM and Matroska.
+    Matroska,
+    /// MPEG audio layer III.
+    Mp3,
+}
+
+impl MediaContainer {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Some(match extension {
+            "mp4" | "m4v" | "m4a" | "mov" => MediaContainer::Mp4,
+            "webm" | "mkv" | "mka" => MediaContainer::Matroska,
+            "mp3" => MediaContainer::Mp3,
+            _ => return None,
+        })
+    }
+}
+
+/// Analyzes an audio or video file and returns meta information about it.
+/// Files which can't be read result in an issue and empty meta data.
+#[turbo_tasks::function]
+pub async fn get_media_meta_data(
+    ident: Vc<AssetIdent>,
+    content: Vc<FileContent>,
+) -> Result<Vc<MediaMetaData>> {
+    let FileContent::Content(content) = &*content.await? else {
+        bail!("Input media not found");
+    };
+    let data = content.content();
+    let path_resolved = ident.path().to_resolved().await?;
+    let path = path_resolved.await?;
+    let Some(container) = path
+        .extension_ref()
+        .and_then(MediaContainer::from_extension)
+    else {
+        return Ok(MediaMetaData::default().cell());
+    };
+    let meta_data = match container {
+        MediaContainer::Mp4 => read_mp4(data, path.extension_ref() == Some("m4a")),
+        MediaContainer::Matroska => read_matroska(&data.range(0..MAX_HEADER_LEN)?),
+        MediaContainer::Mp3 => read_mp3(data),
+    };
+    let meta_data =
+        meta_data.with_context(|| format!("Failed to read the {container:?} container"));
+    Ok(result_to_issue(path_resolved, meta_data)
+        .unwrap_or_default()
+        .cell())
+}
+
+fn result_to_issue(
+    path: ResolvedVc<FileSystemPath>,
+    result: Result<MediaMetaData>,
+) -> Option<MediaMetaData> {
+    match result {
+        Ok(meta_data) => Some(meta_data),
+        Err(err) => {
+            ImageProcessingIssue {
+                path,
+                message: StyledString::Text(format!("{}", PrettyPrintError(&err)).into())
+                    .resolved_cell(),
+                issue_severity: None,
+                title: Some(StyledString::Text("Processing media failed".into()).resolved_cell()),
+            }
+            .cell()
+            .emit();
+            None
+        }
+    }
+}
+
+/// How many bytes are read at most to find the meta data at the start of a
+/// file, before its media data.
+const MAX_HEADER_LEN: usize = 1024 * 1024;
+
+/// The content of a media file, of which only the parts containing meta data
+/// are read.
+trait MediaData {
+    fn len(&self) -> usize;
+
+    /// The bytes in `range`, which is clamped to the end of the data.
+    fn range(&self, range: Range<usize>) -> Result<Cow<'_, [u8]>>;
+}
+
+impl MediaData for [u8] {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn range(&self, range: Range<usize>) -> Result<Cow<'_, [u8]>> {
+        let end = min(range.end, self.len());
+        Ok(Cow::Borrowed(&self[min(range.start, end)..end]))
+    }
+}
+
+impl MediaData for Rope {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn range(&self, range: Range<usize>) -> Result<Cow<'_, [u8]>> {
+        // Binary data can't be sliced with `Rope::slice`, which only splits
+        // between UTF-8 encoded characters.
+        let mut bytes = Vec::new();
+        let mut offset = 0;
+        for chunk in self.read() {
+            let chunk_start = offset;
+            offset += chunk.len();
+            if offset <= range.start {
+                continue;
+            }
+            if chunk_start >= range.end {
+                break;
+            }
+            let start = range.start.saturating_sub(chunk_start);
+            let end = min(chunk.len(), range.end - chunk_start);
+            bytes.extend_from_slice(&chunk[start..end]);
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
+
+/// A cursor over big endian binary data.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.checked_add(len).context("length overflow")?)
+            .context("unexpected end of data")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn uint(&mut self, len: usize) -> Result<u64> {
+        Ok(self
+            .bytes(len)?
+            .iter()
+            .fold(0, |value, byte| (value << 8) | *byte as u64))
+    }
+}
+
+fn read_mp4(data: &(impl MediaData + ?Sized), audio_only: bool) -> Result<MediaMetaData> {
+    let mut meta_data = MediaMetaData::default();
+    let moov = find_mp4_box(data, b"moov")?.context("no movie box (moov) found")?;
+    let mut has_video = false;
+    for (ty, content) in mp4_boxes(&moov)? {
+        match &ty {
+            b"mvhd" => {
+                let mut reader = Reader::new(content);
+                let version = reader.u8()?;
+                reader.bytes(3)?;
+                let (timescale, duration) = if version == 1 {
+                    reader.bytes(16)?;
+                    (reader.uint(4)?, reader.uint(8)?)
+                } else {
+                    reader.bytes(8)?;
+                    (reader.uint(4)?, reader.uint(4)?)
+                };
+                let duration = duration
+                    .checked_mul(1000)
+                    .context("the duration is too long")?;
+                meta_data.duration_ms = duration.checked_div(timescale);
+            }
+            b"trak" => {
+                let track = read_mp4_track(content)?;
+                meta_data.codecs.extend(track.codec);
+                if track.handler == *b"vide" && meta_data.width.is_none() {
+                    has_video = true;
+                    meta_data.width = track.width;
+                    meta_data.height = track.height;
+                }
+            }
+            _ => {}
+        }
+    }
+    meta_data.mime_type = Some(if has_video || !audio_only {
+        "video/mp4".parse()?
+    } else {
+        "audio/mp4".parse()?
+    });
+    Ok(meta_data)
+}
+
+#[derive(Default)]
+struct Mp4Track {
+    handler: [u8; 4],
+    codec: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+fn read_mp4_track(trak: &[u8]) -> Result<Mp4Track> {
+    let mut track = Mp4Track::default();
+    let mut containers = vec![trak];
+    while let Some(container) = containers.pop() {
+        for (ty, content) in mp4_boxes(container)? {
+            match &ty {
+                b"mdia" | b"minf" | b"stbl" => containers.push(content),
+                b"tkhd" if content.len() >= 8 => {
+                    // The dimensions are 16.16 fixed point numbers at the end.
+                    let mut reader = Reader::new(&content[content.len() - 8..]);
+                    let width = (reader.uint(4)? >> 16) as u32;
+                    let height = (reader.uint(4)? >> 16) as u32;
+                    if width > 0 && height > 0 {
+                        track.width = Some(width);
+                        track.height = Some(height);
+                    }
+                }
+                b"hdlr" => {
+                    let mut reader = Reader::new(content);
+                    reader.bytes(8)?;
+                    track.handler.copy_from_slice(reader.bytes(4)?);
+                }
+                b"stsd" => {
+                    let mut reader = Reader::new(content);
+                    reader.bytes(8)?;
+                    if let Some((codec, _)) = mp4_boxes(&content[reader.pos..])?.first() {
+                        track.codec = Some(String::from_utf8_lossy(codec).trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(track)
+}
+
+/// Splits ISO base media file format data into its boxes.
+fn mp4_boxes(data: &[u8]) -> Result<Vec<([u8; 4], &[u8])>> {
+    let mut boxes = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let (ty, content) = mp4_box_header(&data[start..], start, data.len())?;
+        start = content.end;
+        boxes.push((ty, &data[content]));
+    }
+    Ok(boxes)
+}
+
+/// Finds the top level box of type `ty` and returns its content. Only the
+/// headers of the boxes before it are read, as they might contain all of the
+/// media data.
+fn find_mp4_box<'a>(
+    data: &'a (impl MediaData + ?Sized),
+    ty: &[u8; 4],
+) -> Result<Option<Cow<'a, [u8]>>> {
+    let mut start = 0;
+    while start < data.len() {
+        let (box_ty, content) = mp4_box_header(&data.range(start..start + 16)?, start, data.len())?;
+        if box_ty == *ty {
+            return Ok(Some(data.range(content)?));
+        }
+        start = content.end;
+    }
+    Ok(None)
+}
+
+/// Reads the header of the box at `start`, the beginning of `header`, and
+/// returns the box's type and the range of its content.
+fn mp4_box_header(header: &[u8], start: usize, len: usize) -> Result<([u8; 4], Range<usize>)> {
+    let mut reader = Reader::new(header);
+    let size = reader.uint(4)?;
+    let mut ty = [0; 4];
+    ty.copy_from_slice(reader.bytes(4)?);
+    let end = match size {
+        0 => Some(len),
+        1 => usize::try_from(reader.uint(8)?)
+            .ok()
+            .and_then(|size| start.checked_add(size)),
+        size => start.checked_add(size as usize),
+    };
+    let content_start = start + reader.pos;
+    match end {
+        Some(end) if end >= content_start && end <= len => Ok((ty, content_start..end)),
+        _ => bail!("box {} has an invalid size", String::from_utf8_lossy(&ty)),
+    }
+}
+
+const EBML_HEADER: u64 = 0x1a45dfa3;
+const EBML_DOC_TYPE: u64 = 0x4282;
+const SEGMENT: u64 = 0x18538067;
+const SEGMENT_INFO: u64 = 0x1549a966;
+const TIMESTAMP_SCALE: u64 = 0x2ad7b1;
+const DURATION: u64 = 0x4489;
+const TRACKS: u64 = 0x1654ae6b;
+const TRACK_ENTRY: u64 = 0xae;
+const TRACK_TYPE: u64 = 0x83;
+const CODEC_ID: u64 = 0x86;
+const VIDEO: u64 = 0xe0;
+const PIXEL_WIDTH: u64 = 0xb0;
+const PIXEL_HEIGHT: u64 = 0xba;
+const CLUSTER: u64 = 0x1f43b675;
+
+fn read_matroska(data: &[u8]) -> Result<MediaMetaData> {
+    let mut meta_data = MediaMetaData::default();
+    let mut doc_type = None;
+    let mut timestamp_scale = 1_000_000;
+    let mut duration = None;
+    let mut has_video = false;
+
+    for (id, content) in ebml_elements(data)? {
+        match id {
+            EBML_HEADER => {
+                for (id, content) in ebml_elements(content)? {
+                    if id == EBML_DOC_TYPE {
+                        doc_type = Some(String::from_utf8_lossy(content).to_string());
+                    }
+                }
+            }
+            SEGMENT => {
+                for (id, content) in ebml_elements(content)? {
+                    match id {
+                        SEGMENT_INFO => {
+                            for (id, content) in ebml_elements(content)? {
+                                match id {
+                                    TIMESTAMP_SCALE => {
+                                        timestamp_scale =
+                                            Reader::new(content).uint(content.len())?
+                                    }
+                                    DURATION => duration = Some(ebml_float(content)?),
+                                    _ => {}
+                                }
+                            }
+                        }
+                        TRACKS => {
+                            for (id, content) in ebml_elements(content)? {
+                                if id != TRACK_ENTRY {
+                                    continue;
+                                }
+                                let track_video = read_matroska_track(content, &mut meta_data)?;
+                                has_video |= track_video;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !matches!(doc_type.as_deref(), Some("webm" | "matroska")) {
+        bail!("not a WebM or Matroska file");
+    }
+    if let Some(duration) = duration {
+        meta_data.duration_ms = Some((duration * timestamp_scale as f64 / 1_000_000.0) as u64);
+    }
+    let kind = if has_video { "video" } else { "audio" };
+    let subtype = if doc_type.as_deref() == Some("webm") {
+        "webm"
+    } else {
+        "x-matroska"
+    };
+    meta_data.mime_type = Some(format!("{kind}/{subtype}").parse()?);
+    Ok(meta_data)
+}
+
+/// Reads a track entry. Returns whether it's a video track.
+fn read_matroska_track(entry: &[u8], meta_data: &mut MediaMetaData) -> Result<bool> {
+    let mut is_video = false;
+    for (id, content) in ebml_elements(entry)? {
+        match id {
+            TRACK_TYPE => is_video = Reader::new(content).uint(content.len())? == 1,
+            CODEC_ID => meta_data
+                .codecs
+                .push(String::from_utf8_lossy(content).to_string()),
+            VIDEO if meta_data.width.is_none() => {
+                for (id, content) in ebml_elements(content)? {
+                    let value = Reader::new(content).uint(content.len())? as u32;
+                    match id {
+                        PIXEL_WIDTH => meta_data.width = Some(value),
+                        PIXEL_HEIGHT => meta_data.height = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(is_video)
+}
+
+/// Splits EBML data into its elements. Elements of unknown size extend to the
+/// end of the data. Clusters, which only contain media data, end the list.
+fn ebml_elements(data: &[u8]) -> Result<Vec<(u64, &[u8])>> {
+    let mut elements = Vec::new();
+    let mut reader = Reader::new(data);
+    while !reader.is_empty() {
+        let (id, _) = ebml_vint(&mut reader)?;
+        if id == CLUSTER {
+            break;
+        }
+        let (size, length) = ebml_vint(&mut reader)?;
+        let marker = 1 << (7 * length);
+        let size = size & (marker - 1);
+        let end = if size == marker - 1 {
+            data.len()
+        } else {
+            reader.pos.saturating_add(size as usize).min(data.len())
+        };
+        elements.push((id, &data[reader.pos..end]));
+        reader.pos = end;
+    }
+    Ok(elements)
+}
+
+/// Reads a variable size integer, including its length marker, and returns it
+/// with its length in bytes.
+fn ebml_vint(reader: &mut Reader) -> Result<(u64, usize)> {
+    let first = reader.u8()?;
+    if first == 0 {
+        bail!("invalid variable size integer");
+    }
+    let length = first.leading_zeros() as usize + 1;
+    let rest = reader.uint(length - 1)?;
+    Ok((((first as u64) << (8 * (length - 1))) | rest, length))
+}
+
+fn ebml_float(data: &[u8]) -> Result<f64> {
+    Ok(match data.len() {
+        4 => f32::from_be_bytes(data.try_into()?) as f64,
+        8 => f64::from_be_bytes(data.try_into()?),
+        0 => 0.0,
+        len => bail!("invalid float size {len}"),
+    })
+}
+
+fn read_mp3(file: &(impl MediaData + ?Sized)) -> Result<MediaMetaData> {
+    let mut offset = 0;
+    // Skip an ID3v2 tag, whose size is a sync safe integer.
+    let id3 = file.range(0..10)?;
+    if id3.starts_with(b"ID3") && id3.len() >= 10 {
+        let size = id3[6..10]
+            .iter()
+            .fold(0usize, |size, byte| (size << 7) | (*byte & 0x7f) as usize);
+        let footer = if id3[5] & 0x10 != 0 { 10 } else { 0 };
+        offset = 10 + size + footer;
+    }
+    let data = file.range(offset..offset + MAX_HEADER_LEN)?;
+    let frame = data
+        .windows(4)
+        .position(|w| w[0] == 0xff && w[1] & 0xe0 == 0xe0 && (w[1] >> 1) & 3 == 1)
+        .context("no MPEG audio layer III frame found")?;
+    let header = &data[frame..frame + 4];
+
+    let version = (header[1] >> 3) & 3;
+    let mpeg1 = version == 3;
+    let bitrate_index = (header[2] >> 4) as usize;
+    let sample_rate_index = ((header[2] >> 2) & 3) as usize;
+    let mono = header[3] >> 6 == 3;
+    if version == 1 || bitrate_index == 0 || bitrate_index == 15 || sample_rate_index == 3 {
+        bail!("invalid MPEG audio frame header");
+    }
+
+    const BITRATES_MPEG1: [u64; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const BITRATES_MPEG2: [u64; 15] =
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+    let bitrate = if mpeg1 {
+        BITRATES_MPEG1[bitrate_index]
+    } else {
+        BITRATES_MPEG2[bitrate_index]
+    } * 1000;
+    let sample_rate = [44100, 48000, 32000][sample_rate_index]
+        >> match version {
+            3 => 0,
+            2 => 1,
+            _ => 2,
+        };
+    let samples_per_frame = if mpeg1 { 1152 } else { 576 };
+
+    // A Xing or Info header after the side information, or a VBRI header at a
+    // fixed position, contains the number of frames of variable bitrate files.
+    let side_info = match (mpeg1, mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing = frame + 4 + side_info;
+    let vbri = frame + 4 + 32;
+    let frames = if matches!(data.get(xing..xing + 4), Some(b"Xing" | b"Info")) {
+        let mut reader = Reader::new(&data[xing + 4..]);
+        let flags = reader.uint(4)?;
+        if flags & 1 != 0 {
+            Some(reader.uint(4)?)
+        } else {
+            None
+        }
+    } else if data.get(vbri..vbri + 4) == Some(b"VBRI") {
+        let mut reader = Reader::new(&data[vbri + 4..]);
+        reader.bytes(10)?;
+        Some(reader.uint(4)?)
+    } else {
+        None
+    };
+
+    let duration_ms = match frames {
+        Some(frames) => frames * samples_per_frame * 1000 / sample_rate,
+        None => (file.len() - offset - frame) as u64 * 8 * 1000 / bitrate,
+    };
+    Ok(MediaMetaData {
+        duration_ms: Some(duration_ms),
+        width: None,
+        height: None,
+        codecs: vec!["mp3".to_string()],
+        mime_type: Some(mime::AUDIO_MPEG),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(ty: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut data = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(ty);
+        data.extend_from_slice(content);
+        data
+    }
+
+    #[test]
+    fn mp4() {
+        let mut mvhd = vec![0; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&2500u32.to_be_bytes());
+        let mut tkhd = vec![0; 76];
+        tkhd.extend_from_slice(&(640u32 << 16).to_be_bytes());
+        tkhd.extend_from_slice(&(360u32 << 16).to_be_bytes());
+        let mut hdlr = vec![0; 8];
+        hdlr.extend_from_slice(b"vide");
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend(mp4_box(b"avc1", &[0; 8]));
+        let stbl = mp4_box(b"stbl", &mp4_box(b"stsd", &stsd));
+        let minf = mp4_box(b"minf", &stbl);
+        let mdia = mp4_box(b"mdia", &[mp4_box(b"hdlr", &hdlr), minf].concat());
+        let trak = mp4_box(b"trak", &[mp4_box(b"tkhd", &tkhd), mdia].concat());
+        let moov = mp4_box(b"moov", &[mp4_box(b"mvhd", &mvhd), trak].concat());
+        let data = [mp4_box(b"ftyp", b"isom"), moov].concat();
+
+        let meta_data = read_mp4(&data[..], false).unwrap();
+        assert_eq!(meta_data.duration_ms, Some(2500));
+        assert_eq!((meta_data.width, meta_data.height), (Some(640), Some(360)));
+        assert_eq!(meta_data.codecs, vec!["avc1"]);
+    }
+
+    #[test]
+    fn mp3_constant_bitrate() {
+        // MPEG 1 layer III, 128 kbit/s, 44.1 kHz, one second of data.
+        let mut data = vec![0xff, 0xfb, 0x90, 0x00];
+        data.resize(16_000, 0);
+        let meta_data = read_mp3(&data[..]).unwrap();
+        assert_eq!(meta_data.duration_ms, Some(1000));
+    }
+
+    #[test]
+    fn mp4_invalid_box_sizes() {
+        // A 64 bit size which overflows the end of the box.
+        let mut data = vec![0, 0, 0, 1];
+        data.extend_from_slice(b"moov");
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert!(read_mp4(&data[..], false).is_err());
+
+        // A version 1 header with a duration which overflows in milliseconds.
+        let mut mvhd = vec![1, 0, 0, 0];
+        mvhd.extend_from_slice(&[0; 16]);
+        mvhd.extend_from_slice(&1u32.to_be_bytes());
+        mvhd.extend_from_slice(&u64::MAX.to_be_bytes());
+        let data = mp4_box(b"moov", &mp4_box(b"mvhd", &mvhd));
+        assert!(read_mp4(&data[..], false).is_err());
+    }
+
+    fn ebml_element(id: u64, content: &[u8]) -> Vec<u8> {
+        let id_len = 8 - id.leading_zeros() as usize / 8;
+        let mut data = id.to_be_bytes()[8 - id_len..].to_vec();
+        // An 8 byte size.
+        data.push(1);
+        data.extend_from_slice(&(content.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(content);
+        data
+    }
+
+    #[test]
+    fn matroska() {
+        let header = ebml_element(EBML_HEADER, &ebml_element(EBML_DOC_TYPE, b"matroska"));
+        let info = ebml_element(
+            SEGMENT_INFO,
+            &[
+                ebml_element(TIMESTAMP_SCALE, &[0x0f, 0x42, 0x40]),
+                ebml_element(DURATION, &2500f64.to_be_bytes()),
+            ]
+            .concat(),
+        );
+        let video = ebml_element(
+            VIDEO,
+            &[
+                ebml_element(PIXEL_WIDTH, &[0x07, 0x80]),
+                ebml_element(PIXEL_HEIGHT, &[0x04, 0x38]),
+            ]
+            .concat(),
+        );
+        let video_track = ebml_element(
+            TRACK_ENTRY,
+            &[
+                ebml_element(TRACK_TYPE, &[1]),
+                ebml_element(CODEC_ID, b"V_VP9"),
+                video,
+            ]
+            .concat(),
+        );
+        let audio_track = ebml_element(
+            TRACK_ENTRY,
+            &[
+                ebml_element(TRACK_TYPE, &[2]),
+                ebml_element(CODEC_ID, b"A_OPUS"),
+            ]
+            .concat(),
+        );
+        let tracks = ebml_element(TRACKS, &[video_track, audio_track].concat());
+        let cluster = ebml_element(CLUSTER, &[0; 16]);
+        let segment = ebml_element(SEGMENT, &[info, tracks, cluster].concat());
+        let data = [header, segment].concat();
+
+        let meta_data = read_matroska(&data).unwrap();
+        assert_eq!(meta_data.duration_ms, Some(2500));
+        assert_eq!(
+            (meta_data.width, meta_data.height),
+            (Some(1920), Some(1080))
+        );
+        assert_eq!(meta_data.codecs, vec!["V_VP9", "A_OPUS"]);
+        assert_eq!(
+            meta_data.mime_type.unwrap().essence_str(),
+            "video/x-matroska"
+        );
+    }
+}
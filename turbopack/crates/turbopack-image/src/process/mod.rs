@@ -1,3 +1,4 @@
+pub mod media;
 pub mod svg;
 
 use std::{io::Cursor, str::FromStr};
@@ -22,7 +23,10 @@ use turbo_tasks_fs::{File, FileContent, FileSystemPath};
 use turbopack_core::{
     error::PrettyPrintError,
     ident::AssetIdent,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
 };
 
 use self::svg::calculate;
@@ -494,6 +498,11 @@ struct ImageProcessingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for ImageProcessingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::IMAGE_PROCESSING_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         self.issue_severity
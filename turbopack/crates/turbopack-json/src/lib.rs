@@ -1,6 +1,8 @@
 //! JSON asset support for turbopack.
 //!
-//! JSON assets are parsed to ensure they contain valid JSON.
+//! JSON assets are parsed to ensure they contain valid JSON. JSONC (comments
+//! and trailing commas) and JSON5 inputs are supported as well, see
+//! [JsonFormat].
 //!
 //! When imported from ES modules, they produce a module that exports the
 //! JSON value as an object.
@@ -12,8 +14,9 @@
 use std::fmt::Write;
 
 use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ValueToString, Vc};
+use turbo_tasks::{trace::TraceRawVcs, TaskInput, ValueToString, Vc};
 use turbo_tasks_fs::{FileContent, FileJsonContent};
 use turbopack_core::{
     asset::{Asset, AssetContent},
@@ -28,21 +31,70 @@ use turbopack_ecmascript::chunk::{
     EcmascriptExports,
 };
 
+/// The syntax a JSON module is parsed with.
+#[derive(
+    PartialOrd,
+    Ord,
+    Eq,
+    PartialEq,
+    Hash,
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    TaskInput,
+    TraceRawVcs,
+)]
+pub enum JsonFormat {
+    /// Strict JSON.
+    #[default]
+    Json,
+    /// JSON with comments and trailing commas, e.g. `.jsonc` files.
+    Jsonc,
+    /// JSON5, which additionally allows unquoted property names.
+    Json5,
+}
+
+impl JsonFormat {
+    fn name(self) -> &'static str {
+        match self {
+            JsonFormat::Json => "JSON",
+            JsonFormat::Jsonc => "JSONC",
+            JsonFormat::Json5 => "JSON5",
+        }
+    }
+}
+
 #[turbo_tasks::function]
-fn modifier() -> Vc<RcStr> {
-    Vc::cell("json".into())
+fn modifier(format: JsonFormat) -> Vc<RcStr> {
+    Vc::cell(
+        match format {
+            JsonFormat::Json => "json",
+            JsonFormat::Jsonc => "jsonc",
+            JsonFormat::Json5 => "json5",
+        }
+        .into(),
+    )
 }
 
 #[turbo_tasks::value]
 pub struct JsonModuleAsset {
     source: Vc<Box<dyn Source>>,
+    format: JsonFormat,
 }
 
 #[turbo_tasks::value_impl]
 impl JsonModuleAsset {
     #[turbo_tasks::function]
     pub fn new(source: Vc<Box<dyn Source>>) -> Vc<Self> {
-        Self::cell(JsonModuleAsset { source })
+        Self::new_with_format(source, JsonFormat::Json)
+    }
+
+    #[turbo_tasks::function]
+    pub fn new_with_format(source: Vc<Box<dyn Source>>, format: JsonFormat) -> Vc<Self> {
+        Self::cell(JsonModuleAsset { source, format })
     }
 }
 
@@ -50,7 +102,7 @@ impl JsonModuleAsset {
 impl Module for JsonModuleAsset {
     #[turbo_tasks::function]
     fn ident(&self) -> Vc<AssetIdent> {
-        self.source.ident().with_modifier(modifier())
+        self.source.ident().with_modifier(modifier(self.format))
     }
 }
 
@@ -130,9 +182,16 @@ impl EcmascriptChunkItem for JsonChunkItem {
     #[turbo_tasks::function]
     async fn content(&self) -> Result<Vc<EcmascriptChunkItemContent>> {
         // We parse to JSON and then stringify again to ensure that the
-        // JSON is valid.
+        // JSON is valid. This also strips comments and other syntax of JSONC
+        // and JSON5 that `JSON.parse` doesn't support.
+        let format = self.module.await?.format;
         let content = self.module.content().file_content();
-        let data = content.parse_json().await?;
+        let data = match format {
+            JsonFormat::Json => content.parse_json(),
+            JsonFormat::Jsonc => content.parse_json_with_comments(),
+            JsonFormat::Json5 => content.parse_json5(),
+        }
+        .await?;
         match &*data {
             FileJsonContent::Content(data) => {
                 let js_str_content = serde_json::to_string(&data.to_string())?;
@@ -146,7 +205,8 @@ impl EcmascriptChunkItem for JsonChunkItem {
                 .into())
             }
             FileJsonContent::Unparseable(e) => {
-                let mut message = "Unable to make a module from invalid JSON: ".to_string();
+                let mut message =
+                    format!("Unable to make a module from invalid {}: ", format.name());
                 if let FileContent::Content(content) = &*content.await? {
                     let text = content.content().to_str()?;
                     e.write_with_content(&mut message, text.as_ref())?;
@@ -158,7 +218,8 @@ impl EcmascriptChunkItem for JsonChunkItem {
             }
             FileJsonContent::NotFound => {
                 bail!(
-                    "JSON file not found: {}",
+                    "{} file not found: {}",
+                    format.name(),
                     self.module.ident().to_string().await?
                 );
             }
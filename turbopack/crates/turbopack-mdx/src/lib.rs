@@ -11,8 +11,8 @@ use turbopack_core::{
     asset::{Asset, AssetContent},
     ident::AssetIdent,
     issue::{
-        Issue, IssueDescriptionExt, IssueExt, IssueSource, IssueStage, OptionIssueSource,
-        OptionStyledString, StyledString,
+        Issue, IssueCode, IssueDescriptionExt, IssueExt, IssueSource, IssueStage, OptionIssueCode,
+        OptionIssueSource, OptionStyledString, StyledString,
     },
     source::Source,
     source_pos::SourcePos,
@@ -263,6 +263,16 @@ struct MdxIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for MdxIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::MDX_ERROR))
+    }
+
+    #[turbo_tasks::function]
+    fn transform(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(Some("mdx".into()))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.path
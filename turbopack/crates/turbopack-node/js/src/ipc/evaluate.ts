@@ -1,5 +1,7 @@
 import { IPC } from "./index";
 import type { Ipc as GenericIpc } from "./index";
+import path from "node:path";
+import { forbidNetworkAccess, forbidReadsOutsideOfRoots } from "./hermetic";
 
 type IpcIncomingMessage =
   | {
@@ -36,6 +38,15 @@ export type Ipc<IM, RM> = {
 };
 const ipc = IPC as GenericIpc<IpcIncomingMessage, IpcOutgoingMessage>;
 
+if (process.env.TURBOPACK_HERMETIC === "1") {
+  forbidNetworkAccess();
+  forbidReadsOutsideOfRoots(
+    (process.env.TURBOPACK_HERMETIC_ROOTS ?? "")
+      .split(path.delimiter)
+      .filter(Boolean)
+  );
+}
+
 const queue: string[][] = [];
 
 export const run = async (
@@ -0,0 +1,141 @@
+import dns from "node:dns";
+import fs from "node:fs";
+import net from "node:net";
+import path from "node:path";
+
+function networkError(target: string): Error {
+  return new Error(
+    `Network access to ${target} is not allowed in hermetic builds, as responses aren't declared inputs of the build`
+  );
+}
+
+function describeConnectArgs(args: any[]): string {
+  const [options, host] = args;
+  if (typeof options === "object" && options !== null) {
+    return options.path ?? `${options.host ?? "localhost"}:${options.port}`;
+  }
+  if (typeof options === "string") {
+    return options;
+  }
+  return `${typeof host === "string" ? host : "localhost"}:${options}`;
+}
+
+/**
+ * Makes all network access of evaluated code fail, for hermetic builds
+ * (`TURBOPACK_HERMETIC=1`). It has to be called after the IPC connection is
+ * established, as it's a socket too.
+ */
+export function forbidNetworkAccess() {
+  net.Socket.prototype.connect = function (...args: any[]): never {
+    throw networkError(describeConnectArgs(args));
+  } as any;
+  dns.lookup = function (hostname: string): never {
+    throw networkError(hostname);
+  } as any;
+  dns.promises.lookup = async function (hostname: string): Promise<never> {
+    throw networkError(hostname);
+  } as any;
+  globalThis.fetch = async function (input: any): Promise<never> {
+    throw networkError(String(input instanceof Request ? input.url : input));
+  };
+}
+
+function isInside(file: string, dir: string): boolean {
+  const relative = path.relative(dir, file);
+  return (
+    relative === "" ||
+    (!relative.startsWith(`..${path.sep}`) &&
+      relative !== ".." &&
+      !path.isAbsolute(relative))
+  );
+}
+
+/**
+ * Makes reads of evaluated code outside of `roots` fail as if the path didn't
+ * exist, for hermetic builds (`TURBOPACK_HERMETIC_ROOTS`). Symlinks are
+ * resolved before checking a path, and directories containing a root can be
+ * listed, like the filesystem of the build does.
+ */
+export function forbidReadsOutsideOfRoots(roots: string[]) {
+  const realpath = fs.realpathSync.native;
+  const realRoots = roots.map((root) => {
+    try {
+      return realpath(root);
+    } catch {
+      return path.resolve(root);
+    }
+  });
+
+  function check(file: fs.PathOrFileDescriptor, isDir: boolean) {
+    if (typeof file === "number") {
+      return;
+    }
+    let real: string;
+    try {
+      real = realpath(file instanceof URL ? file : file.toString());
+    } catch {
+      // Paths which don't exist fail anyway.
+      return;
+    }
+    if (
+      realRoots.some(
+        (root) => isInside(real, root) || (isDir && isInside(root, real))
+      )
+    ) {
+      return;
+    }
+    const error: NodeJS.ErrnoException = new Error(
+      `ENOENT: reading ${real} is not allowed, as it is outside of the declared inputs of the hermetic build (${realRoots.join(", ")})`
+    );
+    error.code = "ENOENT";
+    error.path = real;
+    throw error;
+  }
+
+  function guard<F extends (...args: any[]) => any>(fn: F, isDir: boolean): F {
+    return function (this: any, file: any, ...args: any[]) {
+      check(file, isDir);
+      return fn.call(this, file, ...args);
+    } as F;
+  }
+
+  function guardCallback<F extends (...args: any[]) => any>(
+    fn: F,
+    isDir: boolean
+  ): F {
+    return function (this: any, file: any, ...args: any[]) {
+      try {
+        check(file, isDir);
+      } catch (error) {
+        const callback = args[args.length - 1];
+        process.nextTick(() => callback(error));
+        return;
+      }
+      return fn.call(this, file, ...args);
+    } as F;
+  }
+
+  function guardPromise<F extends (...args: any[]) => Promise<any>>(
+    fn: F,
+    isDir: boolean
+  ): F {
+    return async function (this: any, file: any, ...args: any[]) {
+      check(file, isDir);
+      return fn.call(this, file, ...args);
+    } as F;
+  }
+
+  fs.readFileSync = guard(fs.readFileSync, false);
+  fs.readdirSync = guard(fs.readdirSync, true);
+  fs.statSync = guard(fs.statSync, true);
+  fs.openSync = guard(fs.openSync, false);
+  fs.createReadStream = guard(fs.createReadStream, false);
+  fs.readFile = guardCallback(fs.readFile, false);
+  fs.readdir = guardCallback(fs.readdir, true);
+  fs.stat = guardCallback(fs.stat, true);
+  fs.open = guardCallback(fs.open, false);
+  fs.promises.readFile = guardPromise(fs.promises.readFile, false);
+  fs.promises.readdir = guardPromise(fs.promises.readdir, true);
+  fs.promises.stat = guardPromise(fs.promises.stat, true);
+  fs.promises.open = guardPromise(fs.promises.open, false);
+}
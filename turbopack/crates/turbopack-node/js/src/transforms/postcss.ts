@@ -4,7 +4,8 @@ declare const __turbopack_external_require__: (id: string, thunk: () => any, esm
 import postcss from "@vercel/turbopack/postcss";
 // @ts-ignore
 import importedConfig from "CONFIG";
-import { relative, isAbsolute, sep } from "path";
+import { existsSync } from "fs";
+import { relative, isAbsolute, join, sep } from "path";
 import type { Ipc } from "../ipc/evaluate";
 import type { IpcInfoMessage, IpcRequestMessage } from "./webpack-loaders";
 
@@ -21,6 +22,61 @@ function toPath(file: string) {
 }
 
 let processor: any;
+let plugins: any[] = [];
+let lastCandidates: string | undefined;
+
+const TAILWIND_CONFIG_FILES = [
+  "tailwind.config.js",
+  "tailwind.config.cjs",
+  "tailwind.config.mjs",
+  "tailwind.config.ts",
+];
+
+function loadPlugins(candidates?: string[]) {
+  return plugins.map((plugin) => {
+    if (Array.isArray(plugin)) {
+      const [arg, options] = plugin;
+      let pluginFactory = arg;
+
+      if (candidates && pluginFactory === "tailwindcss") {
+        return loadTailwind(options, candidates);
+      }
+
+      if (typeof pluginFactory === "string") {
+        pluginFactory = require(/* turbopackIgnore: true */ pluginFactory);
+      }
+
+      if (pluginFactory.default) {
+        pluginFactory = pluginFactory.default;
+      }
+
+      return pluginFactory(options);
+    }
+    return plugin;
+  });
+}
+
+// Tailwind CSS generates CSS for the class candidates found by Turbopack,
+// instead of scanning the `content` files of its config itself.
+function loadTailwind(options: any, candidates: string[]) {
+  const tailwind = require(/* turbopackIgnore: true */ "tailwindcss");
+  const loadConfig = require(
+    /* turbopackIgnore: true */ "tailwindcss/loadConfig"
+  );
+  let config = options;
+  if (typeof options === "string" || typeof options?.config === "string") {
+    config = loadConfig(join(contextDir, options.config ?? options));
+  } else if (options?.config === undefined && options?.content === undefined) {
+    const file = TAILWIND_CONFIG_FILES.map((file) =>
+      join(contextDir, file)
+    ).find((file) => existsSync(file));
+    config = file ? loadConfig(file) : {};
+  }
+  return tailwind({
+    ...config,
+    content: [{ raw: candidates.join(" "), extension: "html" }],
+  });
+}
 
 export const init = async (ipc: Ipc<IpcInfoMessage, IpcRequestMessage>) => {
   let config = importedConfig;
@@ -32,7 +88,6 @@ export const init = async (ipc: Ipc<IpcInfoMessage, IpcRequestMessage>) => {
       "PostCSS config is undefined (make sure to export an function or object from config file)"
     );
   }
-  let plugins: any[];
   if (Array.isArray(config.plugins)) {
     plugins = config.plugins.map((plugin: [string, any] | string | any) => {
       if (Array.isArray(plugin)) {
@@ -48,32 +103,23 @@ export const init = async (ipc: Ipc<IpcInfoMessage, IpcRequestMessage>) => {
   } else {
     plugins = [];
   }
-  const loadedPlugins = plugins.map((plugin) => {
-    if (Array.isArray(plugin)) {
-      const [arg, options] = plugin;
-      let pluginFactory = arg;
-
-      if (typeof pluginFactory === "string") {
-        pluginFactory = require(/* turbopackIgnore: true */ pluginFactory);
-      }
-
-      if (pluginFactory.default) {
-        pluginFactory = pluginFactory.default;
-      }
-
-      return pluginFactory(options);
-    }
-    return plugin;
-  });
-
-  processor = postcss(loadedPlugins);
+  processor = postcss(loadPlugins());
 };
 
 export default async function transform(
   ipc: Ipc<IpcInfoMessage, IpcRequestMessage>,
   cssContent: string,
-  name: string
+  name: string,
+  candidates: string[] | null
 ) {
+  if (candidates) {
+    // Rebuilding the processor is only necessary when the candidates change.
+    const key = candidates.join(" ");
+    if (key !== lastCandidates) {
+      lastCandidates = key;
+      processor = postcss(loadPlugins(candidates));
+    }
+  }
   const { css, map, messages } = await processor.process(cssContent, {
     from: name,
     to: name,
@@ -26,7 +26,9 @@ use turbopack_core::{
     error::PrettyPrintError,
     file_source::FileSource,
     ident::AssetIdent,
-    issue::{Issue, IssueExt, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+    },
     module::Module,
     output::{OutputAsset, OutputAssets},
     reference_type::{InnerAssets, ReferenceType},
@@ -673,6 +675,11 @@ pub struct EvaluationIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for EvaluationIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::EVALUATION_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn title(&self) -> Vc<StyledString> {
         StyledString::Text("Error evaluating Node.js code".into()).cell()
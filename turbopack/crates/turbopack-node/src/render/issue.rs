@@ -1,6 +1,8 @@
 use turbo_tasks::{ResolvedVc, Vc};
 use turbo_tasks_fs::FileSystemPath;
-use turbopack_core::issue::{Issue, IssueStage, OptionStyledString, StyledString};
+use turbopack_core::issue::{
+    Issue, IssueCode, IssueStage, OptionIssueCode, OptionStyledString, StyledString,
+};
 
 #[turbo_tasks::value(shared)]
 #[derive(Copy, Clone)]
@@ -12,6 +14,11 @@ pub struct RenderingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for RenderingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::RENDERING_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn title(&self) -> Vc<StyledString> {
         StyledString::Text("Error during SSR Rendering".into()).cell()
@@ -0,0 +1,180 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks_fs::{to_sys_path, FileSystemPath};
+use turbopack_core::{
+    introspect::{Introspectable, IntrospectableChildren},
+    output::{OutputAsset, OutputAssets},
+    reference::all_assets_from_entries,
+    source_map::GenerateSourceMap,
+    version::VersionedContentExt,
+};
+use turbopack_dev_server::source::{
+    query::QueryValue,
+    route_tree::{BaseSegment, RouteTree, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, ContentSourceDataFilter,
+    ContentSourceDataVary, GetContentSourceContent,
+};
+
+use super::SourceMapTrace;
+use crate::AssetsForSourceMapping;
+
+/// Maps a frame of a runtime stack trace to its original position, for error
+/// overlays.
+///
+/// Requests look like `<path>?file=<file>&lineNumber=<line>&column=<column>`
+/// with an optional `methodName`, where `file` is the URL or the file path of
+/// a chunk below `root`. The response is the JSON produced by
+/// [SourceMapTrace::content]: the original frame, the code frame and whether
+/// the frame is user code.
+///
+/// Traces are cached per source map, so they are recomputed when the chunk
+/// containing the frame changes.
+#[turbo_tasks::value(shared)]
+pub struct StackFrameContentSource {
+    path: ResolvedVc<RcStr>,
+    /// The directory which is served at `/` and on disk contains the chunks.
+    root: ResolvedVc<FileSystemPath>,
+    /// The entries of the graph of chunks frames can refer to.
+    assets: ResolvedVc<OutputAssets>,
+}
+
+#[turbo_tasks::value_impl]
+impl StackFrameContentSource {
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<RcStr>,
+        root: ResolvedVc<FileSystemPath>,
+        assets: ResolvedVc<OutputAssets>,
+    ) -> Vc<Self> {
+        StackFrameContentSource { path, root, assets }.cell()
+    }
+
+    /// The assets with source maps, keyed by their path relative to `root`.
+    #[turbo_tasks::function]
+    async fn assets_for_source_mapping(&self) -> Result<Vc<AssetsForSourceMapping>> {
+        let root = self.root.await?;
+        let mut assets_for_source_mapping = HashMap::new();
+        for asset in all_assets_from_entries(*self.assets).await?.iter() {
+            let Some(generate_source_map) =
+                ResolvedVc::try_sidecast::<Box<dyn GenerateSourceMap>>(*asset).await?
+            else {
+                continue;
+            };
+            if let Some(path) = root.get_path_to(&*asset.ident().path().await?) {
+                assets_for_source_mapping.insert(path.to_string(), generate_source_map);
+            }
+        }
+        Ok(Vc::cell(assets_for_source_mapping))
+    }
+}
+
+impl StackFrameContentSource {
+    /// Finds the chunk a frame refers to, by URL or by file path.
+    async fn relative_path(&self, file: &str) -> Result<Option<String>> {
+        if let Some((_, url)) = file.split_once("://") {
+            let path = url.find('/').map_or("", |index| &url[index + 1..]);
+            let path = path.split(['?', '#']).next().unwrap_or_default();
+            return Ok(Some(path.to_string()));
+        }
+        let Some(root) = to_sys_path(*self.root).await? else {
+            return Ok(None);
+        };
+        let Ok(path) = Path::new(file).strip_prefix(root) else {
+            return Ok(None);
+        };
+        Ok(Some(
+            path.to_string_lossy()
+                .replace(std::path::MAIN_SEPARATOR, "/"),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for StackFrameContentSource {
+    #[turbo_tasks::function]
+    async fn get_routes(self: Vc<Self>) -> Result<Vc<RouteTree>> {
+        let path = self.await?.path.await?;
+        Ok(RouteTree::new_route(
+            BaseSegment::from_static_pathname(path.as_str()).collect(),
+            RouteType::Exact,
+            Vc::upcast(self),
+        ))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for StackFrameContentSource {
+    #[turbo_tasks::function]
+    fn vary(&self) -> Vc<ContentSourceDataVary> {
+        ContentSourceDataVary {
+            query: Some(ContentSourceDataFilter::All),
+            ..Default::default()
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn get(
+        self: Vc<Self>,
+        _path: RcStr,
+        data: Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let Some(query) = &data.query else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let param = |name: &str| match query.get(name) {
+            Some(QueryValue::String(value)) => Some(value.as_str()),
+            _ => None,
+        };
+        let (Some(file), Some(line), Some(column)) = (
+            param("file"),
+            param("lineNumber").and_then(|line| line.parse::<usize>().ok()),
+            param("column").and_then(|column| column.parse::<usize>().ok()),
+        ) else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let name = param("methodName").map(RcStr::from);
+
+        let this = self.await?;
+        let Some(file) = this.relative_path(file).await? else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let assets = self.assets_for_source_mapping().await?;
+        let Some(generate_source_map) = assets.get(&file) else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let Some(map) = *generate_source_map.generate_source_map().await? else {
+            return Ok(ContentSourceContent::not_found());
+        };
+        let content = SourceMapTrace::new(map, line, column, name).content();
+        Ok(ContentSourceContent::static_content(content.versioned()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Introspectable for StackFrameContentSource {
+    #[turbo_tasks::function]
+    fn ty(&self) -> Vc<RcStr> {
+        Vc::cell("stack frame content source".into())
+    }
+
+    #[turbo_tasks::function]
+    async fn details(&self) -> Result<Vc<RcStr>> {
+        Ok(Vc::cell(
+            format!(
+                "path: {}\nroot: {}",
+                self.path.await?,
+                self.root.to_string().await?
+            )
+            .into(),
+        ))
+    }
+
+    #[turbo_tasks::function]
+    fn children(&self) -> Vc<IntrospectableChildren> {
+        Vc::cell(Default::default())
+    }
+}
@@ -8,7 +8,7 @@ use anyhow::Result;
 use const_format::concatcp;
 use once_cell::sync::Lazy;
 use regex::Regex;
-pub use trace::{SourceMapTrace, StackFrame, TraceResult};
+pub use trace::{is_user_code, SourceMapTrace, StackFrame, TraceResult};
 use tracing::{instrument, Level};
 use turbo_tasks::{ReadRef, Vc};
 use turbo_tasks_fs::{
@@ -22,6 +22,7 @@ use turbopack_ecmascript::magic_identifier::unmangle_identifiers;
 
 use crate::{internal_assets_for_source_mapping, pool::FormattingMode, AssetsForSourceMapping};
 
+pub mod content_source;
 pub mod trace;
 
 const MAX_CODE_FRAMES: usize = 3;
@@ -1,15 +1,17 @@
 use std::{borrow::Cow, fmt::Display};
 
 use anyhow::Result;
+use const_format::concatcp;
 use mime::APPLICATION_JSON;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{ResolvedVc, Vc};
-use turbo_tasks_fs::File;
+use turbo_tasks_fs::{source_context::get_source_context, File, FileLinesContent};
 use turbopack_core::{
-    asset::AssetContent,
+    asset::{Asset, AssetContent},
     source_map::{SourceMap, Token},
+    SOURCE_MAP_PREFIX,
 };
 use turbopack_ecmascript::magic_identifier::unmangle_identifiers;
 
@@ -81,6 +83,14 @@ impl Display for StackFrame<'_> {
         }
     }
 }
+/// Whether an original file, as named by a source map, is code of the project
+/// rather than code of a library or of the framework.
+pub fn is_user_code(file: &str) -> bool {
+    !(file.starts_with(concatcp!(SOURCE_MAP_PREFIX, "[next]"))
+        || file.starts_with(concatcp!(SOURCE_MAP_PREFIX, "[turbopack]"))
+        || file.contains("/node_modules/"))
+}
+
 /// Source Map Trace is a convenient wrapper to perform and consume a source map
 /// trace's token.
 #[turbo_tasks::value(shared)]
@@ -100,6 +110,9 @@ pub enum TraceResult {
     Found(#[turbo_tasks(trace_ignore)] StackFrame<'static>),
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct OptionCodeFrame(Option<RcStr>);
+
 #[turbo_tasks::value_impl]
 impl SourceMapTrace {
     #[turbo_tasks::function]
@@ -152,6 +165,33 @@ impl SourceMapTrace {
         Ok(result.cell())
     }
 
+    /// The lines of the original source around the traced position, with the
+    /// position marked. None when the source map doesn't contain the original
+    /// source.
+    #[turbo_tasks::function]
+    pub async fn code_frame(&self) -> Result<Vc<OptionCodeFrame>> {
+        let token = self
+            .map
+            .lookup_token_and_source(self.line.saturating_sub(1), self.column.saturating_sub(1))
+            .await?;
+        let (Token::Original(t), Some(source)) = (&*token.token.await?, token.source_content)
+        else {
+            return Ok(Vc::cell(None));
+        };
+        let lines = source.content().lines().await?;
+        let FileLinesContent::Lines(lines) = &*lines else {
+            return Ok(Vc::cell(None));
+        };
+        let ctx = get_source_context(
+            lines.iter().map(|l| l.content.as_str()),
+            t.original_line,
+            t.original_column,
+            t.original_line,
+            t.original_column,
+        );
+        Ok(Vc::cell(Some(ctx.to_string().into())))
+    }
+
     /// Takes the trace and generates a (possibly valid) JSON asset content.
     #[turbo_tasks::function]
     pub async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
@@ -162,8 +202,8 @@ impl SourceMapTrace {
             TraceResult::NotFound => "".to_string(),
             TraceResult::Found(frame) => json!({
                 "originalStackFrame": frame,
-                // TODO
-                "originalCodeFrame": null,
+                "originalCodeFrame": &*self.code_frame().await?,
+                "isUserCode": is_user_code(&frame.file),
             })
             .to_string(),
         };
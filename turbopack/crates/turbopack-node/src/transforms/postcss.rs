@@ -1,6 +1,7 @@
 use anyhow::{bail, Context, Result};
 use indoc::formatdoc;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
     fxindexmap, trace::TraceRawVcs, Completion, Completions, ResolvedVc, TaskInput,
@@ -17,7 +18,8 @@ use turbopack_core::{
     file_source::FileSource,
     ident::AssetIdent,
     issue::{
-        Issue, IssueDescriptionExt, IssueSeverity, IssueStage, OptionStyledString, StyledString,
+        Issue, IssueCode, IssueDescriptionExt, IssueSeverity, IssueStage, OptionIssueCode,
+        OptionStyledString, StyledString,
     },
     reference_type::{EntryReferenceSubType, InnerAssets, ReferenceType},
     resolve::{find_context_file_or_package_key, options::ImportMapping, FindContextFileResult},
@@ -26,6 +28,7 @@ use turbopack_core::{
     source_transform::SourceTransform,
     virtual_source::VirtualSource,
 };
+use turbopack_ecmascript::class_candidates::ClassCandidatesSource;
 
 use super::{
     util::{emitted_assets_to_virtual_sources, EmittedAsset},
@@ -60,6 +63,9 @@ pub enum PostCssConfigLocation {
 pub struct PostCssTransformOptions {
     pub postcss_package: Option<ResolvedVc<ImportMapping>>,
     pub config_location: PostCssConfigLocation,
+    /// The class candidates Tailwind CSS generates CSS for. When set, Tailwind
+    /// CSS doesn't scan the `content` files of its config itself.
+    pub class_candidates: Option<ResolvedVc<Box<dyn ClassCandidatesSource>>>,
     pub placeholder_for_future_extensions: u8,
 }
 
@@ -97,6 +103,7 @@ pub struct PostCssTransform {
     evaluate_context: ResolvedVc<Box<dyn AssetContext>>,
     execution_context: ResolvedVc<ExecutionContext>,
     config_location: PostCssConfigLocation,
+    class_candidates: Option<ResolvedVc<Box<dyn ClassCandidatesSource>>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -106,11 +113,13 @@ impl PostCssTransform {
         evaluate_context: ResolvedVc<Box<dyn AssetContext>>,
         execution_context: ResolvedVc<ExecutionContext>,
         config_location: PostCssConfigLocation,
+        class_candidates: Option<ResolvedVc<Box<dyn ClassCandidatesSource>>>,
     ) -> Vc<Self> {
         PostCssTransform {
             evaluate_context,
             execution_context,
             config_location,
+            class_candidates,
         }
         .cell()
     }
@@ -125,6 +134,7 @@ impl SourceTransform for PostCssTransform {
                 evaluate_context: self.evaluate_context,
                 execution_context: self.execution_context,
                 config_location: self.config_location,
+                class_candidates: self.class_candidates,
                 source,
             }
             .cell(),
@@ -137,6 +147,7 @@ struct PostCssTransformedAsset {
     evaluate_context: ResolvedVc<Box<dyn AssetContext>>,
     execution_context: ResolvedVc<ExecutionContext>,
     config_location: PostCssConfigLocation,
+    class_candidates: Option<ResolvedVc<Box<dyn ClassCandidatesSource>>>,
     source: ResolvedVc<Box<dyn Source>>,
 }
 
@@ -503,6 +514,20 @@ impl PostCssTransformedAsset {
             "".into()
         };
 
+        // Reading the candidates makes the transform depend on them, but it's
+        // only invalidated when the set of candidates changes.
+        let class_candidates = match self.class_candidates {
+            Some(class_candidates) => JsonValue::Array(
+                class_candidates
+                    .class_candidates()
+                    .await?
+                    .iter()
+                    .map(|candidate| JsonValue::String(candidate.to_string()))
+                    .collect(),
+            ),
+            None => JsonValue::Null,
+        };
+
         let config_value = evaluate_webpack_loader(WebpackLoaderContext {
             module_asset: postcss_executor,
             cwd: *project_path,
@@ -514,6 +539,7 @@ impl PostCssTransformedAsset {
             args: vec![
                 ResolvedVc::cell(content.into()),
                 ResolvedVc::cell(css_path.into()),
+                ResolvedVc::cell(class_candidates),
             ],
             additional_invalidation: config_changed,
         })
@@ -549,6 +575,16 @@ struct PostCssTransformIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for PostCssTransformIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::POSTCSS_ERROR))
+    }
+
+    #[turbo_tasks::function]
+    fn transform(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(Some("postcss".into()))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.source
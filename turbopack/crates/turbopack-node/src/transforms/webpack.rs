@@ -1,4 +1,4 @@
-use std::mem::take;
+use std::{mem::take, time::Duration};
 
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
@@ -8,7 +8,8 @@ use serde_json::{json, Value as JsonValue};
 use serde_with::serde_as;
 use turbo_rcstr::RcStr;
 use turbo_tasks::{
-    trace::TraceRawVcs, Completion, ResolvedVc, TaskInput, TryJoinIterExt, Value, ValueToString, Vc,
+    feature_flags::FeatureFlag, trace::TraceRawVcs, Completion, ResolvedVc, TaskInput,
+    TryJoinIterExt, Value, ValueToString, Vc,
 };
 use turbo_tasks_bytes::stream::SingleValue;
 use turbo_tasks_env::ProcessEnv;
@@ -22,7 +23,10 @@ use turbopack_core::{
     context::{AssetContext, ProcessResult},
     file_source::FileSource,
     ident::AssetIdent,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        timeout::with_timeout_issue, Issue, IssueCode, IssueExt, IssueSeverity, IssueStage,
+        OptionIssueCode, OptionStyledString, StyledString,
+    },
     module::Module,
     reference_type::{InnerAssets, ReferenceType},
     resolve::{
@@ -75,6 +79,7 @@ struct WebpackLoadersProcessingResult {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, TraceRawVcs, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WebpackLoaderItem {
     pub loader: RcStr,
     #[turbo_tasks(trace_ignore)]
@@ -156,7 +161,7 @@ impl Source for WebpackLoadersProcessedAsset {
 impl Asset for WebpackLoadersProcessedAsset {
     #[turbo_tasks::function]
     async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
-        Ok(*self.process().await?.content)
+        Ok(*self.process_with_timeout().await?.content)
     }
 }
 
@@ -164,7 +169,9 @@ impl Asset for WebpackLoadersProcessedAsset {
 impl GenerateSourceMap for WebpackLoadersProcessedAsset {
     #[turbo_tasks::function]
     async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
-        Ok(Vc::cell(self.process().await?.source_map.map(|v| *v)))
+        Ok(Vc::cell(
+            self.process_with_timeout().await?.source_map.map(|v| *v),
+        ))
     }
 }
 
@@ -189,8 +196,50 @@ async fn webpack_loaders_executor(
     ))
 }
 
+/// The time after which webpack loaders are given up on with a
+/// [TaskTimeoutIssue][turbopack_core::issue::timeout::TaskTimeoutIssue], which lists the tasks
+/// they were waiting for. Set with `TURBOPACK_WEBPACK_LOADER_TIMEOUT=<seconds>`, there's
+/// no timeout by default.
+fn webpack_loader_timeout() -> Option<Duration> {
+    static WEBPACK_LOADER_TIMEOUT: FeatureFlag<usize> = FeatureFlag::new(
+        "webpack-loader-timeout",
+        "TURBOPACK_WEBPACK_LOADER_TIMEOUT",
+        0,
+    );
+    match WEBPACK_LOADER_TIMEOUT.get() {
+        0 => None,
+        seconds => Some(Duration::from_secs(seconds as u64)),
+    }
+}
+
 #[turbo_tasks::value_impl]
 impl WebpackLoadersProcessedAsset {
+    /// [Self::process], but gives up on loaders which take longer than [webpack_loader_timeout].
+    #[turbo_tasks::function]
+    async fn process_with_timeout(self: Vc<Self>) -> Result<Vc<ProcessWebpackLoadersResult>> {
+        let Some(timeout) = webpack_loader_timeout() else {
+            return Ok(self.process());
+        };
+        let file_path = self.await?.source.ident().path().to_resolved().await?;
+        let result = with_timeout_issue(
+            timeout,
+            self.process(),
+            file_path,
+            IssueStage::Transform,
+            "Webpack loaders".into(),
+        )
+        .await?;
+        if result.is_none() {
+            return Ok(ProcessWebpackLoadersResult {
+                content: AssetContent::File(FileContent::NotFound.resolved_cell()).resolved_cell(),
+                assets: Vec::new(),
+                source_map: None,
+            }
+            .cell());
+        }
+        Ok(self.process())
+    }
+
     #[turbo_tasks::function]
     async fn process(self: Vc<Self>) -> Result<Vc<ProcessWebpackLoadersResult>> {
         let this = self.await?;
@@ -700,6 +749,11 @@ pub struct BuildDependencyIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for BuildDependencyIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::BUILD_DEPENDENCY_ERROR))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         IssueSeverity::Warning.into()
@@ -789,6 +843,16 @@ pub struct EvaluateEmittedErrorIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for EvaluateEmittedErrorIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::WEBPACK_LOADER_ERROR))
+    }
+
+    #[turbo_tasks::function]
+    fn transform(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(Some("webpack-loaders".into()))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.file_path
@@ -841,6 +905,16 @@ pub struct EvaluateErrorLoggingIssue {
 
 #[turbo_tasks::value_impl]
 impl Issue for EvaluateErrorLoggingIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::WEBPACK_LOADER_LOG))
+    }
+
+    #[turbo_tasks::function]
+    fn transform(self: Vc<Self>) -> Vc<Option<RcStr>> {
+        Vc::cell(Some("webpack-loaders".into()))
+    }
+
     #[turbo_tasks::function]
     fn file_path(&self) -> Vc<FileSystemPath> {
         *self.file_path
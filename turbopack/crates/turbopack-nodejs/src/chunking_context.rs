@@ -1,10 +1,14 @@
 use std::iter::once;
 
 use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use tracing::Instrument;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, TryJoinIterExt, Value, ValueToString, Vc};
+use turbo_tasks::{
+    trace::TraceRawVcs, ResolvedVc, TaskInput, TryJoinIterExt, Value, ValueToString, Vc,
+};
 use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_hash::HashAlgorithm;
 use turbopack_core::{
     chunk::{
         availability_info::AvailabilityInfo,
@@ -29,6 +33,30 @@ use crate::ecmascript::node::{
     chunk::EcmascriptBuildNodeChunk, entry::chunk::EcmascriptBuildNodeEntryChunk,
 };
 
+/// The module format of the emitted chunks.
+#[derive(
+    Debug, Default, TaskInput, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs,
+)]
+pub enum NodeJsChunkFormat {
+    /// CommonJS modules, which are loaded with `require`.
+    #[default]
+    CommonJs,
+    /// ES modules with an `.mjs` extension. Entry chunks import their chunks
+    /// statically and export the exports of the entry module as named
+    /// exports, so they can be imported by ESM-only runtimes and packages.
+    /// Entry chunk paths should use the `.mjs` extension too.
+    Esm,
+}
+
+impl NodeJsChunkFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            NodeJsChunkFormat::CommonJs => ".js",
+            NodeJsChunkFormat::Esm => ".mjs",
+        }
+    }
+}
+
 /// A builder for [`Vc<NodeJsChunkingContext>`].
 pub struct NodeJsChunkingContextBuilder {
     chunking_context: NodeJsChunkingContext,
@@ -45,6 +73,11 @@ impl NodeJsChunkingContextBuilder {
         self
     }
 
+    pub fn content_hash_algorithm(mut self, content_hash_algorithm: HashAlgorithm) -> Self {
+        self.chunking_context.content_hash_algorithm = content_hash_algorithm;
+        self
+    }
+
     pub fn file_tracing(mut self, enable_tracing: bool) -> Self {
         self.chunking_context.enable_file_tracing = enable_tracing;
         self
@@ -73,9 +106,29 @@ impl NodeJsChunkingContextBuilder {
         self
     }
 
-    /// Builds the chunking context.
-    pub fn build(self) -> Vc<NodeJsChunkingContext> {
-        NodeJsChunkingContext::new(Value::new(self.chunking_context))
+    pub fn chunk_format(mut self, chunk_format: NodeJsChunkFormat) -> Self {
+        self.chunking_context.chunk_format = chunk_format;
+        self
+    }
+
+    /// Builds the chunking context, failing when options were combined that
+    /// can't work together.
+    pub fn build(self) -> Result<Vc<NodeJsChunkingContext>> {
+        self.validate()?;
+        Ok(NodeJsChunkingContext::new(Value::new(
+            self.chunking_context,
+        )))
+    }
+
+    fn validate(&self) -> Result<()> {
+        let context = &self.chunking_context;
+        if context.manifest_chunks && context.chunk_format == NodeJsChunkFormat::Esm {
+            bail!(
+                "Invalid Node.js chunking context: manifest chunks are loaded with `require`, \
+                 which can't load the chunks of the ES module chunk format"
+            );
+        }
+        Ok(())
     }
 }
 
@@ -104,12 +157,16 @@ pub struct NodeJsChunkingContext {
     enable_file_tracing: bool,
     /// Whether to minify resulting chunks
     minify_type: MinifyType,
+    /// The hash function for the content hashes of static assets
+    content_hash_algorithm: HashAlgorithm,
     /// Whether to use manifest chunks for lazy compilation
     manifest_chunks: bool,
     /// The strategy to use for generating module ids
     module_id_strategy: ResolvedVc<Box<dyn ModuleIdStrategy>>,
     /// Whether to use file:// uris for source map sources
     should_use_file_source_map_uris: bool,
+    /// The module format of the emitted chunks
+    chunk_format: NodeJsChunkFormat,
 }
 
 impl NodeJsChunkingContext {
@@ -135,9 +192,11 @@ impl NodeJsChunkingContext {
                 environment,
                 runtime_type,
                 minify_type: MinifyType::NoMinify,
+                content_hash_algorithm: HashAlgorithm::default(),
                 manifest_chunks: false,
                 should_use_file_source_map_uris: false,
                 module_id_strategy: ResolvedVc::upcast(DevModuleIdStrategy::new_resolved()),
+                chunk_format: NodeJsChunkFormat::CommonJs,
             },
         }
     }
@@ -156,6 +215,11 @@ impl NodeJsChunkingContext {
     pub fn minify_type(&self) -> MinifyType {
         self.minify_type
     }
+
+    /// Returns the module format of the emitted chunks.
+    pub fn chunk_format(&self) -> NodeJsChunkFormat {
+        self.chunk_format
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -247,7 +311,13 @@ impl ChunkingContext for NodeJsChunkingContext {
         extension: RcStr,
     ) -> Result<Vc<FileSystemPath>> {
         let root_path = *self.chunk_root_path;
-        let name = ident.output_name(*self.context_path, extension).await?;
+        let name = ident
+            .output_name(
+                *self.context_path,
+                extension,
+                Vc::cell(self.content_hash_algorithm),
+            )
+            .await?;
         Ok(root_path.join(name.clone_value()))
     }
 
@@ -261,6 +331,11 @@ impl ChunkingContext for NodeJsChunkingContext {
         Vc::cell(self.should_use_file_source_map_uris)
     }
 
+    #[turbo_tasks::function]
+    fn content_hash_algorithm(&self) -> Vc<HashAlgorithm> {
+        Vc::cell(self.content_hash_algorithm)
+    }
+
     #[turbo_tasks::function]
     async fn asset_path(
         &self,
@@ -323,7 +398,8 @@ impl ChunkingContext for NodeJsChunkingContext {
 
     /// Generates an output chunk that:
     /// * evaluates the given assets; and
-    /// * exports the result of evaluating the given module as a CommonJS default export.
+    /// * exports the result of evaluating the given module as a CommonJS default export, or as
+    ///   named exports with [NodeJsChunkFormat::Esm].
     #[turbo_tasks::function]
     pub async fn entry_chunk_group(
         self: Vc<Self>,
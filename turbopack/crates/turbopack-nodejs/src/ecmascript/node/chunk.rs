@@ -67,9 +67,12 @@ impl EcmascriptBuildNodeChunk {
 #[turbo_tasks::value_impl]
 impl OutputAsset for EcmascriptBuildNodeChunk {
     #[turbo_tasks::function]
-    fn ident(&self) -> Vc<AssetIdent> {
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
         let ident = self.chunk.ident().with_modifier(modifier());
-        AssetIdent::from_path(self.chunking_context.chunk_path(ident, ".js".into()))
+        let extension = self.chunking_context.await?.chunk_format().extension();
+        Ok(AssetIdent::from_path(
+            self.chunking_context.chunk_path(ident, extension.into()),
+        ))
     }
 
     #[turbo_tasks::function]
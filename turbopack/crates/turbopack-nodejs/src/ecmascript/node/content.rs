@@ -6,20 +6,20 @@ use turbo_tasks::{ReadRef, ResolvedVc, TryJoinIterExt, Vc};
 use turbo_tasks_fs::File;
 use turbopack_core::{
     asset::AssetContent,
-    chunk::{ChunkItemExt, ChunkingContext, MinifyType, ModuleId},
-    code_builder::{Code, CodeBuilder},
+    chunk::{AsyncModuleInfo, ChunkItemExt, ChunkingContext, MinifyType, ModuleId},
+    code_builder::{code_batches, Code, CodeBuilder},
     output::OutputAsset,
     source_map::{GenerateSourceMap, OptionSourceMap},
     version::{Version, VersionedContent},
 };
 use turbopack_ecmascript::{
-    chunk::{EcmascriptChunkContent, EcmascriptChunkItemExt},
+    chunk::{EcmascriptChunkContent, EcmascriptChunkItem, EcmascriptChunkItemExt},
     minify::minify,
     utils::StringifyJs,
 };
 
 use super::{chunk::EcmascriptBuildNodeChunk, version::EcmascriptBuildNodeChunkVersion};
-use crate::NodeJsChunkingContext;
+use crate::{NodeJsChunkFormat, NodeJsChunkingContext};
 
 #[turbo_tasks::value]
 pub(super) struct EcmascriptBuildNodeChunkContent {
@@ -45,12 +45,15 @@ impl EcmascriptBuildNodeChunkContent {
     }
 }
 
+type ChunkItemWithAsyncInfo = (
+    Vc<Box<dyn EcmascriptChunkItem>>,
+    Option<Vc<AsyncModuleInfo>>,
+);
+
 pub(super) async fn chunk_items(
-    content: Vc<EcmascriptChunkContent>,
+    chunk_items: &[ChunkItemWithAsyncInfo],
 ) -> Result<Vec<(ReadRef<ModuleId>, ReadRef<Code>)>> {
-    content
-        .await?
-        .chunk_items
+    chunk_items
         .iter()
         .map(|&(chunk_item, async_module_info)| async move {
             Ok((
@@ -72,18 +75,38 @@ impl EcmascriptBuildNodeChunkContent {
 
         let mut code = CodeBuilder::default();
 
-        writedoc!(
-            code,
-            r#"
-                module.exports = {{
+        match this.chunking_context.await?.chunk_format() {
+            NodeJsChunkFormat::CommonJs => writedoc!(
+                code,
+                r#"
+                    module.exports = {{
 
-            "#,
-        )?;
+                "#,
+            )?,
+            NodeJsChunkFormat::Esm => writedoc!(
+                code,
+                r#"
+                    export default {{
+
+                "#,
+            )?,
+        }
 
-        for (id, item_code) in chunk_items(*this.content).await? {
-            write!(code, "{}: ", StringifyJs(&id))?;
-            code.push_code(&item_code);
-            writeln!(code, ",")?;
+        let items = this
+            .content
+            .await?
+            .chunk_items
+            .iter()
+            .map(|&item| async move { Ok((item.0.id().await?, item)) })
+            .try_join()
+            .await?;
+        let batches = code_batches(items, |(id, _)| (**id).clone())
+            .into_iter()
+            .map(|batch| code_batch(batch.into_iter().map(|(_, item)| item).collect()))
+            .try_join()
+            .await?;
+        for batch in batches {
+            code.push_code(&batch);
         }
 
         write!(code, "\n}};")?;
@@ -119,6 +142,21 @@ impl EcmascriptBuildNodeChunkContent {
     }
 }
 
+/// Concatenates the code of a batch of the chunk items of a chunk, see [code_batches]. The batches
+/// of a chunk are independent tasks, so they are built in parallel and only merged in order by
+/// [EcmascriptBuildNodeChunkContent::code].
+#[turbo_tasks::function]
+async fn code_batch(items: Vec<ChunkItemWithAsyncInfo>) -> Result<Vc<Code>> {
+    let mut code = CodeBuilder::default();
+    for (id, item_code) in chunk_items(&items).await? {
+        write!(code, "{}: ", StringifyJs(&id))?;
+        code.push_code(&item_code);
+        writeln!(code, ",")?;
+    }
+
+    Ok(code.build().cell())
+}
+
 #[turbo_tasks::value_impl]
 impl GenerateSourceMap for EcmascriptBuildNodeChunkContent {
     #[turbo_tasks::function]
@@ -13,10 +13,12 @@ use turbopack_core::{
     output::{OutputAsset, OutputAssets},
     source_map::{GenerateSourceMap, OptionSourceMap, SourceMapAsset},
 };
-use turbopack_ecmascript::{chunk::EcmascriptChunkPlaceable, utils::StringifyJs};
+use turbopack_ecmascript::{
+    chunk::EcmascriptChunkPlaceable, library::library_exports, utils::StringifyJs,
+};
 
 use super::runtime::EcmascriptBuildNodeRuntimeChunk;
-use crate::NodeJsChunkingContext;
+use crate::{NodeJsChunkFormat, NodeJsChunkingContext};
 
 /// An Ecmascript chunk that loads a list of parallel chunks, then instantiates
 /// runtime entries.
@@ -78,22 +80,57 @@ impl EcmascriptBuildNodeEntryChunk {
             );
         };
 
+        let chunk_format = this.chunking_context.await?.chunk_format();
         let mut code = CodeBuilder::default();
 
-        writedoc!(
-            code,
-            r#"
-                const CHUNK_PUBLIC_PATH = {};
-                const runtime = require({});
-            "#,
-            StringifyJs(chunk_public_path),
-            StringifyJs(&*runtime_relative_path)
-        )?;
+        match chunk_format {
+            NodeJsChunkFormat::CommonJs => writedoc!(
+                code,
+                r#"
+                    const CHUNK_PUBLIC_PATH = {};
+                    const runtime = require({});
+                "#,
+                StringifyJs(chunk_public_path),
+                StringifyJs(&*runtime_relative_path)
+            )?,
+            NodeJsChunkFormat::Esm => writedoc!(
+                code,
+                r#"
+                    import runtime from {};
+                    const CHUNK_PUBLIC_PATH = {};
+                "#,
+                StringifyJs(&*runtime_relative_path),
+                StringifyJs(chunk_public_path)
+            )?,
+        }
 
         let other_chunks = this.other_chunks.await?;
-        for other_chunk in &*other_chunks {
+        for (index, other_chunk) in other_chunks.iter().enumerate() {
             let other_chunk_path = &*other_chunk.ident().path().await?;
-            if let Some(other_chunk_public_path) = output_root.get_path_to(other_chunk_path) {
+            let Some(other_chunk_public_path) = output_root.get_path_to(other_chunk_path) else {
+                continue;
+            };
+            if chunk_format == NodeJsChunkFormat::Esm
+                && other_chunk_path.extension_ref() == Some("mjs")
+            {
+                // ES module chunks can't be loaded synchronously, so they are
+                // imported statically and registered before anything is
+                // instantiated.
+                let Some(other_chunk_relative_path) =
+                    chunk_directory.get_relative_path_to(other_chunk_path)
+                else {
+                    continue;
+                };
+                writedoc!(
+                    code,
+                    r#"
+                        import __turbopack_chunk_{index}__ from {};
+                        runtime.registerChunk({}, __turbopack_chunk_{index}__);
+                    "#,
+                    StringifyJs(&*other_chunk_relative_path),
+                    StringifyJs(&other_chunk_public_path)
+                )?;
+            } else {
                 writedoc!(
                     code,
                     // TODO(WEB-1112) This should call `require()` directly, perhaps as an argument
@@ -133,13 +170,41 @@ impl EcmascriptBuildNodeEntryChunk {
             .id()
             .await?;
 
-        writedoc!(
-            code,
-            r#"
+        match chunk_format {
+            NodeJsChunkFormat::CommonJs => writedoc!(
+                code,
+                r#"
                     module.exports = runtime.getOrInstantiateRuntimeModule({}, CHUNK_PUBLIC_PATH).exports;
                 "#,
-            StringifyJs(&*runtime_module_id),
-        )?;
+                StringifyJs(&*runtime_module_id),
+            )?,
+            NodeJsChunkFormat::Esm => {
+                // Async modules export a promise, which is awaited at the top
+                // level.
+                writedoc!(
+                    code,
+                    r#"
+                        const __turbopack_exports__ = await runtime.getOrInstantiateRuntimeModule({}, CHUNK_PUBLIC_PATH).exports;
+                    "#,
+                    StringifyJs(&*runtime_module_id),
+                )?;
+                let exports = library_exports(*this.exported_module).await?;
+                for (index, name) in exports.names.iter().enumerate() {
+                    writeln!(
+                        code,
+                        "const __turbopack_export_{index}__ = \
+                         __turbopack_exports__[{name}];\nexport {{ __turbopack_export_{index}__ \
+                         as {name} }};",
+                        name = StringifyJs(name)
+                    )?;
+                }
+                if exports.has_default {
+                    writeln!(code, "export default __turbopack_exports__.default;")?;
+                } else if exports.dynamic {
+                    writeln!(code, "export default __turbopack_exports__;")?;
+                }
+            }
+        }
 
         Ok(Code::cell(code.build()))
     }
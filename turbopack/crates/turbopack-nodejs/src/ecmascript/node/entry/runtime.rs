@@ -16,7 +16,7 @@ use turbopack_core::{
 use turbopack_ecmascript::utils::StringifyJs;
 use turbopack_ecmascript_runtime::RuntimeType;
 
-use crate::NodeJsChunkingContext;
+use crate::{NodeJsChunkFormat, NodeJsChunkingContext};
 
 /// An Ecmascript chunk that contains the Node.js runtime code.
 #[turbo_tasks::value(shared)]
@@ -49,6 +49,22 @@ impl EcmascriptBuildNodeRuntimeChunk {
         };
 
         let mut code = CodeBuilder::default();
+        let chunk_format = this.chunking_context.await?.chunk_format();
+        if chunk_format == NodeJsChunkFormat::Esm {
+            // The runtime is written as CommonJS module, so it gets the CommonJS
+            // module scope.
+            writedoc!(
+                code,
+                r#"
+                    import {{ createRequire as __turbopack_create_require__ }} from "node:module";
+                    import {{ fileURLToPath as __turbopack_file_url_to_path__ }} from "node:url";
+                    const require = __turbopack_create_require__(import.meta.url);
+                    const __filename = __turbopack_file_url_to_path__(import.meta.url);
+                    const __dirname = require("node:path").dirname(__filename);
+                    const module = {{ exports: {{}} }};
+                "#,
+            )?;
+        }
         let output_root = output_root.to_string();
         let asset_prefix = this.chunking_context.asset_prefix().await?;
         let asset_prefix = asset_prefix.as_deref().unwrap_or("/");
@@ -85,6 +101,10 @@ impl EcmascriptBuildNodeRuntimeChunk {
             }
         }
 
+        if chunk_format == NodeJsChunkFormat::Esm {
+            writeln!(code, "\nexport default module.exports;")?;
+        }
+
         Ok(Code::cell(code.build()))
     }
 }
@@ -100,14 +120,17 @@ impl ValueToString for EcmascriptBuildNodeRuntimeChunk {
 #[turbo_tasks::value_impl]
 impl OutputAsset for EcmascriptBuildNodeRuntimeChunk {
     #[turbo_tasks::function]
-    fn ident(&self) -> Vc<AssetIdent> {
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
         let ident = AssetIdent::from_path(
             turbopack_ecmascript_runtime::embed_fs()
                 .root()
                 .join("runtime.js".into()),
         );
+        let extension = self.chunking_context.await?.chunk_format().extension();
 
-        AssetIdent::from_path(self.chunking_context.chunk_path(ident, ".js".into()))
+        Ok(AssetIdent::from_path(
+            self.chunking_context.chunk_path(ident, extension.into()),
+        ))
     }
 
     #[turbo_tasks::function]
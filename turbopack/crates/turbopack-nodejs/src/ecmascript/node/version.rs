@@ -39,7 +39,7 @@ impl EcmascriptBuildNodeChunkVersion {
                 output_root.to_string()
             );
         };
-        let chunk_items = chunk_items(content).await?;
+        let chunk_items = chunk_items(&content.await?.chunk_items).await?;
         Ok(EcmascriptBuildNodeChunkVersion {
             chunk_path: chunk_path.to_string(),
             chunk_items,
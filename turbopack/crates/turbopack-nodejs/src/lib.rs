@@ -4,8 +4,14 @@
 
 pub(crate) mod chunking_context;
 pub(crate) mod ecmascript;
+pub(crate) mod single_file;
 
-pub use chunking_context::{NodeJsChunkingContext, NodeJsChunkingContextBuilder};
+pub use chunking_context::{
+    NodeJsChunkFormat, NodeJsChunkingContext, NodeJsChunkingContextBuilder,
+};
+pub use single_file::{
+    SingleFileAssets, SingleFileFormat, SingleFileServerBundle, SINGLE_FILE_CHUNKS_GLOBAL,
+};
 
 pub fn register() {
     turbo_tasks::register();
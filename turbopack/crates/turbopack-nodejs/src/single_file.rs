@@ -0,0 +1,335 @@
+use std::io::Write;
+
+use anyhow::{bail, Result};
+use base64::{display::Base64Display, engine::general_purpose::STANDARD};
+use indoc::writedoc;
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, TaskInput, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbo_tasks_hash::{encode_hex, hash_xxh3_hash64};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    code_builder::{Code, CodeBuilder},
+    ident::AssetIdent,
+    output::{OutputAsset, OutputAssets},
+    reference::all_assets_from_entries,
+    virtual_output::VirtualOutputAsset,
+};
+use turbopack_ecmascript::utils::StringifyJs;
+
+/// The global under which a single-file bundle exposes the code of its
+/// chunks. Chunking contexts which produce the chunks of a single-file bundle
+/// should load chunks from it with
+/// [turbopack_core::environment::ChunkLoaderBackend::VirtualFileSystem],
+/// otherwise only chunks which are loaded synchronously are found.
+pub const SINGLE_FILE_CHUNKS_GLOBAL: &str = "__turbopack_single_file_chunks__";
+
+/// The module format of a single-file bundle.
+#[derive(
+    Debug, Default, TaskInput, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs,
+)]
+pub enum SingleFileFormat {
+    #[default]
+    CommonJs,
+    Esm,
+}
+
+impl SingleFileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SingleFileFormat::CommonJs => ".cjs",
+            SingleFileFormat::Esm => ".mjs",
+        }
+    }
+}
+
+/// How the assets which aren't JavaScript chunks, e.g. native addons or
+/// WebAssembly, are shipped with a single-file bundle.
+#[derive(
+    Debug, Default, TaskInput, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs,
+)]
+pub enum SingleFileAssets {
+    /// Assets are inlined into the bundle and extracted into a temporary
+    /// directory when it starts.
+    #[default]
+    Inline,
+    /// Assets are emitted into a directory next to the bundle, which is named
+    /// like the bundle with an `.assets` suffix.
+    Directory,
+}
+
+/// Concatenates an entry chunk and everything it references into a single
+/// self-contained file.
+///
+/// The chunks need to be CommonJS chunks, as produced by a
+/// [crate::NodeJsChunkingContext] with [crate::NodeJsChunkFormat::CommonJs].
+/// They are evaluated from memory, with `__filename` and `__dirname` pointing
+/// into the asset directory, so relative paths between chunks and assets keep
+/// working.
+#[turbo_tasks::value(shared)]
+pub struct SingleFileServerBundle {
+    path: ResolvedVc<FileSystemPath>,
+    output_root: ResolvedVc<FileSystemPath>,
+    entry: ResolvedVc<Box<dyn OutputAsset>>,
+    format: SingleFileFormat,
+    assets: SingleFileAssets,
+}
+
+#[turbo_tasks::value_impl]
+impl SingleFileServerBundle {
+    /// Creates a bundle at `path` for `entry`, whose chunks are emitted
+    /// relative to `output_root`.
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<FileSystemPath>,
+        output_root: ResolvedVc<FileSystemPath>,
+        entry: ResolvedVc<Box<dyn OutputAsset>>,
+        format: SingleFileFormat,
+        assets: SingleFileAssets,
+    ) -> Vc<Self> {
+        SingleFileServerBundle {
+            path,
+            output_root,
+            entry,
+            format,
+            assets,
+        }
+        .cell()
+    }
+
+    /// All assets of the bundle, grouped into chunks and other assets and
+    /// keyed by their path relative to the output root.
+    #[turbo_tasks::function]
+    async fn contents(&self) -> Result<Vc<SingleFileContents>> {
+        let output_root = self.output_root.await?;
+        let mut chunks = FxIndexMap::default();
+        let mut assets = FxIndexMap::default();
+        for asset in all_assets_from_entries(Vc::cell(vec![self.entry]))
+            .await?
+            .iter()
+        {
+            let path = asset.ident().path().await?;
+            let Some(relative_path) = output_root.get_path_to(&path) else {
+                bail!(
+                    "{} is outside of the output root {}",
+                    path.to_string(),
+                    output_root.to_string()
+                );
+            };
+            let relative_path = RcStr::from(relative_path);
+            match path.extension_ref() {
+                // Source maps can't be used without the original files.
+                Some("map") => {}
+                Some("js") => {
+                    chunks.insert(relative_path, *asset);
+                }
+                _ => {
+                    assets.insert(relative_path, *asset);
+                }
+            }
+        }
+        Ok(SingleFileContents { chunks, assets }.cell())
+    }
+
+    #[turbo_tasks::function]
+    async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
+        let this = self.await?;
+        let contents = self.contents().await?;
+        let output_root = this.output_root.await?;
+        let entry_path = this.entry.ident().path().await?;
+        let Some(entry) = output_root.get_path_to(&entry_path) else {
+            bail!(
+                "the entry {} is outside of the output root {}",
+                entry_path.to_string(),
+                output_root.to_string()
+            );
+        };
+
+        let mut code = CodeBuilder::default();
+        match this.format {
+            SingleFileFormat::CommonJs => writeln!(code, "\"use strict\";")?,
+            SingleFileFormat::Esm => writedoc!(
+                code,
+                r#"
+                    import {{ createRequire as __turbopack_create_require__ }} from "node:module";
+                    import {{ fileURLToPath as __turbopack_file_url_to_path__ }} from "node:url";
+                    const require = __turbopack_create_require__(import.meta.url);
+                    const __dirname = require("node:path").dirname(__turbopack_file_url_to_path__(import.meta.url));
+                "#,
+            )?,
+        }
+
+        writeln!(code, "const __turbopack_path__ = require(\"node:path\");")?;
+        writeln!(code, "const __turbopack_chunks__ = {{")?;
+        for (path, chunk) in &contents.chunks {
+            let content = chunk.content().file_content().await?;
+            let FileContent::Content(file) = &*content else {
+                bail!("chunk {path} has no content");
+            };
+            writeln!(
+                code,
+                "  {}: {},",
+                StringifyJs(path),
+                StringifyJs(&*file.content().to_str()?)
+            )?;
+        }
+        writeln!(code, "}};")?;
+        writeln!(
+            code,
+            "globalThis[{}] = __turbopack_chunks__;",
+            StringifyJs(SINGLE_FILE_CHUNKS_GLOBAL)
+        )?;
+
+        match this.assets {
+            SingleFileAssets::Inline => {
+                let mut assets = String::from("{\n");
+                for (path, asset) in &contents.assets {
+                    let content = asset.content().file_content().await?;
+                    let FileContent::Content(file) = &*content else {
+                        continue;
+                    };
+                    assets += &format!(
+                        "  {}: \"{}\",\n",
+                        StringifyJs(path),
+                        Base64Display::new(&file.content().to_bytes()?, &STANDARD)
+                    );
+                }
+                assets += "}";
+                // The directory is named after the content, so bundles can share it
+                // and it doesn't need to be written again on every start.
+                writedoc!(
+                    code,
+                    r#"
+                        const __turbopack_root__ = (() => {{
+                          const fs = require("node:fs");
+                          const root = __turbopack_path__.join(require("node:os").tmpdir(), {});
+                          for (const [file, content] of Object.entries({})) {{
+                            const target = __turbopack_path__.join(root, file);
+                            if (!fs.existsSync(target)) {{
+                              fs.mkdirSync(__turbopack_path__.dirname(target), {{ recursive: true }});
+                              fs.writeFileSync(target, Buffer.from(content, "base64"));
+                            }}
+                          }}
+                          return root;
+                        }})();
+                    "#,
+                    StringifyJs(&format!(
+                        "turbopack-{}",
+                        encode_hex(hash_xxh3_hash64(assets.as_str()))
+                    )),
+                    assets,
+                )?;
+            }
+            SingleFileAssets::Directory => writeln!(
+                code,
+                "const __turbopack_root__ = __turbopack_path__.join(__dirname, {});",
+                StringifyJs(&format!("{}.assets", this.path.await?.file_name()))
+            )?,
+        }
+
+        writedoc!(
+            code,
+            r#"
+                const __turbopack_cache__ = Object.create(null);
+                function __turbopack_load__(file) {{
+                  let module = __turbopack_cache__[file];
+                  if (module) {{
+                    return module.exports;
+                  }}
+                  module = __turbopack_cache__[file] = {{ exports: {{}} }};
+                  const filename = __turbopack_path__.join(__turbopack_root__, file);
+                  const dirname = __turbopack_path__.dirname(filename);
+                  const localRequire = (id) => __turbopack_require__(id, dirname);
+                  localRequire.resolve = require.resolve;
+                  // eslint-disable-next-line no-eval
+                  (0, eval)(
+                    "(function(module, exports, require, __dirname, __filename) {{" +
+                      __turbopack_chunks__[file] +
+                      "\n}})\n//# sourceURL=" +
+                      file
+                  )(module, module.exports, localRequire, dirname, filename);
+                  return module.exports;
+                }}
+                function __turbopack_require__(id, dirname) {{
+                  if (id.startsWith("./") || id.startsWith("../") || __turbopack_path__.isAbsolute(id)) {{
+                    const resolved = __turbopack_path__.resolve(dirname, id);
+                    const file = __turbopack_path__
+                      .relative(__turbopack_root__, resolved)
+                      .split(__turbopack_path__.sep)
+                      .join("/");
+                    if (file in __turbopack_chunks__) {{
+                      return __turbopack_load__(file);
+                    }}
+                    return require(resolved);
+                  }}
+                  return require(id);
+                }}
+            "#,
+        )?;
+
+        match this.format {
+            SingleFileFormat::CommonJs => writeln!(
+                code,
+                "module.exports = __turbopack_load__({});",
+                StringifyJs(&entry)
+            )?,
+            SingleFileFormat::Esm => writeln!(
+                code,
+                "export default __turbopack_load__({});",
+                StringifyJs(&entry)
+            )?,
+        }
+
+        Ok(Code::cell(code.build()))
+    }
+}
+
+#[turbo_tasks::value]
+struct SingleFileContents {
+    chunks: FxIndexMap<RcStr, ResolvedVc<Box<dyn OutputAsset>>>,
+    assets: FxIndexMap<RcStr, ResolvedVc<Box<dyn OutputAsset>>>,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for SingleFileServerBundle {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(*self.path)
+    }
+
+    #[turbo_tasks::function]
+    async fn references(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
+        let this = self.await?;
+        if this.assets == SingleFileAssets::Inline {
+            return Ok(OutputAssets::empty());
+        }
+        let directory = this
+            .path
+            .parent()
+            .join(format!("{}.assets", this.path.await?.file_name()).into());
+        let mut references = Vec::new();
+        for (path, asset) in &self.contents().await?.assets {
+            references.push(ResolvedVc::upcast(
+                VirtualOutputAsset::new(
+                    directory.join(path.clone()).to_resolved().await?,
+                    asset.content().to_resolved().await?,
+                )
+                .to_resolved()
+                .await?,
+            ));
+        }
+        Ok(Vc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for SingleFileServerBundle {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let code = self.code().await?;
+        Ok(AssetContent::file(
+            File::from(code.source_code().clone()).into(),
+        ))
+    }
+}
@@ -0,0 +1,59 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_tasks::{TurboTasks, Value, Vc};
+use turbo_tasks_fs::{FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::environment::{Environment, ExecutionEnvironment, NodeJsEnvironment};
+use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_nodejs::{NodeJsChunkFormat, NodeJsChunkingContext, NodeJsChunkingContextBuilder};
+
+async fn builder(root: Vc<FileSystemPath>) -> Result<NodeJsChunkingContextBuilder> {
+    let root = root.to_resolved().await?;
+    let environment = Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+        NodeJsEnvironment::default().resolved_cell(),
+    )));
+    Ok(NodeJsChunkingContext::builder(
+        root,
+        root,
+        root,
+        root.join("chunks".into()).to_resolved().await?,
+        root.join("assets".into()).to_resolved().await?,
+        environment.to_resolved().await?,
+        RuntimeType::Production,
+    ))
+}
+
+#[tokio::test]
+async fn rejects_manifest_chunks_of_es_modules() {
+    turbopack_nodejs::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+
+        let Err(error) = builder(root)
+            .await?
+            .manifest_chunks(true)
+            .chunk_format(NodeJsChunkFormat::Esm)
+            .build()
+        else {
+            panic!("the chunking context was built");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Invalid Node.js chunking context: manifest chunks are loaded with `require`, which \
+             can't load the chunks of the ES module chunk format"
+        );
+
+        builder(root).await?.manifest_chunks(true).build()?;
+        builder(root)
+            .await?
+            .chunk_format(NodeJsChunkFormat::Esm)
+            .build()?;
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,109 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_tasks::{ResolvedVc, TurboTasks, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    output::{OutputAsset, OutputAssets},
+    virtual_output::VirtualOutputAsset,
+};
+use turbopack_nodejs::{SingleFileAssets, SingleFileFormat, SingleFileServerBundle};
+
+async fn asset(
+    path: Vc<FileSystemPath>,
+    content: &str,
+    references: Vec<ResolvedVc<Box<dyn OutputAsset>>>,
+) -> Result<ResolvedVc<Box<dyn OutputAsset>>> {
+    let content = AssetContent::file(FileContent::Content(File::from(content)).cell());
+    Ok(ResolvedVc::upcast(
+        VirtualOutputAsset::new_with_references(path, content, Vc::cell(references))
+            .to_resolved()
+            .await?,
+    ))
+}
+
+/// An entry chunk which loads another chunk and a native addon, and has a
+/// source map.
+async fn entry(root: Vc<FileSystemPath>) -> Result<ResolvedVc<Box<dyn OutputAsset>>> {
+    let chunk = asset(root.join("chunks/a.js".into()), "exports.a = 1;", vec![]).await?;
+    let addon = asset(root.join("addon.node".into()), "binary", vec![]).await?;
+    let map = asset(root.join("entry.js.map".into()), "{}", vec![]).await?;
+    asset(
+        root.join("entry.js".into()),
+        "module.exports = require(\"./chunks/a.js\");",
+        vec![chunk, addon, map],
+    )
+    .await
+}
+
+async fn code(bundle: Vc<SingleFileServerBundle>) -> Result<String> {
+    let content = bundle.content().file_content().await?;
+    let FileContent::Content(file) = &*content else {
+        panic!("the bundle has no content");
+    };
+    Ok(file.content().to_str()?.into_owned())
+}
+
+#[tokio::test]
+async fn inlines_chunks_and_assets() {
+    turbopack_nodejs::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+        let bundle = SingleFileServerBundle::new(
+            root.join("server.cjs".into()),
+            root,
+            *entry(root).await?,
+            SingleFileFormat::CommonJs,
+            SingleFileAssets::Inline,
+        );
+
+        let code = code(bundle).await?;
+        assert!(code.starts_with("\"use strict\";"));
+        assert!(code.contains(r#""entry.js": "module.exports = require(\"./chunks/a.js\");","#));
+        assert!(code.contains(r#""chunks/a.js": "exports.a = 1;","#));
+        // "binary" encoded in base64.
+        assert!(code.contains(r#""addon.node": "YmluYXJ5","#));
+        assert!(!code.contains("entry.js.map"));
+        assert!(code.contains(r#"module.exports = __turbopack_load__("entry.js");"#));
+        assert!(bundle.references().await?.is_empty());
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn emits_assets_into_a_directory() {
+    turbopack_nodejs::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+        let bundle = SingleFileServerBundle::new(
+            root.join("server.mjs".into()),
+            root,
+            *entry(root).await?,
+            SingleFileFormat::Esm,
+            SingleFileAssets::Directory,
+        );
+
+        let code = code(bundle).await?;
+        assert!(code.contains("import.meta.url"));
+        assert!(code.contains(r#"__turbopack_path__.join(__dirname, "server.mjs.assets")"#));
+        assert!(!code.contains("YmluYXJ5"));
+        assert!(code.contains(r#"export default __turbopack_load__("entry.js");"#));
+
+        let references = bundle.references().await?;
+        assert_eq!(references.len(), 1);
+        assert_eq!(
+            &*references[0].ident().path().to_string().await?,
+            "[virtual file system]/server.mjs.assets/addon.node"
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,523 @@
+//! Plugins running in a separate process, which can be written in any
+//! language. A plugin can resolve requests, transform sources and report
+//! issues, see [protocol] for the messages exchanged with it.
+//!
+//! A crashing or hanging plugin doesn't take down the compiler. The affected
+//! request falls back to the default behavior, an issue is reported, and the
+//! plugin process is started again for the next request. A plugin that can't
+//! be started is retried after a while, so fixing it doesn't need a restart of
+//! the compiler.
+
+#![feature(min_specialization)]
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+mod process;
+pub mod protocol;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Result};
+use serde::de::DeserializeOwned;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{get_invalidator, Invalidator, ResolvedVc, Value, Vc};
+use turbo_tasks_fs::{glob::Glob, to_sys_path, File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    file_source::FileSource,
+    ident::AssetIdent,
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
+    reference_type::ReferenceType,
+    resolve::{
+        parse::Request,
+        plugin::{BeforeResolvePlugin, BeforeResolvePluginCondition},
+        ExternalTraced, ExternalType, ResolveResult, ResolveResultItem, ResolveResultOption,
+    },
+    source::Source,
+    source_map::{GenerateSourceMap, OptionSourceMap, SourceMap},
+    source_transform::SourceTransform,
+};
+
+use self::{
+    process::PluginProcess,
+    protocol::{Diagnostic, Method, ResolveResponse, TransformResponse},
+};
+
+/// How long a plugin process may take to answer a request before it's
+/// considered hanging and killed.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to wait before starting a plugin process again whose handshake
+/// failed.
+const HANDSHAKE_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A plugin process started with `command` and `args` in `root`, which must
+/// be on disk. The process is started on first use and shared by everything
+/// using the same plugin. Requests are sent concurrently, see [protocol].
+#[turbo_tasks::value(cell = "new", serialization = "none", eq = "manual")]
+pub struct RpcPlugin {
+    root: ResolvedVc<FileSystemPath>,
+    command: RcStr,
+    args: Vec<RcStr>,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    process: Arc<tokio::sync::Mutex<Option<Arc<PluginProcess>>>>,
+    /// Invalidates [RpcPlugin::hooks], to announce the hooks of a restarted
+    /// plugin process.
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    hooks_invalidator: Arc<Mutex<Option<Invalidator>>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct RpcPlugins(Vec<ResolvedVc<RpcPlugin>>);
+
+/// The hooks a plugin provides, as announced in its handshake.
+#[turbo_tasks::value(shared)]
+pub struct PluginHooks {
+    pub name: RcStr,
+    pub resolve_filter: Option<RcStr>,
+    pub transform_filter: Option<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl RpcPlugin {
+    #[turbo_tasks::function]
+    pub fn new(root: ResolvedVc<FileSystemPath>, command: RcStr, args: Vec<RcStr>) -> Vc<Self> {
+        RpcPlugin {
+            root,
+            command,
+            args,
+            process: Default::default(),
+            hooks_invalidator: Default::default(),
+        }
+        .cell()
+    }
+
+    /// Starts the plugin process and returns the hooks it provides. A plugin
+    /// that can't be started provides no hooks until it's started again
+    /// successfully.
+    #[turbo_tasks::function]
+    pub async fn hooks(&self) -> Result<Vc<PluginHooks>> {
+        *self.hooks_invalidator.lock().unwrap() = Some(get_invalidator());
+        Ok(match self.process().await {
+            Ok(process) => {
+                let hello = process.hello().clone();
+                PluginHooks {
+                    name: hello.name,
+                    resolve_filter: hello.resolve_filter,
+                    transform_filter: hello.transform_filter,
+                }
+            }
+            Err(err) => {
+                self.report_failure(IssueStage::Config, *self.root, err)
+                    .await?;
+                let hooks_invalidator = self.hooks_invalidator.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(HANDSHAKE_RETRY_DELAY).await;
+                    invalidate_hooks(&hooks_invalidator);
+                });
+                PluginHooks {
+                    name: self.command.clone(),
+                    resolve_filter: None,
+                    transform_filter: None,
+                }
+            }
+        }
+        .cell())
+    }
+
+    /// A resolve plugin that asks the plugin process to resolve requests
+    /// matching its resolve filter.
+    #[turbo_tasks::function]
+    pub fn resolve_plugin(self: ResolvedVc<Self>) -> Vc<RpcResolvePlugin> {
+        RpcResolvePlugin { plugin: self }.cell()
+    }
+
+    /// A source transform that lets the plugin process transform sources
+    /// matching its transform filter.
+    #[turbo_tasks::function]
+    pub fn source_transform(self: ResolvedVc<Self>) -> Vc<RpcSourceTransform> {
+        RpcSourceTransform { plugin: self }.cell()
+    }
+}
+
+impl RpcPlugin {
+    /// Returns the plugin process, starting it when it's not running.
+    async fn process(&self) -> Result<Arc<PluginProcess>> {
+        let mut process = self.process.lock().await;
+        if let Some(running) = &*process {
+            if !running.is_closed() {
+                return Ok(running.clone());
+            }
+        }
+        *process = None;
+        let Some(cwd) = to_sys_path(*self.root).await? else {
+            bail!("the root of plugin {} is not on disk", self.command);
+        };
+        let started = Arc::new(PluginProcess::start(&self.command, &self.args, &cwd).await?);
+        *process = Some(started.clone());
+        Ok(started)
+    }
+
+    /// Sends a request, starting the plugin process when it's not running. A
+    /// process that crashes or doesn't answer in time is killed, and started
+    /// again by the next request. The hooks are announced again then.
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method<'_>,
+    ) -> Result<(Option<T>, Vec<Diagnostic>)> {
+        let process = match self.process().await {
+            Ok(process) => process,
+            Err(err) => {
+                invalidate_hooks(&self.hooks_invalidator);
+                return Err(err);
+            }
+        };
+        let result = tokio::time::timeout(REQUEST_TIMEOUT, process.request(method))
+            .await
+            .map_err(|_| anyhow!("plugin process didn't answer within {REQUEST_TIMEOUT:?}"))
+            .and_then(|result| result);
+        if result.is_err() {
+            let mut current = self.process.lock().await;
+            // Another request might have started a new process already.
+            if current
+                .as_ref()
+                .is_some_and(|current| Arc::ptr_eq(current, &process))
+            {
+                *current = None;
+            }
+            drop(current);
+            invalidate_hooks(&self.hooks_invalidator);
+        }
+        result
+    }
+
+    /// Reports the issues sent by the plugin for `path`.
+    async fn report(
+        &self,
+        stage: IssueStage,
+        path: Vc<FileSystemPath>,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Result<()> {
+        if diagnostics.is_empty() {
+            return Ok(());
+        }
+        let path = path.to_resolved().await?;
+        let stage = stage.resolved_cell();
+        for diagnostic in diagnostics {
+            PluginIssue {
+                plugin: self.command.clone(),
+                path,
+                stage,
+                severity: diagnostic.severity.resolved_cell(),
+                title: diagnostic.title,
+                description: diagnostic.description,
+            }
+            .cell()
+            .emit();
+        }
+        Ok(())
+    }
+
+    /// Reports that the plugin process failed.
+    async fn report_failure(
+        &self,
+        stage: IssueStage,
+        path: Vc<FileSystemPath>,
+        err: anyhow::Error,
+    ) -> Result<()> {
+        self.report(
+            stage,
+            path,
+            vec![Diagnostic {
+                severity: IssueSeverity::Error,
+                title: "Plugin process failed".into(),
+                description: Some(format!("{err:#}").into()),
+            }],
+        )
+        .await
+    }
+}
+
+fn invalidate_hooks(hooks_invalidator: &Mutex<Option<Invalidator>>) {
+    if let Some(invalidator) = hooks_invalidator.lock().unwrap().take() {
+        invalidator.invalidate();
+    }
+}
+
+#[turbo_tasks::value]
+pub struct RpcResolvePlugin {
+    plugin: ResolvedVc<RpcPlugin>,
+}
+
+#[turbo_tasks::value_impl]
+impl BeforeResolvePlugin for RpcResolvePlugin {
+    #[turbo_tasks::function]
+    async fn before_resolve_condition(&self) -> Result<Vc<BeforeResolvePluginCondition>> {
+        Ok(match &self.plugin.hooks().await?.resolve_filter {
+            Some(filter) => {
+                BeforeResolvePluginCondition::from_request_glob(Glob::new(filter.clone()))
+            }
+            // An empty list of modules matches no request.
+            None => BeforeResolvePluginCondition::from_modules(Vc::cell(vec![])),
+        })
+    }
+
+    #[turbo_tasks::function]
+    async fn before_resolve(
+        &self,
+        lookup_path: Vc<FileSystemPath>,
+        _reference_type: Value<ReferenceType>,
+        request: Vc<Request>,
+    ) -> Result<Vc<ResolveResultOption>> {
+        let Some(request) = request.await?.request() else {
+            return Ok(ResolveResultOption::none());
+        };
+        let plugin = self.plugin.await?;
+        let root = plugin.root.await?;
+        let Some(relative_lookup_path) = root.get_path_to(&*lookup_path.await?) else {
+            return Ok(ResolveResultOption::none());
+        };
+        let response = plugin
+            .request::<ResolveResponse>(Method::Resolve {
+                request: &request,
+                lookup_path: relative_lookup_path,
+            })
+            .await;
+        let (response, diagnostics) = match response {
+            Ok(response) => response,
+            Err(err) => {
+                plugin
+                    .report_failure(IssueStage::Resolve, lookup_path, err)
+                    .await?;
+                return Ok(ResolveResultOption::none());
+            }
+        };
+        plugin
+            .report(IssueStage::Resolve, lookup_path, diagnostics)
+            .await?;
+
+        let item = match response {
+            None => return Ok(ResolveResultOption::none()),
+            Some(ResolveResponse::Path { path }) => ResolveResultItem::Source(ResolvedVc::upcast(
+                FileSource::new(plugin.root.join(path))
+                    .to_resolved()
+                    .await?,
+            )),
+            Some(ResolveResponse::External { name }) => ResolveResultItem::External {
+                name,
+                ty: ExternalType::CommonJs,
+                traced: ExternalTraced::Untraced,
+            },
+            Some(ResolveResponse::Ignore) => ResolveResultItem::Ignore,
+            Some(ResolveResponse::Error { message }) => ResolveResultItem::Error(Vc::cell(message)),
+        };
+        Ok(ResolveResultOption::some(
+            ResolveResult::primary(item).cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value]
+pub struct RpcSourceTransform {
+    plugin: ResolvedVc<RpcPlugin>,
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for RpcSourceTransform {
+    #[turbo_tasks::function]
+    async fn transform(&self, source: Vc<Box<dyn Source>>) -> Result<Vc<Box<dyn Source>>> {
+        let Some(filter) = self.plugin.hooks().await?.transform_filter.clone() else {
+            return Ok(source);
+        };
+        let root = self.plugin.await?.root.await?;
+        let path = source.ident().path().await?;
+        let matches = match root.get_path_to(&path) {
+            Some(relative_path) => Glob::new(filter).await?.execute(relative_path),
+            None => false,
+        };
+        if !matches {
+            return Ok(source);
+        }
+        Ok(Vc::upcast(
+            RpcTransformedSource {
+                source: source.to_resolved().await?,
+                plugin: self.plugin,
+            }
+            .cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct PluginTransformResult {
+    content: RcStr,
+    map: Option<RcStr>,
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionPluginTransformResult(Option<ResolvedVc<PluginTransformResult>>);
+
+#[turbo_tasks::value]
+struct RpcTransformedSource {
+    source: ResolvedVc<Box<dyn Source>>,
+    plugin: ResolvedVc<RpcPlugin>,
+}
+
+#[turbo_tasks::value_impl]
+impl RpcTransformedSource {
+    /// The result of the plugin, or `None` when the source isn't a text file
+    /// or the plugin left it unchanged.
+    #[turbo_tasks::function]
+    async fn transformed(&self) -> Result<Vc<OptionPluginTransformResult>> {
+        let content = self.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(Vc::cell(None));
+        };
+        let Ok(text) = file.content().to_str() else {
+            return Ok(Vc::cell(None));
+        };
+        let plugin = self.plugin.await?;
+        let path = self.source.ident().path();
+        let Some(relative_path) = plugin.root.await?.get_path_to(&*path.await?) else {
+            return Ok(Vc::cell(None));
+        };
+        let response = plugin
+            .request::<TransformResponse>(Method::Transform {
+                path: relative_path,
+                content: &text,
+            })
+            .await;
+        let (response, diagnostics) = match response {
+            Ok(response) => response,
+            Err(err) => {
+                plugin
+                    .report_failure(IssueStage::SourceTransform, path, err)
+                    .await?;
+                return Ok(Vc::cell(None));
+            }
+        };
+        plugin
+            .report(IssueStage::SourceTransform, path, diagnostics)
+            .await?;
+        Ok(Vc::cell(response.map(|response| {
+            PluginTransformResult {
+                content: response.content,
+                map: response.map,
+            }
+            .resolved_cell()
+        })))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for RpcTransformedSource {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
+        let name = &self.plugin.hooks().await?.name;
+        Ok(self
+            .source
+            .ident()
+            .with_modifier(Vc::cell(format!("plugin {name}").into())))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for RpcTransformedSource {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let Some(transformed) = *self.transformed().await? else {
+            return Ok(self.await?.source.content());
+        };
+        let content = self.await?.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(self.await?.source.content());
+        };
+        let mut transformed_file = File::from(transformed.await?.content.clone());
+        if let Some(content_type) = file.content_type() {
+            transformed_file = transformed_file.with_content_type(content_type.clone());
+        }
+        Ok(AssetContent::file(transformed_file.into()))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GenerateSourceMap for RpcTransformedSource {
+    #[turbo_tasks::function]
+    async fn generate_source_map(self: Vc<Self>) -> Result<Vc<OptionSourceMap>> {
+        let Some(transformed) = *self.transformed().await? else {
+            return Ok(Vc::cell(None));
+        };
+        let Some(map) = transformed.await?.map.clone() else {
+            return Ok(Vc::cell(None));
+        };
+        let map =
+            SourceMap::new_from_file_content(FileContent::Content(File::from(map)).cell()).await?;
+        Ok(Vc::cell(map.map(|map| map.cell())))
+    }
+}
+
+/// An issue reported by a plugin, or about a plugin process that failed.
+#[turbo_tasks::value(shared)]
+struct PluginIssue {
+    plugin: RcStr,
+    path: ResolvedVc<FileSystemPath>,
+    stage: ResolvedVc<IssueStage>,
+    severity: ResolvedVc<IssueSeverity>,
+    title: RcStr,
+    description: Option<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for PluginIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::PLUGIN_PROCESS_ERROR))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        *self.severity
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        *self.stage
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(
+            self.description
+                .clone()
+                .map(|description| StyledString::Text(description).cell()),
+        )
+    }
+
+    #[turbo_tasks::function]
+    fn transform(&self) -> Vc<Option<RcStr>> {
+        Vc::cell(Some(self.plugin.clone()))
+    }
+}
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
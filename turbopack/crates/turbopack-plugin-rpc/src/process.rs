@@ -0,0 +1,211 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::DeserializeOwned;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::{oneshot, Mutex as AsyncMutex},
+    task::JoinHandle,
+};
+use turbo_rcstr::RcStr;
+use turbopack_core::issue::IssueSeverity;
+
+use crate::protocol::{Diagnostic, Hello, Method, Request, Response, PROTOCOL_VERSION};
+
+/// A running plugin process, which has completed the handshake.
+///
+/// Requests are pipelined: each one is written as soon as it's made, and the responses are
+/// matched to the requests by their id, in whatever order the plugin answers them.
+pub(crate) struct PluginProcess {
+    // Kept to kill the process when it's dropped.
+    _child: Child,
+    stdin: AsyncMutex<ChildStdin>,
+    pending: Arc<Mutex<Pending>>,
+    /// Reads the responses from stdout.
+    reader: JoinHandle<()>,
+    next_id: AtomicU64,
+    hello: Hello,
+}
+
+#[derive(Default)]
+struct Pending {
+    responses: HashMap<u64, oneshot::Sender<Response>>,
+    /// Why the process can't be used anymore, once it can't.
+    closed: Option<String>,
+}
+
+impl Pending {
+    fn close(&mut self, reason: String) {
+        self.closed.get_or_insert(reason);
+        // Dropping the senders fails the waiting requests.
+        self.responses.clear();
+    }
+}
+
+impl PluginProcess {
+    pub async fn start(command: &str, args: &[RcStr], cwd: &Path) -> Result<Self> {
+        let mut child = Command::new(command)
+            .args(args.iter().map(|arg| arg.as_str()))
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to start plugin process {command}"))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        let reader = tokio::spawn(read_responses(stdout, pending.clone()));
+        let mut process = PluginProcess {
+            _child: child,
+            stdin: AsyncMutex::new(stdin),
+            pending,
+            reader,
+            next_id: AtomicU64::new(0),
+            hello: Hello::default(),
+        };
+        let (hello, _) = process
+            .request::<Hello>(Method::Hello {
+                version: PROTOCOL_VERSION,
+            })
+            .await
+            .context("handshake with plugin process failed")?;
+        let Some(hello) = hello else {
+            bail!("plugin process didn't answer the handshake");
+        };
+        if hello.version != PROTOCOL_VERSION {
+            bail!(
+                "plugin {} implements protocol version {}, but version {PROTOCOL_VERSION} is \
+                 required",
+                hello.name,
+                hello.version
+            );
+        }
+        process.hello = hello;
+        Ok(process)
+    }
+
+    /// The answer to the handshake.
+    pub fn hello(&self) -> &Hello {
+        &self.hello
+    }
+
+    /// Whether the process crashed or sent an invalid message, so it can't answer requests
+    /// anymore.
+    pub fn is_closed(&self) -> bool {
+        self.pending.lock().unwrap().closed.is_some()
+    }
+
+    /// Sends a request and waits for the response. An error means that the
+    /// process can't be used anymore. Errors reported by the plugin are
+    /// returned as [Diagnostic]s instead.
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method<'_>,
+    ) -> Result<(Option<T>, Vec<Diagnostic>)> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if let Some(reason) = &pending.closed {
+                bail!("{reason}");
+            }
+            pending.responses.insert(id, sender);
+        }
+        // Forgets the request when it fails or times out.
+        let _guard = RemovePending {
+            pending: &self.pending,
+            id,
+        };
+
+        let mut message = serde_json::to_vec(&Request { id, method })?;
+        message.push(b'\n');
+        {
+            let mut stdin = self.stdin.lock().await;
+            let written = async {
+                stdin.write_all(&message).await?;
+                stdin.flush().await
+            }
+            .await;
+            if let Err(err) = written {
+                let reason = format!("writing to the plugin process failed: {err}");
+                self.pending.lock().unwrap().close(reason.clone());
+                bail!("{reason}");
+            }
+        }
+
+        let Ok(mut response) = receiver.await else {
+            let pending = self.pending.lock().unwrap();
+            return Err(anyhow!(pending
+                .closed
+                .clone()
+                .unwrap_or_else(|| "plugin process exited".to_string())));
+        };
+        if let Some(error) = response.error {
+            response.issues.push(Diagnostic {
+                severity: IssueSeverity::Error,
+                title: error,
+                description: None,
+            });
+        }
+        let result = if response.result.is_null() {
+            None
+        } else {
+            Some(
+                serde_json::from_value(response.result)
+                    .context("plugin process sent an invalid result")?,
+            )
+        };
+        Ok((result, response.issues))
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+struct RemovePending<'a> {
+    pending: &'a Mutex<Pending>,
+    id: u64,
+}
+
+impl Drop for RemovePending<'_> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().responses.remove(&self.id);
+    }
+}
+
+/// Hands the responses on stdout to the waiting requests, until the process exits or sends an
+/// invalid message.
+async fn read_responses(stdout: ChildStdout, pending: Arc<Mutex<Pending>>) {
+    let mut lines = BufReader::new(stdout).lines();
+    let reason = loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break "plugin process exited".to_string(),
+            Err(err) => break format!("reading from the plugin process failed: {err}"),
+        };
+        let response: Response = match serde_json::from_str(&line) {
+            Ok(response) => response,
+            Err(err) => break format!("plugin process sent an invalid message: {err}"),
+        };
+        let mut pending = pending.lock().unwrap();
+        // The request might have timed out already.
+        if let Some(sender) = pending.responses.remove(&response.id) {
+            let _ = sender.send(response);
+        }
+    };
+    pending.lock().unwrap().close(reason);
+}
@@ -0,0 +1,147 @@
+//! The messages exchanged with a plugin process.
+//!
+//! Messages are JSON objects, one per line, on stdin and stdout of the plugin
+//! process. The plugin can log to stderr, which is forwarded to the stderr of
+//! the compiler.
+//!
+//! Every session starts with a `hello` request carrying the
+//! [PROTOCOL_VERSION] of the compiler. The plugin answers with the version it
+//! implements and the hooks it provides. Plugins implementing a different
+//! version are rejected.
+//!
+//! The compiler doesn't wait for a response before sending the next request.
+//! The plugin can answer the requests in any order, the `id` of a response
+//! matches it to its request.
+//!
+//! ```text
+//! > {"id":0,"method":"hello","params":{"version":1}}
+//! < {"id":0,"result":{"version":1,"name":"svg","resolveFilter":"*.svg","transformFilter":"**/*.svg"}}
+//! > {"id":1,"method":"resolve","params":{"request":"./icon.svg","lookupPath":"src"}}
+//! < {"id":1,"result":{"kind":"path","path":"src/icons/icon.svg"}}
+//! > {"id":2,"method":"transform","params":{"path":"src/icons/icon.svg","content":"<svg>..."}}
+//! < {"id":2,"result":{"content":"export default ..."},"issues":[{"severity":"warning","title":"..."}]}
+//! ```
+//!
+//! Paths are relative to the root passed to
+//! [RpcPlugin::new][crate::RpcPlugin::new]. A `null` result leaves the
+//! request to the compiler.
+
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbopack_core::issue::IssueSeverity;
+
+/// The version of the protocol. It's increased on every incompatible change.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct Request<'a> {
+    pub id: u64,
+    #[serde(flatten)]
+    pub method: Method<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum Method<'a> {
+    Hello {
+        version: u32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Resolve {
+        request: &'a str,
+        lookup_path: &'a str,
+    },
+    Transform {
+        path: &'a str,
+        content: &'a str,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Response {
+    pub id: u64,
+    #[serde(default)]
+    pub result: serde_json::Value,
+    /// The plugin failed to handle the request. It's reported as an issue.
+    #[serde(default)]
+    pub error: Option<RcStr>,
+    #[serde(default)]
+    pub issues: Vec<Diagnostic>,
+}
+
+/// The answer to the `hello` request.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hello {
+    pub version: u32,
+    pub name: RcStr,
+    /// A glob for the requests the plugin wants to resolve. Without it the
+    /// plugin is never asked to resolve.
+    #[serde(default)]
+    pub resolve_filter: Option<RcStr>,
+    /// A glob for the paths of the sources the plugin wants to transform.
+    /// Without it the plugin is never asked to transform.
+    #[serde(default)]
+    pub transform_filter: Option<RcStr>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResolveResponse {
+    Path { path: RcStr },
+    External { name: RcStr },
+    Ignore,
+    Error { message: RcStr },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransformResponse {
+    pub content: RcStr,
+    /// A source map from the transformed content to the original content.
+    #[serde(default)]
+    pub map: Option<RcStr>,
+}
+
+/// An issue reported by the plugin.
+#[derive(Debug, Deserialize)]
+pub struct Diagnostic {
+    #[serde(default = "default_severity")]
+    pub severity: IssueSeverity,
+    pub title: RcStr,
+    #[serde(default)]
+    pub description: Option<RcStr>,
+}
+
+fn default_severity() -> IssueSeverity {
+    IssueSeverity::Error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        assert_eq!(
+            serde_json::to_string(&Request {
+                id: 1,
+                method: Method::Resolve {
+                    request: "./icon.svg",
+                    lookup_path: "src",
+                },
+            })
+            .unwrap(),
+            r#"{"id":1,"method":"resolve","params":{"request":"./icon.svg","lookupPath":"src"}}"#
+        );
+
+        let response: Response = serde_json::from_str(
+            r#"{"id":1,"result":{"kind":"path","path":"src/icon.svg"},"issues":[{"title":"deprecated","severity":"warning"}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            serde_json::from_value(response.result).unwrap(),
+            ResolveResponse::Path { path } if &*path == "src/icon.svg"
+        ));
+        assert_eq!(response.issues[0].severity, IssueSeverity::Warning);
+    }
+}
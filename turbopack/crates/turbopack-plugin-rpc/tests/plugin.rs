@@ -0,0 +1,142 @@
+#![cfg(unix)]
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TryJoinIterExt, TurboTasks, Vc};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    asset::Asset,
+    file_source::FileSource,
+    issue::{IssueDescriptionExt, StyledString},
+    source::Source,
+    source_transform::SourceTransform,
+};
+use turbopack_plugin_rpc::RpcPlugin;
+
+/// Answers the handshake, and holds back the response to every other transform request until
+/// the next one was received, to answer them in reverse order.
+const OUT_OF_ORDER_PLUGIN: &str = r#"
+held=""
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed 's/^{"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"method":"hello"'*)
+      printf '{"id":%s,"result":{"version":1,"name":"stub","transformFilter":"*.txt"}}\n' "$id" ;;
+    *'"method":"transform"'*)
+      path=$(printf '%s' "$line" | sed 's/.*"path":"\([^"]*\)".*/\1/')
+      if [ -z "$held" ]; then
+        held="$id $path"
+      else
+        printf '{"id":%s,"result":{"content":"transformed %s"}}\n' "$id" "$path"
+        set -- $held
+        printf '{"id":%s,"result":{"content":"transformed %s"}}\n' "$1" "$2"
+        held=""
+      fi ;;
+  esac
+done
+"#;
+
+/// Crashes when asked to transform `crash.txt`.
+const CRASHING_PLUGIN: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed 's/^{"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"method":"hello"'*)
+      printf '{"id":%s,"result":{"version":1,"name":"stub","transformFilter":"*.txt"}}\n' "$id" ;;
+    *'"path":"crash.txt"'*)
+      exit 1 ;;
+    *'"method":"transform"'*)
+      printf '{"id":%s,"result":{"content":"transformed"}}\n' "$id" ;;
+  esac
+done
+"#;
+
+fn project(plugin: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("plugin.sh"), plugin).unwrap();
+    for file in ["a.txt", "b.txt", "crash.txt"] {
+        std::fs::write(dir.path().join(file), "original").unwrap();
+    }
+    dir
+}
+
+fn transformed_source(
+    plugin: Vc<RpcPlugin>,
+    root: Vc<FileSystemPath>,
+    file: &str,
+) -> Vc<Box<dyn Source>> {
+    let source = Vc::upcast(FileSource::new(root.join(file.into())));
+    plugin.source_transform().transform(source)
+}
+
+async fn content(source: Vc<Box<dyn Source>>) -> Result<String> {
+    let content = source.content().file_content().await?;
+    Ok(content
+        .as_content()
+        .unwrap()
+        .content()
+        .to_str()?
+        .into_owned())
+}
+
+#[tokio::test]
+async fn matches_responses_to_pipelined_requests() {
+    turbopack_plugin_rpc::register();
+    let dir = project(OUT_OF_ORDER_PLUGIN);
+    let root: RcStr = dir.path().to_str().unwrap().into();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = DiskFileSystem::new("project".into(), root, vec![]).root();
+        let plugin = RpcPlugin::new(
+            root.to_resolved().await?,
+            "sh".into(),
+            vec!["plugin.sh".into()],
+        );
+        // The plugin only answers once both requests were sent.
+        let contents = ["a.txt", "b.txt"]
+            .into_iter()
+            .map(|file| async move { content(transformed_source(plugin, root, file)).await })
+            .try_join()
+            .await?;
+        assert_eq!(contents, ["transformed a.txt", "transformed b.txt"]);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn starts_a_crashed_plugin_again() {
+    turbopack_plugin_rpc::register();
+    let dir = project(CRASHING_PLUGIN);
+    let root: RcStr = dir.path().to_str().unwrap().into();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = DiskFileSystem::new("project".into(), root, vec![]).root();
+        let plugin = RpcPlugin::new(
+            root.to_resolved().await?,
+            "sh".into(),
+            vec!["plugin.sh".into()],
+        );
+
+        let crashed = transformed_source(plugin, root, "crash.txt");
+        assert_eq!(content(crashed).await?, "original");
+        let issues = crashed.content().peek_issues_with_path().await?;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            *issues.iter().next().unwrap().title().await?,
+            StyledString::Text("Plugin process failed".into())
+        );
+
+        let transformed = transformed_source(plugin, root, "a.txt");
+        assert_eq!(content(transformed).await?, "transformed");
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -1,11 +1,11 @@
 use anyhow::Result;
 use turbo_tasks::Vc;
-use turbo_tasks_fs::{FileSystem, FileSystemPath};
+use turbo_tasks_fs::{FileSystem, FileSystemEntryType, FileSystemPath};
 use turbopack_core::resolve::{
     find_context_file,
     options::{
-        ConditionValue, ImportMap, ImportMapping, ResolutionConditions, ResolveInPackage,
-        ResolveIntoPackage, ResolveModules, ResolveOptions,
+        typescript_extension_alias, ConditionValue, ImportMap, ImportMapping, ResolutionConditions,
+        ResolveInPackage, ResolveIntoPackage, ResolveModules, ResolveOptions,
     },
     AliasMap, AliasPattern, ExternalTraced, ExternalType, FindContextFileResult,
 };
@@ -136,6 +136,9 @@ async fn base_resolve_options(
     }
 
     let mut import_map = ImportMap::new(direct_mappings);
+    if let Some(virtual_modules) = opt.virtual_modules {
+        import_map.extend_ref(&*virtual_modules.import_map().await?);
+    }
     if let Some(additional_import_map) = opt.import_map {
         let additional_import_map = additional_import_map.await?;
         import_map.extend_ref(&additional_import_map);
@@ -230,6 +233,16 @@ async fn base_resolve_options(
         } else {
             let mut mods = Vec::new();
             if let Some(dir) = opt.enable_node_modules {
+                if opt.enable_pnp {
+                    // Most projects don't use Plug'n'Play, so only look up
+                    // packages in the manifest when there is one.
+                    let manifest = dir.join(".pnp.cjs".into());
+                    if matches!(&*manifest.get_type().await?, FileSystemEntryType::File) {
+                        mods.push(ResolveModules::Pnp {
+                            manifest: manifest.to_resolved().await?,
+                        });
+                    }
+                }
                 mods.push(ResolveModules::Nested(
                     dir.to_resolved().await?,
                     vec!["node_modules".into()],
@@ -272,12 +285,21 @@ async fn base_resolve_options(
         resolved_map: opt.resolved_map,
         plugins,
         before_resolve_plugins: opt.before_resolve_plugins.clone(),
+        extension_alias: opt.extension_alias.clone().unwrap_or_default(),
+        enable_module_files: opt.enable_module_files,
         loose_errors: opt.loose_errors,
+        persistent_cache: opt.persistent_resolve_cache,
         ..Default::default()
     }
     .into())
 }
 
+fn is_in_node_modules(path: &FileSystemPath) -> bool {
+    path.path
+        .split('/')
+        .any(|segment| segment == "node_modules")
+}
+
 #[turbo_tasks::function]
 pub async fn resolve_options(
     resolve_path: Vc<FileSystemPath>,
@@ -299,7 +321,17 @@ pub async fn resolve_options(
         let tsconfig = find_context_file(resolve_path, tsconfig()).await?;
         match *tsconfig {
             FindContextFileResult::Found(path, _) => {
-                apply_tsconfig_resolve_options(resolve_options, tsconfig_resolve_options(*path))
+                let resolve_options = apply_tsconfig_resolve_options(
+                    resolve_options,
+                    tsconfig_resolve_options(*path, resolve_path),
+                );
+                if options_context_value.extension_alias.is_none()
+                    && !is_in_node_modules(&*resolve_path.await?)
+                {
+                    resolve_options.with_extension_alias(typescript_extension_alias())
+                } else {
+                    resolve_options
+                }
             }
             FindContextFileResult::NotFound(_) => resolve_options,
         }
@@ -7,8 +7,10 @@ use turbopack_core::{
     environment::Environment,
     resolve::{
         options::{ImportMap, ResolvedMap},
+        persistent_cache::PersistentResolveCache,
         plugin::{AfterResolvePlugin, BeforeResolvePlugin},
     },
+    virtual_module::VirtualModules,
 };
 
 #[turbo_tasks::value(shared)]
@@ -28,6 +30,15 @@ pub struct ResolveOptionsContext {
     // Enable resolving of .mjs files without the .mjs extension
     pub enable_mjs_extension: bool,
     #[serde(default)]
+    /// Enable resolving packages with the Yarn Plug'n'Play manifest
+    /// (`.pnp.cjs`) in the directory of [Self::enable_node_modules], when
+    /// there is one.
+    pub enable_pnp: bool,
+    #[serde(default)]
+    /// Enable resolving module requests to files directly inside of the
+    /// node_modules folder, e.g. `foo` to `node_modules/foo.js`.
+    pub enable_module_files: bool,
+    #[serde(default)]
     /// Enable resolving of the node_modules folder when within the provided
     /// directory
     pub enable_node_modules: Option<ResolvedVc<FileSystemPath>>,
@@ -49,6 +60,12 @@ pub struct ResolveOptionsContext {
     #[serde(default)]
     pub custom_extensions: Option<Vec<RcStr>>,
     #[serde(default)]
+    /// Overrides the extension alias, see `ResolveOptions::extension_alias`.
+    /// When not set and `enable_typescript` is enabled, the TypeScript output
+    /// extensions are aliased to their source extensions within projects that
+    /// have a tsconfig.json.
+    pub extension_alias: Option<Vec<(RcStr, Vec<RcStr>)>>,
+    #[serde(default)]
     /// An additional import map to use when resolving modules.
     ///
     /// If set, this import map will be applied to `ResolveOption::import_map`.
@@ -56,6 +73,10 @@ pub struct ResolveOptionsContext {
     /// precedence over any other (e.g. tsconfig.json `compilerOptions.paths`).
     pub import_map: Option<ResolvedVc<ImportMap>>,
     #[serde(default)]
+    /// A registry of virtual modules, which are resolvable by their prefixed
+    /// specifier from any importer. It's applied before `import_map`.
+    pub virtual_modules: Option<ResolvedVc<VirtualModules>>,
+    #[serde(default)]
     /// An import map to fall back to when a request could not be resolved.
     ///
     /// If set, this import map will be applied to
@@ -75,6 +96,10 @@ pub struct ResolveOptionsContext {
     pub before_resolve_plugins: Vec<ResolvedVc<Box<dyn BeforeResolvePlugin>>>,
     /// Warn instead of error for resolve errors
     pub loose_errors: bool,
+    #[serde(default)]
+    /// Keeps the results of requests from within `node_modules` across
+    /// restarts. See `ResolveOptions::persistent_cache`.
+    pub persistent_resolve_cache: Option<ResolvedVc<PersistentResolveCache>>,
 
     #[serde(default)]
     pub placeholder_for_future_extensions: (),
@@ -129,6 +154,25 @@ impl ResolveOptionsContext {
         );
         Ok(resolve_options_context.into())
     }
+
+    /// Returns a new [Vc<ResolveOptionsContext>] which keeps the results of
+    /// requests from within `node_modules` in the given cache, also in the
+    /// contexts of its rules.
+    #[turbo_tasks::function]
+    pub async fn with_persistent_resolve_cache(
+        self: Vc<Self>,
+        cache: ResolvedVc<PersistentResolveCache>,
+    ) -> Result<Vc<Self>> {
+        let mut resolve_options_context = self.await?.clone_value();
+        resolve_options_context.persistent_resolve_cache = Some(cache);
+        for (_, context) in resolve_options_context.rules.iter_mut() {
+            *context = context
+                .with_persistent_resolve_cache(*cache)
+                .to_resolved()
+                .await?;
+        }
+        Ok(resolve_options_context.into())
+    }
 }
 
 #[turbo_tasks::value_impl]
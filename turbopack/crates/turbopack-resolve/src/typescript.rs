@@ -3,14 +3,17 @@ use std::{collections::HashMap, fmt::Write, mem::take};
 use anyhow::Result;
 use serde_json::Value as JsonValue;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{fxindexset, ResolvedVc, Value, ValueDefault, Vc};
-use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemPath};
+use turbo_tasks::{fxindexset, ResolvedVc, TryJoinIterExt, Value, ValueDefault, Vc};
+use turbo_tasks_fs::{FileContent, FileJsonContent, FileSystemEntryType, FileSystemPath};
 use turbopack_core::{
     asset::Asset,
     context::AssetContext,
     file_source::FileSource,
     ident::AssetIdent,
-    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
     reference_type::{ReferenceType, TypeScriptReferenceSubType},
     resolve::{
         handle_resolve_error,
@@ -221,7 +224,6 @@ pub async fn read_from_tsconfigs<T>(
 pub struct TsConfigResolveOptions {
     base_url: Option<ResolvedVc<FileSystemPath>>,
     import_map: Option<ResolvedVc<ImportMap>>,
-    is_module_resolution_nodenext: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -232,10 +234,185 @@ impl ValueDefault for TsConfigResolveOptions {
     }
 }
 
-/// Returns the resolve options
+/// A project referenced with `references` in a tsconfig.json.
+#[turbo_tasks::value(shared)]
+pub struct TsConfigProjectReference {
+    pub tsconfig: ResolvedVc<FileSystemPath>,
+    /// The directories containing the files included in the project.
+    pub include_roots: Vec<ResolvedVc<FileSystemPath>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct TsConfigProjectReferences(Vec<ResolvedVc<TsConfigProjectReference>>);
+
+/// Returns the projects referenced by the tsconfig. Like in tsc, `references`
+/// are not inherited with `extends`.
+#[turbo_tasks::function]
+pub async fn tsconfig_project_references(
+    tsconfig: Vc<FileSystemPath>,
+) -> Result<Vc<TsConfigProjectReferences>> {
+    let FileJsonContent::Content(json) = &*tsconfig.read().parse_json_with_comments().await? else {
+        return Ok(Vc::cell(Vec::new()));
+    };
+    let JsonValue::Array(references) = &json["references"] else {
+        return Ok(Vc::cell(Vec::new()));
+    };
+    let tsconfig_dir = tsconfig.parent();
+    let mut projects = Vec::new();
+    for reference in references {
+        let Some(path) = reference["path"].as_str() else {
+            continue;
+        };
+        let Some(path) = *tsconfig_dir.try_join(path.into()).await? else {
+            continue;
+        };
+        // A reference either points to a tsconfig file or to a directory
+        // containing a tsconfig.json.
+        let referenced = match *path.get_type().await? {
+            FileSystemEntryType::File => path,
+            FileSystemEntryType::Directory => {
+                path.join("tsconfig.json".into()).to_resolved().await?
+            }
+            _ => continue,
+        };
+        if referenced == tsconfig.to_resolved().await? {
+            continue;
+        }
+        let configs = read_tsconfigs(
+            referenced.read(),
+            ResolvedVc::upcast(FileSource::new(*referenced).to_resolved().await?),
+            node_cjs_resolve_options(referenced.root()),
+        )
+        .await?;
+        if configs.is_empty() {
+            continue;
+        }
+        let includes = read_from_tsconfigs(&configs, |json, source| {
+            let JsonValue::Array(include) = &json["include"] else {
+                return None;
+            };
+            Some(
+                include
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str())
+                    .map(|pattern| (source, include_root(pattern)))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await?;
+        let include_roots = match includes {
+            Some(includes) => {
+                let mut include_roots = Vec::new();
+                for (source, root) in includes {
+                    if let Some(root) = *source.ident().path().parent().try_join(root).await? {
+                        include_roots.push(root);
+                    }
+                }
+                include_roots
+            }
+            None => vec![referenced.parent().to_resolved().await?],
+        };
+        projects.push(
+            TsConfigProjectReference {
+                tsconfig: referenced,
+                include_roots,
+            }
+            .resolved_cell(),
+        );
+    }
+    Ok(Vc::cell(projects))
+}
+
+/// Returns the directory containing all files matched by an `include` pattern,
+/// i.e. the segments before the first wildcard. A trailing file name is
+/// removed as well.
+fn include_root(pattern: &str) -> RcStr {
+    let mut segments = pattern
+        .split('/')
+        .take_while(|segment| !segment.contains(['*', '?']))
+        .collect::<Vec<_>>();
+    let has_wildcard = segments.len() < pattern.split('/').count();
+    if !has_wildcard && segments.last().is_some_and(|segment| segment.contains('.')) {
+        segments.pop();
+    }
+    segments.join("/").into()
+}
+
+/// Returns the resolve options of the tsconfig for modules in `resolve_path`.
+///
+/// When the tsconfig has project `references`, the `paths` of the referenced
+/// project including `resolve_path` take precedence, followed by the `paths` of
+/// the tsconfig itself and of all other referenced projects. `paths` stay
+/// relative to the tsconfig declaring them, but only the `baseUrl` of the
+/// project including `resolve_path` applies, or the `baseUrl` of the tsconfig
+/// itself when no referenced project includes it.
 #[turbo_tasks::function]
 pub async fn tsconfig_resolve_options(
     tsconfig: Vc<FileSystemPath>,
+    resolve_path: Vc<FileSystemPath>,
+) -> Result<Vc<TsConfigResolveOptions>> {
+    let own_options = tsconfig_own_resolve_options(tsconfig);
+    let references = tsconfig_project_references(tsconfig).await?;
+    if references.is_empty() {
+        return Ok(own_options);
+    }
+
+    let resolve_path = resolve_path.await?;
+    let mut including_project = None;
+    let mut other_projects = Vec::new();
+    for reference in references.iter() {
+        let reference = reference.await?;
+        let options = tsconfig_own_resolve_options(*reference.tsconfig);
+        let mut includes_resolve_path = false;
+        for root in reference.include_roots.iter() {
+            if resolve_path.is_inside_or_equal_ref(&*root.await?) {
+                includes_resolve_path = true;
+                break;
+            }
+        }
+        if includes_resolve_path && including_project.is_none() {
+            including_project = Some(options);
+        } else {
+            other_projects.push(options);
+        }
+    }
+
+    // Modules are part of a single project, so they don't see the `baseUrl` of
+    // other projects.
+    let base_url = including_project.unwrap_or(own_options).await?.base_url;
+
+    // Ordered by descending precedence.
+    let all_options = including_project
+        .into_iter()
+        .chain(std::iter::once(own_options))
+        .chain(other_projects)
+        .map(|options| options.to_resolved())
+        .try_join()
+        .await?;
+    let mut import_map: Option<ImportMap> = None;
+    for options in all_options.iter().rev() {
+        let options = options.await?;
+        if let Some(options_import_map) = options.import_map {
+            let options_import_map = options_import_map.await?;
+            match &mut import_map {
+                Some(import_map) => import_map.extend_ref(&options_import_map),
+                None => import_map = Some(options_import_map.clone_value()),
+            }
+        }
+    }
+
+    Ok(TsConfigResolveOptions {
+        base_url,
+        import_map: import_map.map(|import_map| import_map.resolved_cell()),
+    }
+    .cell())
+}
+
+/// Returns the resolve options of a single tsconfig and the configs it
+/// extends.
+#[turbo_tasks::function]
+async fn tsconfig_own_resolve_options(
+    tsconfig: Vc<FileSystemPath>,
 ) -> Result<Vc<TsConfigResolveOptions>> {
     let configs = read_tsconfigs(
         tsconfig.read(),
@@ -326,18 +503,9 @@ pub async fn tsconfig_resolve_options(
         None
     };
 
-    let is_module_resolution_nodenext = read_from_tsconfigs(&configs, |json, _| {
-        json["compilerOptions"]["moduleResolution"]
-            .as_str()
-            .map(|module_resolution| module_resolution.eq_ignore_ascii_case("nodenext"))
-    })
-    .await?
-    .unwrap_or_default();
-
     Ok(TsConfigResolveOptions {
         base_url,
         import_map,
-        is_module_resolution_nodenext,
     }
     .cell())
 }
@@ -376,8 +544,6 @@ pub async fn apply_tsconfig_resolve_options(
                 .await?,
         );
     }
-    resolve_options.enable_typescript_with_output_extension =
-        tsconfig_resolve_options.is_module_resolution_nodenext;
 
     Ok(resolve_options.cell())
 }
@@ -514,6 +680,11 @@ async fn apply_typescript_types_options(
 
 #[turbo_tasks::value_impl]
 impl Issue for TsConfigIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::INVALID_TSCONFIG))
+    }
+
     #[turbo_tasks::function]
     fn severity(&self) -> Vc<IssueSeverity> {
         *self.severity
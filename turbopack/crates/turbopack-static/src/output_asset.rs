@@ -7,6 +7,7 @@ use turbopack_core::{
     ident::AssetIdent,
     output::OutputAsset,
     source::Source,
+    version::{VersionedAssetContent, VersionedContent},
 };
 #[turbo_tasks::value]
 pub struct StaticAsset {
@@ -35,17 +36,19 @@ impl OutputAsset for StaticAsset {
         let content = self.source.content();
         let content_hash = if let AssetContent::File(file) = &*content.await? {
             if let FileContent::Content(file) = &*file.await? {
-                turbo_tasks_hash::hash_xxh3_hash64(file.content())
+                self.chunking_context
+                    .content_hash_algorithm()
+                    .await?
+                    .hash(file.content())
             } else {
                 anyhow::bail!("StaticAsset::path: not found")
             }
         } else {
             anyhow::bail!("StaticAsset::path: unsupported file content")
         };
-        let content_hash_b16 = turbo_tasks_hash::encode_hex(content_hash);
         let asset_path = self
             .chunking_context
-            .asset_path(content_hash_b16.into(), self.source.ident());
+            .asset_path(content_hash.to_hex().into(), self.source.ident());
         Ok(AssetIdent::from_path(asset_path))
     }
 }
@@ -56,4 +59,12 @@ impl Asset for StaticAsset {
     fn content(&self) -> Vc<AssetContent> {
         self.source.content()
     }
+
+    #[turbo_tasks::function]
+    fn versioned_content(&self) -> Vc<Box<dyn VersionedContent>> {
+        Vc::upcast(VersionedAssetContent::new_with_hash_algorithm(
+            self.source.content(),
+            self.chunking_context.content_hash_algorithm(),
+        ))
+    }
 }
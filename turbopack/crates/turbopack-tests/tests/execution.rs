@@ -365,7 +365,7 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
         env,
         RuntimeType::Development,
     )
-    .build();
+    .build()?;
 
     let jest_entry_source = FileSource::new(jest_entry_path);
     let test_source = FileSource::new(test_path);
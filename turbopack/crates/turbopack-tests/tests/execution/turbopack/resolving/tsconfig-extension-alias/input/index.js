@@ -0,0 +1,12 @@
+import foo from "./src/foo.js";
+import fileTsx from "./src/file-tsx.js";
+import nested from "./src/nested.mjs";
+
+it("should resolve output extensions to TypeScript sources with any module resolution", () => {
+  expect(foo).toBe("foo.ts");
+  expect(fileTsx).toBe("file-tsx");
+});
+
+it("should resolve output extensions in fully specified ESM requests", () => {
+  expect(nested).toBe("foo.ts");
+});
@@ -0,0 +1 @@
+throw new Error("Should have a lower precedence than foo.ts");
@@ -0,0 +1,15 @@
+import app from "./src/app.js";
+import lib from "./lib/modules/lib.js";
+import other from "./other/other.js";
+
+it("should resolve paths of referenced projects relative to their own baseUrl", () => {
+  expect(app).toEqual({ value: "app", lib: "lib", helper: "app helper" });
+});
+
+it("should resolve bare imports against the baseUrl of the project of the module", () => {
+  expect(lib).toBe("lib helper");
+});
+
+it("should not apply the baseUrl of referenced projects to modules outside of them", () => {
+  expect(other).toBe("package helper");
+});
@@ -0,0 +1,3 @@
+import helper from "helper";
+
+export default helper;
@@ -0,0 +1,5 @@
+import value from "@/value";
+import lib from "@lib/value";
+import helper from "helper";
+
+export default { value, lib, helper };
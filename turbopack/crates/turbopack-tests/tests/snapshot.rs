@@ -58,7 +58,7 @@ use turbopack_ecmascript_plugins::transform::{
 };
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_env::ProcessEnvAsset;
-use turbopack_nodejs::NodeJsChunkingContext;
+use turbopack_nodejs::{NodeJsChunkFormat, NodeJsChunkingContext};
 use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
 use turbopack_test_utils::snapshot::{diff, expected, matches_expected, snapshot_issues};
 
@@ -95,6 +95,11 @@ struct SnapshotOptions {
     environment: SnapshotEnvironment,
     #[serde(default)]
     tree_shaking_mode: Option<TreeShakingMode>,
+    /// The module format of the chunks with the `NodeJs` runtime.
+    #[serde(default)]
+    chunk_format: NodeJsChunkFormat,
+    #[serde(default)]
+    react_refresh: bool,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -121,6 +126,8 @@ impl Default for SnapshotOptions {
             runtime_type: default_runtime_type(),
             environment: Default::default(),
             tree_shaking_mode: Default::default(),
+            chunk_format: Default::default(),
+            react_refresh: false,
         }
     }
 }
@@ -288,6 +295,7 @@ async fn run_test(resource: RcStr) -> Result<Vc<FileSystemPath>> {
             ecmascript: EcmascriptOptionsContext {
                 enable_jsx: Some(JsxTransformOptions::resolved_cell(JsxTransformOptions {
                     development: true,
+                    react_refresh: options.react_refresh,
                     ..Default::default()
                 })),
                 ignore_dynamic_requests: true,
@@ -350,7 +358,7 @@ async fn run_test(resource: RcStr) -> Result<Vc<FileSystemPath>> {
                 env,
                 options.runtime_type,
             )
-            .build(),
+            .build()?,
         ),
         Runtime::NodeJs => Vc::upcast(
             NodeJsChunkingContext::builder(
@@ -363,7 +371,8 @@ async fn run_test(resource: RcStr) -> Result<Vc<FileSystemPath>> {
                 options.runtime_type,
             )
             .minify_type(options.minify_type)
-            .build(),
+            .chunk_format(options.chunk_format)
+            .build()?,
         ),
     };
 
@@ -410,7 +419,9 @@ async fn run_test(resource: RcStr) -> Result<Vc<FileSystemPath>> {
                                         .unwrap()
                                         .into(),
                                 )
-                                .with_extension("entry.js".into()),
+                                .with_extension(
+                                    format!("entry{}", options.chunk_format.extension()).into(),
+                                ),
                             entry_module,
                             runtime_entries
                                 .unwrap_or_else(EvaluatableAssets::empty)
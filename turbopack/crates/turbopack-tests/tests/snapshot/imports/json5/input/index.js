@@ -0,0 +1,2 @@
+import data from "./data.json5";
+console.log(data.name, data.hex, data.list, data.text);
@@ -0,0 +1,2 @@
+import config from "./config.jsonc";
+console.log(config.compilerOptions.target, config.include);
@@ -0,0 +1 @@
+export const prop = 1;
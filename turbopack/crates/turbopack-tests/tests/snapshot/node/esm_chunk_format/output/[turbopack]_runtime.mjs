@@ -0,0 +1,12 @@
+import { createRequire as __turbopack_create_require__ } from "node:module";
+import { fileURLToPath as __turbopack_file_url_to_path__ } from "node:url";
+const require = __turbopack_create_require__(import.meta.url);
+const __filename = __turbopack_file_url_to_path__(import.meta.url);
+const __dirname = require("node:path").dirname(__filename);
+const module = { exports: {} };
+const RUNTIME_PUBLIC_PATH = "output/[turbopack]_runtime.mjs";
+const OUTPUT_ROOT = "turbopack/crates/turbopack-tests/tests/snapshot/node/esm_chunk_format";
+const ASSET_PREFIX = "/";
+// Dummy runtime
+
+export default module.exports;
\ No newline at end of file
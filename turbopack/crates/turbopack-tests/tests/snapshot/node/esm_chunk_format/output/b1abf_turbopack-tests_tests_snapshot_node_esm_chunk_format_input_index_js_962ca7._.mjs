@@ -0,0 +1,16 @@
+export default {
+
+"[project]/turbopack/crates/turbopack-tests/tests/snapshot/node/esm_chunk_format/input/index.js [test] (ecmascript)": ((__turbopack_context__) => {
+"use strict";
+
+var { r: __turbopack_require__, f: __turbopack_module_context__, i: __turbopack_import__, s: __turbopack_esm__, v: __turbopack_export_value__, n: __turbopack_export_namespace__, c: __turbopack_cache__, M: __turbopack_modules__, l: __turbopack_load__, j: __turbopack_dynamic__, P: __turbopack_resolve_absolute_path__, U: __turbopack_relative_url__, R: __turbopack_resolve_module_id_path__, b: __turbopack_worker_blob_url__, g: global, __dirname, z: __turbopack_require_stub__ } = __turbopack_context__;
+{
+__turbopack_esm__({
+    "prop": (()=>prop)
+});
+const prop = 1;
+}}),
+
+};
+
+//# sourceMappingURL=b1abf_turbopack-tests_tests_snapshot_node_esm_chunk_format_input_index_js_962ca7._.mjs.map
\ No newline at end of file
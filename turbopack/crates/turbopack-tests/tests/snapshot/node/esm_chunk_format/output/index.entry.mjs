@@ -0,0 +1,8 @@
+import runtime from "./[turbopack]_runtime.mjs";
+const CHUNK_PUBLIC_PATH = "output/index.entry.mjs";
+import __turbopack_chunk_0__ from "./b1abf_turbopack-tests_tests_snapshot_node_esm_chunk_format_input_index_js_962ca7._.mjs";
+runtime.registerChunk("output/b1abf_turbopack-tests_tests_snapshot_node_esm_chunk_format_input_index_js_962ca7._.mjs", __turbopack_chunk_0__);
+runtime.getOrInstantiateRuntimeModule("[project]/turbopack/crates/turbopack-tests/tests/snapshot/node/esm_chunk_format/input/index.js [test] (ecmascript)", CHUNK_PUBLIC_PATH);
+const __turbopack_exports__ = await runtime.getOrInstantiateRuntimeModule("[project]/turbopack/crates/turbopack-tests/tests/snapshot/node/esm_chunk_format/input/index.js [test] (ecmascript)", CHUNK_PUBLIC_PATH).exports;
+const __turbopack_export_0__ = __turbopack_exports__["prop"];
+export { __turbopack_export_0__ as "prop" };
\ No newline at end of file
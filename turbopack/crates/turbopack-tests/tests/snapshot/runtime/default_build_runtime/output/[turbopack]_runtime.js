@@ -336,6 +336,167 @@ relativeURL.prototype = URL.prototype;
  */ function requireStub(_moduleId) {
     throw new Error("dynamic usage of require is not supported");
 }
+const CHUNK_LOADER_BACKEND = null;
+const CHUNK_LOAD_RETRY = {"retries":2,"initialDelayMs":500,"maxDelayMs":4000,"onStaleDeployment":"event"};
+/**
+ * This file contains the chunk loader backends, which allow embedders to
+ * supply the code of chunks themselves, e.g. from a virtual file system
+ * embedded into a single binary or through a custom `fetch` in a sandboxed
+ * iframe.
+ *
+ * The backend is selected at build time and passed in `CHUNK_LOADER_BACKEND`.
+ * When it's `null`, the runtime uses its default loading strategy.
+ *
+ * It also contains the retry logic for chunks which fail to load, configured
+ * at build time in `CHUNK_LOAD_RETRY`.
+ */ /* eslint-disable @typescript-eslint/no-unused-vars */ /// <reference path="./runtime-utils.ts" />
+const CHUNK_LOAD_ERROR_EVENT = "turbopack:chunk-load-error";
+const STALE_DEPLOYMENT_RELOAD_KEY = "__turbopack_stale_deployment_reload";
+/**
+ * The page isn't reloaded again for a stale deployment within this time, to
+ * avoid reload loops when the chunk is missing from the new deployment too.
+ */ const STALE_DEPLOYMENT_RELOAD_INTERVAL_MS = 10_000;
+function getChunkLoaderGlobal(backend) {
+    const value = globalThis[backend.global];
+    if (value == null) {
+        throw new Error(`The chunk loader \`globalThis.${backend.global}\` is not defined`);
+    }
+    return value;
+}
+/**
+ * Reads the code of a chunk synchronously. Returns `undefined` when the
+ * backend can only read chunks asynchronously.
+ */ function readChunkSync(backend, chunkPath) {
+    if (backend.type !== "vfs") {
+        return undefined;
+    }
+    const files = getChunkLoaderGlobal(backend);
+    const code = files instanceof Map ? files.get(chunkPath) : files[chunkPath];
+    if (code == null) {
+        throw new Error(`Chunk ${chunkPath} is not in the virtual file system`);
+    }
+    return code;
+}
+/**
+ * Reads the code of a chunk. `chunkUrl` is the URL the chunk would be loaded
+ * from by default.
+ */ async function readChunk(backend, chunkPath, chunkUrl) {
+    switch(backend.type){
+        case "vfs":
+            return readChunkSync(backend, chunkPath);
+        case "fetch":
+            {
+                const response = await getChunkLoaderGlobal(backend)(chunkUrl);
+                if (!response.ok) {
+                    throw new Error(`Fetching chunk ${chunkPath} failed with status ${response.status}`);
+                }
+                return response.text();
+            }
+        default:
+            invariant(backend, (backend)=>`Unknown chunk loader backend: ${backend?.type}`);
+    }
+}
+/**
+ * Loads a chunk with `load`, which is called with the URL to load it from,
+ * and retries with backoff as configured in `CHUNK_LOAD_RETRY`. Rejects with
+ * an error named `ChunkLoadError` once all attempts failed.
+ */ async function loadChunkWithRetry(chunkPath, chunkUrl, load) {
+    for(let attempt = 1;; attempt++){
+        const url = attempt === 1 ? chunkUrl : getRetryUrl(chunkUrl, attempt - 1);
+        try {
+            return await load(url);
+        } catch (error) {
+            const staleDeployment = await isChunkGone(url);
+            const willRetry = !staleDeployment && attempt <= CHUNK_LOAD_RETRY.retries;
+            const prevented = dispatchChunkLoadError({
+                chunkPath,
+                chunkUrl,
+                attempt,
+                willRetry,
+                staleDeployment,
+                error
+            });
+            if (willRetry) {
+                await new Promise((resolve)=>setTimeout(resolve, getRetryDelay(attempt)));
+                continue;
+            }
+            if (staleDeployment && !prevented && CHUNK_LOAD_RETRY.onStaleDeployment === "reload" && reloadForStaleDeployment()) {
+                // The page is reloading, so there is nothing left to do.
+                return new Promise(()=>{});
+            }
+            throw createChunkLoadError(chunkPath, staleDeployment, error);
+        }
+    }
+}
+function getRetryUrl(chunkUrl, retry) {
+    return `${chunkUrl}${chunkUrl.includes("?") ? "&" : "?"}turbopack-retry=${retry}`;
+}
+function getRetryDelay(attempt) {
+    const delay = Math.min(CHUNK_LOAD_RETRY.initialDelayMs * 2 ** (attempt - 1), CHUNK_LOAD_RETRY.maxDelayMs);
+    // Jitter keeps the clients of a failing server from retrying in lockstep.
+    return delay * (0.8 + Math.random() * 0.4);
+}
+/**
+ * Whether the server responds that the chunk doesn't exist. Network errors
+ * don't count, as they can be transient.
+ */ async function isChunkGone(url) {
+    if (typeof fetch !== "function") {
+        return false;
+    }
+    try {
+        const response = await fetch(url, {
+            method: "HEAD",
+            cache: "no-store"
+        });
+        return response.status === 404 || response.status === 410;
+    } catch  {
+        return false;
+    }
+}
+/**
+ * Dispatches the `turbopack:chunk-load-error` event. Returns whether a
+ * listener called `preventDefault()`.
+ */ function dispatchChunkLoadError(detail) {
+    if (typeof CustomEvent !== "function" || typeof globalThis.dispatchEvent !== "function") {
+        return false;
+    }
+    const event = new CustomEvent(CHUNK_LOAD_ERROR_EVENT, {
+        detail,
+        cancelable: true
+    });
+    return !globalThis.dispatchEvent(event);
+}
+/**
+ * Reloads the page, unless it was already reloaded for a stale deployment
+ * recently. Returns whether the page is reloading.
+ */ function reloadForStaleDeployment() {
+    // Only windows can be reloaded, workers don't have `location.reload`.
+    const { location, sessionStorage } = globalThis;
+    if (typeof location?.reload !== "function" || sessionStorage == null) {
+        return false;
+    }
+    try {
+        const lastReload = Number(sessionStorage.getItem(STALE_DEPLOYMENT_RELOAD_KEY));
+        if (Date.now() - lastReload < STALE_DEPLOYMENT_RELOAD_INTERVAL_MS) {
+            return false;
+        }
+        sessionStorage.setItem(STALE_DEPLOYMENT_RELOAD_KEY, String(Date.now()));
+    } catch  {
+        // Storage can be disabled, in which case reload loops can't be detected.
+        return false;
+    }
+    location.reload();
+    return true;
+}
+function createChunkLoadError(chunkPath, staleDeployment, cause) {
+    const error = new Error(staleDeployment ? `Chunk ${chunkPath} no longer exists on the server, the app was probably deployed again` : `Chunk ${chunkPath} failed to load after ${CHUNK_LOAD_RETRY.retries + 1} attempts`, cause ? {
+        cause
+    } : undefined);
+    error.name = "ChunkLoadError";
+    error.chunkPath = chunkPath;
+    error.staleDeployment = staleDeployment;
+    return error;
+}
 /* eslint-disable @typescript-eslint/no-unused-vars */ /// <reference path="../shared/runtime-utils.ts" />
 /// A 'base' utilities to support runtime can have externals.
 /// Currently this is for node.js / edge runtime both.
@@ -417,6 +578,7 @@ async function instantiateWebAssemblyFromPath(path, importsObj) {
     return instance.exports;
 }
 /* eslint-disable @typescript-eslint/no-unused-vars */ /// <reference path="../shared/runtime-utils.ts" />
+/// <reference path="../shared/chunk-loader.ts" />
 /// <reference path="../shared-node/base-externals-utils.ts" />
 /// <reference path="../shared-node/node-externals-utils.ts" />
 /// <reference path="../shared-node/node-wasm-utils.ts" />
@@ -444,6 +606,9 @@ const url = require("url");
 const fs = require("fs/promises");
 const moduleFactories = Object.create(null);
 const moduleCache = Object.create(null);
+/**
+ * ES module chunks which have been registered with `registerChunk`.
+ */ const registeredChunks = new Set();
 /**
  * Returns an absolute path to the given module's id.
  */ function createResolvePathFromModule(resolver) {
@@ -465,7 +630,29 @@ function loadChunk(chunkData, source) {
         return loadChunkPath(chunkData.path, source);
     }
 }
+/**
+ * Registers the module factories of an ES module chunk. ES module chunks can't
+ * be loaded synchronously, so entry chunks import them statically and register
+ * them before any module is instantiated.
+ */ function registerChunk(chunkPath, chunkModules) {
+    for (const [moduleId, moduleFactory] of Object.entries(chunkModules)){
+        if (!moduleFactories[moduleId]) {
+            moduleFactories[moduleId] = moduleFactory;
+        }
+    }
+    registeredChunks.add(chunkPath);
+}
 function loadChunkPath(chunkPath, source) {
+    if (chunkPath.endsWith(".mjs")) {
+        if (registeredChunks.has(chunkPath)) {
+            return;
+        }
+        let errorMessage = `Failed to load chunk ${chunkPath}`;
+        if (source) {
+            errorMessage += ` from ${stringifySourceInfo(source)}`;
+        }
+        throw new Error(`${errorMessage}: ES module chunks need to be imported by the entry chunk or loaded asynchronously`);
+    }
     if (!chunkPath.endsWith(".js")) {
         // We only support loading JS chunks in Node.js.
         // This branch can be hit when trying to load a CSS chunk.
@@ -473,7 +660,16 @@ function loadChunkPath(chunkPath, source) {
     }
     try {
         const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
-        const chunkModules = require(resolved);
+        let chunkModules;
+        if (CHUNK_LOADER_BACKEND != null) {
+            const contents = readChunkSync(CHUNK_LOADER_BACKEND, chunkPath);
+            if (contents == null) {
+                throw new Error(`The ${CHUNK_LOADER_BACKEND.type} chunk loader can only load chunks asynchronously`);
+            }
+            chunkModules = evaluateChunk(contents, resolved);
+        } else {
+            chunkModules = require(resolved);
+        }
         for (const [moduleId, moduleFactory] of Object.entries(chunkModules)){
             if (!moduleFactories[moduleId]) {
                 moduleFactories[moduleId] = moduleFactory;
@@ -491,6 +687,25 @@ function loadChunkPath(chunkPath, source) {
 }
 async function loadChunkAsync(source, chunkData) {
     const chunkPath = typeof chunkData === "string" ? chunkData : chunkData.path;
+    if (chunkPath.endsWith(".mjs")) {
+        if (registeredChunks.has(chunkPath)) {
+            return;
+        }
+        const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
+        try {
+            const chunk = await import(url.pathToFileURL(resolved).href);
+            registerChunk(chunkPath, chunk.default);
+        } catch (e) {
+            let errorMessage = `Failed to load chunk ${chunkPath}`;
+            if (source) {
+                errorMessage += ` from ${stringifySourceInfo(source)}`;
+            }
+            throw new Error(errorMessage, {
+                cause: e
+            });
+        }
+        return;
+    }
     if (!chunkPath.endsWith(".js")) {
         // We only support loading JS chunks in Node.js.
         // This branch can be hit when trying to load a CSS chunk.
@@ -498,22 +713,8 @@ async function loadChunkAsync(source, chunkData) {
     }
     const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
     try {
-        const contents = await fs.readFile(resolved, "utf-8");
-        const localRequire = (id)=>{
-            let resolvedId = require.resolve(id, {
-                paths: [
-                    path.dirname(resolved)
-                ]
-            });
-            return require(resolvedId);
-        };
-        const module1 = {
-            exports: {}
-        };
-        // TODO: Use vm.runInThisContext once our minimal supported Node.js version includes https://github.com/nodejs/node/pull/52153
-        // eslint-disable-next-line no-eval -- Can't use vm.runInThisContext due to https://github.com/nodejs/node/issues/52102
-        (0, eval)("(function(module, exports, require, __dirname, __filename) {" + contents + "\n})" + "\n//# sourceURL=" + url.pathToFileURL(resolved))(module1, module1.exports, localRequire, path.dirname(resolved), resolved);
-        const chunkModules = module1.exports;
+        const contents = CHUNK_LOADER_BACKEND != null ? await readChunk(CHUNK_LOADER_BACKEND, chunkPath, url.pathToFileURL(resolved).href) : await fs.readFile(resolved, "utf-8");
+        const chunkModules = evaluateChunk(contents, resolved);
         for (const [moduleId, moduleFactory] of Object.entries(chunkModules)){
             if (!moduleFactories[moduleId]) {
                 moduleFactories[moduleId] = moduleFactory;
@@ -529,6 +730,26 @@ async function loadChunkAsync(source, chunkData) {
         });
     }
 }
+/**
+ * Evaluates the code of a CommonJS chunk as if it was located at `resolved`
+ * and returns its module factories.
+ */ function evaluateChunk(contents, resolved) {
+    const localRequire = (id)=>{
+        let resolvedId = require.resolve(id, {
+            paths: [
+                path.dirname(resolved)
+            ]
+        });
+        return require(resolvedId);
+    };
+    const module1 = {
+        exports: {}
+    };
+    // TODO: Use vm.runInThisContext once our minimal supported Node.js version includes https://github.com/nodejs/node/pull/52153
+    // eslint-disable-next-line no-eval -- Can't use vm.runInThisContext due to https://github.com/nodejs/node/issues/52102
+    (0, eval)("(function(module, exports, require, __dirname, __filename) {" + contents + "\n})" + "\n//# sourceURL=" + url.pathToFileURL(resolved))(module1, module1.exports, localRequire, path.dirname(resolved), resolved);
+    return module1.exports;
+}
 function loadWebAssembly(chunkPath, imports) {
     const resolved = path.resolve(RUNTIME_ROOT, chunkPath);
     return instantiateWebAssemblyFromPath(resolved, imports);
@@ -670,5 +891,6 @@ function getOrInstantiateRuntimeModule(moduleId, chunkPath) {
 }
 module.exports = {
     getOrInstantiateRuntimeModule,
-    loadChunk
+    loadChunk,
+    registerChunk
 };
\ No newline at end of file
@@ -346,6 +346,167 @@ relativeURL.prototype = URL.prototype;
  */ function requireStub(_moduleId) {
     throw new Error("dynamic usage of require is not supported");
 }
+const CHUNK_LOADER_BACKEND = null;
+const CHUNK_LOAD_RETRY = {"retries":2,"initialDelayMs":500,"maxDelayMs":4000,"onStaleDeployment":"event"};
+/**
+ * This file contains the chunk loader backends, which allow embedders to
+ * supply the code of chunks themselves, e.g. from a virtual file system
+ * embedded into a single binary or through a custom `fetch` in a sandboxed
+ * iframe.
+ *
+ * The backend is selected at build time and passed in `CHUNK_LOADER_BACKEND`.
+ * When it's `null`, the runtime uses its default loading strategy.
+ *
+ * It also contains the retry logic for chunks which fail to load, configured
+ * at build time in `CHUNK_LOAD_RETRY`.
+ */ /* eslint-disable @typescript-eslint/no-unused-vars */ /// <reference path="./runtime-utils.ts" />
+const CHUNK_LOAD_ERROR_EVENT = "turbopack:chunk-load-error";
+const STALE_DEPLOYMENT_RELOAD_KEY = "__turbopack_stale_deployment_reload";
+/**
+ * The page isn't reloaded again for a stale deployment within this time, to
+ * avoid reload loops when the chunk is missing from the new deployment too.
+ */ const STALE_DEPLOYMENT_RELOAD_INTERVAL_MS = 10_000;
+function getChunkLoaderGlobal(backend) {
+    const value = globalThis[backend.global];
+    if (value == null) {
+        throw new Error(`The chunk loader \`globalThis.${backend.global}\` is not defined`);
+    }
+    return value;
+}
+/**
+ * Reads the code of a chunk synchronously. Returns `undefined` when the
+ * backend can only read chunks asynchronously.
+ */ function readChunkSync(backend, chunkPath) {
+    if (backend.type !== "vfs") {
+        return undefined;
+    }
+    const files = getChunkLoaderGlobal(backend);
+    const code = files instanceof Map ? files.get(chunkPath) : files[chunkPath];
+    if (code == null) {
+        throw new Error(`Chunk ${chunkPath} is not in the virtual file system`);
+    }
+    return code;
+}
+/**
+ * Reads the code of a chunk. `chunkUrl` is the URL the chunk would be loaded
+ * from by default.
+ */ async function readChunk(backend, chunkPath, chunkUrl) {
+    switch(backend.type){
+        case "vfs":
+            return readChunkSync(backend, chunkPath);
+        case "fetch":
+            {
+                const response = await getChunkLoaderGlobal(backend)(chunkUrl);
+                if (!response.ok) {
+                    throw new Error(`Fetching chunk ${chunkPath} failed with status ${response.status}`);
+                }
+                return response.text();
+            }
+        default:
+            invariant(backend, (backend)=>`Unknown chunk loader backend: ${backend?.type}`);
+    }
+}
+/**
+ * Loads a chunk with `load`, which is called with the URL to load it from,
+ * and retries with backoff as configured in `CHUNK_LOAD_RETRY`. Rejects with
+ * an error named `ChunkLoadError` once all attempts failed.
+ */ async function loadChunkWithRetry(chunkPath, chunkUrl, load) {
+    for(let attempt = 1;; attempt++){
+        const url = attempt === 1 ? chunkUrl : getRetryUrl(chunkUrl, attempt - 1);
+        try {
+            return await load(url);
+        } catch (error) {
+            const staleDeployment = await isChunkGone(url);
+            const willRetry = !staleDeployment && attempt <= CHUNK_LOAD_RETRY.retries;
+            const prevented = dispatchChunkLoadError({
+                chunkPath,
+                chunkUrl,
+                attempt,
+                willRetry,
+                staleDeployment,
+                error
+            });
+            if (willRetry) {
+                await new Promise((resolve)=>setTimeout(resolve, getRetryDelay(attempt)));
+                continue;
+            }
+            if (staleDeployment && !prevented && CHUNK_LOAD_RETRY.onStaleDeployment === "reload" && reloadForStaleDeployment()) {
+                // The page is reloading, so there is nothing left to do.
+                return new Promise(()=>{});
+            }
+            throw createChunkLoadError(chunkPath, staleDeployment, error);
+        }
+    }
+}
+function getRetryUrl(chunkUrl, retry) {
+    return `${chunkUrl}${chunkUrl.includes("?") ? "&" : "?"}turbopack-retry=${retry}`;
+}
+function getRetryDelay(attempt) {
+    const delay = Math.min(CHUNK_LOAD_RETRY.initialDelayMs * 2 ** (attempt - 1), CHUNK_LOAD_RETRY.maxDelayMs);
+    // Jitter keeps the clients of a failing server from retrying in lockstep.
+    return delay * (0.8 + Math.random() * 0.4);
+}
+/**
+ * Whether the server responds that the chunk doesn't exist. Network errors
+ * don't count, as they can be transient.
+ */ async function isChunkGone(url) {
+    if (typeof fetch !== "function") {
+        return false;
+    }
+    try {
+        const response = await fetch(url, {
+            method: "HEAD",
+            cache: "no-store"
+        });
+        return response.status === 404 || response.status === 410;
+    } catch  {
+        return false;
+    }
+}
+/**
+ * Dispatches the `turbopack:chunk-load-error` event. Returns whether a
+ * listener called `preventDefault()`.
+ */ function dispatchChunkLoadError(detail) {
+    if (typeof CustomEvent !== "function" || typeof globalThis.dispatchEvent !== "function") {
+        return false;
+    }
+    const event = new CustomEvent(CHUNK_LOAD_ERROR_EVENT, {
+        detail,
+        cancelable: true
+    });
+    return !globalThis.dispatchEvent(event);
+}
+/**
+ * Reloads the page, unless it was already reloaded for a stale deployment
+ * recently. Returns whether the page is reloading.
+ */ function reloadForStaleDeployment() {
+    // Only windows can be reloaded, workers don't have `location.reload`.
+    const { location, sessionStorage } = globalThis;
+    if (typeof location?.reload !== "function" || sessionStorage == null) {
+        return false;
+    }
+    try {
+        const lastReload = Number(sessionStorage.getItem(STALE_DEPLOYMENT_RELOAD_KEY));
+        if (Date.now() - lastReload < STALE_DEPLOYMENT_RELOAD_INTERVAL_MS) {
+            return false;
+        }
+        sessionStorage.setItem(STALE_DEPLOYMENT_RELOAD_KEY, String(Date.now()));
+    } catch  {
+        // Storage can be disabled, in which case reload loops can't be detected.
+        return false;
+    }
+    location.reload();
+    return true;
+}
+function createChunkLoadError(chunkPath, staleDeployment, cause) {
+    const error = new Error(staleDeployment ? `Chunk ${chunkPath} no longer exists on the server, the app was probably deployed again` : `Chunk ${chunkPath} failed to load after ${CHUNK_LOAD_RETRY.retries + 1} attempts`, cause ? {
+        cause
+    } : undefined);
+    error.name = "ChunkLoadError";
+    error.chunkPath = chunkPath;
+    error.staleDeployment = staleDeployment;
+    return error;
+}
 /**
  * This file contains runtime types and functions that are shared between all
  * Turbopack *development* ECMAScript runtimes.
@@ -469,9 +630,17 @@ async function loadChunkPath(source, chunkPath) {
             default:
                 invariant(source, (source)=>`Unknown source type: ${source?.type}`);
         }
-        throw new Error(`Failed to load chunk ${chunkPath} ${loadReason}${error ? `: ${error}` : ""}`, error ? {
+        const loadError = new Error(`Failed to load chunk ${chunkPath} ${loadReason}${error ? `: ${error}` : ""}`, error ? {
             cause: error
         } : undefined);
+        if (error instanceof Error && error.name === "ChunkLoadError") {
+            // Keep the name and the properties of errors from `loadChunkWithRetry`,
+            // so applications can tell stale deployments apart.
+            loadError.name = error.name;
+            loadError.chunkPath = error.chunkPath;
+            loadError.staleDeployment = error.staleDeployment;
+        }
+        throw loadError;
     }
 }
 /**
@@ -587,6 +756,12 @@ class UpdateApplyError extends Error {
 /**
  * Modules that call `module.hot.invalidate()` (while being updated).
  */ const queuedInvalidatedModules = new Set();
+/**
+ * Maps module IDs to whether they can be React Refresh boundaries, as
+ * determined when the module was compiled. Only the exports of modules which
+ * can be boundaries, or which are missing from this map, are checked at
+ * runtime.
+ */ const moduleRefreshBoundaries = new Map();
 /**
  * Gets or instantiates a runtime module.
  */ // @ts-ignore
@@ -753,9 +928,12 @@ function instantiateModule(id, source) {
     const currentExports = module.exports;
     const prevExports = module.hot.data.prevExports ?? null;
     helpers.registerExportsForReactRefresh(currentExports, module.id);
+    // The names of the exports can only tell that a module isn't a boundary, the
+    // values of the exports are authoritative.
+    const isBoundary = moduleRefreshBoundaries.get(module.id)?.isBoundary !== false && helpers.isReactRefreshBoundary(currentExports);
     // A module can be accepted automatically based on its exports, e.g. when
     // it is a Refresh Boundary.
-    if (helpers.isReactRefreshBoundary(currentExports)) {
+    if (isBoundary) {
         // Save the previous exports on update, so we can compare the boundary
         // signatures.
         module.hot.dispose((data)=>{
@@ -800,17 +978,34 @@ function computeOutdatedModules(added, modified) {
     for (const [moduleId, entry] of added){
         if (entry != null) {
             newModuleFactories.set(moduleId, _eval(entry));
+            setRefreshBoundary(moduleId, entry);
         }
     }
     const outdatedModules = computedInvalidatedModules(modified.keys());
     for (const [moduleId, entry] of modified){
         newModuleFactories.set(moduleId, _eval(entry));
+        setRefreshBoundary(moduleId, entry);
     }
     return {
         outdatedModules,
         newModuleFactories
     };
 }
+function setRefreshBoundary(moduleId, entry) {
+    if (entry.refresh != null) {
+        moduleRefreshBoundaries.set(moduleId, entry.refresh);
+    } else {
+        moduleRefreshBoundaries.delete(moduleId);
+    }
+}
+/**
+ * Registers the React Refresh boundaries of the modules of a chunk, before any
+ * of them is executed.
+ */ function registerRefreshBoundaries(refreshBoundaries) {
+    for (const [moduleId, refreshBoundary] of Object.entries(refreshBoundaries)){
+        moduleRefreshBoundaries.set(moduleId, refreshBoundary);
+    }
+}
 function computedInvalidatedModules(invalidated) {
     const outdatedModules = new Set();
     for (const moduleId of invalidated){
@@ -935,6 +1130,7 @@ function disposePhase(outdatedModules, disposedModules) {
         case "clear":
             delete devModuleCache[module.id];
             moduleHotData.delete(module.id);
+            moduleRefreshBoundaries.delete(module.id);
             break;
         case "replace":
             moduleHotData.set(module.id, data);
@@ -1390,6 +1586,13 @@ globalThis.TURBOPACK_CHUNK_LISTS = {
         registerChunkList(globalThis.TURBOPACK_CHUNK_UPDATE_LISTENERS, chunkList);
     }
 };
+const refreshBoundariesToRegister = globalThis.TURBOPACK_REFRESH_BOUNDARIES;
+if (Array.isArray(refreshBoundariesToRegister)) {
+    refreshBoundariesToRegister.forEach(registerRefreshBoundaries);
+}
+globalThis.TURBOPACK_REFRESH_BOUNDARIES = {
+    push: registerRefreshBoundaries
+};
 /**
  * This file contains the runtime code specific to the Turbopack development
  * ECMAScript DOM runtime.
@@ -1397,6 +1600,7 @@ globalThis.TURBOPACK_CHUNK_LISTS = {
  * It will be appended to the base development runtime code.
  */ /* eslint-disable @typescript-eslint/no-unused-vars */ /// <reference path="../../../browser/runtime/base/runtime-base.ts" />
 /// <reference path="../../../shared/runtime-types.d.ts" />
+/// <reference path="../../../shared/chunk-loader.ts" />
 let BACKEND;
 function augmentContext(context) {
     return context;
@@ -1489,6 +1693,12 @@ async function loadWebAssemblyModule(_source, wasmChunkPath) {
         }
         const chunkUrl = getChunkRelativeUrl(chunkPath);
         const decodedChunkUrl = decodeURI(chunkUrl);
+        if (CHUNK_LOADER_BACKEND != null) {
+            const backend = CHUNK_LOADER_BACKEND;
+            const load = backend.type === "fetch" ? loadChunkWithRetry(chunkPath, chunkUrl, (url)=>loadChunkFromBackend(backend, chunkPath, url)) : loadChunkFromBackend(backend, chunkPath, chunkUrl);
+            load.catch((error)=>resolver.reject(error));
+            return resolver.promise;
+        }
         if (typeof importScripts === "function") {
             // We're in a web worker
             if (chunkPath.endsWith(".css")) {
@@ -1506,18 +1716,9 @@ async function loadWebAssemblyModule(_source, wasmChunkPath) {
                     // loaded instantly.
                     resolver.resolve();
                 } else {
-                    const link = document.createElement("link");
-                    link.rel = "stylesheet";
-                    link.href = chunkUrl;
-                    link.onerror = ()=>{
-                        resolver.reject();
-                    };
-                    link.onload = ()=>{
-                        // CSS chunks do not register themselves, and as such must be marked as
-                        // loaded instantly.
-                        resolver.resolve();
-                    };
-                    document.body.appendChild(link);
+                    loadChunkWithRetry(chunkPath, chunkUrl, (url)=>appendStylesheet(url)).then(// CSS chunks do not register themselves, and as such must be marked as
+                    // loaded instantly.
+                    ()=>resolver.resolve(), (error)=>resolver.reject(error));
                 }
             } else if (chunkPath.endsWith(".js")) {
                 const previousScripts = document.querySelectorAll(`script[src="${chunkUrl}"],script[src^="${chunkUrl}?"],script[src="${decodedChunkUrl}"],script[src^="${decodedChunkUrl}?"]`);
@@ -1530,15 +1731,10 @@ async function loadWebAssemblyModule(_source, wasmChunkPath) {
                         });
                     }
                 } else {
-                    const script = document.createElement("script");
-                    script.src = chunkUrl;
                     // We'll only mark the chunk as loaded once the script has been executed,
                     // which happens in `registerChunk`. Hence the absence of `resolve()` in
                     // this branch.
-                    script.onerror = ()=>{
-                        resolver.reject();
-                    };
-                    document.body.appendChild(script);
+                    loadChunkWithRetry(chunkPath, chunkUrl, (url)=>appendScript(resolver, url)).catch((error)=>resolver.reject(error));
                 }
             } else {
                 throw new Error(`can't infer type of chunk from path ${chunkPath}`);
@@ -1546,6 +1742,62 @@ async function loadWebAssemblyModule(_source, wasmChunkPath) {
         }
         return resolver.promise;
     }
+    /**
+   * Appends a stylesheet, and returns a promise that resolves once it has been
+   * loaded. The element is removed when it fails to load, so it can be retried.
+   */ function appendStylesheet(url) {
+        return new Promise((resolve, reject)=>{
+            const link = document.createElement("link");
+            link.rel = "stylesheet";
+            link.href = url;
+            link.onerror = ()=>{
+                link.remove();
+                reject(new Error(`Failed to load stylesheet ${url}`));
+            };
+            link.onload = ()=>resolve();
+            document.body.appendChild(link);
+        });
+    }
+    /**
+   * Appends a script, and returns a promise that resolves once the chunk it
+   * contains has registered itself. The element is removed when it fails to
+   * load, so it can be retried.
+   */ function appendScript(resolver, url) {
+        return new Promise((resolve, reject)=>{
+            const script = document.createElement("script");
+            script.src = url;
+            script.onerror = ()=>{
+                script.remove();
+                reject(new Error(`Failed to load script ${url}`));
+            };
+            resolver.promise.then(resolve, reject);
+            document.body.appendChild(script);
+        });
+    }
+    /**
+   * Loads the given chunk through the chunk loader backend selected at build
+   * time instead of `<script>` and `<link>` tags.
+   */ async function loadChunkFromBackend(backend, chunkPath, chunkUrl) {
+        const resolver = getOrCreateResolver(chunkPath);
+        const contents = await readChunk(backend, chunkPath, chunkUrl);
+        if (chunkPath.endsWith(".css")) {
+            if (typeof document !== "undefined") {
+                const style = document.createElement("style");
+                style.textContent = contents;
+                document.head.appendChild(style);
+            }
+            // CSS chunks do not register themselves, and as such must be marked as
+            // loaded instantly.
+            resolver.resolve();
+        } else if (chunkPath.endsWith(".js")) {
+            // The chunk is marked as loaded once it registers itself, which happens
+            // synchronously during evaluation.
+            // eslint-disable-next-line no-eval
+            (0, eval)(`${contents}\n//# sourceURL=${chunkUrl}`);
+        } else {
+            throw new Error(`can't infer type of chunk from path ${chunkPath}`);
+        }
+    }
 })();
 /**
  * This file contains the runtime code specific to the Turbopack development
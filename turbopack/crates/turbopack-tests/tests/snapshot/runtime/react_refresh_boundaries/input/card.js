@@ -0,0 +1,5 @@
+export function Card() {
+  return null;
+}
+
+export const THEME = {};
@@ -0,0 +1,7 @@
+export function useTheme() {
+  return null;
+}
+
+export function Provider() {
+  return null;
+}
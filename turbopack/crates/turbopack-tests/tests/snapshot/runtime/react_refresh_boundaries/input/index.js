@@ -0,0 +1,5 @@
+import { Button } from "./button";
+import { Card, THEME } from "./card";
+import { useTheme } from "./hooks";
+
+console.log(Button, Card, THEME, useTheme);
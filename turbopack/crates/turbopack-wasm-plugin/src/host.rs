@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use turbo_rcstr::RcStr;
+use turbo_tasks::ResolvedVc;
+use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_core::issue::IssueSeverity;
+use wasmtime::{
+    AsContextMut, Caller, Config, Engine, Extern, Linker, Memory, StoreLimits, TypedFunc,
+};
+
+/// The name of the module the host API is imported from.
+const HOST_MODULE: &str = "turbopack";
+
+pub(crate) static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.async_support(true).consume_fuel(true);
+    Engine::new(&config).expect("the WASM plugin engine configuration must be valid")
+});
+
+pub(crate) static LINKER: Lazy<Linker<HostState>> =
+    Lazy::new(|| host_api().expect("the WASM plugin host API must be valid"));
+
+/// The state of a single plugin invocation, which collects everything the
+/// plugin passes to the host.
+pub(crate) struct HostState {
+    /// Files outside of the root can't be read.
+    root: ResolvedVc<FileSystemPath>,
+    pub limits: StoreLimits,
+    pub output: Option<RcStr>,
+    pub assets: Vec<(RcStr, Vec<u8>)>,
+    pub issues: Vec<PluginDiagnostic>,
+}
+
+pub(crate) struct PluginDiagnostic {
+    pub severity: IssueSeverity,
+    pub title: RcStr,
+    pub description: Option<RcStr>,
+}
+
+impl HostState {
+    pub fn new(root: ResolvedVc<FileSystemPath>, limits: StoreLimits) -> Self {
+        HostState {
+            root,
+            limits,
+            output: None,
+            assets: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+}
+
+fn host_api() -> Result<Linker<HostState>> {
+    let mut linker = Linker::new(&ENGINE);
+    linker.func_wrap(
+        HOST_MODULE,
+        "set_output",
+        |mut caller: Caller<'_, HostState>, ptr: u32, len: u32| -> Result<()> {
+            let output = read_string(&mut caller, ptr, len)?;
+            caller.data_mut().output = Some(output);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap_async(
+        HOST_MODULE,
+        "read_file",
+        |mut caller: Caller<'_, HostState>, (ptr, len): (u32, u32)| {
+            Box::new(async move {
+                let path = read_string(&mut caller, ptr, len)?;
+                let Some(content) = read_file(caller.data().root, path).await? else {
+                    return Ok(u64::MAX);
+                };
+                let alloc = guest_alloc(&mut caller)?;
+                let memory = guest_memory(&mut caller)?;
+                let ptr = write_bytes(&mut caller, alloc, memory, &content).await?;
+                Ok(((ptr as u64) << 32) | content.len() as u64)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "emit_asset",
+        |mut caller: Caller<'_, HostState>,
+         path_ptr: u32,
+         path_len: u32,
+         content_ptr: u32,
+         content_len: u32|
+         -> Result<()> {
+            let path = read_string(&mut caller, path_ptr, path_len)?;
+            let content = read_bytes(&mut caller, content_ptr, content_len)?;
+            caller.data_mut().assets.push((path, content));
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        HOST_MODULE,
+        "report_issue",
+        |mut caller: Caller<'_, HostState>,
+         severity: u32,
+         title_ptr: u32,
+         title_len: u32,
+         description_ptr: u32,
+         description_len: u32|
+         -> Result<()> {
+            let title = read_string(&mut caller, title_ptr, title_len)?;
+            let description = read_string(&mut caller, description_ptr, description_len)?;
+            caller.data_mut().issues.push(PluginDiagnostic {
+                severity: match severity {
+                    0 => IssueSeverity::Error,
+                    1 => IssueSeverity::Warning,
+                    _ => IssueSeverity::Info,
+                },
+                title,
+                description: (!description.is_empty()).then_some(description),
+            });
+            Ok(())
+        },
+    )?;
+    Ok(linker)
+}
+
+/// Reads a file inside of `root`. Returns `None` when it doesn't exist or
+/// would be outside of `root`.
+async fn read_file(root: ResolvedVc<FileSystemPath>, path: RcStr) -> Result<Option<Vec<u8>>> {
+    let Some(path) = *root.try_join_inside(path).await? else {
+        return Ok(None);
+    };
+    let content = path.read().await?;
+    Ok(match &*content {
+        FileContent::Content(file) => Some(file.content().to_bytes()?.into_owned()),
+        FileContent::NotFound => None,
+    })
+}
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .context("the plugin doesn't export its memory")
+}
+
+fn guest_alloc(caller: &mut Caller<'_, HostState>) -> Result<TypedFunc<u32, u32>> {
+    caller
+        .get_export("alloc")
+        .and_then(Extern::into_func)
+        .context("the plugin doesn't export alloc")?
+        .typed(&caller)
+}
+
+fn read_bytes(caller: &mut Caller<'_, HostState>, ptr: u32, len: u32) -> Result<Vec<u8>> {
+    let memory = guest_memory(caller)?;
+    let start = ptr as usize;
+    let end = start + len as usize;
+    Ok(memory
+        .data(&caller)
+        .get(start..end)
+        .context("the plugin passed memory out of bounds")?
+        .to_vec())
+}
+
+fn read_string(caller: &mut Caller<'_, HostState>, ptr: u32, len: u32) -> Result<RcStr> {
+    let bytes = read_bytes(caller, ptr, len)?;
+    Ok(String::from_utf8(bytes)
+        .context("the plugin passed a string that isn't valid UTF-8")?
+        .into())
+}
+
+/// Copies `bytes` into a buffer allocated by the plugin and returns its
+/// address.
+pub(crate) async fn write_bytes(
+    mut store: impl AsContextMut<Data = HostState>,
+    alloc: TypedFunc<u32, u32>,
+    memory: Memory,
+    bytes: &[u8],
+) -> Result<u32> {
+    let len = u32::try_from(bytes.len()).context("data is too large for the plugin")?;
+    let ptr = alloc.call_async(&mut store, len).await?;
+    memory.write(&mut store, ptr as usize, bytes)?;
+    Ok(ptr)
+}
@@ -0,0 +1,440 @@
+//! Source transform plugins compiled to WebAssembly, which run inside of the
+//! compiler process but can only use the capabilities granted by the host API.
+//!
+//! # ABI
+//!
+//! A plugin module exports
+//! - `memory`,
+//! - `alloc(len: u32) -> u32`, which allocates a buffer for data passed to the plugin,
+//! - `transform(path_ptr: u32, path_len: u32, content_ptr: u32, content_len: u32) -> u32`, which
+//!   returns `0` on success.
+//!
+//! The plugin can import these functions from the `turbopack` module. Strings
+//! are UTF-8 and passed as pointer and length.
+//! - `set_output(ptr: u32, len: u32)` sets the transformed content. Without it the content stays
+//!   unchanged.
+//! - `read_file(path_ptr: u32, path_len: u32) -> u64` reads a file relative to the plugin root into
+//!   a buffer allocated with `alloc`, and returns its pointer in the upper and its length in the
+//!   lower 32 bits. It returns `u64::MAX` when the file doesn't exist or is outside of the root.
+//! - `emit_asset(path_ptr: u32, path_len: u32, content_ptr: u32, content_len: u32)` emits an
+//!   additional output asset relative to the output root. Assets outside of the output root are
+//!   reported as an issue and not emitted.
+//! - `report_issue(severity: u32, title_ptr: u32, title_len: u32, description_ptr: u32,
+//!   description_len: u32)` reports an issue with severity `0` (error), `1` (warning) or `2`
+//!   (info).
+//!
+//! Every invocation runs in a new instance, limited by [WasmPluginLimits].
+//! Exceeding the limits aborts the invocation, which is reported as an issue
+//! and leaves the content unchanged.
+
+#![feature(min_specialization)]
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+mod host;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{trace::TraceRawVcs, ResolvedVc, TaskInput, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbo_tasks_hash::hash_xxh3_hash64;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    ident::AssetIdent,
+    issue::{
+        Issue, IssueCode, IssueExt, IssueSeverity, IssueStage, OptionIssueCode, OptionStyledString,
+        StyledString,
+    },
+    output::OutputAssets,
+    source::Source,
+    source_transform::SourceTransform,
+    virtual_output::VirtualOutputAsset,
+};
+use wasmtime::{Module, Store, StoreLimitsBuilder};
+
+use self::host::{write_bytes, HostState, PluginDiagnostic, ENGINE, LINKER};
+
+/// Resource limits of a single plugin invocation.
+#[derive(
+    Debug, Clone, Copy, TaskInput, PartialEq, Eq, Hash, Serialize, Deserialize, TraceRawVcs,
+)]
+pub struct WasmPluginLimits {
+    /// The fuel available to the plugin. Roughly one unit is consumed per
+    /// executed instruction.
+    pub fuel: u64,
+    /// The maximum size of the linear memory of the plugin in bytes.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for WasmPluginLimits {
+    fn default() -> Self {
+        WasmPluginLimits {
+            fuel: 10_000_000_000,
+            max_memory_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// A compiled plugin module. Two modules are equal when their bytes are, so
+/// touching the plugin file without changing it doesn't invalidate any
+/// transform results.
+#[turbo_tasks::value(serialization = "none", eq = "manual")]
+pub struct CompiledWasmPlugin {
+    hash: u64,
+    #[turbo_tasks(trace_ignore, debug_ignore)]
+    module: Module,
+}
+
+impl PartialEq for CompiledWasmPlugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Eq for CompiledWasmPlugin {}
+
+#[turbo_tasks::function]
+pub async fn compile_wasm_plugin(path: Vc<FileSystemPath>) -> Result<Vc<CompiledWasmPlugin>> {
+    let content = path.read().await?;
+    let FileContent::Content(file) = &*content else {
+        bail!("WASM plugin {} not found", path.to_string().await?);
+    };
+    let bytes = file.content().to_bytes()?;
+    let module = match Module::new(&ENGINE, &bytes) {
+        Ok(module) => module,
+        Err(err) => {
+            return Err(err.context(format!(
+                "failed to compile WASM plugin {}",
+                path.to_string().await?
+            )))
+        }
+    };
+    Ok(CompiledWasmPlugin {
+        hash: hash_xxh3_hash64(&bytes),
+        module,
+    }
+    .cell())
+}
+
+/// A WASM plugin at `path`, which can read files in `root` and emits assets
+/// into `output_root`.
+#[turbo_tasks::value]
+pub struct WasmPlugin {
+    path: ResolvedVc<FileSystemPath>,
+    root: ResolvedVc<FileSystemPath>,
+    output_root: ResolvedVc<FileSystemPath>,
+    limits: WasmPluginLimits,
+}
+
+/// The result of a plugin invocation.
+#[turbo_tasks::value(shared)]
+pub struct WasmPluginOutput {
+    /// The transformed content, or `None` when it's unchanged.
+    pub content: Option<RcStr>,
+    pub assets: ResolvedVc<OutputAssets>,
+}
+
+#[turbo_tasks::value_impl]
+impl WasmPlugin {
+    #[turbo_tasks::function]
+    pub fn new(
+        path: ResolvedVc<FileSystemPath>,
+        root: ResolvedVc<FileSystemPath>,
+        output_root: ResolvedVc<FileSystemPath>,
+        limits: WasmPluginLimits,
+    ) -> Vc<Self> {
+        WasmPlugin {
+            path,
+            root,
+            output_root,
+            limits,
+        }
+        .cell()
+    }
+
+    /// Runs the plugin on the `content` of the source at `path`. Results are
+    /// cached by plugin module and input, see [CompiledWasmPlugin].
+    #[turbo_tasks::function]
+    pub fn run(&self, path: ResolvedVc<FileSystemPath>, content: RcStr) -> Vc<WasmPluginOutput> {
+        run_wasm_plugin(
+            compile_wasm_plugin(*self.path),
+            *self.root,
+            *self.output_root,
+            self.limits,
+            *path,
+            content,
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn source_transform(self: ResolvedVc<Self>) -> Vc<WasmPluginSourceTransform> {
+        WasmPluginSourceTransform { plugin: self }.cell()
+    }
+}
+
+/// The fuel a plugin consumes between yielding to the async runtime.
+const FUEL_ASYNC_YIELD_INTERVAL: u64 = 10_000;
+
+#[turbo_tasks::function]
+async fn run_wasm_plugin(
+    plugin: Vc<CompiledWasmPlugin>,
+    root: ResolvedVc<FileSystemPath>,
+    output_root: Vc<FileSystemPath>,
+    limits: WasmPluginLimits,
+    path: ResolvedVc<FileSystemPath>,
+    content: RcStr,
+) -> Result<Vc<WasmPluginOutput>> {
+    let plugin = plugin.await?;
+    let path_value = path.await?;
+    let relative_path: RcStr = match root.await?.get_path_to(&path_value) {
+        Some(relative_path) => relative_path.into(),
+        None => path_value.path.clone(),
+    };
+
+    let store_limits = StoreLimitsBuilder::new()
+        .memory_size(limits.max_memory_bytes)
+        .instances(1)
+        .build();
+    let mut store = Store::new(&ENGINE, HostState::new(root, store_limits));
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(limits.fuel)?;
+    // Plugins run on the async runtime, so a long running plugin must yield regularly instead of
+    // blocking a worker thread until it runs out of fuel.
+    store.fuel_async_yield_interval(Some(FUEL_ASYNC_YIELD_INTERVAL))?;
+    let result = invoke(&mut store, &plugin.module, &relative_path, &content).await;
+    let state = store.into_data();
+
+    let mut diagnostics = state.issues;
+    if let Err(err) = &result {
+        diagnostics.push(PluginDiagnostic {
+            severity: IssueSeverity::Error,
+            title: "WASM plugin failed".into(),
+            description: Some(format!("{err:#}").into()),
+        });
+    }
+    for diagnostic in diagnostics {
+        WasmPluginIssue {
+            path,
+            severity: diagnostic.severity.resolved_cell(),
+            title: diagnostic.title,
+            description: diagnostic.description,
+        }
+        .cell()
+        .emit();
+    }
+    if result.is_err() {
+        return Ok(WasmPluginOutput {
+            content: None,
+            assets: ResolvedVc::cell(vec![]),
+        }
+        .cell());
+    }
+
+    let mut assets = Vec::with_capacity(state.assets.len());
+    for (asset_path, asset_content) in state.assets {
+        let Some(asset_path) = *output_root.try_join_inside(asset_path.clone()).await? else {
+            WasmPluginIssue {
+                path,
+                severity: IssueSeverity::Error.resolved_cell(),
+                title: "WASM plugin emitted an asset outside of the output root".into(),
+                description: Some(format!("The asset {asset_path} was not emitted.").into()),
+            }
+            .cell()
+            .emit();
+            continue;
+        };
+        assets.push(ResolvedVc::upcast(
+            VirtualOutputAsset::new(
+                *asset_path,
+                AssetContent::file(File::from(asset_content).into()),
+            )
+            .to_resolved()
+            .await?,
+        ));
+    }
+    Ok(WasmPluginOutput {
+        content: state.output,
+        assets: ResolvedVc::cell(assets),
+    }
+    .cell())
+}
+
+async fn invoke(
+    store: &mut Store<HostState>,
+    module: &Module,
+    path: &str,
+    content: &str,
+) -> Result<()> {
+    let instance = LINKER.instantiate_async(&mut *store, module).await?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .context("the plugin doesn't export its memory")?;
+    let alloc = instance.get_typed_func::<u32, u32>(&mut *store, "alloc")?;
+    let transform =
+        instance.get_typed_func::<(u32, u32, u32, u32), u32>(&mut *store, "transform")?;
+    let path_ptr = write_bytes(&mut *store, alloc, memory, path.as_bytes()).await?;
+    let content_ptr = write_bytes(&mut *store, alloc, memory, content.as_bytes()).await?;
+    let status = transform
+        .call_async(
+            &mut *store,
+            (
+                path_ptr,
+                path.len() as u32,
+                content_ptr,
+                content.len() as u32,
+            ),
+        )
+        .await?;
+    if status != 0 {
+        bail!("the plugin returned status {status}");
+    }
+    Ok(())
+}
+
+/// Applies a [WasmPlugin] to text sources. The transform is usually limited to
+/// some sources via module rule conditions.
+#[turbo_tasks::value]
+pub struct WasmPluginSourceTransform {
+    plugin: ResolvedVc<WasmPlugin>,
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for WasmPluginSourceTransform {
+    #[turbo_tasks::function]
+    async fn transform(&self, source: Vc<Box<dyn Source>>) -> Result<Vc<Box<dyn Source>>> {
+        Ok(Vc::upcast(
+            WasmTransformedSource {
+                source: source.to_resolved().await?,
+                plugin: self.plugin,
+            }
+            .cell(),
+        ))
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct OptionWasmPluginOutput(Option<ResolvedVc<WasmPluginOutput>>);
+
+#[turbo_tasks::value]
+pub struct WasmTransformedSource {
+    source: ResolvedVc<Box<dyn Source>>,
+    plugin: ResolvedVc<WasmPlugin>,
+}
+
+#[turbo_tasks::value_impl]
+impl WasmTransformedSource {
+    /// The plugin output, or `None` when the source isn't a text file.
+    #[turbo_tasks::function]
+    async fn output(&self) -> Result<Vc<OptionWasmPluginOutput>> {
+        let content = self.source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(Vc::cell(None));
+        };
+        let Ok(text) = file.content().to_str() else {
+            return Ok(Vc::cell(None));
+        };
+        Ok(Vc::cell(Some(
+            self.plugin
+                .run(self.source.ident().path(), text.as_ref().into())
+                .to_resolved()
+                .await?,
+        )))
+    }
+
+    /// The assets emitted by the plugin while transforming this source.
+    #[turbo_tasks::function]
+    pub async fn emitted_assets(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
+        Ok(match *self.output().await? {
+            Some(output) => *output.await?.assets,
+            None => OutputAssets::empty(),
+        })
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for WasmTransformedSource {
+    #[turbo_tasks::function]
+    async fn ident(&self) -> Result<Vc<AssetIdent>> {
+        let plugin = compile_wasm_plugin(*self.plugin.await?.path).await?;
+        Ok(self
+            .source
+            .ident()
+            .with_modifier(Vc::cell(format!("wasm plugin {:016x}", plugin.hash).into())))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for WasmTransformedSource {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let source = self.await?.source;
+        let Some(output) = *self.output().await? else {
+            return Ok(source.content());
+        };
+        let Some(transformed) = output.await?.content.clone() else {
+            return Ok(source.content());
+        };
+        let content = source.content().file_content().await?;
+        let FileContent::Content(file) = &*content else {
+            return Ok(source.content());
+        };
+        let mut transformed_file = File::from(transformed);
+        if let Some(content_type) = file.content_type() {
+            transformed_file = transformed_file.with_content_type(content_type.clone());
+        }
+        Ok(AssetContent::file(transformed_file.into()))
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct WasmPluginIssue {
+    path: ResolvedVc<FileSystemPath>,
+    severity: ResolvedVc<IssueSeverity>,
+    title: RcStr,
+    description: Option<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for WasmPluginIssue {
+    #[turbo_tasks::function]
+    fn code(self: Vc<Self>) -> Vc<OptionIssueCode> {
+        Vc::cell(Some(IssueCode::WASM_PLUGIN_ERROR))
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        *self.severity
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::SourceTransform.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(
+            self.description
+                .clone()
+                .map(|description| StyledString::Text(description).cell()),
+        )
+    }
+}
+
+pub fn register() {
+    turbo_tasks::register();
+    turbo_tasks_fs::register();
+    turbopack_core::register();
+    include!(concat!(env!("OUT_DIR"), "/register.rs"));
+}
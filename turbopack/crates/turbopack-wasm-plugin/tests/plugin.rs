@@ -0,0 +1,131 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_tasks::{TurboTasks, ValueToString};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    asset::Asset,
+    issue::{IssueDescriptionExt, StyledString},
+};
+use turbopack_wasm_plugin::{WasmPlugin, WasmPluginLimits};
+
+/// Replaces the content with `transformed` and emits it as two assets, one of
+/// them outside of the output root.
+const PLUGIN: &str = r#"
+(module
+  (import "turbopack" "set_output" (func $set_output (param i32 i32)))
+  (import "turbopack" "emit_asset" (func $emit_asset (param i32 i32 i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "transformed")
+  (data (i32.const 16) "assets/out.txt")
+  (data (i32.const 32) "../escaped.txt")
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func (export "transform") (param i32 i32 i32 i32) (result i32)
+    (call $set_output (i32.const 0) (i32.const 11))
+    (call $emit_asset (i32.const 16) (i32.const 14) (i32.const 0) (i32.const 11))
+    (call $emit_asset (i32.const 32) (i32.const 14) (i32.const 0) (i32.const 11))
+    (i32.const 0)))
+"#;
+
+#[tokio::test]
+async fn emits_assets_only_inside_of_the_output_root() {
+    turbopack_wasm_plugin::register();
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("plugin.wasm"),
+        wat::parse_str(PLUGIN).unwrap(),
+    )
+    .unwrap();
+    let root = dir.path().to_str().unwrap().into();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = DiskFileSystem::new("project".into(), root, vec![]).root();
+        let plugin = WasmPlugin::new(
+            root.join("plugin.wasm".into()).to_resolved().await?,
+            root.to_resolved().await?,
+            root.join("out".into()).to_resolved().await?,
+            WasmPluginLimits::default(),
+        );
+        let output = plugin.run(
+            root.join("src/index.js".into()).to_resolved().await?,
+            "original".into(),
+        );
+        let output_value = output.strongly_consistent().await?;
+        assert_eq!(output_value.content.as_deref(), Some("transformed"));
+
+        let assets = output_value.assets.await?;
+        assert_eq!(assets.len(), 1);
+        assert_eq!(
+            &*assets[0].ident().path().to_string().await?,
+            "[project]/out/assets/out.txt"
+        );
+        let content = assets[0].content().file_content().await?;
+        assert_eq!(
+            content.as_content().unwrap().content().to_str()?,
+            "transformed"
+        );
+
+        let issues = output.peek_issues_with_path().await?;
+        assert_eq!(issues.len(), 1);
+        let issue = issues.iter().next().unwrap();
+        assert_eq!(
+            *issue.title().await?,
+            StyledString::Text("WASM plugin emitted an asset outside of the output root".into())
+        );
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn leaves_the_content_unchanged_when_the_plugin_runs_out_of_fuel() -> Result<()> {
+    turbopack_wasm_plugin::register();
+    let dir = tempfile::tempdir()?;
+    std::fs::write(
+        dir.path().join("plugin.wasm"),
+        wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "transform") (param i32 i32 i32 i32) (result i32)
+                (loop $forever (br $forever))
+                (i32.const 0)))
+            "#,
+        )?,
+    )?;
+    let root = dir.path().to_str().unwrap().into();
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = DiskFileSystem::new("project".into(), root, vec![]).root();
+        let plugin = WasmPlugin::new(
+            root.join("plugin.wasm".into()).to_resolved().await?,
+            root.to_resolved().await?,
+            root.join("out".into()).to_resolved().await?,
+            WasmPluginLimits {
+                fuel: 10_000,
+                ..Default::default()
+            },
+        );
+        let output = plugin.run(
+            root.join("src/index.js".into()).to_resolved().await?,
+            "original".into(),
+        );
+        let output_value = output.strongly_consistent().await?;
+        assert_eq!(output_value.content, None);
+        assert!(output_value.assets.await?.is_empty());
+        assert_eq!(output.peek_issues_with_path().await?.len(), 1);
+        anyhow::Ok(())
+    })
+    .await
+}
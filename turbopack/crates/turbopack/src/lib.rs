@@ -60,7 +60,8 @@ use turbopack_ecmascript::{
     references::external_module::{CachedExternalModule, CachedExternalType},
     tree_shake::asset::EcmascriptModulePartAsset,
 };
-use turbopack_json::JsonModuleAsset;
+use turbopack_graphql::GraphQlModuleAsset;
+use turbopack_json::{JsonFormat, JsonModuleAsset};
 pub use turbopack_resolve::{resolve::resolve_options, resolve_options_context};
 use turbopack_resolve::{resolve_options_context::ResolveOptionsContext, typescript::type_resolve};
 use turbopack_static::StaticModuleAsset;
@@ -226,6 +227,21 @@ async fn apply_module_type(
             }
         }
         ModuleType::Json => ResolvedVc::upcast(JsonModuleAsset::new(*source).to_resolved().await?),
+        ModuleType::Jsonc => ResolvedVc::upcast(
+            JsonModuleAsset::new_with_format(*source, JsonFormat::Jsonc)
+                .to_resolved()
+                .await?,
+        ),
+        ModuleType::Json5 => ResolvedVc::upcast(
+            JsonModuleAsset::new_with_format(*source, JsonFormat::Json5)
+                .to_resolved()
+                .await?,
+        ),
+        ModuleType::GraphQl { options } => ResolvedVc::upcast(
+            GraphQlModuleAsset::new(*source, Vc::upcast(module_asset_context), **options)
+                .to_resolved()
+                .await?,
+        ),
         ModuleType::Raw => ResolvedVc::upcast(RawModule::new(*source).to_resolved().await?),
         ModuleType::CssGlobal => {
             return Ok(module_asset_context.process(
@@ -1059,9 +1075,12 @@ pub fn register() {
     turbopack_node::register();
     turbopack_env::register();
     turbopack_mdx::register();
+    turbopack_graphql::register();
     turbopack_json::register();
     turbopack_resolve::register();
     turbopack_static::register();
     turbopack_wasm::register();
+    turbopack_plugin_rpc::register();
+    turbopack_wasm_plugin::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
 }
@@ -11,18 +11,23 @@ pub use module_options_context::*;
 pub use module_rule::*;
 pub use rule_condition::*;
 use turbo_rcstr::RcStr;
-use turbo_tasks::{ResolvedVc, Vc};
+use turbo_tasks::{ReadRef, ResolvedVc, Vc};
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use turbopack_core::{
     reference_type::{CssReferenceSubType, ReferenceType, UrlReferenceSubType},
     resolve::options::{ImportMap, ImportMapping},
+    source_transform::ReplaceSourceTransform,
+    text_transform::TextSourceTransform,
 };
 use turbopack_css::CssModuleAssetType;
 use turbopack_ecmascript::{
     EcmascriptInputTransform, EcmascriptInputTransforms, EcmascriptOptions, SpecifiedModuleType,
 };
 use turbopack_mdx::MdxTransform;
-use turbopack_node::transforms::{postcss::PostCssTransform, webpack::WebpackLoaders};
+use turbopack_node::{
+    execution_context::ExecutionContext,
+    transforms::{postcss::PostCssTransform, webpack::WebpackLoaders},
+};
 use turbopack_wasm::source::WebAssemblySourceType;
 
 use crate::{
@@ -55,6 +60,36 @@ async fn package_import_map_from_context(
     import_map.cell()
 }
 
+/// Creates a condition matching sources by a glob. Globs without `/` match the
+/// file name, other globs match the path relative to `base`.
+async fn resource_glob_condition(
+    glob: &RcStr,
+    base: &ReadRef<FileSystemPath>,
+) -> Result<RuleCondition> {
+    Ok(RuleCondition::All(vec![
+        if !glob.contains('/') {
+            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
+        } else {
+            RuleCondition::ResourcePathGlob {
+                base: base.clone(),
+                glob: Glob::new(glob.clone()).await?,
+            }
+        },
+        RuleCondition::not(RuleCondition::ResourceIsVirtualSource),
+    ]))
+}
+
+/// The path that globs of rules are relative to.
+async fn project_path(
+    path: Vc<FileSystemPath>,
+    execution_context: Option<ResolvedVc<ExecutionContext>>,
+) -> Result<ReadRef<FileSystemPath>> {
+    Ok(match execution_context {
+        Some(execution_context) => execution_context.project_path().await?,
+        None => path.root().await?,
+    })
+}
+
 #[turbo_tasks::value(cell = "new", eq = "manual")]
 pub struct ModuleOptions {
     pub rules: Vec<ModuleRule>,
@@ -83,11 +118,16 @@ impl ModuleOptions {
                 },
             enable_mdx,
             enable_mdx_rs,
+            enable_graphql,
             css: CssOptionsContext { enable_raw_css, .. },
             ref enable_postcss_transform,
             ref enable_webpack_loaders,
             preset_env_versions,
             ref module_rules,
+            ref text_transforms,
+            ref wasm_plugins,
+            ref rpc_plugins,
+            ref replacements,
             execution_context,
             ref rules,
             tree_shaking_mode,
@@ -156,6 +196,7 @@ impl ModuleOptions {
             let options = options.await?;
             Some(EcmascriptInputTransform::TypeScript {
                 use_define_for_class_fields: options.use_define_for_class_fields,
+                verbatim_module_syntax: options.verbatim_module_syntax,
             })
         } else {
             None
@@ -219,6 +260,14 @@ impl ModuleOptions {
                 RuleCondition::ResourcePathEndsWith(".json".to_string()),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Json)],
             ),
+            ModuleRule::new_all(
+                RuleCondition::ResourcePathEndsWith(".jsonc".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Jsonc)],
+            ),
+            ModuleRule::new_all(
+                RuleCondition::ResourcePathEndsWith(".json5".to_string()),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Json5)],
+            ),
             ModuleRule::new_all(
                 RuleCondition::any(vec![
                     RuleCondition::ResourcePathEndsWith(".js".to_string()),
@@ -424,6 +473,7 @@ impl ModuleOptions {
                             ),
                             *execution_context,
                             options.config_location,
+                            options.class_candidates,
                         )),
                     ]))],
                 ));
@@ -528,6 +578,51 @@ impl ModuleOptions {
             ));
         }
 
+        if let Some(options) = enable_graphql {
+            rules.push(ModuleRule::new_all(
+                RuleCondition::any(vec![
+                    RuleCondition::ResourcePathEndsWith(".graphql".to_string()),
+                    RuleCondition::ResourcePathEndsWith(".gql".to_string()),
+                ]),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::GraphQl {
+                    options,
+                })],
+            ));
+        }
+
+        if !text_transforms.is_empty() {
+            let base = project_path(path, execution_context).await?;
+            for (glob, transform) in text_transforms.iter() {
+                rules.push(ModuleRule::new(
+                    resource_glob_condition(glob, &base).await?,
+                    vec![ModuleRuleEffect::SourceTransforms(ResolvedVc::cell(vec![
+                        Vc::upcast(TextSourceTransform::new(**transform)),
+                    ]))],
+                ));
+            }
+        }
+
+        if !wasm_plugins.is_empty() {
+            let base = project_path(path, execution_context).await?;
+            for (glob, plugin) in wasm_plugins.iter() {
+                rules.push(ModuleRule::new(
+                    resource_glob_condition(glob, &base).await?,
+                    vec![ModuleRuleEffect::SourceTransforms(ResolvedVc::cell(vec![
+                        Vc::upcast(plugin.source_transform()),
+                    ]))],
+                ));
+            }
+        }
+
+        for plugin in rpc_plugins.iter() {
+            rules.push(ModuleRule::new(
+                RuleCondition::all(vec![]),
+                vec![ModuleRuleEffect::SourceTransforms(ResolvedVc::cell(vec![
+                    Vc::upcast(plugin.source_transform()),
+                ]))],
+            ));
+        }
+
         if let Some(webpack_loaders_options) = enable_webpack_loaders {
             let webpack_loaders_options = webpack_loaders_options.await?;
             let execution_context =
@@ -544,17 +639,7 @@ impl ModuleOptions {
             };
             for (glob, rule) in webpack_loaders_options.rules.await?.iter() {
                 rules.push(ModuleRule::new(
-                    RuleCondition::All(vec![
-                        if !glob.contains('/') {
-                            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
-                        } else {
-                            RuleCondition::ResourcePathGlob {
-                                base: execution_context.project_path().await?,
-                                glob: Glob::new(glob.clone()).await?,
-                            }
-                        },
-                        RuleCondition::not(RuleCondition::ResourceIsVirtualSource),
-                    ]),
+                    resource_glob_condition(glob, &execution_context.project_path().await?).await?,
                     vec![ModuleRuleEffect::SourceTransforms(ResolvedVc::cell(vec![
                         Vc::upcast(WebpackLoaders::new(
                             node_evaluate_asset_context(
@@ -576,6 +661,22 @@ impl ModuleOptions {
 
         rules.extend(module_rules.iter().cloned());
 
+        // Replacements have to be applied before any other rule, so the
+        // replaced source is never transformed or parsed.
+        if !replacements.is_empty() {
+            let base = project_path(path, execution_context).await?;
+            let mut replacement_rules = Vec::with_capacity(replacements.len());
+            for (glob, replacement) in replacements.iter() {
+                replacement_rules.push(ModuleRule::new(
+                    resource_glob_condition(glob, &base).await?,
+                    vec![ModuleRuleEffect::SourceTransforms(ResolvedVc::cell(vec![
+                        Vc::upcast(ReplaceSourceTransform::new(**replacement)),
+                    ]))],
+                ));
+            }
+            rules.splice(0..0, replacement_rules);
+        }
+
         Ok(ModuleOptions::cell(ModuleOptions { rules }))
     }
 }
@@ -4,14 +4,17 @@ use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, ResolvedVc, ValueDefault, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     chunk::MinifyType, condition::ContextCondition, environment::Environment,
-    resolve::options::ImportMapping,
+    resolve::options::ImportMapping, source::Source, text_transform::TextTransform,
 };
 use turbopack_ecmascript::{references::esm::UrlRewriteBehavior, TreeShakingMode};
+pub use turbopack_graphql::GraphQlOptions;
 pub use turbopack_mdx::MdxTransformOptions;
 use turbopack_node::{
     execution_context::ExecutionContext,
     transforms::{postcss::PostCssTransformOptions, webpack::WebpackLoaderItems},
 };
+use turbopack_plugin_rpc::RpcPlugin;
+use turbopack_wasm_plugin::WasmPlugin;
 
 use super::ModuleRule;
 
@@ -87,6 +90,10 @@ impl ValueDefault for DecoratorsOptions {
 #[derive(Default, Clone, Debug)]
 pub struct TypescriptTransformOptions {
     pub use_define_for_class_fields: bool,
+    /// Keeps imports whose specifiers are all types as side-effect imports
+    /// instead of eliding them, only `import type` is elided.
+    /// (https://www.typescriptlang.org/tsconfig#verbatimModuleSyntax)
+    pub verbatim_module_syntax: bool,
 }
 
 #[turbo_tasks::value_impl]
@@ -120,6 +127,8 @@ pub struct ModuleOptionsContext {
     // however we might want to unify them in the future.
     pub enable_mdx: bool,
     pub enable_mdx_rs: Option<ResolvedVc<MdxTransformOptions>>,
+    /// Treats `.graphql` and `.gql` files as GraphQL documents.
+    pub enable_graphql: Option<ResolvedVc<GraphQlOptions>>,
 
     pub preset_env_versions: Option<ResolvedVc<Environment>>,
     pub execution_context: Option<ResolvedVc<ExecutionContext>>,
@@ -133,6 +142,27 @@ pub struct ModuleOptionsContext {
     /// runtime.
     pub enable_externals_tracing: Option<ResolvedVc<FileSystemPath>>,
 
+    /// Transforms of the raw text of sources matching a glob, applied in
+    /// order before the source is parsed. Globs without `/` match the file
+    /// name, other globs match the path relative to the project.
+    pub text_transforms: Vec<(RcStr, ResolvedVc<Box<dyn TextTransform>>)>,
+
+    /// WASM plugins transforming sources matching a glob, applied in order
+    /// after the [Self::text_transforms]. Globs are matched like in
+    /// [Self::text_transforms].
+    pub wasm_plugins: Vec<(RcStr, ResolvedVc<WasmPlugin>)>,
+
+    /// Plugin processes transforming sources, applied in order after the
+    /// [Self::wasm_plugins]. Each plugin announces the sources it transforms
+    /// itself.
+    pub rpc_plugins: Vec<ResolvedVc<RpcPlugin>>,
+
+    /// Replaces sources matching a glob with another source, e.g. to mock
+    /// modules in tests and previews. Replacements are applied after
+    /// resolving and before any other rule. Globs are matched like in
+    /// [Self::text_transforms].
+    pub replacements: Vec<(RcStr, ResolvedVc<Box<dyn Source>>)>,
+
     /// Custom rules to be applied after all default rules.
     pub module_rules: Vec<ModuleRule>,
     /// A list of rules to use a different module option context for certain
@@ -7,6 +7,7 @@ use turbopack_core::{
 };
 use turbopack_css::CssModuleAssetType;
 use turbopack_ecmascript::{EcmascriptInputTransforms, EcmascriptOptions};
+use turbopack_graphql::GraphQlOptions;
 use turbopack_wasm::source::WebAssemblySourceType;
 
 use super::{match_mode::MatchMode, CustomModuleType, RuleCondition};
@@ -98,6 +99,12 @@ pub enum ModuleType {
         options: ResolvedVc<EcmascriptOptions>,
     },
     Json,
+    /// JSON with comments and trailing commas.
+    Jsonc,
+    Json5,
+    GraphQl {
+        options: ResolvedVc<GraphQlOptions>,
+    },
     Raw,
     CssGlobal,
     CssModule,
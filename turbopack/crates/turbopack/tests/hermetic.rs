@@ -0,0 +1,144 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{CollectiblesSource, TurboTasks, Vc};
+use turbo_tasks_fs::{DirectoryContent, DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::issue::{hermetic::HermeticViolationIssueReporter, Issue};
+
+/// Creates an empty directory for the test, with the given files in it.
+fn test_dir(name: &str, files: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("turbopack-hermetic-{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    for file in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "export {}").unwrap();
+    }
+    dir
+}
+
+fn hermetic_fs(root: &Path, roots: &[PathBuf]) -> Vc<FileSystemPath> {
+    let roots: Vec<RcStr> = roots
+        .iter()
+        .map(|root| root.to_string_lossy().into())
+        .collect();
+    DiskFileSystem::new_hermetic(
+        "project".into(),
+        root.to_string_lossy().into(),
+        vec![],
+        roots.clone(),
+        Vc::upcast(HermeticViolationIssueReporter::new(roots)),
+    )
+    .root()
+}
+
+/// Whether the file was found, and the paths of the reported violations.
+async fn read(path: Vc<FileSystemPath>) -> Result<(bool, Vec<String>)> {
+    let content = path.read();
+    let found = matches!(
+        &*content.strongly_consistent().await?,
+        FileContent::Content(_)
+    );
+    Ok((found, violations(content).await?))
+}
+
+/// Whether the directory was found, and the paths of the reported violations.
+async fn read_dir(path: Vc<FileSystemPath>) -> Result<(bool, Vec<String>)> {
+    let content = path.read_dir();
+    let found = matches!(
+        &*content.strongly_consistent().await?,
+        DirectoryContent::Entries(_)
+    );
+    Ok((found, violations(content).await?))
+}
+
+async fn violations<T>(vc: Vc<T>) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for issue in vc.peek_collectibles::<Box<dyn Issue>>() {
+        paths.push(issue.file_path().to_string().await?.to_string());
+    }
+    Ok(paths)
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn single_project_rejects_links_out_of_the_project() {
+    turbopack::register();
+    let dir = test_dir("single", &["project/index.js", "outside/secret.js"]);
+    std::os::unix::fs::symlink("../outside", dir.join("project/linked")).unwrap();
+    let project = dir.join("project");
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = hermetic_fs(&project, &[project.clone()]);
+
+        assert_eq!(read(root.join("index.js".into())).await?, (true, vec![]));
+        assert_eq!(read(root.join("missing.js".into())).await?, (false, vec![]));
+        assert_eq!(
+            read(root.join("linked/secret.js".into())).await?,
+            (false, vec!["[project]/linked/secret.js".to_string()])
+        );
+        assert_eq!(
+            read_dir(root.join("linked".into())).await?,
+            (false, vec!["[project]/linked".to_string()])
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn monorepo_reads_only_declared_packages() {
+    turbopack::register();
+    let dir = test_dir(
+        "monorepo",
+        &[
+            "apps/web/index.js",
+            "packages/ui/index.js",
+            "packages/other/index.js",
+        ],
+    );
+    let web = dir.join("apps/web");
+    let ui = web.join("../../packages/ui");
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = hermetic_fs(&dir, &[web, ui]);
+
+        assert_eq!(
+            read(root.join("apps/web/index.js".into())).await?,
+            (true, vec![])
+        );
+        assert_eq!(
+            read(root.join("packages/ui/index.js".into())).await?,
+            (true, vec![])
+        );
+        assert_eq!(
+            read(root.join("packages/other/index.js".into())).await?,
+            (false, vec!["[project]/packages/other/index.js".to_string()])
+        );
+        // Resolving walks up the directory tree, so directories containing a root can be listed.
+        assert_eq!(
+            read_dir(root.join("packages".into())).await?,
+            (true, vec![])
+        );
+        assert_eq!(
+            read_dir(root.join("packages/other".into())).await?,
+            (false, vec!["[project]/packages/other".to_string()])
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
@@ -102,6 +102,7 @@ static ALLOC: turbo_tasks_malloc::TurboMalloc = turbo_tasks_malloc::TurboMalloc;
 #[case::fetch_h2("integration/fetch-h2.js")]
 #[cfg_attr(target_arch = "x86_64", case::ffmpeg_js("integration/ffmpeg.js"))]
 // Could not find ffmpeg executable
+#[case::file_url_to_path("integration/file-url-to-path.mjs")]
 #[case::firebase_admin("integration/firebase-admin.js")]
 #[case::firebase("integration/firebase.js")]
 #[case::firestore("integration/firestore.js")]
@@ -119,6 +120,7 @@ static ALLOC: turbo_tasks_malloc::TurboMalloc = turbo_tasks_malloc::TurboMalloc;
 #[case::leveldown("integration/leveldown.js")]
 #[case::lighthouse("integration/lighthouse.js")]
 #[case::loopback("integration/loopback.js")]
+#[case::lstat_access("integration/lstat-access.js")]
 #[case::mailgun("integration/mailgun.js")]
 #[case::mariadb("integration/mariadb.js")]
 #[case::memcached("integration/memcached.js")]
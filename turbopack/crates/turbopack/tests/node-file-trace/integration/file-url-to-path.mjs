@@ -0,0 +1,17 @@
+import fs from "fs";
+import { fileURLToPath, pathToFileURL } from "url";
+
+const name = "hello";
+
+const relative = fileURLToPath(
+  new URL(`./content/${name}.json`, import.meta.url)
+);
+const cwd = fileURLToPath(`file://${process.cwd()}/content/${name}.json`);
+const roundTrip = fileURLToPath(
+  pathToFileURL(`${process.cwd()}/content/${name}.json`)
+);
+
+console.log(JSON.parse(fs.readFileSync(relative, "utf8")));
+console.log(fs.lstatSync(cwd).isFile());
+fs.accessSync(roundTrip, fs.constants.R_OK);
+console.log("accessible");
@@ -0,0 +1,9 @@
+const fs = require("fs");
+const { fileURLToPath } = require("url");
+
+const name = "hello";
+
+const file = fileURLToPath(`file://${__dirname}/content/${name}.json`);
+console.log(fs.lstatSync(file).isFile());
+fs.accessSync(fileURLToPath(new URL(`file://${__dirname}/content/hello.json`)));
+console.log(JSON.parse(fs.readFileSync(file, "utf8")));
@@ -0,0 +1,196 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use turbo_tasks::{TurboTasks, Value, Vc};
+use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::{module_options::ModuleOptionsContext, ModuleAssetContext};
+use turbopack_core::{
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        parse::Request,
+        resolve,
+        trace::trace_reference_type,
+        ResolveResultItem,
+    },
+    source::Source,
+};
+use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+
+/// Creates an empty directory for the test, with the given files in it.
+fn test_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("turbopack-resolve-{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    for (file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+/// How a request was resolved.
+#[derive(Debug, PartialEq)]
+enum Resolved {
+    /// To the file at the path, relative to the project.
+    File(String),
+    Empty,
+    Unresolvable,
+}
+
+/// Resolves `specifier` like `require` in `index.js` of the project.
+async fn resolve_request(
+    root: Vc<FileSystemPath>,
+    resolve_options_context: ResolveOptionsContext,
+    specifier: &str,
+) -> Result<Resolved> {
+    let environment = Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+        NodeJsEnvironment::default().resolved_cell(),
+    )));
+    let asset_context = ModuleAssetContext::new(
+        Default::default(),
+        CompileTimeInfo::new(environment),
+        ModuleOptionsContext::default().cell(),
+        ResolveOptionsContext {
+            enable_node_modules: Some(root.to_resolved().await?),
+            ..resolve_options_context
+        }
+        .cell(),
+        Vc::cell("test".into()),
+    );
+    let importer = root.join("index.js".into());
+    let reference_type = trace_reference_type(Some("require")).unwrap();
+    let result = resolve(
+        root,
+        Value::new(reference_type.clone()),
+        Request::parse_string(specifier.into()),
+        asset_context.resolve_options(importer, Value::new(reference_type)),
+    )
+    .await?;
+
+    let root = root.await?;
+    Ok(match result.primary.values().next() {
+        None => Resolved::Unresolvable,
+        Some(ResolveResultItem::Empty) => Resolved::Empty,
+        Some(ResolveResultItem::Source(source)) => {
+            let path = source.ident().path().await?;
+            Resolved::File(root.get_path_to(&path).unwrap().to_string())
+        }
+        Some(_) => anyhow::bail!("unexpected result for {specifier}"),
+    })
+}
+
+#[tokio::test]
+async fn module_files_are_resolved_when_enabled() {
+    turbopack::register();
+    let dir = test_dir(
+        "module-files",
+        &[
+            ("index.js", "require('single');\n"),
+            ("node_modules/single.js", "module.exports = 1;\n"),
+            (
+                "node_modules/pkg/package.json",
+                r#"{ "name": "pkg", "main": "main.js" }"#,
+            ),
+            ("node_modules/pkg/main.js", "module.exports = 2;\n"),
+        ],
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let module_files = || ResolveOptionsContext {
+            enable_module_files: true,
+            ..Default::default()
+        };
+
+        // Without a package.json, the file is only found when enabled.
+        assert_eq!(
+            resolve_request(root, Default::default(), "single").await?,
+            Resolved::Unresolvable
+        );
+        assert_eq!(
+            resolve_request(root, module_files(), "single").await?,
+            Resolved::File("node_modules/single.js".into())
+        );
+
+        // Packages are resolved in the same way either way.
+        for context in [Default::default(), module_files()] {
+            assert_eq!(
+                resolve_request(root, context, "pkg").await?,
+                Resolved::File("node_modules/pkg/main.js".into())
+            );
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn fallbacks_only_apply_to_unresolvable_requests() {
+    turbopack::register();
+    let dir = test_dir(
+        "fallback",
+        &[
+            ("index.js", "require('pkg');\n"),
+            ("src/fallback.js", "module.exports = 1;\n"),
+            (
+                "node_modules/pkg/package.json",
+                r#"{ "name": "pkg", "main": "main.js" }"#,
+            ),
+            ("node_modules/pkg/main.js", "module.exports = 2;\n"),
+        ],
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let fallback = ImportMapping::PrimaryAlternative(
+            "./src/fallback.js".into(),
+            Some(root.to_resolved().await?),
+        )
+        .resolved_cell();
+        let mut fallback_import_map = ImportMap::empty();
+        fallback_import_map.insert_exact_alias("missing", fallback);
+        fallback_import_map.insert_exact_alias("pkg", fallback);
+        // Like `false` in `resolveFallback`.
+        fallback_import_map.insert_exact_alias("stubbed", ImportMapping::Empty.resolved_cell());
+        let fallback_import_map = fallback_import_map.resolved_cell();
+        let context = || ResolveOptionsContext {
+            fallback_import_map: Some(fallback_import_map),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            resolve_request(root, context(), "missing").await?,
+            Resolved::File("src/fallback.js".into())
+        );
+        assert_eq!(
+            resolve_request(root, context(), "stubbed").await?,
+            Resolved::Empty
+        );
+        // The package is found, so the fallback isn't used.
+        assert_eq!(
+            resolve_request(root, context(), "pkg").await?,
+            Resolved::File("node_modules/pkg/main.js".into())
+        );
+        assert_eq!(
+            resolve_request(root, Default::default(), "missing").await?,
+            Resolved::Unresolvable
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
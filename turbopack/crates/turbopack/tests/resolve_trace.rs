@@ -0,0 +1,340 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use turbo_tasks::{FxIndexMap, TurboTasks, Value, ValueToString, Vc};
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::{module_options::ModuleOptionsContext, ModuleAssetContext};
+use turbopack_core::{
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{Environment, ExecutionEnvironment, NodeJsEnvironment},
+    resolve::{
+        options::{ImportMap, ImportMapping},
+        parse::Request,
+        resolve,
+        trace::{trace_reference_type, trace_resolve, ResolveTraceStep, ResolveTraceStepKind},
+    },
+    version::VersionedContent,
+};
+use turbopack_dev_server::source::{
+    query::{Query, QueryValue},
+    resolve_trace::ResolveTraceContentSource,
+    ContentSourceContent, ContentSourceData, GetContentSourceContent,
+};
+use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+
+/// Creates an empty directory for the test, with the given files in it.
+fn test_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("turbopack-resolve-trace-{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    for (file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+/// A project with an aliased file, a package with conditional exports and a fallback for a
+/// missing package.
+fn project_dir(name: &str) -> PathBuf {
+    test_dir(
+        name,
+        &[
+            ("index.js", "import 'pkg';\n"),
+            ("src/aliased.js", "export {};\n"),
+            ("src/fallback.js", "export {};\n"),
+            (
+                "node_modules/pkg/package.json",
+                r#"{
+                    "name": "pkg",
+                    "exports": { ".": { "custom": "./custom.js", "default": "./main.js" } }
+                }"#,
+            ),
+            ("node_modules/pkg/custom.js", "export {};\n"),
+            ("node_modules/pkg/main.js", "export {};\n"),
+        ],
+    )
+}
+
+async fn asset_context(root: Vc<FileSystemPath>) -> Result<Vc<Box<dyn AssetContext>>> {
+    let root = root.to_resolved().await?;
+    let mut import_map = ImportMap::empty();
+    import_map.insert_exact_alias(
+        "@alias",
+        ImportMapping::PrimaryAlternative("./src/aliased.js".into(), Some(root)).resolved_cell(),
+    );
+    let mut fallback_import_map = ImportMap::empty();
+    fallback_import_map.insert_exact_alias(
+        "missing",
+        ImportMapping::PrimaryAlternative("./src/fallback.js".into(), Some(root)).resolved_cell(),
+    );
+    let environment = Environment::new(Value::new(ExecutionEnvironment::NodeJsLambda(
+        NodeJsEnvironment::default().resolved_cell(),
+    )));
+    Ok(Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        CompileTimeInfo::new(environment),
+        ModuleOptionsContext::default().cell(),
+        ResolveOptionsContext {
+            enable_node_modules: Some(root),
+            custom_conditions: vec!["custom".into()],
+            import_map: Some(import_map.resolved_cell()),
+            fallback_import_map: Some(fallback_import_map.resolved_cell()),
+            ..Default::default()
+        }
+        .cell(),
+        Vc::cell("test".into()),
+    )))
+}
+
+/// The messages of the steps of the given kind taken for `request`.
+fn messages<'a>(
+    steps: &'a [ResolveTraceStep],
+    kind: ResolveTraceStepKind,
+    request: &str,
+) -> Vec<&'a str> {
+    steps
+        .iter()
+        .filter(|step| step.kind == kind && step.request.as_str() == request)
+        .map(|step| step.message.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn traces_aliases_exports_and_fallbacks() {
+    turbopack::register();
+    let dir = project_dir("steps");
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let asset_context = asset_context(root).await?;
+        let importer = root.join("index.js".into());
+        let reference_type = trace_reference_type(None).unwrap();
+        let trace = |specifier: &str| {
+            trace_resolve(
+                asset_context,
+                importer,
+                Request::parse_string(specifier.into()),
+                Value::new(reference_type.clone()),
+            )
+        };
+
+        // The import map alias is followed and the target is probed.
+        let alias = trace("@alias").await?;
+        assert!(alias.result.contains("src/aliased.js"), "{}", alias.result);
+        let aliases = messages(&alias.steps, ResolveTraceStepKind::Alias, "@alias");
+        assert!(
+            aliases
+                .iter()
+                .any(|message| message.starts_with("import map: ")
+                    && message.contains("./src/aliased.js")),
+            "{aliases:?}"
+        );
+        assert!(alias
+            .steps
+            .iter()
+            .any(|step| step.kind == ResolveTraceStepKind::Probe
+                && step.message.contains("found file")
+                && step.message.contains("aliased.js")));
+
+        // The package is found and its exports are matched against the conditions.
+        let package = trace("pkg").await?;
+        assert!(
+            package.result.contains("node_modules/pkg/custom.js"),
+            "{}",
+            package.result
+        );
+        let modules = messages(&package.steps, ResolveTraceStepKind::Module, "pkg");
+        assert!(
+            modules
+                .iter()
+                .any(|message| message.starts_with("package `pkg` was found")),
+            "{modules:?}"
+        );
+        let exports = package
+            .steps
+            .iter()
+            .filter(|step| step.kind == ResolveTraceStepKind::ExportsConditions)
+            .map(|step| step.message.as_str())
+            .collect::<Vec<_>>();
+        assert!(
+            exports
+                .iter()
+                .any(|message| message.starts_with("exports field of ")
+                    && message.contains("`./custom.js`")),
+            "{exports:?}"
+        );
+
+        // Missing packages fall back to the fallback import map.
+        let missing = trace("missing").await?;
+        assert!(
+            missing.result.contains("src/fallback.js"),
+            "{}",
+            missing.result
+        );
+        let modules = messages(&missing.steps, ResolveTraceStepKind::Module, "missing");
+        assert!(
+            modules
+                .iter()
+                .any(|message| message.contains("wasn't found in the modules directories")),
+            "{modules:?}"
+        );
+        let aliases = messages(&missing.steps, ResolveTraceStepKind::Alias, "missing");
+        assert!(
+            aliases
+                .iter()
+                .any(|message| message.starts_with("fallback import map: ")
+                    && message.contains("./src/fallback.js")),
+            "{aliases:?}"
+        );
+
+        // Every trace starts with the traced request.
+        for trace in [&alias, &package, &missing] {
+            assert!(trace
+                .steps
+                .iter()
+                .any(|step| step.kind == ResolveTraceStepKind::Request
+                    && step.request == trace.request));
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn tracing_does_not_change_the_result() {
+    turbopack::register();
+    let dir = project_dir("result");
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let asset_context = asset_context(root).await?;
+        let importer = root.join("index.js".into());
+        for kind in ["import", "require"] {
+            let reference_type = trace_reference_type(Some(kind)).unwrap();
+            for specifier in ["@alias", "pkg", "missing", "./src/aliased", "unknown"] {
+                let request = Request::parse_string(specifier.into());
+                let options =
+                    asset_context.resolve_options(importer, Value::new(reference_type.clone()));
+                assert!(!options.await?.trace);
+                let result = resolve(
+                    importer.parent(),
+                    Value::new(reference_type.clone()),
+                    request,
+                    options,
+                );
+                let trace = trace_resolve(
+                    asset_context,
+                    importer,
+                    request,
+                    Value::new(reference_type.clone()),
+                )
+                .await?;
+                assert_eq!(
+                    trace.result,
+                    *result.to_string().await?,
+                    "{kind} of {specifier}"
+                );
+            }
+        }
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn dev_server_serves_traces() {
+    turbopack::register();
+    let dir = project_dir("dev-server");
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let source = ResolveTraceContentSource {
+            environments: FxIndexMap::from_iter([(
+                "client".into(),
+                asset_context(root).await?.to_resolved().await?,
+            )]),
+            root: root.to_resolved().await?,
+        }
+        .cell();
+        let get = |params: &[(&str, &str)]| {
+            let mut query = Query::default();
+            for (name, value) in params {
+                query.insert(name.to_string(), QueryValue::String(value.to_string()));
+            }
+            Vc::upcast::<Box<dyn GetContentSourceContent>>(source).get(
+                "".into(),
+                Value::new(ContentSourceData {
+                    query: Some(query),
+                    ..Default::default()
+                }),
+            )
+        };
+        let json = |content: Vc<ContentSourceContent>| async move {
+            let ContentSourceContent::Static(content) = &*content.await? else {
+                anyhow::bail!("expected static content");
+            };
+            let FileContent::Content(file) =
+                &*content.await?.content.content().file_content().await?
+            else {
+                anyhow::bail!("expected a file");
+            };
+            anyhow::Ok(serde_json::from_str::<JsonValue>(
+                &file.content().to_str()?,
+            )?)
+        };
+
+        // Without a specifier, the environments are listed.
+        assert_eq!(json(get(&[])).await?, serde_json::json!(["client"]));
+
+        let trace = json(get(&[
+            ("environment", "client"),
+            ("specifier", "pkg"),
+            ("importer", "index.js"),
+            ("kind", "require"),
+        ]))
+        .await?;
+        assert_eq!(trace["request"], "pkg");
+        assert!(trace["importer"]
+            .as_str()
+            .is_some_and(|importer| importer.ends_with("index.js")));
+        assert!(trace["result"]
+            .as_str()
+            .is_some_and(|result| result.contains("node_modules/pkg/custom.js")));
+        let steps = trace["steps"].as_array().unwrap();
+        assert!(steps
+            .iter()
+            .any(|step| step["kind"] == "exports-conditions" && step["lookupPath"].is_string()));
+
+        // The first environment is the default one.
+        let default = json(get(&[("specifier", "pkg")])).await?;
+        assert_eq!(default["result"], trace["result"]);
+
+        assert!(get(&[("environment", "server"), ("specifier", "pkg")])
+            .await
+            .is_err());
+        assert!(get(&[("specifier", "pkg"), ("kind", "dynamic-import")])
+            .await
+            .is_err());
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
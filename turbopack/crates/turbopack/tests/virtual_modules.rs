@@ -0,0 +1,84 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use anyhow::Result;
+use turbo_rcstr::RcStr;
+use turbo_tasks::{ResolvedVc, TurboTasks, Value, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    reference_type::ReferenceType,
+    resolve::{parse::Request, resolve},
+    virtual_module::{VirtualModuleContent, VirtualModules, DEFAULT_VIRTUAL_MODULE_PREFIX},
+};
+use turbopack_resolve::{resolve::resolve_options, resolve_options_context::ResolveOptionsContext};
+
+#[tokio::test]
+async fn resolves_registered_virtual_modules() {
+    turbopack::register();
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root = VirtualFileSystem::new().root();
+        let registry = VirtualModules::new(
+            root.join("virtual".into()).to_resolved().await?,
+            DEFAULT_VIRTUAL_MODULE_PREFIX.into(),
+        );
+        let options = ResolveOptionsContext {
+            virtual_modules: Some(registry.to_resolved().await?),
+            ..Default::default()
+        }
+        .cell();
+
+        assert_eq!(resolve_content(root, options, "virtual:a").await?, None);
+
+        registry.register("a".into(), content("one").await?).await?;
+        assert_eq!(
+            resolve_content(root, options, "virtual:a")
+                .await?
+                .as_deref(),
+            Some("one")
+        );
+
+        registry.register("a".into(), content("two").await?).await?;
+        assert_eq!(
+            resolve_content(root, options, "virtual:a")
+                .await?
+                .as_deref(),
+            Some("two")
+        );
+
+        assert!(registry.unregister("a").await?);
+        assert_eq!(resolve_content(root, options, "virtual:a").await?, None);
+        assert!(!registry.unregister("a").await?);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+async fn content(text: &str) -> Result<ResolvedVc<Box<dyn VirtualModuleContent>>> {
+    let content = AssetContent::file(FileContent::Content(File::from(text)).cell());
+    Ok(ResolvedVc::upcast(content.to_resolved().await?))
+}
+
+async fn resolve_content(
+    root: Vc<FileSystemPath>,
+    options: Vc<ResolveOptionsContext>,
+    request: &str,
+) -> Result<Option<String>> {
+    let result = resolve(
+        root,
+        Value::new(ReferenceType::Undefined),
+        Request::parse_string(RcStr::from(request)),
+        resolve_options(root, options),
+    );
+    let Some(source) = *result.first_source().await? else {
+        return Ok(None);
+    };
+    Ok(match &*source.content().file_content().await? {
+        FileContent::Content(file) => Some(file.content().to_str()?.into_owned()),
+        FileContent::NotFound => None,
+    })
+}
@@ -0,0 +1,348 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use serde_json::{json, Value as JsonValue};
+use turbo_rcstr::RcStr;
+use turbo_tasks::{TurboTasks, Value, ValueToString, Vc};
+use turbo_tasks_fs::{DiskFileSystem, FileContent, FileSystem, FileSystemPath};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::{module_options::ModuleOptionsContext, ModuleAssetContext};
+use turbopack_browser::{
+    service_worker_environment, BrowserChunkingContext, ServiceWorkerEntry, WebManifestAsset,
+};
+use turbopack_core::{
+    asset::Asset,
+    chunk::{availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext},
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
+    file_source::FileSource,
+    module::Module,
+    output::OutputAsset,
+    reference_type::ReferenceType,
+};
+use turbopack_dev_server::source::{
+    query::{Query, QueryValue},
+    ContentSourceContent, ContentSourceData, GetContentSourceContent,
+};
+use turbopack_ecmascript_runtime::RuntimeType;
+use turbopack_node::source_map::content_source::StackFrameContentSource;
+use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
+
+/// Creates an empty directory for the test, with the given files in it.
+fn test_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("turbopack-web-app-{name}"));
+    let _ = fs::remove_dir_all(&dir);
+    for (file, content) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    dir
+}
+
+fn browser_environment(service_worker: bool) -> Vc<Environment> {
+    if service_worker {
+        return service_worker_environment("chrome 100".into());
+    }
+    Environment::new(Value::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: true,
+            web_worker: false,
+            service_worker: false,
+            browserslist_query: "chrome 100".into(),
+        }
+        .resolved_cell(),
+    )))
+}
+
+fn asset_context(environment: Vc<Environment>) -> Vc<Box<dyn AssetContext>> {
+    Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        CompileTimeInfo::new(environment),
+        ModuleOptionsContext::default().cell(),
+        ResolveOptionsContext::default().cell(),
+        Vc::cell("test".into()),
+    ))
+}
+
+/// A chunking context which emits everything into `out` of `root`.
+async fn chunking_context(
+    root: Vc<FileSystemPath>,
+    environment: Vc<Environment>,
+) -> Result<Vc<BrowserChunkingContext>> {
+    let out = root.join("out".into()).to_resolved().await?;
+    BrowserChunkingContext::builder(
+        root.to_resolved().await?,
+        out,
+        out,
+        out.join("_chunks".into()).to_resolved().await?,
+        out.join("_assets".into()).to_resolved().await?,
+        environment.to_resolved().await?,
+        RuntimeType::Development,
+    )
+    .build()
+}
+
+async fn content(asset: Vc<Box<dyn OutputAsset>>) -> Result<String> {
+    let FileContent::Content(file) = &*asset.content().file_content().await? else {
+        anyhow::bail!("{} has no content", asset.ident().to_string().await?);
+    };
+    Ok(file.content().to_str()?.into_owned())
+}
+
+#[tokio::test]
+async fn web_manifest_points_to_emitted_images() {
+    turbopack::register();
+    turbopack_browser::register();
+    let dir = test_dir(
+        "manifest",
+        &[
+            (
+                "manifest.webmanifest",
+                r#"{
+                    "name": "App",
+                    "icons": [
+                        { "src": "icons/icon.png", "sizes": "192x192" },
+                        { "src": "/absolute.png" },
+                        { "src": "https://example.com/remote.png" }
+                    ],
+                    "shortcuts": [{ "name": "New", "icons": [{ "src": "icons/icon.png" }] }]
+                }"#,
+            ),
+            ("icons/icon.png", "not really a png"),
+        ],
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let environment = browser_environment(false);
+        let chunking_context = chunking_context(root, environment).await?;
+        let manifest = Vc::upcast::<Box<dyn OutputAsset>>(WebManifestAsset::new(
+            root.join("out/manifest.webmanifest".into()),
+            Vc::upcast(FileSource::new(root.join("manifest.webmanifest".into()))),
+            asset_context(environment),
+            Vc::upcast(chunking_context),
+        ));
+
+        // The image is emitted once, even though it's referenced twice.
+        let references = manifest.references().await?;
+        assert_eq!(references.len(), 1);
+        let image = references[0];
+        let image_path = image.ident().path().await?;
+        assert!(
+            image_path.path.starts_with("out/_assets/icon.") && image_path.path.ends_with(".png"),
+            "unexpected image path {}",
+            image_path.path
+        );
+        assert_eq!(content(*image).await?, "not really a png");
+
+        let url = format!("/{}", image_path.path.strip_prefix("out/").unwrap());
+        let manifest: JsonValue = serde_json::from_str(&content(manifest).await?)?;
+        assert_eq!(
+            manifest,
+            json!({
+                "name": "App",
+                "icons": [
+                    { "src": url, "sizes": "192x192" },
+                    { "src": "/absolute.png" },
+                    { "src": "https://example.com/remote.png" }
+                ],
+                "shortcuts": [{ "name": "New", "icons": [{ "src": url }] }]
+            })
+        );
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn service_worker_imports_its_chunks() {
+    turbopack::register();
+    turbopack_browser::register();
+    let dir = test_dir(
+        "service-worker",
+        &[(
+            "sw.js",
+            "self.addEventListener('install', () => console.log('installed'));\n",
+        )],
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let environment = browser_environment(true);
+        let service_worker = Vc::upcast::<Box<dyn OutputAsset>>(ServiceWorkerEntry::new(
+            root.join("out/sw.js".into()),
+            Vc::upcast(FileSource::new(root.join("sw.js".into()))),
+            asset_context(browser_environment(false)),
+            Vc::upcast(chunking_context(root, environment).await?),
+        ));
+
+        // The script is emitted at the given path, and its chunks are loaded
+        // relative to it.
+        assert_eq!(
+            service_worker.ident().path().await?.path,
+            RcStr::from("out/sw.js")
+        );
+        let mut chunks = Vec::new();
+        for chunk in service_worker.references().await?.iter() {
+            let path = chunk.ident().path().await?;
+            if path.extension_ref() == Some("js") {
+                chunks.push(format!("./{}", path.path.strip_prefix("out/").unwrap()));
+            }
+        }
+        assert!(!chunks.is_empty());
+
+        let code = content(service_worker).await?;
+        let mut lines = code.lines();
+        assert_eq!(
+            lines.next(),
+            Some("self.TURBOPACK_WORKER_LOCATION = self.location.origin;")
+        );
+        let import_scripts = lines.next().unwrap();
+        assert_eq!(
+            import_scripts,
+            format!(
+                "importScripts({});",
+                chunks
+                    .iter()
+                    .map(|chunk| format!("{chunk:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        );
+
+        // The service worker's module is in one of the chunks.
+        let mut found = false;
+        for chunk in service_worker.references().await?.iter() {
+            if chunk.ident().path().await?.extension_ref() == Some("js") {
+                found |= content(**chunk).await?.contains("console.log('installed')");
+            }
+        }
+        assert!(found);
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn stack_frames_map_to_original_positions() {
+    turbopack::register();
+    turbopack_browser::register();
+    let dir = test_dir(
+        "stack-frame",
+        &[(
+            "index.js",
+            "export function fail() {\n  throw new Error(\"boom\");\n}\n",
+        )],
+    );
+
+    let tt = TurboTasks::new(MemoryBackend::default());
+    tt.run_once(async move {
+        let root =
+            DiskFileSystem::new("project".into(), dir.to_string_lossy().into(), vec![]).root();
+        let environment = browser_environment(false);
+        let chunking_context = chunking_context(root, environment).await?;
+        let source = Vc::upcast(FileSource::new(root.join("index.js".into())));
+        let module = asset_context(environment)
+            .process(source, Value::new(ReferenceType::Undefined))
+            .module();
+        let module = Vc::try_resolve_sidecast::<Box<dyn ChunkableModule>>(module)
+            .await?
+            .unwrap();
+        let assets = chunking_context
+            .chunk_group(module.ident(), module, Value::new(AvailabilityInfo::Root))
+            .await?
+            .assets;
+
+        // Find the generated position of the throw.
+        let mut frame = None;
+        for asset in assets.await?.iter() {
+            let path = asset.ident().path().await?;
+            if path.extension_ref() != Some("js") {
+                continue;
+            }
+            let code = content(**asset).await?;
+            for (index, line) in code.lines().enumerate() {
+                if let Some(column) = line.find("new Error") {
+                    let url = format!(
+                        "http://localhost:3000/{}",
+                        path.path.strip_prefix("out/").unwrap()
+                    );
+                    frame = Some((url, index + 1, column + 1));
+                }
+            }
+        }
+        let (url, line, column) = frame.expect("the throw is in a chunk");
+
+        let stack_frame = StackFrameContentSource::new(
+            Vc::cell("__turbopack_stack_frame__".into()),
+            root.join("out".into()),
+            assets,
+        );
+        let get = |file: String, line: usize, column: usize| {
+            let mut query = Query::default();
+            query.insert("file".into(), QueryValue::String(file));
+            query.insert("lineNumber".into(), QueryValue::String(line.to_string()));
+            query.insert("column".into(), QueryValue::String(column.to_string()));
+            Vc::upcast::<Box<dyn GetContentSourceContent>>(stack_frame).get(
+                "__turbopack_stack_frame__".into(),
+                Value::new(ContentSourceData {
+                    query: Some(query),
+                    ..Default::default()
+                }),
+            )
+        };
+
+        let ContentSourceContent::Static(content) = &*get(url.clone(), line, column).await? else {
+            anyhow::bail!("the frame of {url} wasn't mapped");
+        };
+        let FileContent::Content(file) = &*content.await?.content.content().file_content().await?
+        else {
+            anyhow::bail!("the frame of {url} has no content");
+        };
+        let trace: JsonValue = serde_json::from_str(&file.content().to_str()?)?;
+        let original = &trace["originalStackFrame"];
+        assert!(
+            original["file"]
+                .as_str()
+                .is_some_and(|file| file.ends_with("[project]/index.js")),
+            "unexpected original frame {original}"
+        );
+        assert_eq!(original["lineNumber"], json!(2));
+        // The column is the one of the throw statement or of the error.
+        assert!(original["column"]
+            .as_u64()
+            .is_some_and(|column| (3..=9).contains(&column)));
+        assert_eq!(trace["isUserCode"], json!(true));
+        assert!(trace["originalCodeFrame"]
+            .as_str()
+            .is_some_and(|frame| frame.contains("throw new Error(\"boom\")")));
+
+        // Frames of unknown files aren't mapped.
+        assert!(matches!(
+            *get(
+                "http://localhost:3000/_chunks/unknown.js".into(),
+                line,
+                column
+            )
+            .await?,
+            ContentSourceContent::NotFound
+        ));
+
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap();
+}